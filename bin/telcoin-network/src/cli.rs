@@ -1,7 +1,7 @@
 //! CLI definition and entrypoint to executable
 use crate::{
     args::clap_genesis_parser,
-    genesis, keytool, node,
+    db, genesis, keytool, node,
     version::{LONG_VERSION, SHORT_VERSION},
 };
 use clap::{value_parser, Parser, Subcommand};
@@ -132,6 +132,7 @@ impl<Ext: clap::Args + fmt::Debug> Cli<Ext> {
             Commands::Genesis(command) => command.execute(),
             Commands::Node(command) => command.execute(true, launcher),
             Commands::Keytool(command) => command.execute(),
+            Commands::Db(command) => command.execute(),
         }
     }
 
@@ -160,6 +161,11 @@ pub enum Commands<Ext: clap::Args + fmt::Debug = NoArgs> {
     /// Start the node
     #[command(name = "node")]
     Node(Box<node::NodeCommand<Ext>>),
+
+    /// Database maintenance.
+    /// Migrate the consensus DB between backends.
+    #[command(name = "db")]
+    Db(db::DbArgs),
 }
 
 #[cfg(test)]
@@ -0,0 +1,57 @@
+//! Database maintenance commands.
+
+use clap::{Args, Subcommand};
+use std::path::PathBuf;
+use tn_storage::{migrate_db_paths, DbBackend};
+use tracing::info;
+
+/// Manage the node's consensus database.
+#[derive(Debug, Args)]
+pub struct DbArgs {
+    /// The database command to run.
+    #[command(subcommand)]
+    pub command: DbSubcommand,
+}
+
+/// Subcommands for database maintenance.
+#[derive(Debug, Clone, Subcommand)]
+pub enum DbSubcommand {
+    /// Copy all the consensus tables from one DB backend into another.
+    #[command(name = "migrate")]
+    Migrate(MigrateArgs),
+}
+
+/// Arguments to migrate a DB between backends.
+#[derive(Debug, Clone, Args)]
+pub struct MigrateArgs {
+    /// The backend of the source DB: mdbx, redb or rocksdb.
+    #[arg(long, value_name = "BACKEND")]
+    pub from: DbBackend,
+
+    /// The directory of the source DB.
+    #[arg(long, value_name = "PATH")]
+    pub from_path: PathBuf,
+
+    /// The backend of the destination DB: mdbx, redb or rocksdb.
+    #[arg(long, value_name = "BACKEND")]
+    pub to: DbBackend,
+
+    /// The directory of the destination DB.
+    /// Any existing data in the node tables will be replaced.
+    #[arg(long, value_name = "PATH")]
+    pub to_path: PathBuf,
+}
+
+impl DbArgs {
+    /// Execute command
+    pub fn execute(&self) -> eyre::Result<()> {
+        match &self.command {
+            DbSubcommand::Migrate(args) => {
+                info!(target: "tn::cli", from = ?args.from, to = ?args.to, "Migrating database");
+                migrate_db_paths(args.from, &args.from_path, args.to, &args.to_path)?;
+                info!(target: "tn::cli", path = ?args.to_path, "Database migration complete");
+            }
+        }
+        Ok(())
+    }
+}
@@ -259,6 +259,7 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
             tn_config,
             opt_faucet_args: None,
             consensus_metrics,
+            opt_tx_validation_hook: None,
         };
 
         launcher(builder, ext, tn_datadir)
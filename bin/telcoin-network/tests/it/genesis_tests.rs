@@ -7,10 +7,9 @@ mod tests {
     use reth_chainspec::ChainSpec;
     use std::{sync::Arc, time::Duration};
     use tn_config::{test_fetch_file_content_relative_to_manifest, ContractStandardJson};
-    use tn_test_utils::{get_contract_state_for_genesis, TransactionFactory};
+    use tn_test_utils::{GenesisBuilder, TransactionFactory};
     use tn_types::{
-        adiri_genesis, hex, sol, Address, BlsKeypair, Bytes, GenesisAccount, NetworkKeypair,
-        SolValue, U256,
+        adiri_genesis, hex, sol, Address, BlsKeypair, Bytes, NetworkKeypair, SolValue, U256,
     };
 
     #[tokio::test]
@@ -30,19 +29,6 @@ mod tests {
         let mut tx_factory = TransactionFactory::new();
         let factory_address = tx_factory.address();
 
-        // deploy impl and fund `factory_address`
-        let tmp_genesis = tmp_chain.genesis.clone().extend_accounts(
-            vec![
-                (factory_address, GenesisAccount::default().with_balance(U256::MAX)),
-                (
-                    registry_impl_address,
-                    GenesisAccount::default()
-                        .with_code(Some(registry_impl_bytecode.clone().into())),
-                ),
-            ]
-            .into_iter(),
-        );
-
         // ERC1967Proxy interface
         sol!(
             #[allow(clippy::too_many_arguments)]
@@ -146,7 +132,6 @@ mod tests {
         // construct proxy deployment and initialize txs
         let gas_price = 7;
         let gas_limit = 3_000_000;
-        let pre_genesis_chain: Arc<ChainSpec> = Arc::new(tmp_genesis.into());
         let registry_tx_raw = tx_factory.create_eip1559_encoded(
             tmp_chain.clone(),
             Some(gas_limit),
@@ -167,45 +152,23 @@ mod tests {
         );
         let raw_txs = vec![registry_tx_raw.clone(), initialize_tx_raw];
 
-        // fetch storage changes from pre-genesis for actual genesis
-        let execution_outcome = get_contract_state_for_genesis(pre_genesis_chain.clone(), raw_txs)
-            .await
-            .expect("unable to fetch contract state");
-        let execution_bundle = execution_outcome.bundle;
-        let execution_storage_registry = &execution_bundle
-            .state
-            .get(&registry_proxy_address)
-            .expect("registry address missing from bundle state")
-            .storage;
-        let proxy_json = test_fetch_file_content_relative_to_manifest(
-            "../../tn-contracts/artifacts/ERC1967Proxy.json".into(),
+        // perform canonical adiri chain genesis with storage fetched from pre-genesis
+        let chain: Arc<ChainSpec> = Arc::new(
+            GenesisBuilder::new(adiri_genesis())
+                .with_contract(
+                    registry_impl_address,
+                    registry_impl_bytecode.clone().into(),
+                    Default::default(),
+                )
+                .with_executed_contracts(
+                    vec![factory_address],
+                    raw_txs,
+                    vec![registry_proxy_address],
+                )
+                .await
+                .expect("unable to fetch contract state")
+                .build(),
         );
-        let proxy_contract: ContractStandardJson =
-            serde_json::from_str(&proxy_json).expect("json parsing failure");
-        let proxy_bytecode = hex::decode(proxy_contract.deployed_bytecode.object)
-            .expect("invalid bytecode hexstring");
-
-        // perform canonical adiri chain genesis with fetched storage
-        let genesis_accounts = vec![
-            (
-                registry_impl_address,
-                GenesisAccount::default().with_code(Some(registry_impl_bytecode.clone().into())),
-            ),
-            (
-                registry_proxy_address,
-                GenesisAccount::default().with_code(Some(proxy_bytecode.into())).with_storage(
-                    Some(
-                        execution_storage_registry
-                            .iter()
-                            .map(|(k, v)| ((*k).into(), v.present_value.into()))
-                            .collect(),
-                    ),
-                ),
-            ),
-        ];
-        let real_genesis = adiri_genesis();
-        let genesis = real_genesis.extend_accounts(genesis_accounts.into_iter());
-        let chain: Arc<ChainSpec> = Arc::new(genesis.into());
 
         spawn_local_testnet(chain, "0x0000000000000000000000000000000000000000")
             .expect("failed to spawn testnet");
@@ -1,6 +1,7 @@
 //! Configuration for consensus network (primary and worker).
 use crate::{
-    Config, ConfigFmt, ConfigTrait as _, KeyConfig, NetworkConfig, Parameters, TelcoinDirs,
+    Config, ConfigFmt, ConfigTrait as _, GossipMeshConfig, KeyConfig, NetworkConfig, Parameters,
+    TelcoinDirs,
 };
 use libp2p::PeerId;
 use std::{
@@ -106,7 +107,8 @@ where
             .clone();
 
         let shutdown = Notifier::new();
-        let network_config = NetworkConfig::default();
+        let network_config = NetworkConfig::default()
+            .with_send_certificate_rate_limit(config.parameters.send_certificate_rate_limit);
         let genesis = Certificate::genesis(&committee)
             .into_iter()
             .map(|cert| (cert.digest(), cert))
@@ -178,6 +180,11 @@ where
         &self.inner.network_config
     }
 
+    /// The gossipsub mesh parameters for the committee.
+    pub fn gossip_mesh_config(&self) -> GossipMeshConfig {
+        GossipMeshConfig::new(self.inner.committee.size())
+    }
+
     /// Committee network peer ids.
     pub fn committee_peer_ids(&self) -> HashSet<PeerId> {
         self.inner.committee.authorities().iter().map(|a| a.peer_id()).collect()
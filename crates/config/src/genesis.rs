@@ -1,5 +1,5 @@
 //! Genesis information used when configuring a node.
-use crate::{Config, ConfigFmt, ConfigTrait, TelcoinDirs};
+use crate::{Config, ConfigFmt, ConfigTrait, TelcoinDirs, CONSENSUS_REGISTRY_ADDRESS};
 use eyre::Context;
 use reth_chainspec::ChainSpec;
 use serde::{Deserialize, Serialize};
@@ -13,9 +13,9 @@ use std::{
 };
 use tn_types::{
     adiri_genesis, hex, keccak256, verify_proof_of_possession_bls, Address, BlsPublicKey,
-    BlsSignature, Committee, CommitteeBuilder, Epoch, FromHex as _, GenesisAccount, Intent,
-    IntentMessage, Multiaddr, NetworkPublicKey, PrimaryInfo, ProtocolSignature, Signer,
-    WorkerCache, WorkerIndex, B256,
+    BlsSignature, Committee, CommitteeBuilder, Epoch, GenesisAccount, Intent, IntentMessage,
+    Multiaddr, NetworkPublicKey, PrimaryInfo, ProtocolSignature, Signer, WorkerCache, WorkerIndex,
+    B256,
 };
 use tracing::{info, warn};
 /// The validators directory used to create genesis.
@@ -108,8 +108,7 @@ impl NetworkGenesis {
             serde_json::from_str(registry_standard_json).expect("json parsing failure");
         let registry_bytecode = hex::decode(registry_contract.deployed_bytecode.object)
             .expect("invalid bytecode hexstring");
-        let registry_proxy = Address::from_hex("0x07e17e17e17e17e17e17e17e17e17e17e17e17e1")
-            .expect("invalid hex address");
+        let registry_proxy = CONSENSUS_REGISTRY_ADDRESS;
         let proxy_standard_json = ERC1967_PROXY;
         let proxy_contract: ContractStandardJson =
             serde_json::from_str(proxy_standard_json).expect("json parsing failure");
@@ -14,5 +14,7 @@ mod traits;
 pub use traits::*;
 mod network;
 pub use network::*;
+mod registry;
+pub use registry::*;
 mod retry;
 pub use retry::*;
@@ -1,7 +1,7 @@
 //! Configuration for network variables.
 
 use libp2p::{request_response::ProtocolSupport, StreamProtocol};
-use std::time::Duration;
+use std::{num::NonZeroU32, time::Duration};
 use tn_types::Round;
 
 /// The container for all network configurations.
@@ -32,6 +32,12 @@ impl NetworkConfig {
     pub fn quic_config(&self) -> &QuicConfig {
         &self.quic_config
     }
+
+    /// Set the per-peer rate limit for outbound requests carrying certificates.
+    pub fn with_send_certificate_rate_limit(mut self, limit: Option<NonZeroU32>) -> Self {
+        self.libp2p_config.send_certificate_rate_limit = limit;
+        self
+    }
 }
 
 /// Configurations for libp2p library.
@@ -56,6 +62,37 @@ pub struct LibP2pConfig {
     pub max_gossip_message_size: usize,
     /// The maximum duration to keep an idle connection alive between peers.
     pub max_idle_connection_timeout: Duration,
+    /// The maximum number of outbound requests in flight to a single peer.
+    ///
+    /// Additional requests to the peer are queued until an in-flight request completes.
+    pub max_outbound_requests_per_peer: usize,
+    /// The minimum size (bytes) for published gossip to be compressed with zstd.
+    ///
+    /// Compressed gossip is tagged by the zstd frame header so peers can tell it apart from
+    /// uncompressed gossip. Peers without compression enabled still decode compressed gossip, but
+    /// they only publish uncompressed messages. Compression is disabled when `None`.
+    pub gossip_compression_threshold: Option<usize>,
+    /// The maximum number of attempts to dial a peer before the dial fails.
+    ///
+    /// Only transport errors (ie - connection refused or timed out) are retried.
+    pub max_dial_attempts: usize,
+    /// The delay before the first redial after a failed attempt.
+    ///
+    /// The delay doubles for each following attempt.
+    pub dial_retry_backoff: Duration,
+    /// The amount of time gossip from a removed authority is ignored instead of rejected.
+    ///
+    /// Messages published before the authorized publishers are updated can still be in flight.
+    /// These messages are ignored without penalizing the forwarding peers until the grace period
+    /// expires.
+    pub removed_publisher_grace_period: Duration,
+    /// The maximum number of outbound requests carrying certificates sent to a single peer per
+    /// second.
+    ///
+    /// Matches the anemo primary network's `send_certificate_rate_limit`. Requests that exceed
+    /// the rate are delayed until the peer's quota allows them. There is no limit when `None`.
+    /// Set from `Parameters::send_certificate_rate_limit`.
+    pub send_certificate_rate_limit: Option<NonZeroU32>,
 }
 
 impl Default for LibP2pConfig {
@@ -68,6 +105,58 @@ impl Default for LibP2pConfig {
             max_rpc_message_size: 1024 * 1024, // 1 MiB
             max_gossip_message_size: 12_000,   // 12kb
             max_idle_connection_timeout: Duration::from_secs(60 * 60), // 60min
+            max_outbound_requests_per_peer: 8,
+            gossip_compression_threshold: None,
+            max_dial_attempts: 3,
+            dial_retry_backoff: Duration::from_millis(500),
+            removed_publisher_grace_period: Duration::from_secs(10),
+            send_certificate_rate_limit: None,
+        }
+    }
+}
+
+/// Gossipsub mesh parameters derived from the committee size.
+///
+/// The default gossipsub mesh (D=6) is larger than the number of peers in small committees, which
+/// prevents the mesh from ever reaching its target degree. Committees with fewer peers than the
+/// default mesh size use a full mesh and a faster heartbeat so the mesh recovers quickly when a peer
+/// reconnects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GossipMeshConfig {
+    /// The target number of peers in the mesh.
+    pub mesh_n: usize,
+    /// The minimum number of peers in the mesh before more peers are grafted.
+    pub mesh_n_low: usize,
+    /// The maximum number of peers in the mesh before peers are pruned.
+    pub mesh_n_high: usize,
+    /// The minimum number of outbound peers in the mesh.
+    pub mesh_outbound_min: usize,
+    /// The interval between gossipsub heartbeats.
+    pub heartbeat_interval: Duration,
+}
+
+impl GossipMeshConfig {
+    /// The default gossipsub target mesh size.
+    const DEFAULT_MESH_N: usize = 6;
+
+    /// Derive the mesh parameters for a committee of `committee_size` nodes.
+    pub fn new(committee_size: usize) -> Self {
+        // every node except this one is a potential mesh peer
+        let peers = committee_size.saturating_sub(1).max(1);
+        let mesh_n = peers.min(Self::DEFAULT_MESH_N);
+        let heartbeat_interval = if peers <= Self::DEFAULT_MESH_N {
+            Duration::from_millis(500)
+        } else {
+            Duration::from_secs(1)
+        };
+
+        Self {
+            mesh_n,
+            // matches gossipsub defaults (5, 12, 2) for the default mesh size
+            mesh_n_low: (mesh_n * 5 / 6).max(1),
+            mesh_n_high: mesh_n * 2,
+            mesh_outbound_min: mesh_n / 3,
+            heartbeat_interval,
         }
     }
 }
@@ -144,7 +233,7 @@ impl Default for SyncConfig {
 }
 
 /// Configure the quic transport for libp2p.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct QuicConfig {
     /// Timeout for the initial handshake when establishing a connection.
     /// The actual timeout is the minimum of this and the [`Config::max_idle_timeout`].
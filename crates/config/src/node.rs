@@ -3,10 +3,15 @@
 use crate::{ConfigTrait, ValidatorInfo};
 use reth_chainspec::ChainSpec;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    num::NonZeroU32,
+    time::Duration,
+};
 use tn_types::{
-    adiri_genesis, get_available_tcp_port, get_available_udp_port, Address, BlsPublicKey,
-    BlsSignature, Genesis, Multiaddr, NetworkPublicKey, WorkerIndex,
+    adiri_genesis, get_available_tcp_port, get_available_udp_port, Address, AuthorityIdentifier,
+    BlsPublicKey, BlsSignature, EvmHardfork, Genesis, Multiaddr, NetworkPublicKey,
+    TimestampGranularity, WorkerId, WorkerIndex,
 };
 use tracing::info;
 
@@ -135,6 +140,12 @@ pub struct Parameters {
     /// even if batches have not reached `header_num_of_batches_threshold`.
     #[serde(with = "humantime_serde", default = "Parameters::default_min_header_delay")]
     pub min_header_delay: Duration,
+    /// The maximum time to collect votes for a proposed header.
+    ///
+    /// A certificate is formed as soon as votes reach quorum and late votes are ignored. If quorum
+    /// is not reached before this timeout, the primary abandons the proposed header.
+    #[serde(with = "humantime_serde", default = "Parameters::default_vote_aggregation_timeout")]
+    pub vote_aggregation_timeout: Duration,
 
     /// The depth of the garbage collection (Denominated in number of rounds).
     #[serde(default = "Parameters::default_gc_depth")]
@@ -159,6 +170,150 @@ pub struct Parameters {
     /// Worker timeout when request vote from peers.
     #[serde(default = "Parameters::default_batch_vote_timeout")]
     pub batch_vote_timeout: Duration,
+    /// The maximum number of transactions the worker's pending pool can hold.
+    ///
+    /// Transactions are evicted based on `pool_eviction_policy` once the limit is reached.
+    #[serde(default = "Parameters::default_max_pending_pool_txs")]
+    pub max_pending_pool_txs: usize,
+    /// The policy for evicting transactions when the pending pool is full.
+    #[serde(default)]
+    pub pool_eviction_policy: PoolEvictionPolicy,
+    /// The max amount of time a queued transaction stays in the worker's pool.
+    ///
+    /// Queued transactions (ie - nonce gaps that are never filled) are evicted after this
+    /// lifetime. `None` or a lifetime of zero never evicts queued transactions.
+    #[serde(with = "humantime_serde", default)]
+    pub max_queued_tx_lifetime: Option<Duration>,
+    /// The policy for propagating transactions the worker included in a batch.
+    ///
+    /// Included transactions stay in the pool until the batch reaches quorum.
+    #[serde(default)]
+    pub included_tx_propagation: IncludedTxPropagation,
+    /// The number of additional tasks validating transactions submitted to the worker's pool.
+    ///
+    /// Overrides reth's `txpool.additional_validation_tasks`. Signature verification at admission
+    /// is cpu heavy, so validators can raise this up to one less than the number of available cpu
+    /// cores because reth always runs one validation service in addition to these tasks. The cap
+    /// isn't enforced if the node can't read its available cores. `None` uses reth's value.
+    #[serde(default)]
+    pub pool_validation_tasks: Option<usize>,
+    /// The minimum priority fee (in wei) for transactions to enter the worker's pool.
+    ///
+    /// Transactions paying less than this are rejected when they are submitted.
+    #[serde(default)]
+    pub min_priority_fee: u128,
+    /// The capacity of the channel notifying the batch builder of new pending transactions.
+    ///
+    /// Notifications are dropped while the channel is full. The batch builder still checks the
+    /// pending pool every `max_batch_delay`, so dropped notifications only delay the next batch.
+    #[serde(default = "Parameters::default_pending_tx_listener_capacity")]
+    pub pending_tx_listener_capacity: usize,
+    /// The number of recent batch digests the worker keeps in memory.
+    ///
+    /// Availability checks for gossiped batches fall back to the database for older batches.
+    #[serde(default = "Parameters::default_recent_batches_depth")]
+    pub recent_batches_depth: usize,
+    /// The max number of batches each worker can have waiting for quorum at the same time.
+    ///
+    /// Concurrent batches never include transactions from the same sender. Increasing this can
+    /// raise the batch rate when quorum latency dominates.
+    #[serde(default = "Parameters::default_max_concurrent_batch_builds")]
+    pub max_concurrent_batch_builds: usize,
+    /// The minimum number of peers connected to the worker's network before building batches.
+    ///
+    /// Batches built before the worker can reach quorum are wasted work. `0` never defers
+    /// building.
+    #[serde(default)]
+    pub min_peers_to_build: usize,
+    /// The transports the primary and worker networks listen and dial on.
+    ///
+    /// Each listen address is also listened on with every other enabled transport (same ip and
+    /// port), so validators behind networks that block UDP can enable TCP as a fallback.
+    #[serde(default = "Parameters::default_network_transports")]
+    pub network_transports: Vec<NetworkTransport>,
+    /// The beneficiary for batches built by each worker.
+    ///
+    /// Workers without an entry use the validator's execution address.
+    #[serde(default)]
+    pub worker_beneficiaries: BTreeMap<WorkerId, Address>,
+    /// The consensus tables to read into the page cache when the node starts.
+    ///
+    /// Tables are identified by name (ie - "certificates", "batches"). Preloading is disabled
+    /// when empty.
+    #[serde(default)]
+    pub preload_tables: Vec<String>,
+    /// The unit for batch timestamps.
+    ///
+    /// Millisecond timestamps let workers build more than one batch per second without
+    /// timestamps running ahead of the parent. All validators must use the same granularity.
+    #[serde(default)]
+    pub batch_timestamp_granularity: TimestampGranularity,
+    /// The number of blocks behind the finalized block that execution state is retained for.
+    ///
+    /// Peer batches built on older parents can't be validated against the parent state. `None`
+    /// if the node does not prune state.
+    #[serde(default)]
+    pub retained_state_depth: Option<u64>,
+    /// The interval between pings to each connected peer on the consensus networks.
+    #[serde(with = "humantime_serde", default = "Parameters::default_peer_ping_interval")]
+    pub peer_ping_interval: Duration,
+    /// The amount of time to wait for a peer to respond to a ping.
+    #[serde(with = "humantime_serde", default = "Parameters::default_peer_ping_timeout")]
+    pub peer_ping_timeout: Duration,
+    /// The number of successive failed pings before a peer is disconnected.
+    ///
+    /// Pings detect peers that silently dropped the connection long before the transport's idle
+    /// timeout.
+    #[serde(default = "Parameters::default_max_peer_ping_failures")]
+    pub max_peer_ping_failures: usize,
+    /// Optional hardfork that overrides the EVM spec id used to execute consensus output.
+    ///
+    /// This is intended for testing forward-compatibility. The hardfork must be scheduled in the
+    /// chain spec. `None` executes with the network's current hardfork.
+    #[serde(default)]
+    pub evm_hardfork: Option<EvmHardfork>,
+    /// The interval to record the on-disk size of the consensus store.
+    ///
+    /// The size is exported with the `store_bytes` gauge. `None` disables the size monitor.
+    #[serde(with = "humantime_serde", default)]
+    pub store_size_interval: Option<Duration>,
+    /// The max number of blocks executed from a consensus output before the blocks are made
+    /// canonical and finalized.
+    ///
+    /// Large outputs are executed and finalized in chunks to bound the blocks held in memory.
+    /// `None` finalizes each output in a single step.
+    #[serde(default)]
+    pub max_blocks_per_finalization: Option<usize>,
+    /// The max delay between failed attempts to fetch missing certificates from peers.
+    ///
+    /// The delay doubles (with jitter) after each failed attempt until it reaches this cap, so a
+    /// partitioned node doesn't flood its peers with fetch requests.
+    #[serde(
+        with = "humantime_serde",
+        default = "Parameters::default_max_certificate_fetch_backoff"
+    )]
+    pub max_certificate_fetch_backoff: Duration,
+    /// The maximum number of outbound requests carrying certificates sent to a single peer per
+    /// second.
+    ///
+    /// Requests that exceed the rate are delayed in order until the peer's quota allows them.
+    /// There is no limit when `None`.
+    #[serde(default)]
+    pub send_certificate_rate_limit: Option<NonZeroU32>,
+    /// The stake used to elect each authority as leader.
+    ///
+    /// Leaders are elected proportionally to these stakes, which should match the stakes in the
+    /// on-chain `ConsensusRegistry`. Authorities missing from a non-empty map are never elected.
+    /// All validators must use the same weights. Leaders are elected by the committee's voting
+    /// power when empty.
+    #[serde(default)]
+    pub leader_weights: BTreeMap<AuthorityIdentifier, u64>,
+    /// Batch gas limits accepted from peers in addition to the protocol's limit.
+    ///
+    /// Set this to both the old and new limits during a network gas limit change so peer batches
+    /// built with either limit remain valid.
+    #[serde(default)]
+    pub accepted_batch_gas_limits: BTreeSet<u64>,
 }
 
 impl Parameters {
@@ -178,6 +333,10 @@ impl Parameters {
         Duration::from_secs(5)
     }
 
+    fn default_vote_aggregation_timeout() -> Duration {
+        Duration::from_secs(30)
+    }
+
     pub fn default_gc_depth() -> u32 {
         50
     }
@@ -201,6 +360,75 @@ impl Parameters {
     fn default_batch_vote_timeout() -> Duration {
         Duration::from_secs(10)
     }
+
+    fn default_max_pending_pool_txs() -> usize {
+        10_000
+    }
+
+    fn default_pending_tx_listener_capacity() -> usize {
+        2_048
+    }
+
+    fn default_recent_batches_depth() -> usize {
+        1_000
+    }
+
+    fn default_max_concurrent_batch_builds() -> usize {
+        1
+    }
+
+    fn default_network_transports() -> Vec<NetworkTransport> {
+        vec![NetworkTransport::Quic]
+    }
+
+    fn default_peer_ping_interval() -> Duration {
+        Duration::from_secs(15)
+    }
+
+    fn default_peer_ping_timeout() -> Duration {
+        Duration::from_secs(20)
+    }
+
+    fn default_max_peer_ping_failures() -> usize {
+        3
+    }
+
+    fn default_max_certificate_fetch_backoff() -> Duration {
+        Duration::from_secs(60)
+    }
+}
+
+/// The policy for evicting transactions from the worker's pending pool once it is full.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolEvictionPolicy {
+    /// Evict the transactions paying the lowest fees first.
+    #[default]
+    LowestFeeFirst,
+}
+
+/// The policy for propagating transactions after the worker includes them in a batch.
+///
+/// Included transactions are still in peers' pools until the batch reaches quorum, so
+/// re-broadcasting them is usually redundant.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IncludedTxPropagation {
+    /// Included transactions keep the propagate flag they were validated with.
+    #[default]
+    Propagate,
+    /// Included transactions are marked `propagate = false` while the batch waits for quorum.
+    Suppress,
+}
+
+/// The transports for consensus networks.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkTransport {
+    /// QUIC over UDP.
+    Quic,
+    /// TCP secured with noise and multiplexed with yamux.
+    Tcp,
 }
 
 /// Admin server settings.
@@ -263,6 +491,7 @@ impl Default for Parameters {
             max_header_num_of_batches: Parameters::default_max_header_num_of_batches(),
             max_header_delay: Parameters::default_max_header_delay(),
             min_header_delay: Parameters::default_min_header_delay(),
+            vote_aggregation_timeout: Parameters::default_vote_aggregation_timeout(),
             gc_depth: Parameters::default_gc_depth(),
             sync_retry_delay: Parameters::default_sync_retry_delay(),
             sync_retry_nodes: Parameters::default_sync_retry_nodes(),
@@ -270,6 +499,31 @@ impl Default for Parameters {
             max_concurrent_requests: Parameters::default_max_concurrent_requests(),
             prometheus_metrics: PrometheusMetricsParameters::default(),
             batch_vote_timeout: Parameters::default_batch_vote_timeout(),
+            max_pending_pool_txs: Parameters::default_max_pending_pool_txs(),
+            pool_eviction_policy: PoolEvictionPolicy::default(),
+            max_queued_tx_lifetime: None,
+            included_tx_propagation: IncludedTxPropagation::default(),
+            pool_validation_tasks: None,
+            min_priority_fee: 0,
+            pending_tx_listener_capacity: Parameters::default_pending_tx_listener_capacity(),
+            recent_batches_depth: Parameters::default_recent_batches_depth(),
+            max_concurrent_batch_builds: Parameters::default_max_concurrent_batch_builds(),
+            min_peers_to_build: 0,
+            network_transports: Parameters::default_network_transports(),
+            worker_beneficiaries: BTreeMap::new(),
+            preload_tables: Vec::new(),
+            batch_timestamp_granularity: TimestampGranularity::default(),
+            retained_state_depth: None,
+            peer_ping_interval: Parameters::default_peer_ping_interval(),
+            peer_ping_timeout: Parameters::default_peer_ping_timeout(),
+            max_peer_ping_failures: Parameters::default_max_peer_ping_failures(),
+            evm_hardfork: None,
+            store_size_interval: None,
+            max_blocks_per_finalization: None,
+            max_certificate_fetch_backoff: Parameters::default_max_certificate_fetch_backoff(),
+            send_certificate_rate_limit: None,
+            leader_weights: BTreeMap::new(),
+            accepted_batch_gas_limits: BTreeSet::new(),
         }
     }
 }
@@ -283,17 +537,56 @@ impl Parameters {
         params
     }
 
+    /// Return the beneficiary for the worker's batches.
+    ///
+    /// The `default` address is used if the worker's beneficiary is not configured.
+    pub fn worker_beneficiary(&self, worker_id: &WorkerId, default: Address) -> Address {
+        self.worker_beneficiaries.get(worker_id).copied().unwrap_or(default)
+    }
+
     /// Tracing::info! for [Self].
     pub fn tracing(&self) {
         info!("Header number of batches threshold set to {}", self.header_num_of_batches_threshold);
         info!("Header max number of batches set to {}", self.max_header_num_of_batches);
         info!("Max header delay set to {} ms", self.max_header_delay.as_millis());
+        info!("Vote aggregation timeout set to {} ms", self.vote_aggregation_timeout.as_millis());
         info!("Min header delay set to {} ms", self.min_header_delay.as_millis());
         info!("Garbage collection depth set to {} rounds", self.gc_depth);
         info!("Sync retry delay set to {} ms", self.sync_retry_delay.as_millis());
         info!("Sync retry nodes set to {} nodes", self.sync_retry_nodes);
         info!("Max batch delay set to {} ms", self.max_batch_delay.as_millis());
         info!("Max concurrent requests set to {}", self.max_concurrent_requests);
+        info!("Max pending pool transactions set to {}", self.max_pending_pool_txs);
+        info!("Pool eviction policy set to {:?}", self.pool_eviction_policy);
+        info!("Max queued transaction lifetime set to {:?}", self.max_queued_tx_lifetime);
+        info!("Included transaction propagation set to {:?}", self.included_tx_propagation);
+        info!("Pool validation tasks set to {:?}", self.pool_validation_tasks);
+        info!("Min priority fee set to {} wei", self.min_priority_fee);
+        info!("Pending tx listener capacity set to {}", self.pending_tx_listener_capacity);
+        info!("Recent batches depth set to {}", self.recent_batches_depth);
+        info!("Min peers to build set to {}", self.min_peers_to_build);
+        info!("Batch timestamp granularity set to {:?}", self.batch_timestamp_granularity);
+        info!("Retained state depth set to {:?}", self.retained_state_depth);
+        info!("Peer ping interval set to {} ms", self.peer_ping_interval.as_millis());
+        info!("Peer ping timeout set to {} ms", self.peer_ping_timeout.as_millis());
+        info!("Max peer ping failures set to {}", self.max_peer_ping_failures);
+        info!("EVM hardfork override set to {:?}", self.evm_hardfork);
+        info!("Store size interval set to {:?}", self.store_size_interval);
+        info!("Max blocks per finalization set to {:?}", self.max_blocks_per_finalization);
+        info!(
+            "Max certificate fetch backoff set to {} ms",
+            self.max_certificate_fetch_backoff.as_millis()
+        );
+        info!("Send certificate rate limit set to {:?}", self.send_certificate_rate_limit);
+        for gas_limit in self.accepted_batch_gas_limits.iter() {
+            info!("Accepted batch gas limit set to {gas_limit}");
+        }
+        for (authority, stake) in self.leader_weights.iter() {
+            info!("Leader weight for {authority} set to {stake}");
+        }
+        for (worker_id, beneficiary) in self.worker_beneficiaries.iter() {
+            info!("Worker {worker_id} beneficiary set to {beneficiary}");
+        }
         info!("Prometheus metrics server will run on {}", self.prometheus_metrics.socket_addr);
     }
 }
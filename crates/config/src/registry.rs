@@ -0,0 +1,39 @@
+//! Bindings for the on-chain `ConsensusRegistry`.
+//!
+//! The registry is deployed at genesis behind a proxy at [CONSENSUS_REGISTRY_ADDRESS]. Each
+//! validator's entry records the execution address (`ecdsaPubkey`) that receives the validator's
+//! block rewards.
+
+use tn_types::{hex_literal::hex, sol, Address};
+
+/// The address of the `ConsensusRegistry` proxy deployed at genesis.
+pub const CONSENSUS_REGISTRY_ADDRESS: Address =
+    Address::new(hex!("07e17e17e17e17e17e17e17e17e17e17e17e17e1"));
+
+sol! {
+    /// The subset of the `ConsensusRegistry` interface used by the node.
+    interface ConsensusRegistry {
+        /// The status of a validator in the registry.
+        enum ValidatorStatus {
+            Undefined,
+            PendingActivation,
+            Active,
+            PendingExit,
+            Exited
+        }
+
+        /// A validator's entry in the registry.
+        struct ValidatorInfo {
+            bytes blsPubkey;
+            bytes32 ed25519Pubkey;
+            address ecdsaPubkey;
+            uint32 activationEpoch;
+            uint32 exitEpoch;
+            uint24 validatorIndex;
+            ValidatorStatus currentStatus;
+        }
+
+        /// Return all validators with the status.
+        function getValidators(ValidatorStatus status) external view returns (ValidatorInfo[] memory);
+    }
+}
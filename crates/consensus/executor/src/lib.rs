@@ -34,6 +34,9 @@ pub struct Executor;
 
 impl Executor {
     /// Spawn a new client subscriber.
+    ///
+    /// The subscriber is stopped with the [tn_types::ShutdownStage::Network] tasks once
+    /// `rx_shutdown` resolves.
     pub fn spawn<DB: Database>(
         config: ConsensusConfig<DB>,
         rx_shutdown: Noticer,
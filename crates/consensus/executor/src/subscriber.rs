@@ -20,8 +20,8 @@ use tn_primary::{
 use tn_storage::CertificateStore;
 use tn_types::{
     AuthorityIdentifier, Batch, BlockHash, CommittedSubDag, Committee, ConsensusHeader,
-    ConsensusOutput, Database, Hash as _, Noticer, TaskManager, TaskManagerClone, Timestamp,
-    TnReceiver, TnSender, B256,
+    ConsensusOutput, Database, Hash as _, Noticer, ShutdownStage, TaskManager, TaskManagerClone,
+    Timestamp, TnReceiver, TnSender, B256,
 };
 use tracing::{debug, error, info};
 
@@ -67,7 +67,8 @@ pub fn spawn_subscriber<DB: Database>(
     match mode {
         // If we are active then partcipate in consensus.
         NodeMode::CvvActive => {
-            task_manager.spawn_task(
+            task_manager.spawn_ordered_task(
+                ShutdownStage::Network,
                 "subscriber consensus",
                 monitored_future!(
                     async move {
@@ -81,7 +82,8 @@ pub fn spawn_subscriber<DB: Database>(
         NodeMode::CvvInactive => {
             let clone = task_manager.get_spawner();
             // If we are not active but are a CVV then catch up and rejoin.
-            task_manager.spawn_task(
+            task_manager.spawn_ordered_task(
+                ShutdownStage::Network,
                 "subscriber catch up and rejoin consensus",
                 monitored_future!(
                     async move {
@@ -95,7 +97,8 @@ pub fn spawn_subscriber<DB: Database>(
         NodeMode::Observer => {
             let clone = task_manager.get_spawner();
             // If we are not active then just follow consensus.
-            task_manager.spawn_task(
+            task_manager.spawn_ordered_task(
+                ShutdownStage::Network,
                 "subscriber follow consensus",
                 monitored_future!(
                     async move {
@@ -236,11 +239,12 @@ impl<DB: Database> Subscriber<DB> {
 
                     // Record the latest ConsensusHeader, we probably don't need this in this mode but keep it up to date anyway.
                     // Note we don't bother sending this to the consensus header channel since not needed when an active CVV.
-                    if let Err(e) = self.consensus_bus.last_consensus_header().send(ConsensusHeader { parent_hash, sub_dag: sub_dag.clone(), number, extra: B256::default() }) {
+                    let header = ConsensusHeader { parent_hash, sub_dag: sub_dag.clone(), number, extra: B256::default() };
+                    if let Err(e) = self.consensus_bus.last_consensus_header().send(header.clone()) {
                         error!(target: "subscriber", "error sending latest consensus header for authority {}: {}", self.inner.authority_id, e);
                         return Ok(());
                     }
-                    if let Err(e) = network.publish_consensus(number, last_parent).await {
+                    if let Err(e) = network.publish_consensus(last_parent, header).await {
                         error!(target: "subscriber", "error publishing latest consensus to network {}: {}", self.inner.authority_id, e);
                     }
                     last_number += 1;
@@ -413,6 +417,8 @@ impl<DB: Database> Subscriber<DB> {
     }
 
     fn record_fetched_batch_metrics(&self, batch: &Batch, digest: &BlockHash) {
+        let created_at =
+            self.config.parameters().batch_timestamp_granularity.to_secs(batch.created_at());
         if let Some(received_at) = batch.received_at() {
             let remote_duration = received_at.elapsed().as_secs_f64();
             debug!(
@@ -425,7 +431,7 @@ impl<DB: Database> Subscriber<DB> {
                 .with_label_values(&["other"])
                 .observe(remote_duration);
         } else {
-            let local_duration = batch.created_at().elapsed().as_secs_f64();
+            let local_duration = created_at.elapsed().as_secs_f64();
             debug!(
                 "Batch was fetched for execution after being created locally {}s ago.",
                 local_duration
@@ -437,7 +443,7 @@ impl<DB: Database> Subscriber<DB> {
                 .observe(local_duration);
         };
 
-        let block_fetch_duration = batch.created_at().elapsed().as_secs_f64();
+        let block_fetch_duration = created_at.elapsed().as_secs_f64();
         self.consensus_bus.executor_metrics().block_execution_latency.observe(block_fetch_duration);
         debug!(
             "Block {:?} took {} seconds since it has been created to when it has been fetched for execution",
@@ -456,11 +462,11 @@ mod tests {
     use tn_network_types::MockPrimaryToWorkerClient;
     use tn_primary::consensus::{Bullshark, Consensus, LeaderSchedule};
     use tn_primary_metrics::ConsensusMetrics;
-    use tn_storage::mem_db::MemDatabase;
+    use tn_storage::{mem_db::MemDatabase, ConsensusStore as _};
     use tn_test_utils::CommitteeFixture;
     use tn_types::{
-        Certificate, CertificateDigest, ExecHeader, HeaderBuilder, Round, SealedHeader,
-        TimestampSec, WorkerId, DEFAULT_BAD_NODES_STAKE_THRESHOLD,
+        Certificate, CertificateDigest, ExecHeader, HeaderBuilder, ReputationScores, Round,
+        SealedHeader, TimestampSec, WorkerId, DEFAULT_BAD_NODES_STAKE_THRESHOLD, U256,
     };
     use tokio::sync::mpsc;
 
@@ -635,4 +641,52 @@ mod tests {
 
         Ok(())
     }
+
+    /// A consensus header that was partially executed before a restart between chunks is executed
+    /// again.
+    #[tokio::test]
+    async fn test_missing_consensus_resumes_partially_executed_header() -> eyre::Result<()> {
+        let fixture = CommitteeFixture::builder(MemDatabase::default).build();
+        let primary = fixture.authorities().next().unwrap();
+        let config = primary.consensus_config().clone();
+        let consensus_bus = ConsensusBus::new();
+
+        // store two consensus headers with 3 batches each
+        let parents: BTreeSet<_> = fixture.genesis().collect();
+        let mut headers = Vec::new();
+        for number in 1..=2 {
+            let (_, cert, _) =
+                signed_cert(primary.id(), number as Round, parents.clone(), &fixture);
+            let sub_dag = CommittedSubDag::new(
+                vec![cert.clone()],
+                cert,
+                number,
+                ReputationScores::default(),
+                None,
+            );
+            assert_eq!(sub_dag.num_primary_blocks(), 3);
+            config.node_storage().write_subdag_for_test(number, sub_dag.clone());
+            headers.push(ConsensusHeader { number, sub_dag, ..Default::default() });
+        }
+
+        // the node stopped after the first chunk of the first header (2 of 3 batches)
+        let executed_block = |batch_index: u64| {
+            SealedHeader::seal(ExecHeader {
+                number: batch_index + 1,
+                parent_beacon_block_root: Some(headers[0].digest()),
+                difficulty: U256::from(batch_index),
+                ..Default::default()
+            })
+        };
+        consensus_bus.recent_blocks().send_modify(|blocks| blocks.push_latest(executed_block(1)));
+        let missing = get_missing_consensus(&config, &consensus_bus).await?;
+        assert_eq!(missing.iter().map(|header| header.number).collect::<Vec<_>>(), vec![1, 2]);
+
+        // once every batch is executed only the next header is missing
+        consensus_bus.recent_blocks().send_modify(|blocks| blocks.push_latest(executed_block(2)));
+        let missing = get_missing_consensus(&config, &consensus_bus).await?;
+        assert_eq!(missing.iter().map(|header| header.number).collect::<Vec<_>>(), vec![2]);
+
+        Ok(())
+    }
 }
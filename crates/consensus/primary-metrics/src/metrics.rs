@@ -285,6 +285,10 @@ pub struct PrimaryMetrics {
     pub proposed_header_round: IntGauge,
     /// The number of received votes for the proposed last round
     pub votes_received_last_round: IntGauge,
+    /// count number of votes received from each authority per epoch
+    pub authority_votes_received: IntCounterVec,
+    /// count number of certificates observed from each authority per epoch
+    pub authority_certificates_observed: IntCounterVec,
     // total number of parent certificates included in votes.
     pub certificates_in_votes: IntCounter,
     /// The round of the latest created certificate by our node
@@ -313,6 +317,11 @@ pub struct PrimaryMetrics {
     pub certificate_fetcher_num_certificates_processed: IntCounter,
     /// Total time spent in certificate verifications, in microseconds.
     pub certificate_fetcher_total_verification_us: IntCounter,
+    /// Number of certificate signatures verified directly.
+    pub certificate_signatures_verified: IntCounter,
+    /// Number of certificates that skipped signature verification because the signature was
+    /// already verified.
+    pub certificate_verification_cache_hits: IntCounter,
     /// Number of votes that were requested but not sent due to previously having voted differently
     pub votes_dropped_equivocation_protection: IntCounter,
     /// Number of pending batches in proposer
@@ -379,6 +388,18 @@ impl PrimaryMetrics {
                 "The number of received votes for the proposed last round",
                 registry
             )?,
+            authority_votes_received: register_int_counter_vec_with_registry!(
+                "authority_votes_received",
+                "Number of votes received from each authority for our proposed headers",
+                &["authority", "epoch"],
+                registry
+            )?,
+            authority_certificates_observed: register_int_counter_vec_with_registry!(
+                "authority_certificates_observed",
+                "Number of certificates observed from each authority while aggregating parents",
+                &["authority", "epoch"],
+                registry
+            )?,
             certificates_in_votes: register_int_counter_with_registry!(
                 "certificates_in_votes",
                 "Total number of parent certificates included in votes.",
@@ -455,6 +476,16 @@ impl PrimaryMetrics {
                 "Total time spent in certificate verifications, in microseconds.",
                 registry
             )?,
+            certificate_signatures_verified: register_int_counter_with_registry!(
+                "certificate_signatures_verified",
+                "Number of certificate signatures verified directly.",
+                registry
+            )?,
+            certificate_verification_cache_hits: register_int_counter_with_registry!(
+                "certificate_verification_cache_hits",
+                "Number of certificates that skipped signature verification because the signature was already verified.",
+                registry
+            )?,
             votes_dropped_equivocation_protection: register_int_counter_with_registry!(
                 "votes_dropped_equivocation_protection",
                 "Number of votes that were requested but not sent due to previously having voted differently",
@@ -2,6 +2,7 @@
 
 use crate::{error::CertManagerResult, ConsensusBus};
 use std::collections::{BTreeMap, HashSet};
+use tn_primary_metrics::PrimaryMetrics;
 use tn_types::{AuthorityIdentifier, Certificate, Committee, Round, TnSender as _, VotingPower};
 use tracing::trace;
 
@@ -33,7 +34,7 @@ impl CertificatesAggregatorManager {
             .aggregators
             .entry(round)
             .or_insert_with(|| Box::new(CertificatesAggregator::new()))
-            .append(certificate, committee);
+            .append(certificate, committee, &self.consensus_bus.primary_metrics().node_metrics);
 
         // forward to proposer if enough parents to advance the round (2f+1)
         if let Some(parents) = quorum {
@@ -76,6 +77,7 @@ impl CertificatesAggregator {
         &mut self,
         certificate: Certificate,
         committee: &Committee,
+        metrics: &PrimaryMetrics,
     ) -> Option<Vec<Certificate>> {
         let origin = certificate.origin().clone();
 
@@ -84,6 +86,12 @@ impl CertificatesAggregator {
             return None;
         }
 
+        // track participation
+        metrics
+            .authority_certificates_observed
+            .with_label_values(&[&origin.to_string(), &certificate.epoch().to_string()])
+            .inc();
+
         // accumulate certificates and voting power
         self.certificates.push(certificate);
         self.weight += committee.voting_power_by_id(&origin);
@@ -4,3 +4,7 @@ pub(crate) mod certificates;
 pub(crate) mod sync;
 mod votes;
 pub(crate) use votes::VotesAggregator;
+
+#[cfg(test)]
+#[path = "../tests/aggregators_tests.rs"]
+mod aggregators_tests;
@@ -55,6 +55,10 @@ impl VotesAggregator {
 
         // update metrics
         self.metrics.votes_received_last_round.set(self.votes.len() as i64);
+        self.metrics
+            .authority_votes_received
+            .with_label_values(&[&author.to_string(), &vote.epoch().to_string()])
+            .inc();
 
         // check if this vote reaches quorum
         if self.weight >= committee.quorum_threshold() {
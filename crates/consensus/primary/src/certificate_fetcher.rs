@@ -8,9 +8,10 @@ use crate::{
 };
 use consensus_metrics::{monitored_future, monitored_scope};
 use futures::{stream::FuturesUnordered, StreamExt};
-use rand::{rngs::ThreadRng, seq::SliceRandom};
+use rand::{rngs::ThreadRng, seq::SliceRandom, Rng as _};
 use std::{
     collections::{BTreeMap, BTreeSet},
+    pin::Pin,
     sync::Arc,
     time::Duration,
 };
@@ -21,11 +22,11 @@ use tn_primary_metrics::PrimaryMetrics;
 use tn_storage::CertificateStore;
 use tn_types::{
     validate_received_certificate, AuthorityIdentifier, Certificate, Committee, Database, Noticer,
-    Round, TaskManager, TnReceiver, TnSender,
+    Round, ShutdownStage, TaskManager, TnReceiver, TnSender,
 };
 use tokio::{
     task::JoinSet,
-    time::{sleep, timeout, Instant},
+    time::{sleep, timeout, Instant, Sleep},
 };
 use tracing::{debug, error, instrument, trace};
 
@@ -40,6 +41,9 @@ const PARALLEL_FETCH_REQUEST_INTERVAL_SECS: Duration = Duration::from_secs(5);
 // The timeout for an iteration of parallel fetch requests over all peers would be
 // num peers * PARALLEL_FETCH_REQUEST_INTERVAL_SECS + PARALLEL_FETCH_REQUEST_ADDITIONAL_TIMEOUT
 const PARALLEL_FETCH_REQUEST_ADDITIONAL_TIMEOUT: Duration = Duration::from_secs(15);
+// The delay before fetching again after the first failed fetch. The delay doubles for each
+// following failure up to `Parameters::max_certificate_fetch_backoff`.
+const INITIAL_FETCH_BACKOFF: Duration = Duration::from_secs(1);
 
 #[derive(Clone, Debug)]
 pub enum CertificateFetcherCommand {
@@ -49,6 +53,46 @@ pub enum CertificateFetcherCommand {
     Kick,
 }
 
+/// Exponential backoff with jitter between failed fetch attempts.
+#[derive(Debug)]
+struct FetchBackoff {
+    /// The delay after the first failure.
+    initial: Duration,
+    /// The max delay between attempts.
+    max: Duration,
+    /// The number of successive failed fetch attempts.
+    failures: u32,
+}
+
+impl FetchBackoff {
+    /// Create a new instance of [Self].
+    fn new(initial: Duration, max: Duration) -> Self {
+        Self { initial, max, failures: 0 }
+    }
+
+    /// Record a failed fetch and return the delay before the next attempt.
+    ///
+    /// The backoff doubles for each successive failure and is capped at the max delay. The
+    /// returned delay is a random value between half of the backoff and the full backoff, so
+    /// nodes that failed at the same time don't retry together.
+    fn next_delay(&mut self) -> Duration {
+        let backoff = self.backoff(self.failures);
+        self.failures = self.failures.saturating_add(1);
+        let half = backoff / 2;
+        half + ThreadRng::default().gen_range(Duration::ZERO..=backoff - half)
+    }
+
+    /// The backoff (without jitter) after `failures` previous failures.
+    fn backoff(&self, failures: u32) -> Duration {
+        self.initial.saturating_mul(2u32.saturating_pow(failures)).min(self.max)
+    }
+
+    /// Reset the backoff after a successful fetch.
+    fn reset(&mut self) {
+        self.failures = 0;
+    }
+}
+
 /// The CertificateFetcher is responsible for fetching certificates that this primary is missing
 /// from peers. It operates a loop which listens for commands to fetch a specific certificate's
 /// ancestors, or just to start one fetch attempt.
@@ -76,7 +120,15 @@ pub(crate) struct CertificateFetcher<DB> {
     /// correctness).
     targets: BTreeMap<AuthorityIdentifier, Round>,
     /// Keeps the handle to the (at most one) inflight fetch certificates task.
-    fetch_certificates_task: JoinSet<()>,
+    ///
+    /// The task returns true if certificates were fetched.
+    fetch_certificates_task: JoinSet<bool>,
+    /// The backoff between failed fetch attempts.
+    backoff: FetchBackoff,
+    /// The timer for the next fetch attempt after a failed fetch.
+    retry_timer: Pin<Box<Sleep>>,
+    /// Indicates fetching is paused until the retry timer expires.
+    retry_pending: bool,
 }
 
 /// Thread-safe internal state of CertificateFetcher shared with its fetch task.
@@ -104,7 +156,11 @@ impl<DB: Database> CertificateFetcher<DB> {
         let authority_id = config.authority().id();
         let committee = config.committee().clone();
         let certificate_store = config.node_storage().clone();
-        let rx_shutdown = config.shutdown().subscribe();
+        let rx_shutdown = task_manager.stage_shutdown(ShutdownStage::Network);
+        let backoff = FetchBackoff::new(
+            INITIAL_FETCH_BACKOFF,
+            config.parameters().max_certificate_fetch_backoff,
+        );
         let state = Arc::new(CertificateFetcherState {
             authority_id,
             network,
@@ -113,7 +169,8 @@ impl<DB: Database> CertificateFetcher<DB> {
             config: config.clone(),
         });
 
-        task_manager.spawn_task(
+        task_manager.spawn_ordered_task(
+            ShutdownStage::Network,
             "certificate fetcher task",
             monitored_future!(
                 async move {
@@ -125,6 +182,9 @@ impl<DB: Database> CertificateFetcher<DB> {
                         rx_shutdown,
                         targets: BTreeMap::new(),
                         fetch_certificates_task: JoinSet::new(),
+                        backoff,
+                        retry_timer: Box::pin(sleep(Duration::ZERO)),
+                        retry_pending: false,
                     }
                     .run()
                     .await
@@ -199,7 +259,14 @@ impl<DB: Database> CertificateFetcher<DB> {
                 },
                 Some(result) = self.fetch_certificates_task.join_next(), if !self.fetch_certificates_task.is_empty() => {
                     match result {
-                        Ok(()) => {},
+                        Ok(true) => self.backoff.reset(),
+                        Ok(false) => {
+                            // wait before fetching again so a partitioned node doesn't flood peers
+                            let delay = self.backoff.next_delay();
+                            debug!(target: "primary::cert_fetcher", ?delay, "failed to fetch certificates - backing off");
+                            self.retry_timer.as_mut().reset(Instant::now() + delay);
+                            self.retry_pending = true;
+                        }
                         Err(e) => {
                             if e.is_cancelled() {
                                 // avoid crashing on ungraceful shutdown
@@ -218,6 +285,12 @@ impl<DB: Database> CertificateFetcher<DB> {
                         self.kickstart();
                     }
                 },
+                _ = &mut self.retry_timer, if self.retry_pending => {
+                    self.retry_pending = false;
+                    if self.fetch_certificates_task.is_empty() {
+                        self.kickstart();
+                    }
+                },
                 _ = &self.rx_shutdown => {
                     return
                 }
@@ -231,6 +304,11 @@ impl<DB: Database> CertificateFetcher<DB> {
     // continue until there are no more target rounds to catch up to.
     #[allow(clippy::mutable_key_type)]
     fn kickstart(&mut self) {
+        // Wait for the backoff to expire after a failed fetch.
+        if self.retry_pending {
+            return;
+        }
+
         // Skip fetching certificates at or below the gc round.
         let gc_round = self.gc_round();
         // Skip fetching certificates that already exist locally.
@@ -270,6 +348,7 @@ impl<DB: Database> CertificateFetcher<DB> {
         });
         if self.targets.is_empty() {
             debug!(target: "primary::cert_fetcher", "Certificates have caught up. Skip fetching.");
+            self.backoff.reset();
             return;
         }
 
@@ -287,19 +366,23 @@ impl<DB: Database> CertificateFetcher<DB> {
             state.metrics.certificate_fetcher_inflight_fetch.inc();
 
             let now = Instant::now();
-            match run_fetch_task(state.clone(), committee, gc_round, written_rounds).await {
-                Ok(_) => {
-                    debug!(target: "primary::cert_fetcher",
-                        "Finished task to fetch certificates successfully, elapsed = {}s",
-                        now.elapsed().as_secs_f64()
-                    );
-                }
-                Err(e) => {
-                    error!(target: "primary::cert_fetcher", ?e, "Error from fetch certificates task");
-                }
-            };
+            let fetched =
+                match run_fetch_task(state.clone(), committee, gc_round, written_rounds).await {
+                    Ok(_) => {
+                        debug!(target: "primary::cert_fetcher",
+                            "Finished task to fetch certificates successfully, elapsed = {}s",
+                            now.elapsed().as_secs_f64()
+                        );
+                        true
+                    }
+                    Err(e) => {
+                        error!(target: "primary::cert_fetcher", ?e, "Error from fetch certificates task");
+                        false
+                    }
+                };
 
             state.metrics.certificate_fetcher_inflight_fetch.dec();
+            fetched
         }));
     }
 
@@ -20,7 +20,7 @@ use tn_types::{
     ensure,
     error::{DagError, DagResult},
     AuthorityIdentifier, Certificate, CertificateDigest, Committee, Database, Header, Noticer,
-    TaskManager, TnReceiver, TnSender, Vote, CHANNEL_CAPACITY,
+    ShutdownStage, TaskManager, TnReceiver, TnSender, Vote, CHANNEL_CAPACITY,
 };
 use tokio::sync::broadcast;
 use tracing::{debug, enabled, error, info, instrument, trace, warn};
@@ -67,7 +67,8 @@ impl<DB: Database> Certifier<DB> {
         primary_network: PrimaryNetworkHandle,
         task_manager: &TaskManager,
     ) {
-        let rx_shutdown = config.shutdown().subscribe();
+        // stop requesting votes before the network stops
+        let rx_shutdown = task_manager.stage_shutdown(ShutdownStage::Proposing);
         let primary_metrics = consensus_bus.primary_metrics().node_metrics.clone();
         // These channels are used internally to this module (file) and don't need to go in the
         // consensus bus. If this changes they can move.  Note there can be issues receiving
@@ -92,7 +93,9 @@ impl<DB: Database> Certifier<DB> {
         // TODO- these tasks to send to each peer should be replaced with a libp2p pub/sub topic.
         for (name, rx_own_certificate_broadcast) in broadcast_targets.into_iter() {
             trace!(target:"primary::synchronizer::broadcast_certificates", ?name, "spawning sender for peer");
-            task_manager.spawn_task(
+            // exits once the certifier stops and drops the broadcast sender
+            task_manager.spawn_ordered_task(
+                ShutdownStage::Proposing,
                 format!("broadcast certificates to {name}"),
                 Self::push_certificates(
                     primary_network.clone(),
@@ -108,7 +111,7 @@ impl<DB: Database> Certifier<DB> {
             }
         }
 
-        task_manager.spawn_task("certifier task", monitored_future!(
+        task_manager.spawn_ordered_task(ShutdownStage::Proposing, "certifier task", monitored_future!(
             async move {
                 info!(target: "primary::certifier", "Certifier on node {} has started successfully.", config.authority().id());
                 Self {
@@ -279,6 +282,13 @@ impl<DB: Database> Certifier<DB> {
                 self.request_vote(name, header)
             })
             .collect();
+        // Abandon the header if votes don't reach quorum in time.
+        //
+        // Once quorum is reached the certificate is formed immediately and outstanding vote
+        // requests are dropped.
+        let aggregation_timeout =
+            tokio::time::sleep(self.config.parameters().vote_aggregation_timeout);
+        tokio::pin!(aggregation_timeout);
         loop {
             if certificate.is_some() {
                 break;
@@ -302,6 +312,10 @@ impl<DB: Database> Certifier<DB> {
                         }
                     }
                 },
+                _ = &mut aggregation_timeout => {
+                    warn!(target: "primary::certifier", ?authority_id, "vote aggregation timed out for Header proposal {header} for round {}", header.round());
+                    break;
+                },
                 _ = rx_headers.recv() => {
                     warn!(target: "primary::certifier", ?authority_id, "canceling Header proposal {header} for round {}", header.round());
                     // This allows us to inturupt the propose_header future- just put it back on the headers channel to get picked up in outer select.
@@ -4,7 +4,7 @@ use super::Dag;
 use parking_lot::RwLock;
 use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fmt::{Debug, Formatter},
     sync::Arc,
 };
@@ -175,6 +175,63 @@ impl LeaderSwapTable {
     }
 }
 
+/// Stake weights used to elect leaders, keyed by authority.
+///
+/// These are expected to be sourced from the on-chain `ConsensusRegistry` so that the frequency
+/// an authority is elected leader is proportional to its stake. Authorities missing from the
+/// weights are never elected while weights are in effect.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LeaderWeights {
+    /// The stake for each authority.
+    stakes: BTreeMap<AuthorityIdentifier, u64>,
+}
+
+impl LeaderWeights {
+    /// Create a new instance of [Self] from the provided stakes.
+    pub fn new(stakes: BTreeMap<AuthorityIdentifier, u64>) -> Self {
+        Self { stakes }
+    }
+
+    /// Return the stake for an authority, or zero if the authority is unknown.
+    pub fn stake(&self, id: &AuthorityIdentifier) -> u64 {
+        self.stakes.get(id).copied().unwrap_or_default()
+    }
+
+    /// The total stake across all authorities.
+    pub fn total_stake(&self) -> u64 {
+        self.stakes.values().fold(0u64, |total, stake| total.saturating_add(*stake))
+    }
+
+    /// Elect a leader from the committee in a stake-weighted choice seeded by the round.
+    ///
+    /// Returns `None` if none of the committee members have any stake.
+    fn elect(&self, committee: &Committee, round: Round) -> Option<Authority> {
+        let mut seed_bytes = [0u8; 32];
+        seed_bytes[32 - 8..].copy_from_slice(&(round as u64).to_le_bytes());
+        let mut rng = StdRng::from_seed(seed_bytes);
+
+        // committee authorities are sorted by id so the choice is deterministic for all nodes
+        let choices = committee
+            .authorities()
+            .into_iter()
+            .map(|authority| {
+                let stake = self.stake(&authority.id());
+                (authority, stake)
+            })
+            .collect::<Vec<_>>();
+        choices
+            .choose_weighted(&mut rng, |item| item.1)
+            .ok()
+            .map(|(authority, _)| authority.clone())
+    }
+}
+
+impl From<BTreeMap<AuthorityIdentifier, u64>> for LeaderWeights {
+    fn from(stakes: BTreeMap<AuthorityIdentifier, u64>) -> Self {
+        Self::new(stakes)
+    }
+}
+
 /// The LeaderSchedule is responsible for producing the leader schedule across an epoch.
 ///
 /// It provides methods to derive the leader of a round based on the provided leader swap table.
@@ -183,11 +240,28 @@ impl LeaderSwapTable {
 pub struct LeaderSchedule {
     pub committee: Committee,
     pub leader_swap_table: Arc<RwLock<LeaderSwapTable>>,
+    /// Optional stake weights used for leader election instead of the committee's voting power.
+    pub leader_weights: Arc<RwLock<Option<LeaderWeights>>>,
 }
 
 impl LeaderSchedule {
     pub fn new(committee: Committee, table: LeaderSwapTable) -> Self {
-        Self { committee, leader_swap_table: Arc::new(RwLock::new(table)) }
+        Self {
+            committee,
+            leader_swap_table: Arc::new(RwLock::new(table)),
+            leader_weights: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Elect leaders proportionally to the configured stakes, ie - `Parameters::leader_weights`.
+    ///
+    /// Empty stakes keep electing leaders by the committee's voting power. The weights are kept
+    /// when the swap table is reloaded from the store.
+    pub fn with_leader_weights(self, stakes: &BTreeMap<AuthorityIdentifier, u64>) -> Self {
+        if !stakes.is_empty() {
+            self.update_leader_weights(Some(LeaderWeights::new(stakes.clone())));
+        }
+        self
     }
 
     /// Restores the LeaderSchedule by using the storage. It will attempt to retrieve the last
@@ -237,12 +311,31 @@ impl LeaderSchedule {
         *write = table;
     }
 
+    /// Atomically updates the stake weights used for leader election. Passing `None` reverts to
+    /// electing leaders based on the committee's voting power.
+    pub fn update_leader_weights(&self, weights: Option<LeaderWeights>) {
+        trace!("Updating leader weights {:?}", weights);
+
+        let mut write = self.leader_weights.write();
+        *write = weights;
+    }
+
     /// Returns the leader for the provided round. Keep in mind that this method will return a
     /// leader according to the provided LeaderSwapTable. Providing a different table can
     /// potentially produce a different leader for the same round.
+    ///
+    /// If [LeaderWeights] are set, the leader is elected proportionally to those stakes.
     pub fn leader(&self, round: Round) -> Authority {
         assert_eq!(round % 2, 0, "We should never attempt to do a leader election for odd rounds");
 
+        // Elect the leader in a choice weighted by the on-chain stake if available
+        if let Some(leader) =
+            self.leader_weights.read().as_ref().and_then(|w| w.elect(&self.committee, round))
+        {
+            let table = self.leader_swap_table.read();
+            return table.swap(&leader.id(), round).unwrap_or(leader);
+        }
+
         // TODO: split the leader election logic for testing from the production code.
         cfg_if::cfg_if! {
             if #[cfg(test)] {
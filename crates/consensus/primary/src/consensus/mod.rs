@@ -9,7 +9,7 @@ mod state;
 mod utils;
 pub use crate::consensus::{
     bullshark::Bullshark,
-    leader_schedule::{LeaderSchedule, LeaderSwapTable},
+    leader_schedule::{LeaderSchedule, LeaderSwapTable, LeaderWeights},
     state::{Consensus, ConsensusRound, ConsensusState, Dag},
     utils::gc_round,
 };
@@ -15,7 +15,7 @@ use tn_config::ConsensusConfig;
 use tn_storage::{CertificateStore, ConsensusStore};
 use tn_types::{
     AuthorityIdentifier, Certificate, CertificateDigest, CommittedSubDag, Committee, Database,
-    Hash as _, Noticer, Round, TaskManager, Timestamp, TnReceiver, TnSender,
+    Hash as _, Noticer, Round, ShutdownStage, TaskManager, Timestamp, TnReceiver, TnSender,
 };
 use tracing::{debug, info, instrument};
 
@@ -295,7 +295,8 @@ impl<DB: Database> Consensus<DB> {
         task_manager: &TaskManager,
     ) {
         let metrics = consensus_bus.consensus_metrics();
-        let rx_shutdown = consensus_config.shutdown().subscribe();
+        // stop committing new sub dags before the network and executor stop
+        let rx_shutdown = task_manager.stage_shutdown(ShutdownStage::Proposing);
         // The consensus state (everything else is immutable).
         let recovered_last_committed = consensus_config.node_storage().read_last_committed();
         let last_committed_round = recovered_last_committed
@@ -340,7 +341,11 @@ impl<DB: Database> Consensus<DB> {
         // Only run the consensus task if we are an active CVV.
         // Active means we are participating in consensus.
         if consensus_bus.node_mode().borrow().is_active_cvv() {
-            task_manager.spawn_task("consensus", monitored_future!(s.run(), "Consensus", INFO));
+            task_manager.spawn_ordered_task(
+                ShutdownStage::Proposing,
+                "consensus",
+                monitored_future!(s.run(), "Consensus", INFO),
+            );
         }
     }
 
@@ -1,7 +1,10 @@
 //! Leader schedule tests
 
-use crate::consensus::{Dag, LeaderSchedule, LeaderSwapTable};
-use std::{collections::BTreeSet, num::NonZeroUsize};
+use crate::consensus::{Dag, LeaderSchedule, LeaderSwapTable, LeaderWeights};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    num::NonZeroUsize,
+};
 use tempfile::TempDir;
 use tn_storage::{mem_db::MemDatabase, open_db, ConsensusStore};
 use tn_test_utils::{mock_certificate, CommitteeFixture};
@@ -185,3 +188,51 @@ async fn test_leader_schedule_from_store() {
 
     assert_ne!(leader_2.id(), new_leader_2.id());
 }
+
+#[tokio::test]
+async fn test_leader_schedule_with_uneven_weights() {
+    // GIVEN
+    let fixture = CommitteeFixture::builder(MemDatabase::default).build();
+    let committee = fixture.committee();
+    let authority_ids: Vec<AuthorityIdentifier> = fixture.authorities().map(|a| a.id()).collect();
+
+    // the first authority has as much stake as all the others combined
+    let heavy = authority_ids[0].clone();
+    let others = (authority_ids.len() - 1) as u64;
+    let stakes: BTreeMap<AuthorityIdentifier, u64> = authority_ids
+        .iter()
+        .map(|id| if *id == heavy { (id.clone(), others * 10) } else { (id.clone(), 10) })
+        .collect();
+    assert_eq!(LeaderWeights::new(stakes.clone()).total_stake(), others * 20);
+
+    let schedule = LeaderSchedule::new(committee.clone(), LeaderSwapTable::default())
+        .with_leader_weights(&stakes);
+
+    // WHEN electing leaders across many rounds
+    let rounds = 2_000;
+    let mut counts: HashMap<AuthorityIdentifier, u64> = HashMap::new();
+    for round in (2..=rounds * 2).step_by(2) {
+        *counts.entry(schedule.leader(round).id()).or_default() += 1;
+    }
+
+    // THEN the heavy authority leads roughly half of the rounds
+    let heavy_count = counts.get(&heavy).copied().unwrap_or_default();
+    assert!(heavy_count > rounds as u64 * 4 / 10, "heavy leader elected {heavy_count} times");
+    assert!(heavy_count < rounds as u64 * 6 / 10, "heavy leader elected {heavy_count} times");
+
+    // AND every other authority leads less often than the heavy authority
+    for id in authority_ids.iter().filter(|id| **id != heavy) {
+        let count = counts.get(id).copied().unwrap_or_default();
+        assert!(count > 0);
+        assert!(count < heavy_count);
+    }
+
+    // AND the weights are kept when the swap table is reloaded from the store
+    let temp_dir = TempDir::new().unwrap();
+    schedule.reload_from_store(open_db(temp_dir.path()), 33);
+    assert_eq!(*schedule.leader_weights.read(), Some(LeaderWeights::new(stakes)));
+
+    // AND clearing the weights restores the default round robin schedule used in tests
+    schedule.update_leader_weights(None);
+    assert_eq!(schedule.leader(2).id(), authority_ids[0]);
+}
@@ -37,4 +37,16 @@ pub(crate) enum PrimaryNetworkError {
     /// Unknown consensus header.
     #[error("Unknown consensus header: {0}")]
     UnknowConsensusHeaderDigest(BlockHash),
+    /// The published consensus header number and digest are invalid.
+    #[error("Invalid consensus header published: {0} - {1}")]
+    InvalidConsensusHeader(u64, BlockHash),
+}
+
+impl PrimaryNetworkError {
+    /// Bool indicating if the error is caused by a malformed gossip message.
+    ///
+    /// Peers that publish malformed messages are penalized.
+    pub(crate) fn is_malformed_gossip(&self) -> bool {
+        matches!(self, Self::Decode(_) | Self::InvalidConsensusHeader(..))
+    }
 }
@@ -0,0 +1,69 @@
+//! Validate consensus headers published by peers.
+//!
+//! Primaries publish each new consensus header with its digest. Nodes that are not participating
+//! in consensus use these messages to request the latest header, so a malformed message is
+//! rejected before it is forwarded to the rest of the node.
+
+use crate::error::{PrimaryNetworkError, PrimaryNetworkResult};
+use tn_config::ConsensusConfig;
+use tn_storage::tables::{ConsensusBlockNumbersByDigest, ConsensusBlocks};
+use tn_types::{ensure, BlockHash, ConsensusHeader, Database};
+
+/// Validate consensus headers received through gossip.
+#[derive(Clone)]
+pub(crate) struct ConsensusHeaderValidator<DB> {
+    /// Consensus config with access to the committee and database.
+    consensus_config: ConsensusConfig<DB>,
+}
+
+impl<DB> ConsensusHeaderValidator<DB>
+where
+    DB: Database,
+{
+    /// Create a new instance of Self.
+    pub(crate) fn new(consensus_config: ConsensusConfig<DB>) -> Self {
+        Self { consensus_config }
+    }
+
+    /// Validate a consensus header and the digest published by a peer.
+    ///
+    /// The genesis header is never published and the header must produce the published digest.
+    /// If this node already has a consensus header with the same number or digest, the published
+    /// digest must match the local consensus chain.
+    pub(crate) fn validate_published(
+        &self,
+        header: &ConsensusHeader,
+        digest: BlockHash,
+    ) -> PrimaryNetworkResult<()> {
+        let number = header.number;
+        ensure!(number > 0, PrimaryNetworkError::InvalidConsensusHeader(number, digest));
+        self.validate_digest(header, digest)?;
+
+        let db = self.consensus_config.node_storage();
+        if let Some(local) = db.get::<ConsensusBlocks>(&number)? {
+            self.validate_digest(&local, digest)?;
+        }
+
+        if let Some(local_number) = db.get::<ConsensusBlockNumbersByDigest>(&digest)? {
+            ensure!(
+                local_number == number,
+                PrimaryNetworkError::InvalidConsensusHeader(number, digest)
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Ensure the header produces the expected digest.
+    fn validate_digest(
+        &self,
+        header: &ConsensusHeader,
+        digest: BlockHash,
+    ) -> PrimaryNetworkResult<()> {
+        ensure!(
+            header.digest() == digest,
+            PrimaryNetworkError::InvalidConsensusHeader(header.number, digest)
+        );
+        Ok(())
+    }
+}
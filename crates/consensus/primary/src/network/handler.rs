@@ -1,6 +1,9 @@
 //! Handle specific request types received from the network.
 
-use super::{message::MissingCertificatesRequest, PrimaryResponse};
+use super::{
+    consensus_header::ConsensusHeaderValidator, message::MissingCertificatesRequest,
+    PrimaryResponse,
+};
 use crate::{
     error::{CertManagerError, PrimaryNetworkError, PrimaryNetworkResult},
     network::message::PrimaryGossip,
@@ -36,6 +39,8 @@ pub(crate) struct RequestHandler<DB> {
     consensus_bus: ConsensusBus,
     /// Synchronize state between peers.
     state_sync: StateSynchronizer<DB>,
+    /// Validate consensus headers published by peers.
+    consensus_header_validator: ConsensusHeaderValidator<DB>,
     /// The digests of parents that are currently being requested from peers.
     ///
     /// Missing parents are requested from peers. This is a local map to track in-flight requests
@@ -55,7 +60,14 @@ where
         consensus_bus: ConsensusBus,
         state_sync: StateSynchronizer<DB>,
     ) -> Self {
-        Self { consensus_config, consensus_bus, state_sync, requested_parents: Default::default() }
+        let consensus_header_validator = ConsensusHeaderValidator::new(consensus_config.clone());
+        Self {
+            consensus_config,
+            consensus_bus,
+            state_sync,
+            consensus_header_validator,
+            requested_parents: Default::default(),
+        }
     }
 
     /// Process gossip from the committee.
@@ -76,9 +88,13 @@ where
                 let unverified_cert = cert.validate_received().map_err(CertManagerError::from)?;
                 self.state_sync.process_peer_certificate(unverified_cert).await?;
             }
-            PrimaryGossip::Consenus(number, hash) => {
-                // Other side of this needs to verify.
-                let _ = self.consensus_bus.last_published_consensus_num_hash().send((number, hash));
+            PrimaryGossip::Consenus(hash, header) => {
+                // reject malformed headers before forwarding
+                self.consensus_header_validator.validate_published(&header, hash)?;
+                let _ = self
+                    .consensus_bus
+                    .last_published_consensus_num_hash()
+                    .send((header.number, hash));
             }
         }
 
@@ -24,12 +24,18 @@ pub enum PrimaryGossip {
     ///
     /// NOTE: `snappy` is slightly larger than uncompressed.
     Certificate(Box<Certificate>),
-    /// Consensus output reached- publish the consensus chain height and new block hash.
-    Consenus(u64, BlockHash),
+    /// Consensus output reached- publish the new consensus header and its hash.
+    ///
+    /// Peers recompute the header's digest and reject the message if it doesn't match.
+    Consenus(BlockHash, Box<ConsensusHeader>),
 }
 
 // impl TNMessage trait for types
-impl TNMessage for PrimaryRequest {}
+impl TNMessage for PrimaryRequest {
+    fn carries_certificates(&self) -> bool {
+        matches!(self, Self::Vote { parents, .. } if !parents.is_empty())
+    }
+}
 impl TNMessage for PrimaryResponse {}
 
 /// Requests from Primary.
@@ -23,11 +23,12 @@ use tn_network_types::{
 };
 use tn_storage::PayloadStore;
 use tn_types::{
-    encode, BlockHash, Certificate, CertificateDigest, ConsensusHeader, Database, Header, Noticer,
-    TaskManager, TnSender, Vote,
+    encode, BlockHash, Certificate, CertificateDigest, ConsensusHeader, Database, Header,
+    ShutdownStage, TaskManager, TnSender, Vote,
 };
 use tokio::sync::{mpsc, oneshot};
-use tracing::warn;
+use tracing::{debug, warn};
+mod consensus_header;
 pub mod handler;
 mod message;
 
@@ -40,6 +41,11 @@ pub(crate) type Req = PrimaryRequest;
 /// Convenience type for Primary network.
 pub(crate) type Res = PrimaryResponse;
 
+/// The application score penalty for peers that return an unexpected response.
+const BAD_RESPONSE_PENALTY: f64 = 10.0;
+/// The application score penalty for peers that publish malformed gossip.
+const MALFORMED_GOSSIP_PENALTY: f64 = 10.0;
+
 /// Primary network specific handle.
 #[derive(Clone)]
 pub struct PrimaryNetworkHandle {
@@ -77,13 +83,14 @@ impl PrimaryNetworkHandle {
         Ok(())
     }
 
-    /// Publish a onsensus block number and hash of the header.
+    /// Publish a consensus header and its hash.
     pub async fn publish_consensus(
         &self,
-        consensus_block_num: u64,
         consensus_header_hash: BlockHash,
+        consensus_header: ConsensusHeader,
     ) -> NetworkResult<()> {
-        let data = encode(&PrimaryGossip::Consenus(consensus_block_num, consensus_header_hash));
+        let data =
+            encode(&PrimaryGossip::Consenus(consensus_header_hash, Box::new(consensus_header)));
         self.handle.publish(IdentTopic::new("tn-primary"), data).await?;
         Ok(())
     }
@@ -159,10 +166,25 @@ impl PrimaryNetworkHandle {
         // Try up to three times (from three peers) to get consensus.
         // This could be a lot more complicated but this KISS method should work fine.
         for _ in 0..3 {
-            let res = self.handle.send_request_any(request.clone()).await?;
-            let res = res.await?;
-            if let Ok(PrimaryResponse::ConsensusHeader(header)) = res {
-                return Ok(Arc::unwrap_or_clone(header));
+            let (peer, res) = match self.handle.send_request_any(request.clone()).await {
+                Ok(sent) => sent,
+                Err(NetworkError::NoPeers) => continue,
+                Err(e) => return Err(e),
+            };
+            match res.await? {
+                Ok(PrimaryResponse::ConsensusHeader(header)) => {
+                    return Ok(Arc::unwrap_or_clone(header))
+                }
+                // peers are allowed to return errors
+                Ok(PrimaryResponse::Error(_)) => {}
+                // wrong response
+                //
+                // transient I/O failures and timeouts are not penalized
+                Ok(_) => {
+                    warn!(target: "primary::network", ?peer, "bad consensus header response");
+                    self.handle.report_peer(peer, BAD_RESPONSE_PENALTY).await?;
+                }
+                Err(_) => {}
             }
         }
         Err(NetworkError::RPCError("Could not get the consensus header!".to_string()))
@@ -177,8 +199,6 @@ pub struct PrimaryNetwork<DB> {
     network_handle: PrimaryNetworkHandle,
     /// Request handler to process requests and return responses.
     request_handler: RequestHandler<DB>,
-    /// Shutdown notification.
-    shutdown_rx: Noticer,
 }
 
 impl<DB> PrimaryNetwork<DB>
@@ -193,10 +213,9 @@ where
         consensus_bus: ConsensusBus,
         state_sync: StateSynchronizer<DB>,
     ) -> Self {
-        let shutdown_rx = consensus_config.shutdown().subscribe();
         let request_handler =
             RequestHandler::new(consensus_config, consensus_bus, state_sync.clone());
-        Self { network_events, network_handle, request_handler, shutdown_rx }
+        Self { network_events, network_handle, request_handler }
     }
 
     pub fn handle(&self) -> &PrimaryNetworkHandle {
@@ -204,20 +223,27 @@ where
     }
 
     /// Run the network.
+    ///
+    /// Events are processed until the [ShutdownStage::Network] tasks are stopped.
     pub fn spawn(mut self, task_manager: &TaskManager) {
-        task_manager.spawn_task("primary network events", async move {
-            loop {
-                tokio::select!(
-                    _ = &self.shutdown_rx => break,
-                    event = self.network_events.recv() => {
-                        match event {
-                            Some(e) => self.process_network_event(e),
-                            None => break,
+        let shutdown_rx = task_manager.stage_shutdown(ShutdownStage::Network);
+        task_manager.spawn_ordered_task(
+            ShutdownStage::Network,
+            "primary network events",
+            async move {
+                loop {
+                    tokio::select!(
+                        _ = &shutdown_rx => break,
+                        event = self.network_events.recv() => {
+                            match event {
+                                Some(e) => self.process_network_event(e),
+                                None => break,
+                            }
                         }
-                    }
-                )
-            }
-        });
+                    )
+                }
+            },
+        );
     }
 
     /// Handle events concurrently.
@@ -244,6 +270,12 @@ where
             NetworkEvent::Gossip(msg) => {
                 self.process_gossip(msg);
             }
+            NetworkEvent::PeerConnected(peer) => {
+                debug!(target: "primary::network", ?peer, "peer connected");
+            }
+            NetworkEvent::PeerDisconnected(peer) => {
+                debug!(target: "primary::network", ?peer, "peer disconnected");
+            }
         }
     }
 
@@ -331,26 +363,23 @@ where
     fn process_gossip(&self, msg: GossipMessage) {
         // clone for spawned tasks
         let request_handler = self.request_handler.clone();
-        // let network_handle = self.network_handle.clone();
+        let network_handle = self.network_handle.clone();
 
-        // commented out to prevent CertificateError::TooNew from forcing disconnect when peers
-        // are trying to resync
         tokio::spawn(async move {
             if let Err(e) = request_handler.process_gossip(&msg).await {
                 warn!(target: "primary::network", ?e, "process_gossip");
-                // TODO: peers don't track reputation yet
+
+                // only penalize malformed messages to prevent CertificateError::TooNew from
+                // forcing disconnect when peers are trying to resync
                 //
                 // NOTE: the network ensures the peer id is present before forwarding the msg
-                // if let Some(peer_id) = msg.source {
-                //     if let Err(e) =
-                //         network_handle.handle.set_application_score(peer_id, -100.0).await
-                //     {
-                //         error!(target: "primary::network", ?e, "failed to penalize malicious
-                // peer")     }
-                // }
-
-                // match on error to lower peer score
-                //todo!();
+                if let Some(peer_id) = msg.source.filter(|_| e.is_malformed_gossip()) {
+                    if let Err(e) =
+                        network_handle.handle.report_peer(peer_id, MALFORMED_GOSSIP_PENALTY).await
+                    {
+                        warn!(target: "primary::network", ?e, "failed to penalize malicious peer");
+                    }
+                }
             }
         });
     }
@@ -11,8 +11,8 @@ use crate::{
 };
 use std::sync::Arc;
 use tn_config::ConsensusConfig;
-use tn_types::{network_public_key_to_libp2p, Database, TaskManager};
-use tracing::info;
+use tn_types::{network_public_key_to_libp2p, Database, ShutdownStage, TaskManager};
+use tracing::{error, info};
 
 #[cfg(test)]
 #[path = "tests/primary_tests.rs"]
@@ -98,11 +98,31 @@ impl<DB: Database> Primary<DB> {
         StateHandler::spawn(
             config.authority().id(),
             consensus_bus,
-            config.shutdown().subscribe(),
+            task_manager.stage_shutdown(ShutdownStage::Network),
             self.primary_network.clone(),
             task_manager,
         );
 
+        // Flush the stores on shutdown once the proposer and network have stopped so no new
+        // writes can arrive.
+        let rx_flush = task_manager.stage_shutdown(ShutdownStage::Storage);
+        let node_storage = config.node_storage().clone();
+        task_manager.spawn_ordered_task(
+            ShutdownStage::Storage,
+            "flush primary stores",
+            async move {
+                rx_flush.await;
+                // flushing waits on the database's writer thread
+                match tokio::task::spawn_blocking(move || node_storage.flush()).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => {
+                        error!(target: "primary", ?e, "failed to flush stores on shutdown")
+                    }
+                    Err(e) => error!(target: "primary", ?e, "flush stores task failed on shutdown"),
+                }
+            },
+        );
+
         // NOTE: This log entry is used to compute performance.
         info!(
             "Primary {} successfully booted on {}",
@@ -31,7 +31,8 @@ use tn_primary_metrics::PrimaryMetrics;
 use tn_storage::ProposerStore;
 use tn_types::{
     now, AuthorityIdentifier, BlockHash, Certificate, Committee, Database, Epoch, Hash as _,
-    Header, Noticer, Round, TaskManager, TimestampSec, TnReceiver, TnSender, WorkerId,
+    Header, Noticer, Round, ShutdownStage, TaskManager, TimestampGranularity, TimestampSec,
+    TnReceiver, TnSender, WorkerId,
 };
 use tokio::{
     sync::oneshot,
@@ -39,6 +40,11 @@ use tokio::{
 };
 use tracing::{debug, enabled, error, info, trace, warn};
 
+/// The encoded size (bytes) of a single block digest in the header's payload.
+///
+/// Each payload entry is the block hash (32 bytes), worker id (2 bytes), and timestamp (8 bytes).
+const HEADER_PAYLOAD_ENTRY_SIZE: usize = 42;
+
 /// Type alias for the async task that creates, stores, and sends the proposer's new header.
 type PendingHeaderTask = oneshot::Receiver<ProposerResult<Header>>;
 
@@ -50,7 +56,9 @@ pub struct OurDigestMessage {
     /// The worker that produced this block.
     pub worker_id: WorkerId,
     /// The timestamp for when the block was created.
-    pub timestamp: TimestampSec,
+    ///
+    /// The unit is the network's [TimestampGranularity].
+    pub timestamp: u64,
     /// A channel to send an () as an ack after this digest is processed by the primary.
     pub ack_channel: oneshot::Sender<()>,
 }
@@ -76,7 +84,9 @@ struct ProposerDigest {
     /// The worker that produced this block.
     pub worker_id: WorkerId,
     /// The timestamp for when the block was created.
-    pub timestamp: TimestampSec,
+    ///
+    /// The unit is the network's [TimestampGranularity].
+    pub timestamp: u64,
 }
 
 #[cfg(test)]
@@ -95,8 +105,10 @@ pub struct Proposer<DB: ProposerStore> {
     /// `header_num_of_batches_threshold` batches we are ok
     /// to try and propose a header
     header_num_of_batches_threshold: usize,
-    /// The maximum number of batches in header.
-    max_header_num_of_batches: usize,
+    /// The maximum number of batch digests in a header.
+    ///
+    /// Digests beyond this limit are deferred to the next header. See [max_header_digests].
+    max_header_digests: usize,
     /// The minimum duration between generating headers.
     min_header_delay: Duration,
     /// The maximum duration to wait for conditions like having leader in parents.
@@ -134,6 +146,8 @@ pub struct Proposer<DB: ProposerStore> {
     leader_schedule: LeaderSchedule,
     /// Flag if enough conditions are met to advance the round.
     advance_round: bool,
+    /// The unit for batch timestamps reported by workers.
+    batch_timestamp_granularity: TimestampGranularity,
 }
 
 impl<DB: Database> Proposer<DB> {
@@ -151,12 +165,19 @@ impl<DB: Database> Proposer<DB> {
         // create min/max delay intervals
         let min_delay_interval = tokio::time::interval(config.parameters().min_header_delay);
         let max_delay_interval = tokio::time::interval(config.parameters().max_header_delay);
+        let max_header_digests = max_header_digests(
+            config.parameters().max_header_num_of_batches,
+            config.network_config().libp2p_config().max_rpc_message_size,
+        );
+        if max_header_digests < config.parameters().max_header_num_of_batches {
+            warn!(target: "primary::proposer", max_header_digests, configured=config.parameters().max_header_num_of_batches, "max header batches exceeds request size limit");
+        }
 
         Self {
             authority_id: config.authority().id(),
             committee: config.committee().clone(),
             header_num_of_batches_threshold: config.parameters().header_num_of_batches_threshold,
-            max_header_num_of_batches: config.parameters().max_header_num_of_batches,
+            max_header_digests,
             min_header_delay: config.parameters().min_header_delay,
             max_header_delay: config.parameters().max_header_delay,
             min_delay_interval,
@@ -169,10 +190,11 @@ impl<DB: Database> Proposer<DB> {
             last_round_timestamp: None,
             last_parents: genesis,
             last_leader: None,
-            digests: VecDeque::with_capacity(2 * config.parameters().max_header_num_of_batches),
+            digests: VecDeque::with_capacity(2 * max_header_digests),
             proposed_headers: BTreeMap::new(),
             leader_schedule,
             advance_round: true,
+            batch_timestamp_granularity: config.parameters().batch_timestamp_granularity,
         }
     }
 
@@ -195,6 +217,7 @@ impl<DB: Database> Proposer<DB> {
         metrics: Arc<PrimaryMetrics>,
         leader_and_support: String,
         max_delay: Duration,
+        batch_timestamp_granularity: TimestampGranularity,
     ) -> ProposerResult<Header> {
         // check that the included timestamp is consistent with the parent's timestamp
         //
@@ -240,9 +263,13 @@ impl<DB: Database> Proposer<DB> {
 
         // Update metrics related to latency
         let mut total_inclusion_secs = 0.0;
+        let batch_created_at =
+            |digest: &ProposerDigest| batch_timestamp_granularity.to_secs(digest.timestamp);
         for digest in &digests {
-            let batch_inclusion_secs =
-                Duration::from_secs(*header.created_at() - digest.timestamp).as_secs_f64();
+            let batch_inclusion_secs = Duration::from_secs(
+                (*header.created_at()).saturating_sub(batch_created_at(digest)),
+            )
+            .as_secs_f64();
             total_inclusion_secs += batch_inclusion_secs;
 
             // NOTE: this log entry is used to measure performance
@@ -258,7 +285,10 @@ impl<DB: Database> Proposer<DB> {
         // NOTE: this log entry is used to measure performance
         let (header_creation_secs, avg_inclusion_secs) = if let Some(digest) = digests.front() {
             (
-                Duration::from_secs(*header.created_at() - digest.timestamp).as_secs_f64(),
+                Duration::from_secs(
+                    (*header.created_at()).saturating_sub(batch_created_at(digest)),
+                )
+                .as_secs_f64(),
                 total_inclusion_secs / digests.len() as f64,
             )
         } else {
@@ -680,8 +710,7 @@ impl<DB: Database> Proposer<DB> {
             // create new header
             None => {
                 // collect values from &mut self for this header
-                let num_of_digests = self.digests.len().min(self.max_header_num_of_batches);
-                let digests: VecDeque<_> = self.digests.drain(..num_of_digests).collect();
+                let digests = self.next_header_digests();
                 let parents = std::mem::take(&mut self.last_parents);
                 let authority_id = self.authority_id.clone();
                 let min_delay = self.min_header_delay; // copy
@@ -702,6 +731,7 @@ impl<DB: Database> Proposer<DB> {
                 };
 
                 let consensus_bus = self.consensus_bus.clone();
+                let batch_timestamp_granularity = self.batch_timestamp_granularity;
                 // spawn tokio task to create, store, and send new header to certifier
                 tokio::task::spawn(async move {
                     let proposal = Proposer::propose_header(
@@ -716,6 +746,7 @@ impl<DB: Database> Proposer<DB> {
                         metrics,
                         leader_and_support.to_string(),
                         min_delay,
+                        batch_timestamp_granularity,
                     )
                     .await;
 
@@ -753,7 +784,10 @@ impl<DB: Database> Proposer<DB> {
 
     pub fn spawn(mut self, task_manager: &TaskManager) {
         if self.consensus_bus.node_mode().borrow().is_active_cvv() {
-            task_manager.spawn_task(
+            // Stop proposing before the network and stores are shutdown.
+            self.rx_shutdown = task_manager.stage_shutdown(ShutdownStage::Proposing);
+            task_manager.spawn_ordered_task(
+                ShutdownStage::Proposing,
                 "proposer task",
                 monitored_future!(
                     async move {
@@ -780,6 +814,14 @@ impl<DB: Database> Proposer<DB> {
         }
     }
 
+    /// Take the digests for the next header in FIFO order.
+    ///
+    /// Digests beyond the max for a single header remain queued for the next header.
+    fn next_header_digests(&mut self) -> VecDeque<ProposerDigest> {
+        let num_of_digests = self.digests.len().min(self.max_header_digests);
+        self.digests.drain(..num_of_digests).collect()
+    }
+
     /// Run the proposer task.
     /// Returns Ok on shutdown or an error to indicate a fatal condition.
     async fn run(&mut self) -> ProposerResult<()> {
@@ -908,3 +950,13 @@ impl<DB: Database> Proposer<DB> {
         }
     }
 }
+
+/// Return the max number of batch digests in a header.
+///
+/// Headers are sent to peers with vote requests, so the configured max is capped to keep the
+/// header's payload within half of the max request size. The rest of the request is reserved for
+/// the header's parents and any parent certificates included with the request.
+fn max_header_digests(max_header_num_of_batches: usize, max_rpc_message_size: usize) -> usize {
+    let request_limit = (max_rpc_message_size / 2 / HEADER_PAYLOAD_ENTRY_SIZE).max(1);
+    max_header_num_of_batches.min(request_limit)
+}
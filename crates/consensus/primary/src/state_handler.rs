@@ -3,7 +3,8 @@
 use crate::{network::PrimaryNetworkHandle, ConsensusBus};
 use consensus_metrics::monitored_future;
 use tn_types::{
-    AuthorityIdentifier, Certificate, Noticer, Round, TaskManager, TnReceiver, TnSender,
+    AuthorityIdentifier, Certificate, Noticer, Round, ShutdownStage, TaskManager, TnReceiver,
+    TnSender,
 };
 use tracing::{debug, error, info};
 
@@ -29,7 +30,8 @@ impl StateHandler {
     ) {
         let state_handler =
             Self { authority_id, consensus_bus: consensus_bus.clone(), rx_shutdown, network };
-        task_manager.spawn_task(
+        task_manager.spawn_ordered_task(
+            ShutdownStage::Network,
             "state handler task",
             monitored_future!(
                 async move {
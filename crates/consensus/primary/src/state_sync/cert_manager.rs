@@ -19,7 +19,7 @@ use tn_config::ConsensusConfig;
 use tn_storage::CertificateStore;
 use tn_types::{
     error::{CertificateError, HeaderError},
-    Certificate, CertificateDigest, Database, Hash as _, TnReceiver as _, TnSender as _,
+    Certificate, CertificateDigest, Database, Hash as _, Noticer, TnReceiver as _, TnSender as _,
 };
 use tokio::sync::oneshot;
 use tracing::{debug, error};
@@ -313,8 +313,9 @@ where
     /// parents are missing, the manager tracks them as pending. As parents become available or are
     /// removed through garbage collection, the certificate manager will update pending state and
     /// try to accept all known certificates.
-    pub(crate) async fn run(mut self) -> CertManagerResult<()> {
-        let shutdown_rx = self.config.shutdown().subscribe();
+    ///
+    /// The manager exits once `shutdown_rx` resolves.
+    pub(crate) async fn run(mut self, shutdown_rx: Noticer) -> CertManagerResult<()> {
         let mut certificate_manager_rx = self.consensus_bus.certificate_manager().subscribe();
 
         // recover state
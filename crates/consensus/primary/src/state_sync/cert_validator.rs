@@ -1,6 +1,10 @@
 //! Validate certificates received from peers.
 
-use super::{cert_manager::CertificateManager, AtomicRound, HeaderValidator};
+use super::{
+    cert_manager::CertificateManager,
+    verified_certs::{VerifiedCertificates, VERIFIED_CERTIFICATES_CAPACITY},
+    AtomicRound, HeaderValidator,
+};
 use crate::{
     certificate_fetcher::CertificateFetcherCommand,
     error::{CertManagerError, CertManagerResult},
@@ -37,6 +41,10 @@ pub(super) struct CertificateValidator<DB> {
     highest_processed_round: AtomicRound,
     /// Highest round of verfied certificate that has been received.
     highest_received_round: AtomicRound,
+    /// Recently verified certificates.
+    ///
+    /// Re-delivered certificates skip signature verification.
+    verified_certificates: VerifiedCertificates,
 }
 
 impl<DB> CertificateValidator<DB>
@@ -51,7 +59,14 @@ where
         highest_processed_round: AtomicRound,
         highest_received_round: AtomicRound,
     ) -> Self {
-        Self { consensus_bus, config, gc_round, highest_processed_round, highest_received_round }
+        Self {
+            consensus_bus,
+            config,
+            gc_round,
+            highest_processed_round,
+            highest_received_round,
+            verified_certificates: VerifiedCertificates::new(VERIFIED_CERTIFICATES_CAPACITY),
+        }
     }
 
     /// Convenience method for obtaining a new [CertificateManager].
@@ -138,8 +153,9 @@ where
 
     /// Validate and verify the certificate.
     ///
-    /// This method validates the certificate and verifies signatures.
-    fn validate_and_verify(&self, certificate: Certificate) -> CertManagerResult<Certificate> {
+    /// This method validates the certificate and verifies signatures. Signatures that were
+    /// already verified for this certificate are not verified again.
+    fn validate_and_verify(&self, mut certificate: Certificate) -> CertManagerResult<Certificate> {
         // certificates outside gc can never be included in the DAG
         let gc_round = self.gc_round.load();
 
@@ -152,10 +168,32 @@ where
             .into());
         }
 
+        // skip verification if the same signature was already verified for this certificate by
+        // the current committee
+        let metrics = &self.consensus_bus.primary_metrics().node_metrics;
+        let epoch = self.config.committee().epoch();
+        if let Some(signature) = self.verified_certificates.verified_signature(&certificate, epoch)
+        {
+            trace!(target: "primary::cert_validator", digest=?certificate.digest(), "certificate signature already verified");
+            metrics.certificate_verification_cache_hits.inc();
+            certificate.set_signature_verification_state(
+                SignatureVerificationState::VerifiedDirectly(signature),
+            );
+            return Ok(certificate);
+        }
+
         // validate certificate and verify signatures
         // TODO: rename this method too
         let verified_cert =
             certificate.verify(self.config.committee(), self.config.worker_cache())?;
+        if matches!(
+            verified_cert.signature_verification_state(),
+            SignatureVerificationState::VerifiedDirectly(_)
+        ) {
+            metrics.certificate_signatures_verified.inc();
+            self.verified_certificates.insert(&verified_cert, epoch);
+        }
+
         Ok(verified_cert)
     }
 
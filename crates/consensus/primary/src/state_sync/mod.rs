@@ -7,7 +7,8 @@ use header_validator::HeaderValidator;
 use tn_config::ConsensusConfig;
 use tn_storage::ConsensusStore;
 use tn_types::{
-    error::HeaderResult, Certificate, CertificateDigest, Database, Header, Round, TaskManager,
+    error::HeaderResult, Certificate, CertificateDigest, Database, Header, Round, ShutdownStage,
+    TaskManager,
 };
 mod cert_collector;
 mod cert_manager;
@@ -15,6 +16,7 @@ mod cert_validator;
 mod gc;
 mod header_validator;
 mod pending_cert_manager;
+mod verified_certs;
 pub(crate) use cert_collector::CertificateCollector;
 pub(crate) use cert_manager::CertificateManagerCommand;
 
@@ -61,7 +63,12 @@ where
     /// Spawn the certificate manager and synchronize state between peers.
     pub(crate) fn spawn(&self, task_manager: &TaskManager) {
         let certificate_manager = self.certificate_validator.new_cert_manager();
-        task_manager.spawn_task("certificate-manager", certificate_manager.run());
+        let rx_shutdown = task_manager.stage_shutdown(ShutdownStage::Network);
+        task_manager.spawn_ordered_task(
+            ShutdownStage::Network,
+            "certificate-manager",
+            certificate_manager.run(rx_shutdown),
+        );
     }
 
     //
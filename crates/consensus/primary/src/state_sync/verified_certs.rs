@@ -0,0 +1,93 @@
+//! Cache of certificates with verified signatures.
+//!
+//! Certificates are often re-delivered by peers. Verifying the aggregate signature is expensive,
+//! so the digests of recently verified certificates are cached to skip re-verification.
+//!
+//! The certificate digest is the header digest, so the cache also stores the verified signature
+//! and signers. A certificate with the same digest but different signatures is verified again.
+//!
+//! Certificates are cached by the epoch of the committee that verified them. Lookups use the
+//! current committee's epoch, so certificates verified by a previous committee are verified again.
+
+use parking_lot::Mutex;
+use roaring::RoaringBitmap;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
+use tn_types::{
+    BlsSignature, Certificate, CertificateDigest, Epoch, Hash as _, SignatureVerificationState,
+};
+
+/// The max number of verified certificates held in memory.
+pub(super) const VERIFIED_CERTIFICATES_CAPACITY: usize = 1_000;
+
+/// Bounded cache of certificates whose signatures were verified directly.
+#[derive(Debug, Clone)]
+pub(super) struct VerifiedCertificates {
+    /// The in-memory ring of verified certificates.
+    inner: Arc<Mutex<VerifiedCertificatesInner>>,
+}
+
+/// The in-memory ring of verified certificates.
+#[derive(Debug)]
+struct VerifiedCertificatesInner {
+    /// The max number of certificates held in memory.
+    capacity: usize,
+    /// The verified signature and signers by committee epoch and certificate digest.
+    verified: HashMap<(Epoch, CertificateDigest), (BlsSignature, RoaringBitmap)>,
+    /// The keys in the order they were added.
+    ///
+    /// Used to evict the oldest certificate once the capacity is reached.
+    order: VecDeque<(Epoch, CertificateDigest)>,
+}
+
+impl VerifiedCertificates {
+    /// Create a new instance of Self that holds `capacity` verified certificates.
+    pub(super) fn new(capacity: usize) -> Self {
+        let inner = VerifiedCertificatesInner {
+            capacity,
+            verified: HashMap::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+        };
+        Self { inner: Arc::new(Mutex::new(inner)) }
+    }
+
+    /// Return the verified signature if this certificate's signature was already verified by the
+    /// committee for `epoch`.
+    pub(super) fn verified_signature(
+        &self,
+        certificate: &Certificate,
+        epoch: Epoch,
+    ) -> Option<BlsSignature> {
+        let signature = certificate.aggregated_signature()?;
+        let inner = self.inner.lock();
+        let (verified, signers) = inner.verified.get(&(epoch, certificate.digest()))?;
+        (*verified == signature && signers == certificate.signed_authorities()).then_some(signature)
+    }
+
+    /// Track a certificate with a signature directly verified by the committee for `epoch`.
+    ///
+    /// Certificates that were not verified directly are ignored.
+    pub(super) fn insert(&self, certificate: &Certificate, epoch: Epoch) {
+        let SignatureVerificationState::VerifiedDirectly(signature) =
+            certificate.signature_verification_state()
+        else {
+            return;
+        };
+
+        let key = (epoch, certificate.digest());
+        let mut inner = self.inner.lock();
+        if inner.capacity == 0 || inner.verified.contains_key(&key) {
+            return;
+        }
+
+        if inner.order.len() >= inner.capacity {
+            if let Some(oldest) = inner.order.pop_front() {
+                inner.verified.remove(&oldest);
+            }
+        }
+        inner.order.push_back(key);
+        inner.verified.insert(key, (*signature, certificate.signed_authorities().clone()));
+    }
+}
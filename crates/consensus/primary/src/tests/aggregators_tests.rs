@@ -0,0 +1,62 @@
+//! Tests for the vote and certificate aggregators.
+
+use super::{certificates::CertificatesAggregatorManager, VotesAggregator};
+use crate::ConsensusBus;
+use std::sync::Arc;
+use tn_primary_metrics::PrimaryMetrics;
+use tn_storage::mem_db::MemDatabase;
+use tn_test_utils::CommitteeFixture;
+use tn_types::AuthorityIdentifier;
+
+#[tokio::test]
+async fn test_participation_metrics_ignore_silent_authority() {
+    let fixture = CommitteeFixture::builder(MemDatabase::default).randomize_ports(true).build();
+    let committee = fixture.committee();
+    let primary = fixture.last_authority();
+    // the first authority never votes or issues certificates
+    let silent = fixture.first_authority().id();
+    let header = primary.header(&committee);
+    let epoch = header.epoch().to_string();
+
+    // aggregate our own vote and every peer vote except the silent authority
+    let metrics = Arc::new(PrimaryMetrics::default());
+    let mut votes_aggregator = VotesAggregator::new(metrics.clone());
+    let votes = std::iter::once(primary.vote(&header))
+        .chain(fixture.votes(&header).into_iter().filter(|vote| vote.author() != &silent));
+    let mut certificate = None;
+    for vote in votes {
+        certificate = votes_aggregator.append(vote, &committee, &header).expect("valid vote");
+    }
+    assert!(certificate.is_some());
+
+    let votes_received = |id: &AuthorityIdentifier| {
+        metrics.authority_votes_received.with_label_values(&[&id.to_string(), &epoch]).get()
+    };
+    assert_eq!(votes_received(&silent), 0);
+    for authority in fixture.authorities().filter(|a| a.id() != silent) {
+        assert_eq!(votes_received(&authority.id()), 1);
+    }
+
+    // aggregate certificates from every authority except the silent authority
+    let cb = ConsensusBus::new();
+    let _rx_parents = cb.parents().subscribe();
+    let mut parents = CertificatesAggregatorManager::new(cb.clone());
+    for header in fixture.headers().iter().filter(|header| header.author() != &silent) {
+        parents
+            .append_certificate(fixture.certificate(header), &committee)
+            .await
+            .expect("certificate aggregated");
+    }
+
+    let node_metrics = cb.primary_metrics().node_metrics.clone();
+    let certificates_observed = |id: &AuthorityIdentifier| {
+        node_metrics
+            .authority_certificates_observed
+            .with_label_values(&[&id.to_string(), &epoch])
+            .get()
+    };
+    assert_eq!(certificates_observed(&silent), 0);
+    for authority in fixture.authorities().filter(|a| a.id() != silent) {
+        assert_eq!(certificates_observed(&authority.id()), 1);
+    }
+}
@@ -215,3 +215,38 @@ async fn test_process_fetched_certificates_in_parallel() -> eyre::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_certificate_signature_verified_once() -> eyre::Result<()> {
+    let TestTypes { validator, cb, fixture } = create_test_types();
+    let metrics = &cb.primary_metrics().node_metrics;
+    let cert = fixture.certificate(&fixture.headers()[0]);
+    assert!(!cert.is_verified());
+
+    // first delivery verifies the signature
+    let verified = validator.validate_and_verify(cert.clone())?;
+    assert!(verified.is_verified());
+    assert_eq!(metrics.certificate_signatures_verified.get(), 1);
+    assert_eq!(metrics.certificate_verification_cache_hits.get(), 0);
+
+    // re-delivery skips verification
+    let verified = validator.validate_and_verify(cert.clone())?;
+    assert!(verified.is_verified());
+    assert_eq!(metrics.certificate_signatures_verified.get(), 1);
+    assert_eq!(metrics.certificate_verification_cache_hits.get(), 1);
+
+    // the verification is only cached for the committee's epoch
+    let epoch = fixture.committee().epoch();
+    assert!(validator.verified_certificates.verified_signature(&verified, epoch).is_some());
+    assert!(validator.verified_certificates.verified_signature(&verified, epoch + 1).is_none());
+
+    // same digest with a different signature is verified again
+    let mut forged = cert;
+    forged.set_signature_verification_state(SignatureVerificationState::Unverified(
+        BlsSignature::default(),
+    ));
+    assert!(validator.validate_and_verify(forged).is_err());
+    assert_eq!(metrics.certificate_verification_cache_hits.get(), 1);
+
+    Ok(())
+}
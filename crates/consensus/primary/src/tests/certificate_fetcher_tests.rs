@@ -1,7 +1,10 @@
 //! Certificate fetcher tests
 
 use crate::{
-    certificate_fetcher::CertificateFetcher,
+    certificate_fetcher::{
+        CertificateFetcher, CertificateFetcherCommand, FetchBackoff, INITIAL_FETCH_BACKOFF,
+        PARALLEL_FETCH_REQUEST_ADDITIONAL_TIMEOUT, PARALLEL_FETCH_REQUEST_INTERVAL_SECS,
+    },
     error::CertManagerError,
     network::{PrimaryRequest, PrimaryResponse},
     state_sync::StateSynchronizer,
@@ -10,15 +13,19 @@ use crate::{
 use assert_matches::assert_matches;
 use itertools::Itertools;
 use std::{collections::BTreeSet, time::Duration};
-use tn_network_libp2p::types::{NetworkCommand, NetworkHandle};
+use tn_network_libp2p::types::{NetworkCommand, NetworkHandle, NetworkResult};
 use tn_storage::{mem_db::MemDatabase, CertificateStore, PayloadStore};
 use tn_test_utils::CommitteeFixture;
 use tn_types::{
     BlsSignature, Certificate, Hash as _, Header, SignatureVerificationState, TaskManager,
+    TnSender as _,
 };
 use tokio::{
-    sync::mpsc::{self, error::TryRecvError},
-    time::sleep,
+    sync::{
+        mpsc::{self, error::TryRecvError},
+        oneshot,
+    },
+    time::{sleep, sleep_until, Instant},
 };
 
 async fn verify_certificates_in_store<DB: CertificateStore>(
@@ -73,6 +80,30 @@ async fn verify_certificates_in_store<DB: CertificateStore>(
     );
 }
 
+/// Wait for the next fetch request from the certificate fetcher and return the time it was sent.
+///
+/// The reply is held without answering so the request never completes, like a peer that doesn't
+/// respond.
+async fn next_unanswered_fetch_request(
+    receiver: &mut mpsc::Receiver<NetworkCommand<PrimaryRequest, PrimaryResponse>>,
+    unanswered: &mut Vec<oneshot::Sender<NetworkResult<PrimaryResponse>>>,
+) -> Instant {
+    loop {
+        match receiver.recv().await {
+            Some(NetworkCommand::SendRequest {
+                request: PrimaryRequest::MissingCertificates { .. },
+                reply,
+                ..
+            }) => {
+                unanswered.push(reply);
+                return Instant::now();
+            }
+            Some(_) => {}
+            None => panic!("Unexpected channel closing!"),
+        }
+    }
+}
+
 fn verify_certificates_not_in_store<DB: CertificateStore>(
     certificate_store: &DB,
     certificates: &[Certificate],
@@ -404,3 +435,104 @@ async fn fetch_certificates_basic() {
     )
     .await;
 }
+
+#[test]
+fn fetch_backoff_grows_with_jitter_and_is_capped() {
+    let max = Duration::from_secs(60);
+    let mut backoff = FetchBackoff::new(INITIAL_FETCH_BACKOFF, max);
+
+    // 1s, 2s, 4s, 8s, 16s, 32s, then capped at 60s.
+    for failures in 0..10 {
+        let expected = INITIAL_FETCH_BACKOFF.saturating_mul(2u32.pow(failures)).min(max);
+        let delay = backoff.next_delay();
+        assert!(
+            delay >= expected / 2 && delay <= expected,
+            "delay {delay:?} after {failures} failures is outside [{:?}, {expected:?}]",
+            expected / 2
+        );
+    }
+
+    // A successful fetch starts the backoff over.
+    backoff.reset();
+    assert!(backoff.next_delay() <= INITIAL_FETCH_BACKOFF);
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn fetch_retries_back_off_from_unresponsive_peers() {
+    let fixture = CommitteeFixture::builder(MemDatabase::default).randomize_ports(true).build();
+    let primary = fixture.authorities().next().unwrap();
+    let certificate_store = primary.consensus_config().node_storage().clone();
+    let payload_store = primary.consensus_config().node_storage().clone();
+    let max_backoff = primary.consensus_config().parameters().max_certificate_fetch_backoff;
+
+    let cb = ConsensusBus::new();
+    let synchronizer = StateSynchronizer::new(primary.consensus_config(), cb.clone());
+    let task_manager = TaskManager::default();
+    synchronizer.spawn(&task_manager);
+
+    // peers never respond to fetch requests
+    let (sender, mut fake_receiver) = mpsc::channel(1000);
+    let client_network: NetworkHandle<PrimaryRequest, PrimaryResponse> = NetworkHandle::new(sender);
+    CertificateFetcher::spawn(
+        primary.consensus_config(),
+        client_network.into(),
+        cb.clone(),
+        synchronizer.clone(),
+        &task_manager,
+    );
+
+    // generate a few rounds of certificates
+    let genesis_certs: Vec<_> = Certificate::genesis(&fixture.committee());
+    for cert in genesis_certs.iter() {
+        certificate_store.write(cert.clone()).expect("Writing certificate to store failed");
+    }
+    let mut current_round: Vec<_> =
+        genesis_certs.into_iter().map(|cert| cert.header().clone()).collect();
+    let mut headers = vec![];
+    for i in 0..3 {
+        let parents: BTreeSet<_> =
+            current_round.into_iter().map(|header| fixture.certificate(&header).digest()).collect();
+        (_, current_round) = fixture.headers_round(i, &parents);
+        headers.extend(current_round.clone());
+    }
+    for (digest, (worker_id, _)) in headers.iter().flat_map(|h| h.payload().iter()) {
+        payload_store.write_payload(digest, worker_id).unwrap();
+    }
+
+    // a certificate with missing parents triggers fetching
+    let target = fixture.certificate(headers.last().expect("last header"));
+    let expected_digest = target.digest();
+    let error = synchronizer.process_peer_certificate(target).await;
+    assert_matches!(error, Err(CertManagerError::Pending(digest)) if digest == expected_digest);
+
+    // each fetch asks every peer before it times out
+    let num_peers = fixture.authorities().count() - 1;
+    let fetch_timeout = PARALLEL_FETCH_REQUEST_INTERVAL_SECS * num_peers as u32
+        + PARALLEL_FETCH_REQUEST_ADDITIONAL_TIMEOUT;
+    let tolerance = Duration::from_millis(10);
+    let mut unanswered = Vec::new();
+    let mut fetch_start = next_unanswered_fetch_request(&mut fake_receiver, &mut unanswered).await;
+
+    // 1s, 2s, 4s, 8s, 16s, 32s, then capped at the max backoff
+    for failures in 0..8 {
+        for _ in 1..num_peers {
+            next_unanswered_fetch_request(&mut fake_receiver, &mut unanswered).await;
+        }
+
+        // kicking the fetcher while it backs off doesn't start another fetch
+        sleep_until(fetch_start + fetch_timeout + Duration::from_millis(1)).await;
+        cb.certificate_fetcher().send(CertificateFetcherCommand::Kick).await.unwrap();
+
+        // the next fetch starts once the backoff expires
+        let next_fetch_start =
+            next_unanswered_fetch_request(&mut fake_receiver, &mut unanswered).await;
+        let delay = (next_fetch_start - fetch_start).saturating_sub(fetch_timeout);
+        let backoff = INITIAL_FETCH_BACKOFF.saturating_mul(2u32.pow(failures)).min(max_backoff);
+        assert!(
+            delay >= backoff / 2 && delay <= backoff + tolerance,
+            "delay {delay:?} after {failures} failures is outside [{:?}, {backoff:?}]",
+            backoff / 2
+        );
+        fetch_start = next_fetch_start;
+    }
+}
@@ -18,7 +18,7 @@ use tn_test_utils::{
 };
 use tn_types::{
     error::CertificateError, Certificate, CertificateDigest, Database, Hash as _, Round,
-    TaskManager, TnReceiver as _, TnSender,
+    ShutdownStage, TaskManager, TnReceiver as _, TnSender,
 };
 use tokio::time::timeout;
 
@@ -88,7 +88,8 @@ async fn test_accept_valid_certs() -> eyre::Result<()> {
     let certificate_store = primary.consensus_config().node_storage().clone();
 
     // spawn manager task
-    task_manager.spawn_task("manager", manager.run());
+    task_manager
+        .spawn_task("manager", manager.run(task_manager.stage_shutdown(ShutdownStage::Network)));
 
     // receive parent updates (proposer)
     let mut rx_parents = cb.parents().subscribe();
@@ -136,7 +137,8 @@ async fn test_accept_pending_certs() -> eyre::Result<()> {
     let TestTypes { validator, manager, cb, fixture, task_manager, .. } = create_all_test_types();
 
     // spawn manager task
-    task_manager.spawn_task("manager", manager.run());
+    task_manager
+        .spawn_task("manager", manager.run(task_manager.stage_shutdown(ShutdownStage::Network)));
 
     let committee = fixture.committee();
     let num_authorities = fixture.num_authorities();
@@ -213,7 +215,8 @@ async fn test_gc_pending_certs() -> eyre::Result<()> {
     let certificate_store = primary.consensus_config().node_storage().clone();
 
     // spawn manager task
-    task_manager.spawn_task("manager", manager.run());
+    task_manager
+        .spawn_task("manager", manager.run(task_manager.stage_shutdown(ShutdownStage::Network)));
 
     let committee = fixture.committee();
     let num_authorities = fixture.num_authorities();
@@ -281,7 +284,8 @@ async fn test_node_restart_syncs_state() -> eyre::Result<()> {
     let certificate_store = primary.consensus_config().node_storage().clone();
 
     // spawn manager task
-    task_manager.spawn_task("manager", manager.run());
+    task_manager
+        .spawn_task("manager", manager.run(task_manager.stage_shutdown(ShutdownStage::Network)));
 
     // create 3 certs
     // NOTE: test types uses the last authority
@@ -309,7 +313,10 @@ async fn test_node_restart_syncs_state() -> eyre::Result<()> {
     let (manager_first_recovery, validator_first_recovery, cb_first_recovery) =
         create_core_test_types(primary);
 
-    task_manager.spawn_task("recovered manager", manager_first_recovery.run());
+    task_manager.spawn_task(
+        "recovered manager",
+        manager_first_recovery.run(task_manager.stage_shutdown(ShutdownStage::Network)),
+    );
 
     // assert proposer receives parents for round after recovery
     let mut rx_parents_first_recovery = cb_first_recovery.parents().subscribe();
@@ -337,7 +344,10 @@ async fn test_node_restart_syncs_state() -> eyre::Result<()> {
 
     let (manager_second_recovery, _validator, cb_second_recovery) = create_core_test_types(primary);
 
-    task_manager.spawn_task("recovered manager", manager_second_recovery.run());
+    task_manager.spawn_task(
+        "recovered manager",
+        manager_second_recovery.run(task_manager.stage_shutdown(ShutdownStage::Network)),
+    );
 
     // assert proposer receives parents for round after recovery
     let mut rx_parents_second_recovery = cb_second_recovery.parents().subscribe();
@@ -368,7 +378,8 @@ async fn test_filter_unknown_parents() -> eyre::Result<()> {
     let primary = fixture.authorities().last().unwrap();
 
     // spawn manager task
-    task_manager.spawn_task("manager", manager.run());
+    task_manager
+        .spawn_task("manager", manager.run(task_manager.stage_shutdown(ShutdownStage::Network)));
 
     let committee = fixture.committee();
     let num_authorities = fixture.num_authorities();
@@ -138,6 +138,104 @@ async fn propose_header_failure() {
     }
 }
 
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn propose_header_vote_aggregation_timeout() {
+    let fixture = CommitteeFixture::builder(MemDatabase::default).randomize_ports(true).build();
+    let committee = fixture.committee();
+    let primary = fixture.authorities().last().unwrap();
+    let id = primary.id();
+    let timeout = primary.consensus_config().parameters().vote_aggregation_timeout;
+
+    // Create a fake header.
+    let proposed_header = primary.header(&committee);
+
+    // Set up network handle- this is all we need to simulate then network for the certifier.
+    let (sender, mut network_rx) = mpsc::channel(100);
+    let network: NetworkHandle<PrimaryRequest, PrimaryResponse> = NetworkHandle::new(sender);
+
+    // Only one peer votes: with our own vote this is one vote short of quorum.
+    let peers: Vec<_> = fixture.authorities().filter(|a| a.id() != id).collect();
+    let voter = peers[0].id();
+    let vote =
+        Vote::new(&proposed_header, voter.clone(), peers[0].consensus_config().key_config()).await;
+
+    let cb = ConsensusBus::new();
+    let mut rx_new_certificates = cb.new_certificates().subscribe();
+    // Spawn the core.
+    let synchronizer = StateSynchronizer::new(primary.consensus_config(), cb.clone());
+
+    let task_manager = TaskManager::default();
+    synchronizer.spawn(&task_manager);
+    Certifier::spawn(
+        primary.consensus_config(),
+        cb.clone(),
+        synchronizer,
+        network.clone().into(),
+        &task_manager,
+    );
+
+    // Propose header and send back a single vote.
+    cb.headers().send(proposed_header.clone()).await.unwrap();
+    let mut stragglers = Vec::new();
+    let mut vote = Some(vote);
+    while let Some(req) = network_rx.recv().await {
+        if let NetworkCommand::SendRequest {
+            peer,
+            request: PrimaryRequest::Vote { header: _, parents: _ },
+            reply,
+        } = req
+        {
+            if peer == voter.peer_id() {
+                reply.send(Ok(PrimaryResponse::Vote(vote.take().unwrap()))).unwrap();
+            } else {
+                // hold the remaining requests open to simulate slow peers
+                stragglers.push(reply);
+            }
+        }
+        if vote.is_none() && stragglers.len() == peers.len() - 1 {
+            break;
+        }
+    }
+
+    // The timeout fires without forming a certificate.
+    if let Ok(result) = tokio::time::timeout(timeout * 2, rx_new_certificates.recv()).await {
+        panic!("expected no certificate to form; got {result:?}");
+    }
+
+    // The abandoned aggregation dropped the outstanding vote requests.
+    assert!(stragglers.iter().all(|reply| reply.is_closed()));
+
+    // The certifier forms a certificate for the next proposal once all peers vote.
+    let mut peer_votes = HashMap::new();
+    for peer in &peers {
+        let name = peer.id();
+        let vote =
+            Vote::new(&proposed_header, name.clone(), peer.consensus_config().key_config()).await;
+        peer_votes.insert(name.peer_id(), vote);
+    }
+    cb.headers().send(proposed_header.clone()).await.unwrap();
+    while let Some(req) = network_rx.recv().await {
+        if let NetworkCommand::SendRequest {
+            peer,
+            request: PrimaryRequest::Vote { header: _, parents: _ },
+            reply,
+        } = req
+        {
+            if let Some(vote) = peer_votes.remove(&peer) {
+                reply.send(Ok(PrimaryResponse::Vote(vote))).unwrap();
+            }
+        }
+        if peer_votes.is_empty() {
+            break;
+        }
+    }
+    let certificate = tokio::time::timeout(Duration::from_secs(10), rx_new_certificates.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(certificate.header().digest(), proposed_header.digest());
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn propose_header_scenario_with_bad_sigs() {
     // expect cert if less than 2 byzantines, otherwise no cert
@@ -2,19 +2,19 @@
 
 use crate::{
     error::PrimaryNetworkError,
-    network::{MissingCertificatesRequest, RequestHandler},
+    network::{message::PrimaryGossip, MissingCertificatesRequest, RequestHandler},
     state_sync::StateSynchronizer,
     ConsensusBus, RecentBlocks,
 };
 use assert_matches::assert_matches;
 use std::collections::{BTreeMap, BTreeSet};
-use tn_network_libp2p::PeerId;
-use tn_storage::mem_db::MemDatabase;
+use tn_network_libp2p::{types::IdentTopic, GossipMessage, PeerId};
+use tn_storage::{mem_db::MemDatabase, tables::ConsensusBlocks};
 use tn_test_utils::CommitteeFixture;
 use tn_types::{
     error::HeaderError, network_public_key_to_libp2p, now, AuthorityIdentifier, BlockHash,
-    BlockHeader, BlockNumHash, Certificate, CertificateDigest, ExecHeader, Hash as _, SealedHeader,
-    TaskManager,
+    BlockHeader, BlockNumHash, Certificate, CertificateDigest, ConsensusHeader, Database as _,
+    ExecHeader, Hash as _, SealedHeader, TaskManager,
 };
 use tracing::debug;
 
@@ -295,3 +295,73 @@ async fn test_vote_fails_unknown_authority() -> eyre::Result<()> {
     assert_matches!(res, Err(PrimaryNetworkError::InvalidHeader(HeaderError::UnknownAuthority(wrong))) if wrong == wrong_authority.to_string());
     Ok(())
 }
+
+/// Create a consensus header gossip message published by a peer.
+fn consensus_gossip(hash: BlockHash, header: ConsensusHeader) -> GossipMessage {
+    GossipMessage {
+        source: Some(PeerId::random()),
+        data: tn_types::encode(&PrimaryGossip::Consenus(hash, Box::new(header))),
+        sequence_number: None,
+        topic: IdentTopic::new("tn-primary").hash(),
+    }
+}
+
+#[tokio::test]
+async fn test_consensus_gossip_rejects_mismatched_digest() -> eyre::Result<()> {
+    // common types
+    let TestTypes { committee, handler, .. } = create_test_types();
+
+    // the node already has the consensus header for number 1
+    let header = ConsensusHeader { number: 1, ..Default::default() };
+    let digest = header.digest();
+    committee
+        .first_authority()
+        .consensus_config()
+        .node_storage()
+        .insert::<ConsensusBlocks>(&header.number, &header)?;
+
+    // peer publishes a different header for the same number
+    let other =
+        ConsensusHeader { number: 1, parent_hash: BlockHash::random(), ..Default::default() };
+    let wrong_digest = other.digest();
+    let res = handler.process_gossip(&consensus_gossip(wrong_digest, other)).await;
+    assert_matches!(res, Err(PrimaryNetworkError::InvalidConsensusHeader(1, hash)) if hash == wrong_digest);
+    assert!(res.expect_err("digest mismatch").is_malformed_gossip());
+
+    // the genesis header is never published
+    let genesis = ConsensusHeader::default();
+    let res = handler.process_gossip(&consensus_gossip(genesis.digest(), genesis)).await;
+    assert_matches!(res, Err(PrimaryNetworkError::InvalidConsensusHeader(0, _)));
+
+    // the matching digest and unknown headers are accepted
+    handler.process_gossip(&consensus_gossip(digest, header)).await?;
+    let unknown = ConsensusHeader { number: 2, parent_hash: digest, ..Default::default() };
+    handler.process_gossip(&consensus_gossip(unknown.digest(), unknown)).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_consensus_gossip_rejects_tampered_header() -> eyre::Result<()> {
+    // common types
+    let TestTypes { handler, .. } = create_test_types();
+
+    // the header is unknown to this node
+    let header =
+        ConsensusHeader { number: 5, parent_hash: BlockHash::random(), ..Default::default() };
+    let digest = header.digest();
+
+    // peer tampers with the header after computing the digest
+    let tampered = ConsensusHeader { parent_hash: BlockHash::random(), ..header.clone() };
+    let res = handler.process_gossip(&consensus_gossip(digest, tampered)).await;
+    assert_matches!(res, Err(PrimaryNetworkError::InvalidConsensusHeader(5, hash)) if hash == digest);
+    assert!(res.expect_err("tampered header").is_malformed_gossip());
+
+    // peer publishes a digest that was never produced by the header
+    let random_digest = BlockHash::random();
+    let res = handler.process_gossip(&consensus_gossip(random_digest, header.clone())).await;
+    assert_matches!(res, Err(PrimaryNetworkError::InvalidConsensusHeader(5, hash)) if hash == random_digest);
+
+    // the untampered header is accepted
+    handler.process_gossip(&consensus_gossip(digest, header)).await?;
+    Ok(())
+}
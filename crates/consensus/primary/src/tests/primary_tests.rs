@@ -2,6 +2,7 @@
 
 use super::Primary;
 use crate::{
+    consensus::{LeaderSchedule, LeaderSwapTable},
     network::{
         handler::RequestHandler, MissingCertificatesRequest, PrimaryNetwork, PrimaryResponse,
     },
@@ -713,3 +714,44 @@ async fn test_request_vote_created_at_in_future() {
     };
     assert!(created_at <= now());
 }
+
+#[tokio::test]
+async fn test_primary_shutdown_stops_tasks_in_stages() {
+    let fixture = CommitteeFixture::builder(MemDatabase::default).randomize_ports(true).build();
+    let config = fixture.authorities().next().unwrap().consensus_config();
+    let mut task_manager = TaskManager::new("primary shutdown test");
+    let (consensus_bus, mut primary) = get_bus_and_primary(config.clone(), &task_manager);
+    let leader_schedule = LeaderSchedule::new(fixture.committee(), LeaderSwapTable::default());
+    primary.spawn(config.clone(), &consensus_bus, leader_schedule, &task_manager);
+
+    // the primary's tasks are stopped in order instead of on the node shutdown
+    let tasks = task_manager.to_string();
+    for task in [
+        "proposer task (Proposing)",
+        "certifier task (Proposing)",
+        "primary network events (Network)",
+        "certificate fetcher task (Network)",
+        "certificate-manager (Network)",
+        "state handler task (Network)",
+        "flush primary stores (Storage)",
+    ] {
+        assert!(tasks.contains(task), "{task} missing from:\n{tasks}");
+    }
+
+    // shutdown the node
+    let shutdown = config.shutdown().clone();
+    let notifier = shutdown.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        notifier.notify();
+    });
+    timeout(Duration::from_secs(10), task_manager.join(shutdown))
+        .await
+        .expect("primary shutdown in time");
+
+    // every ordered task exited within its stage
+    let tasks = task_manager.to_string();
+    for stage in ["(Proposing)", "(Network)", "(Storage)"] {
+        assert!(!tasks.contains(stage), "{stage} tasks still running:\n{tasks}");
+    }
+}
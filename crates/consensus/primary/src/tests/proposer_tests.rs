@@ -126,3 +126,59 @@ async fn test_equivocation_protection_after_restart() {
         assert_eq!(header, new_header);
     }
 }
+
+#[tokio::test]
+async fn test_digests_beyond_max_deferred_to_next_header() {
+    let fixture = CommitteeFixture::builder(MemDatabase::default).build();
+    let committee = fixture.committee();
+    let primary = fixture.authorities().next().unwrap();
+    let config = primary.consensus_config();
+    let max_digests = config.parameters().max_header_num_of_batches;
+
+    let cb = ConsensusBus::new();
+    let mut proposer = Proposer::new(
+        config,
+        cb.clone(),
+        LeaderSchedule::new(committee.clone(), LeaderSwapTable::default()),
+    );
+    assert_eq!(proposer.max_header_digests, max_digests);
+
+    // queue more digests than fit in two headers
+    let num_digests = max_digests * 2 + max_digests / 2;
+    let expected: Vec<_> = (0..num_digests).map(|_| B256::random()).collect();
+    for (timestamp, digest) in expected.iter().enumerate() {
+        proposer.digests.push_back(ProposerDigest {
+            digest: *digest,
+            worker_id: 0,
+            timestamp: timestamp as TimestampSec,
+        });
+    }
+
+    // digests are split across consecutive headers in FIFO order
+    let first = proposer.next_header_digests();
+    let second = proposer.next_header_digests();
+    let third = proposer.next_header_digests();
+    assert_eq!(first.len(), max_digests);
+    assert_eq!(second.len(), max_digests);
+    assert_eq!(third.len(), max_digests / 2);
+    assert!(proposer.next_header_digests().is_empty());
+
+    // no digests are lost
+    let proposed: Vec<_> = first.into_iter().chain(second).chain(third).map(|d| d.digest).collect();
+    assert_eq!(proposed, expected);
+}
+
+#[test]
+fn test_max_header_digests_within_request_size() {
+    // the configured max is used when headers fit within the request size
+    assert_eq!(max_header_digests(10, 1024 * 1024), 10);
+
+    // the configured max is capped to keep the payload within half of the request size
+    let max_rpc_message_size = 1024;
+    let capped = max_header_digests(usize::MAX, max_rpc_message_size);
+    assert_eq!(capped, max_rpc_message_size / 2 / HEADER_PAYLOAD_ENTRY_SIZE);
+
+    // the payload entry size matches the encoded digest
+    let entry = (B256::random(), (0 as WorkerId, 0 as TimestampSec));
+    assert_eq!(tn_types::encode(&entry).len(), HEADER_PAYLOAD_ENTRY_SIZE);
+}
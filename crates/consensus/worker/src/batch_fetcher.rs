@@ -9,7 +9,7 @@ use std::{
 };
 use thiserror::Error;
 use tn_network_libp2p::error::NetworkError;
-use tn_storage::tables::Batches;
+use tn_storage::tables::{BatchReceivedAt, Batches};
 use tn_types::{now, Batch, BlockHash, Database, DbTxMut};
 use tokio::time::error::Elapsed;
 use tracing::debug;
@@ -60,13 +60,18 @@ impl<DB: Database> BatchFetcher<DB> {
                     new_batches.iter().filter(|(d, _)| remaining_digests.remove(*d))
                 {
                     let mut batch = (*batch).clone();
-                    batch.set_received_at(now());
+                    let received_at = now();
+                    batch.set_received_at(received_at);
                     updated_new_batches.insert(*digest, batch.clone());
                     // Also persist the batches, so they are available after restarts.
                     if let Err(e) = txn.insert::<Batches>(digest, &batch) {
                         tracing::error!(target: "batch_fetcher", "failed to insert batch! We can not continue.. {e}");
                         panic!("failed to insert batch! We can not continue.. {e}");
                     }
+                    if let Err(e) = txn.insert::<BatchReceivedAt>(digest, &received_at) {
+                        tracing::error!(target: "batch_fetcher", "failed to insert batch received at! We can not continue.. {e}");
+                        panic!("failed to insert batch received at! We can not continue.. {e}");
+                    }
                 }
                 if let Err(e) = txn.commit() {
                     tracing::error!(target: "batch_fetcher", "failed to commit batch! We can not continue.. {e}");
@@ -92,8 +97,13 @@ impl<DB: Database> BatchFetcher<DB> {
         debug!(target: "batch_fetcher", "Local attempt to fetch {} digests", digests.len());
         if let Ok(local_batches) = self.batch_store.multi_get::<Batches>(digests.iter()) {
             for (digest, batch) in digests.into_iter().zip(local_batches.into_iter()) {
-                if let Some(batch) = batch {
+                if let Some(mut batch) = batch {
                     self.metrics.batch_fetch.with_label_values(&["local", "success"]).inc();
+                    // received_at is not serialized with the batch, restore it if we have it.
+                    if let Ok(Some(received_at)) = self.batch_store.get::<BatchReceivedAt>(&digest)
+                    {
+                        batch.set_received_at(received_at);
+                    }
                     fetched_batches.insert(digest, batch);
                 } else {
                     self.metrics.batch_fetch.with_label_values(&["local", "missing"]).inc();
@@ -329,6 +339,35 @@ mod tests {
         assert_eq!(fetched_batches, expected_batches);
     }
 
+    #[tokio::test]
+    pub async fn test_fetcher_restores_received_at() {
+        let mut network = TestRequestBatchesNetwork::new();
+        let temp_dir = TempDir::new().unwrap();
+        let batch_store = open_db(temp_dir.path());
+        let local_batch = Batch { transactions: vec![transaction()], ..Default::default() };
+        let remote_batch = Batch { transactions: vec![transaction()], ..Default::default() };
+        batch_store.insert::<Batches>(&local_batch.digest(), &local_batch).unwrap();
+        network.put(&[1, 2], remote_batch.clone()).await;
+        let fetcher = BatchFetcher {
+            network: Arc::new(network.handle()),
+            batch_store: batch_store.clone(),
+            metrics: Arc::new(WorkerMetrics::default()),
+        };
+        let digests = HashSet::from_iter(vec![local_batch.digest(), remote_batch.digest()]);
+        let fetched_batches = fetcher.fetch(digests.clone()).await;
+        let received_at = fetched_batches[&remote_batch.digest()].received_at();
+        assert!(received_at.is_some());
+        assert_eq!(
+            batch_store.get::<BatchReceivedAt>(&remote_batch.digest()).unwrap(),
+            received_at
+        );
+
+        // Both batches are now local, the remote one keeps the time it was received.
+        let fetched_batches = fetcher.fetch(digests).await;
+        assert_eq!(fetched_batches[&remote_batch.digest()].received_at(), received_at);
+        assert!(fetched_batches[&local_batch.digest()].received_at().is_none());
+    }
+
     #[tokio::test]
     pub async fn test_fetcher_response_size_limit() {
         let mut network = TestRequestBatchesNetwork::new();
@@ -5,8 +5,10 @@
 
 mod batch_fetcher;
 mod network;
+mod recent_batches;
 mod worker;
 pub use network::{WorkerNetwork, WorkerNetworkHandle, WorkerRequest, WorkerResponse};
+pub use recent_batches::RecentBatches;
 pub mod quorum_waiter;
 
 pub mod metrics;
@@ -1,11 +1,12 @@
+use crate::RecentBatches;
 use itertools::Itertools;
 use std::sync::Arc;
 use tn_config::ConsensusConfig;
 use tn_network_libp2p::GossipMessage;
 use tn_network_types::{WorkerOthersBatchMessage, WorkerToPrimaryClient};
-use tn_storage::tables::Batches;
+use tn_storage::tables::{BatchReceivedAt, Batches};
 use tn_types::{
-    now, try_decode, Batch, BatchValidation, BlockHash, Database, SealedBatch, WorkerId,
+    try_decode, Batch, BatchValidation, BlockHash, Database, DbTxMut, SealedBatch, WorkerId,
 };
 
 use super::{
@@ -25,6 +26,8 @@ pub struct RequestHandler<DB> {
     consensus_config: ConsensusConfig<DB>,
     /// Network handle- so we can respond to gossip.
     network_handle: WorkerNetworkHandle,
+    /// The most recent batches, backed by the batch store.
+    recent_batches: RecentBatches<DB>,
 }
 
 impl<DB> RequestHandler<DB>
@@ -38,7 +41,11 @@ where
         consensus_config: ConsensusConfig<DB>,
         network_handle: WorkerNetworkHandle,
     ) -> Self {
-        Self { id, validator, consensus_config, network_handle }
+        let recent_batches = RecentBatches::new(
+            consensus_config.node_storage().clone(),
+            consensus_config.parameters().recent_batches_depth,
+        );
+        Self { id, validator, consensus_config, network_handle, recent_batches }
     }
 
     /// Process gossip from the committee.
@@ -56,18 +63,20 @@ where
             WorkerGossip::Batch(batch_hash) => {
                 // Retrieve the block...
                 let store = self.consensus_config.node_storage();
-                if !matches!(store.get::<Batches>(&batch_hash), Ok(Some(_))) {
+                if !self.recent_batches.contains(&batch_hash) {
                     // If we don't have this batch already then try to get it.
                     // If we are CVV then we should already have it.
                     // This allows non-CVVs to pre fetch batches they will soon need.
                     match self.network_handle.request_batches(vec![batch_hash]).await {
                         Ok(batches) => {
                             if let Some(batch) = batches.first() {
-                                store.insert::<Batches>(&batch.digest(), batch).map_err(|e| {
+                                let digest = batch.digest();
+                                store.insert::<Batches>(&digest, batch).map_err(|e| {
                                     WorkerNetworkError::Internal(format!(
                                         "failed to write to batch store: {e}"
                                     ))
                                 })?;
+                                self.recent_batches.push_latest(digest);
                             }
                         }
                         Err(e) => {
@@ -88,16 +97,25 @@ where
     ) -> WorkerNetworkResult<()> {
         let client = self.consensus_config.local_network().clone();
         let store = self.consensus_config.node_storage().clone();
-        // validate batch - log error if invalid
-        self.validator.validate_batch(sealed_batch.clone())?;
+        // validate batch and set received_at timestamp for remote batch - log error if invalid
+        let sealed_batch = self.validator.validate_received_batch(sealed_batch)?;
 
-        let (mut batch, digest) = sealed_batch.split();
-
-        // Set received_at timestamp for remote batch.
-        batch.set_received_at(now());
-        store.insert::<Batches>(&digest, &batch).map_err(|e| {
+        let (batch, digest) = sealed_batch.split();
+        let mut txn = store.write_txn().map_err(|e| {
+            WorkerNetworkError::Internal(format!("failed to create batch transaction: {e}"))
+        })?;
+        txn.insert::<Batches>(&digest, &batch).map_err(|e| {
             WorkerNetworkError::Internal(format!("failed to write to batch store: {e}"))
         })?;
+        if let Some(received_at) = batch.received_at() {
+            txn.insert::<BatchReceivedAt>(&digest, &received_at).map_err(|e| {
+                WorkerNetworkError::Internal(format!("failed to write to batch store: {e}"))
+            })?;
+        }
+        txn.commit().map_err(|e| {
+            WorkerNetworkError::Internal(format!("failed to commit to batch store: {e}"))
+        })?;
+        self.recent_batches.push_latest(digest);
 
         // notify primary for payload store
         client
@@ -12,13 +12,13 @@ use tn_network_libp2p::{
     GossipMessage, Multiaddr, PeerId, ResponseChannel,
 };
 use tn_network_types::{FetchBatchResponse, PrimaryToWorkerClient, WorkerSynchronizeMessage};
-use tn_storage::tables::Batches;
+use tn_storage::tables::{BatchReceivedAt, Batches};
 use tn_types::{
-    encode, now, Batch, BatchValidation, BlockHash, Database, DbTxMut, Noticer, SealedBatch,
+    encode, now, Batch, BatchValidation, BlockHash, Database, DbTxMut, SealedBatch, ShutdownStage,
     TaskManager, WorkerId,
 };
 use tokio::{
-    sync::{mpsc, oneshot},
+    sync::{mpsc, oneshot, watch},
     task::JoinHandle,
 };
 use tracing::{debug, error, trace, warn};
@@ -212,8 +212,8 @@ pub struct WorkerNetwork<DB> {
     network_handle: WorkerNetworkHandle,
     // Request handler to process requests and return responses.
     request_handler: RequestHandler<DB>,
-    /// Shutdown notification.
-    shutdown_rx: Noticer,
+    /// The number of connected peers.
+    connected_peers: watch::Sender<usize>,
 }
 
 impl<DB> WorkerNetwork<DB>
@@ -228,27 +228,41 @@ where
         id: WorkerId,
         validator: Arc<dyn BatchValidation>,
     ) -> Self {
-        let shutdown_rx = consensus_config.shutdown().subscribe();
         let request_handler =
             RequestHandler::new(id, validator, consensus_config, network_handle.clone());
-        Self { network_events, network_handle, request_handler, shutdown_rx }
+        let (connected_peers, _) = watch::channel(0);
+        Self { network_events, network_handle, request_handler, connected_peers }
+    }
+
+    /// Subscribe to the number of peers connected to the worker's network.
+    ///
+    /// The count is updated as peer connection events are processed.
+    pub fn subscribe_connected_peers(&self) -> watch::Receiver<usize> {
+        self.connected_peers.subscribe()
     }
 
     /// Run the network.
+    ///
+    /// Events are processed until the [ShutdownStage::Network] tasks are stopped.
     pub fn spawn(mut self, task_manager: &TaskManager) {
-        task_manager.spawn_task("worker network events", async move {
-            loop {
-                tokio::select!(
-                    _ = &self.shutdown_rx => break,
-                    event = self.network_events.recv() => {
-                        match event {
-                            Some(e) => self.process_network_event(e),
-                            None => break,
+        let shutdown_rx = task_manager.stage_shutdown(ShutdownStage::Network);
+        task_manager.spawn_ordered_task(
+            ShutdownStage::Network,
+            "worker network events",
+            async move {
+                loop {
+                    tokio::select!(
+                        _ = &shutdown_rx => break,
+                        event = self.network_events.recv() => {
+                            match event {
+                                Some(e) => self.process_network_event(e),
+                                None => break,
+                            }
                         }
-                    }
-                )
-            }
-        });
+                    )
+                }
+            },
+        );
     }
 
     /// Handle events concurrently.
@@ -266,6 +280,14 @@ where
             NetworkEvent::Gossip(msg) => {
                 self.process_gossip(msg);
             }
+            NetworkEvent::PeerConnected(peer) => {
+                debug!(target: "worker::network", ?peer, "peer connected");
+                self.connected_peers.send_modify(|count| *count += 1);
+            }
+            NetworkEvent::PeerDisconnected(peer) => {
+                debug!(target: "worker::network", ?peer, "peer disconnected");
+                self.connected_peers.send_modify(|count| *count = count.saturating_sub(1));
+            }
         }
     }
 
@@ -414,7 +436,8 @@ impl<DB: Database> PrimaryToWorkerClient for PrimaryReceiverHandler<DB> {
             let (mut batch, digest) = sealed_batch.split();
             if missing.remove(&digest) {
                 // Set received_at timestamp for remote batch.
-                batch.set_received_at(now());
+                let received_at = now();
+                batch.set_received_at(received_at);
                 let mut tx = self.store.write_txn().map_err(|e| {
                     WorkerNetworkError::Internal(format!(
                         "failed to create batch transaction to commit: {e:?}"
@@ -425,6 +448,11 @@ impl<DB: Database> PrimaryToWorkerClient for PrimaryReceiverHandler<DB> {
                         "failed to batch transaction to commit: {e:?}"
                     ))
                 })?;
+                tx.insert::<BatchReceivedAt>(&digest, &received_at).map_err(|e| {
+                    WorkerNetworkError::Internal(format!(
+                        "failed to batch transaction to commit: {e:?}"
+                    ))
+                })?;
                 tx.commit().map_err(|e| {
                     WorkerNetworkError::Internal(format!("failed to commit batch: {e:?}"))
                 })?;
@@ -0,0 +1,142 @@
+//! Cache of the most recent batches for the worker.
+//!
+//! Workers check if a batch is available every time a batch digest is gossiped. The digests of the
+//! most recent batches are kept in memory so availability checks for new batches don't hit the
+//! database. Older digests and the batches themselves are read from the [Batches] table.
+//!
+//! Only digests are cached so memory stays small regardless of the batch size.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{Arc, Mutex},
+};
+use tn_storage::tables::Batches;
+use tn_types::{Batch, BlockHash, Database};
+use tracing::warn;
+
+/// Tracks the `depth` most recent batch digests in memory, backed by the batch store.
+#[derive(Clone, Debug)]
+pub struct RecentBatches<DB> {
+    /// The in-memory ring of recent batch digests.
+    inner: Arc<Mutex<RecentBatchesInner>>,
+    /// The store with all batches.
+    store: DB,
+}
+
+/// The in-memory ring of recent batch digests.
+#[derive(Debug)]
+struct RecentBatchesInner {
+    /// The max number of batch digests held in memory.
+    depth: usize,
+    /// The recent batch digests.
+    batches: HashSet<BlockHash>,
+    /// The digests in the order they were added.
+    ///
+    /// Used to evict the oldest digest once the depth is reached.
+    order: VecDeque<BlockHash>,
+}
+
+impl<DB> RecentBatches<DB>
+where
+    DB: Database,
+{
+    /// Create a RecentBatches that will hold `depth` most recent batch digests in memory.
+    pub fn new(store: DB, depth: usize) -> Self {
+        let inner = RecentBatchesInner {
+            depth,
+            batches: HashSet::with_capacity(depth),
+            order: VecDeque::with_capacity(depth),
+        };
+        Self { inner: Arc::new(Mutex::new(inner)), store }
+    }
+
+    /// Max number of batch digests that can be held in memory.
+    pub fn depth(&self) -> usize {
+        self.lock().depth
+    }
+
+    /// Push the latest batch digest, will remove the oldest from memory if needed to make room.
+    ///
+    /// The batch must already be written to the store.
+    pub fn push_latest(&self, digest: BlockHash) {
+        let mut inner = self.lock();
+        if inner.depth == 0 || inner.batches.contains(&digest) {
+            return;
+        }
+
+        if inner.order.len() >= inner.depth {
+            if let Some(oldest) = inner.order.pop_front() {
+                inner.batches.remove(&oldest);
+            }
+        }
+        inner.order.push_back(digest);
+        inner.batches.insert(digest);
+    }
+
+    /// Return the batch from the store.
+    pub fn get(&self, digest: &BlockHash) -> Option<Batch> {
+        self.store.get::<Batches>(digest).unwrap_or_else(|e| {
+            warn!(target: "worker::recent_batches", ?digest, ?e, "failed to read batch from store");
+            None
+        })
+    }
+
+    /// Is the batch available in memory or the store?
+    pub fn contains(&self, digest: &BlockHash) -> bool {
+        if self.lock().batches.contains(digest) {
+            return true;
+        }
+
+        self.store.contains_key::<Batches>(digest).unwrap_or_else(|e| {
+            warn!(target: "worker::recent_batches", ?digest, ?e, "failed to read batch from store");
+            false
+        })
+    }
+
+    /// Lock the in-memory ring.
+    ///
+    /// The lock is never held across an await or store read, so poisoning is not expected.
+    fn lock(&self) -> std::sync::MutexGuard<'_, RecentBatchesInner> {
+        self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RecentBatches;
+    use tn_storage::{mem_db::MemDatabase, tables::Batches};
+    use tn_test_utils::transaction;
+    use tn_types::{Batch, Database};
+
+    #[test]
+    fn test_evicted_batch_available_from_store() {
+        let store = MemDatabase::default();
+        let recent = RecentBatches::new(store.clone(), 2);
+        let batches: Vec<_> = (0..3)
+            .map(|_| Batch { transactions: vec![transaction()], ..Default::default() })
+            .collect();
+
+        for batch in &batches {
+            store.insert::<Batches>(&batch.digest(), batch).expect("batch stored");
+            recent.push_latest(batch.digest());
+        }
+
+        // the first digest was evicted from memory but the batch is still available from the store
+        let first = batches[0].digest();
+        assert!(!recent.lock().batches.contains(&first));
+        assert!(recent.contains(&first));
+        assert_eq!(recent.get(&first), Some(batches[0].clone()));
+
+        // recent digests are held in memory
+        for batch in &batches[1..] {
+            assert!(recent.lock().batches.contains(&batch.digest()));
+            assert!(recent.contains(&batch.digest()));
+            assert_eq!(recent.get(&batch.digest()), Some(batch.clone()));
+        }
+
+        // unknown batches are not available
+        let unknown = Batch { transactions: vec![transaction()], ..Default::default() };
+        assert!(!recent.contains(&unknown.digest()));
+        assert!(recent.get(&unknown.digest()).is_none());
+    }
+}
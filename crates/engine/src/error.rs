@@ -4,6 +4,7 @@ use reth_blockchain_tree::error::InsertBlockError;
 use reth_errors::{CanonicalError, ProviderError, RethError};
 use reth_revm::primitives::EVMError;
 use reth_rpc_eth_types::EthApiError;
+use tn_types::{BlockHash, EvmHardfork};
 use tokio::sync::oneshot;
 
 /// Result alias for [`TNEngineError`].
@@ -27,6 +28,18 @@ pub enum TnEngineError {
     /// The next block digest is missing.
     #[error("Missing next block digest for recovered sealed block with senders.")]
     NextBlockDigestMissing,
+    /// The number of batches doesn't match the number of batch digests in the output.
+    #[error("Output contains {0} batches but {1} batch digests.")]
+    BatchDigestsLength(usize, usize),
+    /// The batch at the index doesn't match the committed batch digest in canonical order.
+    #[error("Batch at index {0} does not match committed digest - expected: {1} - found: {2}")]
+    BatchDigestMismatch(usize, BlockHash, BlockHash),
+    /// The block that completes the output has a different number of ommers than batches.
+    #[error("Output contains {0} batches but the completing block has {1} ommers.")]
+    OmmersLength(usize, usize),
+    /// The ommer at the index doesn't match the output's batch in canonical order.
+    #[error("Ommer at index {0} does not match the output's batch - expected: {1} - found: {2}")]
+    OmmerMismatch(usize, BlockHash, BlockHash),
     /// The block body and senders lengths don't match.
     #[error("Failed to seal block with senders - lengths don't match")]
     SealBlockWithSenders,
@@ -48,6 +61,9 @@ pub enum TnEngineError {
     // Failed to find the block we need to finalize- forked?.
     #[error("Could not finalize execution block- forked?")]
     MissingFinalBlock,
+    /// The EVM hardfork override is not scheduled in the chain spec.
+    #[error("EVM hardfork override {0} is not scheduled in the chain spec")]
+    UnscheduledHardfork(EvmHardfork),
 }
 
 impl From<oneshot::error::RecvError> for TnEngineError {
@@ -23,7 +23,7 @@ mod payload_builder;
 use error::{EngineResult, TnEngineError};
 use futures::{Future, StreamExt};
 use futures_util::FutureExt;
-pub use payload_builder::execute_consensus_output;
+pub use payload_builder::{execute_consensus_output, replay_batch_execution};
 use reth_blockchain_tree::BlockchainTreeEngine;
 use reth_chainspec::ChainSpec;
 use reth_evm::ConfigureEvm;
@@ -36,8 +36,11 @@ use std::{
     pin::{pin, Pin},
     task::{Context, Poll},
 };
-use tn_node_traits::BuildArguments;
-use tn_types::{ConsensusOutput, ExecHeader, Noticer, SealedHeader, TransactionSigned};
+use tn_node_traits::{BuildArguments, CanonicalUpdateRetry};
+use tn_types::{
+    ConsensusOutput, EvmHardfork, ExecHeader, Noticer, SealedHeader, TimestampGranularity,
+    TransactionSigned,
+};
 use tokio::sync::oneshot;
 use tokio_stream::wrappers::BroadcastStream;
 use tracing::{error, info, trace, warn};
@@ -51,10 +54,10 @@ type PendingExecutionTask = oneshot::Receiver<EngineResult<SealedHeader>>;
 /// The engine makes no attempt to track consensus. It's only purpose is to receive output from
 /// consensus then try to execute it.
 ///
-/// The engine runs until either the maximum round of consensus is reached OR the sending broadcast
-/// channel is dropped. If the sending channel is dropped, the engine attempts to execute any
-/// remaining output that is queued up before shutting itself down gracefully. If the maximum round
-/// is reached, the engine shuts down immediately.
+/// The engine runs until either the maximum round of consensus or maximum block is reached OR the
+/// sending broadcast channel is dropped. If the sending channel is dropped, the engine attempts to
+/// execute any remaining output that is queued up before shutting itself down gracefully. If the
+/// maximum round or block is reached, the engine shuts down immediately.
 pub struct ExecutorEngine<BT, CE> {
     /// The backlog of output from consensus that's ready to be executed.
     queued: VecDeque<ConsensusOutput>,
@@ -71,6 +74,12 @@ pub struct ExecutorEngine<BT, CE> {
     ///
     /// NOTE: this is primarily useful for debugging and testing
     max_round: Option<u64>,
+    /// Optional block number that bounds execution. Once the canonical tip reaches this block, the
+    /// engine stops executing output from consensus and returns.
+    ///
+    /// Output is always executed in full, so the canonical tip is the last block of the output that
+    /// reached `max_block`. This is useful for bounded test runs and controlled syncs.
+    max_block: Option<u64>,
     /// Receiving end from CL's `Executor`. The `ConsensusOutput` is sent
     /// to the mining task here.
     consensus_output_stream: BroadcastStream<ConsensusOutput>,
@@ -78,6 +87,17 @@ pub struct ExecutorEngine<BT, CE> {
     ///
     /// This information reflects the current finalized block number and hash.
     parent_header: SealedHeader,
+    /// The retry policy for canonical and finalized updates after output is executed.
+    canonical_update_retry: CanonicalUpdateRetry,
+    /// Optional hardfork that overrides the EVM spec id used to execute output.
+    ///
+    /// NOTE: this is primarily useful for testing forward-compatibility
+    evm_hardfork: Option<EvmHardfork>,
+    /// Optional max number of blocks executed from an output before they are made canonical and
+    /// finalized.
+    max_blocks_per_finalization: Option<usize>,
+    /// The granularity of batch timestamps in output from consensus.
+    timestamp_granularity: TimestampGranularity,
     /// Used to receive shutdown notification.
     rx_shutdown: Noticer,
 }
@@ -104,6 +124,7 @@ where
         blockchain: BT,
         evm_config: CE,
         max_round: Option<u64>,
+        max_block: Option<u64>,
         consensus_output_stream: BroadcastStream<ConsensusOutput>,
         parent_header: SealedHeader,
         rx_shutdown: Noticer,
@@ -114,12 +135,48 @@ where
             blockchain,
             evm_config,
             max_round,
+            max_block,
             consensus_output_stream,
             parent_header,
+            canonical_update_retry: Default::default(),
+            evm_hardfork: None,
+            max_blocks_per_finalization: None,
+            timestamp_granularity: TimestampGranularity::default(),
             rx_shutdown,
         }
     }
 
+    /// Set the retry policy for canonical and finalized updates after output is executed.
+    pub fn with_canonical_update_retry(mut self, retry: CanonicalUpdateRetry) -> Self {
+        self.canonical_update_retry = retry;
+        self
+    }
+
+    /// Override the EVM spec id used to execute output.
+    ///
+    /// The hardfork must be scheduled in the chain spec or execution fails.
+    pub fn with_evm_hardfork(mut self, evm_hardfork: Option<EvmHardfork>) -> Self {
+        self.evm_hardfork = evm_hardfork;
+        self
+    }
+
+    /// Execute and finalize large outputs in chunks of at most `max` blocks.
+    ///
+    /// `None` finalizes each output in a single step.
+    pub fn with_max_blocks_per_finalization(mut self, max: Option<usize>) -> Self {
+        self.max_blocks_per_finalization = max;
+        self
+    }
+
+    /// Set the granularity of batch timestamps in output from consensus.
+    pub fn with_timestamp_granularity(
+        mut self,
+        timestamp_granularity: TimestampGranularity,
+    ) -> Self {
+        self.timestamp_granularity = timestamp_granularity;
+        self
+    }
+
     /// Spawns a blocking task to execute consensus output.
     ///
     /// This approach allows the engine to yield back to the runtime while executing blocks.
@@ -140,7 +197,11 @@ where
             let provider = self.blockchain.clone();
             let evm_config = self.evm_config.clone();
             let parent = self.parent_header.clone();
-            let build_args = BuildArguments::new(provider, output, parent);
+            let build_args = BuildArguments::new(provider, output, parent)
+                .with_canonical_update_retry(self.canonical_update_retry)
+                .with_evm_hardfork(self.evm_hardfork)
+                .with_max_blocks_per_finalization(self.max_blocks_per_finalization)
+                .with_timestamp_granularity(self.timestamp_granularity);
 
             // spawn blocking task and return future
             tokio::task::spawn_blocking(move || {
@@ -179,6 +240,21 @@ where
         }
         has_reached_max_round
     }
+
+    /// Check if the canonical tip has reached the block number specified by `max_block`.
+    fn has_reached_max_block(&self, progress: u64) -> bool {
+        let has_reached_max_block =
+            self.max_block.map(|target| progress >= target).unwrap_or_default();
+        if has_reached_max_block {
+            trace!(
+                target: "engine",
+                ?progress,
+                max_block = ?self.max_block,
+                "Consensus engine reached max block"
+            );
+        }
+        has_reached_max_block
+    }
 }
 
 /// The [ExecutorEngine] is a future that loops through the following:
@@ -224,6 +300,11 @@ where
             }
         }
 
+        // the canonical tip may already be at the max block when the engine starts
+        if this.pending_task.is_none() && this.has_reached_max_block(this.parent_header.number) {
+            return Poll::Ready(Ok(()));
+        }
+
         loop {
             // check if output is available from consensus to keep broadcast stream from "lagging"
             match this.consensus_output_stream.poll_next_unpin(cx) {
@@ -282,6 +363,12 @@ where
                             return Poll::Ready(Ok(()));
                         }
 
+                        // check max_block
+                        if this.has_reached_max_block(this.parent_header.number) {
+                            // immediately terminate if the canonical tip reached the max block
+                            return Poll::Ready(Ok(()));
+                        }
+
                         // allow loop to continue: poll broadcast stream for next output
                     }
                     Poll::Pending => {
@@ -305,6 +392,10 @@ impl<BT, CE> std::fmt::Debug for ExecutorEngine<BT, CE> {
             .field("queued", &self.queued.len())
             .field("pending_task", &self.pending_task.is_some())
             .field("max_round", &self.max_round)
+            .field("max_block", &self.max_block)
+            .field("canonical_update_retry", &self.canonical_update_retry)
+            .field("evm_hardfork", &self.evm_hardfork)
+            .field("max_blocks_per_finalization", &self.max_blocks_per_finalization)
             .field("parent_header", &self.parent_header)
             .finish_non_exhaustive()
     }
@@ -312,22 +403,53 @@ impl<BT, CE> std::fmt::Debug for ExecutorEngine<BT, CE> {
 
 #[cfg(test)]
 mod tests {
-    use crate::ExecutorEngine;
-    use reth_blockchain_tree::BlockchainTreeViewer;
-    use reth_chainspec::ChainSpec;
-    use reth_provider::{BlockIdReader, BlockNumReader, BlockReader, TransactionVariant};
+    use crate::{
+        error::TnEngineError, execute_consensus_output, replay_batch_execution, ExecutorEngine,
+    };
+    use alloy::{eips::BlockNumberOrTag, rpc::types::engine::ForkchoiceState};
+    use reth_blockchain_tree::{
+        error::InsertBlockError, BlockValidationKind, BlockchainTreeEngine, BlockchainTreeViewer,
+        CanonicalOutcome, InsertPayloadOk,
+    };
+    use reth_chainspec::{ChainInfo, ChainSpec};
+    use reth_errors::CanonicalError;
+    use reth_provider::{
+        BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, CanonChainTracker,
+        CanonStateNotification, ChainSpecProvider, ChainStateBlockWriter, DBProvider as _,
+        DatabaseProviderFactory as _, HeaderProvider, ProviderError, ProviderResult,
+        ReceiptProvider as _, StateProviderBox, StateProviderFactory, TransactionVariant,
+    };
     use reth_revm::primitives::FixedBytes;
-    use std::{collections::VecDeque, str::FromStr as _, sync::Arc, time::Duration};
+    use std::{
+        collections::{BTreeMap, VecDeque},
+        ops::RangeBounds,
+        str::FromStr as _,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::{Duration, Instant},
+    };
     use tn_batch_builder::test_utils::execute_test_batch;
-    use tn_test_utils::{default_test_execution_node, seeded_genesis_from_random_batches};
+    use tn_node_traits::{BuildArguments, CanonicalUpdateRetry};
+    use tn_storage::{
+        mem_db::MemDatabase,
+        tables::{ConsensusBlockNumbersByDigest, ConsensusBlocks},
+    };
+    use tn_test_utils::{
+        default_test_execution_node, seeded_genesis_from_random_batches, test_genesis,
+        TestExecutionNode, TransactionFactory,
+    };
     use tn_types::{
-        adiri_chain_spec_arc, adiri_genesis, max_batch_gas, now, Address, BlockHash,
-        BlockHashOrNumber, Bloom, Certificate, CommittedSubDag, ConsensusHeader, ConsensusOutput,
-        Hash as _, Notifier, ReputationScores, TaskManager, B256, EMPTY_OMMER_ROOT_HASH,
-        EMPTY_WITHDRAWALS, MIN_PROTOCOL_BASE_FEE, U256,
+        adiri_chain_spec_arc, adiri_genesis, calculate_ommers_root, max_batch_gas, now, Address,
+        Batch, BlockHash, BlockHashOrNumber, BlockNumHash, BlockNumber, Bloom, Bytes, Certificate,
+        CommittedSubDag, ConsensusHeader, ConsensusOutput, Database as _, Encodable2718 as _,
+        EvmHardfork, ExecHeader, Hash as _, Notifier, Receipt, ReputationScores, SealedBlock,
+        SealedBlockWithSenders, SealedHeader, TaskManager, TimestampGranularity, B256,
+        EMPTY_OMMER_ROOT_HASH, EMPTY_WITHDRAWALS, MIN_PROTOCOL_BASE_FEE, U256,
     };
     use tokio::{sync::oneshot, time::timeout};
-    use tokio_stream::wrappers::BroadcastStream;
+    use tokio_stream::{wrappers::BroadcastStream, StreamExt as _};
     use tracing::debug;
 
     /// This tests that a single block is executed if the output from consensus contains no
@@ -383,6 +505,7 @@ mod tests {
             provider.clone(),
             evm_config,
             max_round,
+            None,
             consensus_output_stream,
             genesis_header.clone(),
             shutdown.subscribe(),
@@ -491,6 +614,77 @@ mod tests {
         Ok(())
     }
 
+    /// This tests that external subscribers to the execution node's canonical state receive a
+    /// commit notification when consensus output is executed.
+    #[tokio::test]
+    async fn test_canon_state_subscriber_receives_commit() -> eyre::Result<()> {
+        let mut leader = Certificate::default();
+        let sub_dag_index = 0;
+        leader.header.round = sub_dag_index as u32;
+        let consensus_output = ConsensusOutput {
+            sub_dag: CommittedSubDag::new(
+                vec![Certificate::default()],
+                leader,
+                sub_dag_index,
+                ReputationScores::default(),
+                None,
+            )
+            .into(),
+            batches: Default::default(), // empty
+            beneficiary: Address::random(),
+            batch_digests: Default::default(), // empty
+            parent_hash: ConsensusHeader::default().digest(),
+            number: 0,
+            extra: Default::default(),
+            early_finalize: true,
+        };
+
+        let chain = adiri_chain_spec_arc();
+
+        // execution node components
+        let execution_node = default_test_execution_node(Some(chain.clone()), None)?;
+
+        // subscribe before output is executed
+        let mut canon_state = execution_node.subscribe_canon_state().await;
+
+        let (to_engine, from_consensus) = tokio::sync::broadcast::channel(1);
+        let consensus_output_stream = BroadcastStream::from(from_consensus);
+        let provider = execution_node.get_provider().await;
+        let evm_config = execution_node.get_evm_config().await;
+        let shutdown = Notifier::default();
+        let engine = ExecutorEngine::new(
+            provider,
+            evm_config,
+            None,
+            None,
+            consensus_output_stream,
+            chain.sealed_genesis_header(),
+            shutdown.subscribe(),
+        );
+
+        // send output and drop sending channel to shut engine down
+        let broadcast_result = to_engine.send(consensus_output);
+        assert!(broadcast_result.is_ok());
+        drop(to_engine);
+
+        // spawn engine task
+        TaskManager::default().spawn_blocking(Box::pin(async move {
+            let _ = engine.await;
+        }));
+
+        let notification = timeout(Duration::from_secs(10), canon_state.next())
+            .await?
+            .expect("canon state notification");
+        match notification {
+            CanonStateNotification::Commit { new } => {
+                assert_eq!(new.tip().block.header.header().number, 1)
+            }
+            CanonStateNotification::Reorg { .. } => panic!("expected commit notification"),
+        }
+
+        Ok(())
+    }
+
     /// This tests that a single block is NOT executed if the output from consensus contains no
     /// transactions and we are not setting early finalize.
     #[tokio::test]
@@ -543,6 +737,7 @@ mod tests {
             provider.clone(),
             evm_config,
             max_round,
+            None,
             consensus_output_stream,
             genesis_header.clone(),
             shutdown.subscribe(),
@@ -738,6 +933,7 @@ mod tests {
             blockchain.clone(),
             evm_config,
             max_round,
+            None,
             consensus_output_stream,
             parent,
             shutdown.subscribe(),
@@ -879,6 +1075,16 @@ mod tests {
             //
             // NOTE: this is currently always empty
             assert_eq!(block.withdrawals_root, Some(EMPTY_WITHDRAWALS));
+            // only the block that completes the output carries the batches as ommers
+            if expected_batch_index == 3 {
+                let expected_ommers = expected_output.ommers();
+                assert_eq!(expected_ommers.len(), 4);
+                assert_eq!(block.body.ommers, expected_ommers);
+                assert_eq!(block.ommers_hash, calculate_ommers_root(&expected_ommers));
+            } else {
+                assert!(block.body.ommers.is_empty());
+                assert_eq!(block.ommers_hash, EMPTY_OMMER_ROOT_HASH);
+            }
         }
 
         Ok(())
@@ -1070,6 +1276,7 @@ mod tests {
             blockchain.clone(),
             evm_config,
             max_round,
+            None,
             consensus_output_stream,
             parent,
             shutdown.subscribe(),
@@ -1231,8 +1438,14 @@ mod tests {
         Ok(())
     }
 
-    #[tokio::test]
-    async fn test_max_round_terminates_early() -> eyre::Result<()> {
+    /// Execute two outputs from consensus with 4 batches each, queued before the engine starts.
+    ///
+    /// The sending channel is not dropped, so the engine only terminates once `max_round` or
+    /// `max_block` is reached. Returns the execution node and the hash of the first output.
+    async fn execute_two_queued_outputs(
+        max_round: Option<u64>,
+        max_block: Option<u64>,
+    ) -> eyre::Result<(TestExecutionNode, BlockHash)> {
         // create batches for consensus output
         let mut batches_1 = tn_test_utils::batches(4); // create 4 batches
         let mut batches_2 = tn_test_utils::batches(4); // create 4 batches
@@ -1359,8 +1572,6 @@ mod tests {
         let consensus_output_stream = BroadcastStream::from(from_consensus);
         let blockchain = execution_node.get_provider().await;
         let evm_config = execution_node.get_evm_config().await;
-        // set max round to "1" - this should receive both digests, but stop after the first round
-        let max_round = Some(1);
         let parent = chain.sealed_genesis_header();
 
         let shutdown = Notifier::default();
@@ -1368,6 +1579,7 @@ mod tests {
             blockchain.clone(),
             evm_config,
             max_round,
+            max_block,
             consensus_output_stream,
             parent,
             shutdown.subscribe(),
@@ -1377,8 +1589,8 @@ mod tests {
         engine.queued.push_back(consensus_output_1);
         engine.queued.push_back(consensus_output_2);
 
-        // NOTE: sending channel is NOT dropped in this test, so engine should continue listening
-        // until max block reached
+        // NOTE: sending channel is NOT dropped, so engine should continue listening until max
+        // round or max block reached
 
         // channels for engine shutting down
         let (tx, rx) = oneshot::channel();
@@ -1394,6 +1606,16 @@ mod tests {
         let engine_task = timeout(Duration::from_secs(10), rx).await?;
         assert!(engine_task.is_ok());
 
+        Ok((execution_node, consensus_output_1_hash))
+    }
+
+    #[tokio::test]
+    async fn test_max_round_terminates_early() -> eyre::Result<()> {
+        // set max round to "1" - this should receive both outputs, but stop after the first round
+        let (execution_node, consensus_output_1_hash) =
+            execute_two_queued_outputs(Some(1), None).await?;
+
+        let blockchain = execution_node.get_provider().await;
         let last_block_num = blockchain.last_block_number()?;
         let canonical_tip = blockchain.canonical_tip();
         let final_block = blockchain.finalized_block_num_hash()?.expect("finalized block");
@@ -1416,4 +1638,765 @@ mod tests {
 
         Ok(())
     }
+
+    /// This tests that the engine stops once the canonical tip reaches the max block, even if more
+    /// output from consensus is queued.
+    #[tokio::test]
+    async fn test_max_block_terminates_early() -> eyre::Result<()> {
+        // set max block to "4" - this should receive both outputs, but stop after the first round
+        let (execution_node, consensus_output_1_hash) =
+            execute_two_queued_outputs(None, Some(4)).await?;
+
+        // the canonical tip stops at the max block and the second output is not executed
+        let blockchain = execution_node.get_provider().await;
+        let canonical_tip = blockchain.canonical_tip();
+        assert_eq!(canonical_tip.number, 4);
+        assert_eq!(blockchain.last_block_number()?, 4);
+        let final_block = blockchain.finalized_block_num_hash()?.expect("finalized block");
+        assert_eq!(canonical_tip, final_block);
+        let last_output = execution_node.last_executed_output().await?;
+        assert_eq!(last_output, consensus_output_1_hash);
+
+        Ok(())
+    }
+
+    /// This tests that a round finalized before all batches were executed is flagged for replay.
+    #[tokio::test]
+    async fn test_partial_output_needs_replay() -> eyre::Result<()> {
+        // create batches for consensus output
+        let mut batches = tn_test_utils::batches(4); // create 4 batches
+
+        // use default genesis and seed accounts to execute batches
+        let genesis = adiri_genesis();
+        let (genesis, _txs_by_block, _signers_by_block) =
+            seeded_genesis_from_random_batches(genesis, batches.iter());
+        let chain: Arc<ChainSpec> = Arc::new(genesis.into());
+
+        // create execution node components
+        let execution_node = default_test_execution_node(Some(chain.clone()), None)?;
+        let parent = chain.sealed_genesis_header();
+
+        // execute batches to update headers with valid data
+        for (idx, batch) in batches.iter_mut().enumerate() {
+            batch.beneficiary = Address::random();
+            batch.base_fee_per_gas = Some(MIN_PROTOCOL_BASE_FEE + idx as u64);
+            execute_test_batch(batch, &parent);
+        }
+
+        //=== Consensus
+        let mut leader = Certificate::default();
+        leader.update_created_at_for_test(now());
+        let sub_dag_index = 1;
+        leader.header.round = sub_dag_index as u32;
+        let batch_digests: VecDeque<BlockHash> = batches.iter().map(|b| b.digest()).collect();
+        let mut cert = Certificate::default();
+        cert.header.payload = batch_digests.iter().map(|digest| (*digest, (0, 0))).collect();
+        let consensus_output = ConsensusOutput {
+            sub_dag: CommittedSubDag::new(
+                vec![cert],
+                leader,
+                sub_dag_index,
+                ReputationScores::default(),
+                None,
+            )
+            .into(),
+            batches: vec![batches],
+            beneficiary: Address::random(),
+            batch_digests: batch_digests.clone(),
+            parent_hash: ConsensusHeader::default().digest(),
+            number: 0,
+            extra: Default::default(),
+            early_finalize: true,
+        };
+
+        // store the consensus header the expected batches are read from
+        let consensus_db = MemDatabase::default();
+        let consensus_header: ConsensusHeader = consensus_output.clone().into();
+        consensus_db.insert::<ConsensusBlocks>(&consensus_header.number, &consensus_header)?;
+        consensus_db.insert::<ConsensusBlockNumbersByDigest>(
+            &consensus_header.digest(),
+            &consensus_header.number,
+        )?;
+
+        //=== Execution
+        let (to_engine, from_consensus) = tokio::sync::broadcast::channel(1);
+        let consensus_output_stream = BroadcastStream::from(from_consensus);
+        let blockchain = execution_node.get_provider().await;
+        let evm_config = execution_node.get_evm_config().await;
+        let shutdown = Notifier::default();
+        let engine = ExecutorEngine::new(
+            blockchain.clone(),
+            evm_config,
+            None,
+            None,
+            consensus_output_stream,
+            parent,
+            shutdown.subscribe(),
+        );
+
+        // send output and drop sending channel to shut engine down
+        let broadcast_result = to_engine.send(consensus_output);
+        assert!(broadcast_result.is_ok());
+        drop(to_engine);
+
+        let (tx, rx) = oneshot::channel();
+        TaskManager::default().spawn_blocking(Box::pin(async move {
+            let res = engine.await;
+            let _ = tx.send(res);
+        }));
+        let engine_task = timeout(Duration::from_secs(10), rx).await?;
+        assert!(engine_task.is_ok());
+
+        // all 4 batches executed and finalized
+        assert_eq!(blockchain.finalized_block_number()?, Some(4));
+        let replay = execution_node.last_output_needs_replay(&consensus_db).await?;
+        assert_eq!(replay, None);
+
+        // simulate a db that was only finalized through the second batch
+        let provider_rw = blockchain.database_provider_rw()?;
+        provider_rw.save_finalized_block_number(2)?;
+        provider_rw.commit()?;
+
+        let replay = execution_node.last_output_needs_replay(&consensus_db).await?;
+        assert_eq!(replay, Some(sub_dag_index));
+
+        // the expected batches can't be read without the round's consensus header
+        let replay = execution_node.last_output_needs_replay(&MemDatabase::default()).await;
+        assert!(replay.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reordered_batch_digests_rejected() -> eyre::Result<()> {
+        let batches = tn_test_utils::batches(4);
+        let chain = adiri_chain_spec_arc();
+        let execution_node = default_test_execution_node(Some(chain.clone()), None)?;
+        let parent = chain.sealed_genesis_header();
+
+        // swap the order of two digests so they no longer match the batches
+        let mut batch_digests: VecDeque<BlockHash> = batches.iter().map(|b| b.digest()).collect();
+        batch_digests.swap(1, 2);
+
+        //=== Consensus
+        let mut leader = Certificate::default();
+        leader.update_created_at_for_test(now());
+        let sub_dag_index = 1;
+        leader.header.round = sub_dag_index as u32;
+        let consensus_output = ConsensusOutput {
+            sub_dag: CommittedSubDag::new(
+                vec![Certificate::default()],
+                leader,
+                sub_dag_index,
+                ReputationScores::default(),
+                None,
+            )
+            .into(),
+            batches: vec![batches],
+            beneficiary: Address::random(),
+            batch_digests,
+            parent_hash: ConsensusHeader::default().digest(),
+            number: 0,
+            extra: Default::default(),
+            early_finalize: true,
+        };
+
+        //=== Execution
+        let (to_engine, from_consensus) = tokio::sync::broadcast::channel(1);
+        let consensus_output_stream = BroadcastStream::from(from_consensus);
+        let blockchain = execution_node.get_provider().await;
+        let evm_config = execution_node.get_evm_config().await;
+        let shutdown = Notifier::default();
+        let engine = ExecutorEngine::new(
+            blockchain.clone(),
+            evm_config,
+            None,
+            None,
+            consensus_output_stream,
+            parent,
+            shutdown.subscribe(),
+        );
+
+        // send output and drop sending channel to shut engine down
+        let broadcast_result = to_engine.send(consensus_output);
+        assert!(broadcast_result.is_ok());
+        drop(to_engine);
+
+        let (tx, rx) = oneshot::channel();
+        TaskManager::default().spawn_blocking(Box::pin(async move {
+            let res = engine.await;
+            let _ = tx.send(res);
+        }));
+        let engine_task = timeout(Duration::from_secs(10), rx).await??;
+
+        // the first mismatch is the second batch
+        assert!(matches!(engine_task, Err(TnEngineError::BatchDigestMismatch(1, _, _))));
+
+        // nothing was executed
+        assert_eq!(blockchain.last_block_number()?, 0);
+
+        Ok(())
+    }
+
+    /// Outputs that exceed the max blocks per finalization are finalized in chunks.
+    #[tokio::test]
+    async fn test_large_output_finalized_in_chunks() -> eyre::Result<()> {
+        // create 5 batches so the output is split into chunks of 2, 2, and 1
+        let mut batches = tn_test_utils::batches(5);
+        let (genesis, _txs_by_block, _signers_by_block) =
+            seeded_genesis_from_random_batches(adiri_genesis(), batches.iter());
+        let chain: Arc<ChainSpec> = Arc::new(genesis.into());
+        let execution_node = default_test_execution_node(Some(chain.clone()), None)?;
+        let parent = chain.sealed_genesis_header();
+        for batch in batches.iter_mut() {
+            execute_test_batch(batch, &parent);
+        }
+
+        //=== Consensus
+        let mut leader = Certificate::default();
+        leader.update_created_at_for_test(now());
+        let sub_dag_index = 1;
+        leader.header.round = sub_dag_index as u32;
+        let batch_digests: VecDeque<BlockHash> = batches.iter().map(|b| b.digest()).collect();
+        let consensus_output = ConsensusOutput {
+            sub_dag: CommittedSubDag::new(
+                vec![Certificate::default()],
+                leader,
+                sub_dag_index,
+                ReputationScores::default(),
+                None,
+            )
+            .into(),
+            batches: vec![batches],
+            beneficiary: Address::random(),
+            batch_digests,
+            parent_hash: ConsensusHeader::default().digest(),
+            number: 0,
+            extra: Default::default(),
+            early_finalize: true,
+        };
+
+        //=== Execution
+        let mut canon_state = execution_node.subscribe_canon_state().await;
+        let provider = execution_node.get_provider().await;
+        let evm_config = execution_node.get_evm_config().await;
+        let args = BuildArguments::new(provider.clone(), consensus_output.clone(), parent)
+            .with_max_blocks_per_finalization(Some(2));
+        let tip = execute_consensus_output(&evm_config, args)?;
+
+        // each chunk is committed separately
+        for expected in [2, 4, 5] {
+            let notification = timeout(Duration::from_secs(10), canon_state.next())
+                .await?
+                .expect("canon state notification");
+            assert_eq!(notification.tip().number, expected);
+        }
+
+        // the final tip is canonical and finalized
+        assert_eq!(tip.number, 5);
+        assert_eq!(provider.last_block_number()?, 5);
+        let final_block = provider.finalized_block_num_hash()?.expect("finalized block");
+        assert_eq!(final_block.number, 5);
+        assert_eq!(final_block.hash, tip.hash());
+
+        // every block keeps the output's round and batch index
+        for number in 1..=5 {
+            let block = provider.block_by_number(number)?.expect("executed block");
+            assert_eq!(<FixedBytes<8> as Into<u64>>::into(block.header.nonce), sub_dag_index);
+            assert_eq!(block.header.difficulty, U256::from(number - 1));
+        }
+
+        // output that was already executed is not executed again
+        let args = BuildArguments::new(provider.clone(), consensus_output, tip.clone())
+            .with_max_blocks_per_finalization(Some(2));
+        let resumed = execute_consensus_output(&evm_config, args)?;
+        assert_eq!(resumed, tip);
+        assert_eq!(provider.last_block_number()?, 5);
+
+        Ok(())
+    }
+
+    /// Create consensus output for a single batch.
+    fn output_for_batch(batch: Batch) -> ConsensusOutput {
+        let batch_digests = VecDeque::from([batch.digest()]);
+        ConsensusOutput {
+            sub_dag: CommittedSubDag::new(
+                vec![Certificate::default()],
+                Certificate::default(),
+                1,
+                ReputationScores::default(),
+                None,
+            )
+            .into(),
+            batches: vec![vec![batch]],
+            beneficiary: Address::random(),
+            batch_digests,
+            parent_hash: ConsensusHeader::default().digest(),
+            number: 0,
+            extra: Default::default(),
+            early_finalize: true,
+        }
+    }
+
+    /// The same batch executes differently under different hardfork overrides.
+    #[tokio::test]
+    async fn test_evm_hardfork_override_changes_execution() -> eyre::Result<()> {
+        // schedule cancun so both hardforks are valid for the chain
+        let mut genesis = test_genesis();
+        genesis.config.cancun_time = Some(0);
+        let chain: Arc<ChainSpec> = Arc::new(genesis.into());
+        let parent = chain.sealed_genesis_header();
+
+        // contract creation with init code that reads transient storage (EIP-1153)
+        //
+        // PUSH1 0x00 TLOAD STOP
+        //
+        // TLOAD is an invalid opcode before cancun, so execution halts and consumes all gas
+        let gas_limit = 100_000;
+        let mut tx_factory = TransactionFactory::new();
+        let transaction = tx_factory.create_explicit_eip1559(
+            Some(chain.chain.id()),
+            None,
+            None,
+            None,
+            Some(gas_limit),
+            None, // create
+            Some(U256::ZERO),
+            Some(Bytes::from_static(&[0x60, 0x00, 0x5c, 0x00])),
+            None,
+        );
+        let mut batch =
+            Batch { transactions: vec![transaction.encoded_2718()], ..Default::default() };
+        execute_test_batch(&mut batch, &parent);
+        let output = output_for_batch(batch);
+
+        // execute the same output with each hardfork
+        let mut gas_used = Vec::new();
+        for hardfork in [EvmHardfork::Shanghai, EvmHardfork::Cancun] {
+            let execution_node = default_test_execution_node(Some(chain.clone()), None)?;
+            let provider = execution_node.get_provider().await;
+            let evm_config = execution_node.get_evm_config().await;
+            let args = BuildArguments::new(provider, output.clone(), parent.clone())
+                .with_evm_hardfork(Some(hardfork));
+            let header = execute_consensus_output(&evm_config, args)?;
+            assert_eq!(header.number, 1);
+            gas_used.push(header.gas_used);
+        }
+
+        // the transaction halts with shanghai and succeeds with cancun
+        assert_eq!(gas_used[0], gas_limit);
+        assert!(gas_used[1] < gas_limit);
+
+        Ok(())
+    }
+
+    /// Hardforks that aren't scheduled in the chain spec are rejected before execution.
+    #[tokio::test]
+    async fn test_unscheduled_evm_hardfork_rejected() -> eyre::Result<()> {
+        // adiri doesn't schedule cancun
+        let chain = adiri_chain_spec_arc();
+        let execution_node = default_test_execution_node(Some(chain.clone()), None)?;
+        let provider = execution_node.get_provider().await;
+        let evm_config = execution_node.get_evm_config().await;
+        let output = output_for_batch(Batch::default());
+
+        let args = BuildArguments::new(provider.clone(), output, chain.sealed_genesis_header())
+            .with_evm_hardfork(Some(EvmHardfork::Cancun));
+        let res = execute_consensus_output(&evm_config, args);
+        assert!(matches!(res, Err(TnEngineError::UnscheduledHardfork(EvmHardfork::Cancun))));
+
+        // nothing executed
+        assert_eq!(provider.last_block_number()?, 0);
+
+        Ok(())
+    }
+
+    /// Replaying an executed batch reproduces the receipts from the original execution.
+    #[tokio::test]
+    async fn test_replay_batch_execution_matches_original_receipts() -> eyre::Result<()> {
+        let chain: Arc<ChainSpec> = Arc::new(test_genesis().into());
+        let parent = chain.sealed_genesis_header();
+
+        // a few transfers from the funded account
+        let mut tx_factory = TransactionFactory::new();
+        let transactions = (0..3)
+            .map(|_| {
+                tx_factory.create_eip1559_encoded(
+                    chain.clone(),
+                    None,
+                    MIN_PROTOCOL_BASE_FEE as u128,
+                    Some(Address::random()),
+                    U256::from(1),
+                    Bytes::new(),
+                )
+            })
+            .collect();
+        let mut batch = Batch { transactions, ..Default::default() };
+        execute_test_batch(&mut batch, &parent);
+
+        // execute the batch through the engine
+        let execution_node = default_test_execution_node(Some(chain.clone()), None)?;
+        let provider = execution_node.get_provider().await;
+        let evm_config = execution_node.get_evm_config().await;
+        let output = output_for_batch(batch.clone());
+        let args = BuildArguments::new(provider.clone(), output, parent.clone());
+        let header = execute_consensus_output(&evm_config, args)?;
+        assert_eq!(header.number, 1);
+        let expected =
+            provider.receipts_by_block(header.number.into())?.expect("executed block receipts");
+        assert_eq!(expected.len(), 3);
+
+        // replay the batch on top of the same parent
+        let outcome = replay_batch_execution(
+            &evm_config,
+            &provider,
+            batch,
+            parent,
+            None,
+            TimestampGranularity::default(),
+        )?;
+        let replayed: Vec<_> =
+            outcome.receipts_by_block(header.number).iter().flatten().cloned().collect();
+        assert_eq!(replayed, expected);
+
+        // replay doesn't extend the chain
+        assert_eq!(provider.last_block_number()?, 1);
+
+        Ok(())
+    }
+
+    /// Provider wrapper that fails `make_canonical` with a transient error a set number of times
+    /// before delegating to the inner provider.
+    #[derive(Clone)]
+    struct FlakyProvider<P> {
+        inner: P,
+        /// Remaining failures to inject.
+        failures: Arc<AtomicUsize>,
+        /// Calls to `make_canonical`.
+        attempts: Arc<AtomicUsize>,
+    }
+
+    impl<P> FlakyProvider<P> {
+        fn new(inner: P, failures: usize) -> Self {
+            Self {
+                inner,
+                failures: Arc::new(AtomicUsize::new(failures)),
+                attempts: Arc::new(AtomicUsize::new(0)),
+            }
+        }
+    }
+
+    impl<P: BlockHashReader> BlockHashReader for FlakyProvider<P> {
+        fn block_hash(&self, number: BlockNumber) -> ProviderResult<Option<B256>> {
+            self.inner.block_hash(number)
+        }
+
+        fn canonical_hashes_range(
+            &self,
+            start: BlockNumber,
+            end: BlockNumber,
+        ) -> ProviderResult<Vec<B256>> {
+            self.inner.canonical_hashes_range(start, end)
+        }
+    }
+
+    impl<P: BlockNumReader> BlockNumReader for FlakyProvider<P> {
+        fn chain_info(&self) -> ProviderResult<ChainInfo> {
+            self.inner.chain_info()
+        }
+
+        fn best_block_number(&self) -> ProviderResult<BlockNumber> {
+            self.inner.best_block_number()
+        }
+
+        fn last_block_number(&self) -> ProviderResult<BlockNumber> {
+            self.inner.last_block_number()
+        }
+
+        fn block_number(&self, hash: B256) -> ProviderResult<Option<BlockNumber>> {
+            self.inner.block_number(hash)
+        }
+    }
+
+    impl<P: BlockIdReader> BlockIdReader for FlakyProvider<P> {
+        fn pending_block_num_hash(&self) -> ProviderResult<Option<BlockNumHash>> {
+            self.inner.pending_block_num_hash()
+        }
+
+        fn safe_block_num_hash(&self) -> ProviderResult<Option<BlockNumHash>> {
+            self.inner.safe_block_num_hash()
+        }
+
+        fn finalized_block_num_hash(&self) -> ProviderResult<Option<BlockNumHash>> {
+            self.inner.finalized_block_num_hash()
+        }
+    }
+
+    impl<P: StateProviderFactory> StateProviderFactory for FlakyProvider<P> {
+        fn latest(&self) -> ProviderResult<StateProviderBox> {
+            self.inner.latest()
+        }
+
+        fn state_by_block_number_or_tag(
+            &self,
+            number_or_tag: BlockNumberOrTag,
+        ) -> ProviderResult<StateProviderBox> {
+            self.inner.state_by_block_number_or_tag(number_or_tag)
+        }
+
+        fn history_by_block_number(&self, block: BlockNumber) -> ProviderResult<StateProviderBox> {
+            self.inner.history_by_block_number(block)
+        }
+
+        fn history_by_block_hash(&self, block: BlockHash) -> ProviderResult<StateProviderBox> {
+            self.inner.history_by_block_hash(block)
+        }
+
+        fn state_by_block_hash(&self, block: BlockHash) -> ProviderResult<StateProviderBox> {
+            self.inner.state_by_block_hash(block)
+        }
+
+        fn pending(&self) -> ProviderResult<StateProviderBox> {
+            self.inner.pending()
+        }
+
+        fn pending_state_by_hash(
+            &self,
+            block_hash: B256,
+        ) -> ProviderResult<Option<StateProviderBox>> {
+            self.inner.pending_state_by_hash(block_hash)
+        }
+    }
+
+    impl<P: HeaderProvider<Header = ExecHeader>> HeaderProvider for FlakyProvider<P> {
+        type Header = ExecHeader;
+
+        fn header(&self, block_hash: &BlockHash) -> ProviderResult<Option<ExecHeader>> {
+            self.inner.header(block_hash)
+        }
+
+        fn header_by_number(&self, num: u64) -> ProviderResult<Option<ExecHeader>> {
+            self.inner.header_by_number(num)
+        }
+
+        fn header_td(&self, hash: &BlockHash) -> ProviderResult<Option<U256>> {
+            self.inner.header_td(hash)
+        }
+
+        fn header_td_by_number(&self, number: BlockNumber) -> ProviderResult<Option<U256>> {
+            self.inner.header_td_by_number(number)
+        }
+
+        fn headers_range(
+            &self,
+            range: impl RangeBounds<BlockNumber>,
+        ) -> ProviderResult<Vec<ExecHeader>> {
+            self.inner.headers_range(range)
+        }
+
+        fn sealed_header(&self, number: BlockNumber) -> ProviderResult<Option<SealedHeader>> {
+            self.inner.sealed_header(number)
+        }
+
+        fn sealed_headers_while(
+            &self,
+            range: impl RangeBounds<BlockNumber>,
+            predicate: impl FnMut(&SealedHeader) -> bool,
+        ) -> ProviderResult<Vec<SealedHeader>> {
+            self.inner.sealed_headers_while(range, predicate)
+        }
+    }
+
+    impl<P: ChainSpecProvider<ChainSpec = ChainSpec>> ChainSpecProvider for FlakyProvider<P> {
+        type ChainSpec = ChainSpec;
+
+        fn chain_spec(&self) -> Arc<ChainSpec> {
+            self.inner.chain_spec()
+        }
+    }
+
+    impl<P: CanonChainTracker<Header = ExecHeader>> CanonChainTracker for FlakyProvider<P> {
+        type Header = ExecHeader;
+
+        fn on_forkchoice_update_received(&self, update: &ForkchoiceState) {
+            self.inner.on_forkchoice_update_received(update)
+        }
+
+        fn last_received_update_timestamp(&self) -> Option<Instant> {
+            self.inner.last_received_update_timestamp()
+        }
+
+        fn on_transition_configuration_exchanged(&self) {
+            self.inner.on_transition_configuration_exchanged()
+        }
+
+        fn last_exchanged_transition_configuration_timestamp(&self) -> Option<Instant> {
+            self.inner.last_exchanged_transition_configuration_timestamp()
+        }
+
+        fn set_canonical_head(&self, header: SealedHeader) {
+            self.inner.set_canonical_head(header)
+        }
+
+        fn set_safe(&self, header: SealedHeader) {
+            self.inner.set_safe(header)
+        }
+
+        fn set_finalized(&self, header: SealedHeader) {
+            self.inner.set_finalized(header)
+        }
+    }
+
+    impl<P: BlockchainTreeViewer> BlockchainTreeViewer for FlakyProvider<P> {
+        fn header_by_hash(&self, hash: BlockHash) -> Option<SealedHeader> {
+            self.inner.header_by_hash(hash)
+        }
+
+        fn block_by_hash(&self, hash: BlockHash) -> Option<SealedBlock> {
+            self.inner.block_by_hash(hash)
+        }
+
+        fn block_with_senders_by_hash(&self, hash: BlockHash) -> Option<SealedBlockWithSenders> {
+            self.inner.block_with_senders_by_hash(hash)
+        }
+
+        fn buffered_block_by_hash(&self, block_hash: BlockHash) -> Option<SealedBlock> {
+            self.inner.buffered_block_by_hash(block_hash)
+        }
+
+        fn buffered_header_by_hash(&self, block_hash: BlockHash) -> Option<SealedHeader> {
+            self.inner.buffered_header_by_hash(block_hash)
+        }
+
+        fn is_canonical(&self, hash: BlockHash) -> Result<bool, ProviderError> {
+            self.inner.is_canonical(hash)
+        }
+
+        fn lowest_buffered_ancestor(&self, hash: BlockHash) -> Option<SealedBlockWithSenders> {
+            self.inner.lowest_buffered_ancestor(hash)
+        }
+
+        fn canonical_tip(&self) -> BlockNumHash {
+            self.inner.canonical_tip()
+        }
+
+        fn pending_block_num_hash(&self) -> Option<BlockNumHash> {
+            BlockchainTreeViewer::pending_block_num_hash(&self.inner)
+        }
+
+        fn pending_block_and_receipts(&self) -> Option<(SealedBlock, Vec<Receipt>)> {
+            self.inner.pending_block_and_receipts()
+        }
+
+        fn receipts_by_block_hash(&self, block_hash: BlockHash) -> Option<Vec<Receipt>> {
+            self.inner.receipts_by_block_hash(block_hash)
+        }
+    }
+
+    impl<P: BlockchainTreeEngine> BlockchainTreeEngine for FlakyProvider<P> {
+        fn buffer_block(&self, block: SealedBlockWithSenders) -> Result<(), InsertBlockError> {
+            self.inner.buffer_block(block)
+        }
+
+        fn insert_block(
+            &self,
+            block: SealedBlockWithSenders,
+            validation_kind: BlockValidationKind,
+        ) -> Result<InsertPayloadOk, InsertBlockError> {
+            self.inner.insert_block(block, validation_kind)
+        }
+
+        fn finalize_block(&self, finalized_block: BlockNumber) -> ProviderResult<()> {
+            self.inner.finalize_block(finalized_block)
+        }
+
+        fn connect_buffered_blocks_to_canonical_hashes_and_finalize(
+            &self,
+            last_finalized_block: BlockNumber,
+        ) -> Result<(), CanonicalError> {
+            self.inner
+                .connect_buffered_blocks_to_canonical_hashes_and_finalize(last_finalized_block)
+        }
+
+        fn update_block_hashes_and_clear_buffered(
+            &self,
+        ) -> Result<BTreeMap<BlockNumber, BlockHash>, CanonicalError> {
+            self.inner.update_block_hashes_and_clear_buffered()
+        }
+
+        fn connect_buffered_blocks_to_canonical_hashes(&self) -> Result<(), CanonicalError> {
+            self.inner.connect_buffered_blocks_to_canonical_hashes()
+        }
+
+        fn make_canonical(
+            &self,
+            block_hash: BlockHash,
+        ) -> Result<CanonicalOutcome, CanonicalError> {
+            self.attempts.fetch_add(1, Ordering::SeqCst);
+            let inject = self
+                .failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok();
+            if inject {
+                // transient database error
+                return Err(CanonicalError::Provider(ProviderError::BestBlockNotFound));
+            }
+            self.inner.make_canonical(block_hash)
+        }
+    }
+
+    /// Create empty consensus output.
+    fn empty_output() -> ConsensusOutput {
+        ConsensusOutput {
+            sub_dag: CommittedSubDag::new(
+                vec![Certificate::default()],
+                Certificate::default(),
+                0,
+                ReputationScores::default(),
+                None,
+            )
+            .into(),
+            batches: Default::default(),
+            beneficiary: Address::random(),
+            batch_digests: Default::default(),
+            parent_hash: ConsensusHeader::default().digest(),
+            number: 0,
+            extra: Default::default(),
+            early_finalize: true,
+        }
+    }
+
+    /// Transient failures from the provider are retried while executing consensus output.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_transient_canonical_failure_is_retried() -> eyre::Result<()> {
+        let chain = adiri_chain_spec_arc();
+        let retry = CanonicalUpdateRetry { max_attempts: 3, delay: Duration::from_millis(10) };
+
+        // one failure less than the max attempts
+        let execution_node = default_test_execution_node(Some(chain.clone()), None)?;
+        let provider = FlakyProvider::new(execution_node.get_provider().await, 2);
+        let evm_config = execution_node.get_evm_config().await;
+        let args =
+            BuildArguments::new(provider.clone(), empty_output(), chain.sealed_genesis_header())
+                .with_canonical_update_retry(retry);
+        let header = execute_consensus_output(&evm_config, args)?;
+        assert_eq!(header.number, 1);
+        assert_eq!(provider.attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(provider.inner.last_block_number()?, 1);
+        assert_eq!(provider.inner.canonical_tip().hash, header.hash());
+
+        // retries are bounded
+        let execution_node = default_test_execution_node(Some(chain.clone()), None)?;
+        let provider = FlakyProvider::new(execution_node.get_provider().await, 3);
+        let evm_config = execution_node.get_evm_config().await;
+        let args =
+            BuildArguments::new(provider.clone(), empty_output(), chain.sealed_genesis_header())
+                .with_canonical_update_retry(retry);
+        let res = execute_consensus_output(&evm_config, args);
+        assert!(matches!(res, Err(TnEngineError::Canonical(_))));
+        assert_eq!(provider.attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(provider.inner.last_block_number()?, 0);
+
+        Ok(())
+    }
 }
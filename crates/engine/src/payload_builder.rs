@@ -16,20 +16,66 @@ use reth_revm::{
     DatabaseCommit, State,
 };
 use reth_rpc_eth_types::utils::recover_raw_transaction;
-use std::sync::Arc;
-use tn_node_traits::{BuildArguments, TNPayload, TNPayloadAttributes};
+use std::{fmt::Debug, sync::Arc, time::Duration};
+use tn_node_traits::{BuildArguments, CanonicalUpdateRetry, TNPayload, TNPayloadAttributes};
 use tn_types::{
-    calculate_transaction_root, max_batch_gas, Batch, Block, BlockBody, BlockExt as _,
-    ConsensusOutput, ExecHeader, Hash as _, Receipt, SealedBlockWithSenders, SealedHeader,
-    TransactionSigned, Withdrawals, B256, EMPTY_OMMER_ROOT_HASH, EMPTY_RECEIPTS,
-    EMPTY_TRANSACTIONS, EMPTY_WITHDRAWALS, U256,
+    calculate_ommers_root, calculate_transaction_root, max_batch_gas, Batch, Block, BlockBody,
+    BlockExt as _, ConsensusOutput, EvmHardfork, ExecHeader, Hash as _, Receipt,
+    SealedBlockWithSenders, SealedHeader, TimestampGranularity, TransactionSigned, Withdrawals,
+    B256, EMPTY_OMMER_ROOT_HASH, EMPTY_RECEIPTS, EMPTY_TRANSACTIONS, EMPTY_WITHDRAWALS, U256,
 };
 use tracing::{debug, error, info, warn};
 
+/// Retry a canonical or finalized update for the chain.
+///
+/// The update is attempted up to `retry.max_attempts` times. The last error is returned if all
+/// attempts fail. See [retry_delay] for how the delay between attempts is awaited.
+fn with_retry<T, E, F>(retry: &CanonicalUpdateRetry, update: &str, mut f: F) -> Result<T, E>
+where
+    E: Debug,
+    F: FnMut() -> Result<T, E>,
+{
+    let mut attempt = 1;
+    loop {
+        match f() {
+            Ok(res) => return Ok(res),
+            Err(e) if attempt < retry.max_attempts => {
+                warn!(target: "engine", ?e, ?attempt, max_attempts=?retry.max_attempts, update, "chain update failed - retrying");
+                retry_delay(retry.delay);
+                attempt += 1;
+            }
+            Err(e) => {
+                error!(target: "engine", ?e, ?attempt, update, "chain update failed");
+                return Err(e);
+            }
+        }
+    }
+}
+
+/// Wait between attempts to update the chain.
+///
+/// Execution is synchronous and the engine runs it on a blocking thread, so sleeping here is safe.
+/// If the update is retried from a multi-threaded runtime's worker instead, the worker is handed
+/// off with `block_in_place` so other tasks on the executor keep making progress while this one
+/// waits.
+fn retry_delay(delay: Duration) {
+    if delay.is_zero() {
+        return;
+    }
+
+    match tokio::runtime::Handle::try_current().map(|handle| handle.runtime_flavor()) {
+        Ok(tokio::runtime::RuntimeFlavor::MultiThread) => {
+            tokio::task::block_in_place(|| std::thread::sleep(delay))
+        }
+        _ => std::thread::sleep(delay),
+    }
+}
+
 fn finalize_signed_blocks<Provider>(
     provider: &Provider,
     output: &ConsensusOutput,
     canonical_header: &SealedHeader,
+    retry: &CanonicalUpdateRetry,
 ) -> EngineResult<()>
 where
     Provider: StateProviderFactory
@@ -52,7 +98,7 @@ where
             // this removes canonical blocks from the tree, stores the finalized block number in the
             // database, but still need to set_finalized afterwards for utilization in-memory for
             // components, like RPC
-            provider.finalize_block(block.header().number)?;
+            with_retry(retry, "finalize_block", || provider.finalize_block(block.header().number))?;
             provider.set_finalized(block.clone());
 
             // update safe block last because this is less time sensitive but still needs to happen
@@ -67,7 +113,8 @@ where
 
 /// Execute output from consensus to extend the canonical chain.
 ///
-/// The function handles all types of output, included multiple blocks and empty blocks.
+/// The function handles all types of output, included multiple blocks and empty blocks. The block
+/// executed from the output's final batch carries every batch as an ommer in canonical order.
 #[inline]
 pub fn execute_consensus_output<EvmConfig, Provider>(
     evm_config: &EvmConfig,
@@ -81,19 +128,31 @@ where
         + HeaderProvider<Header = ExecHeader>
         + CanonChainTracker<Header = ExecHeader>,
 {
-    let BuildArguments { provider, mut output, parent_header } = args;
+    let BuildArguments {
+        provider,
+        mut output,
+        parent_header,
+        canonical_update_retry,
+        evm_hardfork,
+        max_blocks_per_finalization,
+        timestamp_granularity,
+    } = args;
     debug!(target: "engine", ?output, "executing output");
 
+    // ensure the hardfork override is valid for the chain before executing anything
+    if let Some(hardfork) = evm_hardfork {
+        if !hardfork.is_scheduled(&provider.chain_spec()) {
+            error!(target: "engine", ?hardfork, "evm hardfork override not scheduled in chain spec");
+            return Err(TnEngineError::UnscheduledHardfork(hardfork));
+        }
+    }
+
     // output digest returns the `ConsensusHeader` digest
     let output_digest: B256 = output.digest().into();
     let batches = output.flatten_batches();
 
-    // assert vecs match
-    debug_assert_eq!(
-        batches.len(),
-        output.batch_digests.len(),
-        "uneven number of sealed blocks from batches and batch digests"
-    );
+    // ensure batches match the committed digests before executing anything
+    validate_batch_digests(&batches, &output)?;
 
     // rename canonical header for clarity
     let mut canonical_header = parent_header;
@@ -120,7 +179,7 @@ where
             output_digest, // use output digest for mix hash
             withdrawals,
         );
-        let payload = TNPayload::new(payload_attributes);
+        let payload = TNPayload::new(payload_attributes).with_evm_hardfork(evm_hardfork);
 
         // execute
         let next_canonical_block = build_block_from_empty_payload(
@@ -141,13 +200,24 @@ where
                 error!(target: "engine", header=?canonical_header, ?e, "failed to insert next canonical block");
             })?;
     } else {
+        // resume after the last committed chunk if the output was partially executed
+        let num_batches = batches.len();
+        let mut ommers = Some(output.ommers());
+        let resume_index = executed_batches(&canonical_header, &output);
+        if resume_index > 0 {
+            info!(target: "engine", ?resume_index, ?num_batches, "resuming partially executed output");
+        }
+
         // loop and construct blocks with transactions
         for (block_index, block) in batches.into_iter().enumerate() {
             let batch_digest =
                 output.next_batch_digest().ok_or(TnEngineError::NextBlockDigestMissing)?;
+            if block_index < resume_index {
+                continue;
+            }
             // use batch's base fee, gas limit, and withdrawals
             let base_fee_per_gas = block.base_fee_per_gas.unwrap_or_default();
-            let gas_limit = max_batch_gas(block.timestamp);
+            let gas_limit = max_batch_gas(timestamp_granularity.to_secs(block.timestamp));
 
             // apply XOR bitwise operator with worker's digest to ensure unique mixed hash per block
             // for round
@@ -164,20 +234,31 @@ where
                 mix_hash,
                 withdrawals,
             );
-            let payload = TNPayload::new(payload_attributes);
+            let payload = TNPayload::new(payload_attributes).with_evm_hardfork(evm_hardfork);
+
+            // the block that completes the output carries every batch as an ommer
+            let final_block = block_index + 1 == num_batches;
+            let block_ommers =
+                if final_block { ommers.take().unwrap_or_default() } else { Vec::new() };
 
             // execute
-            let next_canonical_block = build_block_from_batch_payload(
+            let (next_canonical_block, _) = build_block_from_batch_payload(
                 evm_config,
                 payload,
                 &provider,
                 provider.chain_spec(),
                 block,
                 output.consensus_header_hash(),
+                block_ommers,
             )?;
 
             debug!(target: "engine", ?next_canonical_block, "worker's block executed");
 
+            // ensure the completing block's ommers match the output's batches
+            if final_block {
+                validate_ommers(&next_canonical_block.body.ommers, &output)?;
+            }
+
             // update header for next block execution in loop
             canonical_header = next_canonical_block.header.clone();
 
@@ -186,9 +267,64 @@ where
                 .insert_block(next_canonical_block, BlockValidationKind::SkipStateRootValidation).inspect_err(|e| {
                     error!(target: "engine", header=?canonical_header, ?e, "failed to insert next canonical block");
                 })?;
+
+            // commit the chunk before executing the rest of the output
+            //
+            // the final chunk is committed after the loop
+            let executed = block_index + 1;
+            let chunk_complete = max_blocks_per_finalization
+                .is_some_and(|max| max > 0 && (executed - resume_index) % max == 0);
+            if chunk_complete && executed < num_batches {
+                debug!(target: "engine", ?executed, ?num_batches, "finalizing chunk of output");
+                commit_executed_blocks(
+                    &provider,
+                    &output,
+                    &canonical_header,
+                    &canonical_update_retry,
+                )?;
+            }
         }
     } // end block execution for round
 
+    commit_executed_blocks(&provider, &output, &canonical_header, &canonical_update_retry)?;
+
+    // return new canonical header for next engine task
+    Ok(canonical_header)
+}
+
+/// The number of the output's batches that were already executed and committed.
+///
+/// Outputs that exceed the max blocks per finalization are committed in chunks. If the node
+/// restarts between chunks, the canonical tip is a block from the same output. Blocks store the
+/// output's consensus header hash, round (nonce), and batch index (difficulty), so execution
+/// resumes with the next batch and the remaining blocks keep the same round and batch indices.
+fn executed_batches(parent: &SealedHeader, output: &ConsensusOutput) -> usize {
+    let same_output = parent.parent_beacon_block_root == Some(output.consensus_header_hash())
+        && u64::from(parent.nonce) == output.nonce();
+    if same_output {
+        parent.difficulty.saturating_to::<usize>() + 1
+    } else {
+        0
+    }
+}
+
+/// Make the executed blocks canonical and finalize them.
+///
+/// This is called after the output is fully executed, and at each chunk boundary for outputs that
+/// exceed the max blocks per finalization.
+fn commit_executed_blocks<Provider>(
+    provider: &Provider,
+    output: &ConsensusOutput,
+    canonical_header: &SealedHeader,
+    canonical_update_retry: &CanonicalUpdateRetry,
+) -> EngineResult<()>
+where
+    Provider: StateProviderFactory
+        + ChainSpecProvider<ChainSpec = ChainSpec>
+        + BlockchainTreeEngine
+        + HeaderProvider<Header = ExecHeader>
+        + CanonChainTracker<Header = ExecHeader>,
+{
     // broadcast new base_fee after executing round
     //
     // ensure this value is updated before making the round canonical
@@ -201,7 +337,12 @@ where
     // the canon_state_notifications include every block executed in this round
     //
     // the worker's pool maintenance task subcribes to these events
-    provider.make_canonical(canonical_header.hash())?;
+    //
+    // the blocks are already executed and in the tree, so transient errors are retried
+    let canonical_hash = canonical_header.hash();
+    with_retry(canonical_update_retry, "make_canonical", || {
+        provider.make_canonical(canonical_hash)
+    })?;
 
     // set last executed header as the tracked header
     //
@@ -215,20 +356,149 @@ where
         // this removes canonical blocks from the tree, stores the finalized block number in the
         // database, but still need to set_finalized afterwards for utilization in-memory for
         // components, like RPC
-        provider.finalize_block(canonical_header.number)?;
+        with_retry(canonical_update_retry, "finalize_block", || {
+            provider.finalize_block(canonical_header.number)
+        })?;
         provider.set_finalized(canonical_header.clone());
 
         // update safe block last because this is less time sensitive but still needs to happen
         provider.set_safe(canonical_header.clone());
     } else {
-        finalize_signed_blocks(&provider, &output, &canonical_header)?;
+        finalize_signed_blocks(provider, output, canonical_header, canonical_update_retry)?;
     }
 
-    // return new canonical header for next engine task
-    Ok(canonical_header)
+    Ok(())
+}
+
+/// Validate the output's batches correspond one-to-one with its batch digests.
+///
+/// Batch digests are included in the output's digest, but the batches are not. Each executed block
+/// stores its batch index and digest, which is how a partially executed round is detected on
+/// restart. Batches must be in the same canonical order as the digests so executed blocks match
+/// the committed output.
+fn validate_batch_digests(batches: &[Batch], output: &ConsensusOutput) -> EngineResult<()> {
+    if batches.len() != output.batch_digests.len() {
+        error!(target: "engine", batches=batches.len(), digests=output.batch_digests.len(), "uneven number of batches and batch digests");
+        return Err(TnEngineError::BatchDigestsLength(batches.len(), output.batch_digests.len()));
+    }
+
+    for (index, (batch, expected)) in batches.iter().zip(output.batch_digests.iter()).enumerate() {
+        let digest = batch.digest();
+        if digest != *expected {
+            error!(target: "engine", ?index, ?digest, ?expected, "batch does not match committed digest");
+            return Err(TnEngineError::BatchDigestMismatch(index, *expected, digest));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate the ommers of the block that completes the output.
+///
+/// The final block executed from an output carries one ommer per batch in the canonical order the
+/// batches were executed. Each ommer must match the output's batch at the same index, so the
+/// completing block records exactly which batches the output contained.
+fn validate_ommers(ommers: &[ExecHeader], output: &ConsensusOutput) -> EngineResult<()> {
+    let batches: Vec<_> = output.batches.iter().flatten().collect();
+    if ommers.len() != batches.len() {
+        error!(target: "engine", batches=batches.len(), ommers=ommers.len(), "uneven number of batches and ommers");
+        return Err(TnEngineError::OmmersLength(batches.len(), ommers.len()));
+    }
+
+    for (index, (ommer, batch)) in ommers.iter().zip(batches).enumerate() {
+        if *ommer != batch.ommer_header() {
+            let expected = batch.digest();
+            let found = B256::try_from(ommer.extra_data.as_ref()).unwrap_or_default();
+            error!(target: "engine", ?index, ?expected, ?found, "ommer does not match output's batch");
+            return Err(TnEngineError::OmmerMismatch(index, expected, found));
+        }
+    }
+
+    Ok(())
+}
+
+/// Re-execute a batch on top of its parent without committing the result.
+///
+/// The block env is taken from the block that was executed from this batch if it is stored.
+/// Otherwise, the batch's own values are used with its timestamp converted to seconds using the
+/// network's `timestamp_granularity`. The returned outcome contains the receipts and
+/// state diff so operators can reproduce a block's execution when diagnosing state root disputes.
+pub fn replay_batch_execution<EvmConfig, Provider>(
+    evm_config: &EvmConfig,
+    provider: &Provider,
+    batch: Batch,
+    parent: SealedHeader,
+    evm_hardfork: Option<EvmHardfork>,
+    timestamp_granularity: TimestampGranularity,
+) -> EngineResult<ExecutionOutcome>
+where
+    EvmConfig: ConfigureEvm<Transaction = TransactionSigned>,
+    Provider: StateProviderFactory
+        + ChainSpecProvider<ChainSpec = ChainSpec>
+        + HeaderProvider<Header = ExecHeader>,
+{
+    let batch_digest = batch.digest();
+
+    // use the block produced by the engine for this batch if it exists
+    let executed = provider.header_by_number(parent.number + 1)?.filter(|header| {
+        header.parent_hash == parent.hash() && header.extra_data.as_ref() == batch_digest.as_slice()
+    });
+    debug!(target: "engine", ?batch_digest, parent_hash=?parent.hash(), found_executed_block=executed.is_some(), "replaying batch");
+
+    let attributes = match executed {
+        Some(header) => TNPayloadAttributes {
+            parent_header: parent,
+            beneficiary: header.beneficiary,
+            nonce: header.nonce.into(),
+            batch_index: header.difficulty.to(),
+            timestamp: header.timestamp,
+            batch_digest,
+            consensus_output_digest: header.parent_beacon_block_root.unwrap_or_default(),
+            base_fee_per_gas: header.base_fee_per_gas.unwrap_or_default(),
+            gas_limit: header.gas_limit,
+            mix_hash: header.mix_hash,
+            withdrawals: Withdrawals::new(vec![]),
+        },
+        None => {
+            // execution headers are always in seconds
+            let timestamp = timestamp_granularity.to_secs(batch.timestamp);
+            TNPayloadAttributes {
+                parent_header: parent,
+                beneficiary: batch.beneficiary,
+                nonce: 0,
+                batch_index: 0,
+                timestamp,
+                batch_digest,
+                consensus_output_digest: B256::ZERO,
+                base_fee_per_gas: batch.base_fee_per_gas.unwrap_or_default(),
+                gas_limit: max_batch_gas(timestamp),
+                mix_hash: batch_digest,
+                withdrawals: Withdrawals::new(vec![]),
+            }
+        }
+    };
+    let consensus_header_hash = attributes.consensus_output_digest;
+    let payload = TNPayload::new(attributes).with_evm_hardfork(evm_hardfork);
+
+    // the block is dropped so nothing is inserted into the tree
+    //
+    // ommers don't affect execution
+    let (_, execution_outcome) = build_block_from_batch_payload(
+        evm_config,
+        payload,
+        provider,
+        provider.chain_spec(),
+        batch,
+        consensus_header_hash,
+        vec![],
+    )?;
+
+    Ok(execution_outcome)
 }
 
 /// Construct a canonical block from a worker's block that reached consensus.
+///
+/// The execution outcome is returned with the block so batches can be replayed for debugging.
 #[inline]
 fn build_block_from_batch_payload<EvmConfig, Provider>(
     evm_config: &EvmConfig,
@@ -237,7 +507,8 @@ fn build_block_from_batch_payload<EvmConfig, Provider>(
     chain_spec: Arc<ChainSpec>,
     batch: Batch,
     consensus_header_hash: B256,
-) -> EngineResult<SealedBlockWithSenders>
+    ommers: Vec<ExecHeader>,
+) -> EngineResult<(SealedBlockWithSenders, ExecutionOutcome)>
 where
     EvmConfig: ConfigureEvm<Transaction = TransactionSigned>,
     Provider: StateProviderFactory,
@@ -395,10 +666,11 @@ where
 
     // create the block header
     let transactions_root = calculate_transaction_root(&executed_txs);
+    let ommers_hash = calculate_ommers_root(&ommers);
 
     let header = ExecHeader {
         parent_hash: payload.parent(),
-        ommers_hash: EMPTY_OMMER_ROOT_HASH,
+        ommers_hash,
         beneficiary: block_env.coinbase,
         state_root,
         transactions_root,
@@ -424,16 +696,14 @@ where
     let withdrawals = Some(payload.withdrawals().clone());
 
     // seal the block
-    let block = Block {
-        header,
-        body: BlockBody { transactions: executed_txs, ommers: vec![], withdrawals },
-    };
+    let block =
+        Block { header, body: BlockBody { transactions: executed_txs, ommers, withdrawals } };
 
     let sealed_block = block.seal_slow();
     let sealed_block_with_senders = SealedBlockWithSenders::new(sealed_block, senders)
         .ok_or(TnEngineError::SealBlockWithSenders)?;
 
-    Ok(sealed_block_with_senders)
+    Ok((sealed_block_with_senders, execution_outcome))
 }
 
 /// Extend the canonical tip with one block, despite no blocks from workers are included in the
@@ -525,3 +795,63 @@ where
 
     Ok(sealed_block_with_senders)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::validate_ommers;
+    use crate::error::TnEngineError;
+    use tn_types::{
+        Address, Batch, Certificate, CommittedSubDag, ConsensusOutput, ReputationScores, B256,
+    };
+
+    fn output_with_batches(batches: Vec<Batch>) -> ConsensusOutput {
+        let sub_dag = CommittedSubDag::new(
+            vec![],
+            Certificate::default(),
+            1,
+            ReputationScores::default(),
+            None,
+        );
+        ConsensusOutput {
+            sub_dag: sub_dag.into(),
+            batch_digests: batches.iter().map(|batch| batch.digest()).collect(),
+            batches: vec![batches],
+            beneficiary: Address::ZERO,
+            parent_hash: B256::ZERO,
+            number: 0,
+            extra: B256::ZERO,
+            early_finalize: true,
+        }
+    }
+
+    #[test]
+    fn test_completing_block_ommers_match_batches() {
+        let batches = tn_test_utils::batches(3);
+        let output = output_with_batches(batches.clone());
+        let ommers = output.ommers();
+        assert!(validate_ommers(&ommers, &output).is_ok());
+
+        // reordered ommers
+        let mut reordered = ommers.clone();
+        reordered.swap(0, 1);
+        let res = validate_ommers(&reordered, &output);
+        assert!(matches!(res, Err(TnEngineError::OmmerMismatch(0, expected, found))
+            if expected == batches[0].digest() && found == batches[1].digest()));
+
+        // missing ommer
+        let res = validate_ommers(&ommers[..2], &output);
+        assert!(matches!(res, Err(TnEngineError::OmmersLength(3, 2))));
+
+        // extra ommer
+        let mut extra = ommers.clone();
+        extra.push(tn_test_utils::batches(1)[0].ommer_header());
+        let res = validate_ommers(&extra, &output);
+        assert!(matches!(res, Err(TnEngineError::OmmersLength(3, 4))));
+
+        // an output without batches has no ommers
+        let empty = output_with_batches(vec![]);
+        assert!(validate_ommers(&[], &empty).is_ok());
+        let res = validate_ommers(&ommers[..1], &empty);
+        assert!(matches!(res, Err(TnEngineError::OmmersLength(0, 1))));
+    }
+}
@@ -4,14 +4,28 @@
 //! executed. Block size is measured in bytes and a transaction's max gas limit. The block is sealed
 //! when the pending pool devoid of transactions or the max block size is reached (wei or bytes).
 //!
+//! Local transactions (ie - the faucet) are attempted before external transactions, regardless of
+//! the fee. All transactions are subject to the same gas and byte limits.
+//!
+//! The pool orders transactions with the same effective tip by the time they were received, which
+//! differs between workers. Ties are ordered by transaction hash instead so workers building from
+//! the same pool produce the same batch.
+//!
 //! The mined transactions are returned with the built block so the worker can update the pool.
 
 use crate::error::BatchBuilderError;
-use reth_primitives_traits::InMemorySize as _;
-use reth_transaction_pool::{error::InvalidPoolTransactionError, PoolTransaction, TransactionPool};
+use reth_transaction_pool::{
+    error::InvalidPoolTransactionError, BestTransactions, BestTransactionsAttributes,
+    PoolTransaction, TransactionOrigin, TransactionPool, ValidPoolTransaction,
+};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tn_types::{
-    max_batch_gas, max_batch_size, now, Batch, BatchBuilderArgs, Encodable2718 as _,
-    PendingBlockConfig, TransactionSigned, TransactionTrait as _, TxHash,
+    max_batch_gas, max_batch_size, transaction_size, Address, Batch, BatchBuilderArgs,
+    Encodable2718 as _, PendingBlockConfig, TransactionSigned, TransactionTrait as _, TxHash,
 };
 use tracing::{debug, warn};
 
@@ -32,6 +46,258 @@ pub struct BatchBuilderOutput {
     /// This is less efficient when accounts have lots of transactions in the pending
     /// pool, but this approach is easier to implement in the short term.
     pub(crate) mined_transactions: Vec<TxHash>,
+    /// Transaction selection stats for building this batch.
+    pub(crate) stats: BatchBuildStats,
+}
+
+impl BatchBuilderOutput {
+    /// The batch built from the pending pool.
+    pub fn batch(&self) -> &Batch {
+        &self.batch
+    }
+
+    /// The transaction hashes included in the batch.
+    pub fn mined_transactions(&self) -> &[TxHash] {
+        &self.mined_transactions
+    }
+
+    /// The transaction selection stats for building the batch.
+    pub fn stats(&self) -> &BatchBuildStats {
+        &self.stats
+    }
+}
+
+/// Transaction selection stats from building a batch.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchBuildStats {
+    /// The amount of time the best transactions were held from the pool.
+    ///
+    /// The pool's read lock is held while selecting transactions.
+    pub pool_lock_duration: Duration,
+    /// The number of transactions pulled from the pool.
+    pub txs_considered: usize,
+    /// The number of transactions included in the batch.
+    pub txs_included: usize,
+    /// The number of transactions marked invalid because the batch's gas limit was reached.
+    pub txs_invalid_gas: usize,
+    /// The number of transactions marked invalid because the batch's max size was reached.
+    pub txs_invalid_bytes: usize,
+    /// The total gas limit of all transactions included in the batch.
+    pub gas_used: u64,
+    /// The max gas for all transactions in the batch.
+    pub gas_limit: u64,
+    /// The total size of all transactions included in the batch.
+    pub size_used: usize,
+    /// The max size of all encoded transactions in the batch.
+    pub max_size: usize,
+}
+
+impl BatchBuildStats {
+    /// The fraction of the batch's gas limit used by the included transactions.
+    pub fn gas_utilization(&self) -> f64 {
+        if self.gas_limit == 0 {
+            return 0.0;
+        }
+        self.gas_used as f64 / self.gas_limit as f64
+    }
+
+    /// The fraction of the batch's max size used by the included transactions.
+    pub fn size_utilization(&self) -> f64 {
+        if self.max_size == 0 {
+            return 0.0;
+        }
+        self.size_used as f64 / self.max_size as f64
+    }
+}
+
+/// The transactions selected for the next batch.
+#[derive(Debug)]
+struct SelectedTransactions {
+    /// The max gas for all transactions in the batch.
+    gas_limit: u64,
+    /// The max size of all encoded transactions in the batch.
+    max_size: usize,
+    /// The total gas limit of all selected transactions.
+    total_possible_gas: u64,
+    /// The total size of all selected transactions.
+    total_bytes_size: usize,
+    /// The encoded transactions.
+    transactions: Vec<Vec<u8>>,
+    /// The hashes of the selected transactions.
+    mined_transactions: Vec<TxHash>,
+}
+
+impl SelectedTransactions {
+    /// Create a new instance of Self.
+    fn new(gas_limit: u64, max_size: usize) -> Self {
+        Self {
+            gas_limit,
+            max_size,
+            total_possible_gas: 0,
+            total_bytes_size: 0,
+            transactions: Vec::new(),
+            mined_transactions: Vec::new(),
+        }
+    }
+
+    /// The gas still available in the batch.
+    fn remaining_gas(&self) -> u64 {
+        self.gas_limit.saturating_sub(self.total_possible_gas)
+    }
+
+    /// The size (in bytes) still available in the batch.
+    fn remaining_size(&self) -> usize {
+        self.max_size.saturating_sub(self.total_bytes_size)
+    }
+
+    /// Add the transaction to the batch if there is capacity.
+    ///
+    /// Returns the reason the transaction is invalid for this batch if it doesn't fit.
+    fn try_add<T>(
+        &mut self,
+        pool_tx: &ValidPoolTransaction<T>,
+        stats: &mut BatchBuildStats,
+    ) -> Result<(), InvalidPoolTransactionError>
+    where
+        T: PoolTransaction<Consensus = TransactionSigned>,
+    {
+        // ensure block has capacity (in gas) for this transaction
+        if self.total_possible_gas + pool_tx.gas_limit() > self.gas_limit {
+            debug!(target: "worker::batch_builder", ?pool_tx, "marking tx invalid due to gas constraint");
+            stats.txs_invalid_gas += 1;
+            return Err(InvalidPoolTransactionError::ExceedsGasLimit(
+                pool_tx.gas_limit(),
+                self.gas_limit,
+            ));
+        }
+
+        // convert tx to a signed transaction
+        let tx = pool_tx.to_consensus().into_tx();
+
+        // measure the same encoding peers use to validate the batch
+        let encoded_tx = tx.encoded_2718();
+        let tx_size = transaction_size(&encoded_tx);
+
+        // ensure block has capacity (in bytes) for this transaction
+        if self.total_bytes_size + tx_size > self.max_size {
+            debug!(target: "worker::batch_builder", ?pool_tx, "marking tx invalid due to bytes constraint");
+            stats.txs_invalid_bytes += 1;
+            return Err(InvalidPoolTransactionError::Other(Box::new(
+                BatchBuilderError::MaxBatchSize(tx_size, self.max_size),
+            )));
+        }
+
+        // txs are not executed, so use the gas_limit
+        self.total_possible_gas += tx.gas_limit();
+        self.total_bytes_size += tx_size;
+
+        // append transaction to the list of executed transactions
+        self.mined_transactions.push(*pool_tx.hash());
+        self.transactions.push(encoded_tx);
+        Ok(())
+    }
+}
+
+/// The pool's best transactions with ties ordered by transaction hash.
+///
+/// Consecutive transactions with the same effective tip are buffered and sorted by hash. A sender's
+/// transactions keep their nonce order within the buffer.
+///
+/// The buffer is bounded by the batch's remaining gas and size, so a large group of equal-tip
+/// transactions is not drained from the pool when only a few can be included. Ties are only
+/// ordered by hash within each buffered window.
+struct TieBreakingTransactions<I: BestTransactions> {
+    /// The pool's best transactions.
+    inner: I,
+    /// The base fee used to calculate the effective tip.
+    base_fee: u64,
+    /// The ordered transactions with the same effective tip.
+    buffer: VecDeque<I::Item>,
+    /// The first transaction pulled from the pool with a lower effective tip than the buffer.
+    peeked: Option<I::Item>,
+    /// Senders with a transaction marked invalid.
+    ///
+    /// Buffered descendants of invalid transactions are skipped.
+    invalid_senders: HashSet<Address>,
+}
+
+impl<T, I> TieBreakingTransactions<I>
+where
+    T: PoolTransaction,
+    I: BestTransactions<Item = Arc<ValidPoolTransaction<T>>>,
+{
+    /// Create a new instance of Self.
+    fn new(inner: I, base_fee: u64) -> Self {
+        Self {
+            inner,
+            base_fee,
+            buffer: VecDeque::new(),
+            peeked: None,
+            invalid_senders: HashSet::new(),
+        }
+    }
+
+    /// The next best transaction.
+    ///
+    /// The buffer is refilled with at most `remaining_gas` and `remaining_size` worth of
+    /// transactions.
+    fn next(&mut self, remaining_gas: u64, remaining_size: usize) -> Option<I::Item> {
+        loop {
+            if self.buffer.is_empty() {
+                self.fill_buffer(remaining_gas, remaining_size);
+            }
+            let tx = self.buffer.pop_front()?;
+            if !self.invalid_senders.contains(&tx.sender()) {
+                return Some(tx);
+            }
+        }
+    }
+
+    /// Mark the transaction and its descendants invalid.
+    fn mark_invalid(&mut self, tx: &I::Item, kind: InvalidPoolTransactionError) {
+        self.invalid_senders.insert(tx.sender());
+        self.inner.mark_invalid(tx, kind);
+    }
+
+    /// Buffer the next transactions with the same effective tip.
+    ///
+    /// Transactions are buffered until their total gas limit or encoded size reaches the remaining
+    /// capacity of the batch. The first transaction is always buffered.
+    fn fill_buffer(&mut self, remaining_gas: u64, remaining_size: usize) {
+        let Some(first) = self.peeked.take().or_else(|| self.inner.next()) else {
+            return;
+        };
+        let tip = first.transaction.effective_tip_per_gas(self.base_fee);
+        let mut buffered_gas = first.gas_limit();
+        let mut buffered_size = first.transaction.encoded_length();
+        let mut tied = vec![first];
+        while buffered_gas < remaining_gas && buffered_size < remaining_size {
+            let Some(tx) = self.inner.next() else {
+                break;
+            };
+            if tx.transaction.effective_tip_per_gas(self.base_fee) != tip {
+                self.peeked = Some(tx);
+                break;
+            }
+            buffered_gas = buffered_gas.saturating_add(tx.gas_limit());
+            buffered_size = buffered_size.saturating_add(tx.transaction.encoded_length());
+            tied.push(tx);
+        }
+
+        // sort by hash, then restore each sender's nonce order in the sender's positions
+        tied.sort_by_key(|tx| *tx.hash());
+        let mut by_sender: HashMap<Address, Vec<I::Item>> = HashMap::new();
+        for tx in tied.iter() {
+            by_sender.entry(tx.sender()).or_default().push(tx.clone());
+        }
+        for txs in by_sender.values_mut() {
+            txs.sort_by_key(|tx| std::cmp::Reverse(tx.nonce()));
+        }
+        self.buffer = tied
+            .iter()
+            .filter_map(|tx| by_sender.get_mut(&tx.sender()).and_then(|txs| txs.pop()))
+            .collect();
+    }
 }
 
 /// Construct an TN batch using the best transactions from the pool.
@@ -39,7 +305,17 @@ pub struct BatchBuilderOutput {
 /// Returns the [`BatchBuilderOutput`] and cannot fail. The batch continues to add
 /// transactions to the proposed block until either:
 /// - accumulated transaction gas limit reached (measured by tx.gas_limit())
-/// - max byte size of transactions (measured by tx.size())
+/// - max byte size of transactions (measured by [transaction_size])
+///
+/// Transactions that can't pay the canonical base fee (or blob fee) are excluded when the best
+/// transactions are selected.
+///
+/// Local transactions are attempted first. External transactions are attempted afterwards in the
+/// pool's priority order. Local transactions that depend on an external transaction from the same
+/// sender are attempted with the external transactions to preserve nonce order. External
+/// transactions are only collected until they fill the remaining capacity of the batch.
+///
+/// Transactions from senders in [BatchBuilderArgs::in_flight_senders] are excluded.
 ///
 /// NOTE: it's possible to under utilize resources if users submit transactions
 /// with very high gas limits. It's impossible to know the amount of gas a transaction
@@ -50,83 +326,130 @@ where
     P: TransactionPool,
     P::Transaction: PoolTransaction<Consensus = TransactionSigned>,
 {
-    let BatchBuilderArgs { pool, batch_config } = args;
+    let BatchBuilderArgs { pool, batch_config, in_flight_senders, timestamp_granularity, epoch } =
+        args;
     let gas_limit = max_batch_gas(batch_config.parent_info.tip.timestamp);
     let max_size = max_batch_size(batch_config.parent_info.tip.timestamp);
     let PendingBlockConfig { beneficiary, parent_info } = batch_config;
 
     // NOTE: this obtains a `read` lock on the tx pool
-    // pull best transactions and rely on watch channel to ensure basefee is current
-    let mut best_txs = pool.best_transactions();
+    let lock_start = Instant::now();
+    let mut stats = BatchBuildStats::default();
+
+    // pull best transactions that can pay the canonical base fee and blob fee for the next block
+    let best_attributes = BestTransactionsAttributes::new(
+        parent_info.pending_block_base_fee,
+        parent_info.pending_block_blob_fee.map(|fee| fee as u64),
+    );
+    let mut best_txs = TieBreakingTransactions::new(
+        pool.best_transactions_with_attributes(best_attributes),
+        parent_info.pending_block_base_fee,
+    );
 
     // NOTE: batches always build off the latest finalized block
     let parent_hash = parent_info.tip.hash();
 
-    // collect data for successful transactions
-    // let mut sum_blob_gas_used = 0;
-    let mut total_bytes_size = 0;
-    let mut total_possible_gas = 0;
-    let mut transactions = Vec::new();
-    let mut mined_transactions = Vec::new();
+    // local transactions are attempted before external transactions
+    let has_local = !pool.get_transactions_by_origin(TransactionOrigin::Local).is_empty();
+    let mut deferred = Vec::new();
+    let mut deferred_senders = HashSet::new();
+    let mut deferred_gas = 0u64;
+    let mut deferred_size = 0usize;
+    let mut selected = SelectedTransactions::new(gas_limit, max_size);
 
     // begin loop through sorted "best" transactions in pending pool
     // and execute them to build the block
-    while let Some(pool_tx) = best_txs.next() {
-        // filter best transactions against Arc<hashset<TxHash>>
+    while let Some(pool_tx) = best_txs.next(selected.remaining_gas(), selected.remaining_size()) {
+        stats.txs_considered += 1;
 
-        // ensure block has capacity (in gas) for this transaction
-        if total_possible_gas + pool_tx.gas_limit() > gas_limit {
-            // the tx could exceed max gas limit for the block
-            // marking as invalid within the context of the `BestTransactions` pulled in this
-            // current iteration  all dependents for this transaction are now considered invalid
-            // before continuing loop
-            best_txs.mark_invalid(
-                &pool_tx,
-                InvalidPoolTransactionError::ExceedsGasLimit(pool_tx.gas_limit(), gas_limit),
-            );
-            debug!(target: "worker::batch_builder", ?pool_tx, "marking tx invalid due to gas constraint");
+        // skip senders with transactions in batches still waiting for quorum
+        //
+        // the batches may be executed in any order, so descendants must wait for the next batch
+        if in_flight_senders.contains(&pool_tx.sender()) {
+            let kind = InvalidPoolTransactionError::Other(Box::new(
+                BatchBuilderError::SenderInFlight(pool_tx.sender()),
+            ));
+            best_txs.mark_invalid(&pool_tx, kind);
             continue;
         }
 
-        // convert tx to a signed transaction
-        //
-        // NOTE: `ValidPoolTransaction::size()` is private
-        let tx = pool_tx.to_consensus();
+        // defer external transactions and any descendants from the same sender
+        if has_local && (!pool_tx.origin.is_local() || deferred_senders.contains(&pool_tx.sender()))
+        {
+            // stop collecting once the deferred transactions fill the remaining capacity
+            //
+            // the remaining capacity only shrinks as local transactions are added, so these
+            // transactions can't fit in the batch
+            if deferred_gas >= selected.remaining_gas()
+                || deferred_size >= selected.remaining_size()
+            {
+                let kind = InvalidPoolTransactionError::ExceedsGasLimit(
+                    pool_tx.gas_limit(),
+                    selected.remaining_gas(),
+                );
+                best_txs.mark_invalid(&pool_tx, kind);
+                continue;
+            }
 
-        // ensure block has capacity (in bytes) for this transaction
-        if total_bytes_size + tx.size() > max_size {
-            // the tx could exceed max gas limit for the block
+            deferred_gas = deferred_gas.saturating_add(pool_tx.gas_limit());
+            deferred_size = deferred_size.saturating_add(pool_tx.transaction.encoded_length());
+            deferred_senders.insert(pool_tx.sender());
+            deferred.push(pool_tx);
+            continue;
+        }
+
+        if let Err(kind) = selected.try_add(&pool_tx, &mut stats) {
+            // the tx could exceed max gas limit or size for the block
             // marking as invalid within the context of the `BestTransactions` pulled in this
             // current iteration  all dependents for this transaction are now considered invalid
             // before continuing loop
-            best_txs.mark_invalid(
-                &pool_tx,
-                InvalidPoolTransactionError::Other(Box::new(BatchBuilderError::MaxBatchSize(
-                    tx.size(),
-                    max_size,
-                ))),
-            );
-            debug!(target: "worker::batch_builder", ?pool_tx, "marking tx invalid due to bytes constraint");
-            continue;
+            best_txs.mark_invalid(&pool_tx, kind);
         }
+    }
 
-        // txs are not executed, so use the gas_limit
-        total_possible_gas += tx.gas_limit();
-        total_bytes_size += tx.size();
+    // attempt external transactions in priority order
+    //
+    // deferred transactions are no longer tracked by `BestTransactions`, so skip dependents of
+    // invalid transactions here
+    let mut invalid_senders = HashSet::new();
+    for pool_tx in deferred {
+        if invalid_senders.contains(&pool_tx.sender()) {
+            continue;
+        }
 
-        // append transaction to the list of executed transactions
-        mined_transactions.push(*pool_tx.hash());
-        transactions.push(tx.into_tx().encoded_2718());
+        if selected.try_add(&pool_tx, &mut stats).is_err() {
+            invalid_senders.insert(pool_tx.sender());
+        }
     }
 
-    // sometimes batch are produced too quickly in certain configs (<1s diff)
-    // resulting in batch timestamp == parent timestamp
+    // release the pool
+    drop(best_txs);
+    stats.pool_lock_duration = lock_start.elapsed();
+    let SelectedTransactions {
+        transactions,
+        mined_transactions,
+        total_possible_gas,
+        total_bytes_size,
+        ..
+    } = selected;
+    stats.txs_included = mined_transactions.len();
+    stats.gas_used = total_possible_gas;
+    stats.gas_limit = gas_limit;
+    stats.size_used = total_bytes_size;
+    stats.max_size = max_size;
+
+    // sometimes batch are produced too quickly in certain configs (<1s diff with second
+    // granularity) resulting in batch timestamp == parent timestamp
+    //
+    // the parent's timestamp is always in seconds, so millisecond granularity only offsets the
+    // batch if it is built in the same millisecond the parent's second started
     //
     // TODO: check for this error at the quorum waiter level?
-    let mut timestamp = now();
-    if timestamp == parent_info.tip.timestamp {
-        warn!(target: "worker::batch_builder", "new block timestamp same as parent - setting offset by 1sec");
-        timestamp = parent_info.tip.timestamp + 1;
+    let parent_timestamp = timestamp_granularity.from_secs(parent_info.tip.timestamp);
+    let mut timestamp = timestamp_granularity.now();
+    if timestamp <= parent_timestamp {
+        warn!(target: "worker::batch_builder", ?timestamp_granularity, "new block timestamp same as parent - setting offset by 1");
+        timestamp = parent_timestamp + 1;
     }
 
     // batch
@@ -136,9 +459,10 @@ where
         beneficiary,
         timestamp,
         base_fee_per_gas: Some(parent_info.pending_block_base_fee),
+        epoch,
         received_at: None,
     };
 
     // return output
-    BatchBuilderOutput { batch, mined_transactions }
+    BatchBuilderOutput { batch, mined_transactions, stats }
 }
@@ -2,6 +2,7 @@
 
 use reth_errors::{CanonicalError, ProviderError, RethError};
 use reth_transaction_pool::error::PoolTransactionError;
+use tn_types::Address;
 use tokio::sync::{mpsc, oneshot};
 
 /// Result alias for [`TNEngineError`].
@@ -40,6 +41,19 @@ pub enum BatchBuilderError {
         "The transaction was not included becuase it would exceed the max batch size. Tx size: {0} bytes - max size: {1} bytes."
     )]
     MaxBatchSize(usize, usize),
+    /// The transaction's priority fee is below the worker's minimum.
+    #[error(
+        "The transaction was rejected because the priority fee is below the minimum priority fee. Tx priority fee: {0} wei - min priority fee: {1} wei."
+    )]
+    MinPriorityFee(u128, u128),
+    /// The sender has transactions in a batch that is still waiting for quorum.
+    #[error(
+        "The transaction was not included because the sender has transactions in a batch waiting for quorum. Sender: {0}"
+    )]
+    SenderInFlight(Address),
+    /// The transaction was rejected by the operator's validation hook.
+    #[error("The transaction was rejected by the validation hook: {0}")]
+    ValidationHook(String),
 }
 
 impl From<oneshot::error::RecvError> for BatchBuilderError {
@@ -22,36 +22,115 @@
 #![deny(unused_must_use, rust_2018_idioms)]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
-pub use batch::{build_batch, BatchBuilderOutput};
+pub use crate::metrics::BatchBuilderMetrics;
+pub use batch::{build_batch, BatchBuildStats, BatchBuilderOutput};
 use error::{BatchBuilderError, BatchBuilderResult};
 use futures_util::{FutureExt, StreamExt};
+pub use pool::{
+    apply_included_tx_propagation, apply_pending_pool_limits, apply_pending_tx_listener_capacity,
+    evict_expired_queued_transactions, HookedTransactionValidator, MinPriorityFeeValidator,
+    TransactionValidationHook,
+};
 use reth_execution_types::ChangedAccount;
 use reth_provider::{CanonStateNotification, CanonStateNotificationStream, Chain};
 use reth_transaction_pool::{
-    CanonicalStateUpdate, PoolTransaction, PoolUpdateKind, TransactionPool, TransactionPoolExt,
+    CanonicalStateUpdate, PoolTransaction, PoolUpdateKind, TransactionListenerKind,
+    TransactionPool, TransactionPoolExt,
 };
 use std::{
+    collections::HashSet,
     future::Future,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
     time::Duration,
 };
+use tn_config::IncludedTxPropagation;
 use tn_types::{
-    error::BlockSealError, Address, BatchBuilderArgs, BatchSender, LastCanonicalUpdate,
-    PendingBlockConfig, TransactionSigned, TxHash, MIN_PROTOCOL_BASE_FEE,
+    error::BlockSealError, Address, BatchBuilderArgs, BatchSender, BlockNumber, Epoch,
+    LastCanonicalUpdate, PendingBlockConfig, TimestampGranularity, TransactionSigned, TxHash,
+    WorkerId, MIN_PROTOCOL_BASE_FEE,
+};
+use tokio::{
+    sync::{mpsc, oneshot, watch},
+    time::{Interval, MissedTickBehavior},
 };
-use tokio::{sync::oneshot, time::Interval};
-use tracing::{debug, error, trace, warn};
+use tokio_stream::wrappers::WatchStream;
+use tracing::{debug, error, instrument, trace, warn, Instrument as _, Span};
 
 mod batch;
 mod error;
+mod metrics;
+mod pool;
 #[cfg(feature = "test-utils")]
 pub mod test_utils;
 
+/// The max amount of time between checks for expired queued transactions.
+const MAX_QUEUED_EXPIRY_INTERVAL: Duration = Duration::from_secs(60);
+
 /// Type alias for the blocking task that locks the tx pool and builds the next batch.
 type BuildResult = oneshot::Receiver<BatchBuilderResult<Vec<TxHash>>>;
 
+/// Control messages for pausing and resuming batch production.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BatchBuilderControl {
+    /// Build batches from pending transactions.
+    #[default]
+    Resume,
+    /// Stop building new batches.
+    ///
+    /// Canonical updates are still applied to the pool and any batch already being proposed
+    /// completes.
+    Pause,
+}
+
+/// Handle to pause and resume a [BatchBuilder].
+///
+/// The handle remains valid after the batch builder is spawned.
+#[derive(Clone, Debug)]
+pub struct BatchBuilderHandle {
+    /// The sending side of the batch builder's control channel.
+    control: Arc<watch::Sender<BatchBuilderControl>>,
+    /// The block number of the latest canonical tip applied by the batch builder.
+    tip_number: watch::Receiver<BlockNumber>,
+}
+
+impl BatchBuilderHandle {
+    /// Stop building new batches.
+    pub fn pause(&self) {
+        self.control.send_replace(BatchBuilderControl::Pause);
+    }
+
+    /// Resume building batches.
+    pub fn resume(&self) {
+        self.control.send_replace(BatchBuilderControl::Resume);
+    }
+
+    /// Bool indicating if the batch builder is paused.
+    pub fn is_paused(&self) -> bool {
+        *self.control.borrow() == BatchBuilderControl::Pause
+    }
+
+    /// The block number the next built batch will carry.
+    ///
+    /// Batches are built on the latest canonical tip, so this advances with every canonical
+    /// update from the engine.
+    pub fn next_block_number(&self) -> BlockNumber {
+        self.tip_number.borrow().saturating_add(1)
+    }
+}
+
+/// A batch that was built and is waiting for quorum.
+#[derive(Debug)]
+struct PendingBuild {
+    /// The receiver for the mined transactions once the batch reaches quorum.
+    result: BuildResult,
+    /// The senders with transactions in the batch.
+    ///
+    /// Later builds exclude these senders until the batch completes.
+    senders: HashSet<Address>,
+}
+
 /// The type that builds blocks for workers to propose.
 ///
 /// This is a future that:
@@ -61,15 +140,29 @@ type BuildResult = oneshot::Receiver<BatchBuilderResult<Vec<TxHash>>>;
 /// -
 #[derive(Debug)]
 pub struct BatchBuilder<BT, Pool> {
-    /// Single active future that executes consensus output on a blocking thread and then returns
-    /// the result through a oneshot channel.
-    pending_task: Option<BuildResult>,
+    /// The batches that are waiting for quorum.
+    ///
+    /// Each build returns the mined transactions through a oneshot channel once the batch reaches
+    /// quorum. The number of builds is limited by `max_concurrent_builds`.
+    pending_tasks: Vec<PendingBuild>,
+    /// The max number of batches that can wait for quorum at the same time.
+    ///
+    /// Defaults to `1`, so batches are proposed one at a time.
+    max_concurrent_builds: usize,
     /// The type used to query both the database and the blockchain tree.
     ///
     /// TODO: leaving this for now to prevent generics refactor
     _blockchain: BT,
     /// The transaction pool with pending transactions.
     pool: Pool,
+    /// Hashes of transactions added to the pending pool.
+    ///
+    /// This channel only wakes the task when new transactions are pending - the hashes are
+    /// ignored and batches are always built from the pool. The capacity is set by the pool's
+    /// `pending_tx_listener_buffer_size`. The pool never blocks on this channel, so hashes are
+    /// dropped while the channel is full and the task relies on `max_delay_interval` to
+    /// check the pool again.
+    pending_tx_hashes: mpsc::Receiver<TxHash>,
     /// Canonical state changes from the engine.
     ///
     /// Notifications are sent on this stream after each round of consensus
@@ -97,8 +190,51 @@ pub struct BatchBuilder<BT, Pool> {
     /// Maximum amount of time to wait before querying block builds.
     ///
     /// This interval wakes the task periodically to check on the progress of the latest built
-    /// block and the pending transaction pool.
+    /// block and the pending transaction pool. This is the fallback if pending transaction
+    /// notifications are dropped.
     max_delay_interval: Interval,
+    /// Metrics for polling and build behavior.
+    metrics: Arc<BatchBuilderMetrics>,
+    /// The sending side of the control channel used to create [BatchBuilderHandle]s.
+    control: Arc<watch::Sender<BatchBuilderControl>>,
+    /// Pause and resume messages from [BatchBuilderHandle]s.
+    control_stream: WatchStream<BatchBuilderControl>,
+    /// Bool indicating if building new batches is paused.
+    paused: bool,
+    /// The block number of the latest canonical tip shared with [BatchBuilderHandle]s.
+    tip_number: watch::Sender<BlockNumber>,
+    /// The unit for batch timestamps.
+    timestamp_granularity: TimestampGranularity,
+    /// The epoch batches are built in.
+    ///
+    /// Peers reject batches from other epochs.
+    epoch: Epoch,
+    /// The id of the worker this batch builder proposes batches for.
+    ///
+    /// Included in tracing spans to correlate logs across tasks.
+    worker_id: WorkerId,
+    /// The minimum number of connected peers before building batches.
+    ///
+    /// Batches built before the worker can reach quorum are wasted work. Defaults to `0`, so
+    /// building is never deferred.
+    min_peers_to_build: usize,
+    /// The latest number of peers connected to the worker's network.
+    connected_peers: usize,
+    /// The max amount of time a queued transaction stays in the pool.
+    ///
+    /// Queued transactions are never built into batches, so transactions with nonce gaps that
+    /// are never filled are evicted after this lifetime. `None` never evicts queued transactions.
+    max_queued_tx_lifetime: Option<Duration>,
+    /// The interval to check for expired queued transactions.
+    ///
+    /// This is `None` unless a `max_queued_tx_lifetime` is configured.
+    queued_expiry_interval: Option<Interval>,
+    /// Updates to the number of peers connected to the worker's network.
+    ///
+    /// This is `None` unless a `min_peers_to_build` is configured.
+    connected_peers_stream: Option<WatchStream<usize>>,
+    /// The policy for propagating transactions included in a batch that is waiting for quorum.
+    included_tx_propagation: IncludedTxPropagation,
 }
 
 impl<BT, Pool> BatchBuilder<BT, Pool>
@@ -117,15 +253,176 @@ where
         max_delay: Duration,
     ) -> Self {
         let max_delay_interval = tokio::time::interval(max_delay);
+        let pending_tx_hashes =
+            pool.pending_transactions_listener_for(TransactionListenerKind::All);
+        let (control, control_rx) = watch::channel(BatchBuilderControl::default());
+        let (tip_number, _) = watch::channel(latest_canon_state.tip.header.number);
         Self {
-            pending_task: None,
+            pending_tasks: Vec::new(),
+            max_concurrent_builds: 1,
             _blockchain,
             pool,
+            pending_tx_hashes,
             canonical_state_stream,
             latest_canon_state,
             to_worker,
             address,
             max_delay_interval,
+            metrics: Arc::new(BatchBuilderMetrics::default()),
+            control: Arc::new(control),
+            control_stream: WatchStream::from_changes(control_rx),
+            paused: false,
+            tip_number,
+            timestamp_granularity: TimestampGranularity::default(),
+            epoch: 0,
+            worker_id: 0,
+            min_peers_to_build: 0,
+            connected_peers: 0,
+            max_queued_tx_lifetime: None,
+            queued_expiry_interval: None,
+            connected_peers_stream: None,
+            included_tx_propagation: IncludedTxPropagation::default(),
+        }
+    }
+
+    /// Set the max number of batches that can wait for quorum at the same time.
+    ///
+    /// Concurrent batches never include transactions from the same sender. The minimum is `1`.
+    pub fn with_max_concurrent_builds(mut self, max_concurrent_builds: usize) -> Self {
+        self.max_concurrent_builds = max_concurrent_builds.max(1);
+        self
+    }
+
+    /// Set the unit for batch timestamps.
+    ///
+    /// This must match the granularity peers use to validate batches.
+    pub fn with_timestamp_granularity(
+        mut self,
+        timestamp_granularity: TimestampGranularity,
+    ) -> Self {
+        self.timestamp_granularity = timestamp_granularity;
+        self
+    }
+
+    /// Set the epoch batches are built in.
+    ///
+    /// This must match the epoch peers use to validate batches.
+    pub fn with_epoch(mut self, epoch: Epoch) -> Self {
+        self.epoch = epoch;
+        self
+    }
+
+    /// Set the id of the worker this batch builder proposes batches for.
+    ///
+    /// The id is only used for tracing.
+    pub fn with_worker_id(mut self, worker_id: WorkerId) -> Self {
+        self.worker_id = worker_id;
+        self
+    }
+
+    /// Defer building batches until at least `min_peers_to_build` peers are connected.
+    ///
+    /// The `connected_peers` watch channel reports the number of peers connected to the worker's
+    /// network. Batches that are waiting for quorum are still polled while building is deferred.
+    pub fn with_min_peers_to_build(
+        mut self,
+        min_peers_to_build: usize,
+        connected_peers: watch::Receiver<usize>,
+    ) -> Self {
+        self.min_peers_to_build = min_peers_to_build;
+        self.connected_peers = *connected_peers.borrow();
+        self.connected_peers_stream = Some(WatchStream::new(connected_peers));
+        self
+    }
+
+    /// Evict queued transactions that stay in the pool longer than `max_queued_tx_lifetime`.
+    ///
+    /// The pool is checked for expired transactions at least once a minute. A lifetime of zero is
+    /// ignored and queued transactions are never evicted.
+    pub fn with_max_queued_tx_lifetime(mut self, max_queued_tx_lifetime: Duration) -> Self {
+        if max_queued_tx_lifetime.is_zero() {
+            warn!(target: "worker::batch_builder", "ignoring max queued transaction lifetime of zero");
+            return self;
+        }
+
+        let period = max_queued_tx_lifetime.min(MAX_QUEUED_EXPIRY_INTERVAL);
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        self.max_queued_tx_lifetime = Some(max_queued_tx_lifetime);
+        self.queued_expiry_interval = Some(interval);
+        self
+    }
+
+    /// Set the policy for propagating transactions included in a batch.
+    ///
+    /// The policy is applied to the pool after each successful build while the batch waits for
+    /// quorum.
+    pub fn with_included_tx_propagation(
+        mut self,
+        included_tx_propagation: IncludedTxPropagation,
+    ) -> Self {
+        self.included_tx_propagation = included_tx_propagation;
+        self
+    }
+
+    /// Remove queued transactions that exceeded the max lifetime.
+    fn evict_expired_queued_transactions(&self) {
+        if let Some(max_lifetime) = self.max_queued_tx_lifetime {
+            let expired = evict_expired_queued_transactions(&self.pool, max_lifetime);
+            self.metrics.queued_txs_expired.inc_by(expired.len() as u64);
+        }
+    }
+
+    /// The block number the next built batch will carry.
+    fn next_block_number(&self) -> BlockNumber {
+        self.latest_canon_state.tip.header.number.saturating_add(1)
+    }
+
+    /// Bool indicating if enough peers are connected to build the next batch.
+    fn enough_peers_to_build(&self) -> bool {
+        self.connected_peers >= self.min_peers_to_build
+    }
+
+    /// Return a handle to pause and resume this batch builder.
+    pub fn handle(&self) -> BatchBuilderHandle {
+        BatchBuilderHandle {
+            control: self.control.clone(),
+            tip_number: self.tip_number.subscribe(),
+        }
+    }
+
+    /// Set the metrics for this batch builder.
+    ///
+    /// Metrics are registered once per worker, so a batch builder restarted for a new epoch keeps
+    /// reporting to the same metrics.
+    pub fn with_metrics(mut self, metrics: Arc<BatchBuilderMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Return the metrics for this batch builder.
+    pub fn metrics(&self) -> &Arc<BatchBuilderMetrics> {
+        &self.metrics
+    }
+
+    /// This method is called for every notification from the canonical state stream.
+    ///
+    /// TN reorgs are impossible, but a reorg notification must not crash the worker. The error is
+    /// logged and the reorg's new chain is applied as a commit.
+    fn process_canon_notification(&mut self, notification: CanonStateNotification) {
+        match notification {
+            CanonStateNotification::Commit { new } => {
+                self.process_canon_state_update(new);
+            }
+            CanonStateNotification::Reorg { old, new } => {
+                error!(
+                    target: "block-builder",
+                    old_tip = ?old.tip().block.hash(),
+                    new_tip = ?new.tip().block.hash(),
+                    "received canonical reorg - TN reorgs are impossible! applying new chain as commit"
+                );
+                self.process_canon_state_update(new);
+            }
         }
     }
 
@@ -162,13 +459,18 @@ where
         // for now, always use lowest base fee possible
         let pending_block_base_fee = MIN_PROTOCOL_BASE_FEE;
 
+        // calculate the blob fee for the next round from the tip's excess blob gas
+        //
+        // NOTE: this is `None` until blobs are supported (pre-cancun headers)
+        let pending_block_blob_fee = tip.block.header.next_block_blob_fee();
+
         // Canonical update
         let update = CanonicalStateUpdate {
-            new_tip: &tip.block,          // finalized block
-            pending_block_base_fee,       // current base fee for worker (network-wide)
-            pending_block_blob_fee: None, // current blob fee for worker (network-wide)
-            changed_accounts,             // entire round of consensus
-            mined_transactions,           // entire round of consensus
+            new_tip: &tip.block,    // finalized block
+            pending_block_base_fee, // current base fee for worker (network-wide)
+            pending_block_blob_fee, // current blob fee for worker (network-wide)
+            changed_accounts,       // entire round of consensus
+            mined_transactions,     // entire round of consensus
             update_kind: PoolUpdateKind::Commit,
         };
 
@@ -176,47 +478,96 @@ where
         let latest = LastCanonicalUpdate {
             tip: tip.block.clone(),
             pending_block_base_fee,
-            pending_block_blob_fee: None,
+            pending_block_blob_fee,
         };
 
         debug!(target: "block-builder", ?update, ?latest, "applying update to txpool");
 
         // track canon update so worker updates don't overwrite the tip or base fees
+        self.tip_number.send_replace(latest.tip.header.number);
         self.latest_canon_state = latest;
 
         // sync fn so self will block until all pool updates are complete
         self.pool.on_canonical_state_change(update);
     }
 
-    /// Spawns a task to build the batch and proposer to peers.
+    /// The arguments for building the next batch off the latest canonical state.
+    ///
+    /// Senders with transactions in batches waiting for quorum are excluded.
+    fn next_build_args(&self) -> BatchBuilderArgs<Pool> {
+        let config = PendingBlockConfig::new(self.address, self.latest_canon_state.clone());
+        let in_flight_senders =
+            self.pending_tasks.iter().flat_map(|build| build.senders.iter().copied()).collect();
+        BatchBuilderArgs::new(self.pool.clone(), config)
+            .with_in_flight_senders(in_flight_senders)
+            .with_timestamp_granularity(self.timestamp_granularity)
+            .with_epoch(self.epoch)
+    }
+
+    /// Build the next batch without proposing it to the worker.
+    ///
+    /// This uses the same transaction selection as the batches this builder proposes, but the
+    /// batch is not sent to the worker and the transaction pool is not updated. Useful for
+    /// inspecting the composition of the next batch (ie - fee estimation).
+    pub fn build_preview(&self) -> BatchBuilderOutput {
+        build_batch(self.next_build_args())
+    }
+
+    /// Build the next batch and spawn a task to propose it to peers.
     ///
-    /// This approach allows the block builder to yield back to the runtime while mining blocks.
+    /// The batch is built before the task is spawned so the next build excludes its senders. This
+    /// approach allows the block builder to yield back to the runtime while waiting for quorum.
     ///
     /// The task performs the following actions:
-    /// - create a block
     /// - send the block to worker's block proposer
     /// - wait for ack that quorum was reached
     /// - convert result to fatal/non-fatal
     /// - return result
     ///
-    /// Workers only propose one block at a time.
-    fn spawn_execution_task(&self) -> BuildResult {
-        let pool = self.pool.clone();
+    /// Returns `None` without proposing if other batches are waiting for quorum and none of the
+    /// remaining transactions can be included.
+    #[instrument(
+        level = "info",
+        name = "build_batch",
+        skip_all,
+        fields(worker_id = self.worker_id, next_block_number = self.next_block_number())
+    )]
+    fn spawn_execution_task(&self) -> Option<PendingBuild> {
         let to_worker = self.to_worker.clone();
 
-        // configure params for next block to build
-        let config = PendingBlockConfig::new(self.address, self.latest_canon_state.clone());
-        let build_args = BatchBuilderArgs::new(pool.clone(), config);
+        // build the next batch with the pool's read lock
+        let BatchBuilderOutput { batch, mined_transactions, stats } =
+            build_batch(self.next_build_args());
+        if mined_transactions.is_empty() && !self.pending_tasks.is_empty() {
+            return None;
+        }
+        self.metrics.record_batch_txs(mined_transactions.len());
+        self.metrics.record_build_stats(&stats);
+
+        let senders =
+            self.pool.get_all(mined_transactions.clone()).iter().map(|tx| tx.sender()).collect();
         let (result, done) = oneshot::channel();
+        let pool = self.pool.clone();
+        let included_tx_propagation = self.included_tx_propagation;
+
+        // spawn task to forward to worker
+        //
+        // the task inherits this span so logs carry the worker id and block number
+        let task = async move {
+            // apply the propagation policy to the included transactions
+            //
+            // the policy is applied before the batch is forwarded so the pool update for mined
+            // transactions never races with re-adding suppressed transactions
+            apply_included_tx_propagation(
+                &pool,
+                included_tx_propagation,
+                mined_transactions.clone(),
+            )
+            .await;
 
-        // spawn block building task and forward to worker
-        tokio::spawn(async move {
             // ack once worker reaches quorum
             let (ack, rx) = oneshot::channel();
 
-            // this is safe to call without a semaphore bc it's held as a single `Option`
-            let BatchBuilderOutput { batch, mined_transactions } = build_batch(build_args);
-
             // forward to worker and wait for ack that quorum was reached
             if let Err(e) = to_worker.send((batch.seal_slow(), ack)).await {
                 error!(target: "worker::batch_builder", ?e, "failed to send next batch to worker");
@@ -268,17 +619,22 @@ where
                     }
                 }
             }
-        });
+        };
+        tokio::spawn(task.instrument(Span::current()));
 
         // return oneshot channel for receiving completion status
-        done
+        Some(PendingBuild { result: done, senders })
     }
 }
 
 /// The [BatchBuilder] is a future that loops through the following:
 /// - check/apply canonical state changes that affect the next build
+/// - drain pending transaction notifications
+/// - apply pause/resume messages from any [BatchBuilderHandle]
+/// - track the number of connected peers if `min_peers_to_build` is configured
+/// - build the next block if pending transactions are available and fewer than
+///   `max_concurrent_builds` blocks are waiting for quorum
 /// - poll any pending block building tasks
-/// - otherwise, build next block if pending transactions are available
 ///
 /// If a task completes, the loop continues to poll for any new output from consensus then begins
 /// executing the next task.
@@ -293,8 +649,15 @@ where
 {
     type Output = BatchBuilderResult<()>;
 
+    #[instrument(
+        level = "info",
+        name = "batch_builder",
+        skip_all,
+        fields(worker_id = self.worker_id, next_block_number = self.next_block_number())
+    )]
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
+        this.metrics.builder_poll_count.inc();
 
         // loop when a successful block is built
         loop {
@@ -309,16 +672,68 @@ where
                 // poll canon updates stream and update pool `.on_canon_update`
                 //
                 // maintenance task will handle worker's pending block update
-                match canon_update {
-                    CanonStateNotification::Commit { new } => {
-                        this.process_canon_state_update(new);
-                    }
-                    _ => unreachable!("TN reorgs are impossible"),
+                this.process_canon_notification(canon_update);
+                Span::current().record("next_block_number", this.next_block_number());
+            }
+
+            // evict queued transactions that exceeded the max lifetime
+            //
+            // poll until pending so the interval wakes the task for the next check
+            if let Some(interval) = this.queued_expiry_interval.as_mut() {
+                let mut expired = false;
+                while interval.poll_tick(cx).is_ready() {
+                    expired = true;
+                }
+                if expired {
+                    this.evict_expired_queued_transactions();
+                }
+            }
+
+            // drain pending transaction notifications so the task wakes for the next one
+            //
+            // the pool is checked directly below, so the hashes are not needed
+            while let Poll::Ready(Some(_tx_hash)) = this.pending_tx_hashes.poll_recv(cx) {}
+
+            // apply the latest pause/resume message
+            while let Poll::Ready(Some(control)) = this.control_stream.poll_next_unpin(cx) {
+                debug!(target: "block-builder", ?control, "received control message");
+                this.paused = control == BatchBuilderControl::Pause;
+            }
+
+            // apply the latest connected peer count
+            if let Some(stream) = this.connected_peers_stream.as_mut() {
+                while let Poll::Ready(Some(connected_peers)) = stream.poll_next_unpin(cx) {
+                    trace!(target: "block-builder", ?connected_peers, "connected peers updated");
+                    this.connected_peers = connected_peers;
                 }
             }
 
-            // only propose one block at a time
-            if this.pending_task.is_none() {
+            // don't build the next batch while paused
+            //
+            // canon updates are still applied above and a batch that is already being proposed
+            // is still polled below - the control stream wakes the task when building resumes
+            if this.paused && this.pending_tasks.is_empty() {
+                break;
+            }
+
+            // don't build the next batch until enough peers are connected to reach quorum
+            //
+            // the connected peers stream wakes the task when the peer count changes
+            let enough_peers = this.enough_peers_to_build();
+            if !enough_peers && this.pending_tasks.is_empty() {
+                debug!(
+                    target: "block-builder",
+                    connected_peers = this.connected_peers,
+                    min_peers_to_build = this.min_peers_to_build,
+                    "deferring batch build until enough peers are connected"
+                );
+                this.metrics.builds_skipped_insufficient_peers.inc();
+                break;
+            }
+
+            // only propose up to the max concurrent blocks at a time
+            if !this.paused && enough_peers && this.pending_tasks.len() < this.max_concurrent_builds
+            {
                 // TODO: is there a more efficient approach? only need pending pool stats
                 // create upstream PR for reth?
                 //
@@ -327,83 +742,90 @@ where
                 // considered using: pool.pool_size().pending
                 // but that calculates size for all sub-pools
                 if this.pool.pending_transactions().is_empty() {
-                    // reset interval to wake up after some time
-                    //
-                    // only need to reset here if there is no pending block being built
-                    this.max_delay_interval.reset();
+                    if this.pending_tasks.is_empty() {
+                        this.metrics.builds_skipped_empty_pool.inc();
 
-                    // tick interval to ensure it advances
-                    let _ = this.max_delay_interval.poll_tick(cx);
+                        // reset interval to wake up after some time
+                        //
+                        // only need to reset here if there is no pending block being built
+                        this.max_delay_interval.reset();
 
-                    // nothing pending
-                    break;
-                }
+                        // tick interval to ensure it advances
+                        let _ = this.max_delay_interval.poll_tick(cx);
 
-                // start building the next block
-                this.pending_task = Some(this.spawn_execution_task());
+                        // nothing pending
+                        break;
+                    }
+                } else if let Some(build) = this.spawn_execution_task() {
+                    // start building the next block
+                    this.metrics.builds_triggered.inc();
+                    this.pending_tasks.push(build);
 
-                // don't break so pending_task receiver gets polled
+                    // loop again to start another build or poll the pending builds
+                    continue;
+                }
             }
 
-            // poll receiver that returns mined transactions once the batch reaches quorum
-            if let Some(mut receiver) = this.pending_task.take() {
-                // poll here so waker is notified when ack received
-                match receiver.poll_unpin(cx) {
-                    Poll::Ready(res) => {
-                        debug!(target: "block-builder", ?res, "pending task complete");
-                        // TODO: update tree's pending block?
-
-                        // ensure no fatal errors
-                        let mined_transactions = res??;
-
-                        // NOTE: empty vec returned for non-fatal error during block proposal
-                        if mined_transactions.is_empty() {
-                            // return pending and wait for canonical update to wake up again
-                            break;
-                        }
+            // poll receivers that return mined transactions once the batch reaches quorum
+            //
+            // poll here so waker is notified when ack received
+            let mut completed = None;
+            for (index, build) in this.pending_tasks.iter_mut().enumerate() {
+                if let Poll::Ready(res) = build.result.poll_unpin(cx) {
+                    completed = Some((index, res));
+                    break;
+                }
+            }
 
-                        // use latest values so only mined transactions are updated
-                        let new_tip = &this.latest_canon_state.tip;
-                        let pending_block_base_fee = this.latest_canon_state.pending_block_base_fee;
-                        let pending_block_blob_fee = this.latest_canon_state.pending_block_blob_fee;
+            let Some((index, res)) = completed else {
+                // break loop and return Poll::Pending
+                break;
+            };
 
-                        // create canonical state update
-                        let update = CanonicalStateUpdate {
-                            new_tip,
-                            pending_block_base_fee,
-                            pending_block_blob_fee,
-                            changed_accounts: vec![], // only updated by engine updates
-                            mined_transactions,
-                            update_kind: PoolUpdateKind::Commit,
-                        };
+            this.pending_tasks.remove(index);
+            debug!(target: "block-builder", ?res, "pending task complete");
+            // TODO: update tree's pending block?
 
-                        debug!(target: "block-builder", ?update, "applying block builder's update");
+            // ensure no fatal errors
+            let mined_transactions = res??;
 
-                        // TODO: should this be a spawned blocking task?
-                        //
-                        // update pool to remove mined transactions
-                        this.pool.on_canonical_state_change(update);
+            // NOTE: empty vec returned for non-fatal error during block proposal
+            if mined_transactions.is_empty() {
+                // return pending and wait for canonical update to wake up again
+                break;
+            }
 
-                        // loop again to check for any other pending transactions
-                        // and possibly start building the next block
-                        //
-                        // NOTE: continuing here is important.
-                        // To prevent the following scenario, do not wait for task's waker:
-                        // - there were more transactions in the pool than could fit in the first
-                        //   block
-                        // - pending transaction notifications already drained
-                        // - have to wait for engine's next canonical update to wake up
-                        continue;
-                    }
+            // use latest values so only mined transactions are updated
+            let new_tip = &this.latest_canon_state.tip;
+            let pending_block_base_fee = this.latest_canon_state.pending_block_base_fee;
+            let pending_block_blob_fee = this.latest_canon_state.pending_block_blob_fee;
+
+            // create canonical state update
+            let update = CanonicalStateUpdate {
+                new_tip,
+                pending_block_base_fee,
+                pending_block_blob_fee,
+                changed_accounts: vec![], // only updated by engine updates
+                mined_transactions,
+                update_kind: PoolUpdateKind::Commit,
+            };
 
-                    Poll::Pending => {
-                        this.pending_task = Some(receiver);
+            debug!(target: "block-builder", ?update, "applying block builder's update");
 
-                        // break loop and return Poll::Pending
-                        break;
-                    }
-                }
-            }
+            // TODO: should this be a spawned blocking task?
+            //
+            // update pool to remove mined transactions
+            this.pool.on_canonical_state_change(update);
+
+            // loop again to check for any other pending transactions
+            // and possibly start building the next block
+            //
+            // NOTE: continuing here is important.
+            // To prevent the following scenario, do not wait for task's waker:
+            // - there were more transactions in the pool than could fit in the first
+            //   block
+            // - pending transaction notifications already drained
+            // - have to wait for engine's next canonical update to wake up
         }
 
         // all output executed, yield back to runtime
@@ -426,6 +848,7 @@ mod tests {
         DatabaseEnv,
     };
     use reth_db_common::init::init_genesis;
+    use reth_execution_types::ExecutionOutcome;
     use reth_node_ethereum::{EthEvmConfig, EthExecutorProvider};
     use reth_provider::{
         providers::{BlockchainProvider, StaticFileProvider},
@@ -433,19 +856,27 @@ mod tests {
     };
     use reth_rpc_eth_types::utils::recover_raw_transaction;
     use reth_transaction_pool::{
-        blobstore::InMemoryBlobStore, CoinbaseTipOrdering, EthPooledTransaction,
-        EthTransactionValidator, Pool, PoolConfig, TransactionValidationTaskExecutor,
+        blobstore::InMemoryBlobStore,
+        error::{InvalidPoolTransactionError, PoolErrorKind},
+        CoinbaseTipOrdering, EthPooledTransaction, EthTransactionValidator, Pool, PoolConfig,
+        TransactionOrigin, TransactionValidationTaskExecutor,
     };
     use std::{str::FromStr, time::Duration};
     use tempfile::TempDir;
+    use tn_config::PoolEvictionPolicy;
     use tn_engine::execute_consensus_output;
     use tn_network_types::{local::LocalNetwork, MockWorkerToPrimaryHang};
     use tn_node_traits::{BuildArguments, TNExecution, TelcoinNode};
     use tn_storage::{open_db, tables::Batches};
-    use tn_test_utils::{adiri_genesis_seeded, get_gas_price, TransactionFactory};
+    use tn_test_utils::{
+        adiri_genesis_seeded, execution_outcome_for_tests, get_gas_price, MultiAccountFactory,
+        TransactionFactory,
+    };
     use tn_types::{
-        adiri_genesis, BlockBody, Bytes, CommittedSubDag, ConsensusHeader, ConsensusOutput,
-        Database, GenesisAccount, SealedBatch, SealedBlock, TaskManager, U160, U256,
+        adiri_genesis, max_batch_gas, AccessList, AccessListItem, BlockBody, Bytes,
+        CommittedSubDag, ConsensusHeader, ConsensusOutput, Database, ExecHeader, GenesisAccount,
+        SealedBatch, SealedBlock, SealedBlockWithSenders, SealedHeader,
+        SignedTransactionIntoRecoveredExt as _, TaskManager, B256, U160, U256,
     };
     use tn_worker::{
         metrics::WorkerMetrics,
@@ -676,9 +1107,18 @@ mod tests {
 
     /// Helper function to create common testing infrastructure.
     fn get_test_tools() -> TestTools {
+        get_test_tools_with(vec![], PoolConfig::default())
+    }
+
+    /// Create common testing infrastructure with additional funded accounts and a custom pool
+    /// config.
+    ///
+    /// The account for [TestTools::tx_factory] is always funded.
+    fn get_test_tools_with(funded_accounts: Vec<Address>, pool_config: PoolConfig) -> TestTools {
         let tx_factory = TransactionFactory::new();
-        let factory_address = tx_factory.address();
-        let genesis = adiri_genesis_seeded(vec![factory_address]);
+        let mut accounts = vec![tx_factory.address()];
+        accounts.extend(funded_accounts);
+        let genesis = adiri_genesis_seeded(accounts);
         let head_timestamp = genesis.timestamp;
         let chain: Arc<ChainSpec> = Arc::new(genesis.into());
 
@@ -719,8 +1159,7 @@ mod tests {
                 blob_store.clone(),
             );
 
-        let txpool =
-            reth_transaction_pool::Pool::eth_pool(validator, blob_store, PoolConfig::default());
+        let txpool = reth_transaction_pool::Pool::eth_pool(validator, blob_store, pool_config);
         let tx_pool_latest = txpool.block_info();
         let tip = SealedBlock::new(chain.sealed_genesis_header(), BlockBody::default());
 
@@ -1022,4 +1461,804 @@ mod tests {
         let pending_pool_len = txpool.pool_size().pending;
         assert_eq!(pending_pool_len, 0);
     }
+
+    /// Test previewing a batch selects the same transactions without affecting the pool.
+    #[tokio::test]
+    async fn test_build_preview_matches_built_batch() {
+        let TestTools { mut tx_factory, last_canonical_update, execution_components } =
+            get_test_tools();
+        let TestExecutionComponents { blockchain_db, txpool, chain, .. } = execution_components;
+        let address = Address::from(U160::from(33));
+        let (to_worker, mut from_batch_builder) = tokio::sync::mpsc::channel(2);
+
+        // build execution block proposer
+        let batch_builder = BatchBuilder::new(
+            blockchain_db.clone(),
+            txpool.clone(),
+            blockchain_db.canonical_state_stream(),
+            last_canonical_update,
+            to_worker,
+            address,
+            Duration::from_secs(1),
+        );
+
+        let gas_price = get_gas_price(&blockchain_db);
+        let value = U256::from(10).checked_pow(U256::from(18)).expect("1e18 doesn't overflow U256");
+
+        // submit 3 transactions
+        let mut expected_hashes = Vec::new();
+        for _ in 0..3 {
+            let hash = tx_factory
+                .create_and_submit_eip1559_pool_tx(
+                    chain.clone(),
+                    gas_price,
+                    Address::ZERO,
+                    value, // 1 TEL
+                    &txpool,
+                )
+                .await;
+            expected_hashes.push(hash);
+        }
+        assert_eq!(txpool.pool_size().pending, 3);
+
+        // preview the next batch
+        let preview = batch_builder.build_preview();
+        assert_eq!(preview.mined_transactions(), expected_hashes.as_slice());
+        assert_eq!(preview.batch().transactions().len(), 3);
+        assert_eq!(preview.batch().beneficiary, address);
+
+        // previewing is repeatable and does not remove transactions from the pool
+        let second_preview = batch_builder.build_preview();
+        assert_eq!(second_preview.mined_transactions(), preview.mined_transactions());
+        assert_eq!(txpool.pool_size().pending, 3);
+
+        // spawn batch_builder and compare against the real batch
+        let _batch_builder_task = tokio::spawn(Box::pin(batch_builder));
+        let (sealed_batch, _ack) = timeout(Duration::from_secs(5), from_batch_builder.recv())
+            .await
+            .expect("block builder's sender didn't drop")
+            .expect("batch was built");
+
+        assert_eq!(sealed_batch.batch().transactions(), preview.batch().transactions());
+    }
+
+    /// Test millisecond timestamps increase for batches built within the same second.
+    #[tokio::test]
+    async fn test_millis_timestamps_increase_within_same_second() {
+        let TestTools { last_canonical_update, execution_components, .. } = get_test_tools();
+        let TestExecutionComponents { blockchain_db, txpool, .. } = execution_components;
+        let parent_timestamp = last_canonical_update.tip.timestamp;
+        let (to_worker, _from_batch_builder) = tokio::sync::mpsc::channel(2);
+        let batch_builder = BatchBuilder::new(
+            blockchain_db.clone(),
+            txpool,
+            blockchain_db.canonical_state_stream(),
+            last_canonical_update,
+            to_worker,
+            Address::from(U160::from(33)),
+            Duration::from_secs(1),
+        )
+        .with_timestamp_granularity(TimestampGranularity::Millis);
+
+        // build two batches a few millis apart
+        let start = TimestampGranularity::Millis.now();
+        let first = batch_builder.build_preview().batch().timestamp;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let second = batch_builder.build_preview().batch().timestamp;
+        let end = TimestampGranularity::Millis.now();
+
+        // timestamps follow the wall clock instead of jumping a full second past the parent
+        assert!(first > parent_timestamp * 1_000);
+        assert!(start <= first);
+        assert!(first < second);
+        assert!(second <= end);
+        assert!(second - first < 1_000);
+    }
+
+    /// Test legacy, EIP-2930, and EIP-1559 transactions are included in batches and executed.
+    #[tokio::test]
+    async fn test_build_batch_with_all_tx_types() {
+        let TestTools { mut tx_factory, last_canonical_update, execution_components } =
+            get_test_tools();
+        let TestExecutionComponents { blockchain_db, txpool, chain, .. } = execution_components;
+        let address = Address::from(U160::from(33));
+        let gas_price = get_gas_price(&blockchain_db);
+        let value = U256::from(10).checked_pow(U256::from(18)).expect("1e18 doesn't overflow U256");
+
+        // create one transaction of each type
+        let legacy = tx_factory.create_legacy(
+            chain.clone(),
+            None,
+            gas_price,
+            Some(Address::ZERO),
+            value, // 1 TEL
+            Bytes::new(),
+        );
+        let access_list = AccessList(vec![AccessListItem {
+            address: Address::ZERO,
+            storage_keys: vec![Default::default()],
+        }]);
+        let eip2930 = tx_factory.create_eip2930(
+            chain.clone(),
+            None,
+            gas_price,
+            Some(Address::ZERO),
+            value, // 1 TEL
+            Bytes::new(),
+            access_list,
+        );
+        let eip1559 = tx_factory.create_eip1559(
+            chain.clone(),
+            None,
+            gas_price,
+            Some(Address::ZERO),
+            value, // 1 TEL
+            Bytes::new(),
+        );
+
+        let expected = vec![legacy, eip2930, eip1559];
+        for tx in expected.iter() {
+            let added_result = tx_factory.submit_tx_to_pool(tx.clone(), txpool.clone()).await;
+            assert_matches!(added_result, hash if hash == tx.hash());
+        }
+        assert_eq!(txpool.pool_size().pending, 3);
+
+        // build the batch
+        let config = PendingBlockConfig::new(address, last_canonical_update);
+        let BatchBuilderOutput { batch, mined_transactions, .. } =
+            build_batch(BatchBuilderArgs::new(txpool.clone(), config));
+        assert_eq!(mined_transactions, expected.iter().map(|tx| tx.hash()).collect::<Vec<_>>());
+
+        // decode transactions from the batch
+        for (tx_bytes, expected_tx) in batch.transactions().iter().zip(expected.iter()) {
+            let tx = recover_raw_transaction::<TransactionSigned>(tx_bytes)
+                .expect("recover raw tx for test")
+                .into_tx();
+            assert_eq!(tx.tx_type(), expected_tx.tx_type());
+        }
+
+        // execute the batch and ensure receipts match the transaction types
+        let executor = EthExecutorProvider::ethereum(Arc::clone(&chain));
+        let parent = chain.sealed_genesis_header();
+        let outcome = execution_outcome_for_tests(&batch, &parent, &blockchain_db, &executor);
+        let receipts = outcome.receipts_by_block(parent.number + 1);
+        assert_eq!(receipts.len(), expected.len());
+        for (receipt, expected_tx) in receipts.iter().zip(expected.iter()) {
+            let receipt = receipt.as_ref().expect("receipt for executed tx");
+            assert!(receipt.success);
+            assert_eq!(receipt.tx_type, expected_tx.tx_type());
+        }
+    }
+
+    /// Test submitting many transactions to the pool at once.
+    #[tokio::test]
+    async fn test_submit_batch_to_pool() {
+        let TestTools { mut tx_factory, execution_components, .. } = get_test_tools();
+        let TestExecutionComponents { blockchain_db, txpool, chain, .. } = execution_components;
+        let gas_price = get_gas_price(&blockchain_db);
+        let value = U256::from(10).checked_pow(U256::from(18)).expect("1e18 doesn't overflow U256");
+
+        let hashes = tx_factory
+            .submit_batch_to_pool(50, chain.clone(), gas_price, Address::ZERO, value, &txpool)
+            .await;
+        assert_eq!(hashes.len(), 50);
+        assert_eq!(txpool.pool_size().pending, 50);
+
+        // all transactions are pending with contiguous nonces
+        let mut nonces: Vec<u64> =
+            txpool.pending_transactions().iter().map(|tx| tx.nonce()).collect();
+        nonces.sort_unstable();
+        assert_eq!(nonces, (0..50).collect::<Vec<_>>());
+
+        // hashes are returned in nonce order
+        for (nonce, hash) in hashes.iter().enumerate() {
+            let tx = txpool.get(hash).expect("tx in pool");
+            assert_eq!(tx.nonce(), nonce as u64);
+        }
+    }
+
+    /// Test the blob fee for the next batch is calculated from the canonical tip's excess blob
+    /// gas and applied to the pool.
+    #[tokio::test]
+    async fn test_canon_update_tracks_blob_fee() {
+        let TestTools { last_canonical_update, execution_components, .. } = get_test_tools();
+        let TestExecutionComponents { blockchain_db, txpool, chain, .. } = execution_components;
+        let (to_worker, _from_batch_builder) = tokio::sync::mpsc::channel(2);
+        let mut batch_builder = BatchBuilder::new(
+            blockchain_db.clone(),
+            txpool.clone(),
+            blockchain_db.canonical_state_stream(),
+            last_canonical_update,
+            to_worker,
+            Address::from(U160::from(33)),
+            Duration::from_secs(1),
+        );
+
+        // canonical tip with excess blob gas
+        let genesis = chain.sealed_genesis_header();
+        let header = ExecHeader {
+            parent_hash: genesis.hash(),
+            number: 1,
+            timestamp: genesis.timestamp + 1,
+            blob_gas_used: Some(0),
+            excess_blob_gas: Some(10_000_000),
+            ..genesis.header().clone()
+        };
+        let expected_blob_fee = header.next_block_blob_fee();
+        assert!(expected_blob_fee.is_some_and(|fee| fee > 1));
+
+        let tip = SealedBlockWithSenders::new(
+            SealedBlock::new(SealedHeader::seal(header), BlockBody::default()),
+            vec![],
+        )
+        .expect("sealed block with senders");
+        let chain_update = Chain::new(vec![tip], ExecutionOutcome::default(), None);
+
+        batch_builder.process_canon_state_update(Arc::new(chain_update));
+
+        // pool and builder track the blob fee for the next batch
+        assert_eq!(txpool.block_info().pending_blob_fee, expected_blob_fee);
+        assert_eq!(batch_builder.latest_canon_state.pending_block_blob_fee, expected_blob_fee);
+    }
+
+    /// Test a reorg notification doesn't panic and later commits are still applied.
+    #[tokio::test]
+    async fn test_reorg_notification_does_not_panic() {
+        let TestTools { last_canonical_update, execution_components, .. } = get_test_tools();
+        let TestExecutionComponents { blockchain_db, txpool, chain, .. } = execution_components;
+        let (to_worker, _from_batch_builder) = tokio::sync::mpsc::channel(2);
+        let mut batch_builder = BatchBuilder::new(
+            blockchain_db.clone(),
+            txpool.clone(),
+            blockchain_db.canonical_state_stream(),
+            last_canonical_update,
+            to_worker,
+            Address::from(U160::from(33)),
+            Duration::from_secs(1),
+        );
+
+        // create a chain with a single block extending the parent
+        let genesis = chain.sealed_genesis_header();
+        let chain_at = |number: u64, parent_hash: B256| {
+            let header = ExecHeader {
+                parent_hash,
+                number,
+                timestamp: genesis.timestamp + number,
+                ..genesis.header().clone()
+            };
+            let tip = SealedBlockWithSenders::new(
+                SealedBlock::new(SealedHeader::seal(header), BlockBody::default()),
+                vec![],
+            )
+            .expect("sealed block with senders");
+            Arc::new(Chain::new(vec![tip], ExecutionOutcome::default(), None))
+        };
+
+        // reorg is applied as a commit of the new chain
+        let old = chain_at(1, genesis.hash());
+        let new = chain_at(1, B256::random());
+        let new_tip = new.tip().block.hash();
+        batch_builder.process_canon_notification(CanonStateNotification::Reorg { old, new });
+        assert_eq!(batch_builder.latest_canon_state.tip.hash(), new_tip);
+        assert_eq!(txpool.block_info().last_seen_block_hash, new_tip);
+
+        // subsequent commits are still processed
+        let next = chain_at(2, new_tip);
+        let next_tip = next.tip().block.hash();
+        batch_builder.process_canon_notification(CanonStateNotification::Commit { new: next });
+        assert_eq!(batch_builder.latest_canon_state.tip.hash(), next_tip);
+        assert_eq!(txpool.block_info().last_seen_block_hash, next_tip);
+    }
+
+    /// Test each worker's metrics register with the same registry under prefixed names.
+    #[test]
+    fn test_batch_builder_metrics_per_worker() {
+        let registry = prometheus::Registry::new();
+        let worker_0 = BatchBuilderMetrics::try_new(&registry, 0).expect("worker 0 metrics");
+        let worker_1 = BatchBuilderMetrics::try_new(&registry, 1).expect("worker 1 metrics");
+        worker_0.builds_triggered.inc();
+        worker_1.builds_triggered.inc_by(2);
+
+        let families = registry.gather();
+        assert!(families.iter().all(|family| family.get_name().starts_with("batch_builder_")));
+        let builds = families
+            .iter()
+            .find(|family| family.get_name() == "batch_builder_builds_triggered")
+            .expect("builds triggered metric");
+        assert_eq!(builds.get_metric().len(), 2);
+
+        // registering the same worker twice fails instead of silently creating a new registry
+        assert!(BatchBuilderMetrics::try_new(&registry, 0).is_err());
+    }
+
+    /// Test metrics track polling and build behavior.
+    #[tokio::test]
+    async fn test_batch_builder_metrics() {
+        let TestTools { mut tx_factory, last_canonical_update, execution_components } =
+            get_test_tools();
+        let TestExecutionComponents { blockchain_db, txpool, chain, .. } = execution_components;
+        let (to_worker, mut from_batch_builder) = tokio::sync::mpsc::channel(2);
+        let batch_builder = BatchBuilder::new(
+            blockchain_db.clone(),
+            txpool.clone(),
+            blockchain_db.canonical_state_stream(),
+            last_canonical_update,
+            to_worker,
+            Address::from(U160::from(33)),
+            Duration::from_secs(1),
+        );
+        let metrics = batch_builder.metrics().clone();
+
+        let gas_price = get_gas_price(&blockchain_db);
+        let value = U256::from(10).checked_pow(U256::from(18)).expect("1e18 doesn't overflow U256");
+
+        // submit 3 transactions for the first batch
+        tx_factory
+            .submit_batch_to_pool(3, chain.clone(), gas_price, Address::ZERO, value, &txpool)
+            .await;
+
+        let _batch_builder_task = tokio::spawn(Box::pin(batch_builder));
+        let duration = Duration::from_secs(5);
+
+        // receive first batch and submit another tx before mining
+        let (first_batch, ack) = timeout(duration, from_batch_builder.recv())
+            .await
+            .expect("block builder's sender didn't drop")
+            .expect("batch was built");
+        assert_eq!(first_batch.batch().transactions().len(), 3);
+        tx_factory
+            .create_and_submit_eip1559_pool_tx(
+                chain.clone(),
+                gas_price,
+                Address::ZERO,
+                value,
+                &txpool,
+            )
+            .await;
+        let _ = ack.send(Ok(()));
+
+        // receive second batch
+        let (next_batch, ack) = timeout(duration, from_batch_builder.recv())
+            .await
+            .expect("block builder's sender didn't drop")
+            .expect("batch was built");
+        assert_eq!(next_batch.batch().transactions().len(), 1);
+        let _ = ack.send(Ok(()));
+
+        // give the builder time to apply the update and find the pool empty
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(txpool.pool_size().pending, 0);
+
+        assert_eq!(metrics.builds_triggered.get(), 2);
+        assert!(metrics.builds_skipped_empty_pool.get() >= 1);
+        assert!(metrics.builder_poll_count.get() >= 2);
+        assert_eq!(metrics.txs_per_batch.get_sample_count(), 2);
+        assert_eq!(metrics.avg_txs_per_block.get(), 2.0);
+    }
+
+    /// The batch builder records transactions skipped because the batch's gas limit was reached.
+    #[tokio::test]
+    async fn test_batch_builder_records_selection_stats() {
+        let TestTools { mut tx_factory, last_canonical_update, execution_components } =
+            get_test_tools();
+        let TestExecutionComponents { blockchain_db, txpool, chain, .. } = execution_components;
+        let (to_worker, mut from_batch_builder) = tokio::sync::mpsc::channel(2);
+        let batch_builder = BatchBuilder::new(
+            blockchain_db.clone(),
+            txpool.clone(),
+            blockchain_db.canonical_state_stream(),
+            last_canonical_update,
+            to_worker,
+            Address::from(U160::from(33)),
+            Duration::from_secs(1),
+        );
+        let metrics = batch_builder.metrics().clone();
+        let gas_price = get_gas_price(&blockchain_db);
+
+        // two transactions that can't both fit in the batch's gas limit
+        let gas_limit = max_batch_gas(0) / 2 + 1;
+        for _ in 0..2 {
+            let transaction = tx_factory.create_explicit_eip1559(
+                Some(chain.chain.id()),
+                None,
+                None,
+                Some(gas_price),
+                Some(gas_limit),
+                Some(Address::ZERO),
+                None,
+                None,
+                None,
+            );
+            tx_factory.submit_tx_to_pool(transaction, txpool.clone()).await;
+        }
+        assert_eq!(txpool.pool_size().pending, 2);
+
+        let _batch_builder_task = tokio::spawn(Box::pin(batch_builder));
+
+        // the first transaction is included and the second is skipped
+        let (batch, _ack) = timeout(Duration::from_secs(5), from_batch_builder.recv())
+            .await
+            .expect("block builder's sender didn't drop")
+            .expect("batch was built");
+        assert_eq!(batch.batch().transactions().len(), 1);
+
+        assert_eq!(metrics.txs_considered.get(), 2);
+        assert_eq!(metrics.txs_included.get(), 1);
+        assert_eq!(metrics.txs_invalid_gas.get(), 1);
+        assert_eq!(metrics.txs_invalid_bytes.get(), 0);
+        assert_eq!(metrics.pool_lock_duration.get_sample_count(), 1);
+    }
+
+    /// The batch builder records how full each batch is.
+    #[tokio::test]
+    async fn test_batch_builder_records_utilization() {
+        let TestTools { mut tx_factory, last_canonical_update, execution_components } =
+            get_test_tools();
+        let TestExecutionComponents { blockchain_db, txpool, chain, .. } = execution_components;
+        let (to_worker, mut from_batch_builder) = tokio::sync::mpsc::channel(2);
+        let batch_builder = BatchBuilder::new(
+            blockchain_db.clone(),
+            txpool.clone(),
+            blockchain_db.canonical_state_stream(),
+            last_canonical_update,
+            to_worker,
+            Address::from(U160::from(33)),
+            Duration::from_secs(1),
+        );
+        let metrics = batch_builder.metrics().clone();
+        let gas_price = get_gas_price(&blockchain_db);
+        let _batch_builder_task = tokio::spawn(Box::pin(batch_builder));
+
+        // a quarter full batch followed by a half full batch
+        for gas_limit in [max_batch_gas(0) / 4, max_batch_gas(0) / 2 + 1] {
+            let transaction = tx_factory.create_explicit_eip1559(
+                Some(chain.chain.id()),
+                None,
+                None,
+                Some(gas_price),
+                Some(gas_limit),
+                Some(Address::ZERO),
+                None,
+                None,
+                None,
+            );
+            tx_factory.submit_tx_to_pool(transaction, txpool.clone()).await;
+
+            let (batch, ack) = timeout(Duration::from_secs(5), from_batch_builder.recv())
+                .await
+                .expect("block builder's sender didn't drop")
+                .expect("batch was built");
+            assert_eq!(batch.batch().transactions().len(), 1);
+            let _ = ack.send(Ok(()));
+        }
+
+        // cumulative bucket counts by upper bound
+        use prometheus::core::Collector as _;
+        let buckets = |histogram: &prometheus::Histogram| -> Vec<(f64, u64)> {
+            histogram.collect()[0].get_metric()[0]
+                .get_histogram()
+                .get_bucket()
+                .iter()
+                .map(|bucket| (bucket.get_upper_bound(), bucket.get_cumulative_count()))
+                .collect()
+        };
+
+        let gas = buckets(&metrics.block_gas_utilization);
+        assert_eq!(metrics.block_gas_utilization.get_sample_count(), 2);
+        assert_eq!(gas[1], (0.2, 0));
+        assert_eq!(gas[2], (0.3, 1));
+        assert_eq!(gas[4], (0.5, 1));
+        assert_eq!(gas[5], (0.6, 2));
+
+        // single transactions are far below the max size
+        let size = buckets(&metrics.block_size_utilization);
+        assert_eq!(metrics.block_size_utilization.get_sample_count(), 2);
+        assert_eq!(size[0], (0.1, 2));
+    }
+
+    #[tokio::test]
+    async fn test_pending_pool_evicts_lowest_fee_first() {
+        let max_pending_pool_txs = 5;
+        let mut tx_factory = MultiAccountFactory::new([7; 32], max_pending_pool_txs * 2);
+        let mut pool_config = PoolConfig::default();
+        apply_pending_pool_limits(
+            &mut pool_config,
+            max_pending_pool_txs,
+            PoolEvictionPolicy::LowestFeeFirst,
+        );
+        let TestTools { execution_components, .. } =
+            get_test_tools_with(tx_factory.addresses(), pool_config);
+        let TestExecutionComponents { blockchain_db, txpool, chain, .. } = execution_components;
+
+        let gas_price = get_gas_price(&blockchain_db);
+
+        // submit twice the cap from different accounts with increasing tips
+        let mut hashes = Vec::new();
+        for tip in 1..=(max_pending_pool_txs * 2) as u128 {
+            let tx = tx_factory.next_factory().create_explicit_eip1559(
+                Some(chain.chain.id()),
+                None,
+                Some(tip),
+                Some(gas_price + tip),
+                None,
+                Some(Address::ZERO),
+                None,
+                None,
+                None,
+            );
+            let hash = tx_factory.factories()[0].submit_tx_to_pool(tx, txpool.clone()).await;
+            hashes.push(hash);
+        }
+
+        // pending pool is capped
+        assert_eq!(txpool.pool_size().pending, max_pending_pool_txs);
+
+        // lowest fee transactions are evicted and the highest fee transactions remain pending
+        let (evicted, pending) = hashes.split_at(max_pending_pool_txs);
+        for hash in evicted {
+            assert!(!txpool.contains(hash));
+        }
+        for hash in pending {
+            assert!(txpool.contains(hash));
+        }
+    }
+
+    /// Transactions that can't pay the canonical base fee are excluded from the batch.
+    #[tokio::test]
+    async fn test_build_batch_excludes_tx_below_base_fee() {
+        let TestTools { mut tx_factory, mut last_canonical_update, execution_components } =
+            get_test_tools();
+        let TestExecutionComponents { blockchain_db, txpool, chain, .. } = execution_components;
+        let address = Address::from(U160::from(33));
+        let gas_price = get_gas_price(&blockchain_db);
+        let base_fee = gas_price * 2;
+
+        // first transaction pays the base fee
+        let priced = tx_factory.create_explicit_eip1559(
+            Some(chain.chain.id()),
+            None,
+            None,
+            Some(base_fee * 2),
+            None,
+            Some(Address::ZERO),
+            None,
+            None,
+            None,
+        );
+        // second transaction is below the base fee
+        let underpriced = tx_factory.create_explicit_eip1559(
+            Some(chain.chain.id()),
+            None,
+            None,
+            Some(base_fee - 1),
+            None,
+            Some(Address::ZERO),
+            None,
+            None,
+            None,
+        );
+        for tx in [&priced, &underpriced] {
+            let added_result = tx_factory.submit_tx_to_pool(tx.clone(), txpool.clone()).await;
+            assert_matches!(added_result, hash if hash == tx.hash());
+        }
+
+        // both transactions are pending based on the pool's base fee
+        assert_eq!(txpool.pool_size().pending, 2);
+
+        // the canonical base fee for the next block increased
+        last_canonical_update.pending_block_base_fee = base_fee as u64;
+        let config = PendingBlockConfig::new(address, last_canonical_update);
+        let BatchBuilderOutput { batch, mined_transactions, .. } =
+            build_batch(BatchBuilderArgs::new(txpool.clone(), config));
+
+        // only the transaction that pays the base fee is included
+        assert_eq!(mined_transactions, vec![priced.hash()]);
+        assert_eq!(batch.transactions().len(), 1);
+        let tx = recover_raw_transaction::<TransactionSigned>(&batch.transactions()[0])
+            .expect("recover raw tx for test");
+        assert_eq!(tx.hash(), priced.hash());
+    }
+
+    /// Local transactions are included before external transactions regardless of fee.
+    #[tokio::test]
+    async fn test_local_tx_included_before_higher_fee_external_tx() {
+        let mut tx_factory = MultiAccountFactory::new([9; 32], 2);
+        let TestTools { last_canonical_update, execution_components, .. } =
+            get_test_tools_with(tx_factory.addresses(), PoolConfig::default());
+        let TestExecutionComponents { blockchain_db, txpool, chain, .. } = execution_components;
+
+        let gas_price = get_gas_price(&blockchain_db);
+        let batch_gas = max_batch_gas(last_canonical_update.tip.timestamp);
+
+        // low fee local transaction
+        let local = tx_factory.next_factory().create_explicit_eip1559(
+            Some(chain.chain.id()),
+            None,
+            Some(1),
+            Some(gas_price + 1),
+            None,
+            Some(Address::ZERO),
+            None,
+            None,
+            None,
+        );
+        let local_hash = tx_factory.factories()[0].submit_tx_to_pool(local, txpool.clone()).await;
+
+        // high fee external transaction that fills the batch
+        let external = tx_factory.next_factory().create_explicit_eip1559(
+            Some(chain.chain.id()),
+            None,
+            Some(gas_price * 10),
+            Some(gas_price * 11),
+            Some(batch_gas),
+            Some(Address::ZERO),
+            None,
+            None,
+            None,
+        );
+        let external_hash = external.hash();
+        let recovered = external
+            .try_into_pooled()
+            .expect("tx valid for pool")
+            .try_into_ecrecovered()
+            .expect("tx is recovered");
+        txpool
+            .add_transaction(TransactionOrigin::External, recovered.into())
+            .await
+            .expect("external tx added to pool");
+        assert_eq!(txpool.pool_size().pending, 2);
+
+        // the external tx has the higher priority but both can't fit in the batch
+        let config = PendingBlockConfig::new(Address::from(U160::from(33)), last_canonical_update);
+        let BatchBuilderOutput { batch, mined_transactions, .. } =
+            build_batch(BatchBuilderArgs::new(txpool.clone(), config));
+
+        // only the local transaction is included
+        assert_eq!(mined_transactions, vec![local_hash]);
+        assert!(!mined_transactions.contains(&external_hash));
+        assert_eq!(batch.transactions().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_min_priority_fee_rejects_underpriced_tx() {
+        let TestTools { mut tx_factory, execution_components, .. } = get_test_tools();
+        let TestExecutionComponents { blockchain_db, chain, _manager: task_manager, .. } =
+            execution_components;
+        let min_priority_fee = 10;
+
+        // txpool with min priority fee
+        let blob_store = InMemoryBlobStore::default();
+        let validator = TransactionValidationTaskExecutor::eth_builder(Arc::clone(&chain))
+            .with_additional_tasks(1)
+            .build_with_tasks(
+                blockchain_db.clone(),
+                task_manager.get_spawner(),
+                blob_store.clone(),
+            );
+        let validator = MinPriorityFeeValidator::new(validator, min_priority_fee);
+        let txpool = reth_transaction_pool::Pool::new(
+            validator,
+            CoinbaseTipOrdering::default(),
+            blob_store,
+            PoolConfig::default(),
+        );
+
+        let gas_price = get_gas_price(&blockchain_db);
+
+        // underpriced transaction is rejected at admission
+        let underpriced = tx_factory.create_explicit_eip1559(
+            Some(chain.chain.id()),
+            None,
+            Some(min_priority_fee - 1),
+            Some(gas_price + min_priority_fee),
+            None,
+            Some(Address::ZERO),
+            None,
+            None,
+            None,
+        );
+        let recovered = underpriced
+            .try_into_pooled()
+            .expect("tx valid for pool")
+            .try_into_ecrecovered()
+            .expect("tx is recovered");
+        let err = txpool
+            .add_transaction(TransactionOrigin::External, recovered.into())
+            .await
+            .expect_err("underpriced tx rejected");
+        assert_matches!(
+            err.kind,
+            PoolErrorKind::InvalidTransaction(InvalidPoolTransactionError::Other(_))
+        );
+        assert!(err.to_string().contains("below the minimum priority fee"));
+        assert_eq!(txpool.pool_size().total, 0);
+
+        // transaction paying the min priority fee is accepted with the same nonce
+        tx_factory.set_nonce(0);
+        let transaction = tx_factory.create_explicit_eip1559(
+            Some(chain.chain.id()),
+            None,
+            Some(min_priority_fee),
+            Some(gas_price + min_priority_fee),
+            None,
+            Some(Address::ZERO),
+            None,
+            None,
+            None,
+        );
+        let added_result = tx_factory.submit_tx_to_pool(transaction.clone(), txpool.clone()).await;
+        assert_matches!(added_result, hash if hash == transaction.hash());
+        assert_eq!(txpool.pool_size().pending, 1);
+    }
+
+    #[tokio::test]
+    async fn test_batch_built_after_pending_notifications_dropped() {
+        let listener_capacity = 2;
+        let num_txs = listener_capacity * 5;
+
+        // txpool with a small pending tx listener
+        let mut pool_config = PoolConfig::default();
+        apply_pending_tx_listener_capacity(&mut pool_config, listener_capacity);
+        let TestTools { mut tx_factory, last_canonical_update, execution_components } =
+            get_test_tools_with(vec![], pool_config);
+        let TestExecutionComponents { blockchain_db, txpool, chain, .. } = execution_components;
+
+        let address = Address::from(U160::from(33));
+        let (to_worker, mut from_batch_builder) = tokio::sync::mpsc::channel(2);
+        let batch_builder = BatchBuilder::new(
+            blockchain_db.clone(),
+            txpool.clone(),
+            blockchain_db.canonical_state_stream(),
+            last_canonical_update,
+            to_worker,
+            address,
+            Duration::from_secs(1),
+        );
+
+        // flood the listener before the batch builder drains any notifications
+        let gas_price = get_gas_price(&blockchain_db);
+        let value = U256::from(10).checked_pow(U256::from(18)).expect("1e18 doesn't overflow U256");
+        let mut expected_hashes = Vec::new();
+        for _ in 0..num_txs {
+            let hash = tx_factory
+                .create_and_submit_eip1559_pool_tx(
+                    chain.clone(),
+                    gas_price,
+                    Address::ZERO,
+                    value, // 1 TEL
+                    &txpool,
+                )
+                .await;
+            expected_hashes.push(hash);
+        }
+        assert_eq!(txpool.pool_size().pending, num_txs);
+
+        // batch is still built with all pending transactions
+        let _batch_builder_task = tokio::spawn(Box::pin(batch_builder));
+        let (sealed_batch, ack) = timeout(Duration::from_secs(5), from_batch_builder.recv())
+            .await
+            .expect("block builder's sender didn't drop")
+            .expect("batch was built");
+        assert_eq!(sealed_batch.batch().transactions().len(), num_txs);
+        let _ = ack.send(Ok(()));
+
+        // more transactions after the listener overflowed still produce a batch
+        let expected_hash = tx_factory
+            .create_and_submit_eip1559_pool_tx(
+                chain.clone(),
+                gas_price,
+                Address::ZERO,
+                value, // 1 TEL
+                &txpool,
+            )
+            .await;
+        let (next_batch, _ack) = timeout(Duration::from_secs(5), from_batch_builder.recv())
+            .await
+            .expect("block builder's sender didn't drop")
+            .expect("batch was built");
+        let tx_bytes = next_batch.batch().transactions().first().expect("batch has one tx");
+        let tx = recover_raw_transaction::<TransactionSigned>(tx_bytes)
+            .expect("recover raw tx for test");
+        assert_eq!(tx.hash(), expected_hash);
+    }
 }
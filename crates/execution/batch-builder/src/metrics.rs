@@ -0,0 +1,215 @@
+//! Metrics for the batch builder.
+
+use crate::BatchBuildStats;
+use prometheus::{
+    default_registry, register_gauge_with_registry, register_histogram_with_registry,
+    register_int_counter_with_registry, Gauge, Histogram, HistogramOpts, IntCounter, Opts,
+    Registry,
+};
+use tn_types::WorkerId;
+
+/// The prefix for all batch builder metric names.
+const METRICS_PREFIX: &str = "batch_builder_";
+
+/// The label identifying the worker a batch builder belongs to.
+const WORKER_ID_LABEL: &str = "worker_id";
+
+/// Buckets for the number of transactions in a batch.
+const TXS_PER_BATCH_BUCKETS: &[f64] =
+    &[1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1_000.0, 2_000.0, 5_000.0, 10_000.0];
+
+/// Buckets for the fraction of a batch's gas limit or max size used.
+const UTILIZATION_BUCKETS: &[f64] = &[0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0];
+
+/// Metrics for the batch builder's polling and build behavior.
+#[derive(Clone, Debug)]
+pub struct BatchBuilderMetrics {
+    /// The number of times the batch builder task was polled.
+    pub builder_poll_count: IntCounter,
+    /// The number of batch builds started.
+    pub builds_triggered: IntCounter,
+    /// The number of times the builder skipped building because the pending pool was empty.
+    pub builds_skipped_empty_pool: IntCounter,
+    /// The number of times the builder skipped building because too few peers were connected.
+    pub builds_skipped_insufficient_peers: IntCounter,
+    /// The number of queued transactions evicted because they exceeded the max lifetime.
+    pub queued_txs_expired: IntCounter,
+    /// The number of transactions included in each built batch.
+    pub txs_per_batch: Histogram,
+    /// The average number of transactions included in built batches.
+    pub avg_txs_per_block: Gauge,
+    /// The amount of time (in seconds) the pool is held while selecting transactions.
+    pub pool_lock_duration: Histogram,
+    /// The number of transactions pulled from the pool while building batches.
+    pub txs_considered: IntCounter,
+    /// The number of transactions included in built batches.
+    pub txs_included: IntCounter,
+    /// The number of transactions skipped because the batch's gas limit was reached.
+    pub txs_invalid_gas: IntCounter,
+    /// The number of transactions skipped because the batch's max size was reached.
+    pub txs_invalid_bytes: IntCounter,
+    /// The fraction of the gas limit used by each built batch.
+    pub block_gas_utilization: Histogram,
+    /// The fraction of the max size used by each built batch.
+    pub block_size_utilization: Histogram,
+}
+
+impl BatchBuilderMetrics {
+    /// Try to create and register metrics with the provided registry.
+    ///
+    /// Metric names are prefixed with `batch_builder_` and labeled with the worker id, so each
+    /// worker's batch builder can register with the same registry.
+    pub fn try_new(registry: &Registry, worker_id: WorkerId) -> Result<Self, prometheus::Error> {
+        let worker_id = worker_id.to_string();
+        let opts = |name: &str, help: &str| {
+            Opts::new(format!("{METRICS_PREFIX}{name}"), help)
+                .const_label(WORKER_ID_LABEL, worker_id.as_str())
+        };
+        let histogram_opts = |name: &str, help: &str, buckets: &[f64]| {
+            HistogramOpts::from(opts(name, help)).buckets(buckets.to_vec())
+        };
+
+        Ok(Self {
+            builder_poll_count: register_int_counter_with_registry!(
+                opts("poll_count", "The number of times the batch builder task was polled"),
+                registry
+            )?,
+            builds_triggered: register_int_counter_with_registry!(
+                opts("builds_triggered", "The number of batch builds started"),
+                registry
+            )?,
+            builds_skipped_empty_pool: register_int_counter_with_registry!(
+                opts(
+                    "builds_skipped_empty_pool",
+                    "The number of times the batch builder skipped building because the pending pool was empty",
+                ),
+                registry
+            )?,
+            builds_skipped_insufficient_peers: register_int_counter_with_registry!(
+                opts(
+                    "builds_skipped_insufficient_peers",
+                    "The number of times the batch builder skipped building because too few peers were connected",
+                ),
+                registry
+            )?,
+            queued_txs_expired: register_int_counter_with_registry!(
+                opts(
+                    "queued_txs_expired",
+                    "The number of queued transactions evicted because they exceeded the max lifetime",
+                ),
+                registry
+            )?,
+            txs_per_batch: register_histogram_with_registry!(
+                histogram_opts(
+                    "txs_per_batch",
+                    "The number of transactions included in each built batch",
+                    TXS_PER_BATCH_BUCKETS,
+                ),
+                registry
+            )?,
+            avg_txs_per_block: register_gauge_with_registry!(
+                opts(
+                    "avg_txs_per_block",
+                    "The average number of transactions included in built batches",
+                ),
+                registry
+            )?,
+            pool_lock_duration: register_histogram_with_registry!(
+                HistogramOpts::from(
+                    opts("pool_lock_duration", "The amount of time (in seconds) the pool is held while selecting transactions"),
+                ),
+                registry
+            )?,
+            txs_considered: register_int_counter_with_registry!(
+                opts(
+                    "txs_considered",
+                    "The number of transactions pulled from the pool while building batches",
+                ),
+                registry
+            )?,
+            txs_included: register_int_counter_with_registry!(
+                opts("txs_included", "The number of transactions included in built batches"),
+                registry
+            )?,
+            txs_invalid_gas: register_int_counter_with_registry!(
+                opts(
+                    "txs_invalid_gas",
+                    "The number of transactions skipped because the batch's gas limit was reached",
+                ),
+                registry
+            )?,
+            txs_invalid_bytes: register_int_counter_with_registry!(
+                opts(
+                    "txs_invalid_bytes",
+                    "The number of transactions skipped because the batch's max size was reached",
+                ),
+                registry
+            )?,
+            block_gas_utilization: register_histogram_with_registry!(
+                histogram_opts(
+                    "block_gas_utilization",
+                    "The fraction of the gas limit used by each built batch",
+                    UTILIZATION_BUCKETS,
+                ),
+                registry
+            )?,
+            block_size_utilization: register_histogram_with_registry!(
+                histogram_opts(
+                    "block_size_utilization",
+                    "The fraction of the max size used by each built batch",
+                    UTILIZATION_BUCKETS,
+                ),
+                registry
+            )?,
+        })
+    }
+
+    /// Create and register metrics with the provided registry.
+    ///
+    /// Panics if the metrics for the worker are already registered.
+    pub fn new_with_registry(registry: &Registry, worker_id: WorkerId) -> Self {
+        Self::try_new(registry, worker_id).expect("Prometheus error, are you using it wrong?")
+    }
+
+    /// Record the number of transactions included in a built batch.
+    pub(crate) fn record_batch_txs(&self, num_txs: usize) {
+        self.txs_per_batch.observe(num_txs as f64);
+        let count = self.txs_per_batch.get_sample_count();
+        if count > 0 {
+            self.avg_txs_per_block.set(self.txs_per_batch.get_sample_sum() / count as f64);
+        }
+    }
+
+    /// Record the transaction selection stats for a built batch.
+    pub(crate) fn record_build_stats(&self, stats: &BatchBuildStats) {
+        self.pool_lock_duration.observe(stats.pool_lock_duration.as_secs_f64());
+        self.txs_considered.inc_by(stats.txs_considered as u64);
+        self.txs_included.inc_by(stats.txs_included as u64);
+        self.txs_invalid_gas.inc_by(stats.txs_invalid_gas as u64);
+        self.txs_invalid_bytes.inc_by(stats.txs_invalid_bytes as u64);
+        self.block_gas_utilization.observe(stats.gas_utilization());
+        self.block_size_utilization.observe(stats.size_utilization());
+    }
+}
+
+impl Default for BatchBuilderMetrics {
+    fn default() -> Self {
+        // try_new() should not fail except under certain conditions with testing (see comment
+        // below). This pushes the panic or retry decision lower and supporting try_new
+        // allways a user to deal with errors if desired (have a non-panic option).
+        // We always want do use default_registry() when not in test.
+        match Self::try_new(default_registry(), WorkerId::default()) {
+            Ok(metrics) => metrics,
+            Err(e) => {
+                tracing::warn!(target: "tn::metrics", ?e, "BatchBuilderMetrics::try_new metrics error");
+                // If we are in a test then don't panic on prometheus errors (usually an already
+                // registered error) but try again with a new Registry. This is not
+                // great for prod code, however should not happen, but will happen in tests due to
+                // how Rust runs them so lets just gloss over it. cfg(test) does not
+                // always work as expected.
+                Self::try_new(&Registry::new(), WorkerId::default())
+                    .expect("Prometheus error, are you using it wrong?")
+            }
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! Transaction pool limits for the worker.
+//!
+//! The pending pool is the only sub-pool considered for the next batch. Under sustained spam the
+//! pending pool can grow large, so the worker caps the number of pending transactions and evicts
+//! transactions based on the configured [PoolEvictionPolicy].
+//!
+//! Queued transactions (ie - nonce gaps that are never filled) are never built into a batch. The
+//! worker evicts queued transactions once they exceed the configured max lifetime to bound the
+//! queued pool's growth.
+//!
+//! Transactions are also filtered at admission. Transactions paying less than the worker's minimum
+//! priority fee are rejected by the [MinPriorityFeeValidator] instead of being accepted and never
+//! included in a batch. Operators can supply a [TransactionValidationHook] for additional checks
+//! (ie - blocked recipients) that run through the [HookedTransactionValidator].
+//!
+//! Transactions included in a batch stay in the pool until the batch reaches quorum. The
+//! configured [IncludedTxPropagation] policy controls whether these transactions are still
+//! propagated to peers while the batch waits for quorum.
+
+use crate::error::BatchBuilderError;
+use reth_transaction_pool::{
+    error::InvalidPoolTransactionError, PoolConfig, PoolTransaction, TransactionOrigin,
+    TransactionPool, TransactionValidationOutcome, TransactionValidator,
+};
+use std::{sync::Arc, time::Duration};
+use tn_config::{IncludedTxPropagation, PoolEvictionPolicy};
+use tn_types::{SealedBlock, TxHash};
+use tracing::debug;
+
+/// Apply the worker's pending pool limits to the pool config.
+///
+/// The pool config should be applied before the pool is constructed.
+pub fn apply_pending_pool_limits(
+    pool_config: &mut PoolConfig,
+    max_pending_pool_txs: usize,
+    eviction_policy: PoolEvictionPolicy,
+) {
+    debug!(target: "block-builder", ?max_pending_pool_txs, ?eviction_policy, "applying pending pool limits");
+    pool_config.pending_limit.max_txs = max_pending_pool_txs;
+
+    match eviction_policy {
+        // the pool orders pending transactions by the fee paid to the beneficiary and truncates
+        // the lowest priority transactions first when the pending limit is exceeded
+        PoolEvictionPolicy::LowestFeeFirst => (),
+    }
+}
+
+/// Apply the capacity for the pool's pending transaction listener channels.
+///
+/// The pool notifies listeners (ie - the [BatchBuilder](crate::BatchBuilder)) with the hash of each
+/// transaction that becomes pending. The pool never waits for listeners, so notifications are
+/// dropped while a listener's channel is full.
+pub fn apply_pending_tx_listener_capacity(pool_config: &mut PoolConfig, capacity: usize) {
+    debug!(target: "block-builder", ?capacity, "applying pending tx listener capacity");
+    pool_config.pending_tx_listener_buffer_size = capacity;
+}
+
+/// Remove queued transactions that have been in the pool longer than `max_lifetime`.
+///
+/// The age of a transaction is measured from when it was added to the pool. Pending transactions
+/// are never evicted by this method. Returns the hashes of the evicted transactions.
+pub fn evict_expired_queued_transactions<Pool>(pool: &Pool, max_lifetime: Duration) -> Vec<TxHash>
+where
+    Pool: TransactionPool,
+{
+    let expired: Vec<TxHash> = pool
+        .queued_transactions()
+        .into_iter()
+        .filter(|tx| tx.timestamp.elapsed() > max_lifetime)
+        .map(|tx| *tx.hash())
+        .collect();
+
+    if !expired.is_empty() {
+        debug!(target: "block-builder", ?expired, ?max_lifetime, "evicting expired queued transactions");
+        pool.remove_transactions(expired.clone());
+    }
+
+    expired
+}
+
+/// Apply the [IncludedTxPropagation] policy to transactions included in a batch.
+///
+/// The pool's `propagate` flag is fixed when a transaction is validated, so suppressed
+/// transactions are removed and re-added to the pool as private transactions. Transactions are
+/// re-added in the order they were included so nonces are never left with gaps. Returns the
+/// number of transactions marked `propagate = false`.
+pub async fn apply_included_tx_propagation<Pool>(
+    pool: &Pool,
+    policy: IncludedTxPropagation,
+    included: Vec<TxHash>,
+) -> usize
+where
+    Pool: TransactionPool,
+{
+    match policy {
+        IncludedTxPropagation::Propagate => 0,
+        IncludedTxPropagation::Suppress => {
+            let suppressed: Vec<_> = pool
+                .remove_transactions(included)
+                .into_iter()
+                .map(|tx| tx.transaction.clone())
+                .collect();
+            if suppressed.is_empty() {
+                return 0;
+            }
+
+            debug!(target: "block-builder", suppressed = suppressed.len(), "suppressing propagation for included transactions");
+            pool.add_transactions(TransactionOrigin::Private, suppressed)
+                .await
+                .into_iter()
+                .filter(|res| res.is_ok())
+                .count()
+        }
+    }
+}
+
+/// Transaction validator that rejects transactions below the minimum priority fee.
+///
+/// The priority fee is checked before the transaction is passed to the inner validator. Legacy
+/// transactions use the gas price. Local and external transactions are treated the same.
+#[derive(Clone, Debug)]
+pub struct MinPriorityFeeValidator<V> {
+    /// The validator for all other transaction checks.
+    inner: V,
+    /// The minimum priority fee (in wei) a transaction must pay to enter the pool.
+    min_priority_fee: u128,
+}
+
+impl<V> MinPriorityFeeValidator<V> {
+    /// Create a new instance of Self.
+    pub fn new(inner: V, min_priority_fee: u128) -> Self {
+        Self { inner, min_priority_fee }
+    }
+
+    /// The minimum priority fee (in wei) a transaction must pay to enter the pool.
+    pub fn min_priority_fee(&self) -> u128 {
+        self.min_priority_fee
+    }
+}
+
+impl<V> TransactionValidator for MinPriorityFeeValidator<V>
+where
+    V: TransactionValidator,
+{
+    type Transaction = V::Transaction;
+
+    async fn validate_transaction(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Self::Transaction,
+    ) -> TransactionValidationOutcome<Self::Transaction> {
+        let priority_fee = transaction.priority_fee_or_price();
+        if priority_fee < self.min_priority_fee {
+            debug!(target: "block-builder", hash=?transaction.hash(), ?priority_fee, "rejecting underpriced transaction");
+            return TransactionValidationOutcome::Invalid(
+                transaction,
+                InvalidPoolTransactionError::Other(Box::new(BatchBuilderError::MinPriorityFee(
+                    priority_fee,
+                    self.min_priority_fee,
+                ))),
+            );
+        }
+
+        self.inner.validate_transaction(origin, transaction).await
+    }
+
+    fn on_new_head_block(&self, new_tip_block: &SealedBlock) {
+        self.inner.on_new_head_block(new_tip_block)
+    }
+}
+
+/// Additional transaction checks supplied by the operator.
+///
+/// The hook runs before all other validation when a transaction is added to the worker's pool.
+/// Returning an error rejects the transaction with the reason.
+pub trait TransactionValidationHook<T>: Send + Sync + std::fmt::Debug {
+    /// Validate the transaction before it enters the pool.
+    fn validate(&self, origin: TransactionOrigin, transaction: &T) -> Result<(), String>;
+}
+
+/// Transaction validator that runs an optional [TransactionValidationHook] before the inner
+/// validator.
+#[derive(Debug)]
+pub struct HookedTransactionValidator<V: TransactionValidator> {
+    /// The validator for all other transaction checks.
+    inner: V,
+    /// The operator's hook, if any.
+    hook: Option<Arc<dyn TransactionValidationHook<V::Transaction>>>,
+}
+
+impl<V: TransactionValidator> HookedTransactionValidator<V> {
+    /// Create a new instance of Self.
+    pub fn new(inner: V, hook: Option<Arc<dyn TransactionValidationHook<V::Transaction>>>) -> Self {
+        Self { inner, hook }
+    }
+}
+
+impl<V> Clone for HookedTransactionValidator<V>
+where
+    V: TransactionValidator + Clone,
+{
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone(), hook: self.hook.clone() }
+    }
+}
+
+impl<V> TransactionValidator for HookedTransactionValidator<V>
+where
+    V: TransactionValidator,
+{
+    type Transaction = V::Transaction;
+
+    async fn validate_transaction(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Self::Transaction,
+    ) -> TransactionValidationOutcome<Self::Transaction> {
+        if let Some(hook) = &self.hook {
+            if let Err(reason) = hook.validate(origin, &transaction) {
+                debug!(target: "block-builder", hash=?transaction.hash(), ?reason, "transaction rejected by validation hook");
+                return TransactionValidationOutcome::Invalid(
+                    transaction,
+                    InvalidPoolTransactionError::Other(Box::new(
+                        BatchBuilderError::ValidationHook(reason),
+                    )),
+                );
+            }
+        }
+
+        self.inner.validate_transaction(origin, transaction).await
+    }
+
+    fn on_new_head_block(&self, new_tip_block: &SealedBlock) {
+        self.inner.on_new_head_block(new_tip_block)
+    }
+}
@@ -12,29 +12,41 @@ use reth_chainspec::ChainSpec;
 use reth_consensus::FullConsensus;
 use reth_db::test_utils::{create_test_rw_db, tempdir_path};
 use reth_db_common::init::init_genesis;
+use reth_execution_types::{Chain, ExecutionOutcome};
 use reth_node_ethereum::{EthEvmConfig, EthExecutorProvider};
 use reth_provider::{
     providers::{BlockchainProvider, StaticFileProvider},
-    CanonStateSubscriptions, ProviderFactory,
+    CanonStateNotification, CanonStateSubscriptions, ProviderFactory, StateProviderFactory,
 };
 use reth_rpc_eth_types::utils::recover_raw_transaction;
 use reth_tasks::TaskManager;
 use reth_transaction_pool::{
-    blobstore::InMemoryBlobStore, PoolConfig, TransactionPool, TransactionValidationTaskExecutor,
+    blobstore::InMemoryBlobStore, PoolConfig, TransactionOrigin, TransactionPool,
+    TransactionValidationTaskExecutor,
+};
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Arc,
+    time::Duration,
 };
-use std::{collections::VecDeque, sync::Arc, time::Duration};
 use tempfile::TempDir;
-use tn_batch_builder::{test_utils::execute_test_batch, BatchBuilder};
+use tn_batch_builder::{build_batch, test_utils::execute_test_batch, BatchBuilder};
 use tn_batch_validator::BatchValidator;
+use tn_config::{IncludedTxPropagation, Parameters};
 use tn_engine::execute_consensus_output;
 use tn_network_types::{local::LocalNetwork, MockWorkerToPrimary};
 use tn_node_traits::{BuildArguments, TNExecution, TelcoinNode};
 use tn_storage::{open_db, tables::Batches};
-use tn_test_utils::{get_gas_price, test_genesis, TransactionFactory};
+use tn_test_utils::{
+    capture_test_tracing, get_gas_price, test_genesis, MultiAccountFactory, TransactionFactory,
+    WorkerTestHarness,
+};
 use tn_types::{
-    Address, Batch, BatchValidation, BlockBody, Bytes, Certificate, CommittedSubDag,
-    ConsensusHeader, ConsensusOutput, Database, Encodable2718 as _, LastCanonicalUpdate,
-    ReputationScores, SealedBatch, SealedBlock, TransactionSigned, U160, U256,
+    keccak256, max_batch_gas, max_batch_size, transaction_size, Address, Batch, BatchBuilderArgs,
+    BatchValidation, BlockBody, Bytes, Certificate, CommittedSubDag, ConsensusHeader,
+    ConsensusOutput, Database, Encodable2718 as _, ExecHeader, LastCanonicalUpdate,
+    PendingBlockConfig, ReputationScores, SealedBatch, SealedBlock, SealedBlockWithSenders,
+    SealedHeader, SignedTransactionIntoRecoveredExt as _, TransactionSigned, U160, U256,
 };
 use tn_worker::{
     metrics::WorkerMetrics,
@@ -87,123 +99,47 @@ async fn test_make_batch_el_to_cl() {
     //=== Execution Layer
     //
 
-    // adiri genesis with TxFactory funded
-    let genesis = test_genesis();
-
-    // let genesis = genesis.extend_accounts(account);
-    let head_timestamp = genesis.timestamp;
-    let chain: Arc<ChainSpec> = Arc::new(genesis.into());
-
-    // temp db
-    let db = create_test_rw_db();
-
-    // provider
-    let factory = ProviderFactory::new(
-        Arc::clone(&db),
-        Arc::clone(&chain),
-        StaticFileProvider::read_write(tempdir_path())
-            .expect("static file provider read write created with tempdir path"),
-    );
-
-    let genesis_hash = init_genesis(&factory).expect("init genesis");
-    let blockchain_db: BlockchainProvider<TelcoinNode<_>> =
-        BlockchainProvider::new(factory, Arc::new(NoopBlockchainTree::default()))
-            .expect("test blockchain provider");
-
-    debug!("genesis hash: {genesis_hash:?}");
-
-    // task manger
-    let manager = TaskManager::current();
-    let executor = manager.executor();
-
-    // TODO: abstract the txpool creation to call in engine::inner and here
-    //
-    // txpool
-    let blob_store = InMemoryBlobStore::default();
-    let validator = TransactionValidationTaskExecutor::eth_builder(Arc::clone(&chain))
-        .with_head_timestamp(head_timestamp)
-        .with_additional_tasks(1)
-        .build_with_tasks(blockchain_db.clone(), executor, blob_store.clone());
-
-    let txpool =
-        reth_transaction_pool::Pool::eth_pool(validator, blob_store, PoolConfig::default());
     let address = Address::from(U160::from(333));
-    let tx_pool_latest = txpool.block_info();
-    let tip = SealedBlock::new(chain.sealed_genesis_header(), BlockBody::default());
+    let mut harness = WorkerTestHarness::builder().with_beneficiary(address).build();
 
-    let latest_canon_state = LastCanonicalUpdate {
-        tip, // genesis
-        pending_block_base_fee: tx_pool_latest.pending_basefee,
-        pending_block_blob_fee: tx_pool_latest.pending_blob_fee,
-    };
+    // don't build until every transaction is pending
+    harness.handle.pause();
 
-    // build execution batch proposer
-    let batch_builder = BatchBuilder::new(
-        blockchain_db.clone(),
-        txpool.clone(),
-        blockchain_db.canonical_state_stream(),
-        latest_canon_state,
-        batch_provider.batches_tx(),
-        address,
-        Duration::from_secs(1),
-    );
-
-    let gas_price = get_gas_price(&blockchain_db);
+    let gas_price = get_gas_price(&harness.blockchain_db);
     let value = U256::from(10).checked_pow(U256::from(18)).expect("1e18 doesn't overflow U256");
-    let mut tx_factory = TransactionFactory::new();
 
     // create 3 transactions
-    let transaction1 = tx_factory.create_eip1559(
-        chain.clone(),
-        None,
-        gas_price,
-        Some(Address::ZERO),
-        value, // 1 TEL
-        Bytes::new(),
-    );
-    debug!("transaction 1: {transaction1:?}");
-
-    let transaction2 = tx_factory.create_eip1559(
-        chain.clone(),
-        None,
-        gas_price,
-        Some(Address::ZERO),
-        value, // 1 TEL
-        Bytes::new(),
-    );
-    debug!("transaction 2: {transaction2:?}");
-
-    let transaction3 = tx_factory.create_eip1559(
-        chain.clone(),
-        None,
-        gas_price,
-        Some(Address::ZERO),
-        value, // 1 TEL
-        Bytes::new(),
-    );
-    debug!("transaction 3: {transaction3:?}");
-
-    let added_result = tx_factory.submit_tx_to_pool(transaction1.clone(), txpool.clone()).await;
-    assert_matches!(added_result, hash if hash == transaction1.hash());
-
-    let added_result = tx_factory.submit_tx_to_pool(transaction2.clone(), txpool.clone()).await;
-    assert_matches!(added_result, hash if hash == transaction2.hash());
-
-    let added_result = tx_factory.submit_tx_to_pool(transaction3.clone(), txpool.clone()).await;
-    assert_matches!(added_result, hash if hash == transaction3.hash());
+    let mut transactions = Vec::new();
+    for _ in 0..3 {
+        let transaction = harness.tx_factory.create_eip1559(
+            harness.chain.clone(),
+            None,
+            gas_price,
+            Some(Address::ZERO),
+            value, // 1 TEL
+            Bytes::new(),
+        );
+        debug!("transaction: {transaction:?}");
+        let added_result =
+            harness.tx_factory.submit_tx_to_pool(transaction.clone(), harness.txpool.clone()).await;
+        assert_matches!(added_result, hash if hash == transaction.hash());
+        transactions.push(transaction);
+    }
 
     // txpool size
-    let pending_pool_len = txpool.pool_size().pending;
-    debug!("pool_size(): {:?}", txpool.pool_size());
+    let pending_pool_len = harness.txpool.pool_size().pending;
+    debug!("pool_size(): {:?}", harness.txpool.pool_size());
     assert_eq!(pending_pool_len, 3);
 
-    // spawn batch_builder once worker is ready
-    let _batch_builder = tokio::spawn(Box::pin(batch_builder));
-
     //
     //=== Test batch flow
     //
 
+    // forward the batch to the worker
+    harness.handle.resume();
+    let built = harness.next_batch(Duration::from_secs(5)).await;
+    batch_provider.batches_tx().send(built).await.expect("worker received batch");
+
     // wait for new batch
     let mut sealed_batch = None;
     for _ in 0..5 {
@@ -217,18 +153,14 @@ async fn test_make_batch_el_to_cl() {
     let sealed_batch = sealed_batch.unwrap();
 
     // ensure batch validator succeeds
-    let batch_validator = BatchValidator::new(blockchain_db.clone());
+    let batch_validator = BatchValidator::new(harness.blockchain_db.clone());
 
     let valid_batch_result = batch_validator.validate_batch(sealed_batch.clone());
     assert!(valid_batch_result.is_ok());
 
     // ensure expected transaction is in batch
     let expected_batch = Batch {
-        transactions: vec![
-            transaction1.encoded_2718(),
-            transaction2.encoded_2718(),
-            transaction3.encoded_2718(),
-        ],
+        transactions: transactions.iter().map(|tx| tx.encoded_2718()).collect(),
         received_at: None,
         ..*sealed_batch.batch()
     }
@@ -251,8 +183,8 @@ async fn test_make_batch_el_to_cl() {
 
     // txpool should be empty after mining
     // test_make_batch_no_ack_txs_in_pool_still tests for txs in pool without mining event
-    let pending_pool_len = txpool.pool_size().pending;
-    debug!("pool_size(): {:?}", txpool.pool_size());
+    let pending_pool_len = harness.txpool.pool_size().pending;
+    debug!("pool_size(): {:?}", harness.txpool.pool_size());
     assert_eq!(pending_pool_len, 0);
 }
 
@@ -671,3 +603,519 @@ async fn test_canonical_notification_updates_pool() {
     assert_eq!(pool_size.queued, 0);
     assert_eq!(pool_size.pending, 0);
 }
+
+/// Create a transaction with `input_len` bytes of calldata.
+fn create_tx_with_input(
+    tx_factory: &mut TransactionFactory,
+    chain: &ChainSpec,
+    nonce: u64,
+    gas_price: u128,
+    input_len: usize,
+) -> TransactionSigned {
+    tx_factory.create_explicit_eip1559(
+        Some(chain.chain.id()),
+        Some(nonce),
+        None,                                    // no tip
+        Some(gas_price),                         // basefee for block 1
+        Some(1_000_000),                         // enough gas for calldata
+        Some(Address::ZERO),                     // send to zero address
+        Some(U256::ZERO),                        // no transfer
+        Some(Bytes::from(vec![0u8; input_len])), // calldata to fill batch
+        None,                                    // no access list
+    )
+}
+
+/// Build a batch with transactions totaling exactly the max batch size and validate it on a peer.
+#[tokio::test]
+async fn test_batch_at_max_size_validates_on_peer() {
+    // local worker and a peer with the same genesis
+    //
+    // batches are built directly, so the harness batch builders are paused
+    let mut harness = WorkerTestHarness::new();
+    harness.handle.pause();
+    let peer = WorkerTestHarness::new();
+    peer.handle.pause();
+
+    let chain = harness.chain.clone();
+    let txpool = harness.txpool.clone();
+    let gas_price = get_gas_price(&harness.blockchain_db);
+    let tx_factory = &mut harness.tx_factory;
+    let max_size = max_batch_size(0);
+
+    // fill most of the batch with large transactions
+    let input_len = 99_000;
+    let mut expected_hashes = Vec::new();
+    let mut total_bytes = 0;
+    let mut nonce = 0;
+    for _ in 0..9 {
+        let tx = create_tx_with_input(tx_factory, &chain, nonce, gas_price, input_len);
+        total_bytes += transaction_size(&tx.encoded_2718());
+        let hash = tx_factory.submit_tx_to_pool(tx, txpool.clone()).await;
+        expected_hashes.push(hash);
+        nonce += 1;
+    }
+
+    // size the last transaction's calldata so the batch is exactly the max size
+    //
+    // signature encoding lengths can vary, so adjust until the size is exact
+    let remaining = max_size - total_bytes;
+    let mut last_input_len = remaining - (total_bytes / 9 - input_len);
+    let last_tx = loop {
+        let tx = create_tx_with_input(tx_factory, &chain, nonce, gas_price, last_input_len);
+        let size = transaction_size(&tx.encoded_2718());
+        if size == remaining {
+            break tx;
+        }
+        last_input_len = last_input_len + remaining - size;
+    };
+    expected_hashes.push(tx_factory.submit_tx_to_pool(last_tx, txpool.clone()).await);
+    nonce += 1;
+
+    // this transaction doesn't fit in the batch
+    let overflow_tx = create_tx_with_input(tx_factory, &chain, nonce, gas_price, 0);
+    tx_factory.submit_tx_to_pool(overflow_tx, txpool.clone()).await;
+    assert_eq!(txpool.pool_size().pending, 11);
+
+    // build the batch
+    let tx_pool_latest = txpool.block_info();
+    let parent_info = LastCanonicalUpdate {
+        tip: SealedBlock::new(chain.sealed_genesis_header(), BlockBody::default()),
+        pending_block_base_fee: tx_pool_latest.pending_basefee,
+        pending_block_blob_fee: tx_pool_latest.pending_blob_fee,
+    };
+    let batch_config = PendingBlockConfig::new(Address::from(U160::from(333)), parent_info);
+    let output = build_batch(BatchBuilderArgs::new(txpool.clone(), batch_config));
+
+    // the batch is exactly the max size and excludes the overflow transaction
+    let batch = output.batch().clone();
+    let batch_bytes: usize = batch.transactions().iter().map(|tx| transaction_size(tx)).sum();
+    assert_eq!(batch_bytes, max_size);
+    assert_eq!(output.mined_transactions(), expected_hashes.as_slice());
+
+    // peer validates the batch
+    let peer_validator = BatchValidator::new(peer.blockchain_db.clone());
+    assert!(peer_validator.validate_batch(batch.seal_slow()).is_ok());
+}
+
+/// Pending transactions are not built into batches while the batch builder is paused.
+#[tokio::test]
+async fn test_paused_batch_builder_resumes_building() {
+    let mut harness = WorkerTestHarness::new();
+
+    // pause before any transactions are pending
+    harness.handle.pause();
+    assert!(harness.handle.is_paused());
+
+    let value = U256::from(10).checked_pow(U256::from(18)).expect("1e18 doesn't overflow U256");
+    let expected_tx_hash = harness.submit_transfer(Address::ZERO, value).await;
+    assert_eq!(harness.txpool.pool_size().pending, 1);
+
+    // no batch is built while paused - wait longer than the max batch delay
+    let res = timeout(Duration::from_secs(3), harness.from_batch_builder.recv()).await;
+    assert!(res.is_err(), "batch built while paused");
+    assert_eq!(harness.txpool.pool_size().pending, 1);
+
+    // resume and the pending transaction is built into the next batch
+    harness.handle.resume();
+    assert!(!harness.handle.is_paused());
+    let (batch, ack) = harness.next_batch(Duration::from_secs(5)).await;
+    let _ = ack.send(Ok(()));
+
+    let tx_bytes = batch.batch().transactions().first().expect("batch has one transaction");
+    let tx =
+        recover_raw_transaction::<TransactionSigned>(tx_bytes).expect("recover raw tx for test");
+    assert_eq!(tx.hash(), expected_tx_hash);
+}
+
+/// Workers with configured beneficiaries build batches crediting their own address.
+#[tokio::test]
+async fn test_worker_beneficiaries_credit_configured_addresses() {
+    // configure a beneficiary for worker 0 and 1 - worker 2 uses the validator's address
+    let validator_address = Address::from(U160::from(333));
+    let mut parameters = Parameters::default();
+    parameters.worker_beneficiaries.insert(0, Address::from(U160::from(1)));
+    parameters.worker_beneficiaries.insert(1, Address::from(U160::from(2)));
+
+    let value = U256::from(10).checked_pow(U256::from(18)).expect("1e18 doesn't overflow U256");
+
+    for worker_id in 0..3 {
+        // each worker has it's own pool
+        let beneficiary = parameters.worker_beneficiary(&worker_id, validator_address);
+        let mut harness = WorkerTestHarness::builder()
+            .with_worker_id(worker_id)
+            .with_beneficiary(beneficiary)
+            .build();
+
+        let _ = harness.submit_transfer(Address::ZERO, value).await;
+        let (batch, ack) = harness.next_batch(Duration::from_secs(5)).await;
+        let _ = ack.send(Ok(()));
+
+        let expected = match worker_id {
+            0 => Address::from(U160::from(1)),
+            1 => Address::from(U160::from(2)),
+            _ => validator_address,
+        };
+        assert_eq!(batch.batch().beneficiary, expected);
+    }
+}
+
+/// The worker test harness builds a batch with a submitted transaction.
+#[tokio::test]
+async fn test_worker_harness_builds_batch() {
+    let recipient = Address::random();
+    let mut harness = WorkerTestHarness::builder()
+        .with_funded_account(recipient, U256::from(1))
+        .with_pool_config(PoolConfig { max_account_slots: 4, ..Default::default() })
+        .with_max_batch_delay(Duration::from_millis(500))
+        .build();
+
+    // the additional account is funded at genesis
+    let state = harness.blockchain_db.latest().expect("latest state");
+    assert_eq!(state.account_balance(&recipient).expect("state read"), Some(U256::from(1)));
+
+    let value = U256::from(10).checked_pow(U256::from(18)).expect("1e18 doesn't overflow U256");
+    let expected_tx_hash = harness.submit_transfer(recipient, value).await;
+    assert_eq!(harness.txpool.pool_size().pending, 1);
+
+    let (batch, ack) = harness.next_batch(Duration::from_secs(5)).await;
+    let _ = ack.send(Ok(()));
+
+    let batch_validator = BatchValidator::new(harness.blockchain_db.clone());
+    assert!(batch_validator.validate_batch(batch.clone()).is_ok());
+
+    let tx_bytes = batch.batch().transactions().first().expect("batch has one transaction");
+    let tx =
+        recover_raw_transaction::<TransactionSigned>(tx_bytes).expect("recover raw tx for test");
+    assert_eq!(tx.hash(), expected_tx_hash);
+}
+
+/// The propagate flag of included transactions reflects the configured policy until the batch
+/// reaches quorum and the transactions are mined.
+#[tokio::test]
+async fn test_included_tx_propagation_policy() {
+    for (policy, expected_propagate) in
+        [(IncludedTxPropagation::Propagate, true), (IncludedTxPropagation::Suppress, false)]
+    {
+        let mut harness = WorkerTestHarness::builder()
+            .with_max_batch_delay(Duration::from_millis(500))
+            .with_included_tx_propagation(policy)
+            .build();
+
+        // submitted transactions are propagated before they are included
+        let tx_hash = harness.submit_transfer(Address::ZERO, U256::from(1)).await;
+        assert!(harness.txpool.get(&tx_hash).expect("tx in pool").propagate);
+
+        let (batch, ack) = harness.next_batch(Duration::from_secs(5)).await;
+        assert_eq!(keccak256(&batch.batch().transactions()[0]), tx_hash);
+
+        // the policy is applied before the batch is forwarded
+        let included = harness.txpool.get(&tx_hash).expect("included tx in pool until quorum");
+        assert_eq!(included.propagate, expected_propagate, "{policy:?}");
+        assert_eq!(harness.txpool.pool_size().pending, 1);
+
+        // the mined transaction is removed once the batch reaches quorum
+        let _ = ack.send(Ok(()));
+        timeout(Duration::from_secs(5), async {
+            while harness.txpool.contains(&tx_hash) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("mined tx removed from pool");
+    }
+}
+
+/// Batches are not built until enough peers are connected to reach quorum.
+#[tokio::test]
+async fn test_batch_builder_waits_for_min_peers() {
+    let mut harness = WorkerTestHarness::builder()
+        .with_max_batch_delay(Duration::from_millis(500))
+        .with_min_peers_to_build(2)
+        .build();
+
+    let expected_tx_hash = harness.submit_transfer(Address::ZERO, U256::from(1)).await;
+    assert_eq!(harness.txpool.pool_size().pending, 1);
+
+    // no batch is built without peers - wait longer than the max batch delay
+    let res = timeout(Duration::from_secs(2), harness.from_batch_builder.recv()).await;
+    assert!(res.is_err(), "batch built without peers");
+
+    // still below the threshold
+    harness.connected_peers.send_replace(1);
+    let res = timeout(Duration::from_secs(2), harness.from_batch_builder.recv()).await;
+    assert!(res.is_err(), "batch built with insufficient peers");
+    assert_eq!(harness.txpool.pool_size().pending, 1);
+
+    // building proceeds once the peer count crosses the threshold
+    harness.connected_peers.send_replace(2);
+    let (batch, ack) = harness.next_batch(Duration::from_secs(5)).await;
+    let _ = ack.send(Ok(()));
+
+    let tx_bytes = batch.batch().transactions().first().expect("batch has one transaction");
+    let tx =
+        recover_raw_transaction::<TransactionSigned>(tx_bytes).expect("recover raw tx for test");
+    assert_eq!(tx.hash(), expected_tx_hash);
+}
+
+/// Logs emitted while building a batch carry the worker id and the batch's block number.
+#[tokio::test]
+async fn test_batch_builder_logs_carry_worker_context() {
+    let (capture, _guard) = capture_test_tracing();
+    let mut harness = WorkerTestHarness::builder()
+        .with_max_batch_delay(Duration::from_millis(500))
+        .with_worker_id(3)
+        .build();
+
+    let _ = harness.submit_transfer(Address::ZERO, U256::from(1)).await;
+    let (batch, ack) = harness.next_batch(Duration::from_secs(5)).await;
+    // the batch is built on genesis
+    assert_eq!(batch.batch().parent_hash, harness.chain.sealed_genesis_header().hash());
+    let _ = ack.send(Ok(()));
+
+    // wait for the spawned task to log the ack
+    let mut ack_events = Vec::new();
+    for _ in 0..50 {
+        ack_events = capture.lines_containing("received ack");
+        if !ack_events.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(!ack_events.is_empty(), "ack event not captured");
+
+    // events from the spawned task inherit the batch builder's span
+    for event in ack_events {
+        assert!(event.contains("worker_id=3"), "missing worker id: {event}");
+        assert!(event.contains("next_block_number=1"), "missing block number: {event}");
+    }
+
+    // events from the poll loop also carry the context
+    let poll_events = capture.lines_containing("batch_builder{");
+    assert!(!poll_events.is_empty());
+    assert!(poll_events.iter().all(|event| event.contains("worker_id=3")));
+}
+
+/// Queued transactions are evicted from the pool once they exceed the max lifetime.
+#[tokio::test]
+async fn test_expired_queued_transactions_evicted() {
+    let mut harness =
+        WorkerTestHarness::builder().with_max_queued_tx_lifetime(Duration::from_secs(1)).build();
+
+    // skip a nonce so the transaction is queued
+    harness.tx_factory.set_nonce(1);
+    let queued_tx_hash = harness.submit_transfer(Address::ZERO, U256::from(1)).await;
+    assert_eq!(harness.txpool.pool_size().queued, 1);
+    assert!(harness.txpool.contains(&queued_tx_hash));
+
+    // the transaction is still queued before the lifetime elapses
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(harness.txpool.pool_size().queued, 1);
+
+    // the transaction is evicted after the lifetime elapses
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert_eq!(harness.txpool.pool_size().queued, 0);
+    assert!(!harness.txpool.contains(&queued_tx_hash));
+
+    // no batch was built from the queued transaction
+    assert!(harness.from_batch_builder.try_recv().is_err());
+}
+
+/// A max queued transaction lifetime of zero never evicts queued transactions.
+#[tokio::test]
+async fn test_zero_queued_transaction_lifetime_ignored() {
+    let mut harness =
+        WorkerTestHarness::builder().with_max_queued_tx_lifetime(Duration::ZERO).build();
+
+    // skip a nonce so the transaction is queued
+    harness.tx_factory.set_nonce(1);
+    let queued_tx_hash = harness.submit_transfer(Address::ZERO, U256::from(1)).await;
+    assert_eq!(harness.txpool.pool_size().queued, 1);
+
+    // the transaction is never evicted
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(harness.txpool.pool_size().queued, 1);
+    assert!(harness.txpool.contains(&queued_tx_hash));
+}
+
+/// Transactions with the same effective tip are included in hash order.
+#[tokio::test]
+async fn test_equal_tip_txs_included_in_hash_order() {
+    let mut tx_factory = MultiAccountFactory::new([11; 32], 3);
+    let balance = U256::from(10).checked_pow(U256::from(24)).expect("1e24 doesn't overflow U256");
+    let mut builder = WorkerTestHarness::builder().with_max_batch_delay(Duration::from_millis(500));
+    for address in tx_factory.addresses() {
+        builder = builder.with_funded_account(address, balance);
+    }
+    let mut harness = builder.build();
+
+    // don't build until every transaction is pending
+    harness.handle.pause();
+
+    // equal tip transactions from different senders
+    let gas_price = get_gas_price(&harness.blockchain_db);
+    let mut transactions: Vec<_> = (0..3)
+        .map(|_| {
+            tx_factory.next_factory().create_explicit_eip1559(
+                Some(harness.chain.chain.id()),
+                None,
+                Some(gas_price),
+                Some(gas_price),
+                None,
+                Some(Address::ZERO),
+                None,
+                None,
+                None,
+            )
+        })
+        .collect();
+
+    // submit in reverse hash order so the pool's arrival order is the opposite
+    transactions.sort_by_key(|tx| std::cmp::Reverse(tx.hash()));
+    let mut expected = Vec::new();
+    for tx in transactions {
+        let recovered = tx
+            .try_into_pooled()
+            .expect("tx valid for pool")
+            .try_into_ecrecovered()
+            .expect("tx is recovered");
+        let hash = harness
+            .txpool
+            .add_transaction(TransactionOrigin::Local, recovered.into())
+            .await
+            .expect("tx added to pool");
+        expected.push(hash);
+    }
+    expected.sort();
+    assert_eq!(harness.txpool.pool_size().pending, 3);
+
+    harness.handle.resume();
+    let (batch, ack) = harness.next_batch(Duration::from_secs(5)).await;
+    let _ = ack.send(Ok(()));
+
+    let included: Vec<_> = batch
+        .batch()
+        .transactions()
+        .iter()
+        .map(|tx_bytes| {
+            recover_raw_transaction::<TransactionSigned>(tx_bytes)
+                .expect("recover raw tx for test")
+                .hash()
+        })
+        .collect();
+    assert_eq!(included, expected);
+}
+
+/// The next block number reported by the handle follows the canonical tip.
+#[tokio::test]
+async fn test_next_block_number_advances_with_canonical_tip() {
+    let harness = WorkerTestHarness::new();
+
+    // the next batch extends genesis
+    assert_eq!(harness.handle.next_block_number(), 1);
+
+    // canonical update raises the tip
+    let genesis = harness.chain.sealed_genesis_header();
+    let header = ExecHeader {
+        parent_hash: genesis.hash(),
+        number: 1,
+        timestamp: genesis.timestamp + 1,
+        ..genesis.header().clone()
+    };
+    let tip = SealedBlockWithSenders::new(
+        SealedBlock::new(SealedHeader::seal(header), BlockBody::default()),
+        vec![],
+    )
+    .expect("sealed block with senders");
+    let new = Arc::new(Chain::new(vec![tip], ExecutionOutcome::default(), None));
+    harness
+        .canon_state_notifications
+        .send(CanonStateNotification::Commit { new })
+        .expect("batch builder subscribed to canonical updates");
+
+    // the handle observes the update once the batch builder applies it
+    for _ in 0..50 {
+        if harness.handle.next_block_number() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(harness.handle.next_block_number(), 2);
+}
+
+/// Concurrent batches can wait for quorum at the same time.
+#[tokio::test]
+async fn test_concurrent_builds_in_flight_before_ack() {
+    let mut tx_factory = MultiAccountFactory::new([10; 32], 2);
+    let balance = U256::from(10).checked_pow(U256::from(24)).expect("1e24 doesn't overflow U256");
+    let mut builder = WorkerTestHarness::builder().with_max_concurrent_builds(2);
+    for address in tx_factory.addresses() {
+        builder = builder.with_funded_account(address, balance);
+    }
+    let mut harness = builder.build();
+
+    // don't build until every transaction is pending
+    harness.handle.pause();
+
+    // pending transactions from two senders that can't fit in the same batch
+    let chain = harness.chain.clone();
+    let batch_gas = max_batch_gas(chain.sealed_genesis_header().timestamp);
+    let gas_price = get_gas_price(&harness.blockchain_db);
+    let mut expected = HashSet::new();
+    for index in 0..2 {
+        let tx = tx_factory.next_factory().create_explicit_eip1559(
+            Some(chain.chain.id()),
+            None,
+            None,
+            Some(gas_price),
+            Some(batch_gas),
+            Some(Address::ZERO),
+            None,
+            None,
+            None,
+        );
+        expected.insert(
+            tx_factory.factories()[index].submit_tx_to_pool(tx, harness.txpool.clone()).await,
+        );
+    }
+
+    harness.handle.resume();
+    let duration = Duration::from_secs(5);
+
+    // two batches are proposed before either is acked
+    let mut acks = Vec::new();
+    let mut included = HashSet::new();
+    for _ in 0..2 {
+        let (batch, ack) = harness.next_batch(duration).await;
+        assert_eq!(batch.batch().transactions().len(), 1);
+        included.insert(keccak256(&batch.batch().transactions()[0]));
+        acks.push(ack);
+    }
+
+    // each batch has a transaction from a different sender
+    assert_eq!(included, expected);
+
+    // no more batches are built while both are waiting for quorum
+    let next_tx = tx_factory.next_factory().create_explicit_eip1559(
+        Some(chain.chain.id()),
+        None,
+        None,
+        Some(gas_price),
+        None,
+        Some(Address::ZERO),
+        None,
+        None,
+        None,
+    );
+    let next_hash =
+        tx_factory.factories()[0].submit_tx_to_pool(next_tx, harness.txpool.clone()).await;
+    assert!(timeout(Duration::from_millis(500), harness.from_batch_builder.recv()).await.is_err());
+    assert_eq!(harness.txpool.pool_size().pending, 3);
+
+    // the next batch is built once the in-flight batches reach quorum
+    for ack in acks {
+        let _ = ack.send(Ok(()));
+    }
+    let (batch, _ack) = harness.next_batch(duration).await;
+    assert_eq!(batch.batch().transactions().len(), 1);
+    assert_eq!(keccak256(&batch.batch().transactions()[0]), next_hash);
+}
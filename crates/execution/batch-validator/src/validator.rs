@@ -4,12 +4,15 @@ use rayon::iter::{IntoParallelRefIterator as _, ParallelIterator as _};
 use reth_node_types::NodeTypesWithDB;
 use reth_provider::{
     providers::{BlockchainProvider, TreeNodeTypes},
-    BlockIdReader, HeaderProvider,
+    BlockIdReader, ChainStateBlockReader as _, DatabaseProviderFactory as _, HeaderProvider,
+    ProviderError, StateProviderBox, StateProviderFactory,
 };
 use reth_rpc_eth_types::utils::recover_raw_transaction;
+use std::collections::HashSet;
 use tn_types::{
-    max_batch_gas, max_batch_size, BatchValidation, BatchValidationError, BlockHash, ExecHeader,
-    SealedBatch, TransactionSigned, TransactionTrait as _, PARALLEL_SENDER_RECOVERY_THRESHOLD,
+    max_batch_gas, max_batch_size, transaction_size, BatchValidation, BatchValidationError,
+    BlockHash, Epoch, ExecHeader, SealedBatch, TimestampGranularity, TransactionSigned,
+    TransactionTrait as _, PARALLEL_SENDER_RECOVERY_THRESHOLD,
 };
 
 /// Type convenience for implementing block validation errors.
@@ -23,6 +26,27 @@ where
 {
     /// Database provider to encompass tree and provider factory.
     blockchain_db: BlockchainProvider<N>,
+    /// Batch gas limits accepted from peers in addition to the protocol's limit.
+    ///
+    /// This allows batches built with either the old or new gas limit to validate while a
+    /// network gas limit change is in progress.
+    accepted_gas_limits: HashSet<u64>,
+    /// Validate each transaction against the parent state.
+    ///
+    /// Reading sender accounts is expensive, so this is opt-in.
+    strict: bool,
+    /// The unit for batch timestamps.
+    ///
+    /// Parent timestamps are always seconds and are converted before comparing.
+    timestamp_granularity: TimestampGranularity,
+    /// The number of blocks behind the finalized block that the node retains state for.
+    ///
+    /// `None` if the node does not prune state.
+    retained_state_depth: Option<u64>,
+    /// The current epoch.
+    ///
+    /// Batches built in other epochs are rejected.
+    epoch: Epoch,
 }
 
 impl<N> BatchValidation for BatchValidator<N>
@@ -40,7 +64,8 @@ where
             return Err(BatchValidationError::InvalidDigest);
         }
 
-        // TODO: validate individual transactions against parent
+        // reject batches replayed from another epoch
+        self.validate_epoch(batch.epoch)?;
 
         // obtain info for validation
         let transactions = batch.transactions();
@@ -70,6 +95,9 @@ where
         // validate timestamp vs parent
         self.validate_against_parent_timestamp(batch.timestamp, &parent)?;
 
+        // reject batches built on state this node no longer retains
+        self.validate_parent_retained(&parent)?;
+
         // validate batch size (bytes)
         self.validate_batch_size_bytes(transactions, batch.timestamp)?;
 
@@ -79,10 +107,24 @@ where
         // validate gas limit
         self.validate_batch_gas(&decoded_txs, batch.timestamp)?;
 
+        // validate individual transactions against parent
+        if self.strict {
+            self.validate_transactions_against_parent(transactions, &parent, digest)?;
+        }
+
         // no-op
         self.validate_basefee()?;
         Ok(())
     }
+
+    /// Validate peer batches in parallel.
+    ///
+    /// Each batch's validation is independent given the canonical tip, so batches are validated
+    /// on the rayon thread pool. This is useful when many batches are received at once (ie -
+    /// during sync).
+    fn validate_batches(&self, batches: &[SealedBatch]) -> Vec<BatchValidationResult<()>> {
+        batches.par_iter().map(|batch| self.validate_batch(batch.clone())).collect()
+    }
 }
 
 impl<N> BatchValidator<N>
@@ -91,21 +133,92 @@ where
 {
     /// Create a new instance of [Self]
     pub fn new(blockchain_db: BlockchainProvider<N>) -> Self {
-        Self { blockchain_db }
+        Self {
+            blockchain_db,
+            accepted_gas_limits: HashSet::new(),
+            strict: false,
+            timestamp_granularity: TimestampGranularity::default(),
+            retained_state_depth: None,
+            epoch: 0,
+        }
+    }
+
+    /// Set the number of blocks behind the finalized block that the node retains state for.
+    ///
+    /// Batches with parents older than this depth fail with [BatchValidationError::StatePruned].
+    pub fn with_retained_state_depth(mut self, retained_state_depth: Option<u64>) -> Self {
+        self.retained_state_depth = retained_state_depth;
+        self
+    }
+
+    /// Set the current epoch.
+    ///
+    /// Batches built in any other epoch fail with [BatchValidationError::WrongEpoch].
+    pub fn with_epoch(mut self, epoch: Epoch) -> Self {
+        self.epoch = epoch;
+        self
+    }
+
+    /// Set the unit for batch timestamps.
+    ///
+    /// This must match the granularity used by the network's batch builders.
+    pub fn with_timestamp_granularity(
+        mut self,
+        timestamp_granularity: TimestampGranularity,
+    ) -> Self {
+        self.timestamp_granularity = timestamp_granularity;
+        self
+    }
+
+    /// Validate each transaction's nonce and sender balance against the parent state.
+    ///
+    /// This catches batches with transactions that can never execute before they reach
+    /// consensus, at the cost of reading every sender's account.
+    pub fn with_strict_validation(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Accept batches that fit within any of the provided gas limits in addition to the
+    /// protocol's limit.
+    ///
+    /// Useful during a scheduled gas limit change so in-flight batches remain valid.
+    pub fn with_accepted_gas_limits(mut self, accepted_gas_limits: HashSet<u64>) -> Self {
+        self.accepted_gas_limits = accepted_gas_limits;
+        self
+    }
+
+    /// Return the protocol's gas limit for a batch at the provided timestamp.
+    ///
+    /// The timestamp is in the batch's [TimestampGranularity].
+    fn protocol_gas_limit(&self, timestamp: u64) -> u64 {
+        max_batch_gas(self.timestamp_granularity.to_secs(timestamp))
+    }
+
+    /// Validate the batch was built in the current epoch.
+    #[inline]
+    fn validate_epoch(&self, batch_epoch: Epoch) -> BatchValidationResult<()> {
+        if batch_epoch != self.epoch {
+            return Err(BatchValidationError::WrongEpoch {
+                batch_epoch,
+                current_epoch: self.epoch,
+            });
+        }
+        Ok(())
     }
 
     /// Validates the timestamp against the parent to make sure it is in the past.
+    ///
+    /// The parent's timestamp is converted to the batch's [TimestampGranularity] first.
     #[inline]
     fn validate_against_parent_timestamp(
         &self,
         timestamp: u64,
         parent: &ExecHeader,
     ) -> BatchValidationResult<()> {
-        if timestamp <= parent.timestamp {
-            return Err(BatchValidationError::TimestampIsInPast {
-                parent_timestamp: parent.timestamp,
-                timestamp,
-            });
+        let parent_timestamp = self.timestamp_granularity.from_secs(parent.timestamp);
+        if timestamp <= parent_timestamp {
+            return Err(BatchValidationError::TimestampIsInPast { parent_timestamp, timestamp });
         }
         Ok(())
     }
@@ -117,11 +230,13 @@ where
         timestamp: u64,
     ) -> BatchValidationResult<()> {
         // calculate size (in bytes) of included transactions
+        if transactions.is_empty() {
+            return Err(BatchValidationError::EmptyBatch);
+        }
         let total_bytes = transactions
             .iter()
-            .map(|tx| tx.len())
-            .reduce(|total, size| total + size)
-            .ok_or(BatchValidationError::EmptyBatch)?;
+            .try_fold(0usize, |total, tx| total.checked_add(transaction_size(tx)))
+            .ok_or(BatchValidationError::SizeOverflow)?;
         let max_tx_bytes = max_batch_size(timestamp);
 
         // allow txs that equal max tx bytes
@@ -164,24 +279,98 @@ where
         timestamp: u64,
     ) -> BatchValidationResult<()> {
         // calculate total using tx gas limit
+        if transactions.is_empty() {
+            return Err(BatchValidationError::EmptyBatch);
+        }
         let total_possible_gas = transactions
             .iter()
-            .map(|tx| tx.gas_limit())
-            .reduce(|total, size| total + size)
-            .ok_or(BatchValidationError::EmptyBatch)?;
-
-        // ensure total tx gas limit fits into block's gas limit
-        let max_tx_gas = max_batch_gas(timestamp);
-        if total_possible_gas > max_tx_gas {
-            return Err(BatchValidationError::HeaderMaxGasExceedsGasLimit {
-                total_possible_gas,
-                gas_limit: max_tx_gas,
-            });
+            .try_fold(0u64, |total, tx| total.checked_add(tx.gas_limit()))
+            .ok_or(BatchValidationError::GasOverflow)?;
+
+        // ensure total tx gas limit fits into the protocol's limit or an accepted limit
+        let protocol_limit = self.protocol_gas_limit(timestamp);
+        if total_possible_gas <= protocol_limit
+            || self.accepted_gas_limits.iter().any(|limit| total_possible_gas <= *limit)
+        {
+            return Ok(());
+        }
+
+        // report the largest limit the batch was checked against
+        let gas_limit = self
+            .accepted_gas_limits
+            .iter()
+            .copied()
+            .fold(protocol_limit, |max, limit| max.max(limit));
+        Err(BatchValidationError::HeaderMaxGasExceedsGasLimit { total_possible_gas, gas_limit })
+    }
+
+    /// Validate transactions against the sender accounts in the parent's state.
+    ///
+    /// Each transaction's nonce must be at least the sender's nonce and the sender must have a
+    /// balance. Nonces are not checked for gaps between transactions from the same sender.
+    fn validate_transactions_against_parent(
+        &self,
+        transactions: &[Vec<u8>],
+        parent: &ExecHeader,
+        digest: BlockHash,
+    ) -> BatchValidationResult<()> {
+        let state = self.parent_state(parent)?;
+
+        for tx in transactions {
+            let recovered = recover_raw_transaction::<TransactionSigned>(tx)
+                .map_err(|e| BatchValidationError::RecoverTransaction(digest, e.to_string()))?;
+            let sender = recovered.signer();
+            let tx_nonce = recovered.into_tx().nonce();
+
+            let state_nonce = state
+                .account_nonce(&sender)
+                .map_err(|e| BatchValidationError::ParentState(e.to_string()))?
+                .unwrap_or_default();
+            if tx_nonce < state_nonce {
+                return Err(BatchValidationError::NonceTooLow { sender, tx_nonce, state_nonce });
+            }
+
+            let balance = state
+                .account_balance(&sender)
+                .map_err(|e| BatchValidationError::ParentState(e.to_string()))?
+                .unwrap_or_default();
+            if balance.is_zero() {
+                return Err(BatchValidationError::SenderHasNoBalance(sender));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Ensure the node retains state for the parent block.
+    ///
+    /// Parents more than the retained state depth behind the finalized block are pruned.
+    fn validate_parent_retained(&self, parent: &ExecHeader) -> BatchValidationResult<()> {
+        let Some(depth) = self.retained_state_depth else {
+            return Ok(());
+        };
+
+        let finalized_number = self
+            .blockchain_db
+            .database_provider_ro()
+            .and_then(|provider| provider.last_finalized_block_number())
+            .map_err(|e| BatchValidationError::ParentState(e.to_string()))?
+            .unwrap_or_default();
+        if finalized_number.saturating_sub(parent.number) > depth {
+            return Err(BatchValidationError::StatePruned(parent.number));
         }
 
         Ok(())
     }
 
+    /// Return the state at the parent block.
+    fn parent_state(&self, parent: &ExecHeader) -> BatchValidationResult<StateProviderBox> {
+        self.blockchain_db.state_by_block_hash(parent.hash_slow()).map_err(|e| match e {
+            ProviderError::StateAtBlockPruned(number) => BatchValidationError::StatePruned(number),
+            e => BatchValidationError::ParentState(e.to_string()),
+        })
+    }
+
     /// TODO: Validate the block's basefee
     fn validate_basefee(&self) -> BatchValidationResult<()> {
         Ok(())
@@ -226,13 +415,15 @@ mod tests {
     };
     use reth_db_common::init::init_genesis;
     use reth_node_types::NodeTypesWithDBAdapter;
-    use reth_provider::{providers::StaticFileProvider, ProviderFactory};
+    use reth_provider::{
+        providers::StaticFileProvider, ChainStateBlockWriter as _, DBProvider as _, ProviderFactory,
+    };
     use std::{str::FromStr, sync::Arc};
     use tn_node_traits::{TNExecution, TelcoinNode};
     use tn_test_utils::{test_genesis, TransactionFactory};
     use tn_types::{
         adiri_genesis, hex_literal::hex, max_batch_gas, Address, Batch, Bytes, Encodable2718 as _,
-        GenesisAccount, B256, MIN_PROTOCOL_BASE_FEE, U256,
+        GenesisAccount, TimestampGranularity, B256, MIN_PROTOCOL_BASE_FEE, U256,
     };
     use tracing::debug;
 
@@ -281,6 +472,7 @@ mod tests {
             beneficiary: Address::ZERO,
             timestamp,
             base_fee_per_gas: Some(MIN_PROTOCOL_BASE_FEE),
+            epoch: 0,
             received_at: None,
         };
 
@@ -310,7 +502,11 @@ mod tests {
     async fn test_tools() -> TestTools {
         // genesis with default TransactionFactory funded
         let chain: Arc<ChainSpec> = Arc::new(test_genesis().into());
+        test_tools_with_chain(chain).await
+    }
 
+    /// Create an instance of block validator for tests with the chain's genesis.
+    async fn test_tools_with_chain(chain: Arc<ChainSpec>) -> TestTools {
         // init genesis
         let db = create_test_rw_db();
         let provider_factory = ProviderFactory::new(
@@ -379,6 +575,7 @@ mod tests {
             beneficiary,
             timestamp,
             base_fee_per_gas,
+            epoch: 0,
             received_at,
         };
         assert_matches!(
@@ -410,6 +607,45 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_invalid_batch_replayed_from_previous_epoch() {
+        let TestTools { valid_batch, validator } = test_tools().await;
+
+        // the batch is valid in the epoch it was built in
+        assert!(validator.validate_batch(valid_batch.clone()).is_ok());
+
+        // replaying the batch in the next epoch is rejected
+        let validator = validator.with_epoch(1);
+        assert_matches!(
+            validator.validate_batch(valid_batch.clone()),
+            Err(BatchValidationError::WrongEpoch { batch_epoch: 0, current_epoch: 1 })
+        );
+
+        // a batch built in the current epoch is valid
+        let (mut batch, _) = valid_batch.split();
+        batch.epoch = 1;
+        assert!(validator.validate_batch(batch.seal_slow()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_millis_batch_timestamp_validated_against_parent() {
+        let TestTools { valid_batch, validator } = test_tools().await;
+        let validator = validator.with_timestamp_granularity(TimestampGranularity::Millis);
+        let (mut batch, _) = valid_batch.split();
+        let parent_timestamp = adiri_genesis().timestamp * 1_000;
+
+        // batches within the parent's second are valid
+        batch.timestamp = parent_timestamp + 1;
+        assert!(validator.validate_batch(batch.clone().seal_slow()).is_ok());
+
+        // the parent's timestamp is converted to millis
+        batch.timestamp = parent_timestamp;
+        assert_matches!(
+            validator.validate_batch(batch.seal_slow()),
+            Err(BatchValidationError::TimestampIsInPast{parent_timestamp: parent, timestamp}) if parent == parent_timestamp && timestamp == parent_timestamp
+        );
+    }
+
     #[tokio::test]
     async fn test_invalid_batch_excess_gas_used() {
         // Set excessive gas limit.
@@ -440,6 +676,7 @@ mod tests {
             beneficiary,
             timestamp,
             base_fee_per_gas,
+            epoch: 0,
             received_at,
         };
 
@@ -573,4 +810,248 @@ mod tests {
             Err(BatchValidationError::RecoverTransaction(_, _))
         );
     }
+
+    #[tokio::test]
+    async fn test_accepted_gas_limits() {
+        let TestTools { valid_batch, validator } = test_tools().await;
+        let (batch, _) = valid_batch.split();
+        let old_limit = max_batch_gas(batch.timestamp);
+        let new_limit = old_limit + 10_000_000;
+        let validator = validator.with_accepted_gas_limits(HashSet::from([old_limit, new_limit]));
+
+        let mut tx_factory = TransactionFactory::new();
+        let value = U256::from(10).checked_pow(U256::from(18)).expect("1e18 doesn't overflow U256");
+        let gas_price = 7;
+        let chain: Arc<ChainSpec> = Arc::new(test_genesis().into());
+
+        // helper to decode a transaction with the provided gas limit
+        let mut decoded_tx_with_gas = |gas_limit| {
+            let tx = tx_factory.create_eip1559_encoded(
+                chain.clone(),
+                Some(gas_limit),
+                gas_price,
+                Some(Address::ZERO),
+                value, // 1 TEL
+                Bytes::new(),
+            );
+            validator.decode_transactions(&vec![tx], batch.digest()).expect("txs decode correctly")
+        };
+
+        // both boundary limits are valid
+        let at_old_limit = decoded_tx_with_gas(old_limit);
+        assert!(validator.validate_batch_gas(&at_old_limit, batch.timestamp).is_ok());
+        let at_new_limit = decoded_tx_with_gas(new_limit);
+        assert!(validator.validate_batch_gas(&at_new_limit, batch.timestamp).is_ok());
+
+        // out-of-range limit is rejected
+        let above_limits = decoded_tx_with_gas(new_limit + 1);
+        assert_matches!(
+            validator.validate_batch_gas(&above_limits, batch.timestamp),
+            Err(BatchValidationError::HeaderMaxGasExceedsGasLimit { total_possible_gas, gas_limit })
+                if total_possible_gas == new_limit + 1 && gas_limit == new_limit
+        );
+
+        // without accepted limits, the new limit is rejected
+        let validator = validator.with_accepted_gas_limits(HashSet::new());
+        assert!(validator.validate_batch_gas(&at_old_limit, batch.timestamp).is_ok());
+        assert_matches!(
+            validator.validate_batch_gas(&at_new_limit, batch.timestamp),
+            Err(BatchValidationError::HeaderMaxGasExceedsGasLimit { total_possible_gas, gas_limit })
+                if total_possible_gas == new_limit && gas_limit == old_limit
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_batch_gas_overflow() {
+        let TestTools { valid_batch, validator } = test_tools().await;
+        let (batch, _) = valid_batch.split();
+
+        let mut tx_factory = TransactionFactory::new();
+        let value = U256::from(10).checked_pow(U256::from(18)).expect("1e18 doesn't overflow U256");
+        let gas_price = 7;
+        let chain: Arc<ChainSpec> = Arc::new(test_genesis().into());
+
+        // create transactions whose gas limits sum past u64::MAX
+        //
+        // a wrapped total would be small enough to pass the gas limit check
+        let gas_limit = u64::MAX / 2 + 1;
+        let transactions = (0..2)
+            .map(|_| {
+                tx_factory.create_eip1559_encoded(
+                    chain.clone(),
+                    Some(gas_limit),
+                    gas_price,
+                    Some(Address::ZERO),
+                    value, // 1 TEL
+                    Bytes::new(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let wrapped = gas_limit.wrapping_add(gas_limit);
+        assert!(wrapped < max_batch_gas(batch.timestamp));
+
+        let decoded_txs = validator
+            .decode_transactions(&transactions, batch.digest())
+            .expect("txs decode correctly");
+        assert_matches!(
+            validator.validate_batch_gas(&decoded_txs, batch.timestamp),
+            Err(BatchValidationError::GasOverflow)
+        );
+
+        // overflow is also rejected through the full validation path
+        let invalid_batch = Batch { transactions, ..batch };
+        assert_matches!(
+            validator.validate_batch(invalid_batch.seal_slow()),
+            Err(BatchValidationError::GasOverflow)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_batches_in_parallel() {
+        let TestTools { valid_batch, validator } = test_tools().await;
+
+        // create an invalid batch with no transactions
+        let (mut invalid_batch, _) = valid_batch.clone().split();
+        invalid_batch.transactions = Vec::with_capacity(0);
+
+        // valid batch with non-serialized data set
+        let (mut other_valid_batch, _) = valid_batch.clone().split();
+        other_valid_batch.received_at = Some(tn_types::now());
+
+        let batches = vec![valid_batch, invalid_batch.seal_slow(), other_valid_batch.seal_slow()];
+        let results = validator.validate_batches(&batches);
+
+        // only the invalid batch's index errors
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert_matches!(results[1], Err(BatchValidationError::EmptyBatch));
+        assert!(results[2].is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_validate_received_batch() {
+        let TestTools { valid_batch, validator } = test_tools().await;
+
+        // locally built batches are never stamped
+        assert!(valid_batch.batch().received_at().is_none());
+
+        let digest = valid_batch.digest();
+        let received = validator
+            .validate_received_batch(valid_batch.clone())
+            .expect("valid batch is accepted on receipt");
+        assert!(received.batch().received_at().is_some());
+        // received_at is not part of the digest
+        assert_eq!(received.digest(), digest);
+        assert_eq!(received.batch().clone().seal_slow().digest(), digest);
+
+        // invalid batches are not stamped
+        let (mut invalid_batch, _) = valid_batch.split();
+        invalid_batch.transactions = Vec::with_capacity(0);
+        assert_matches!(
+            validator.validate_received_batch(invalid_batch.seal_slow()),
+            Err(BatchValidationError::EmptyBatch)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_strict_validation_rejects_nonce_too_low() {
+        // the factory's account already sent 5 transactions at genesis
+        let mut tx_factory = TransactionFactory::new();
+        let account = vec![(
+            tx_factory.address(),
+            GenesisAccount::default().with_balance(U256::MAX).with_nonce(Some(5)),
+        )];
+        let chain: Arc<ChainSpec> = Arc::new(adiri_genesis().extend_accounts(account).into());
+        let TestTools { validator, .. } = test_tools_with_chain(chain.clone()).await;
+        let batch_with_tx = |tx: Vec<u8>| {
+            Batch {
+                transactions: vec![tx],
+                parent_hash: chain.genesis_hash(),
+                beneficiary: Address::ZERO,
+                timestamp: 1701790139,
+                base_fee_per_gas: Some(MIN_PROTOCOL_BASE_FEE),
+                epoch: 0,
+                received_at: None,
+            }
+            .seal_slow()
+        };
+        let value = U256::from(10).checked_pow(U256::from(18)).expect("1e18 doesn't overflow U256");
+
+        // nonce 0 is already used
+        let stale_tx = tx_factory.create_eip1559_encoded(
+            chain.clone(),
+            None,
+            7,
+            Some(Address::ZERO),
+            value,
+            Bytes::new(),
+        );
+        let stale_batch = batch_with_tx(stale_tx);
+
+        // only rejected in strict mode
+        assert!(validator.validate_batch(stale_batch.clone()).is_ok());
+        let validator = validator.with_strict_validation(true);
+        assert_matches!(
+            validator.validate_batch(stale_batch),
+            Err(BatchValidationError::NonceTooLow { tx_nonce: 0, state_nonce: 5, .. })
+        );
+
+        // the next nonce is valid
+        tx_factory.set_nonce(5);
+        let next_tx = tx_factory.create_eip1559_encoded(
+            chain.clone(),
+            None,
+            7,
+            Some(Address::ZERO),
+            value,
+            Bytes::new(),
+        );
+        assert!(validator.validate_batch(batch_with_tx(next_tx)).is_ok());
+
+        // unfunded senders are rejected
+        let mut unfunded = TransactionFactory::new_random();
+        let unfunded_tx = unfunded.create_eip1559_encoded(
+            chain,
+            None,
+            7,
+            Some(Address::ZERO),
+            value,
+            Bytes::new(),
+        );
+        assert_matches!(
+            validator.validate_batch(batch_with_tx(unfunded_tx)),
+            Err(BatchValidationError::SenderHasNoBalance(_))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejects_pruned_parent_state() {
+        let TestTools { valid_batch, validator } = test_tools().await;
+        let validator = validator.with_retained_state_depth(Some(10));
+
+        // the genesis parent is retained
+        assert!(validator.validate_batch(valid_batch.clone()).is_ok());
+
+        // finalize beyond the retained depth
+        let provider_rw = validator.blockchain_db.database_provider_rw().expect("provider rw");
+        provider_rw.save_finalized_block_number(11).expect("save finalized");
+        provider_rw.commit().expect("commit finalized");
+
+        // genesis state is pruned
+        assert_matches!(
+            validator.validate_batch(valid_batch.clone()),
+            Err(BatchValidationError::StatePruned(0))
+        );
+
+        // strict validation fails the same way
+        let validator = validator.with_strict_validation(true);
+        assert_matches!(
+            validator.validate_batch(valid_batch.clone()),
+            Err(BatchValidationError::StatePruned(0))
+        );
+
+        // without a retained depth the parent is not checked
+        let validator = validator.with_strict_validation(false).with_retained_state_depth(None);
+        assert!(validator.validate_batch(valid_batch).is_ok());
+    }
 }
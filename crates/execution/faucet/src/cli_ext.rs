@@ -27,6 +27,13 @@ pub struct FaucetArgs {
     #[clap(long, default_value = "86400", value_parser = parse_duration_from_secs, value_name = "WAIT_PERIOD")]
     pub(crate) wait_period: Duration,
 
+    /// The amount of time a faucet transaction can remain pending
+    /// before it is re-issued with higher fees.
+    ///
+    /// Specified in seconds. Must be greater than zero.
+    #[clap(long, default_value = "30", value_parser = parse_resubmit_timeout, value_name = "RESUBMIT_TIMEOUT")]
+    pub(crate) resubmit_timeout: Duration,
+
     /// The address for the Telcoin-Network Faucet which handles TEL and XYZ transfers to each
     /// recipient.
     #[clap(long, default_value_t = Address::ZERO, value_parser = Address::from_str, value_name = "FAUCET_CONTRACT_ADDRESS")]
@@ -137,6 +144,7 @@ impl FaucetArgs {
             let wallet = FaucetWallet { address, public_key_bytes, name };
             let config = FaucetConfig {
                 wait_period: self.wait_period,
+                resubmit_timeout: self.resubmit_timeout,
                 chain_id: self.chain_id,
                 wallet,
                 contract_address: self.contract_address,
@@ -168,6 +176,17 @@ pub fn parse_u256_from_decimal_value(value: &str) -> eyre::Result<U256> {
     Ok(token_amount)
 }
 
+/// Parse the resubmit timeout in seconds.
+///
+/// The timeout is the period of the faucet's resubmit interval, so it must be greater than zero.
+pub fn parse_resubmit_timeout(value: &str) -> eyre::Result<Duration> {
+    let timeout = parse_duration_from_secs(value)?;
+    if timeout.is_zero() {
+        eyre::bail!("resubmit timeout must be greater than zero");
+    }
+    Ok(timeout)
+}
+
 /// Parse public key from pem or hex slice.
 pub fn parse_pubkey(value: &str) -> eyre::Result<PublicKey> {
     // google kms uses pem key formatting
@@ -189,7 +208,7 @@ mod tests {
     use crate::FaucetArgs;
     use clap::Parser;
     use secp256k1::PublicKey;
-    use std::str::FromStr;
+    use std::{str::FromStr, time::Duration};
     use tn_test_utils::CommandParser;
 
     #[test]
@@ -231,4 +250,27 @@ mod tests {
         .unwrap();
         assert_eq!(pem_parsed.args.public_key, expected);
     }
+
+    #[test]
+    fn test_zero_resubmit_timeout_rejected() {
+        let pubkey = "029bef8d556d80e43ae7e0becb3a7e6838b95defe45896ed6075bb9035d06c9964";
+        let parsed = CommandParser::<FaucetArgs>::try_parse_from([
+            "tn",
+            "--public-key",
+            pubkey,
+            "--resubmit-timeout",
+            "5",
+        ])
+        .expect("parsed resubmit timeout");
+        assert_eq!(parsed.args.resubmit_timeout, Duration::from_secs(5));
+
+        let zero = CommandParser::<FaucetArgs>::try_parse_from([
+            "tn",
+            "--public-key",
+            pubkey,
+            "--resubmit-timeout",
+            "0",
+        ]);
+        assert!(zero.is_err());
+    }
 }
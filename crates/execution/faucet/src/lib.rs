@@ -22,15 +22,20 @@ use reth_transaction_pool::{EthPooledTransaction, TransactionPool};
 use secp256k1::constants::PUBLIC_KEY_SIZE;
 use std::time::Duration;
 use tn_types::{Address, TxHash};
-use tokio::sync::{
-    mpsc::{unbounded_channel, UnboundedSender},
-    oneshot,
+use tokio::{
+    sync::{
+        mpsc::{unbounded_channel, UnboundedSender},
+        oneshot,
+    },
+    time::interval,
 };
 use tokio_stream::wrappers::UnboundedReceiverStream;
 mod cli_ext;
+mod nonce;
 mod rpc_ext;
 mod service;
 pub use cli_ext::{parse_u256_from_decimal_value, FaucetArgs};
+use nonce::NonceManager;
 pub use rpc_ext::{FaucetRpcExt, FaucetRpcExtApiServer};
 pub(crate) use service::FaucetService;
 
@@ -48,6 +53,11 @@ pub struct FaucetConfig {
     /// The amount of time recipients must wait between transfers
     /// specified in seconds.
     pub wait_period: Duration,
+    /// The amount of time a faucet transaction can remain pending
+    /// before it is re-issued with higher fees.
+    ///
+    /// Must be greater than zero.
+    pub resubmit_timeout: Duration,
     /// The chain id
     pub chain_id: u64,
     /// Sensitive information regarding the wallet hot-signing transactions
@@ -114,7 +124,8 @@ impl Faucet {
         config: FaucetConfig,
     ) -> (Self, FaucetService<Provider, Pool, Tasks>) {
         let (to_service, rx) = unbounded_channel();
-        let FaucetConfig { wait_period, chain_id, wallet, contract_address } = config;
+        let FaucetConfig { wait_period, resubmit_timeout, chain_id, wallet, contract_address } =
+            config;
 
         // Construct an `LruCache` of `<String, SystemTime>`s, limited by 24hr expiry time
         let success_cache = LruCache::with_expiry_duration(wait_period);
@@ -138,7 +149,9 @@ impl Faucet {
             wallet,
             add_to_success_cache_tx,
             update_success_cache_rx,
-            next_nonce: 0, // start at 0 - service checks db
+            // start at 0 - service checks db
+            nonce_manager: NonceManager::new(resubmit_timeout),
+            resubmit_interval: interval(resubmit_timeout),
         };
         let faucet = Self { to_service };
         (faucet, service)
@@ -0,0 +1,213 @@
+//! Nonce management for the faucet.
+//!
+//! The faucet signs every transaction with the same account, so a single transaction that
+//! never gets mined blocks every drip with a higher nonce. The nonce manager tracks the
+//! highest submitted nonce along with all faucet transactions that have not been mined yet.
+//! Mined updates from the batch builder (received through the pool's transaction events)
+//! remove transactions from the pending set. The transaction with the account's next nonce
+//! blocks every other faucet transaction, so if it remains pending longer than the resubmit
+//! timeout it is re-issued with the same nonce and bumped fees to replace the stuck transaction
+//! in the pool. Transactions queued behind it are not stuck and keep their fees.
+
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+use tn_types::{Address, Transaction, TxEip1559};
+use tracing::debug;
+
+/// The percent to increase fees by when re-issuing a stuck transaction.
+///
+/// The transaction pool requires at least a 10% bump for replacement transactions.
+pub(crate) const FEE_BUMP_PERCENT: u128 = 20;
+
+/// A faucet transaction that was submitted to the pool but has not been mined.
+#[derive(Clone, Debug)]
+pub(crate) struct PendingFaucetTx {
+    /// The unsigned transaction.
+    pub(crate) transaction: TxEip1559,
+    /// The address that requested the drip.
+    pub(crate) user: Address,
+    /// The contract for the digital asset dripped to the user.
+    pub(crate) contract: Address,
+    /// The last time this transaction was submitted to the pool.
+    submitted_at: Instant,
+}
+
+/// Track the faucet's nonces and the transactions that are pending in the pool.
+#[derive(Debug)]
+pub(crate) struct NonceManager {
+    /// The highest nonce submitted by the faucet.
+    highest_submitted: Option<u64>,
+    /// Faucet transactions that were submitted but not mined yet, sorted by nonce.
+    pending: BTreeMap<u64, PendingFaucetTx>,
+    /// The amount of time a transaction can remain pending before it is re-issued.
+    resubmit_timeout: Duration,
+}
+
+impl NonceManager {
+    /// Create a new instance of Self.
+    pub(crate) fn new(resubmit_timeout: Duration) -> Self {
+        Self { highest_submitted: None, pending: BTreeMap::new(), resubmit_timeout }
+    }
+
+    /// The next nonce based on the highest submitted transaction.
+    ///
+    /// Returns `0` if the faucet has not submitted any transactions yet.
+    pub(crate) fn next_nonce(&self) -> u64 {
+        self.highest_submitted.map(|nonce| nonce + 1).unwrap_or_default()
+    }
+
+    /// The number of faucet transactions waiting to be mined.
+    pub(crate) fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Track a transaction that was submitted to the pool.
+    ///
+    /// Transactions are tracked by nonce, so re-issued transactions replace the original.
+    pub(crate) fn submitted(
+        &mut self,
+        transaction: &Transaction,
+        user: Address,
+        contract: Address,
+        now: Instant,
+    ) {
+        let Transaction::Eip1559(transaction) = transaction else {
+            // the faucet only creates eip1559 transactions
+            return;
+        };
+
+        let nonce = transaction.nonce;
+        self.highest_submitted = Some(self.highest_submitted.map_or(nonce, |n| n.max(nonce)));
+        self.pending.insert(
+            nonce,
+            PendingFaucetTx { transaction: transaction.clone(), user, contract, submitted_at: now },
+        );
+    }
+
+    /// Process a mined update for a faucet transaction.
+    ///
+    /// Mined nonces are final, so any pending transaction with an equal or lower nonce is
+    /// no longer pending.
+    pub(crate) fn mined(&mut self, nonce: u64) {
+        // keep everything above the mined nonce
+        self.pending = self.pending.split_off(&nonce.saturating_add(1));
+        debug!(target: "faucet", ?nonce, pending=self.pending.len(), "faucet nonce mined");
+    }
+
+    /// Return the transaction with the account's next nonce if it has been pending longer than
+    /// the resubmit timeout.
+    ///
+    /// `account_nonce` is the faucet account's current nonce, so pending transactions with lower
+    /// nonces were mined and are removed. Only the transaction with the account's next nonce can
+    /// be stuck - transactions with higher nonces are waiting for it to be mined.
+    ///
+    /// The returned transaction has the same nonce with fees bumped by [FEE_BUMP_PERCENT] or the
+    /// current base fee - whichever is higher. The pending transaction is updated with the new
+    /// fees and submission time so it isn't re-issued again until the timeout expires.
+    pub(crate) fn stuck_transaction(
+        &mut self,
+        now: Instant,
+        base_fee: u128,
+        account_nonce: u64,
+    ) -> Option<(Transaction, Address, Address)> {
+        if let Some(mined) = account_nonce.checked_sub(1) {
+            self.mined(mined);
+        }
+
+        let pending = self.pending.get_mut(&account_nonce)?;
+        if now.saturating_duration_since(pending.submitted_at) < self.resubmit_timeout {
+            return None;
+        }
+
+        let fee = bump_fee(pending.transaction.max_fee_per_gas).max(base_fee);
+        debug!(target: "faucet", nonce=?account_nonce, old_fee=?pending.transaction.max_fee_per_gas, new_fee=?fee, "re-issuing stuck faucet transaction");
+        pending.transaction.max_fee_per_gas = fee;
+        pending.transaction.max_priority_fee_per_gas = fee;
+        pending.submitted_at = now;
+        Some((Transaction::Eip1559(pending.transaction.clone()), pending.user, pending.contract))
+    }
+}
+
+/// Increase the fee by [FEE_BUMP_PERCENT].
+///
+/// The fee always increases by at least 1 wei.
+fn bump_fee(fee: u128) -> u128 {
+    let bumped = fee.saturating_mul(100 + FEE_BUMP_PERCENT) / 100;
+    bumped.max(fee.saturating_add(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NonceManager, FEE_BUMP_PERCENT};
+    use std::time::{Duration, Instant};
+    use tn_types::{Address, Transaction, TransactionTrait as _, TxEip1559};
+
+    fn faucet_tx(nonce: u64, fee: u128) -> Transaction {
+        Transaction::Eip1559(TxEip1559 {
+            chain_id: 2017,
+            nonce,
+            max_priority_fee_per_gas: fee,
+            max_fee_per_gas: fee,
+            gas_limit: 1_000_000,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_stuck_nonce_reissued_with_higher_fee() {
+        let timeout = Duration::from_secs(30);
+        let mut manager = NonceManager::new(timeout);
+        let start = Instant::now();
+        let user = Address::random();
+        let contract = Address::ZERO;
+        assert_eq!(manager.next_nonce(), 0);
+
+        // submit three drips
+        for nonce in 0..3 {
+            manager.submitted(&faucet_tx(nonce, 7), user, contract, start);
+        }
+        assert_eq!(manager.next_nonce(), 3);
+        assert_eq!(manager.pending_len(), 3);
+
+        // nothing is stuck before the timeout
+        assert!(manager.stuck_transaction(start + timeout / 2, 7, 0).is_none());
+
+        // nonce 0 is stuck - nonces 1 and 2 are only waiting on it so they keep their fees
+        let (tx, stuck_user, stuck_contract) =
+            manager.stuck_transaction(start + timeout, 7, 0).expect("nonce 0 is stuck");
+        assert_eq!(tx.nonce(), 0);
+        assert_eq!(tx.max_fee_per_gas(), 7 * (100 + FEE_BUMP_PERCENT) / 100);
+        assert_eq!(tx.max_priority_fee_per_gas(), Some(tx.max_fee_per_gas()));
+        assert_eq!(stuck_user, user);
+        assert_eq!(stuck_contract, contract);
+        assert_eq!(manager.pending_len(), 3);
+
+        // re-issued transactions are not returned again until the timeout expires
+        assert!(manager.stuck_transaction(start + timeout + timeout / 2, 7, 0).is_none());
+
+        // the re-issued nonce 0 and nonce 1 are mined, so the account's next nonce is 2
+        assert!(manager.stuck_transaction(start + timeout + timeout / 2, 7, 2).is_none());
+        assert_eq!(manager.pending_len(), 1);
+
+        // nonce 2 is now stuck and the base fee is higher than the bumped fee
+        let (tx, _, _) =
+            manager.stuck_transaction(start + timeout * 2, 1_000, 2).expect("nonce 2 is stuck");
+        assert_eq!(tx.nonce(), 2);
+        assert_eq!(tx.max_fee_per_gas(), 1_000);
+
+        // mined updates never lower the next nonce
+        manager.mined(2);
+        assert_eq!(manager.pending_len(), 0);
+        assert_eq!(manager.next_nonce(), 3);
+    }
+
+    #[test]
+    fn test_fee_bump_always_increases() {
+        assert_eq!(super::bump_fee(0), 1);
+        assert_eq!(super::bump_fee(1), 2);
+        assert_eq!(super::bump_fee(100), 100 + FEE_BUMP_PERCENT);
+        assert_eq!(super::bump_fee(u128::MAX), u128::MAX);
+    }
+}
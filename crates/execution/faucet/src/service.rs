@@ -6,7 +6,7 @@
 //! address if the address hasn't received from the faucet
 //! wallet within the time period.
 
-use crate::{Drip, FaucetWallet, GoogleKMSClient, Secp256k1PubKeyBytes};
+use crate::{nonce::NonceManager, Drip, FaucetWallet, GoogleKMSClient, Secp256k1PubKeyBytes};
 use futures::StreamExt;
 use gcloud_sdk::{
     google::cloud::kms::v1::{
@@ -32,15 +32,18 @@ use std::{
     future::Future,
     pin::Pin,
     task::{ready, Context, Poll},
-    time::{Duration, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
 use tn_types::{
     Address, EthSignature, SolType, Transaction, TransactionSigned, TransactionTrait as _,
     TxEip1559, TxHash, TxKind, B256, U256,
 };
-use tokio::sync::{
-    mpsc::{UnboundedReceiver, UnboundedSender},
-    oneshot,
+use tokio::{
+    sync::{
+        mpsc::{UnboundedReceiver, UnboundedSender},
+        oneshot,
+    },
+    time::Interval,
 };
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::{debug, error, warn};
@@ -55,12 +58,14 @@ pub(crate) struct MinedTxInfo {
     user: Address,
     /// The contract associated with the digital asset that the faucet dripped to the address.
     contract: Address,
+    /// The nonce of the mined faucet transaction.
+    nonce: u64,
 }
 
 impl MinedTxInfo {
     /// Create a new instance of Self.
-    fn new(user: Address, contract: Address) -> Self {
-        Self { user, contract }
+    fn new(user: Address, contract: Address, nonce: u64) -> Self {
+        Self { user, contract, nonce }
     }
 }
 
@@ -116,14 +121,19 @@ pub(crate) struct FaucetService<Provider, Pool, Tasks> {
     ///
     /// Addresses received on this channel are added to the LRU cache.
     pub(crate) update_success_cache_rx: UnboundedReceiver<MinedTxInfo>,
-    /// The nonce for this faucet as tracked through the highest submitted transaction nonce.
+    /// The nonce state for this faucet.
     ///
     /// The faucet service checks for the highest nonce in the transaction pool and if needd, in
     /// the database. However, the faucet also needs to nonce state for transactions that are
-    /// pending in batches.
+    /// pending in batches. The manager tracks the highest submitted transaction nonce and all
+    /// faucet transactions that are waiting to be mined.
     ///
     /// The account nonce read from the database returns the account's CURRENT nonce.
-    pub(crate) next_nonce: u64,
+    pub(crate) nonce_manager: NonceManager,
+    /// The interval for checking the nonce manager for stuck transactions.
+    ///
+    /// Faucet transactions that are pending too long are re-issued with higher fees.
+    pub(crate) resubmit_interval: Interval,
 }
 
 impl<Provider, Pool, Tasks> FaucetService<Provider, Pool, Tasks>
@@ -153,6 +163,54 @@ where
     ) -> EthResult<()> {
         // create transaction based on request type
         let transaction = self.create_transaction_to_sign(user, contract)?;
+
+        debug!(target: "faucet", ?transaction, "processing transfer request");
+
+        self.sign_and_submit(transaction, user, contract, Some(reply));
+
+        Ok(())
+    }
+
+    /// Re-issue the faucet transaction with the account's next nonce if it has been pending too
+    /// long.
+    ///
+    /// The stuck transaction keeps the same nonce and is submitted with higher fees so it
+    /// replaces the original transaction in the pool.
+    fn resubmit_stuck_transactions(&mut self) {
+        let account_nonce = match self
+            .provider
+            .latest()
+            .and_then(|state| state.account_nonce(&self.wallet.address))
+        {
+            Ok(nonce) => nonce.unwrap_or_default(),
+            Err(e) => {
+                warn!(target: "faucet", ?e, "Error reading faucet account nonce");
+                return;
+            }
+        };
+        let base_fee = self.gas_price();
+        if let Some((transaction, user, contract)) =
+            self.nonce_manager.stuck_transaction(Instant::now(), base_fee, account_nonce)
+        {
+            warn!(target: "faucet", ?transaction, "re-issuing stuck faucet transaction");
+            self.sign_and_submit(transaction, user, contract, None);
+        }
+    }
+
+    /// Track the transaction's nonce, then request a signature and submit the transaction to the
+    /// pool.
+    ///
+    /// The result is sent to the rpc if a reply channel is provided.
+    fn sign_and_submit(
+        &mut self,
+        transaction: Transaction,
+        user: Address,
+        contract: Address,
+        reply: Option<oneshot::Sender<EthResult<TxHash>>>,
+    ) {
+        // update tracked nonce
+        self.nonce_manager.submitted(&transaction, user, contract, Instant::now());
+
         // request signature from kms
         let kms_name = self.wallet.name();
         let public_key = self.wallet.kms_public_key();
@@ -160,12 +218,6 @@ where
         let pool = self.pool.clone();
         let add_to_success_cache = self.add_to_success_cache_tx.clone();
 
-        debug!(target: "faucet", ?transaction, "processing transfer request");
-
-        // update tracked nonce
-        let nonce = transaction.nonce();
-        self.next_nonce = nonce + 1;
-
         // request signature and submit to txpool
         self.executor.spawn(Box::pin(async move {
             let digest = transaction.signature_hash();
@@ -179,20 +231,22 @@ where
                     let res =
                         submit_transaction(pool, tx_for_pool, add_to_success_cache, user, contract)
                             .await;
+                    if let Err(e) = &res {
+                        warn!(target: "faucet", ?e, "Error submitting faucet transaction");
+                    }
                     // reply to rpc
-                    let _ = reply.send(res);
+                    if let Some(reply) = reply {
+                        let _ = reply.send(res);
+                    }
                 }
                 Err(e) => error!(target: "faucet", ?e, "Error requesting KMS signature"),
             }
         }));
-
-        Ok(())
     }
 
     /// Create a EIP1559 transaction with max fee per gas set to 1 TEL.
     ///
-    /// This method intentionally uses `&mut self` to ensure nonce is incremented correctly.
-    /// TODO: use AtomicU64 for thread safe nonce increments
+    /// The nonce is tracked by the [NonceManager] once the transaction is submitted.
     fn create_transaction_to_sign(&self, to: Address, contract: Address) -> EthResult<Transaction> {
         let nonce = self.next_nonce()?;
         let gas_price = self.gas_price();
@@ -228,8 +282,8 @@ where
     ///
     /// This method looks at the transaction pool first because the pool is gapless. If no
     /// faucet transactions in the pool, compare the highest nonce in the database and the
-    /// highest nonce the faucet has seen. It is still possible for a transaction to fail after it
-    /// was mined. This would require restarting the faucet service.
+    /// highest nonce the faucet has submitted. Transactions that are not mined are re-issued by
+    /// the [NonceManager], so a stuck nonce does not block all subsequent transactions.
     ///
     /// The account nonce read from the database returns the account's CURRENT nonce.
     fn next_nonce(&self) -> EthResult<u64> {
@@ -265,8 +319,9 @@ where
         // lookup account nonce in db and compare it last known tx nonce mined by worker
         let state = self.provider.latest()?;
         let db_account_nonce = state.account_nonce(&address)?.unwrap_or_default();
-        debug!(target: "faucet", ?db_account_nonce, tracked_nonce=?self.next_nonce, "comparing faucet nonces");
-        let highest_nonce = std::cmp::max(db_account_nonce, self.next_nonce);
+        let tracked_nonce = self.nonce_manager.next_nonce();
+        debug!(target: "faucet", ?db_account_nonce, ?tracked_nonce, "comparing faucet nonces");
+        let highest_nonce = std::cmp::max(db_account_nonce, tracked_nonce);
 
         Ok(highest_nonce)
     }
@@ -420,11 +475,18 @@ where
 
         loop {
             // listen for cache updates
-            while let Poll::Ready(Some(MinedTxInfo { user, contract })) =
+            while let Poll::Ready(Some(MinedTxInfo { user, contract, nonce })) =
                 this.update_success_cache_rx.poll_recv(cx)
             {
                 // insert user's address and contract address into LRU cache
                 this.success_cache.insert((user, contract), SystemTime::now());
+                // mined nonces are no longer pending
+                this.nonce_manager.mined(nonce);
+            }
+
+            // check for stuck transactions
+            while this.resubmit_interval.poll_tick(cx).is_ready() {
+                this.resubmit_stuck_transactions();
             }
 
             match ready!(this.request_rx.poll_next_unpin(cx)) {
@@ -513,6 +575,7 @@ async fn submit_transaction<Pool>(
 where
     Pool: TransactionPool<Transaction = EthPooledTransaction>,
 {
+    let nonce = tx.nonce();
     let pool_tx = tx.try_into_pooled().map_err(|_| EthApiError::TransactionConversionError)?;
     let recovered =
         pool_tx.try_into_ecrecovered().map_err(|_| EthApiError::InvalidTransactionSignature)?;
@@ -521,7 +584,7 @@ where
         pool.add_transaction_and_subscribe(TransactionOrigin::Local, recovered.into()).await?;
 
     let tx_hash = tx_events.hash();
-    let mined_tx_info = MinedTxInfo::new(user, contract);
+    let mined_tx_info = MinedTxInfo::new(user, contract, nonce);
 
     // spawn task to listen for mining event, then update lru cache
     tokio::task::spawn(async move {
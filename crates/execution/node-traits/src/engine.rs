@@ -8,9 +8,10 @@ use reth_revm::primitives::{
     BlobExcessGasAndPrice, BlockEnv, CfgEnv, CfgEnvWithHandlerCfg, SpecId,
 };
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use tn_types::{
-    Address, BlockExt as _, BlockWithSenders, ConsensusOutput, NodePrimitives, SealedBlock,
-    SealedHeader, Withdrawals, B256, U256,
+    Address, BlockExt as _, BlockWithSenders, ConsensusOutput, EvmHardfork, NodePrimitives,
+    SealedBlock, SealedHeader, TimestampGranularity, Withdrawals, B256, U256,
 };
 
 /// Compatibility type to easily integrate with reth.
@@ -95,12 +96,77 @@ pub struct BuildArguments<Provider> {
     pub output: ConsensusOutput,
     /// Last executed block from the previous consensus output.
     pub parent_header: SealedHeader,
+    /// The retry policy for canonical and finalized updates after the output is executed.
+    pub canonical_update_retry: CanonicalUpdateRetry,
+    /// Optional hardfork that overrides the EVM spec id used to execute the output.
+    pub evm_hardfork: Option<EvmHardfork>,
+    /// Optional max number of blocks executed before they are made canonical and finalized.
+    pub max_blocks_per_finalization: Option<usize>,
+    /// The granularity of batch timestamps.
+    pub timestamp_granularity: TimestampGranularity,
 }
 
 impl<P> BuildArguments<P> {
     /// Initialize new instance of [Self].
     pub fn new(provider: P, output: ConsensusOutput, parent_header: SealedHeader) -> Self {
-        Self { provider, output, parent_header }
+        Self {
+            provider,
+            output,
+            parent_header,
+            canonical_update_retry: Default::default(),
+            evm_hardfork: None,
+            max_blocks_per_finalization: None,
+            timestamp_granularity: TimestampGranularity::default(),
+        }
+    }
+
+    /// Set the retry policy for canonical and finalized updates.
+    pub fn with_canonical_update_retry(mut self, retry: CanonicalUpdateRetry) -> Self {
+        self.canonical_update_retry = retry;
+        self
+    }
+
+    /// Override the EVM spec id used to execute the output.
+    ///
+    /// The hardfork must be scheduled in the chain spec.
+    pub fn with_evm_hardfork(mut self, evm_hardfork: Option<EvmHardfork>) -> Self {
+        self.evm_hardfork = evm_hardfork;
+        self
+    }
+
+    /// Execute and finalize the output in chunks of at most `max` blocks.
+    ///
+    /// A value of zero is ignored.
+    pub fn with_max_blocks_per_finalization(mut self, max: Option<usize>) -> Self {
+        self.max_blocks_per_finalization = max;
+        self
+    }
+
+    /// Set the granularity of batch timestamps in the output.
+    pub fn with_timestamp_granularity(
+        mut self,
+        timestamp_granularity: TimestampGranularity,
+    ) -> Self {
+        self.timestamp_granularity = timestamp_granularity;
+        self
+    }
+}
+
+/// The retry policy for updating the canonical and finalized chain after executing output.
+///
+/// Blocks are already executed and inserted into the tree when these updates happen, so a
+/// transient database error shouldn't drop the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CanonicalUpdateRetry {
+    /// The max number of attempts for each update (including the first attempt).
+    pub max_attempts: usize,
+    /// The amount of time to wait between attempts.
+    pub delay: Duration,
+}
+
+impl Default for CanonicalUpdateRetry {
+    fn default() -> Self {
+        Self { max_attempts: 3, delay: Duration::from_millis(100) }
     }
 }
 
@@ -112,12 +178,20 @@ pub struct TNPayload {
     /// Stored here for simplicity to maintain compatibility with reth api and implementing
     /// `PayloadBuilderAttributes` on Self.
     pub attributes: TNPayloadAttributes,
+    /// Optional hardfork that overrides the EVM spec id.
+    pub evm_hardfork: Option<EvmHardfork>,
 }
 
 impl TNPayload {
     /// Create a new instance of [Self].
     pub fn new(attributes: TNPayloadAttributes) -> Self {
-        Self { attributes }
+        Self { attributes, evm_hardfork: None }
+    }
+
+    /// Override the EVM spec id used to execute the payload.
+    pub fn with_evm_hardfork(mut self, evm_hardfork: Option<EvmHardfork>) -> Self {
+        self.evm_hardfork = evm_hardfork;
+        self
     }
 
     pub fn cfg_and_block_env(&self, chain_spec: &ChainSpec) -> (CfgEnvWithHandlerCfg, BlockEnv) {
@@ -125,7 +199,10 @@ impl TNPayload {
         let cfg = CfgEnv::default().with_chain_id(chain_spec.chain().id());
 
         // ensure we're not missing any timestamp based hardforks
-        let spec_id = SpecId::SHANGHAI;
+        //
+        // the override is validated against the chain spec before execution
+        let spec_id =
+            self.evm_hardfork.map(|hardfork| hardfork.spec_id()).unwrap_or(SpecId::SHANGHAI);
 
         // use the blob excess gas and price set by the worker during batch creation
         let blob_excess_gas_and_price = Some(BlobExcessGasAndPrice::new(0, false));
@@ -0,0 +1,17 @@
+//! RPC types for worker block limits.
+
+use serde::{Deserialize, Serialize};
+
+/// The limits for the next batch built by the worker.
+///
+/// Clients use these values to construct transactions that can be included in a batch.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockLimits {
+    /// The max gas for all transactions in a batch.
+    pub gas_limit: u64,
+    /// The max size of all encoded transactions in a batch (in bytes).
+    pub max_size: usize,
+    /// The base fee transactions must pay to be included in the next batch.
+    pub base_fee: u64,
+}
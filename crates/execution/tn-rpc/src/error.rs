@@ -2,8 +2,9 @@
 //!
 //! These errors are returned by the RPC for public requests to the `tn` namespace.
 
+use reth_provider::ProviderError;
 use thiserror::Error;
-use tn_types::hex::encode_prefixed;
+use tn_types::{hex::encode_prefixed, BlockHash, TxHash};
 
 /// The result type for TN RPC namespace.
 pub type TelcoinNetworkRpcResult<T> = Result<T, TNRpcError>;
@@ -14,6 +15,18 @@ pub enum TNRpcError {
     /// Handshake client provided an invalid signature for network key.
     #[error("Invalid proof of possession for provided network key or genesis.")]
     InvalidProofOfPossession,
+    /// The request included more transactions than the max allowed.
+    #[error("Too many transactions in request: {0} (max {1})")]
+    TooManyTransactions(usize, usize),
+    /// The requested block is unknown.
+    #[error("Unknown block: {0}")]
+    UnknownBlock(BlockHash),
+    /// The requested transaction is not included in the block.
+    #[error("Transaction {0} is not included in block {1}")]
+    TransactionNotInBlock(TxHash, BlockHash),
+    /// The node failed to read from its database.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
 }
 
 impl From<TNRpcError> for jsonrpsee_types::ErrorObject<'static> {
@@ -21,6 +34,11 @@ impl From<TNRpcError> for jsonrpsee_types::ErrorObject<'static> {
         // TODO: update this when adding errors
         match error {
             TNRpcError::InvalidProofOfPossession => rpc_error(401, error.to_string(), None),
+            TNRpcError::TooManyTransactions(..) => rpc_error(400, error.to_string(), None),
+            TNRpcError::UnknownBlock(_) | TNRpcError::TransactionNotInBlock(..) => {
+                rpc_error(404, error.to_string(), None)
+            }
+            TNRpcError::Provider(_) => rpc_error(500, error.to_string(), None),
             // _ => rpc_error(500, error.to_string(), None),
         }
     }
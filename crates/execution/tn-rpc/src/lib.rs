@@ -1,9 +1,19 @@
 // SPDX-License-Identifier: MIT or Apache-2.0
 //! RPC request handle for state sync requests from peers.
 
+mod block_limits;
 mod error;
 mod handshake;
+mod pool_status;
+mod raw_transactions;
 mod rpc_ext;
+mod tx_status;
 
+pub use block_limits::BlockLimits;
 pub use handshake::{Handshake, HandshakeBuilder};
+pub use pool_status::{
+    PendingTransactionSummary, PoolStatus, DEFAULT_POOL_STATUS_TXS, MAX_POOL_STATUS_TXS,
+};
+pub use raw_transactions::{RawTransactionResult, MAX_RAW_TRANSACTIONS};
 pub use rpc_ext::{TelcoinNetworkRpcExt, TelcoinNetworkRpcExtApiServer};
+pub use tx_status::TransactionStatus;
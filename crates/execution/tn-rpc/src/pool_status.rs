@@ -0,0 +1,45 @@
+//! RPC types for a snapshot of the worker's transaction pool.
+
+use serde::{Deserialize, Serialize};
+use tn_types::{Address, TxHash};
+
+/// The default number of pending transactions included in the pool status.
+pub const DEFAULT_POOL_STATUS_TXS: usize = 10;
+
+/// The max number of pending transactions included in the pool status.
+pub const MAX_POOL_STATUS_TXS: usize = 100;
+
+/// A snapshot of the worker's transaction pool.
+///
+/// Operators use the snapshot to debug transactions that are not included in a batch.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolStatus {
+    /// The number of transactions ready for the next batch.
+    pub pending: usize,
+    /// The number of transactions that can't pay the current base fee.
+    pub basefee: usize,
+    /// The number of blob transactions that can't be included yet.
+    pub blob: usize,
+    /// The number of transactions waiting for a nonce gap to be filled.
+    pub queued: usize,
+    /// The total number of transactions in the pool.
+    pub total: usize,
+    /// The pending transactions with the highest tip, in the order the batch builder would
+    /// include them.
+    pub top_pending: Vec<PendingTransactionSummary>,
+}
+
+/// Summary of a pending transaction in the pool.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTransactionSummary {
+    /// The transaction hash.
+    pub hash: TxHash,
+    /// The transaction signer.
+    pub sender: Address,
+    /// The transaction nonce.
+    pub nonce: u64,
+    /// The priority fee (or gas price for legacy transactions).
+    pub tip: u128,
+}
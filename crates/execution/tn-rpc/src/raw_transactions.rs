@@ -0,0 +1,23 @@
+//! RPC types for submitting pre-signed transactions in bulk.
+
+use serde::{Deserialize, Serialize};
+use tn_types::TxHash;
+
+/// The max number of raw transactions accepted in a single request.
+pub const MAX_RAW_TRANSACTIONS: usize = 256;
+
+/// The result of submitting a single raw transaction to the worker's pool.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum RawTransactionResult {
+    /// The transaction was added to the pool.
+    Added {
+        /// The transaction hash.
+        hash: TxHash,
+    },
+    /// The transaction could not be decoded or was rejected by the pool.
+    Rejected {
+        /// The reason the transaction was rejected.
+        error: String,
+    },
+}
@@ -2,12 +2,20 @@
 
 use crate::{
     error::{TNRpcError, TelcoinNetworkRpcResult},
-    Handshake,
+    BlockLimits, Handshake, PendingTransactionSummary, PoolStatus, RawTransactionResult,
+    TransactionStatus, DEFAULT_POOL_STATUS_TXS, MAX_POOL_STATUS_TXS, MAX_RAW_TRANSACTIONS,
 };
 use async_trait::async_trait;
 use jsonrpsee::proc_macros::rpc;
 use reth_chainspec::ChainSpec;
+use reth_primitives::PooledTransactionsElement;
+use reth_provider::BlockReader;
+use reth_rpc_eth_types::utils::recover_raw_transaction;
+use reth_transaction_pool::{PoolTransaction, TransactionOrigin, TransactionPool};
 use std::sync::Arc;
+use tn_types::{
+    max_batch_gas, max_batch_size, now, Block, BlockHash, Bytes, TransactionInclusionProof, TxHash,
+};
 
 /// Telcoin Network RPC namespace.
 ///
@@ -17,22 +25,60 @@ pub trait TelcoinNetworkRpcExtApi {
     /// Transfer TEL to an address
     #[method(name = "validatorHandshake")]
     async fn handshake(&self, handshake: Handshake) -> TelcoinNetworkRpcResult<()>;
+
+    /// Return the status of a transaction in the worker's pool.
+    #[method(name = "txStatus")]
+    async fn tx_status(&self, hash: TxHash) -> TelcoinNetworkRpcResult<TransactionStatus>;
+
+    /// Return the gas and size limits for the worker's next batch.
+    #[method(name = "blockLimits")]
+    async fn block_limits(&self) -> TelcoinNetworkRpcResult<BlockLimits>;
+
+    /// Return a snapshot of the worker's pool with the `limit` highest tip pending transactions.
+    #[method(name = "poolStatus")]
+    async fn pool_status(&self, limit: Option<usize>) -> TelcoinNetworkRpcResult<PoolStatus>;
+
+    /// Submit pre-signed transactions to the worker's pool.
+    ///
+    /// Returns the result for each transaction in the order they were submitted.
+    #[method(name = "sendRawTransactions")]
+    async fn send_raw_transactions(
+        &self,
+        transactions: Vec<Bytes>,
+    ) -> TelcoinNetworkRpcResult<Vec<RawTransactionResult>>;
+
+    /// Return the Merkle proof that a transaction is included in an executed block.
+    ///
+    /// The proof is against the block's `transactions_root`.
+    #[method(name = "transactionProof")]
+    async fn transaction_proof(
+        &self,
+        hash: TxHash,
+        block_hash: BlockHash,
+    ) -> TelcoinNetworkRpcResult<TransactionInclusionProof>;
 }
 
 /// The type that implements `tn` namespace trait.
-pub struct TelcoinNetworkRpcExt<N> {
+pub struct TelcoinNetworkRpcExt<N, Provider, Pool> {
     /// The chain id for this node.
     chain: Arc<ChainSpec>,
     /// The inner-node network.
     ///
     /// The interface that handles primary <-> engine network communication.
     _inner_node_network: N,
+    /// The provider for executed blocks.
+    provider: Provider,
+    /// The worker's transaction pool.
+    pool: Pool,
 }
 
 #[async_trait]
-impl<N> TelcoinNetworkRpcExtApiServer for TelcoinNetworkRpcExt<N>
+impl<N, Provider, Pool> TelcoinNetworkRpcExtApiServer for TelcoinNetworkRpcExt<N, Provider, Pool>
 where
     N: Send + Sync + 'static,
+    Provider: BlockReader<Block = Block> + 'static,
+    Pool: TransactionPool + 'static,
+    Pool::Transaction: PoolTransaction<Pooled = PooledTransactionsElement>,
 {
     /// Handshake method.
     ///
@@ -47,11 +93,296 @@ where
         // self.inner_node_network.new_peer
         Ok(())
     }
+
+    /// Transaction status method.
+    ///
+    /// Pending transactions are ready for the next batch. All other transactions in the pool are
+    /// queued until they become pending (ie - the sender's nonce gap is filled).
+    async fn tx_status(&self, hash: TxHash) -> TelcoinNetworkRpcResult<TransactionStatus> {
+        let Some(tx) = self.pool.get(&hash) else {
+            return Ok(TransactionStatus::Unknown);
+        };
+
+        let sender = tx.sender();
+        let nonce = tx.nonce();
+        let pending = self.pool.get_pending_transactions_by_sender(sender);
+        if !pending.iter().any(|pending| *pending.hash() == hash) {
+            return Ok(TransactionStatus::Queued { sender, nonce });
+        }
+
+        let position = pending.iter().filter(|pending| pending.nonce() < nonce).count();
+        Ok(TransactionStatus::Pending { sender, nonce, position })
+    }
+
+    /// Block limits method.
+    ///
+    /// The limits are the same ones the batch builder uses for the next batch. The base fee is
+    /// the canonical base fee applied to the pool.
+    async fn block_limits(&self) -> TelcoinNetworkRpcResult<BlockLimits> {
+        let timestamp = now();
+        Ok(BlockLimits {
+            gas_limit: max_batch_gas(timestamp),
+            max_size: max_batch_size(timestamp),
+            base_fee: self.pool.block_info().pending_basefee,
+        })
+    }
+
+    /// Pool status method.
+    ///
+    /// The number of pending transactions returned defaults to [DEFAULT_POOL_STATUS_TXS] and is
+    /// capped at [MAX_POOL_STATUS_TXS]. Counts come from the pool's size and the pool's best
+    /// transactions are only iterated up to the limit.
+    async fn pool_status(&self, limit: Option<usize>) -> TelcoinNetworkRpcResult<PoolStatus> {
+        let limit = limit.unwrap_or(DEFAULT_POOL_STATUS_TXS).min(MAX_POOL_STATUS_TXS);
+        let size = self.pool.pool_size();
+
+        let top_pending = self
+            .pool
+            .best_transactions()
+            .take(limit)
+            .map(|tx| PendingTransactionSummary {
+                hash: *tx.hash(),
+                sender: tx.sender(),
+                nonce: tx.nonce(),
+                tip: tx.transaction.priority_fee_or_price(),
+            })
+            .collect();
+
+        Ok(PoolStatus {
+            pending: size.pending,
+            basefee: size.basefee,
+            blob: size.blob,
+            queued: size.queued,
+            total: size.total,
+            top_pending,
+        })
+    }
+
+    /// Send raw transactions method.
+    ///
+    /// Transactions that fail to decode or are rejected by the pool don't affect the others. All
+    /// decoded transactions are added to the pool together. Requests are limited to
+    /// [MAX_RAW_TRANSACTIONS].
+    async fn send_raw_transactions(
+        &self,
+        transactions: Vec<Bytes>,
+    ) -> TelcoinNetworkRpcResult<Vec<RawTransactionResult>> {
+        if transactions.len() > MAX_RAW_TRANSACTIONS {
+            return Err(TNRpcError::TooManyTransactions(transactions.len(), MAX_RAW_TRANSACTIONS));
+        }
+
+        // `None` until the pool's result is known
+        let mut results = Vec::with_capacity(transactions.len());
+        let mut indices = Vec::new();
+        let mut pool_txs = Vec::new();
+        for (index, tx) in transactions.iter().enumerate() {
+            match recover_raw_transaction::<PooledTransactionsElement>(tx) {
+                Ok(recovered) => {
+                    indices.push(index);
+                    pool_txs.push(Pool::Transaction::from_pooled(recovered));
+                    results.push(None);
+                }
+                Err(e) => {
+                    results.push(Some(RawTransactionResult::Rejected { error: e.to_string() }))
+                }
+            }
+        }
+
+        let added = self.pool.add_transactions(TransactionOrigin::Local, pool_txs).await;
+        for (index, result) in indices.into_iter().zip(added) {
+            results[index] = Some(match result {
+                Ok(hash) => RawTransactionResult::Added { hash },
+                Err(e) => RawTransactionResult::Rejected { error: e.to_string() },
+            });
+        }
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    /// Transaction proof method.
+    ///
+    /// The proof is built from the executed block's transactions, so batch transactions that were
+    /// skipped during execution are never proven.
+    async fn transaction_proof(
+        &self,
+        hash: TxHash,
+        block_hash: BlockHash,
+    ) -> TelcoinNetworkRpcResult<TransactionInclusionProof> {
+        let block =
+            self.provider.block_by_hash(block_hash)?.ok_or(TNRpcError::UnknownBlock(block_hash))?;
+        TransactionInclusionProof::new(&block.body.transactions, hash)
+            .ok_or(TNRpcError::TransactionNotInBlock(hash, block_hash))
+    }
 }
 
-impl<N> TelcoinNetworkRpcExt<N> {
+impl<N, Provider, Pool> TelcoinNetworkRpcExt<N, Provider, Pool> {
     /// Create new instance of the Telcoin Network RPC extension.
-    pub fn new(chain: Arc<ChainSpec>, _inner_node_network: N) -> Self {
-        Self { chain, _inner_node_network }
+    pub fn new(
+        chain: Arc<ChainSpec>,
+        _inner_node_network: N,
+        provider: Provider,
+        pool: Pool,
+    ) -> Self {
+        Self { chain, _inner_node_network, provider, pool }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TelcoinNetworkRpcExt, TelcoinNetworkRpcExtApiServer as _};
+    use crate::{error::TNRpcError, BlockLimits, TransactionStatus};
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction},
+        PoolTransaction as _, TransactionOrigin, TransactionPool as _, TransactionPoolExt as _,
+    };
+    use tn_types::{
+        adiri_chain_spec_arc, calculate_transaction_root, max_batch_gas, max_batch_size, now,
+        Address, Block, BlockBody, EthSignature, ExecHeader, Transaction, TransactionSigned,
+        TxLegacy, B256, U256,
+    };
+
+    #[tokio::test]
+    async fn test_tx_status_with_nonce_gap() {
+        let pool = testing_pool();
+        let rpc = TelcoinNetworkRpcExt::new(
+            adiri_chain_spec_arc(),
+            (),
+            MockEthProvider::default(),
+            pool.clone(),
+        );
+        let sender = Address::random();
+        let first = MockTransaction::eip1559().with_sender(sender).with_nonce(0);
+        let second = first.next();
+
+        // unknown before submission
+        let status = rpc.tx_status(*second.hash()).await.expect("tx status");
+        assert_eq!(status, TransactionStatus::Unknown);
+
+        // nonce gap
+        pool.add_transaction(TransactionOrigin::External, second.clone())
+            .await
+            .expect("second tx added");
+        let status = rpc.tx_status(*second.hash()).await.expect("tx status");
+        assert_eq!(status, TransactionStatus::Queued { sender, nonce: 1 });
+
+        // fill the gap
+        pool.add_transaction(TransactionOrigin::External, first.clone())
+            .await
+            .expect("first tx added");
+        let status = rpc.tx_status(*first.hash()).await.expect("tx status");
+        assert_eq!(status, TransactionStatus::Pending { sender, nonce: 0, position: 0 });
+        let status = rpc.tx_status(*second.hash()).await.expect("tx status");
+        assert_eq!(status, TransactionStatus::Pending { sender, nonce: 1, position: 1 });
+    }
+
+    #[tokio::test]
+    async fn test_block_limits_reflect_pool() {
+        let pool = testing_pool();
+        let mut block_info = pool.block_info();
+        block_info.pending_basefee = 12;
+        pool.set_block_info(block_info);
+        let rpc =
+            TelcoinNetworkRpcExt::new(adiri_chain_spec_arc(), (), MockEthProvider::default(), pool);
+
+        let limits = rpc.block_limits().await.expect("block limits");
+        let timestamp = now();
+        assert_eq!(
+            limits,
+            BlockLimits {
+                gas_limit: max_batch_gas(timestamp),
+                max_size: max_batch_size(timestamp),
+                base_fee: 12,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pool_status_reflects_pending_txs() {
+        let pool = testing_pool();
+        let rpc = TelcoinNetworkRpcExt::new(
+            adiri_chain_spec_arc(),
+            (),
+            MockEthProvider::default(),
+            pool.clone(),
+        );
+
+        // pending transactions from different senders and origins
+        let mut hashes = Vec::new();
+        for (tip, origin) in [
+            (10, TransactionOrigin::External),
+            (30, TransactionOrigin::Local),
+            (20, TransactionOrigin::External),
+        ] {
+            let tx = MockTransaction::eip1559()
+                .with_sender(Address::random())
+                .with_max_fee(100)
+                .with_priority_fee(tip);
+            hashes.push(*tx.hash());
+            pool.add_transaction(origin, tx).await.expect("tx added");
+        }
+
+        // nonce gap
+        let queued = MockTransaction::eip1559().with_sender(Address::random()).with_nonce(1);
+        pool.add_transaction(TransactionOrigin::External, queued).await.expect("queued tx added");
+
+        let status = rpc.pool_status(Some(2)).await.expect("pool status");
+        assert_eq!(status.pending, 3);
+        assert_eq!(status.queued, 1);
+        assert_eq!(status.total, 4);
+
+        // highest tips first
+        let top: Vec<_> = status.top_pending.iter().map(|tx| (tx.hash, tx.tip)).collect();
+        assert_eq!(top, vec![(hashes[1], 30), (hashes[2], 20)]);
+
+        // all pending transactions are returned by default
+        let status = rpc.pool_status(None).await.expect("pool status");
+        assert_eq!(status.top_pending.len(), 3);
+        assert_eq!(status.top_pending[2].hash, hashes[0]);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_proof_for_executed_block() {
+        let provider = MockEthProvider::default();
+        let rpc =
+            TelcoinNetworkRpcExt::new(adiri_chain_spec_arc(), (), provider.clone(), testing_pool());
+
+        // executed block with a few transactions
+        let transactions: Vec<_> = (0..3)
+            .map(|nonce| {
+                let tx = Transaction::Legacy(TxLegacy {
+                    nonce,
+                    gas_limit: 21_000,
+                    ..Default::default()
+                });
+                let signature = EthSignature::new(U256::from(1), U256::from(1), false);
+                TransactionSigned::new_unhashed(tx, signature)
+            })
+            .collect();
+        let header = ExecHeader {
+            number: 1,
+            transactions_root: calculate_transaction_root(&transactions),
+            ..Default::default()
+        };
+        let block_hash = header.hash_slow();
+        let body = BlockBody { transactions: transactions.clone(), ..Default::default() };
+        provider.add_block(block_hash, Block { header: header.clone(), body });
+
+        // the proof verifies against the block's transactions root
+        let tx_hash = transactions[1].hash();
+        let proof = rpc.transaction_proof(tx_hash, block_hash).await.expect("tx proof");
+        assert_eq!(proof.index, 1);
+        assert!(proof.verify(header.transactions_root));
+        assert!(!proof.verify(B256::ZERO));
+
+        // unknown transaction
+        let unknown = B256::random();
+        let res = rpc.transaction_proof(unknown, block_hash).await;
+        assert!(matches!(res, Err(TNRpcError::TransactionNotInBlock(hash, block))
+            if hash == unknown && block == block_hash));
+
+        // unknown block
+        let res = rpc.transaction_proof(tx_hash, unknown).await;
+        assert!(matches!(res, Err(TNRpcError::UnknownBlock(block)) if block == unknown));
     }
 }
@@ -0,0 +1,30 @@
+//! RPC types for transaction status.
+
+use serde::{Deserialize, Serialize};
+use tn_types::Address;
+
+/// The status of a transaction in the worker's pool.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum TransactionStatus {
+    /// The transaction is ready to be included in the next batch.
+    Pending {
+        /// The transaction signer.
+        sender: Address,
+        /// The transaction nonce.
+        nonce: u64,
+        /// The number of the sender's pending transactions ahead of this one.
+        position: usize,
+    },
+    /// The transaction is in the pool but can't be included in a batch yet.
+    ///
+    /// This is usually because of a nonce gap for the sender.
+    Queued {
+        /// The transaction signer.
+        sender: Address,
+        /// The transaction nonce.
+        nonce: u64,
+    },
+    /// The transaction is not in the pool.
+    Unknown,
+}
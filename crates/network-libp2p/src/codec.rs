@@ -2,7 +2,11 @@
 
 use async_trait::async_trait;
 use futures::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
-use libp2p::{request_response::Codec, StreamProtocol};
+use libp2p::{
+    gossipsub::{DataTransform, Message as GossipMessage, MessageId, RawMessage, TopicHash},
+    request_response::Codec,
+    StreamProtocol,
+};
 use serde::{de::DeserializeOwned, Serialize};
 use snap::read::FrameDecoder;
 use std::{
@@ -10,14 +14,23 @@ use std::{
     io::{Read as _, Write as _},
     marker::PhantomData,
 };
-use tn_types::encode_into_buffer;
+use tn_config::LibP2pConfig;
+use tn_types::{encode_into_buffer, keccak256, Epoch};
 
 #[cfg(test)]
 #[path = "tests/tn_codec_tests.rs"]
 mod tn_codec_tests;
 
 /// Convenience type for all traits implemented for messages used for TN request-response codec.
-pub trait TNMessage: Send + Serialize + DeserializeOwned + Clone + fmt::Debug + 'static {}
+pub trait TNMessage: Send + Serialize + DeserializeOwned + Clone + fmt::Debug + 'static {
+    /// Return true if the message includes certificates.
+    ///
+    /// Outbound requests that include certificates are limited by
+    /// `LibP2pConfig::send_certificate_rate_limit`.
+    fn carries_certificates(&self) -> bool {
+        false
+    }
+}
 
 /// The Telcoin Network request/response codec for consensus messages between peers.
 ///
@@ -199,3 +212,101 @@ where
         self.encode_message(io, res).await
     }
 }
+
+/// The magic number at the start of every zstd frame.
+///
+/// Peers use this prefix as the content tag for compressed gossip.
+const ZSTD_MAGIC_NUMBER: [u8; 4] = 0xFD2FB528_u32.to_le_bytes();
+
+/// The gossipsub data transform for compressing large gossip messages.
+///
+/// Gossip larger than the compression threshold is published as a zstd frame. Uncompressed gossip
+/// is published unchanged, so peers that don't compress gossip remain compatible. Received gossip
+/// is only decompressed if it starts with the zstd magic number.
+///
+/// Gossipsub computes the message id for published messages before the outbound transform and for
+/// received messages after the inbound transform. Message ids are always based on the uncompressed
+/// data.
+#[derive(Clone, Debug)]
+pub struct GossipCompression {
+    /// The minimum size (bytes) for published gossip to be compressed.
+    ///
+    /// Compression is disabled when `None`.
+    threshold: Option<usize>,
+    /// The maximum size (bytes) for decompressed gossip.
+    max_decompressed_size: usize,
+}
+
+impl GossipCompression {
+    /// Create a new instance of Self.
+    pub fn new(threshold: Option<usize>, max_decompressed_size: usize) -> Self {
+        Self { threshold, max_decompressed_size }
+    }
+
+    /// Helper method indicating if the published gossip should be compressed.
+    ///
+    /// Uncompressed data that starts with the zstd magic number is always compressed so peers
+    /// don't mistake it for a zstd frame.
+    fn should_compress(&self, data: &[u8]) -> bool {
+        data.starts_with(&ZSTD_MAGIC_NUMBER)
+            || self.threshold.is_some_and(|threshold| data.len() >= threshold)
+    }
+}
+
+impl Default for GossipCompression {
+    fn default() -> Self {
+        Self::new(None, LibP2pConfig::default().max_gossip_message_size)
+    }
+}
+
+impl DataTransform for GossipCompression {
+    fn inbound_transform(&self, raw_message: RawMessage) -> std::io::Result<GossipMessage> {
+        let data = if raw_message.data.starts_with(&ZSTD_MAGIC_NUMBER) {
+            // decompression fails if the message exceeds the max size
+            zstd::bulk::decompress(&raw_message.data, self.max_decompressed_size)?
+        } else {
+            raw_message.data
+        };
+
+        Ok(GossipMessage {
+            source: raw_message.source,
+            data,
+            sequence_number: raw_message.sequence_number,
+            topic: raw_message.topic,
+        })
+    }
+
+    fn outbound_transform(&self, _: &TopicHash, data: Vec<u8>) -> std::io::Result<Vec<u8>> {
+        if !self.should_compress(&data) {
+            return Ok(data);
+        }
+
+        zstd::bulk::compress(&data, zstd::DEFAULT_COMPRESSION_LEVEL)
+    }
+}
+
+/// The gossipsub message id based on the digest of the uncompressed message data.
+pub fn gossip_message_id(message: &GossipMessage) -> MessageId {
+    MessageId::new(keccak256(&message.data).as_slice())
+}
+
+/// The length of the epoch prefix for published gossip.
+pub(crate) const GOSSIP_EPOCH_LEN: usize = std::mem::size_of::<Epoch>();
+
+/// Prefix gossip with the epoch of the committee that publishes it.
+///
+/// Peers verify the publisher against the committee for this epoch.
+pub(crate) fn encode_gossip_epoch(epoch: Epoch, data: Vec<u8>) -> Vec<u8> {
+    let mut prefixed = Vec::with_capacity(GOSSIP_EPOCH_LEN + data.len());
+    prefixed.extend_from_slice(&epoch.to_be_bytes());
+    prefixed.extend(data);
+    prefixed
+}
+
+/// Return the epoch the gossip was published for.
+///
+/// Returns `None` if the data is too short to contain the epoch prefix.
+pub(crate) fn decode_gossip_epoch(data: &[u8]) -> Option<Epoch> {
+    let prefix = data.get(..GOSSIP_EPOCH_LEN)?;
+    Some(Epoch::from_be_bytes(prefix.try_into().ok()?))
+}
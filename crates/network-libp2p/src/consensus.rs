@@ -3,52 +3,175 @@
 //! This network is used by workers and primaries to reliably send consensus messages.
 
 use crate::{
-    codec::{TNCodec, TNMessage},
+    codec::{
+        decode_gossip_epoch, encode_gossip_epoch, gossip_message_id, GossipCompression, TNCodec,
+        TNMessage, GOSSIP_EPOCH_LEN,
+    },
     error::NetworkError,
+    metrics::NetworkMetrics,
     send_or_log_error,
-    types::{NetworkCommand, NetworkEvent, NetworkHandle, NetworkResult},
+    types::{
+        GossipAck, MeshHealth, NetworkCommand, NetworkEvent, NetworkHandle, NetworkResult,
+        PeerMetadata, TopicMeshHealth, GOSSIP_ACK_PROTOCOL,
+    },
+};
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt as _, StreamExt as _};
+use governor::{
+    clock::{Clock as _, DefaultClock},
+    DefaultKeyedRateLimiter, Quota, RateLimiter,
 };
-use futures::StreamExt as _;
 use libp2p::{
+    core::{
+        muxing::StreamMuxerBox,
+        transport::{Boxed, OptionalTransport},
+        upgrade,
+    },
     gossipsub::{
         self, Event as GossipEvent, IdentTopic, Message as GossipMessage, MessageAcceptance,
+        MessageId, TopicHash,
     },
+    identify::{self, Event as IdentifyEvent},
     multiaddr::Protocol,
+    noise,
+    ping::{self, Event as PingEvent},
+    quic,
     request_response::{
         self, Codec, Event as ReqResEvent, InboundFailure as ReqResInboundFailure,
-        InboundRequestId, OutboundRequestId,
+        InboundRequestId, OutboundRequestId, ProtocolSupport,
     },
-    swarm::{NetworkBehaviour, SwarmEvent},
-    PeerId, Swarm, SwarmBuilder,
+    swarm::{DialError, NetworkBehaviour, SwarmEvent},
+    tcp, yamux, Multiaddr, PeerId, Swarm, SwarmBuilder, Transport as _, TransportError,
 };
 use std::{
-    collections::{hash_map, HashMap, HashSet, VecDeque},
-    time::Duration,
+    collections::{hash_map, BTreeMap, HashMap, HashSet, VecDeque},
+    pin::Pin,
+    time::{Duration, Instant},
 };
-use tn_config::{ConsensusConfig, LibP2pConfig};
-use tn_types::NetworkKeypair;
-use tokio::sync::{
-    mpsc::{self, Receiver, Sender},
-    oneshot,
+use tn_config::{ConsensusConfig, GossipMeshConfig, LibP2pConfig, NetworkTransport, QuicConfig};
+use tn_types::{Epoch, NetworkKeypair};
+use tokio::{
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        oneshot,
+    },
+    time::Sleep,
 };
-use tracing::{error, info, instrument, trace, warn};
+use tracing::{debug, error, info, instrument, trace, warn};
 
 #[cfg(test)]
 #[path = "tests/network_tests.rs"]
 mod network_tests;
 
+/// The maximum amount of time to wait for the old swarm's connections to close while rotating the
+/// network keypair.
+const KEYPAIR_ROTATION_CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The protocol version shared with peers through identify.
+const IDENTIFY_PROTOCOL_VERSION: &str = "/telcoin-network/0.0.0";
+
+/// The number of published messages to track the reach for.
+///
+/// The oldest message is no longer tracked once the capacity is reached.
+const PUBLISHED_REACH_CAPACITY: usize = 1_000;
+
+/// The number of recently forwarded gossip message ids to track.
+///
+/// The oldest message id is no longer tracked once the capacity is reached.
+const RECENT_GOSSIP_CAPACITY: usize = 1_000;
+
+/// The number of attempts to listen on the old swarm's addresses while rotating the network
+/// keypair.
+const KEYPAIR_ROTATION_LISTEN_ATTEMPTS: usize = 10;
+
+/// The number of inbound request failures from a peer before its application score is lowered.
+const INBOUND_FAILURE_THRESHOLD: usize = 3;
+
+/// The application score penalty for peers with repeated inbound request failures.
+const INBOUND_FAILURE_PENALTY: f64 = 1.0;
+
+/// The interval for decaying application scores towards zero.
+const APPLICATION_SCORE_DECAY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The factor application scores are multiplied by every [APPLICATION_SCORE_DECAY_INTERVAL].
+const APPLICATION_SCORE_DECAY: f64 = 0.9;
+
+/// The magnitude below which a decayed application score is reset to zero.
+const APPLICATION_SCORE_DECAY_TO_ZERO: f64 = 0.1;
+
+/// The interval to drop certificate rate limiter state for peers with a replenished quota.
+const CERTIFICATE_RATE_LIMITER_PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A dial in progress for a peer.
+#[derive(Debug)]
+struct PendingDial {
+    /// The callers waiting for the dial to resolve.
+    ///
+    /// Concurrent dials to the same peer join the pending dial.
+    replies: Vec<oneshot::Sender<NetworkResult<()>>>,
+    /// The number of dial attempts so far.
+    attempts: usize,
+}
+
+/// A network keypair rotation in progress.
+///
+/// The new swarm is already processing events. The rotation waits for the old swarm's connections
+/// to close, then listens on the old swarm's addresses before replying with the new [PeerId].
+struct KeypairRotation<B>
+where
+    B: NetworkBehaviour,
+{
+    /// The swarm built with the old keypair.
+    ///
+    /// This is `None` once the old swarm's connections closed and the swarm was dropped.
+    old_swarm: Option<Swarm<B>>,
+    /// The old swarm's listen addresses the new swarm has yet to listen on.
+    listen_addrs: VecDeque<Multiaddr>,
+    /// The number of attempts to listen on the first address.
+    listen_attempts: usize,
+    /// The deadline for closing the old swarm's connections, then the backoff between attempts
+    /// to listen.
+    timer: Pin<Box<Sleep>>,
+    /// The peer id for the new keypair.
+    peer_id: PeerId,
+    /// The reply for the caller once the rotation completes.
+    reply: oneshot::Sender<NetworkResult<PeerId>>,
+}
+
+/// The progress of a [KeypairRotation] driven by the network loop.
+enum KeypairRotationStep {
+    /// The old swarm produced an event while its connections close.
+    OldSwarmEvent,
+    /// The rotation's timer expired.
+    TimerExpired,
+}
+
 /// Custom network libp2p behaviour type for Telcoin Network.
 ///
-/// The behavior includes gossipsub and request-response.
+/// The behavior includes gossipsub, request-response, gossip acknowledgements, identify, and ping.
 #[derive(NetworkBehaviour)]
 pub struct TNBehavior<C>
 where
     C: Codec + Send + Clone + 'static,
 {
     /// The gossipsub network behavior.
-    pub(crate) gossipsub: gossipsub::Behaviour,
+    ///
+    /// Large gossip messages are compressed based on the [LibP2pConfig].
+    pub(crate) gossipsub: gossipsub::Behaviour<GossipCompression>,
     /// The request-response network behavior.
     pub(crate) req_res: request_response::Behaviour<C>,
+    /// The request-response behavior for acknowledging gossip to the message's publisher.
+    ///
+    /// Publishers track the peers that re-propagated their messages through these
+    /// acknowledgements.
+    pub(crate) gossip_ack: request_response::Behaviour<TNCodec<GossipAck, GossipAck>>,
+    /// The identify network behavior.
+    ///
+    /// Peers exchange supported protocols and listen addresses when they connect.
+    pub(crate) identify: identify::Behaviour,
+    /// The ping network behavior.
+    ///
+    /// Peers that fail successive pings are disconnected.
+    pub(crate) ping: ping::Behaviour,
 }
 
 impl<C> TNBehavior<C>
@@ -56,8 +179,14 @@ where
     C: Codec + Send + Clone + 'static,
 {
     /// Create a new instance of Self.
-    pub fn new(gossipsub: gossipsub::Behaviour, req_res: request_response::Behaviour<C>) -> Self {
-        Self { gossipsub, req_res }
+    pub fn new(
+        gossipsub: gossipsub::Behaviour<GossipCompression>,
+        req_res: request_response::Behaviour<C>,
+        gossip_ack: request_response::Behaviour<TNCodec<GossipAck, GossipAck>>,
+        identify: identify::Behaviour,
+        ping: ping::Behaviour,
+    ) -> Self {
+        Self { gossipsub, req_res, gossip_ack, identify, ping }
     }
 }
 
@@ -83,19 +212,54 @@ where
     handle: Sender<NetworkCommand<Req, Res>>,
     /// The receiver for processing network handle requests.
     commands: Receiver<NetworkCommand<Req, Res>>,
-    /// The collection of staked validators.
+    /// The collection of staked validators for each epoch.
     ///
-    /// This set must be updated at the start of each epoch. It is used to verify message sources
-    /// are from validators.
-    authorized_publishers: HashSet<PeerId>,
+    /// A new set must be added at the start of each epoch. During an epoch change, the retiring
+    /// committee and the new committee overlap: messages from either committee are accepted
+    /// until the overlap window is closed and only the latest epoch remains. It is used to verify
+    /// message sources are from validators.
+    authorized_publishers_by_epoch: BTreeMap<Epoch, HashSet<PeerId>>,
+    /// Whether the previous epoch's publishers are still authorized.
+    ///
+    /// Opened when a new epoch's publishers are added and closed with
+    /// [NetworkCommand::CloseEpochOverlap].
+    epoch_overlap_open: bool,
+    /// The authorities removed from the authorized publishers and the time they were removed.
+    ///
+    /// Gossip from these peers is ignored until `LibP2pConfig::removed_publisher_grace_period`
+    /// expires.
+    recently_removed_publishers: HashMap<PeerId, Instant>,
     /// The collection of pending dials.
-    pending_dials: HashMap<PeerId, oneshot::Sender<NetworkResult<()>>>,
+    pending_dials: HashMap<PeerId, PendingDial>,
+    /// The backoff timers for failed dials that will be retried.
+    ///
+    /// Each timer resolves to the peer to redial.
+    dial_retries: FuturesUnordered<BoxFuture<'static, PeerId>>,
     /// The collection of pending outbound requests.
     ///
     /// Callers include a oneshot channel for the network to return response. The caller is
     /// responsible for decoding message bytes and reporting peers who return bad data. Peers that
     /// send messages that fail to decode must receive an application score penalty.
     outbound_requests: HashMap<OutboundRequestId, oneshot::Sender<NetworkResult<Res>>>,
+    /// The outbound requests in flight for each peer.
+    ///
+    /// The number of requests in flight to a single peer is limited by
+    /// `LibP2pConfig::max_outbound_requests_per_peer`.
+    in_flight_requests: HashMap<PeerId, HashSet<OutboundRequestId>>,
+    /// The outbound requests waiting for a peer's in-flight requests to complete.
+    queued_requests: HashMap<PeerId, VecDeque<(Req, oneshot::Sender<NetworkResult<Res>>)>>,
+    /// The per-peer rate limiter for outbound requests that carry certificates.
+    ///
+    /// Built from `LibP2pConfig::send_certificate_rate_limit`. There is no limit when `None`.
+    certificate_rate_limiter: Option<DefaultKeyedRateLimiter<PeerId>>,
+    /// The outbound requests delayed by the certificate rate limit for each peer.
+    ///
+    /// Delayed requests are sent in the order they were made once the peer's quota allows them.
+    rate_limited_requests: HashMap<PeerId, VecDeque<(Req, oneshot::Sender<NetworkResult<Res>>)>>,
+    /// The timers for peers with requests delayed by the certificate rate limit.
+    ///
+    /// Each timer resolves to the peer once its quota should allow the next delayed request.
+    rate_limit_timers: FuturesUnordered<BoxFuture<'static, PeerId>>,
     /// The collection of pending inbound requests.
     ///
     /// Callers include a oneshot channel for the network to return a cancellation notice. The
@@ -105,10 +269,66 @@ where
     inbound_requests: HashMap<InboundRequestId, oneshot::Sender<()>>,
     /// The configurables for the libp2p consensus network implementation.
     config: LibP2pConfig,
+    /// The configurables for the quic transport.
+    ///
+    /// Stored to rebuild the swarm when the network keypair rotates.
+    quic_config: QuicConfig,
+    /// The transports the swarm listens and dials on.
+    transports: Vec<NetworkTransport>,
+    /// The gossipsub mesh parameters derived from the committee size.
+    ///
+    /// Stored to rebuild the swarm when the network keypair rotates.
+    gossip_mesh_config: GossipMeshConfig,
+    /// The interval and timeout for pinging connected peers.
+    ///
+    /// Stored to rebuild the swarm when the network keypair rotates.
+    ping_config: ping::Config,
+    /// The number of successive failed pings before a peer is disconnected.
+    max_ping_failures: usize,
+    /// The successive failed pings for each connected peer.
+    ping_failures: HashMap<PeerId, usize>,
+    /// The known addresses for peers this node dialed.
+    ///
+    /// These peers are redialed when the network keypair rotates.
+    peer_addresses: HashMap<PeerId, Multiaddr>,
+    /// The explicit gossipsub peers.
+    explicit_peers: HashSet<PeerId>,
+    /// The metadata connected peers shared through identify.
+    peer_metadata: HashMap<PeerId, PeerMetadata>,
+    /// The application scores for peers reported by callers.
+    ///
+    /// Penalties accumulate and the total is set as the peer's gossipsub application score. Scores
+    /// decay towards zero every [APPLICATION_SCORE_DECAY_INTERVAL] so peers recover from old
+    /// penalties.
+    application_scores: HashMap<PeerId, f64>,
+    /// The inbound request failures from each peer since the peer was last penalized.
+    ///
+    /// Peers are penalized once [INBOUND_FAILURE_THRESHOLD] failures are reached.
+    inbound_failures: HashMap<PeerId, usize>,
+    /// Metrics for the network.
+    metrics: NetworkMetrics,
+    /// The peers that acknowledged re-propagating each message this node published.
+    ///
+    /// Tracking starts empty when the message is published and grows as [GossipAck]s arrive.
+    published_reach: HashMap<MessageId, HashSet<PeerId>>,
+    /// The published message ids in the order they were published.
+    ///
+    /// Used to stop tracking the oldest message once [PUBLISHED_REACH_CAPACITY] is reached.
+    published_order: VecDeque<MessageId>,
+    /// The ids of gossip messages recently forwarded to the event stream.
+    ///
+    /// Messages re-delivered by multiple mesh peers are only forwarded once.
+    recent_gossip: HashSet<MessageId>,
+    /// The recently forwarded gossip message ids in the order they were received.
+    ///
+    /// Used to stop tracking the oldest message once [RECENT_GOSSIP_CAPACITY] is reached.
+    recent_gossip_order: VecDeque<MessageId>,
     /// Track peers we have a connection with.
     /// This explicitly tracked and is a VecDeque so we can use to round robin requests without an
     /// explicit peer.
     connected_peers: VecDeque<PeerId>,
+    /// The network keypair rotation in progress, if any.
+    keypair_rotation: Option<KeypairRotation<TNBehavior<TNCodec<Req, Res>>>>,
 }
 
 impl<Req, Res> ConsensusNetwork<Req, Res>
@@ -127,7 +347,8 @@ where
         let topics = vec![IdentTopic::new("tn-primary")];
         let network_key = config.key_config().primary_network_keypair().clone();
         let authorized_publishers = config.committee_peer_ids();
-        Self::new(config, event_stream, topics, network_key, authorized_publishers)
+        let metrics = NetworkMetrics::new("primary");
+        Self::new(config, event_stream, topics, network_key, authorized_publishers, metrics)
     }
 
     /// Convenience method for spawning a worker network instance.
@@ -142,7 +363,8 @@ where
         let network_key = config.key_config().worker_network_keypair().clone();
         let authorized_publishers =
             config.worker_cache().all_workers().iter().map(|(id, _)| *id).collect();
-        Self::new(config, event_stream, topics, network_key, authorized_publishers)
+        let metrics = NetworkMetrics::new("worker");
+        Self::new(config, event_stream, topics, network_key, authorized_publishers, metrics)
     }
 
     /// Create a new instance of Self.
@@ -152,82 +374,143 @@ where
         topics: Vec<IdentTopic>,
         keypair: NetworkKeypair,
         authorized_publishers: HashSet<PeerId>,
+        metrics: NetworkMetrics,
     ) -> NetworkResult<Self>
     where
         DB: tn_types::database_traits::Database,
     {
-        let gossipsub_config = gossipsub::ConfigBuilder::default()
-            // explicitly set default
-            .heartbeat_interval(Duration::from_secs(1))
-            // explicitly set default
-            .validation_mode(gossipsub::ValidationMode::Strict)
-            // support peer exchange
-            .do_px()
-            // TN specific: filter against authorized_publishers for certain topics
-            .validate_messages()
-            .build()?;
-        let gossipsub = gossipsub::Behaviour::new(
+        let config = consensus_config.network_config().libp2p_config().clone();
+        let quic_config = consensus_config.network_config().quic_config().clone();
+        let transports = consensus_config.parameters().network_transports.clone();
+        let gossip_mesh_config = consensus_config.gossip_mesh_config();
+        let parameters = consensus_config.parameters();
+        let ping_config = ping::Config::new()
+            .with_interval(parameters.peer_ping_interval)
+            .with_timeout(parameters.peer_ping_timeout);
+        let max_ping_failures = parameters.max_peer_ping_failures;
+        let swarm = Self::build_swarm(
+            keypair,
+            &config,
+            &quic_config,
+            &transports,
+            &gossip_mesh_config,
+            &ping_config,
+        )?;
+
+        let certificate_rate_limiter = certificate_rate_limiter(&config);
+        let (handle, commands) = tokio::sync::mpsc::channel(100);
+        let authorized_publishers_by_epoch =
+            BTreeMap::from([(consensus_config.committee().epoch(), authorized_publishers)]);
+
+        Ok(Self {
+            swarm,
+            topics,
+            handle,
+            commands,
+            event_stream,
+            authorized_publishers_by_epoch,
+            epoch_overlap_open: false,
+            recently_removed_publishers: Default::default(),
+            pending_dials: Default::default(),
+            dial_retries: Default::default(),
+            outbound_requests: Default::default(),
+            in_flight_requests: Default::default(),
+            queued_requests: Default::default(),
+            certificate_rate_limiter,
+            rate_limited_requests: Default::default(),
+            rate_limit_timers: Default::default(),
+            inbound_requests: Default::default(),
+            config,
+            quic_config,
+            transports,
+            gossip_mesh_config,
+            ping_config,
+            max_ping_failures,
+            ping_failures: Default::default(),
+            peer_addresses: Default::default(),
+            explicit_peers: Default::default(),
+            peer_metadata: Default::default(),
+            application_scores: Default::default(),
+            inbound_failures: Default::default(),
+            metrics,
+            published_reach: Default::default(),
+            published_order: Default::default(),
+            recent_gossip: Default::default(),
+            recent_gossip_order: Default::default(),
+            connected_peers: VecDeque::new(),
+            keypair_rotation: None,
+        })
+    }
+
+    /// Build the swarm with the network keypair.
+    ///
+    /// The keypair determines the swarm's [PeerId] and signs published gossip.
+    fn build_swarm(
+        keypair: NetworkKeypair,
+        libp2p_config: &LibP2pConfig,
+        quic_config: &QuicConfig,
+        transports: &[NetworkTransport],
+        gossip_mesh_config: &GossipMeshConfig,
+        ping_config: &ping::Config,
+    ) -> NetworkResult<Swarm<TNBehavior<TNCodec<Req, Res>>>> {
+        let transport = build_transport(&keypair, quic_config, transports)?;
+        let gossipsub_config = gossipsub_config(gossip_mesh_config)?;
+        let gossip_compression = GossipCompression::new(
+            libp2p_config.gossip_compression_threshold,
+            libp2p_config.max_gossip_message_size,
+        );
+        let mut gossipsub = gossipsub::Behaviour::new_with_transform(
             gossipsub::MessageAuthenticity::Signed(keypair.clone()),
             gossipsub_config,
+            gossip_compression,
         )
         .map_err(NetworkError::GossipBehavior)?;
 
-        let tn_codec = TNCodec::<Req, Res>::new(
-            consensus_config.network_config().libp2p_config().max_rpc_message_size,
-        );
+        // peer scoring is required to penalize peers with application scores
+        gossipsub
+            .with_peer_score(
+                gossipsub::PeerScoreParams::default(),
+                gossipsub::PeerScoreThresholds::default(),
+            )
+            .map_err(|e| NetworkError::EnablePeerScoreBehavior(e.to_string()))?;
+
+        let tn_codec = TNCodec::<Req, Res>::new(libp2p_config.max_rpc_message_size);
 
         let req_res = request_response::Behaviour::with_codec(
             tn_codec,
-            consensus_config.network_config().libp2p_config().supported_req_res_protocols.clone(),
+            libp2p_config.supported_req_res_protocols.clone(),
             request_response::Config::default(),
         );
 
+        let gossip_ack = request_response::Behaviour::with_codec(
+            TNCodec::<GossipAck, GossipAck>::new(libp2p_config.max_rpc_message_size),
+            [(GOSSIP_ACK_PROTOCOL, ProtocolSupport::Full)],
+            request_response::Config::default(),
+        );
+
+        let identify = identify::Behaviour::new(
+            identify::Config::new(IDENTIFY_PROTOCOL_VERSION.to_string(), keypair.public())
+                .with_agent_version(format!("telcoin-network/{}", env!("CARGO_PKG_VERSION"))),
+        );
+
+        let ping = ping::Behaviour::new(ping_config.clone());
+
         // create custom behavior
-        let behavior = TNBehavior::new(gossipsub, req_res);
+        let behavior = TNBehavior::new(gossipsub, req_res, gossip_ack, identify, ping);
 
         // create swarm
         let swarm = SwarmBuilder::with_existing_identity(keypair)
             .with_tokio()
-            .with_quic_config(|mut config| {
-                config.handshake_timeout =
-                    consensus_config.network_config().quic_config().handshake_timeout;
-                config.max_idle_timeout =
-                    consensus_config.network_config().quic_config().max_idle_timeout;
-                config.keep_alive_interval =
-                    consensus_config.network_config().quic_config().keep_alive_interval;
-                config.max_concurrent_stream_limit =
-                    consensus_config.network_config().quic_config().max_concurrent_stream_limit;
-                config.max_stream_data =
-                    consensus_config.network_config().quic_config().max_stream_data;
-                config.max_connection_data =
-                    consensus_config.network_config().quic_config().max_connection_data;
-                config
-            })
+            .with_other_transport(|_| transport)
+            .map_err(|_| NetworkError::BuildSwarm)?
             .with_behaviour(|_| behavior)
             .map_err(|_| NetworkError::BuildSwarm)?
             .with_swarm_config(|c| {
-                c.with_idle_connection_timeout(
-                    consensus_config.network_config().libp2p_config().max_idle_connection_timeout,
-                )
+                c.with_idle_connection_timeout(libp2p_config.max_idle_connection_timeout)
             })
             .build();
 
-        let (handle, commands) = tokio::sync::mpsc::channel(100);
-        let config = consensus_config.network_config().libp2p_config().clone();
-
-        Ok(Self {
-            swarm,
-            topics,
-            handle,
-            commands,
-            event_stream,
-            authorized_publishers,
-            pending_dials: Default::default(),
-            outbound_requests: Default::default(),
-            inbound_requests: Default::default(),
-            config,
-            connected_peers: VecDeque::new(),
-        })
+        Ok(swarm)
     }
 
     /// Return a [NetworkHandle] to send commands to this network.
@@ -237,11 +520,26 @@ where
 
     /// Run the network loop to process incoming gossip.
     pub async fn run(mut self) -> NetworkResult<()> {
+        let mut score_decay = tokio::time::interval(APPLICATION_SCORE_DECAY_INTERVAL);
+        score_decay.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut rate_limiter_prune = tokio::time::interval(CERTIFICATE_RATE_LIMITER_PRUNE_INTERVAL);
+        rate_limiter_prune.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
         loop {
             tokio::select! {
                 event = self.swarm.select_next_some() => self.process_event(event).await?,
+                _ = score_decay.tick() => self.decay_application_scores(),
+                _ = rate_limiter_prune.tick() => self.prune_certificate_rate_limiter(),
+                Some(peer_id) = self.dial_retries.next(), if !self.dial_retries.is_empty() => {
+                    self.retry_dial(peer_id)
+                }
+                Some(peer) = self.rate_limit_timers.next(), if !self.rate_limit_timers.is_empty() => {
+                    self.send_delayed_requests(peer)
+                }
+                step = Self::next_keypair_rotation_step(&mut self.keypair_rotation), if self.keypair_rotation.is_some() => {
+                    self.advance_keypair_rotation(step)
+                }
                 command = self.commands.recv() => match command {
-                    Some(c) => self.process_command(c),
+                    Some(c) => self.process_command(c).await,
                     None => {
                         info!(target: "network", topics=?self.topics, "subscriber shutting down...");
                         return Ok(())
@@ -261,6 +559,9 @@ where
             SwarmEvent::Behaviour(behavior) => match behavior {
                 TNBehaviorEvent::Gossipsub(event) => self.process_gossip_event(event)?,
                 TNBehaviorEvent::ReqRes(event) => self.process_reqres_event(event)?,
+                TNBehaviorEvent::GossipAck(event) => self.process_gossip_ack_event(event),
+                TNBehaviorEvent::Identify(event) => self.process_identify_event(event),
+                TNBehaviorEvent::Ping(event) => self.process_ping_event(event),
             },
             SwarmEvent::ConnectionEstablished {
                 peer_id,
@@ -271,13 +572,23 @@ where
                 established_in,
             } => {
                 if endpoint.is_dialer() {
-                    if let Some(sender) = self.pending_dials.remove(&peer_id) {
-                        send_or_log_error!(sender, Ok(()), "ConnectionEstablished", peer = peer_id);
+                    if let Some(pending) = self.pending_dials.remove(&peer_id) {
+                        for reply in pending.replies {
+                            send_or_log_error!(
+                                reply,
+                                Ok(()),
+                                "ConnectionEstablished",
+                                peer = peer_id
+                            );
+                        }
                     }
                 }
                 if !self.connected_peers.contains(&peer_id) {
                     self.connected_peers.push_back(peer_id);
                 }
+                if num_established.get() == 1 {
+                    self.forward_peer_event(NetworkEvent::PeerConnected(peer_id));
+                }
 
                 // Log successful connection establishment
                 info!(
@@ -326,17 +637,23 @@ where
                     // sanity check to prevent the HashMap from growing indefinitely when peers
                     // disconnect after a request is made and the PeerId is lost.
                     self.outbound_requests.retain(|_, sender| !sender.is_closed());
+                    self.peer_metadata.remove(&peer_id);
+                    self.ping_failures.remove(&peer_id);
+                    self.inbound_failures.remove(&peer_id);
 
                     // TODO: schedule reconnection attempt?
-                    if self.authorized_publishers.contains(&peer_id) {
+                    if self
+                        .authorized_publishers_by_epoch
+                        .values()
+                        .any(|publishers| publishers.contains(&peer_id))
+                    {
                         warn!(target: "network::events", ?peer_id, "authorized peer disconnected");
                     }
+                    self.forward_peer_event(NetworkEvent::PeerDisconnected(peer_id));
                 }
             }
             SwarmEvent::OutgoingConnectionError { peer_id: Some(peer_id), error, .. } => {
-                if let Some(sender) = self.pending_dials.remove(&peer_id) {
-                    send_or_log_error!(sender, Err(error.into()), "OutgoingConnectionError");
-                }
+                self.dial_failed(peer_id, error);
             }
             SwarmEvent::ExpiredListenAddr { address, .. } => {
                 // log listening addr
@@ -372,14 +689,49 @@ where
     }
 
     /// Process commands for the network.
-    fn process_command(&mut self, command: NetworkCommand<Req, Res>) {
+    async fn process_command(&mut self, command: NetworkCommand<Req, Res>) {
         match command {
             NetworkCommand::UpdateAuthorizedPublishers { authorities, reply } => {
-                self.authorized_publishers = authorities;
+                // track removed authorities so their in-flight gossip is ignored
+                self.track_removed_publishers(&authorities);
+
+                // replace all epochs with the latest epoch's updated set
+                let epoch = self.latest_epoch();
+                self.authorized_publishers_by_epoch = BTreeMap::from([(epoch, authorities)]);
+                self.epoch_overlap_open = false;
                 send_or_log_error!(reply, Ok(()), "UpdateAuthorizedPublishers");
             }
+            NetworkCommand::NewEpochAuthorizedPublishers { epoch, authorities, reply } => {
+                info!(target: "network", topics=?self.topics, ?epoch, "adding authorized publishers for new epoch");
+                self.authorized_publishers_by_epoch.insert(epoch, authorities);
+
+                // only the retiring committee overlaps with the new epoch
+                let latest = self.latest_epoch();
+                self.authorized_publishers_by_epoch.retain(|e, _| *e >= latest.saturating_sub(1));
+                self.epoch_overlap_open = self.authorized_publishers_by_epoch.len() > 1;
+                send_or_log_error!(reply, Ok(()), "NewEpochAuthorizedPublishers");
+            }
+            NetworkCommand::CloseEpochOverlap { reply } => {
+                let latest = self.latest_epoch();
+                info!(target: "network", topics=?self.topics, epoch=?latest, "closing epoch overlap window");
+
+                // the retiring committee's in-flight gossip is ignored
+                let remaining =
+                    self.authorized_publishers_by_epoch.get(&latest).cloned().unwrap_or_default();
+                self.track_removed_publishers(&remaining);
+                self.authorized_publishers_by_epoch.retain(|e, _| *e == latest);
+                self.epoch_overlap_open = false;
+                send_or_log_error!(reply, Ok(()), "CloseEpochOverlap");
+            }
             NetworkCommand::StartListening { multiaddr, reply } => {
-                let res = self.swarm.listen_on(multiaddr);
+                // listen on the address with every enabled transport
+                let res = transport_addrs(&multiaddr, &self.transports)
+                    .into_iter()
+                    .map(|addr| self.swarm.listen_on(addr))
+                    .collect::<Result<Vec<_>, _>>()
+                    .and_then(|ids| {
+                        ids.first().copied().ok_or(TransportError::MultiaddrNotSupported(multiaddr))
+                    });
                 send_or_log_error!(reply, res, "StartListening");
             }
             NetworkCommand::GetListener { reply } => {
@@ -387,34 +739,40 @@ where
                 send_or_log_error!(reply, addrs, "GetListeners");
             }
             NetworkCommand::AddExplicitPeer { peer_id, addr } => {
+                self.peer_addresses.insert(peer_id, addr.clone());
+                self.explicit_peers.insert(peer_id);
                 self.swarm.add_peer_address(peer_id, addr);
                 self.swarm.behaviour_mut().gossipsub.add_explicit_peer(&peer_id);
             }
             NetworkCommand::Dial { peer_id, peer_addr, reply } => {
-                if let hash_map::Entry::Vacant(entry) = self.pending_dials.entry(peer_id) {
-                    // Add the peer we are dialing so we can easily reconnect after a timeout, etc.
-                    // Can use "peer_addr.with(Protocol::P2p(peer_id))})" as the dial parameter
-                    // without adding the peer but libp2p won't remember it.
-                    self.swarm.add_peer_address(peer_id, peer_addr);
-                    match self.swarm.dial(peer_id) {
-                        Ok(()) => {
-                            entry.insert(reply);
+                match self.pending_dials.entry(peer_id) {
+                    hash_map::Entry::Vacant(entry) => {
+                        // Add the peer we are dialing so we can easily reconnect after a timeout,
+                        // etc. Can use "peer_addr.with(Protocol::P2p(peer_id))})" as the dial
+                        // parameter without adding the peer but libp2p won't remember it.
+                        self.peer_addresses.insert(peer_id, peer_addr.clone());
+                        for addr in transport_addrs(&peer_addr, &self.transports) {
+                            self.swarm.add_peer_address(peer_id, addr);
                         }
-                        Err(e) => {
-                            send_or_log_error!(
-                                reply,
-                                Err(e.into()),
-                                "AddExplicitPeer",
-                                peer = peer_id,
-                            );
+                        match self.swarm.dial(peer_id) {
+                            Ok(()) => {
+                                entry.insert(PendingDial { replies: vec![reply], attempts: 1 });
+                            }
+                            Err(e) => {
+                                send_or_log_error!(
+                                    reply,
+                                    Err(e.into()),
+                                    "AddExplicitPeer",
+                                    peer = peer_id,
+                                );
+                            }
                         }
                     }
-                } else {
-                    // return error - dial attempt already tracked for peer
-                    //
-                    // may be necessary to update entry in future, but for now assume only one dial
-                    // attempt
-                    send_or_log_error!(reply, Err(NetworkError::RedialAttempt), "AddExplicitPeer");
+                    hash_map::Entry::Occupied(mut entry) => {
+                        // resolve with the dial already in progress
+                        trace!(target: "network", ?peer_id, "joining pending dial");
+                        entry.get_mut().replies.push(reply);
+                    }
                 }
             }
             NetworkCommand::LocalPeerId { reply } => {
@@ -422,9 +780,18 @@ where
                 send_or_log_error!(reply, peer_id, "LocalPeerId");
             }
             NetworkCommand::Publish { topic, msg, reply } => {
-                let res = self.swarm.behaviour_mut().gossipsub.publish(topic, msg);
+                // peers authorize the message against the committee for this epoch
+                let data = encode_gossip_epoch(self.publishing_epoch(), msg);
+                let res = self.swarm.behaviour_mut().gossipsub.publish(topic, data);
+                if let Ok(message_id) = &res {
+                    self.track_published_message(message_id.clone());
+                }
                 send_or_log_error!(reply, res, "Publish");
             }
+            NetworkCommand::PublishedReach { message_id, reply } => {
+                let reach = self.published_reach.get(&message_id).cloned();
+                send_or_log_error!(reply, reach, "PublishedReach");
+            }
             NetworkCommand::Subscribe { topic, reply } => {
                 let res = self.swarm.behaviour_mut().gossipsub.subscribe(&topic);
                 send_or_log_error!(reply, res, "Subscribe");
@@ -438,6 +805,8 @@ where
                 send_or_log_error!(reply, opt_score, "PeerScore");
             }
             NetworkCommand::SetApplicationScore { peer_id, new_score, reply } => {
+                // later reports and decay start from the new score
+                self.application_scores.insert(peer_id, new_score);
                 let bool =
                     self.swarm.behaviour_mut().gossipsub.set_application_score(&peer_id, new_score);
                 send_or_log_error!(reply, bool, "SetApplicationScore");
@@ -463,15 +832,31 @@ where
                     self.swarm.behaviour_mut().gossipsub.mesh_peers(&topic).cloned().collect();
                 send_or_log_error!(reply, collection, "MeshPeers");
             }
+            NetworkCommand::MeshHealth { reply } => {
+                let mesh_n_low = self.gossip_mesh_config.mesh_n_low;
+                let gossipsub = &self.swarm.behaviour().gossipsub;
+                let topics = gossipsub
+                    .topics()
+                    .map(|topic| {
+                        let mesh_peers = gossipsub.mesh_peers(topic).count();
+                        (
+                            topic.clone(),
+                            TopicMeshHealth { mesh_peers, healthy: mesh_peers >= mesh_n_low },
+                        )
+                    })
+                    .collect();
+                send_or_log_error!(reply, MeshHealth { mesh_n_low, topics }, "MeshHealth");
+            }
             NetworkCommand::SendRequest { peer, request, reply } => {
-                let request_id = self.swarm.behaviour_mut().req_res.send_request(&peer, request);
-                self.outbound_requests.insert(request_id, reply);
+                self.send_rate_limited_request(peer, request, reply);
             }
             NetworkCommand::SendRequestAny { request, reply } => {
                 self.connected_peers.rotate_left(1);
-                if let Some(peer) = self.connected_peers.front() {
-                    let request_id = self.swarm.behaviour_mut().req_res.send_request(peer, request);
-                    self.outbound_requests.insert(request_id, reply);
+                if let Some(peer) = self.connected_peers.front().copied() {
+                    // return the peer so the caller can attribute the response
+                    let (response, to_caller) = oneshot::channel();
+                    self.send_rate_limited_request(peer, request, response);
+                    send_or_log_error!(reply, Ok((peer, to_caller)), "SendRequestAny");
                 } else {
                     // Ignore error since this means other end lost interest and we don't really
                     // care.
@@ -486,6 +871,420 @@ where
                 let count = self.outbound_requests.len();
                 send_or_log_error!(reply, count, "SendResponse");
             }
+            NetworkCommand::ReportPeer { peer_id, penalty, reply } => {
+                let applied = self.penalize_peer(peer_id, penalty);
+                send_or_log_error!(reply, applied, "ReportPeer");
+            }
+            NetworkCommand::PeerMetadata { peer_id, reply } => {
+                let metadata = self.peer_metadata.get(&peer_id).cloned();
+                send_or_log_error!(reply, metadata, "PeerMetadata");
+            }
+            NetworkCommand::RotateKeypair { keypair, reply } => {
+                // the rotation replies once it completes
+                self.rotate_keypair(keypair, reply);
+            }
+        }
+    }
+
+    /// Send the request to the peer or delay it if the request exceeds the peer's certificate rate
+    /// limit.
+    ///
+    /// Certificate requests queue behind the peer's delayed requests so they are sent in order.
+    fn send_rate_limited_request(
+        &mut self,
+        peer: PeerId,
+        request: Req,
+        reply: oneshot::Sender<NetworkResult<Res>>,
+    ) {
+        if request.carries_certificates() {
+            if let Some(limiter) = &self.certificate_rate_limiter {
+                if let Some(delayed) = self.rate_limited_requests.get_mut(&peer) {
+                    trace!(target: "network", ?peer, "certificate requests delayed - queuing outbound request");
+                    delayed.push_back((request, reply));
+                    return;
+                }
+
+                if let Err(not_until) = limiter.check_key(&peer) {
+                    let delay = not_until.wait_time_from(DefaultClock::default().now());
+                    trace!(target: "network", ?peer, ?delay, "certificate rate limit exceeded - delaying outbound request");
+                    self.rate_limited_requests.entry(peer).or_default().push_back((request, reply));
+                    self.start_rate_limit_timer(peer, delay);
+                    return;
+                }
+            }
+        }
+
+        self.send_or_queue_request(peer, request, reply);
+    }
+
+    /// Send the peer's delayed certificate requests in order until the rate limit is exceeded
+    /// again.
+    ///
+    /// Requests the caller no longer wants are dropped without using the peer's quota.
+    fn send_delayed_requests(&mut self, peer: PeerId) {
+        let Some(limiter) = &self.certificate_rate_limiter else { return };
+        let Some(delayed) = self.rate_limited_requests.get_mut(&peer) else { return };
+
+        let mut ready = Vec::new();
+        let mut next_delay = None;
+        while let Some((_, reply)) = delayed.front() {
+            if reply.is_closed() {
+                delayed.pop_front();
+                continue;
+            }
+
+            match limiter.check_key(&peer) {
+                Ok(_) => ready.extend(delayed.pop_front()),
+                Err(not_until) => {
+                    next_delay = Some(not_until.wait_time_from(DefaultClock::default().now()));
+                    break;
+                }
+            }
+        }
+
+        if delayed.is_empty() {
+            self.rate_limited_requests.remove(&peer);
+        }
+
+        if let Some(delay) = next_delay {
+            self.start_rate_limit_timer(peer, delay);
+        }
+
+        for (request, reply) in ready {
+            self.send_or_queue_request(peer, request, reply);
+        }
+    }
+
+    /// Start the timer for the peer's next delayed certificate request.
+    fn start_rate_limit_timer(&mut self, peer: PeerId, delay: Duration) {
+        self.rate_limit_timers.push(
+            async move {
+                tokio::time::sleep(delay).await;
+                peer
+            }
+            .boxed(),
+        );
+    }
+
+    /// Drop the certificate rate limiter state for peers whose quota has fully replenished.
+    ///
+    /// The keyed limiter keeps state for every peer it has seen otherwise.
+    fn prune_certificate_rate_limiter(&self) {
+        if let Some(limiter) = &self.certificate_rate_limiter {
+            limiter.retain_recent();
+            limiter.shrink_to_fit();
+        }
+    }
+
+    /// Send the request to the peer or queue it if the peer has the max requests in flight.
+    fn send_or_queue_request(
+        &mut self,
+        peer: PeerId,
+        request: Req,
+        reply: oneshot::Sender<NetworkResult<Res>>,
+    ) {
+        if self.has_request_capacity(&peer) {
+            let request_id = self.swarm.behaviour_mut().req_res.send_request(&peer, request);
+            self.in_flight_requests.entry(peer).or_default().insert(request_id);
+            self.outbound_requests.insert(request_id, reply);
+        } else {
+            trace!(target: "network", ?peer, "queuing outbound request");
+            self.queued_requests.entry(peer).or_default().push_back((request, reply));
+        }
+    }
+
+    /// Check if the peer has fewer than the max outbound requests in flight.
+    fn has_request_capacity(&self, peer: &PeerId) -> bool {
+        let in_flight = self.in_flight_requests.get(peer).map(HashSet::len).unwrap_or_default();
+        in_flight < self.config.max_outbound_requests_per_peer
+    }
+
+    /// Free the peer's slot for a completed outbound request and send the next queued requests.
+    fn complete_outbound_request(&mut self, peer: PeerId, request_id: OutboundRequestId) {
+        if let Some(in_flight) = self.in_flight_requests.get_mut(&peer) {
+            in_flight.remove(&request_id);
+            if in_flight.is_empty() {
+                self.in_flight_requests.remove(&peer);
+            }
+        }
+
+        while self.has_request_capacity(&peer) {
+            let Some((request, reply)) =
+                self.queued_requests.get_mut(&peer).and_then(VecDeque::pop_front)
+            else {
+                break;
+            };
+
+            // skip requests the caller no longer wants
+            if !reply.is_closed() {
+                self.send_or_queue_request(peer, request, reply);
+            }
+        }
+
+        if self.queued_requests.get(&peer).is_some_and(VecDeque::is_empty) {
+            self.queued_requests.remove(&peer);
+        }
+    }
+
+    /// Start tracking the peers that re-propagate a message published by this node.
+    ///
+    /// Gossipsub does not report when peers forward a message or advertise it with IHAVE. Instead,
+    /// peers acknowledge the message to the publisher once they accept it, which is when gossipsub
+    /// forwards it to their own mesh peers. See [Self::process_gossip_ack_event].
+    fn track_published_message(&mut self, message_id: MessageId) {
+        if self.published_order.len() >= PUBLISHED_REACH_CAPACITY {
+            if let Some(oldest) = self.published_order.pop_front() {
+                self.published_reach.remove(&oldest);
+            }
+        }
+
+        trace!(target: "network", ?message_id, "tracking published message reach");
+        self.published_order.push_back(message_id.clone());
+        self.published_reach.insert(message_id, HashSet::new());
+    }
+
+    /// Acknowledge an accepted gossip message to its publisher.
+    ///
+    /// Gossipsub forwards the message to this node's mesh peers once it is accepted, so the
+    /// acknowledgement tells the publisher this node re-propagated the message.
+    fn ack_gossip(&mut self, message_id: &MessageId, publisher: Option<PeerId>) {
+        let Some(publisher) = publisher else {
+            return;
+        };
+
+        if publisher == *self.swarm.local_peer_id() {
+            return;
+        }
+
+        let ack = GossipAck { message_id: message_id.0.clone() };
+        self.swarm.behaviour_mut().gossip_ack.send_request(&publisher, ack);
+    }
+
+    /// Process gossip acknowledgement events.
+    ///
+    /// Acknowledgements for messages this node published are added to the message's reach. All
+    /// other acknowledgements are ignored.
+    fn process_gossip_ack_event(&mut self, event: ReqResEvent<GossipAck, GossipAck>) {
+        match event {
+            ReqResEvent::Message { peer, message, connection_id: _ } => match message {
+                request_response::Message::Request { request, channel, .. } => {
+                    let message_id = MessageId::new(&request.message_id);
+                    if let Some(reach) = self.published_reach.get_mut(&message_id) {
+                        trace!(target: "network", ?peer, ?message_id, "published message re-propagated");
+                        reach.insert(peer);
+                    }
+
+                    // echo the ack - failures only affect the peer's view of the exchange
+                    let _ = self.swarm.behaviour_mut().gossip_ack.send_response(channel, request);
+                }
+                request_response::Message::Response { .. } => {}
+            },
+            ReqResEvent::OutboundFailure { peer, error, .. } => {
+                debug!(target: "network", ?peer, ?error, "failed to acknowledge gossip");
+            }
+            ReqResEvent::InboundFailure { peer, error, .. } => {
+                trace!(target: "network", ?peer, ?error, "gossip acknowledgement inbound failure");
+            }
+            ReqResEvent::ResponseSent { .. } => {}
+        }
+    }
+
+    /// Track a gossip message that is about to be forwarded to the event stream.
+    ///
+    /// Returns `false` if the message was recently forwarded.
+    fn track_recent_gossip(&mut self, message_id: &MessageId) -> bool {
+        if self.recent_gossip.contains(message_id) {
+            return false;
+        }
+
+        if self.recent_gossip_order.len() >= RECENT_GOSSIP_CAPACITY {
+            if let Some(oldest) = self.recent_gossip_order.pop_front() {
+                self.recent_gossip.remove(&oldest);
+            }
+        }
+
+        self.recent_gossip_order.push_back(message_id.clone());
+        self.recent_gossip.insert(message_id.clone());
+        true
+    }
+
+    /// Rotate the network keypair.
+    ///
+    /// The keypair determines the swarm's [PeerId] and the gossipsub signing key, which libp2p
+    /// fixes at construction. The swarm is rebuilt with the new keypair, re-subscribes to the
+    /// same topics, and redials known peers before the old swarm's connections are closed. The
+    /// new swarm then listens on the old swarm's addresses.
+    ///
+    /// Pending dials and outbound requests on the old swarm return
+    /// [NetworkError::KeypairRotated] and pending inbound requests are cancelled.
+    ///
+    /// The new swarm processes events immediately. Closing the old swarm and listening on its
+    /// addresses is driven by the network loop through [Self::advance_keypair_rotation], which
+    /// replies with the new [PeerId] once the rotation completes.
+    fn rotate_keypair(
+        &mut self,
+        keypair: NetworkKeypair,
+        reply: oneshot::Sender<NetworkResult<PeerId>>,
+    ) {
+        if self.keypair_rotation.is_some() {
+            send_or_log_error!(
+                reply,
+                Err(NetworkError::KeypairRotationInProgress),
+                "RotateKeypair"
+            );
+            return;
+        }
+
+        let listen_addrs: VecDeque<Multiaddr> = self.swarm.listeners().cloned().collect();
+        let subscriptions: Vec<TopicHash> =
+            self.swarm.behaviour().gossipsub.topics().cloned().collect();
+
+        // build the new swarm and restore subscriptions and peers
+        let mut swarm = match Self::build_swarm(
+            keypair,
+            &self.config,
+            &self.quic_config,
+            &self.transports,
+            &self.gossip_mesh_config,
+            &self.ping_config,
+        ) {
+            Ok(swarm) => swarm,
+            Err(e) => {
+                send_or_log_error!(reply, Err(e), "RotateKeypair");
+                return;
+            }
+        };
+        let peer_id = *swarm.local_peer_id();
+        info!(target: "network", topics=?self.topics, old=?self.swarm.local_peer_id(), new=?peer_id, "rotating network keypair");
+
+        for topic in subscriptions {
+            if let Err(e) =
+                swarm.behaviour_mut().gossipsub.subscribe(&IdentTopic::new(topic.into_string()))
+            {
+                send_or_log_error!(reply, Err(e.into()), "RotateKeypair");
+                return;
+            }
+        }
+        for explicit_peer in self.explicit_peers.iter() {
+            swarm.behaviour_mut().gossipsub.add_explicit_peer(explicit_peer);
+        }
+        for (peer, addr) in self.peer_addresses.iter() {
+            for addr in transport_addrs(addr, &self.transports) {
+                swarm.add_peer_address(*peer, addr);
+            }
+            if let Err(e) = swarm.dial(*peer) {
+                warn!(target: "network", ?peer, ?e, "failed to redial peer after keypair rotation");
+            }
+        }
+
+        let mut old_swarm = std::mem::replace(&mut self.swarm, swarm);
+
+        // in-flight requests and dials belong to the old swarm
+        for (_, pending) in self.pending_dials.drain() {
+            for reply in pending.replies {
+                let _ = reply.send(Err(NetworkError::KeypairRotated));
+            }
+        }
+        self.dial_retries.clear();
+        for (_, reply) in self.outbound_requests.drain() {
+            let _ = reply.send(Err(NetworkError::KeypairRotated));
+        }
+        for (_, reply) in self.queued_requests.drain().flat_map(|(_, queue)| queue) {
+            let _ = reply.send(Err(NetworkError::KeypairRotated));
+        }
+        self.in_flight_requests.clear();
+        for (_, cancel) in self.inbound_requests.drain() {
+            let _ = cancel.send(());
+        }
+        self.connected_peers.clear();
+        self.peer_metadata.clear();
+
+        // close the old swarm's connections before releasing the listen addresses
+        let old_peers: Vec<PeerId> = old_swarm.connected_peers().copied().collect();
+        for peer in old_peers {
+            let _ = old_swarm.disconnect_peer_id(peer);
+        }
+        self.keypair_rotation = Some(KeypairRotation {
+            old_swarm: Some(old_swarm),
+            listen_addrs,
+            listen_attempts: 0,
+            timer: Box::pin(tokio::time::sleep(KEYPAIR_ROTATION_CLOSE_TIMEOUT)),
+            peer_id,
+            reply,
+        });
+
+        // the old swarm may not have any connections to close
+        self.advance_keypair_rotation(KeypairRotationStep::OldSwarmEvent);
+    }
+
+    /// Wait for the next step of the keypair rotation in progress.
+    ///
+    /// Events from the old swarm are only polled to close its connections.
+    async fn next_keypair_rotation_step(
+        rotation: &mut Option<KeypairRotation<TNBehavior<TNCodec<Req, Res>>>>,
+    ) -> KeypairRotationStep {
+        let Some(rotation) = rotation else { return std::future::pending().await };
+        match rotation.old_swarm.as_mut() {
+            Some(old_swarm) => tokio::select! {
+                _ = old_swarm.select_next_some() => KeypairRotationStep::OldSwarmEvent,
+                _ = &mut rotation.timer => KeypairRotationStep::TimerExpired,
+            },
+            None => {
+                (&mut rotation.timer).await;
+                KeypairRotationStep::TimerExpired
+            }
+        }
+    }
+
+    /// Advance the keypair rotation in progress.
+    ///
+    /// The old swarm is dropped once its connections close or the close timeout expires. The new
+    /// swarm then listens on the old swarm's addresses. The old swarm's sockets may take a moment
+    /// to release, so failed attempts are retried up to [KEYPAIR_ROTATION_LISTEN_ATTEMPTS] times.
+    fn advance_keypair_rotation(&mut self, step: KeypairRotationStep) {
+        let Some(rotation) = self.keypair_rotation.as_mut() else { return };
+
+        if let Some(old_swarm) = rotation.old_swarm.as_ref() {
+            match step {
+                KeypairRotationStep::OldSwarmEvent => {
+                    if old_swarm.connected_peers().next().is_some() {
+                        return;
+                    }
+                }
+                KeypairRotationStep::TimerExpired => {
+                    warn!(target: "network", topics=?self.topics, "timed out closing connections after keypair rotation");
+                }
+            }
+            rotation.old_swarm = None;
+        }
+
+        // listen on the same addresses
+        while let Some(addr) = rotation.listen_addrs.front() {
+            match self.swarm.listen_on(addr.clone()) {
+                Ok(_) => {
+                    rotation.listen_addrs.pop_front();
+                    rotation.listen_attempts = 0;
+                }
+                Err(e) => {
+                    rotation.listen_attempts += 1;
+                    if rotation.listen_attempts == KEYPAIR_ROTATION_LISTEN_ATTEMPTS {
+                        error!(target: "network", ?addr, ?e, "failed to listen after keypair rotation");
+                        if let Some(rotation) = self.keypair_rotation.take() {
+                            send_or_log_error!(rotation.reply, Err(e.into()), "RotateKeypair");
+                        }
+                    } else {
+                        rotation
+                            .timer
+                            .as_mut()
+                            .reset(tokio::time::Instant::now() + Duration::from_millis(100));
+                    }
+                    return;
+                }
+            }
+        }
+
+        if let Some(rotation) = self.keypair_rotation.take() {
+            send_or_log_error!(rotation.reply, Ok(rotation.peer_id), "RotateKeypair");
         }
     }
 
@@ -498,11 +1297,19 @@ where
                 let msg_acceptance = self.verify_gossip(&message);
 
                 if msg_acceptance.is_accepted() {
-                    // forward gossip to handler
-                    if let Err(e) = self.event_stream.try_send(NetworkEvent::Gossip(message)) {
-                        error!(target: "network", topics=?self.topics, ?propagation_source, ?message_id, ?e, "failed to forward gossip!");
-                        // fatal - unable to process gossip messages
-                        return Err(e.into());
+                    if self.track_recent_gossip(&message_id) {
+                        self.ack_gossip(&message_id, message.source);
+                        // forward gossip to handler without the epoch prefix
+                        let mut message = message;
+                        message.data.drain(..GOSSIP_EPOCH_LEN);
+                        if let Err(e) = self.event_stream.try_send(NetworkEvent::Gossip(message)) {
+                            error!(target: "network", topics=?self.topics, ?propagation_source, ?message_id, ?e, "failed to forward gossip!");
+                            // fatal - unable to process gossip messages
+                            return Err(e.into());
+                        }
+                    } else {
+                        // re-delivered by another mesh peer
+                        trace!(target: "network", topics=?self.topics, ?propagation_source, ?message_id, "gossip already forwarded");
                     }
                 }
                 trace!(target: "network", ?msg_acceptance, "gossip message verification status");
@@ -534,6 +1341,116 @@ where
         Ok(())
     }
 
+    /// Retry or fail a pending dial after an outgoing connection error.
+    ///
+    /// Transport errors are retried with exponential backoff until the max dial attempts is
+    /// reached. All callers waiting on the dial receive the result.
+    fn dial_failed(&mut self, peer_id: PeerId, error: DialError) {
+        let Some(mut pending) = self.pending_dials.remove(&peer_id) else {
+            return;
+        };
+
+        if matches!(error, DialError::Transport(_))
+            && pending.attempts < self.config.max_dial_attempts
+        {
+            let backoff =
+                self.config.dial_retry_backoff.saturating_mul(1 << (pending.attempts - 1).min(16));
+            debug!(target: "network", ?peer_id, attempts=pending.attempts, ?backoff, ?error, "dial failed, retrying");
+            pending.attempts += 1;
+            self.pending_dials.insert(peer_id, pending);
+            self.dial_retries.push(tokio::time::sleep(backoff).map(move |_| peer_id).boxed());
+            return;
+        }
+
+        warn!(target: "network", ?peer_id, attempts=pending.attempts, ?error, "dial failed");
+        let reason = error.to_string();
+        let mut replies = pending.replies.into_iter();
+        if let Some(reply) = replies.next() {
+            send_or_log_error!(reply, Err(error.into()), "OutgoingConnectionError");
+        }
+        for reply in replies {
+            send_or_log_error!(
+                reply,
+                Err(NetworkError::DialFailed(reason.clone())),
+                "OutgoingConnectionError"
+            );
+        }
+    }
+
+    /// Redial a peer once the backoff for a failed dial expires.
+    fn retry_dial(&mut self, peer_id: PeerId) {
+        if !self.pending_dials.contains_key(&peer_id) {
+            // resolved while waiting
+            return;
+        }
+
+        if let Err(e) = self.swarm.dial(peer_id) {
+            self.dial_failed(peer_id, e);
+        }
+    }
+
+    /// Forward a peer connection event to the handler.
+    ///
+    /// Unlike requests and gossip, a missed connection event is not fatal.
+    fn forward_peer_event(&self, event: NetworkEvent<Req, Res>) {
+        if let Err(e) = self.event_stream.try_send(event) {
+            warn!(target: "network::events", topics=?self.topics, ?e, "failed to forward peer connection event");
+        }
+    }
+
+    /// Process identify events.
+    ///
+    /// The peer's listen addresses are added to the swarm's address book.
+    fn process_identify_event(&mut self, event: IdentifyEvent) {
+        match event {
+            IdentifyEvent::Received { peer_id, info, .. } => {
+                trace!(target: "network", topics=?self.topics, ?peer_id, ?info, "identify event - received");
+                for addr in info.listen_addrs.iter() {
+                    self.swarm.add_peer_address(peer_id, addr.clone());
+                }
+
+                // remember an address to redial the peer if the keypair rotates
+                if let Some(addr) = info.listen_addrs.first() {
+                    self.peer_addresses.entry(peer_id).or_insert_with(|| addr.clone());
+                }
+
+                self.peer_metadata.insert(peer_id, info.into());
+            }
+            IdentifyEvent::Error { peer_id, error, .. } => {
+                warn!(target: "network", topics=?self.topics, ?peer_id, ?error, "identify event - error");
+            }
+            IdentifyEvent::Sent { .. } | IdentifyEvent::Pushed { .. } => {}
+        }
+    }
+
+    /// Process ping events.
+    ///
+    /// Peers are disconnected after `max_ping_failures` successive failed pings. The
+    /// connection closing emits [NetworkEvent::PeerDisconnected].
+    fn process_ping_event(&mut self, event: PingEvent) {
+        let PingEvent { peer, connection, result } = event;
+        match result {
+            Ok(rtt) => {
+                trace!(target: "network", topics=?self.topics, ?peer, ?connection, ?rtt, "ping event - success");
+                self.ping_failures.remove(&peer);
+            }
+            // peers without ping support are not considered dead
+            Err(ping::Failure::Unsupported) => {
+                debug!(target: "network", topics=?self.topics, ?peer, ?connection, "ping event - unsupported");
+            }
+            Err(error) => {
+                let failures = self.ping_failures.entry(peer).or_default();
+                *failures += 1;
+                debug!(target: "network", topics=?self.topics, ?peer, ?connection, ?error, failures, "ping event - failure");
+                if *failures >= self.max_ping_failures {
+                    warn!(target: "network", topics=?self.topics, ?peer, failures, "disconnecting unresponsive peer");
+                    self.ping_failures.remove(&peer);
+                    let _ = self.swarm.disconnect_peer_id(peer);
+                }
+            }
+        }
+    }
+
     /// Process req/res events.
     fn process_reqres_event(&mut self, event: ReqResEvent<Req, Res>) -> NetworkResult<()> {
         match event {
@@ -556,6 +1473,8 @@ where
                         self.inbound_requests.insert(request_id, notify);
                     }
                     request_response::Message::Response { request_id, response } => {
+                        self.complete_outbound_request(peer, request_id);
+
                         // try to forward response to original caller
                         let _ = self
                             .outbound_requests
@@ -567,6 +1486,8 @@ where
             }
             ReqResEvent::OutboundFailure { peer, request_id, error, connection_id: _ } => {
                 error!(target: "network", ?peer, ?error, "outbound failure");
+                self.complete_outbound_request(peer, request_id);
+
                 // try to forward error to original caller
                 let _ = self
                     .outbound_requests
@@ -575,16 +1496,18 @@ where
                     .send(Err(error.into()));
             }
             ReqResEvent::InboundFailure { peer, request_id, error, connection_id: _ } => {
-                match error {
+                match &error {
                     ReqResInboundFailure::Io(e) => {
-                        // TODO: update peer score - could be malicious
                         warn!(target: "network", ?e, ?peer, ?request_id, "inbound IO failure");
                     }
                     ReqResInboundFailure::UnsupportedProtocols => {
                         warn!(target: "network", ?peer, ?request_id, ?error, "inbound failure: unsupported protocol");
                     }
-                    _ => { /* ignore timeout, connection closed, and response ommission */ }
+                    _ => {
+                        debug!(target: "network", ?peer, ?request_id, ?error, "inbound failure");
+                    }
                 }
+                self.record_inbound_failure(peer, &error);
 
                 // forward cancelation to handler
                 let _ = self
@@ -599,34 +1522,270 @@ where
         Ok(())
     }
 
+    /// Record an inbound request failure from the peer.
+    ///
+    /// Failures caused by the peer (ie - timeouts) are counted and the peer's application score is
+    /// lowered once [INBOUND_FAILURE_THRESHOLD] failures are reached. Closed connections and
+    /// requests this node never responded to are not the peer's fault.
+    fn record_inbound_failure(&mut self, peer: PeerId, error: &ReqResInboundFailure) {
+        let (kind, peer_fault) = match error {
+            ReqResInboundFailure::Io(_) => ("io", true),
+            ReqResInboundFailure::Timeout => ("timeout", true),
+            ReqResInboundFailure::UnsupportedProtocols => ("unsupported_protocols", true),
+            ReqResInboundFailure::ConnectionClosed => ("connection_closed", false),
+            ReqResInboundFailure::ResponseOmission => ("response_omission", false),
+        };
+        self.metrics.inbound_rpc_failures.with_label_values(&[kind]).inc();
+
+        if !peer_fault {
+            return;
+        }
+
+        let failures = self.inbound_failures.entry(peer).or_default();
+        *failures += 1;
+        if *failures >= INBOUND_FAILURE_THRESHOLD {
+            self.inbound_failures.remove(&peer);
+            self.penalize_peer(peer, INBOUND_FAILURE_PENALTY);
+        }
+    }
+
+    /// Lower the peer's application score by the penalty.
+    ///
+    /// Returns `true` if the score was applied to a known peer.
+    fn penalize_peer(&mut self, peer_id: PeerId, penalty: f64) -> bool {
+        let score = self.application_scores.entry(peer_id).or_default();
+        *score -= penalty;
+        warn!(target: "network", ?peer_id, ?penalty, ?score, "peer reported");
+        self.swarm.behaviour_mut().gossipsub.set_application_score(&peer_id, *score)
+    }
+
+    /// Decay the application scores towards zero.
+    ///
+    /// Scores close enough to zero are reset and no longer tracked.
+    fn decay_application_scores(&mut self) {
+        let gossipsub = &mut self.swarm.behaviour_mut().gossipsub;
+        self.application_scores.retain(|peer_id, score| {
+            *score *= APPLICATION_SCORE_DECAY;
+            if score.abs() < APPLICATION_SCORE_DECAY_TO_ZERO {
+                *score = 0.0;
+            }
+            gossipsub.set_application_score(peer_id, *score);
+            *score != 0.0
+        });
+    }
+
+    /// The latest epoch with authorized publishers.
+    fn latest_epoch(&self) -> Epoch {
+        self.authorized_publishers_by_epoch.keys().next_back().copied().unwrap_or_default()
+    }
+
+    /// The epoch this node publishes gossip for.
+    ///
+    /// This is the latest epoch with this node in its committee, so a retiring authority keeps
+    /// publishing for the previous epoch while the overlap window is open.
+    fn publishing_epoch(&self) -> Epoch {
+        let local_peer_id = self.swarm.local_peer_id();
+        self.authorized_publishers_by_epoch
+            .iter()
+            .rev()
+            .find(|(_, publishers)| publishers.contains(local_peer_id))
+            .map(|(epoch, _)| *epoch)
+            .unwrap_or_else(|| self.latest_epoch())
+    }
+
+    /// Check if the peer is in the committee for `epoch`.
+    ///
+    /// Only the current epoch, or the previous epoch while the overlap window is open, is
+    /// authorized.
+    fn is_authorized_publisher(&self, peer_id: &PeerId, epoch: Epoch) -> bool {
+        let latest = self.latest_epoch();
+        let authorized_epoch =
+            epoch == latest || (self.epoch_overlap_open && epoch == latest.saturating_sub(1));
+        authorized_epoch
+            && self
+                .authorized_publishers_by_epoch
+                .get(&epoch)
+                .is_some_and(|publishers| publishers.contains(peer_id))
+    }
+
+    /// Track the authorized publishers that are not in `remaining` as recently removed.
+    ///
+    /// Removals older than the grace period are no longer tracked, and publishers in `remaining`
+    /// are no longer considered removed.
+    fn track_removed_publishers(&mut self, remaining: &HashSet<PeerId>) {
+        let now = Instant::now();
+        let grace_period = self.config.removed_publisher_grace_period;
+        self.recently_removed_publishers
+            .retain(|_, removed| now.saturating_duration_since(*removed) < grace_period);
+        let removed: Vec<_> = self
+            .authorized_publishers_by_epoch
+            .values()
+            .flatten()
+            .filter(|peer_id| !remaining.contains(peer_id))
+            .copied()
+            .collect();
+        for peer_id in removed {
+            self.recently_removed_publishers.insert(peer_id, now);
+        }
+        self.recently_removed_publishers.retain(|peer_id, _| !remaining.contains(peer_id));
+    }
+
+    /// Check if the peer was removed from the authorized publishers within the grace period.
+    fn is_recently_removed_publisher(&self, peer_id: &PeerId) -> bool {
+        self.recently_removed_publishers
+            .get(peer_id)
+            .is_some_and(|removed| removed.elapsed() < self.config.removed_publisher_grace_period)
+    }
+
     /// Specific logic to accept gossip messages.
     ///
-    /// Messages are only published by current committee nodes and must be within max size.
+    /// Messages are only published by committee nodes and must be within max size. The publisher
+    /// must be in the committee for the epoch the message was published for. During an epoch
+    /// change, messages for the retiring committee's epoch are accepted until the overlap window
+    /// closes. Messages from authorities removed within the grace period are ignored instead of
+    /// rejected so peers forwarding in-flight messages are not penalized.
     fn verify_gossip(&self, gossip: &GossipMessage) -> GossipAcceptance {
         // verify message size
         if gossip.data.len() > self.config.max_gossip_message_size {
             return GossipAcceptance::Reject;
         }
 
+        // messages must carry the epoch they were published for
+        let Some(epoch) = decode_gossip_epoch(&gossip.data) else {
+            return GossipAcceptance::Reject;
+        };
+
         // ensure publisher is authorized
         //
         // NOTE: expand on this based on gossip::topic - not all topics need to be permissioned
-        if gossip.source.is_some_and(|id| self.authorized_publishers.contains(&id)) {
-            GossipAcceptance::Accept
-        } else {
-            GossipAcceptance::Reject
+        match gossip.source {
+            Some(id) if self.is_authorized_publisher(&id, epoch) => GossipAcceptance::Accept,
+            Some(id) if self.is_recently_removed_publisher(&id) => {
+                trace!(target: "network", topics=?self.topics, publisher=?id, "ignoring gossip from recently removed publisher");
+                GossipAcceptance::Ignore
+            }
+            _ => GossipAcceptance::Reject,
         }
     }
 }
 
+/// Build the per-peer rate limiter for outbound requests that carry certificates.
+fn certificate_rate_limiter(config: &LibP2pConfig) -> Option<DefaultKeyedRateLimiter<PeerId>> {
+    config.send_certificate_rate_limit.map(|limit| RateLimiter::keyed(Quota::per_second(limit)))
+}
+
+/// Build the gossipsub config with the mesh parameters for the committee.
+fn gossipsub_config(mesh_config: &GossipMeshConfig) -> NetworkResult<gossipsub::Config> {
+    let config = gossipsub::ConfigBuilder::default()
+        // TN specific: mesh size and heartbeat based on committee size
+        .mesh_n(mesh_config.mesh_n)
+        .mesh_n_low(mesh_config.mesh_n_low)
+        .mesh_n_high(mesh_config.mesh_n_high)
+        .mesh_outbound_min(mesh_config.mesh_outbound_min)
+        .heartbeat_interval(mesh_config.heartbeat_interval)
+        // explicitly set default
+        .validation_mode(gossipsub::ValidationMode::Strict)
+        // support peer exchange
+        .do_px()
+        // TN specific: filter against authorized_publishers for certain topics
+        .validate_messages()
+        // TN specific: message ids are the digest of uncompressed data
+        .message_id_fn(gossip_message_id)
+        .build()?;
+
+    Ok(config)
+}
+
+/// Build the transport with the enabled [NetworkTransport]s.
+///
+/// QUIC is configured with the [QuicConfig]. TCP connections are secured with noise and
+/// multiplexed with yamux.
+fn build_transport(
+    keypair: &NetworkKeypair,
+    quic_config: &QuicConfig,
+    transports: &[NetworkTransport],
+) -> NetworkResult<Boxed<(PeerId, StreamMuxerBox)>> {
+    let quic = if transports.contains(&NetworkTransport::Quic) {
+        let mut config = quic::Config::new(keypair);
+        config.handshake_timeout = quic_config.handshake_timeout;
+        config.max_idle_timeout = quic_config.max_idle_timeout;
+        config.keep_alive_interval = quic_config.keep_alive_interval;
+        config.max_concurrent_stream_limit = quic_config.max_concurrent_stream_limit;
+        config.max_stream_data = quic_config.max_stream_data;
+        config.max_connection_data = quic_config.max_connection_data;
+        OptionalTransport::some(quic::tokio::Transport::new(config))
+    } else {
+        OptionalTransport::none()
+    };
+
+    let tcp = if transports.contains(&NetworkTransport::Tcp) {
+        let noise =
+            noise::Config::new(keypair).map_err(|e| NetworkError::Transport(e.to_string()))?;
+        OptionalTransport::some(
+            tcp::tokio::Transport::new(tcp::Config::default())
+                .upgrade(upgrade::Version::V1)
+                .authenticate(noise)
+                .multiplex(yamux::Config::default()),
+        )
+    } else {
+        OptionalTransport::none()
+    };
+
+    let transport = quic
+        .map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)))
+        .or_transport(tcp.map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer))))
+        .map(|either, _| either.into_inner())
+        .boxed();
+
+    Ok(transport)
+}
+
+/// Return the address for each enabled transport.
+///
+/// QUIC (`/udp/<port>/quic-v1`) and TCP (`/tcp/<port>`) addresses are converted to the other
+/// transport with the same ip and port. Addresses without a port are returned unchanged.
+fn transport_addrs(addr: &Multiaddr, transports: &[NetworkTransport]) -> Vec<Multiaddr> {
+    let mut host = Vec::new();
+    let mut port = None;
+    let mut suffix = Vec::new();
+    for protocol in addr.iter() {
+        match protocol {
+            Protocol::Udp(p) | Protocol::Tcp(p) if port.is_none() => port = Some(p),
+            Protocol::QuicV1 => (),
+            protocol if port.is_none() => host.push(protocol),
+            protocol => suffix.push(protocol),
+        }
+    }
+
+    let Some(port) = port else {
+        return vec![addr.clone()];
+    };
+
+    transports
+        .iter()
+        .map(|transport| {
+            let mut transport_addr: Multiaddr = host.iter().cloned().collect();
+            match transport {
+                NetworkTransport::Quic => {
+                    transport_addr.push(Protocol::Udp(port));
+                    transport_addr.push(Protocol::QuicV1);
+                }
+                NetworkTransport::Tcp => transport_addr.push(Protocol::Tcp(port)),
+            }
+            suffix.iter().cloned().fold(transport_addr, |addr, protocol| addr.with(protocol))
+        })
+        .collect()
+}
+
 /// Enum if the received gossip is initially accepted for further processing.
 ///
 /// This is necessary because libp2p does not impl `PartialEq` on [MessageAcceptance].
-/// This impl does not map to `MessageAcceptance::Ignore`.
 #[derive(Debug, PartialEq)]
 enum GossipAcceptance {
     /// The message is considered valid, and it should be delivered and forwarded to the network.
     Accept,
+    /// The message is not delivered or forwarded, but it does not trigger the P₄ penalty.
+    Ignore,
     /// The message is considered invalid, and it should be rejected and trigger the P₄ penalty.
     Reject,
 }
@@ -642,6 +1801,7 @@ impl From<GossipAcceptance> for MessageAcceptance {
     fn from(value: GossipAcceptance) -> Self {
         match value {
             GossipAcceptance::Accept => MessageAcceptance::Accept,
+            GossipAcceptance::Ignore => MessageAcceptance::Ignore,
             GossipAcceptance::Reject => MessageAcceptance::Reject,
         }
     }
@@ -16,12 +16,30 @@ pub enum NetworkError {
     /// Swarm error dialing a peer.
     #[error(transparent)]
     Dial(#[from] DialError),
-    /// Dial attempt currently ongoing for peer.
-    #[error("Peer already dialed")]
-    RedialAttempt,
+    /// A dial this caller joined failed.
+    ///
+    /// Only the caller that started the dial receives the [DialError].
+    #[error("Dial failed: {0}")]
+    DialFailed(String),
     /// Gossipsub error publishing message.
+    ///
+    /// Publish errors callers are expected to handle differently are converted to their own
+    /// variants.
     #[error(transparent)]
-    Publish(#[from] PublishError),
+    Publish(PublishError),
+    /// Gossipsub had no peers to publish the message to.
+    ///
+    /// The message may be published again once peers connect.
+    #[error("Insufficient peers to publish message")]
+    InsufficientPeers,
+    /// Gossipsub already published this message.
+    ///
+    /// Publishing the same message again always fails.
+    #[error("Duplicate message already published")]
+    Duplicate,
+    /// The message exceeds the max gossip size.
+    #[error("Message too large to publish")]
+    MessageTooLarge,
     /// Gossipsub error subscribing to topic.
     #[error(transparent)]
     Subscription(#[from] SubscriptionError),
@@ -76,6 +94,33 @@ pub enum NetworkError {
     /// A network operation timed out.
     #[error("Timed Out")]
     Timeout,
+    /// The network keypair rotated before the operation completed.
+    #[error("Network keypair rotated")]
+    KeypairRotated,
+    /// The network keypair is already rotating.
+    #[error("Network keypair rotation already in progress")]
+    KeypairRotationInProgress,
+    /// Failed to build the network transport.
+    #[error("Failed to build transport: {0}")]
+    Transport(String),
+}
+
+impl NetworkError {
+    /// Bool indicating if the operation may succeed if it is tried again later.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::InsufficientPeers | Self::NoPeers | Self::Timeout)
+    }
+}
+
+impl From<PublishError> for NetworkError {
+    fn from(e: PublishError) -> Self {
+        match e {
+            PublishError::InsufficientPeers => Self::InsufficientPeers,
+            PublishError::Duplicate => Self::Duplicate,
+            PublishError::MessageTooLarge => Self::MessageTooLarge,
+            e => Self::Publish(e),
+        }
+    }
 }
 
 impl From<oneshot::error::RecvError> for NetworkError {
@@ -101,3 +146,29 @@ impl<T> From<mpsc::error::TrySendError<T>> for NetworkError {
         Self::MpscTrySend(e.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::NetworkError;
+    use assert_matches::assert_matches;
+    use libp2p::gossipsub::PublishError;
+
+    #[test]
+    fn test_publish_errors_map_to_network_errors() {
+        let err = NetworkError::from(PublishError::InsufficientPeers);
+        assert_matches!(err, NetworkError::InsufficientPeers);
+        assert!(err.is_retryable());
+
+        let err = NetworkError::from(PublishError::Duplicate);
+        assert_matches!(err, NetworkError::Duplicate);
+        assert!(!err.is_retryable());
+
+        let err = NetworkError::from(PublishError::MessageTooLarge);
+        assert_matches!(err, NetworkError::MessageTooLarge);
+        assert!(!err.is_retryable());
+
+        let err = NetworkError::from(PublishError::TransformFailed(std::io::Error::other("test")));
+        assert_matches!(err, NetworkError::Publish(PublishError::TransformFailed(_)));
+        assert!(!err.is_retryable());
+    }
+}
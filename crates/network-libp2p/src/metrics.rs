@@ -0,0 +1,62 @@
+//! Metrics for the consensus network.
+
+use prometheus::{
+    default_registry, register_int_counter_vec_with_registry, IntCounterVec, Opts, Registry,
+};
+
+/// The prefix for all consensus network metric names.
+const METRICS_PREFIX: &str = "consensus_network_";
+
+/// The label identifying the network (ie - "primary" or "worker") the metrics belong to.
+const NETWORK_LABEL: &str = "network";
+
+/// Metrics for the consensus network's request-response behavior.
+#[derive(Clone, Debug)]
+pub struct NetworkMetrics {
+    /// The number of inbound requests that failed, labeled by the kind of failure.
+    pub inbound_rpc_failures: IntCounterVec,
+}
+
+impl NetworkMetrics {
+    /// Try to create and register metrics with the provided registry.
+    ///
+    /// Metric names are prefixed with `consensus_network_` and labeled with the network, so the
+    /// primary and worker networks can register with the same registry.
+    pub fn try_new(registry: &Registry, network: &str) -> Result<Self, prometheus::Error> {
+        let opts = |name: &str, help: &str| {
+            Opts::new(format!("{METRICS_PREFIX}{name}"), help).const_label(NETWORK_LABEL, network)
+        };
+
+        Ok(Self {
+            inbound_rpc_failures: register_int_counter_vec_with_registry!(
+                opts(
+                    "inbound_rpc_failures_total",
+                    "The number of inbound requests that failed by the kind of failure",
+                ),
+                &["kind"],
+                registry
+            )?,
+        })
+    }
+
+    /// Create metrics for the network and register them with the default registry.
+    pub fn new(network: &str) -> Self {
+        // try_new() should not fail except under certain conditions with testing (see comment
+        // below). This pushes the panic or retry decision lower and supporting try_new
+        // allways a user to deal with errors if desired (have a non-panic option).
+        // We always want do use default_registry() when not in test.
+        match Self::try_new(default_registry(), network) {
+            Ok(metrics) => metrics,
+            Err(e) => {
+                tracing::warn!(target: "tn::metrics", ?e, "NetworkMetrics::try_new metrics error");
+                // If we are in a test then don't panic on prometheus errors (usually an already
+                // registered error) but try again with a new Registry. This is not
+                // great for prod code, however should not happen, but will happen in tests due to
+                // how Rust runs them so lets just gloss over it. cfg(test) does not
+                // always work as expected.
+                Self::try_new(&Registry::new(), network)
+                    .expect("Prometheus error, are you using it wrong?")
+            }
+        }
+    }
+}
@@ -7,7 +7,14 @@ use tn_types::{BlockHash, Certificate, CertificateDigest, Header, SealedBatch, V
 // impl TNMessage trait for types
 impl TNMessage for TestWorkerRequest {}
 impl TNMessage for TestWorkerResponse {}
-impl TNMessage for TestPrimaryRequest {}
+impl TNMessage for TestPrimaryRequest {
+    fn carries_certificates(&self) -> bool {
+        match self {
+            Self::NewCertificate { .. } => true,
+            Self::Vote { parents, .. } => !parents.is_empty(),
+        }
+    }
+}
 impl TNMessage for TestPrimaryResponse {}
 
 /// Requests between workers.
@@ -4,10 +4,18 @@ mod common;
 use super::*;
 use assert_matches::assert_matches;
 use common::{TestPrimaryRequest, TestPrimaryResponse, TestWorkerRequest, TestWorkerResponse};
-use tn_config::ConsensusConfig;
+use std::{
+    net::SocketAddr,
+    num::NonZeroU32,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use tn_config::{ConsensusConfig, NetworkTransport};
 use tn_storage::mem_db::MemDatabase;
 use tn_test_utils::{fixture_batch_with_transactions, CommitteeFixture};
-use tn_types::{Certificate, Header};
+use tn_types::{Certificate, Header, NetworkKeypair};
 use tokio::{sync::mpsc, time::timeout};
 
 /// A peer on TN
@@ -41,6 +49,16 @@ where
 /// Helper function to create an instance of [RequestHandler] for the first authority in the
 /// committee.
 fn create_test_types<Req, Res>() -> TestTypes<Req, Res>
+where
+    Req: TNMessage,
+    Res: TNMessage,
+{
+    // room for peer connection events ahead of a request or gossip
+    create_test_types_with_event_capacity(10)
+}
+
+/// Create test types with the capacity for each peer's network events channel.
+fn create_test_types_with_event_capacity<Req, Res>(event_capacity: usize) -> TestTypes<Req, Res>
 where
     Req: TNMessage,
     Res: TNMessage,
@@ -49,51 +67,115 @@ where
     let mut authorities = all_nodes.authorities();
     let authority_1 = authorities.next().expect("first authority");
     let authority_2 = authorities.next().expect("second authority");
-    let config_1 = authority_1.consensus_config();
-    let config_2 = authority_2.consensus_config();
-    let (tx1, network_events_1) = mpsc::channel(1);
-    let (tx2, network_events_2) = mpsc::channel(1);
-    let topics = vec![IdentTopic::new("test-topic")];
+    let peer1 = create_network_peer(authority_1.consensus_config(), event_capacity);
+    let peer2 = create_network_peer(authority_2.consensus_config(), event_capacity);
 
-    // peer1
-    let network_key_1 = config_1.key_config().primary_network_keypair().clone();
-    let authorized_publishers = config_1.committee_peer_ids();
-    let peer1_network = ConsensusNetwork::<Req, Res>::new(
-        &config_1,
-        tx1,
-        topics.clone(),
-        network_key_1,
-        authorized_publishers,
+    TestTypes { peer1, peer2 }
+}
+
+/// Create the consensus config for the authority with the network transports.
+fn config_with_transports(
+    config: ConsensusConfig<MemDatabase>,
+    transports: Vec<NetworkTransport>,
+) -> ConsensusConfig<MemDatabase> {
+    let mut node_config = config.config().clone();
+    node_config.parameters.network_transports = transports;
+    ConsensusConfig::new_with_committee_for_test(
+        node_config,
+        config.node_storage().clone(),
+        config.key_config().clone(),
+        config.committee().clone(),
+        config.worker_cache_clone(),
     )
-    .expect("peer1 network created");
-    let network_handle_1 = peer1_network.network_handle();
-    let peer1 = NetworkPeer {
-        config: config_1,
-        network_events: network_events_1,
-        network_handle: network_handle_1,
-        network: peer1_network,
-    };
+    .expect("consensus config with transports")
+}
 
-    // peer2
-    let network_key_2 = config_2.key_config().primary_network_keypair().clone();
-    let authorized_publishers = config_2.committee_peer_ids();
-    let peer2_network = ConsensusNetwork::<Req, Res>::new(
-        &config_2,
-        tx2,
-        topics.clone(),
-        network_key_2,
+/// Create the consensus config for the authority with the peer ping parameters.
+fn config_with_ping(
+    config: ConsensusConfig<MemDatabase>,
+    interval: Duration,
+    max_failures: usize,
+) -> ConsensusConfig<MemDatabase> {
+    let mut node_config = config.config().clone();
+    node_config.parameters.peer_ping_interval = interval;
+    node_config.parameters.peer_ping_timeout = interval;
+    node_config.parameters.max_peer_ping_failures = max_failures;
+    ConsensusConfig::new_with_committee_for_test(
+        node_config,
+        config.node_storage().clone(),
+        config.key_config().clone(),
+        config.committee().clone(),
+        config.worker_cache_clone(),
+    )
+    .expect("consensus config with ping")
+}
+
+/// Forward udp datagrams between a single client and `target` through a local socket.
+///
+/// Datagrams are dropped without closing the socket once `severed` is set, so the connection is
+/// silently dropped. Returns the proxy's address.
+async fn spawn_udp_proxy(target: SocketAddr, severed: Arc<AtomicBool>) -> eyre::Result<SocketAddr> {
+    let socket = tokio::net::UdpSocket::bind("127.0.0.1:0").await?;
+    let proxy_addr = socket.local_addr()?;
+    tokio::spawn(async move {
+        let mut client = None;
+        let mut buf = vec![0; 65_536];
+        while let Ok((len, from)) = socket.recv_from(&mut buf).await {
+            if severed.load(Ordering::Relaxed) {
+                continue;
+            }
+            let to = if from == target {
+                client
+            } else {
+                client = Some(from);
+                Some(target)
+            };
+            if let Some(to) = to {
+                let _ = socket.send_to(&buf[..len], to).await;
+            }
+        }
+    });
+    Ok(proxy_addr)
+}
+
+/// Receive the next request or gossip, skipping peer connection events.
+async fn next_message<Req, Res>(
+    network_events: &mut mpsc::Receiver<NetworkEvent<Req, Res>>,
+) -> Option<NetworkEvent<Req, Res>> {
+    loop {
+        match network_events.recv().await? {
+            NetworkEvent::PeerConnected(_) | NetworkEvent::PeerDisconnected(_) => continue,
+            event => return Some(event),
+        }
+    }
+}
+
+/// Create a network peer for the authority's consensus config.
+fn create_network_peer<Req, Res>(
+    config: ConsensusConfig<MemDatabase>,
+    event_capacity: usize,
+) -> NetworkPeer<MemDatabase, Req, Res>
+where
+    Req: TNMessage,
+    Res: TNMessage,
+{
+    let (tx, network_events) = mpsc::channel(event_capacity);
+    let topics = vec![IdentTopic::new("test-topic")];
+    let network_key = config.key_config().primary_network_keypair().clone();
+    let authorized_publishers = config.committee_peer_ids();
+    let metrics = NetworkMetrics::new("test");
+    let network = ConsensusNetwork::<Req, Res>::new(
+        &config,
+        tx,
+        topics,
+        network_key,
         authorized_publishers,
+        metrics,
     )
-    .expect("peer2 network created");
-    let network_handle_2 = peer2_network.network_handle();
-    let peer2 = NetworkPeer {
-        config: config_2,
-        network_events: network_events_2,
-        network_handle: network_handle_2,
-        network: peer2_network,
-    };
+    .expect("peer network created");
+    let network_handle = network.network_handle();
 
-    TestTypes { peer1, peer2 }
+    NetworkPeer { config, network_events, network_handle, network }
 }
 
 #[tokio::test]
@@ -133,8 +215,9 @@ async fn test_valid_req_res() -> eyre::Result<()> {
     // send request and wait for response
     let max_time = Duration::from_secs(5);
     let response_from_peer = peer1.send_request(batch_req.clone(), peer2_id).await?;
-    let event =
-        timeout(max_time, network_events_2.recv()).await?.expect("first network event received");
+    let event = timeout(max_time, next_message(&mut network_events_2))
+        .await?
+        .expect("first network event received");
 
     // expect network event
     if let NetworkEvent::Request { request, channel, .. } = event {
@@ -153,6 +236,309 @@ async fn test_valid_req_res() -> eyre::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_outbound_requests_capped_per_peer() -> eyre::Result<()> {
+    let max_in_flight = 2;
+    let num_requests = 5;
+
+    // start peer1 network with a low outbound request cap
+    let TestTypes { peer1, peer2 } = create_test_types_with_event_capacity::<
+        TestWorkerRequest,
+        TestWorkerResponse,
+    >(num_requests);
+    let NetworkPeer { config: config_1, network_handle: peer1, mut network, .. } = peer1;
+    network.config.max_outbound_requests_per_peer = max_in_flight;
+    tokio::spawn(async move {
+        network.run().await.expect("network run failed!");
+    });
+
+    // start honest peer2 network
+    let NetworkPeer {
+        config: config_2,
+        network_handle: peer2,
+        network_events: mut network_events_2,
+        network,
+    } = peer2;
+    tokio::spawn(async move {
+        network.run().await.expect("network run failed!");
+    });
+
+    // start swarm listening on default any address
+    peer1.start_listening(config_1.authority().primary_network_address().clone()).await?;
+    peer2.start_listening(config_2.authority().primary_network_address().clone()).await?;
+    let peer2_id = peer2.local_peer_id().await?;
+    let peer2_addr = peer2.listeners().await?.first().expect("peer2 listen addr").clone();
+
+    // dial peer2
+    peer1.dial(peer2_id, peer2_addr).await?;
+
+    // send a burst of requests to peer2
+    let missing_block = fixture_batch_with_transactions(3).seal_slow();
+    let batch_req = TestWorkerRequest::MissingBatches(vec![missing_block.digest()]);
+    let batch_res = TestWorkerResponse::MissingBatches { batches: vec![missing_block] };
+    let mut responses = Vec::new();
+    for _ in 0..num_requests {
+        responses.push(peer1.send_request(batch_req.clone(), peer2_id).await?);
+    }
+
+    // only the max number of requests reach peer2
+    let max_time = Duration::from_secs(5);
+    let mut channels = VecDeque::new();
+    for _ in 0..max_in_flight {
+        let event = timeout(max_time, next_message(&mut network_events_2))
+            .await?
+            .expect("request received");
+        if let NetworkEvent::Request { channel, .. } = event {
+            channels.push_back(channel);
+        } else {
+            panic!("unexpected network event received");
+        }
+    }
+    assert!(timeout(Duration::from_millis(500), next_message(&mut network_events_2))
+        .await
+        .is_err());
+    assert_eq!(peer1.get_pending_request_count().await?, max_in_flight);
+
+    // each response frees a slot for the next queued request
+    let mut received = max_in_flight;
+    while let Some(channel) = channels.pop_front() {
+        peer2.send_response(batch_res.clone(), channel).await?;
+        if received < num_requests {
+            let event = timeout(max_time, next_message(&mut network_events_2))
+                .await?
+                .expect("request received");
+            if let NetworkEvent::Request { channel, .. } = event {
+                channels.push_back(channel);
+                received += 1;
+            } else {
+                panic!("unexpected network event received");
+            }
+            assert!(peer1.get_pending_request_count().await? <= max_in_flight);
+        }
+    }
+
+    // all requests receive responses
+    for response in responses {
+        let response = timeout(max_time, response).await?.expect("outbound id recv")?;
+        assert_eq!(response, batch_res);
+    }
+    assert_eq!(peer1.get_pending_request_count().await?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_certificate_requests_rate_limited_per_peer() -> eyre::Result<()> {
+    let num_requests = 3;
+
+    // start peer1 network with a limit of one certificate request per second
+    let TestTypes { peer1, peer2 } = create_test_types::<TestPrimaryRequest, TestPrimaryResponse>();
+    let NetworkPeer { config: config_1, network_handle: peer1, mut network, .. } = peer1;
+    network.config.send_certificate_rate_limit = Some(NonZeroU32::MIN);
+    network.certificate_rate_limiter = certificate_rate_limiter(&network.config);
+    tokio::spawn(async move {
+        network.run().await.expect("network run failed!");
+    });
+
+    // start honest peer2 network
+    let NetworkPeer {
+        config: config_2,
+        network_handle: peer2,
+        network_events: mut network_events_2,
+        network,
+    } = peer2;
+    tokio::spawn(async move {
+        network.run().await.expect("network run failed!");
+    });
+
+    // start swarm listening on default any address
+    peer1.start_listening(config_1.authority().primary_network_address().clone()).await?;
+    peer2.start_listening(config_2.authority().primary_network_address().clone()).await?;
+    let peer2_id = peer2.local_peer_id().await?;
+    let peer2_addr = peer2.listeners().await?.first().expect("peer2 listen addr").clone();
+
+    // dial peer2
+    peer1.dial(peer2_id, peer2_addr).await?;
+
+    // send a burst of certificate requests to peer2
+    // each request has a different number of parents to check the order they are received
+    let vote_req = |num_parents| TestPrimaryRequest::Vote {
+        header: Header::default(),
+        parents: vec![Certificate::default(); num_parents],
+    };
+    let start = Instant::now();
+    let mut responses = Vec::new();
+    for num_parents in 1..=num_requests {
+        responses.push(peer1.send_request(vote_req(num_parents), peer2_id).await?);
+    }
+
+    // only the first request is sent before the quota replenishes
+    let max_time = Duration::from_secs(5);
+    let event = timeout(max_time, next_message(&mut network_events_2))
+        .await?
+        .expect("first request received");
+    assert_matches!(event, NetworkEvent::Request { request, .. } if request == vote_req(1));
+    assert!(timeout(Duration::from_millis(500), next_message(&mut network_events_2))
+        .await
+        .is_err());
+
+    // excess requests are delayed in order until the quota allows them
+    for expected_delay in 1..num_requests {
+        let event = timeout(max_time, next_message(&mut network_events_2))
+            .await?
+            .expect("delayed request received");
+        assert_matches!(
+            event,
+            NetworkEvent::Request { request, .. } if request == vote_req(expected_delay + 1)
+        );
+        assert!(start.elapsed() >= Duration::from_millis(900 * expected_delay as u64));
+    }
+
+    // requests without certificates are not limited
+    let _response = peer1.send_request(vote_req(0), peer2_id).await?;
+    let event = timeout(Duration::from_millis(500), next_message(&mut network_events_2))
+        .await?
+        .expect("request without certificates received");
+    assert_matches!(event, NetworkEvent::Request { .. });
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_report_peer_for_bad_response() -> eyre::Result<()> {
+    // start honest peer1 network
+    let TestTypes { peer1, peer2 } = create_test_types::<TestPrimaryRequest, TestPrimaryResponse>();
+    let NetworkPeer { config: config_1, network_handle: peer1, network, .. } = peer1;
+    tokio::spawn(async move {
+        network.run().await.expect("network run failed!");
+    });
+
+    // start peer2 network that returns bad responses
+    let NetworkPeer {
+        config: config_2,
+        network_handle: peer2,
+        network_events: mut network_events_2,
+        network,
+    } = peer2;
+    tokio::spawn(async move {
+        network.run().await.expect("network run failed!");
+    });
+
+    // start swarm listening on default any address
+    peer1.start_listening(config_1.authority().primary_network_address().clone()).await?;
+    peer2.start_listening(config_2.authority().primary_network_address().clone()).await?;
+    let peer2_id = peer2.local_peer_id().await?;
+    let peer2_addr = peer2.listeners().await?.first().expect("peer2 listen addr").clone();
+
+    // dial peer2
+    peer1.dial(peer2_id, peer2_addr).await?;
+    let initial_score = peer1.peer_score(peer2_id).await?.expect("peer2 is scored");
+
+    // request a vote from any peer
+    let max_time = Duration::from_secs(5);
+    let vote_req = TestPrimaryRequest::Vote {
+        header: Header::default(),
+        parents: vec![Certificate::default()],
+    };
+    let (responding_peer, response) = peer1.send_request_any(vote_req).await?;
+    assert_eq!(responding_peer, peer2_id);
+
+    // peer2 returns the wrong response
+    let event =
+        timeout(max_time, next_message(&mut network_events_2)).await?.expect("request received");
+    if let NetworkEvent::Request { channel, .. } = event {
+        peer2.send_response(TestPrimaryResponse::MissingParents(vec![]), channel).await?;
+    } else {
+        panic!("unexpected network event received");
+    }
+
+    // the response is attributed to peer2 and reported
+    let response = timeout(max_time, response).await?.expect("outbound id recv")?;
+    assert_matches!(response, TestPrimaryResponse::MissingParents(_));
+    assert!(peer1.report_peer(responding_peer, 10.0).await?);
+
+    // peer2's score is lowered
+    let score = peer1.peer_score(peer2_id).await?.expect("peer2 is scored");
+    assert!(score < initial_score);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_repeated_inbound_failures_lower_peer_score() -> eyre::Result<()> {
+    let TestTypes { peer1, .. } = create_test_types::<TestWorkerRequest, TestWorkerResponse>();
+    let NetworkPeer { mut network, .. } = peer1;
+    let flaky_peer = PeerId::random();
+
+    // failures below the threshold are only counted
+    for _ in 1..INBOUND_FAILURE_THRESHOLD {
+        network.record_inbound_failure(flaky_peer, &ReqResInboundFailure::Timeout);
+    }
+    assert!(!network.application_scores.contains_key(&flaky_peer));
+
+    // the peer is penalized once the threshold is reached
+    network.record_inbound_failure(flaky_peer, &ReqResInboundFailure::Timeout);
+    assert_eq!(network.application_scores.get(&flaky_peer), Some(&-INBOUND_FAILURE_PENALTY));
+    assert_eq!(
+        network.metrics.inbound_rpc_failures.with_label_values(&["timeout"]).get(),
+        INBOUND_FAILURE_THRESHOLD as u64
+    );
+
+    // repeated failures keep lowering the score
+    for _ in 0..INBOUND_FAILURE_THRESHOLD {
+        network.record_inbound_failure(flaky_peer, &ReqResInboundFailure::Timeout);
+    }
+    assert_eq!(
+        network.application_scores.get(&flaky_peer),
+        Some(&(-2.0 * INBOUND_FAILURE_PENALTY))
+    );
+
+    // failures that aren't the peer's fault are counted without a penalty
+    for _ in 0..INBOUND_FAILURE_THRESHOLD {
+        network.record_inbound_failure(flaky_peer, &ReqResInboundFailure::ConnectionClosed);
+    }
+    assert_eq!(
+        network.application_scores.get(&flaky_peer),
+        Some(&(-2.0 * INBOUND_FAILURE_PENALTY))
+    );
+    assert_eq!(
+        network.metrics.inbound_rpc_failures.with_label_values(&["connection_closed"]).get(),
+        INBOUND_FAILURE_THRESHOLD as u64
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_application_scores_accumulate_and_decay() -> eyre::Result<()> {
+    let TestTypes { peer1, .. } = create_test_types::<TestWorkerRequest, TestWorkerResponse>();
+    let NetworkPeer { mut network, .. } = peer1;
+    let reported_peer = PeerId::random();
+
+    // reports accumulate
+    network.penalize_peer(reported_peer, 10.0);
+    network.penalize_peer(reported_peer, 10.0);
+    assert_eq!(network.application_scores.get(&reported_peer), Some(&-20.0));
+
+    // the score decays towards zero
+    network.decay_application_scores();
+    assert_eq!(
+        network.application_scores.get(&reported_peer),
+        Some(&(-20.0 * APPLICATION_SCORE_DECAY))
+    );
+
+    // the score is reset once close to zero
+    while network.application_scores.contains_key(&reported_peer) {
+        network.decay_application_scores();
+    }
+
+    // later reports start from zero
+    network.penalize_peer(reported_peer, 10.0);
+    assert_eq!(network.application_scores.get(&reported_peer), Some(&-10.0));
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_valid_req_res_connection_closed_cleanup() -> eyre::Result<()> {
     // start honest peer1 network
@@ -214,6 +600,156 @@ async fn test_valid_req_res_connection_closed_cleanup() -> eyre::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_peer_connection_events_forwarded() -> eyre::Result<()> {
+    // start honest peer1 network
+    let TestTypes { peer1, peer2 } = create_test_types::<TestWorkerRequest, TestWorkerResponse>();
+    let NetworkPeer {
+        config: config_1,
+        network_handle: peer1,
+        network_events: mut network_events_1,
+        network,
+    } = peer1;
+    tokio::spawn(async move {
+        network.run().await.expect("network run failed!");
+    });
+
+    // start honest peer2 network
+    let NetworkPeer { config: config_2, network_handle: peer2, network, .. } = peer2;
+    let peer2_network_task = tokio::spawn(async move {
+        network.run().await.expect("network run failed!");
+    });
+
+    // start swarm listening on default any address
+    peer1.start_listening(config_1.authority().primary_network_address().clone()).await?;
+    peer2.start_listening(config_2.authority().primary_network_address().clone()).await?;
+    let peer2_id = peer2.local_peer_id().await?;
+    let peer2_addr = peer2.listeners().await?.first().expect("peer2 listen addr").clone();
+
+    // dial peer2
+    peer1.dial(peer2_id, peer2_addr).await?;
+
+    let max_time = Duration::from_secs(5);
+    let event = timeout(max_time, network_events_1.recv()).await?.expect("connected event");
+    assert_matches!(event, NetworkEvent::PeerConnected(peer) if peer == peer2_id);
+
+    // simulate crashed peer 2
+    peer2_network_task.abort();
+    assert!(peer2_network_task.await.unwrap_err().is_cancelled());
+
+    let event = timeout(max_time, network_events_1.recv()).await?.expect("disconnected event");
+    assert_matches!(event, NetworkEvent::PeerDisconnected(peer) if peer == peer2_id);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_concurrent_dials_to_same_peer() -> eyre::Result<()> {
+    let TestTypes { peer1, peer2 } = create_test_types::<TestWorkerRequest, TestWorkerResponse>();
+    let NetworkPeer { config: config_1, network_handle: peer1, network, .. } = peer1;
+    tokio::spawn(async move {
+        network.run().await.expect("network run failed!");
+    });
+
+    let NetworkPeer { config: config_2, network_handle: peer2, network, .. } = peer2;
+    tokio::spawn(async move {
+        network.run().await.expect("network run failed!");
+    });
+
+    // start swarm listening on default any address
+    peer1.start_listening(config_1.authority().primary_network_address().clone()).await?;
+    peer2.start_listening(config_2.authority().primary_network_address().clone()).await?;
+    let peer2_id = peer2.local_peer_id().await?;
+    let peer2_addr = peer2.listeners().await?.first().expect("peer2 listen addr").clone();
+
+    // the second dial joins the first
+    let max_time = Duration::from_secs(5);
+    let (first, second) = timeout(
+        max_time,
+        futures::future::join(
+            peer1.dial(peer2_id, peer2_addr.clone()),
+            peer1.dial(peer2_id, peer2_addr),
+        ),
+    )
+    .await?;
+    assert!(first.is_ok());
+    assert!(second.is_ok());
+    assert_eq!(peer1.connected_peers().await?, vec![peer2_id]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tcp_peer_connects_only_with_shared_transport() -> eyre::Result<()> {
+    let all_nodes = CommitteeFixture::builder(MemDatabase::default).build();
+    let mut authorities = all_nodes.authorities();
+    let tcp_config = config_with_transports(
+        authorities.next().expect("first authority").consensus_config(),
+        vec![NetworkTransport::Tcp],
+    );
+    let quic_config = config_with_transports(
+        authorities.next().expect("second authority").consensus_config(),
+        vec![NetworkTransport::Quic],
+    );
+    let both_config = config_with_transports(
+        authorities.next().expect("third authority").consensus_config(),
+        vec![NetworkTransport::Quic, NetworkTransport::Tcp],
+    );
+
+    // start all peers
+    let mut handles = Vec::new();
+    for config in [&tcp_config, &quic_config, &both_config] {
+        let NetworkPeer { network_handle, network, .. } =
+            create_network_peer::<TestWorkerRequest, TestWorkerResponse>(config.clone(), 10);
+        tokio::spawn(async move {
+            network.run().await.expect("network run failed!");
+        });
+        network_handle
+            .start_listening(config.authority().primary_network_address().clone())
+            .await?;
+        handles.push(network_handle);
+    }
+    let (tcp_peer, quic_peer, both_peer) = (&handles[0], &handles[1], &handles[2]);
+
+    // the tcp only peer only listens on tcp
+    let tcp_listeners = tcp_peer.listeners().await?;
+    assert!(!tcp_listeners.is_empty());
+    assert!(tcp_listeners.iter().all(|addr| addr.iter().any(|p| matches!(p, Protocol::Tcp(_)))));
+
+    // no shared transport with the quic only peer
+    let quic_peer_id = quic_peer.local_peer_id().await?;
+    let quic_addr = quic_peer.listeners().await?.first().expect("quic listen addr").clone();
+    assert!(timeout(Duration::from_secs(5), tcp_peer.dial(quic_peer_id, quic_addr))
+        .await?
+        .is_err());
+    assert!(tcp_peer.connected_peers().await?.is_empty());
+
+    // the peer with both transports is reachable over tcp
+    let both_peer_id = both_peer.local_peer_id().await?;
+    let both_tcp_addr = both_peer
+        .listeners()
+        .await?
+        .into_iter()
+        .find(|addr| addr.iter().any(|p| matches!(p, Protocol::Tcp(_))))
+        .expect("tcp listen addr");
+    timeout(Duration::from_secs(5), tcp_peer.dial(both_peer_id, both_tcp_addr)).await??;
+    assert_eq!(tcp_peer.connected_peers().await?, vec![both_peer_id]);
+
+    Ok(())
+}
+
+#[test]
+fn test_transport_addrs() {
+    let quic: Multiaddr = "/ip4/127.0.0.1/udp/49590/quic-v1".parse().expect("quic addr");
+    let tcp: Multiaddr = "/ip4/127.0.0.1/tcp/49590".parse().expect("tcp addr");
+    assert_eq!(transport_addrs(&quic, &[NetworkTransport::Quic]), vec![quic.clone()]);
+    assert_eq!(transport_addrs(&quic, &[NetworkTransport::Tcp]), vec![tcp.clone()]);
+    assert_eq!(
+        transport_addrs(&tcp, &[NetworkTransport::Quic, NetworkTransport::Tcp]),
+        vec![quic, tcp]
+    );
+}
+
 #[tokio::test]
 async fn test_valid_req_res_inbound_failure() -> eyre::Result<()> {
     tn_test_utils::init_test_tracing();
@@ -266,8 +802,9 @@ async fn test_valid_req_res_inbound_failure() -> eyre::Result<()> {
     assert_eq!(connected_peers.len(), 1);
 
     // wait for peer2 to receive req
-    let event =
-        timeout(max_time, network_events_2.recv()).await?.expect("first network event received");
+    let event = timeout(max_time, next_message(&mut network_events_2))
+        .await?
+        .expect("first network event received");
 
     // expect network event
     if let NetworkEvent::Request { request, cancel, .. } = event {
@@ -382,8 +919,9 @@ async fn test_outbound_failure_malicious_response() -> eyre::Result<()> {
     };
     let response_from_peer =
         honest_peer.send_request(honest_req.clone(), malicious_peer_id).await?;
-    let event =
-        timeout(max_time, network_events_2.recv()).await?.expect("first network event received");
+    let event = timeout(max_time, next_message(&mut network_events_2))
+        .await?
+        .expect("first network event received");
 
     // expect network event
     if let NetworkEvent::Request { request, channel, .. } = event {
@@ -456,8 +994,9 @@ async fn test_publish_to_one_peer() -> eyre::Result<()> {
 
     // publish correct message and wait to receive
     let _message_id = cvv.publish(test_topic, expected_result.clone()).await?;
-    let event =
-        timeout(Duration::from_secs(2), nvv_network_events.recv()).await?.expect("batch received");
+    let event = timeout(Duration::from_secs(2), next_message(&mut nvv_network_events))
+        .await?
+        .expect("batch received");
 
     // assert gossip message
     if let NetworkEvent::Gossip(msg) = event {
@@ -469,6 +1008,319 @@ async fn test_publish_to_one_peer() -> eyre::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_gossip_event_includes_topic() -> eyre::Result<()> {
+    // start publisher and subscriber networks
+    let TestTypes { peer1, peer2 } = create_test_types::<TestWorkerRequest, TestWorkerResponse>();
+    let NetworkPeer { config: config_1, network_handle: publisher, network, .. } = peer1;
+    tokio::spawn(async move {
+        network.run().await.expect("network run failed!");
+    });
+    let NetworkPeer {
+        config: config_2,
+        network_handle: subscriber,
+        network_events: mut subscriber_events,
+        network,
+    } = peer2;
+    tokio::spawn(async move {
+        network.run().await.expect("network run failed!");
+    });
+
+    publisher.start_listening(config_1.authority().primary_network_address().clone()).await?;
+    subscriber.start_listening(config_2.authority().primary_network_address().clone()).await?;
+    let publisher_id = publisher.local_peer_id().await?;
+    let publisher_addr = publisher.listeners().await?.first().expect("publisher addr").clone();
+
+    // subscribe to two topics
+    let batch_topic = IdentTopic::new("test-topic");
+    let certificate_topic = IdentTopic::new("test-certificate-topic");
+    for topic in [&batch_topic, &certificate_topic] {
+        publisher.subscribe(topic.clone()).await?;
+        subscriber.subscribe(topic.clone()).await?;
+    }
+    subscriber.dial(publisher_id, publisher_addr).await?;
+
+    // sleep for gossip mesh to form
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    // publish on each topic and assert the event carries the topic
+    for (topic, data) in [(&batch_topic, vec![1; 32]), (&certificate_topic, vec![2; 32])] {
+        publisher.publish(topic.clone(), data.clone()).await?;
+        let event = timeout(Duration::from_secs(2), next_message(&mut subscriber_events))
+            .await?
+            .expect("gossip received");
+        assert_matches!(
+            event,
+            NetworkEvent::Gossip(msg) if msg.topic == topic.hash() && msg.data == data
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_published_block_reach_tracks_re_propagating_peers() -> eyre::Result<()> {
+    let all_nodes = CommitteeFixture::builder(MemDatabase::default).build();
+    let mut authorities = all_nodes.authorities();
+    let mut peers = Vec::new();
+    for _ in 0..3 {
+        let config = authorities.next().expect("authority").consensus_config();
+        let NetworkPeer { config, network_handle, network_events, network } =
+            create_network_peer::<TestWorkerRequest, TestWorkerResponse>(config, 10);
+        tokio::spawn(async move {
+            network.run().await.expect("network run failed!");
+        });
+        network_handle
+            .start_listening(config.authority().primary_network_address().clone())
+            .await?;
+        peers.push((network_handle, network_events));
+    }
+
+    // topics for pubsub
+    let test_topic = IdentTopic::new("test-topic");
+
+    // all peers subscribe and the publisher connects to both peers
+    let publisher = &peers[0].0;
+    publisher.subscribe(test_topic.clone()).await?;
+    let publisher_id = publisher.local_peer_id().await?;
+    let publisher_addr = publisher.listeners().await?.first().expect("publisher addr").clone();
+    let mut expected_reach = HashSet::new();
+    for (peer, _) in peers.iter().skip(1) {
+        peer.subscribe(test_topic.clone()).await?;
+        peer.dial(publisher_id, publisher_addr.clone()).await?;
+        expected_reach.insert(peer.local_peer_id().await?);
+    }
+
+    // sleep for gossip mesh to form
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    let mesh_peers: HashSet<_> =
+        publisher.mesh_peers(test_topic.hash()).await?.into_iter().collect();
+    assert_eq!(mesh_peers, expected_reach);
+
+    // publish random block
+    let random_block = fixture_batch_with_transactions(10);
+    let sealed_block = random_block.seal_slow();
+    let expected_result = Vec::from(&sealed_block);
+    let message_id = publisher.publish(test_topic.clone(), expected_result.clone()).await?;
+
+    // both peers receive the block
+    for (_, network_events) in peers.iter_mut().skip(1) {
+        let event = timeout(Duration::from_secs(2), next_message(&mut network_events))
+            .await?
+            .expect("block received");
+        assert_matches!(event, NetworkEvent::Gossip(msg) if msg.data == expected_result);
+    }
+
+    // assert the reach includes every mesh peer once they acknowledge the block
+    let publisher = &peers[0].0;
+    let reach = timeout(Duration::from_secs(2), async {
+        loop {
+            let reach = publisher.published_block_reach(message_id.clone()).await?;
+            if reach.as_ref().is_some_and(|reach| reach.len() == 2) {
+                return eyre::Ok(reach);
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await??
+    .expect("published reach");
+    assert_eq!(reach, mesh_peers);
+
+    // the last peer no longer accepts the publisher's messages and does not re-propagate them
+    let silent_peer = &peers[2].0;
+    let silent_peer_id = silent_peer.local_peer_id().await?;
+    let forwarding_peer_id = peers[1].0.local_peer_id().await?;
+    silent_peer.update_authorized_publishers(HashSet::from([forwarding_peer_id])).await?;
+
+    // publish another block
+    let random_block = fixture_batch_with_transactions(10);
+    let expected_result = Vec::from(&random_block.seal_slow());
+    let publisher = &peers[0].0;
+    let message_id = publisher.publish(test_topic, expected_result.clone()).await?;
+    let event = timeout(Duration::from_secs(2), next_message(&mut peers[1].1))
+        .await?
+        .expect("block received");
+    assert_matches!(event, NetworkEvent::Gossip(msg) if msg.data == expected_result);
+
+    // the reach only includes the peer that re-propagated the block
+    let publisher = &peers[0].0;
+    timeout(Duration::from_secs(2), async {
+        loop {
+            let reach = publisher.published_block_reach(message_id.clone()).await?;
+            if reach.is_some_and(|reach| !reach.is_empty()) {
+                return eyre::Ok(());
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await??;
+    // allow time for an unexpected ack from the silent peer
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let reach = publisher.published_block_reach(message_id).await?.expect("published reach");
+    assert_eq!(reach, HashSet::from([forwarding_peer_id]));
+    assert!(!reach.contains(&silent_peer_id));
+
+    // messages published by other nodes are not tracked
+    let unknown_id = MessageId::new(b"unknown");
+    assert!(publisher.published_block_reach(unknown_id).await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mesh_health_matches_mesh_peers() -> eyre::Result<()> {
+    let all_nodes = CommitteeFixture::builder(MemDatabase::default).build();
+    let mut authorities = all_nodes.authorities();
+    let mut peers = Vec::new();
+    let mut events = Vec::new();
+    for _ in 0..3 {
+        let config = authorities.next().expect("authority").consensus_config();
+        let NetworkPeer { config, network_handle, network_events, network } =
+            create_network_peer::<TestWorkerRequest, TestWorkerResponse>(config, 10);
+        tokio::spawn(async move {
+            network.run().await.expect("network run failed!");
+        });
+        network_handle
+            .start_listening(config.authority().primary_network_address().clone())
+            .await?;
+        peers.push(network_handle);
+        // keep event receivers alive for the duration of the test
+        events.push(network_events);
+    }
+
+    // no subscriptions yet
+    let hub = &peers[0];
+    let health = hub.mesh_health().await?;
+    assert!(health.topics.is_empty());
+
+    // the hub subscribes to two topics and the other peers each subscribe to both
+    let first_topic = IdentTopic::new("first-topic");
+    let second_topic = IdentTopic::new("second-topic");
+    let hub_id = hub.local_peer_id().await?;
+    let hub_addr = hub.listeners().await?.first().expect("hub addr").clone();
+    for peer in peers.iter() {
+        peer.subscribe(first_topic.clone()).await?;
+        peer.subscribe(second_topic.clone()).await?;
+    }
+    for peer in peers.iter().skip(1) {
+        peer.dial(hub_id, hub_addr.clone()).await?;
+    }
+
+    // sleep for gossip mesh to form
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    // the reported counts match the mesh for each subscribed topic
+    for peer in peers.iter() {
+        let health = peer.mesh_health().await?;
+        assert_eq!(health.topics.len(), 2);
+        for topic in [first_topic.hash(), second_topic.hash()] {
+            let mesh_peers = peer.mesh_peers(topic.clone()).await?;
+            let topic_health = health.topics.get(&topic).expect("subscribed topic");
+            assert_eq!(topic_health.mesh_peers, mesh_peers.len());
+            assert_eq!(topic_health.healthy, mesh_peers.len() >= health.mesh_n_low);
+        }
+    }
+
+    // the hub is connected to both peers and meets the threshold
+    let health = hub.mesh_health().await?;
+    assert!(health.is_healthy());
+    assert!(health.topics.values().all(|topic| topic.mesh_peers == 2));
+
+    // the other peers only reach the hub and fall below the threshold
+    let health = peers[1].mesh_health().await?;
+    assert!(!health.is_healthy());
+    assert_eq!(health.unhealthy_topics().count(), 2);
+
+    Ok(())
+}
+
+/// Create a gossip message published by `source` for `epoch`.
+fn epoch_gossip(source: PeerId, epoch: Epoch, data: Vec<u8>) -> GossipMessage {
+    GossipMessage {
+        source: Some(source),
+        data: encode_gossip_epoch(epoch, data),
+        sequence_number: Some(1),
+        topic: IdentTopic::new("test-topic").hash(),
+    }
+}
+
+#[tokio::test]
+async fn test_redelivered_gossip_forwarded_once() -> eyre::Result<()> {
+    let TestTypes { peer1, peer2 } = create_test_types::<TestWorkerRequest, TestWorkerResponse>();
+    let NetworkPeer { config, mut network, network_events: mut events, .. } = peer1;
+    let publisher = *peer2.network.swarm.local_peer_id();
+
+    // the same message delivered by two mesh peers
+    let data = Vec::from(&fixture_batch_with_transactions(10).seal_slow());
+    let message = epoch_gossip(publisher, config.committee().epoch(), data.clone());
+    let message_id = gossip_message_id(&message);
+    for propagation_source in [publisher, PeerId::random()] {
+        network.process_gossip_event(GossipEvent::Message {
+            propagation_source,
+            message_id: message_id.clone(),
+            message: message.clone(),
+        })?;
+    }
+
+    // forwarded downstream exactly once without the epoch prefix
+    assert_matches!(events.try_recv(), Ok(NetworkEvent::Gossip(msg)) if msg.data == data);
+    assert!(events.try_recv().is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_removed_publisher_gossip_ignored_within_grace_period() -> eyre::Result<()> {
+    let TestTypes { peer1, peer2 } = create_test_types::<TestWorkerRequest, TestWorkerResponse>();
+    let NetworkPeer { config, mut network, network_events: mut events, .. } = peer1;
+    let publisher = *peer2.network.swarm.local_peer_id();
+    network.config.removed_publisher_grace_period = Duration::from_secs(60);
+
+    // remove the publisher from the authorities
+    let mut authorities: HashSet<_> =
+        network.authorized_publishers_by_epoch.values().flatten().copied().collect();
+    assert!(authorities.remove(&publisher));
+    let (reply, ack) = oneshot::channel();
+    network
+        .process_command(NetworkCommand::UpdateAuthorizedPublishers {
+            authorities: authorities.clone(),
+            reply,
+        })
+        .await;
+    ack.await??;
+
+    // the publisher's in-flight message is ignored without a penalty
+    let message = epoch_gossip(
+        publisher,
+        config.committee().epoch(),
+        Vec::from(&fixture_batch_with_transactions(10).seal_slow()),
+    );
+    assert_eq!(network.verify_gossip(&message), GossipAcceptance::Ignore);
+    network.process_gossip_event(GossipEvent::Message {
+        propagation_source: publisher,
+        message_id: gossip_message_id(&message),
+        message: message.clone(),
+    })?;
+    assert!(events.try_recv().is_err());
+    assert!(!network.application_scores.contains_key(&publisher));
+
+    // messages are rejected once the grace period expires
+    network.config.removed_publisher_grace_period = Duration::ZERO;
+    assert_eq!(network.verify_gossip(&message), GossipAcceptance::Reject);
+
+    // re-authorized publishers are accepted again
+    authorities.insert(publisher);
+    let (reply, ack) = oneshot::channel();
+    network
+        .process_command(NetworkCommand::UpdateAuthorizedPublishers { authorities, reply })
+        .await;
+    ack.await??;
+    assert!(!network.recently_removed_publishers.contains_key(&publisher));
+    assert_eq!(network.verify_gossip(&message), GossipAcceptance::Accept);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_msg_verification_ignores_unauthorized_publisher() -> eyre::Result<()> {
     // start honest cvv network
@@ -514,8 +1366,9 @@ async fn test_msg_verification_ignores_unauthorized_publisher() -> eyre::Result<
 
     // publish correct message and wait to receive
     let _message_id = cvv.publish(test_topic.clone(), expected_result.clone()).await?;
-    let event =
-        timeout(Duration::from_secs(2), nvv_network_events.recv()).await?.expect("batch received");
+    let event = timeout(Duration::from_secs(2), next_message(&mut nvv_network_events))
+        .await?
+        .expect("batch received");
 
     // assert gossip message
     if let NetworkEvent::Gossip(msg) = event {
@@ -533,10 +1386,350 @@ async fn test_msg_verification_ignores_unauthorized_publisher() -> eyre::Result<
     let _message_id = cvv.publish(test_topic, expected_result.clone()).await?;
 
     // message should never be forwarded
-    let timeout = timeout(Duration::from_secs(2), nvv_network_events.recv()).await;
+    let timeout = timeout(Duration::from_secs(2), next_message(&mut nvv_network_events)).await;
     assert!(timeout.is_err());
 
     // TODO: assert peer score after bad message
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_msg_verification_accepts_retiring_publisher_during_epoch_overlap() -> eyre::Result<()>
+{
+    // start retiring cvv network
+    let TestTypes { peer1, peer2 } = create_test_types::<TestWorkerRequest, TestWorkerResponse>();
+    let NetworkPeer { config: config_1, network_handle: cvv, network, .. } = peer1;
+    tokio::spawn(async move {
+        network.run().await.expect("network run failed!");
+    });
+
+    // start honest nvv network
+    let NetworkPeer {
+        config: config_2,
+        network_handle: nvv,
+        network_events: mut nvv_network_events,
+        network,
+    } = peer2;
+    tokio::spawn(async move {
+        network.run().await.expect("network run failed!");
+    });
+
+    // start swarm listening on default any address
+    cvv.start_listening(config_1.authority().primary_network_address().clone()).await?;
+    nvv.start_listening(config_2.authority().primary_network_address().clone()).await?;
+    let cvv_id = cvv.local_peer_id().await?;
+    let nvv_id = nvv.local_peer_id().await?;
+    let cvv_addr = cvv.listeners().await?.first().expect("peer2 listen addr").clone();
+
+    // topics for pubsub
+    let test_topic = IdentTopic::new("test-topic");
+
+    // subscribe
+    nvv.subscribe(test_topic.clone()).await?;
+
+    // dial cvv
+    nvv.dial(cvv_id, cvv_addr).await?;
+
+    // sleep for gossip connection time lapse
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // the next epoch's committee does not include cvv
+    let next_epoch = config_2.committee().epoch() + 1;
+    nvv.new_epoch_authorized_publishers(next_epoch, HashSet::from([nvv_id])).await?;
+
+    // cvv is still authorized during the overlap window
+    let random_block = fixture_batch_with_transactions(10);
+    let expected_result = Vec::from(&random_block.seal_slow());
+    let _message_id = cvv.publish(test_topic.clone(), expected_result.clone()).await?;
+    let event = timeout(Duration::from_secs(2), next_message(&mut nvv_network_events))
+        .await?
+        .expect("batch received");
+
+    // assert gossip message
+    if let NetworkEvent::Gossip(msg) = event {
+        assert_eq!(msg.data, expected_result);
+    } else {
+        panic!("unexpected network event received");
+    }
+
+    // close the overlap window and try to publish again
+    nvv.close_epoch_overlap().await?;
+
+    let random_block = fixture_batch_with_transactions(10);
+    let expected_result = Vec::from(&random_block.seal_slow());
+    let _message_id = cvv.publish(test_topic, expected_result.clone()).await?;
+
+    // message should never be forwarded
+    let timeout = timeout(Duration::from_secs(2), next_message(&mut nvv_network_events)).await;
+    assert!(timeout.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_only_current_and_overlapping_epoch_publishers_authorized() -> eyre::Result<()> {
+    let TestTypes { peer1, peer2 } = create_test_types::<TestWorkerRequest, TestWorkerResponse>();
+    let NetworkPeer { config, mut network, .. } = peer1;
+    let retiring = *peer2.network.swarm.local_peer_id();
+    let current = PeerId::random();
+    let epoch = config.committee().epoch();
+    let message = |source, epoch| {
+        epoch_gossip(source, epoch, Vec::from(&fixture_batch_with_transactions(10).seal_slow()))
+    };
+    assert_eq!(network.verify_gossip(&message(retiring, epoch)), GossipAcceptance::Accept);
+    assert_eq!(network.verify_gossip(&message(current, epoch)), GossipAcceptance::Reject);
+
+    // messages without the epoch prefix are rejected
+    let mut malformed = message(retiring, epoch);
+    malformed.data.truncate(GOSSIP_EPOCH_LEN - 1);
+    assert_eq!(network.verify_gossip(&malformed), GossipAcceptance::Reject);
+
+    // the next epoch opens the overlap window for the retiring committee only
+    let (reply, ack) = oneshot::channel();
+    network
+        .process_command(NetworkCommand::NewEpochAuthorizedPublishers {
+            epoch: epoch + 1,
+            authorities: HashSet::from([current]),
+            reply,
+        })
+        .await;
+    ack.await??;
+    assert_eq!(network.verify_gossip(&message(current, epoch + 1)), GossipAcceptance::Accept);
+    assert_eq!(network.verify_gossip(&message(retiring, epoch)), GossipAcceptance::Accept);
+
+    // publishers are only authorized for their own committee's epoch
+    assert_eq!(network.verify_gossip(&message(retiring, epoch + 1)), GossipAcceptance::Reject);
+    assert_eq!(network.verify_gossip(&message(current, epoch)), GossipAcceptance::Reject);
+
+    // closing the overlap window leaves the current epoch only
+    //
+    // the retiring committee's in-flight gossip is ignored during the grace period
+    let (reply, ack) = oneshot::channel();
+    network.process_command(NetworkCommand::CloseEpochOverlap { reply }).await;
+    ack.await??;
+    assert_eq!(network.verify_gossip(&message(current, epoch + 1)), GossipAcceptance::Accept);
+    assert_eq!(network.verify_gossip(&message(retiring, epoch)), GossipAcceptance::Ignore);
+    assert!(!network.recently_removed_publishers.contains_key(&current));
+
+    // messages are rejected once the grace period expires
+    network.config.removed_publisher_grace_period = Duration::ZERO;
+    assert_eq!(network.verify_gossip(&message(retiring, epoch)), GossipAcceptance::Reject);
+
+    // a previous epoch's committee is not authorized outside the overlap window
+    network.authorized_publishers_by_epoch.insert(epoch, HashSet::from([retiring]));
+    assert_ne!(network.verify_gossip(&message(retiring, epoch)), GossipAcceptance::Accept);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rotated_keypair_accepted_by_updated_peer() -> eyre::Result<()> {
+    // start rotating cvv network
+    let TestTypes { peer1, peer2 } = create_test_types::<TestWorkerRequest, TestWorkerResponse>();
+    let NetworkPeer { config: config_1, network_handle: cvv, network, .. } = peer1;
+    tokio::spawn(async move {
+        network.run().await.expect("network run failed!");
+    });
+
+    // start honest nvv network
+    let NetworkPeer {
+        config: config_2,
+        network_handle: nvv,
+        network_events: mut nvv_network_events,
+        network,
+    } = peer2;
+    tokio::spawn(async move {
+        network.run().await.expect("network run failed!");
+    });
+
+    // start swarm listening on default any address
+    cvv.start_listening(config_1.authority().primary_network_address().clone()).await?;
+    nvv.start_listening(config_2.authority().primary_network_address().clone()).await?;
+    let old_cvv_id = cvv.local_peer_id().await?;
+    let cvv_addr = cvv.listeners().await?.first().expect("peer1 listen addr").clone();
+
+    // topics for pubsub
+    let test_topic = IdentTopic::new("test-topic");
+    cvv.subscribe(test_topic.clone()).await?;
+    nvv.subscribe(test_topic.clone()).await?;
+
+    // dial cvv
+    nvv.dial(old_cvv_id, cvv_addr.clone()).await?;
+
+    // rotate cvv's keypair
+    let new_cvv_id = cvv.rotate_keypair(NetworkKeypair::generate_ed25519()).await?;
+    assert_ne!(new_cvv_id, old_cvv_id);
+    assert_eq!(cvv.local_peer_id().await?, new_cvv_id);
+
+    // new swarm listens on the same address
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let new_cvv_addr = cvv.listeners().await?.first().expect("peer1 listen addr").clone();
+    assert_eq!(new_cvv_addr, cvv_addr);
+
+    // nvv authorizes cvv's new peer id and connects
+    nvv.update_authorized_publishers(HashSet::from([new_cvv_id])).await?;
+    nvv.dial(new_cvv_id, new_cvv_addr).await?;
+
+    // sleep for gossip connection time lapse
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // publish with the new key and wait to receive
+    let random_block = fixture_batch_with_transactions(10);
+    let expected_result = Vec::from(&random_block.seal_slow());
+    let _message_id = cvv.publish(test_topic, expected_result.clone()).await?;
+    let event = timeout(Duration::from_secs(2), next_message(&mut nvv_network_events))
+        .await?
+        .expect("batch received");
+
+    // assert gossip message is from the new peer id
+    if let NetworkEvent::Gossip(msg) = event {
+        assert_eq!(msg.data, expected_result);
+        assert_eq!(msg.source, Some(new_cvv_id));
+    } else {
+        panic!("unexpected network event received");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_peers_exchange_metadata_through_identify() -> eyre::Result<()> {
+    let TestTypes { peer1, peer2 } = create_test_types::<TestWorkerRequest, TestWorkerResponse>();
+    let NetworkPeer { config: config_1, network_handle: peer1, network, .. } = peer1;
+    tokio::spawn(async move {
+        network.run().await.expect("network run failed!");
+    });
+
+    let NetworkPeer { config: config_2, network_handle: peer2, network, .. } = peer2;
+    tokio::spawn(async move {
+        network.run().await.expect("network run failed!");
+    });
+
+    // start swarm listening on default any address
+    peer1.start_listening(config_1.authority().primary_network_address().clone()).await?;
+    peer2.start_listening(config_2.authority().primary_network_address().clone()).await?;
+    let peer1_id = peer1.local_peer_id().await?;
+    let peer2_id = peer2.local_peer_id().await?;
+    let peer1_addr = peer1.listeners().await?.first().expect("peer1 listen addr").clone();
+    let peer2_addr = peer2.listeners().await?.first().expect("peer2 listen addr").clone();
+
+    // no metadata before connecting
+    assert!(peer2.peer_metadata(peer1_id).await?.is_none());
+
+    // dial peer1
+    peer2.dial(peer1_id, peer1_addr.clone()).await?;
+
+    // sleep for identify exchange
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let expected_protocol =
+        config_1.network_config().libp2p_config().supported_req_res_protocols[0].0.clone();
+
+    // peer2 learns peer1's protocols and listen addresses
+    let peer1_metadata = peer2.peer_metadata(peer1_id).await?.expect("peer1 identified");
+    assert!(peer1_metadata.protocols.contains(&expected_protocol));
+    assert!(peer1_metadata.listen_addrs.contains(&peer1_addr));
+    assert!(peer1_metadata.agent_version.starts_with("telcoin-network/"));
+
+    // peer1 learns peer2's protocols and listen addresses
+    let peer2_metadata = peer1.peer_metadata(peer2_id).await?.expect("peer2 identified");
+    assert!(peer2_metadata.protocols.contains(&expected_protocol));
+    assert!(peer2_metadata.listen_addrs.contains(&peer2_addr));
+    assert_eq!(peer2_metadata.protocol_version, peer1_metadata.protocol_version);
+
+    Ok(())
+}
+
+#[test]
+fn test_gossip_mesh_config_valid_for_committee_sizes() {
+    for committee_size in [4, 7, 100] {
+        let mesh_config = GossipMeshConfig::new(committee_size);
+        let peers = committee_size - 1;
+
+        // gossipsub validates the mesh bounds when the config is built
+        let config = gossipsub_config(&mesh_config).expect("valid gossipsub config");
+        assert!(config.mesh_outbound_min() <= config.mesh_n_low());
+        assert!(config.mesh_n_low() <= config.mesh_n());
+        assert!(config.mesh_n() <= config.mesh_n_high());
+        assert!(config.mesh_outbound_min() * 2 <= config.mesh_n());
+
+        // the target mesh never exceeds the number of peers in the committee
+        assert!(config.mesh_n() <= peers);
+        assert!(config.mesh_n_low() >= 1);
+    }
+
+    // small committees use a full mesh
+    let mesh_config = GossipMeshConfig::new(4);
+    assert_eq!(mesh_config.mesh_n, 3);
+    assert!(mesh_config.heartbeat_interval < Duration::from_secs(1));
+
+    // large committees use the gossipsub defaults
+    let mesh_config = GossipMeshConfig::new(100);
+    let default_config = gossipsub::Config::default();
+    assert_eq!(mesh_config.mesh_n, default_config.mesh_n());
+    assert_eq!(mesh_config.mesh_n_low, default_config.mesh_n_low());
+    assert_eq!(mesh_config.mesh_n_high, default_config.mesh_n_high());
+    assert_eq!(mesh_config.mesh_outbound_min, default_config.mesh_outbound_min());
+    assert_eq!(mesh_config.heartbeat_interval, default_config.heartbeat_interval());
+}
+
+#[tokio::test]
+async fn test_unresponsive_peer_disconnected_after_ping_failures() -> eyre::Result<()> {
+    let all_nodes = CommitteeFixture::builder(MemDatabase::default).build();
+    let mut authorities = all_nodes.authorities();
+    let interval = Duration::from_millis(200);
+    let max_failures = 2;
+    let mut peers = Vec::new();
+    for _ in 0..2 {
+        let config = config_with_ping(
+            authorities.next().expect("authority").consensus_config(),
+            interval,
+            max_failures,
+        );
+        let NetworkPeer { config, network_handle, network_events, network } =
+            create_network_peer::<TestWorkerRequest, TestWorkerResponse>(config, 10);
+        tokio::spawn(async move {
+            network.run().await.expect("network run failed!");
+        });
+        network_handle
+            .start_listening(config.authority().primary_network_address().clone())
+            .await?;
+        peers.push((network_handle, network_events));
+    }
+    let (peer2, _) = peers.pop().expect("peer2");
+    let (peer1, mut peer1_events) = peers.pop().expect("peer1");
+    let peer2_id = peer2.local_peer_id().await?;
+
+    // peer1 connects to peer2 through the proxy
+    let peer2_addr = peer2.listeners().await?.first().expect("peer2 listen addr").clone();
+    let peer2_port = peer2_addr
+        .iter()
+        .find_map(|protocol| match protocol {
+            Protocol::Udp(port) => Some(port),
+            _ => None,
+        })
+        .expect("peer2 udp port");
+    let severed = Arc::new(AtomicBool::new(false));
+    let proxy_addr = spawn_udp_proxy(([127, 0, 0, 1], peer2_port).into(), severed.clone()).await?;
+    let proxy_multiaddr: Multiaddr = format!("/ip4/127.0.0.1/udp/{}/quic-v1", proxy_addr.port())
+        .parse()
+        .expect("proxy multiaddr");
+    peer1.dial(peer2_id, proxy_multiaddr).await?;
+    let event = timeout(Duration::from_secs(2), peer1_events.recv()).await?;
+    assert_matches!(event, Some(NetworkEvent::PeerConnected(peer)) if peer == peer2_id);
+
+    // responsive peers stay connected
+    let still_connected =
+        timeout(interval * 2 * (max_failures as u32 + 1), peer1_events.recv()).await;
+    assert!(still_connected.is_err());
+
+    // silently drop the connection
+    severed.store(true, Ordering::Relaxed);
+
+    // the disconnect is detected by failed pings long before the quic idle timeout
+    let event = timeout(Duration::from_secs(5), peer1_events.recv()).await?;
+    assert_matches!(event, Some(NetworkEvent::PeerDisconnected(peer)) if peer == peer2_id);
+
+    Ok(())
+}
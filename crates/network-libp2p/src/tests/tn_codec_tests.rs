@@ -2,9 +2,9 @@
 
 use super::*;
 use crate::TNCodec;
-use libp2p::StreamProtocol;
+use libp2p::{gossipsub::IdentTopic, StreamProtocol};
 use serde::Deserialize;
-use tn_types::{Certificate, CertificateDigest, Header, Vote};
+use tn_types::{encode, BlockHash, Certificate, CertificateDigest, Header, Vote};
 
 // For some reason, clippy doesn't like importing these from common mod.
 // However, it works just fine for network_tests.rs ¯\_(ツ)_/¯
@@ -243,3 +243,76 @@ async fn test_malicious_prefix_deceives_peer_to_read_message_and_fails() {
     let res = honest_peer.read_response(&protocol, &mut encoded.as_ref()).await;
     assert!(res.is_err());
 }
+
+/// Create a raw gossip message as it's received from the network.
+fn raw_gossip(topic: TopicHash, data: Vec<u8>) -> RawMessage {
+    RawMessage {
+        source: None,
+        data,
+        sequence_number: None,
+        topic,
+        signature: None,
+        key: None,
+        validated: false,
+    }
+}
+
+#[test]
+fn test_large_certificate_gossip_compression_round_trip() {
+    let max_gossip_size = 1024 * 1024; // 1mb
+    let compression = GossipCompression::new(Some(1024), max_gossip_size);
+    let topic = IdentTopic::new("tn-test").hash();
+
+    // create a large certificate
+    let mut certificate = Certificate::default();
+    for i in 0..500u64 {
+        certificate
+            .header_mut_for_test()
+            .payload
+            .insert(BlockHash::left_padding_from(&i.to_be_bytes()), (0, i));
+    }
+    let data = encode(&certificate);
+    assert!(data.len() > 1024);
+
+    // the published message id is computed from the uncompressed data
+    let published = GossipMessage {
+        source: None,
+        data: data.clone(),
+        sequence_number: None,
+        topic: topic.clone(),
+    };
+    let expected_id = gossip_message_id(&published);
+
+    // compress the certificate
+    let compressed = compression.outbound_transform(&topic, data.clone()).expect("compressed");
+    assert!(compressed.starts_with(&ZSTD_MAGIC_NUMBER));
+    assert!(compressed.len() < data.len());
+
+    // decompress the certificate
+    let raw_message = raw_gossip(topic.clone(), compressed.clone());
+    let received = compression.inbound_transform(raw_message).expect("decompressed");
+    assert_eq!(received.data, data);
+    assert_eq!(gossip_message_id(&received), expected_id);
+    let decoded: Certificate = bcs::from_bytes(&received.data).expect("decode certificate");
+    assert_eq!(decoded, certificate);
+
+    // peers that don't compress gossip still decode compressed gossip
+    let uncompressed_peer = GossipCompression::new(None, max_gossip_size);
+    let raw_message = raw_gossip(topic.clone(), compressed);
+    let received = uncompressed_peer.inbound_transform(raw_message).expect("decompressed");
+    assert_eq!(gossip_message_id(&received), expected_id);
+
+    // and publish uncompressed gossip that is decoded as-is
+    let uncompressed =
+        uncompressed_peer.outbound_transform(&topic, data.clone()).expect("uncompressed");
+    assert_eq!(uncompressed, data);
+    let raw_message = raw_gossip(topic.clone(), uncompressed);
+    let received = compression.inbound_transform(raw_message).expect("uncompressed");
+    assert_eq!(gossip_message_id(&received), expected_id);
+
+    // decompressed gossip must be within the max size
+    let small_peer = GossipCompression::new(None, data.len() - 1);
+    let compressed = compression.outbound_transform(&topic, data).expect("compressed");
+    let raw_message = raw_gossip(topic, compressed);
+    assert!(small_peer.inbound_transform(raw_message).is_err());
+}
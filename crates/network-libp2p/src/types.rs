@@ -4,10 +4,13 @@ use crate::{codec::TNMessage, error::NetworkError, GossipMessage};
 use libp2p::{
     core::transport::ListenerId,
     gossipsub::{PublishError, SubscriptionError, TopicHash},
+    identify,
     request_response::ResponseChannel,
-    Multiaddr, PeerId, TransportError,
+    Multiaddr, PeerId, StreamProtocol, TransportError,
 };
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use tn_types::{Epoch, NetworkKeypair};
 use tokio::sync::{mpsc, oneshot};
 
 pub use libp2p::gossipsub::{IdentTopic, MessageId};
@@ -15,6 +18,9 @@ pub use libp2p::gossipsub::{IdentTopic, MessageId};
 /// The result for network operations.
 pub type NetworkResult<T> = Result<T, NetworkError>;
 
+/// The channel for receiving a peer's response to an outbound request.
+pub type ResponseReceiver<Res> = oneshot::Receiver<NetworkResult<Res>>;
+
 /// Helper trait to cast lib-specific results into RPC messages.
 pub trait IntoResponse<M> {
     /// Convert a [Result] into a [TNMessage] type.
@@ -46,6 +52,22 @@ pub const PRIMARY_CERT_TOPIC: &str = "tn_certificates";
 /// The topic for NVVs to subscribe to for published consensus chain.
 pub const CONSENSUS_HEADER_TOPIC: &str = "tn_consensus_headers";
 
+/// The request-response protocol peers use to acknowledge gossip to the message's publisher.
+pub const GOSSIP_ACK_PROTOCOL: StreamProtocol =
+    StreamProtocol::new("/telcoin-network/gossip-ack/0.0.0");
+
+/// The acknowledgement a peer sends to the publisher of a gossip message.
+///
+/// Peers send the acknowledgement once they accept the message, which is when gossipsub forwards
+/// the message to their own mesh peers. The publisher echoes the acknowledgement as the response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GossipAck {
+    /// The id of the accepted gossip message.
+    pub message_id: Vec<u8>,
+}
+
+impl TNMessage for GossipAck {}
+
 /// Events created from network activity.
 #[derive(Debug)]
 pub enum NetworkEvent<Req, Res> {
@@ -61,7 +83,14 @@ pub enum NetworkEvent<Req, Res> {
         cancel: oneshot::Receiver<()>,
     },
     /// Gossip message received.
+    ///
+    /// The message includes the topic hash it was published on, so handlers can route the message
+    /// by topic without decoding the data first.
     Gossip(GossipMessage),
+    /// The first connection to a peer was established.
+    PeerConnected(PeerId),
+    /// The last connection to a peer was closed.
+    PeerDisconnected(PeerId),
 }
 
 /// Commands for the swarm.
@@ -75,12 +104,48 @@ where
     ///
     /// This list is used to verify messages came from an authorized source.
     /// Only valid for Subscriber implementations.
+    ///
+    /// The set replaces the authorized publishers for all epochs.
     UpdateAuthorizedPublishers {
         /// The unique set of authorized peers.
         authorities: HashSet<PeerId>,
         /// The acknowledgement that the set was updated.
         reply: oneshot::Sender<NetworkResult<()>>,
     },
+    /// Add the authorized publishers for a new epoch.
+    ///
+    /// The previous epoch's publishers remain authorized until the overlap window is closed.
+    NewEpochAuthorizedPublishers {
+        /// The epoch for the committee.
+        epoch: Epoch,
+        /// The unique set of authorized peers for the epoch.
+        authorities: HashSet<PeerId>,
+        /// The acknowledgement that the set was added.
+        reply: oneshot::Sender<NetworkResult<()>>,
+    },
+    /// Return the metadata a peer shared through identify.
+    PeerMetadata {
+        /// The peer's id.
+        peer_id: PeerId,
+        /// Reply with the peer's metadata if the peer identified itself.
+        reply: oneshot::Sender<Option<PeerMetadata>>,
+    },
+    /// Rotate the network keypair.
+    ///
+    /// The swarm is rebuilt with the new keypair. Return the new [PeerId].
+    RotateKeypair {
+        /// The new network keypair.
+        keypair: NetworkKeypair,
+        /// The reply with the new peer id.
+        reply: oneshot::Sender<NetworkResult<PeerId>>,
+    },
+    /// Close the epoch overlap window.
+    ///
+    /// Only publishers for the latest epoch remain authorized.
+    CloseEpochOverlap {
+        /// The acknowledgement that the overlap window is closed.
+        reply: oneshot::Sender<NetworkResult<()>>,
+    },
     /// Start listening on the provided multiaddr.
     ///
     /// Return the result to caller.
@@ -133,8 +198,20 @@ where
     SendRequestAny {
         /// The request to send.
         request: Req,
-        /// Channel for forwarding any responses.
-        reply: oneshot::Sender<NetworkResult<Res>>,
+        /// Channel for returning the selected peer and the channel for the peer's response.
+        reply: oneshot::Sender<NetworkResult<(PeerId, ResponseReceiver<Res>)>>,
+    },
+    /// Penalize a peer for a bad message.
+    ///
+    /// The penalty is subtracted from the peer's application score. Reply `true` if the new
+    /// score was applied to a known peer.
+    ReportPeer {
+        /// The peer to penalize.
+        peer_id: PeerId,
+        /// The amount to lower the peer's application score.
+        penalty: f64,
+        /// Reply if the score was applied.
+        reply: oneshot::Sender<bool>,
     },
     /// Send response to a peer's request.
     SendResponse {
@@ -153,6 +230,13 @@ where
         msg: Vec<u8>,
         reply: oneshot::Sender<Result<MessageId, PublishError>>,
     },
+    /// Return the peers that acknowledged forwarding a message published by this node.
+    PublishedReach {
+        /// The id of the published message.
+        message_id: MessageId,
+        /// Reply with the peers if the message was recently published by this node.
+        reply: oneshot::Sender<Option<HashSet<PeerId>>>,
+    },
     /// Map of all known peers and their associated subscribed topics.
     AllPeers { reply: oneshot::Sender<HashMap<PeerId, Vec<TopicHash>>> },
     /// Collection of this node's connected peers.
@@ -161,6 +245,8 @@ where
     AllMeshPeers { reply: oneshot::Sender<Vec<PeerId>> },
     /// Collection of all mesh peers by a certain topic hash.
     MeshPeers { topic: TopicHash, reply: oneshot::Sender<Vec<PeerId>> },
+    /// The mesh peer count for each subscribed topic.
+    MeshHealth { reply: oneshot::Sender<MeshHealth> },
     /// The peer's score, if it exists.
     PeerScore { peer_id: PeerId, reply: oneshot::Sender<Option<f64>> },
     /// Set peer's application score.
@@ -171,6 +257,65 @@ where
     PendingRequestCount { reply: oneshot::Sender<usize> },
 }
 
+/// The metadata a peer shares through the identify protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerMetadata {
+    /// The application-specific protocol version.
+    pub protocol_version: String,
+    /// The name and version of the peer's client.
+    pub agent_version: String,
+    /// The addresses the peer is listening on.
+    pub listen_addrs: Vec<Multiaddr>,
+    /// The protocols the peer supports.
+    pub protocols: Vec<StreamProtocol>,
+    /// The address the peer observed for this node.
+    pub observed_addr: Multiaddr,
+}
+
+impl From<identify::Info> for PeerMetadata {
+    fn from(info: identify::Info) -> Self {
+        Self {
+            protocol_version: info.protocol_version,
+            agent_version: info.agent_version,
+            listen_addrs: info.listen_addrs,
+            protocols: info.protocols,
+            observed_addr: info.observed_addr,
+        }
+    }
+}
+
+/// The health of the gossip mesh for each topic this node is subscribed to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MeshHealth {
+    /// The configured minimum number of mesh peers before gossipsub tries to graft more peers.
+    pub mesh_n_low: usize,
+    /// The mesh health for each subscribed topic.
+    pub topics: HashMap<TopicHash, TopicMeshHealth>,
+}
+
+impl MeshHealth {
+    /// Return `true` if every subscribed topic meets the `mesh_n_low` threshold.
+    pub fn is_healthy(&self) -> bool {
+        self.topics.values().all(|topic| topic.healthy)
+    }
+
+    /// Return the topics below the `mesh_n_low` threshold.
+    ///
+    /// These topics are at risk of a gossip partition.
+    pub fn unhealthy_topics(&self) -> impl Iterator<Item = &TopicHash> {
+        self.topics.iter().filter(|(_, topic)| !topic.healthy).map(|(hash, _)| hash)
+    }
+}
+
+/// The gossip mesh health for a single topic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TopicMeshHealth {
+    /// The number of peers in this node's mesh for the topic.
+    pub mesh_peers: usize,
+    /// Indicates if the number of mesh peers meets the `mesh_n_low` threshold.
+    pub healthy: bool,
+}
+
 /// Network handle.
 ///
 /// The type that sends commands to the running network (swarm) task.
@@ -210,6 +355,45 @@ where
         ack.await?
     }
 
+    /// Add the authorized publishers for a new epoch.
+    ///
+    /// Messages from the previous epoch's publishers are still accepted until
+    /// [Self::close_epoch_overlap] is called.
+    pub async fn new_epoch_authorized_publishers(
+        &self,
+        epoch: Epoch,
+        authorities: HashSet<PeerId>,
+    ) -> NetworkResult<()> {
+        let (reply, ack) = oneshot::channel();
+        self.sender
+            .send(NetworkCommand::NewEpochAuthorizedPublishers { epoch, authorities, reply })
+            .await?;
+        ack.await?
+    }
+
+    /// Return the metadata a connected peer shared through identify.
+    pub async fn peer_metadata(&self, peer_id: PeerId) -> NetworkResult<Option<PeerMetadata>> {
+        let (reply, metadata) = oneshot::channel();
+        self.sender.send(NetworkCommand::PeerMetadata { peer_id, reply }).await?;
+        metadata.await.map_err(Into::into)
+    }
+
+    /// Rotate the network keypair and return the new [PeerId].
+    ///
+    /// Peers must authorize the new peer id to accept gossip published after the rotation.
+    pub async fn rotate_keypair(&self, keypair: NetworkKeypair) -> NetworkResult<PeerId> {
+        let (reply, ack) = oneshot::channel();
+        self.sender.send(NetworkCommand::RotateKeypair { keypair, reply }).await?;
+        ack.await?
+    }
+
+    /// Close the epoch overlap window so only the latest epoch's publishers are authorized.
+    pub async fn close_epoch_overlap(&self) -> NetworkResult<()> {
+        let (reply, ack) = oneshot::channel();
+        self.sender.send(NetworkCommand::CloseEpochOverlap { reply }).await?;
+        ack.await?
+    }
+
     /// Start swarm listening on the given address. Returns an error if the address is not
     /// supported.
     ///
@@ -272,6 +456,21 @@ where
         published.await?.map_err(Into::into)
     }
 
+    /// Return the peers that re-propagated a recently published message, such as a worker's block.
+    ///
+    /// Peers acknowledge a message to its publisher once they accept and forward it to their own
+    /// mesh peers. The reach grows as acknowledgements arrive and is empty right after publishing.
+    /// Peers that receive the message but do not re-propagate it are never included. The reach is
+    /// `None` if the message wasn't published by this node or is no longer tracked.
+    pub async fn published_block_reach(
+        &self,
+        message_id: MessageId,
+    ) -> NetworkResult<Option<HashSet<PeerId>>> {
+        let (reply, reach) = oneshot::channel();
+        self.sender.send(NetworkCommand::PublishedReach { message_id, reply }).await?;
+        reach.await.map_err(Into::into)
+    }
+
     /// Retrieve a collection of connected peers.
     pub async fn connected_peers(&self) -> NetworkResult<Vec<PeerId>> {
         let (reply, peers) = oneshot::channel();
@@ -300,6 +499,16 @@ where
         mesh_peers.await.map_err(Into::into)
     }
 
+    /// Return the mesh peer count for each subscribed topic and whether it meets the configured
+    /// `mesh_n_low` threshold.
+    ///
+    /// Operators can use this to alert on gossip partitions.
+    pub async fn mesh_health(&self) -> NetworkResult<MeshHealth> {
+        let (reply, health) = oneshot::channel();
+        self.sender.send(NetworkCommand::MeshHealth { reply }).await?;
+        health.await.map_err(Into::into)
+    }
+
     /// Retrieve a specific peer's score, if it exists.
     pub async fn peer_score(&self, peer_id: PeerId) -> NetworkResult<Option<f64>> {
         let (reply, score) = oneshot::channel();
@@ -307,6 +516,16 @@ where
         score.await.map_err(Into::into)
     }
 
+    /// Penalize a peer that returned bad data.
+    ///
+    /// The penalty lowers the peer's application score. Returns `true` if the score was applied to
+    /// a known peer.
+    pub async fn report_peer(&self, peer_id: PeerId, penalty: f64) -> NetworkResult<bool> {
+        let (reply, applied) = oneshot::channel();
+        self.sender.send(NetworkCommand::ReportPeer { peer_id, penalty, reply }).await?;
+        applied.await.map_err(Into::into)
+    }
+
     /// Set the peer's application score.
     ///
     /// This is useful for reporting messages from a peer that fails decoding.
@@ -335,14 +554,15 @@ where
 
     /// Send a request to a peer- any peer will do.
     ///
-    /// Returns a handle for the caller to await the peer's response.
+    /// Returns the selected peer and a handle for the caller to await the peer's response. The
+    /// peer is returned so bad responses can be reported with [Self::report_peer].
     pub async fn send_request_any(
         &self,
         request: Req,
-    ) -> NetworkResult<oneshot::Receiver<NetworkResult<Res>>> {
+    ) -> NetworkResult<(PeerId, ResponseReceiver<Res>)> {
         let (reply, to_caller) = oneshot::channel();
         self.sender.send(NetworkCommand::SendRequestAny { request, reply }).await?;
-        Ok(to_caller)
+        to_caller.await?
     }
 
     /// Respond to a peer's request.
@@ -1,6 +1,6 @@
 //! Builder for engine to mantain generics.
 
-use super::{inner::ExecutionNodeInner, TelcoinNodeTypes, TnBuilder};
+use super::{inner::ExecutionNodeInner, TelcoinNodeTypes, TnBuilder, WorkerTxValidationHook};
 use reth::{consensus::FullConsensus, primitives::EthPrimitives};
 use reth_blockchain_tree::{
     BlockchainTree, BlockchainTreeConfig, ShareableBlockchainTree, TreeExternals,
@@ -46,6 +46,7 @@ where
 
     // Optional components
     opt_faucet_args: Option<FaucetArgs>,
+    opt_tx_validation_hook: Option<WorkerTxValidationHook>,
 }
 
 impl<N> ExecutionNodeBuilder<N>
@@ -55,8 +56,14 @@ where
 {
     /// Start the builder with required components
     pub fn new(tn_builder: &TnBuilder<N::DB>) -> Self {
-        let TnBuilder { database, node_config, tn_config, opt_faucet_args, consensus_metrics: _ } =
-            tn_builder;
+        let TnBuilder {
+            database,
+            node_config,
+            tn_config,
+            opt_faucet_args,
+            consensus_metrics: _,
+            opt_tx_validation_hook,
+        } = tn_builder;
 
         Self {
             node_config: node_config.clone(),
@@ -67,6 +74,7 @@ where
             evm_executor: None,
             evm_config: None,
             opt_faucet_args: opt_faucet_args.clone(),
+            opt_tx_validation_hook: opt_tx_validation_hook.clone(),
         }
     }
 
@@ -151,8 +159,12 @@ where
             evm_config,
             evm_executor,
             opt_faucet_args: self.opt_faucet_args,
+            opt_tx_validation_hook: self.opt_tx_validation_hook,
             tn_config: self.tn_config,
             workers: HashMap::default(),
+            worker_connected_peers: HashMap::default(),
+            batch_builder_metrics: HashMap::default(),
+            epoch: 0,
         })
     }
 }
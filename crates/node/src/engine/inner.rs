@@ -2,47 +2,77 @@
 //!
 //! This module contains the logic for execution.
 
-use super::{WorkerComponents, WorkerTxPool};
+use super::{WorkerComponents, WorkerTxPool, WorkerTxValidationHook};
 use crate::{engine::WorkerNetwork, error::ExecutionError};
-use eyre::eyre;
 use jsonrpsee::http_client::HttpClient;
+use prometheus::default_registry;
 use reth::{
     primitives::EthPrimitives,
+    revm::{
+        database::StateProviderDatabase,
+        primitives::{
+            BlockEnv, CfgEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ExecutionResult,
+            ResultAndState, SpecId, TxEnv,
+        },
+    },
     rpc::{
         builder::{config::RethRpcServerConfig, RpcModuleBuilder, RpcServerHandle},
         eth::EthApi,
     },
+    tasks::TaskSpawner,
 };
 use reth_chainspec::ChainSpec;
 use reth_db::{
     database_metrics::{DatabaseMetadata, DatabaseMetrics},
     Database,
 };
+use reth_evm::ConfigureEvm as _;
 use reth_node_builder::{NodeConfig, RethTransactionPoolConfig};
 use reth_provider::{
     providers::BlockchainProvider, BlockIdReader, BlockNumReader, BlockReader,
-    CanonStateSubscriptions as _, ChainSpecProvider, ChainStateBlockReader,
-    DatabaseProviderFactory, EthStorage, HeaderProvider, ProviderFactory, TransactionVariant,
+    CanonStateNotificationStream, CanonStateSubscriptions as _, ChainSpecProvider,
+    ChainStateBlockReader, DatabaseProviderFactory, EthStorage, HeaderProvider, ProviderFactory,
+    StateProviderFactory, TransactionVariant,
 };
 use reth_transaction_pool::{
-    blobstore::DiskFileBlobStore, TransactionPool, TransactionValidationTaskExecutor,
+    blobstore::{BlobStore, DiskFileBlobStore},
+    CoinbaseTipOrdering, EthPooledTransaction, EthTransactionValidator, LocalTransactionConfig,
+    TransactionPool, TransactionValidationTaskExecutor,
+};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    net::SocketAddr,
+    sync::Arc,
+};
+use tn_batch_builder::{
+    apply_pending_pool_limits, apply_pending_tx_listener_capacity, BatchBuilder,
+    BatchBuilderHandle, BatchBuilderMetrics, HookedTransactionValidator, MinPriorityFeeValidator,
 };
-use std::{collections::HashMap, net::SocketAddr, sync::Arc};
-use tn_batch_builder::BatchBuilder;
 use tn_batch_validator::BatchValidator;
-use tn_config::Config;
+use tn_config::{Config, ConsensusRegistry, CONSENSUS_REGISTRY_ADDRESS};
 use tn_engine::ExecutorEngine;
 use tn_faucet::{FaucetArgs, FaucetRpcExtApiServer as _};
 use tn_node_traits::{TNExecution, TelcoinNodeTypes};
 use tn_rpc::{TelcoinNetworkRpcExt, TelcoinNetworkRpcExtApiServer};
+use tn_storage::tables::{ConsensusBlockNumbersByDigest, ConsensusBlocks};
 use tn_types::{
-    Address, BatchSender, BatchValidation, BlockBody, ConsensusOutput, EnvKzgSettings, ExecHeader,
-    LastCanonicalUpdate, Noticer, SealedBlock, SealedBlockWithSenders, SealedHeader, TaskManager,
-    WorkerId, B256, MIN_PROTOCOL_BASE_FEE,
+    Address, BatchSender, BatchValidation, BlockBody, BlockNumber, ConsensusOutput, EnvKzgSettings,
+    Epoch, ExecHeader, LastCanonicalUpdate, Noticer, SealedBlock, SealedBlockWithSenders,
+    SealedHeader, SolCall as _, TaskManager, TxKind, WorkerId, B256, MIN_PROTOCOL_BASE_FEE, U256,
 };
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, watch};
 use tokio_stream::wrappers::BroadcastStream;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+
+/// The batch digest stored in an executed block's extra data.
+///
+/// Blocks without a 32-byte digest return the empty digest.
+fn batch_digest(header: &ExecHeader) -> B256 {
+    B256::try_from(header.extra_data.as_ref()).unwrap_or_default()
+}
+
+/// The gas limit for read-only calls to the consensus registry.
+const REGISTRY_CALL_GAS_LIMIT: u64 = 30_000_000;
 
 /// Inner type for holding execution layer types.
 pub(super) struct ExecutionNodeInner<N>
@@ -74,8 +104,22 @@ where
     pub(super) evm_config: N::EvmConfig,
     /// TODO: temporary solution until upstream reth supports public rpc hooks
     pub(super) opt_faucet_args: Option<FaucetArgs>,
+    /// The operator's additional validation for transactions submitted to the worker's pool.
+    pub(super) opt_tx_validation_hook: Option<WorkerTxValidationHook>,
     /// Collection of execution components by worker.
     pub(super) workers: HashMap<WorkerId, WorkerComponents<N>>,
+    /// The number of peers connected to each worker's network.
+    ///
+    /// Batch builders defer building until `min_peers_to_build` peers are connected.
+    pub(super) worker_connected_peers: HashMap<WorkerId, watch::Receiver<usize>>,
+    /// The metrics for each worker's batch builder.
+    ///
+    /// Registered once per worker and reused when the batch builder restarts.
+    pub(super) batch_builder_metrics: HashMap<WorkerId, Arc<BatchBuilderMetrics>>,
+    /// The current epoch.
+    ///
+    /// Workers build batches in this epoch and reject peer batches from other epochs.
+    pub(super) epoch: Epoch,
     // TODO: add Pool to self.workers for direct access (tests)
 }
 
@@ -94,21 +138,35 @@ where
         task_manager: &TaskManager,
         rx_shutdown: Noticer,
     ) -> eyre::Result<()> {
+        // fail fast instead of misrouting block rewards
+        self.verify_beneficiary()?;
+
         let head = self.node_config.lookup_head(&self.provider_factory)?;
 
         // TODO: call hooks?
 
+        // fail fast if the hardfork override can't execute this chain
+        if let Some(hardfork) = self.tn_config.parameters.evm_hardfork {
+            if !hardfork.is_scheduled(&self.blockchain_db.chain_spec()) {
+                eyre::bail!("evm hardfork override {hardfork} is not scheduled in the chain spec");
+            }
+        }
+
         let parent_header = self.blockchain_db.sealed_header(head.number)?.expect("Failed to retrieve sealed header from head's block number while starting executor engine");
 
         // spawn execution engine to extend canonical tip
         let tn_engine = ExecutorEngine::new(
             self.blockchain_db.clone(),
             self.evm_config.clone(),
+            None,
             self.node_config.debug.max_block,
             BroadcastStream::new(from_consensus),
             parent_header,
             rx_shutdown,
-        );
+        )
+        .with_evm_hardfork(self.tn_config.parameters.evm_hardfork)
+        .with_max_blocks_per_finalization(self.tn_config.parameters.max_blocks_per_finalization)
+        .with_timestamp_granularity(self.tn_config.parameters.batch_timestamp_granularity);
 
         // spawn tn engine
         task_manager.spawn_task("consensus engine", async move {
@@ -129,7 +187,7 @@ where
         block_provider_sender: BatchSender,
         task_manager: &TaskManager,
         rx_shutdown: Noticer,
-    ) -> eyre::Result<()> {
+    ) -> Result<(), ExecutionError> {
         let head = self.node_config.lookup_head(&self.provider_factory)?;
 
         // inspired by reth's default eth tx pool:
@@ -138,24 +196,55 @@ where
         // - `pool_builder.build_pool(&ctx)`
         let transaction_pool = {
             let data_dir = self.node_config.datadir();
-            let pool_config = self.node_config.txpool.pool_config();
-            let blob_store = DiskFileBlobStore::open(data_dir.blobstore(), Default::default())?;
+            let mut pool_config = self.node_config.txpool.pool_config();
+            apply_pending_pool_limits(
+                &mut pool_config,
+                self.tn_config.parameters.max_pending_pool_txs,
+                self.tn_config.parameters.pool_eviction_policy,
+            );
+            apply_pending_tx_listener_capacity(
+                &mut pool_config,
+                self.tn_config.parameters.pending_tx_listener_capacity,
+            );
+            let blob_store = DiskFileBlobStore::open(data_dir.blobstore(), Default::default())
+                .map_err(eyre::Report::from)?;
+            let available_cores = match std::thread::available_parallelism() {
+                Ok(cores) => Some(cores.get()),
+                Err(e) => {
+                    warn!(target: "tn::execution", ?e, "failed to read available cpu cores - pool validation tasks are not capped");
+                    None
+                }
+            };
+            let validation_tasks = Self::pool_validation_tasks(
+                self.tn_config.parameters.pool_validation_tasks,
+                self.node_config.txpool.additional_validation_tasks,
+                available_cores,
+            )?;
+            let validator = Self::pool_validator(
+                self.blockchain_db.clone(),
+                head.timestamp,
+                pool_config.local_transactions_config.clone(),
+                validation_tasks,
+                task_manager.get_spawner(),
+                blob_store.clone(),
+            );
+
+            // reject transactions below the minimum priority fee at admission
             let validator =
-                TransactionValidationTaskExecutor::eth_builder(self.blockchain_db.chain_spec())
-                    .with_head_timestamp(head.timestamp)
-                    .kzg_settings(EnvKzgSettings::Default)
-                    .with_local_transactions_config(pool_config.local_transactions_config.clone())
-                    .with_additional_tasks(self.node_config.txpool.additional_validation_tasks)
-                    .build_with_tasks(
-                        self.blockchain_db.clone(),
-                        task_manager.get_spawner(),
-                        blob_store.clone(),
-                    );
-
-            let transaction_pool =
-                reth_transaction_pool::Pool::eth_pool(validator, blob_store, pool_config);
-
-            info!(target: "tn::execution", "Transaction pool initialized");
+                MinPriorityFeeValidator::new(validator, self.tn_config.parameters.min_priority_fee);
+
+            // run the operator's checks before all other validation
+            let validator =
+                HookedTransactionValidator::new(validator, self.opt_tx_validation_hook.clone());
+
+            let transaction_pool = reth_transaction_pool::Pool::new(
+                validator,
+                CoinbaseTipOrdering::default(),
+                blob_store,
+                pool_config,
+            );
+
+            info!(target: "tn::execution", ?validation_tasks, "Transaction pool initialized");
 
             /* TODO: replace this functionality to save and load the txn pool on start/stop
                The reth function backup_local_tranractions_task's shutdown param can not be easily created.
@@ -195,31 +284,12 @@ where
             self.blockchain_db.finalized_block_number()?.unwrap_or_default();
         transaction_pool.set_block_info(tx_pool_latest);
 
-        let tip = match tx_pool_latest.last_seen_block_number {
-            // use genesis on startup
-            0 => SealedBlockWithSenders::new(
-                SealedBlock::new(
-                    self.tn_config.chain_spec().sealed_genesis_header(),
-                    BlockBody::default(),
-                ),
-                vec![],
-            )
-            .ok_or_else(|| eyre!("Failed to create genesis block for starting tx pool"))?,
-            // retrieve from database
-            _ => self
-                .blockchain_db
-                .sealed_block_with_senders(
-                    tx_pool_latest.last_seen_block_hash.into(),
-                    TransactionVariant::NoHash,
-                )?
-                .ok_or_else(|| {
-                    eyre!(
-                        "Failed to find sealed block during block builder startup! ({} - {:?}) ",
-                        tx_pool_latest.last_seen_block_number,
-                        tx_pool_latest.last_seen_block_hash,
-                    )
-                })?,
-        };
+        let tip = Self::pool_tip(
+            &self.blockchain_db,
+            &self.tn_config.chain_spec(),
+            tx_pool_latest.last_seen_block_number,
+            tx_pool_latest.last_seen_block_hash,
+        )?;
 
         let latest_canon_state = LastCanonicalUpdate {
             tip: tip.block,
@@ -227,15 +297,47 @@ where
             pending_block_blob_fee: tx_pool_latest.pending_blob_fee,
         };
 
-        let batch_builder = BatchBuilder::new(
+        let metrics = match self.batch_builder_metrics.entry(worker_id) {
+            Entry::Occupied(entry) => entry.get().clone(),
+            Entry::Vacant(entry) => entry
+                .insert(Arc::new(
+                    BatchBuilderMetrics::try_new(default_registry(), worker_id)
+                        .map_err(eyre::Report::from)?,
+                ))
+                .clone(),
+        };
+
+        let mut batch_builder = BatchBuilder::new(
             self.blockchain_db.clone(),
             transaction_pool.clone(),
             self.blockchain_db.canonical_state_stream(),
             latest_canon_state,
             block_provider_sender,
-            self.address,
+            self.tn_config.parameters.worker_beneficiary(&worker_id, self.address),
             self.tn_config.parameters.max_batch_delay,
-        );
+        )
+        .with_max_concurrent_builds(self.tn_config.parameters.max_concurrent_batch_builds)
+        .with_timestamp_granularity(self.tn_config.parameters.batch_timestamp_granularity)
+        .with_worker_id(worker_id)
+        .with_epoch(self.epoch)
+        .with_included_tx_propagation(self.tn_config.parameters.included_tx_propagation)
+        .with_metrics(metrics);
+        if let Some(max_queued_tx_lifetime) = self.tn_config.parameters.max_queued_tx_lifetime {
+            batch_builder = batch_builder.with_max_queued_tx_lifetime(max_queued_tx_lifetime);
+        }
+        let min_peers_to_build = self.tn_config.parameters.min_peers_to_build;
+        if min_peers_to_build > 0 {
+            match self.worker_connected_peers.get(&worker_id) {
+                Some(connected_peers) => {
+                    batch_builder = batch_builder
+                        .with_min_peers_to_build(min_peers_to_build, connected_peers.clone());
+                }
+                None => {
+                    warn!(target: "tn::execution", ?worker_id, min_peers_to_build, "connected peers not tracked for worker - building batches without waiting for peers");
+                }
+            }
+        }
+        let batch_builder_handle = batch_builder.handle();
 
         // spawn block builder task
         task_manager.spawn_task("batch builder", async move {
@@ -270,7 +372,12 @@ where
 
         // extend TN namespace
         let engine_to_primary = (); // TODO: pass client/server here
-        let tn_ext = TelcoinNetworkRpcExt::new(self.blockchain_db.chain_spec(), engine_to_primary);
+        let tn_ext = TelcoinNetworkRpcExt::new(
+            self.blockchain_db.chain_spec(),
+            engine_to_primary,
+            self.blockchain_db.clone(),
+            transaction_pool.clone(),
+        );
         if let Err(e) = server.merge_configured(tn_ext.into_rpc()) {
             error!(target: "tn::execution", "Error merging TN rpc module: {e:?}");
         }
@@ -299,19 +406,102 @@ where
 
         // start the RPC server
         let server_config = self.node_config.rpc.rpc_server_config();
-        let rpc_handle = server_config.start(&server).await?;
+        let rpc_handle = server_config.start(&server).await.map_err(eyre::Report::from)?;
 
         // take ownership of worker components
-        let components = WorkerComponents::new(rpc_handle, transaction_pool);
+        let components = WorkerComponents::new(rpc_handle, transaction_pool, batch_builder_handle);
         self.workers.insert(worker_id, components);
 
         Ok(())
     }
 
+    /// Return the sealed block the worker's transaction pool starts from.
+    ///
+    /// The genesis block is used if no blocks have been finalized yet. Otherwise, the last
+    /// finalized block is retrieved from the database.
+    fn pool_tip(
+        blockchain_db: &BlockchainProvider<N>,
+        chain_spec: &ChainSpec,
+        finalized_number: BlockNumber,
+        finalized_hash: B256,
+    ) -> Result<SealedBlockWithSenders, ExecutionError> {
+        let tip = match finalized_number {
+            // use genesis on startup
+            0 => SealedBlockWithSenders::new(
+                SealedBlock::new(chain_spec.sealed_genesis_header(), BlockBody::default()),
+                vec![],
+            )
+            .ok_or(ExecutionError::GenesisBlock)?,
+            // retrieve from database
+            _ => blockchain_db
+                .sealed_block_with_senders(finalized_hash.into(), TransactionVariant::NoHash)?
+                .ok_or(ExecutionError::MissingFinalizedBlock {
+                    number: finalized_number,
+                    hash: finalized_hash,
+                })?,
+        };
+
+        Ok(tip)
+    }
+
+    /// Return the number of additional tasks validating transactions for the worker's pool.
+    ///
+    /// The TN config overrides reth's default. Reth always runs its validation service alongside
+    /// the additional tasks, so configured tasks are capped at one less than the available cpu
+    /// cores. The cap is skipped if the available cores are unknown.
+    fn pool_validation_tasks(
+        configured: Option<usize>,
+        reth_default: usize,
+        available_cores: Option<usize>,
+    ) -> Result<usize, ExecutionError> {
+        match (configured, available_cores) {
+            (Some(configured), Some(available)) if configured >= available => {
+                error!(target: "tn::execution", ?configured, ?available, "pool validation tasks exceed available cpu cores");
+                Err(ExecutionError::PoolValidationTasks { configured, available })
+            }
+            (Some(configured), _) => Ok(configured),
+            (None, _) => Ok(reth_default),
+        }
+    }
+
+    /// Build the transaction validator for the worker's pool.
+    ///
+    /// Reth spawns `validation_tasks` blocking tasks in addition to the critical validation
+    /// service.
+    fn pool_validator<S, B>(
+        blockchain_db: BlockchainProvider<N>,
+        head_timestamp: u64,
+        local_transactions_config: LocalTransactionConfig,
+        validation_tasks: usize,
+        spawner: S,
+        blob_store: B,
+    ) -> TransactionValidationTaskExecutor<
+        EthTransactionValidator<BlockchainProvider<N>, EthPooledTransaction>,
+    >
+    where
+        S: TaskSpawner,
+        B: BlobStore,
+    {
+        TransactionValidationTaskExecutor::eth_builder(blockchain_db.chain_spec())
+            .with_head_timestamp(head_timestamp)
+            .kzg_settings(EnvKzgSettings::Default)
+            .with_local_transactions_config(local_transactions_config)
+            .with_additional_tasks(validation_tasks)
+            .build_with_tasks(blockchain_db, spawner, blob_store)
+    }
+
     /// Create a new block validator.
     pub(super) fn new_batch_validator(&self) -> Arc<dyn BatchValidation> {
         // batch validator
-        Arc::new(BatchValidator::<N>::new(self.blockchain_db.clone()))
+        Arc::new(
+            BatchValidator::<N>::new(self.blockchain_db.clone())
+                .with_timestamp_granularity(self.tn_config.parameters.batch_timestamp_granularity)
+                .with_retained_state_depth(self.tn_config.parameters.retained_state_depth)
+                .with_accepted_gas_limits(
+                    self.tn_config.parameters.accepted_batch_gas_limits.iter().copied().collect(),
+                )
+                .with_epoch(self.epoch),
+        )
     }
 
     /// Fetch the last executed state from the database.
@@ -331,6 +521,11 @@ where
         // `finalized_block_number` would point to the last block of round 7. The primary
         // would then re-send consensus output for round 8.
         //
+        // If `max_blocks_per_finalization` is set, large outputs are finalized in chunks and the
+        // finalized block may be part of a partially executed round. The subscriber re-sends the
+        // round's consensus header on startup and execution resumes after the last finalized
+        // batch.
+        //
         // recover finalized block's nonce: this is the last subdag index from consensus (round)
         let finalized_block_num =
             self.blockchain_db.database_provider_ro()?.last_finalized_block_number()?.unwrap_or(0);
@@ -344,6 +539,89 @@ where
         Ok(last_round_of_consensus)
     }
 
+    /// Check that the last finalized round executed every batch from its consensus output.
+    ///
+    /// Each batch in an output is executed as its own block. The block's nonce is the round,
+    /// difficulty is the batch index, and extra data is the batch digest. An empty output executes
+    /// one block with an empty digest.
+    ///
+    /// The finalized block is the last block of the round that was durably executed. The expected
+    /// batch digests are read from the round's consensus header in `consensus_db`. If the blocks
+    /// for the finalized round don't match them, the output was only partially executed before the
+    /// node stopped.
+    ///
+    /// Returns the round that needs to be re-executed, or `None` if the round is complete.
+    pub(super) fn last_output_needs_replay<DB: tn_types::Database>(
+        &self,
+        consensus_db: &DB,
+    ) -> eyre::Result<Option<u64>> {
+        let provider = self.blockchain_db.database_provider_ro()?;
+        let finalized_block_num = provider.last_finalized_block_number()?.unwrap_or(0);
+        // genesis is not executed from consensus output
+        if finalized_block_num == 0 {
+            return Ok(None);
+        }
+
+        let finalized = provider
+            .header_by_number(finalized_block_num)?
+            .ok_or_else(|| eyre::eyre!("missing finalized header {finalized_block_num}"))?;
+        let round: u64 = finalized.nonce.into();
+        let output = finalized.parent_beacon_block_root;
+
+        // the batch digests from the round's consensus output, in execution order
+        let output_digest = output.unwrap_or_default();
+        let consensus_number =
+            consensus_db
+                .get::<ConsensusBlockNumbersByDigest>(&output_digest)?
+                .ok_or_else(|| eyre::eyre!("missing consensus header {output_digest}"))?;
+        let consensus_header = consensus_db
+            .get::<ConsensusBlocks>(&consensus_number)?
+            .ok_or_else(|| eyre::eyre!("missing consensus header {consensus_number}"))?;
+        let expected_batches: Vec<B256> = consensus_header
+            .sub_dag
+            .certificates
+            .iter()
+            .flat_map(|cert| cert.header().payload().keys().copied())
+            .collect();
+
+        // walk back through the round's blocks
+        let mut executed = vec![(finalized.difficulty, batch_digest(&finalized))];
+        for block_num in (1..finalized_block_num).rev() {
+            let header = provider
+                .header_by_number(block_num)?
+                .ok_or_else(|| eyre::eyre!("missing executed header {block_num}"))?;
+            let header_round: u64 = header.nonce.into();
+            if header_round != round || header.parent_beacon_block_root != output {
+                break;
+            }
+            executed.push((header.difficulty, batch_digest(&header)));
+        }
+        executed.reverse();
+
+        let expected: Vec<_> = if expected_batches.is_empty() {
+            vec![(U256::ZERO, B256::ZERO)]
+        } else {
+            expected_batches
+                .iter()
+                .enumerate()
+                .map(|(idx, digest)| (U256::from(idx), *digest))
+                .collect()
+        };
+
+        if executed != expected {
+            warn!(
+                target: "engine",
+                ?round,
+                executed = executed.len(),
+                expected = expected.len(),
+                "last finalized round is missing batches and needs to be re-executed"
+            );
+            return Ok(Some(round));
+        }
+
+        Ok(None)
+    }
+
     /// Return a vector of the last 'number' executed block headers.
     pub(super) fn last_executed_blocks(&self, number: u64) -> eyre::Result<Vec<ExecHeader>> {
         let finalized_block_num =
@@ -412,6 +690,11 @@ where
         self.blockchain_db.clone()
     }
 
+    /// Return a new stream of canonical state updates.
+    pub(super) fn subscribe_canon_state(&self) -> CanonStateNotificationStream {
+        self.blockchain_db.canonical_state_stream()
+    }
+
     /// Return the node's evm-based block executor
     pub(super) fn get_evm_config(&self) -> N::EvmConfig {
         self.evm_config.clone()
@@ -432,6 +715,37 @@ where
         Ok(handle)
     }
 
+    /// Track the number of peers connected to the worker's network.
+    ///
+    /// This must be set before the worker's batch builder starts.
+    pub(super) fn set_worker_connected_peers(
+        &mut self,
+        worker_id: WorkerId,
+        connected_peers: watch::Receiver<usize>,
+    ) {
+        self.worker_connected_peers.insert(worker_id, connected_peers);
+    }
+
+    /// Set the current epoch for batch builders and validators.
+    ///
+    /// This must be set before batch validators are created or batch builders start.
+    pub(super) fn set_epoch(&mut self, epoch: Epoch) {
+        self.epoch = epoch;
+    }
+
+    /// Return the handle for a worker's batch builder.
+    pub(super) fn worker_batch_builder(
+        &self,
+        worker_id: &WorkerId,
+    ) -> eyre::Result<&BatchBuilderHandle> {
+        let handle = self
+            .workers
+            .get(worker_id)
+            .ok_or(ExecutionError::WorkerNotFound(worker_id.to_owned()))?
+            .batch_builder();
+        Ok(handle)
+    }
+
     /// Return a worker's HttpClient if the RpcServer exists.
     pub(super) fn worker_http_client(
         &self,
@@ -455,6 +769,69 @@ where
         Ok(tx_pool)
     }
 
+    /// Verify the execution address matches this validator's entry in the consensus registry.
+    ///
+    /// The execution address is the beneficiary for this validator's batches, so a mismatch
+    /// misroutes block rewards. The check is skipped if the registry is not deployed or this
+    /// node is not an active validator in the registry (ie - observers).
+    pub(super) fn verify_beneficiary(&self) -> Result<(), ExecutionError> {
+        let state = self.blockchain_db.latest()?;
+        if state.account_code(&CONSENSUS_REGISTRY_ADDRESS)?.is_none() {
+            warn!(target: "engine", "consensus registry not deployed - skipping beneficiary verification");
+            return Ok(());
+        }
+
+        // call the registry against the latest state
+        let calldata = ConsensusRegistry::getValidatorsCall {
+            status: ConsensusRegistry::ValidatorStatus::Active,
+        }
+        .abi_encode();
+        let tx = TxEnv {
+            transact_to: TxKind::Call(CONSENSUS_REGISTRY_ADDRESS),
+            data: calldata.into(),
+            gas_limit: REGISTRY_CALL_GAS_LIMIT,
+            ..Default::default()
+        };
+        let cfg = CfgEnvWithHandlerCfg::new_with_spec_id(
+            CfgEnv::default().with_chain_id(self.blockchain_db.chain_spec().chain.id()),
+            SpecId::SHANGHAI,
+        );
+        let env = EnvWithHandlerCfg::new_with_cfg_env(cfg, BlockEnv::default(), tx);
+        let mut evm = self.evm_config.evm_with_env(StateProviderDatabase::new(state), env);
+        let output = match evm.transact() {
+            Ok(ResultAndState { result: ExecutionResult::Success { output, .. }, .. }) => {
+                output.into_data()
+            }
+            Ok(ResultAndState { result, .. }) => {
+                return Err(ExecutionError::ConsensusRegistry(format!("{result:?}")))
+            }
+            Err(e) => return Err(ExecutionError::ConsensusRegistry(e.to_string())),
+        };
+        let validators = ConsensusRegistry::getValidatorsCall::abi_decode_returns(&output, true)
+            .map_err(|e| ExecutionError::ConsensusRegistry(e.to_string()))?
+            ._0;
+
+        // find the entry for this validator's bls key
+        let bls_public_key = self.tn_config.validator_info.public_key();
+        let Some(entry) =
+            validators.iter().find(|v| v.blsPubkey.as_ref() == bls_public_key.as_ref())
+        else {
+            warn!(target: "engine", ?bls_public_key, "validator not active in consensus registry - skipping beneficiary verification");
+            return Ok(());
+        };
+
+        if entry.ecdsaPubkey != self.address {
+            error!(target: "engine", derived=?self.address, registered=?entry.ecdsaPubkey, "execution address does not match consensus registry");
+            return Err(ExecutionError::BeneficiaryMismatch {
+                derived: self.address,
+                registered: entry.ecdsaPubkey,
+            });
+        }
+
+        info!(target: "engine", address=?self.address, "execution address matches consensus registry");
+        Ok(())
+    }
+
     /// Return a worker's local Http address if the RpcServer exists.
     pub(super) fn worker_http_local_address(
         &self,
@@ -464,3 +841,157 @@ where
         Ok(addr)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ExecutionNodeInner;
+    use crate::error::ExecutionError;
+    use assert_matches::assert_matches;
+    use futures::future::BoxFuture;
+    use reth::tasks::TaskSpawner;
+    use reth_blockchain_tree::noop::NoopBlockchainTree;
+    use reth_chainspec::ChainSpec;
+    use reth_db::{
+        test_utils::{create_test_rw_db, tempdir_path, TempDatabase},
+        DatabaseEnv,
+    };
+    use reth_db_common::init::init_genesis;
+    use reth_provider::{
+        providers::{BlockchainProvider, StaticFileProvider},
+        ProviderFactory,
+    };
+    use reth_transaction_pool::{blobstore::InMemoryBlobStore, LocalTransactionConfig};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use tn_node_traits::TelcoinNode;
+    use tn_types::{adiri_genesis, B256};
+    use tokio::task::JoinHandle;
+
+    type TestNode = TelcoinNode<Arc<TempDatabase<DatabaseEnv>>>;
+
+    /// Create a blockchain provider for a temp database initialized with the adiri genesis.
+    fn test_blockchain_db() -> (Arc<ChainSpec>, BlockchainProvider<TestNode>) {
+        let chain: Arc<ChainSpec> = Arc::new(adiri_genesis().into());
+        let provider_factory = ProviderFactory::new(
+            create_test_rw_db(),
+            Arc::clone(&chain),
+            StaticFileProvider::read_write(tempdir_path())
+                .expect("static file provider read write created with tempdir path"),
+        );
+        init_genesis(&provider_factory).expect("init genesis");
+        let blockchain_db =
+            BlockchainProvider::new(provider_factory, Arc::new(NoopBlockchainTree::default()))
+                .expect("test blockchain provider");
+        (chain, blockchain_db)
+    }
+
+    #[test]
+    fn test_pool_tip_missing_finalized_block() {
+        let (chain, blockchain_db) = test_blockchain_db();
+
+        // genesis is used before any blocks are finalized
+        let tip = ExecutionNodeInner::<TestNode>::pool_tip(&blockchain_db, &chain, 0, B256::ZERO)
+            .expect("genesis tip");
+        assert_eq!(tip.block.hash(), chain.genesis_hash());
+
+        // the finalized block is missing from the database
+        let hash = B256::random();
+        let err = ExecutionNodeInner::<TestNode>::pool_tip(&blockchain_db, &chain, 5, hash)
+            .expect_err("finalized block is missing");
+        assert_matches!(
+            err,
+            ExecutionError::MissingFinalizedBlock { number: 5, hash: missing } if missing == hash
+        );
+    }
+
+    /// Count the tasks spawned for the pool's validator without running them.
+    #[derive(Clone, Debug, Default)]
+    struct CountingSpawner {
+        spawned: Arc<AtomicUsize>,
+    }
+
+    impl CountingSpawner {
+        fn record(&self) -> JoinHandle<()> {
+            self.spawned.fetch_add(1, Ordering::SeqCst);
+            tokio::spawn(async {})
+        }
+
+        fn spawned(&self) -> usize {
+            self.spawned.load(Ordering::SeqCst)
+        }
+    }
+
+    impl TaskSpawner for CountingSpawner {
+        fn spawn(&self, _fut: BoxFuture<'static, ()>) -> JoinHandle<()> {
+            self.record()
+        }
+
+        fn spawn_critical(
+            &self,
+            _name: &'static str,
+            _fut: BoxFuture<'static, ()>,
+        ) -> JoinHandle<()> {
+            self.record()
+        }
+
+        fn spawn_blocking(&self, _fut: BoxFuture<'static, ()>) -> JoinHandle<()> {
+            self.record()
+        }
+
+        fn spawn_critical_blocking(
+            &self,
+            _name: &'static str,
+            _fut: BoxFuture<'static, ()>,
+        ) -> JoinHandle<()> {
+            self.record()
+        }
+    }
+
+    #[test]
+    fn test_pool_validation_tasks() {
+        // reth's value is used without an override
+        let tasks = ExecutionNodeInner::<TestNode>::pool_validation_tasks(None, 1, Some(8))
+            .expect("reth default tasks");
+        assert_eq!(tasks, 1);
+
+        // configured tasks leave a core for reth's validation service
+        let tasks = ExecutionNodeInner::<TestNode>::pool_validation_tasks(Some(6), 1, Some(8))
+            .expect("configured tasks");
+        assert_eq!(tasks, 6);
+        let tasks = ExecutionNodeInner::<TestNode>::pool_validation_tasks(Some(7), 1, Some(8))
+            .expect("configured tasks for every core");
+        assert_eq!(tasks, 7);
+
+        // tasks and the validation service exceeding cpu cores is rejected
+        let err = ExecutionNodeInner::<TestNode>::pool_validation_tasks(Some(8), 1, Some(8))
+            .expect_err("tasks exceed cores");
+        assert_matches!(err, ExecutionError::PoolValidationTasks { configured: 8, available: 8 });
+
+        // configured tasks aren't capped if the available cores are unknown
+        let tasks = ExecutionNodeInner::<TestNode>::pool_validation_tasks(Some(16), 1, None)
+            .expect("uncapped tasks");
+        assert_eq!(tasks, 16);
+    }
+
+    #[tokio::test]
+    async fn test_pool_validator_spawns_configured_tasks() {
+        let (chain, blockchain_db) = test_blockchain_db();
+
+        for validation_tasks in [0, 1, 6] {
+            let spawner = CountingSpawner::default();
+            let _validator = ExecutionNodeInner::<TestNode>::pool_validator(
+                blockchain_db.clone(),
+                chain.genesis_header().timestamp,
+                LocalTransactionConfig::default(),
+                validation_tasks,
+                spawner.clone(),
+                InMemoryBlobStore::default(),
+            );
+
+            // the configured tasks run alongside reth's validation service
+            assert_eq!(spawner.spawned(), validation_tasks + 1);
+        }
+    }
+}
@@ -11,6 +11,7 @@
 //! The methods in this module are thread-safe wrappers for the inner type that contains logic.
 
 use self::inner::ExecutionNodeInner;
+use crate::error::ExecutionError;
 use builder::ExecutionNodeBuilder;
 use reth_chainspec::ChainSpec;
 use reth_db::{
@@ -19,16 +20,16 @@ use reth_db::{
 };
 use reth_node_builder::NodeConfig;
 use reth_node_ethereum::{BasicBlockExecutorProvider, EthEvmConfig, EthExecutionStrategyFactory};
-use reth_provider::providers::BlockchainProvider;
+use reth_provider::{providers::BlockchainProvider, CanonStateNotificationStream};
 use std::{net::SocketAddr, sync::Arc};
 use tn_config::Config;
 use tn_faucet::FaucetArgs;
 use tn_node_traits::{TelcoinNode, TelcoinNodeTypes};
 use tn_types::{
-    BatchSender, BatchValidation, ConsensusOutput, ExecHeader, Noticer, SealedHeader, TaskManager,
-    WorkerId, B256,
+    BatchSender, BatchValidation, ConsensusOutput, Epoch, ExecHeader, Noticer, SealedHeader,
+    TaskManager, WorkerId, B256,
 };
-use tokio::sync::{broadcast, RwLock};
+use tokio::sync::{broadcast, watch, RwLock};
 pub use worker::*;
 mod builder;
 mod inner;
@@ -54,6 +55,10 @@ pub struct TnBuilder<DB> {
     ///
     /// The metrics will be served at the given interface and port.
     pub consensus_metrics: Option<SocketAddr>,
+    /// Additional validation for transactions submitted to the worker's pool.
+    ///
+    /// The hook runs before all other checks when a transaction is added to the pool.
+    pub opt_tx_validation_hook: Option<WorkerTxValidationHook>,
 }
 
 /// Wrapper for the inner execution node components.
@@ -93,6 +98,14 @@ where
         guard.start_engine(from_consensus, task_manager, rx_shutdown).await
     }
 
+    /// Verify the execution address matches this validator's entry in the consensus registry.
+    ///
+    /// This check runs before the engine starts.
+    pub async fn verify_beneficiary(&self) -> Result<(), ExecutionError> {
+        let guard = self.internal.read().await;
+        guard.verify_beneficiary()
+    }
+
     /// Batch maker
     pub async fn start_batch_builder(
         &self,
@@ -100,11 +113,59 @@ where
         block_provider_sender: BatchSender,
         task_manager: &TaskManager,
         rx_shutdown: Noticer,
-    ) -> eyre::Result<()> {
+    ) -> Result<(), ExecutionError> {
         let mut guard = self.internal.write().await;
         guard.start_batch_builder(worker_id, block_provider_sender, task_manager, rx_shutdown).await
     }
 
+    /// Set the current epoch.
+    ///
+    /// Workers build batches in this epoch and peer batches from any other epoch are rejected to
+    /// prevent replays. This must be called before [Self::new_batch_validator] and
+    /// [Self::start_batch_builder].
+    pub async fn set_epoch(&self, epoch: Epoch) {
+        let mut guard = self.internal.write().await;
+        guard.set_epoch(epoch);
+    }
+
+    /// Track the number of peers connected to the worker's network.
+    ///
+    /// The worker's batch builder defers building until the configured `min_peers_to_build` are
+    /// connected. This must be called before [Self::start_batch_builder].
+    pub async fn set_worker_connected_peers(
+        &self,
+        worker_id: WorkerId,
+        connected_peers: watch::Receiver<usize>,
+    ) {
+        let mut guard = self.internal.write().await;
+        guard.set_worker_connected_peers(worker_id, connected_peers);
+    }
+
+    /// Pause the worker's batch builder.
+    ///
+    /// The batch builder keeps applying canonical updates to the worker's pool but does not
+    /// build new batches until it is resumed.
+    pub async fn pause_batch_builder(&self, worker_id: &WorkerId) -> eyre::Result<()> {
+        let guard = self.internal.read().await;
+        guard.worker_batch_builder(worker_id)?.pause();
+        Ok(())
+    }
+
+    /// Resume building batches for the worker.
+    pub async fn resume_batch_builder(&self, worker_id: &WorkerId) -> eyre::Result<()> {
+        let guard = self.internal.read().await;
+        guard.worker_batch_builder(worker_id)?.resume();
+        Ok(())
+    }
+
+    /// The block number the worker's next built batch will carry.
+    ///
+    /// This is derived from the latest canonical tip applied by the worker's batch builder.
+    pub async fn next_worker_block_number(&self, worker_id: &WorkerId) -> eyre::Result<u64> {
+        let guard = self.internal.read().await;
+        Ok(guard.worker_batch_builder(worker_id)?.next_block_number())
+    }
+
     /// Batch validator
     pub async fn new_batch_validator(&self) -> Arc<dyn BatchValidation> {
         let guard = self.internal.read().await;
@@ -117,6 +178,18 @@ where
         guard.last_executed_output()
     }
 
+    /// Check that the last finalized round executed every batch from its consensus output.
+    ///
+    /// The expected batches are read from the round's consensus header in `consensus_db`. Returns
+    /// the round that needs to be re-executed if any batches are missing.
+    pub async fn last_output_needs_replay<DB: tn_types::Database>(
+        &self,
+        consensus_db: &DB,
+    ) -> eyre::Result<Option<u64>> {
+        let guard = self.internal.read().await;
+        guard.last_output_needs_replay(consensus_db)
+    }
+
     /// Return a vector of the last 'number' executed block headers.
     pub async fn last_executed_blocks(&self, number: u64) -> eyre::Result<Vec<ExecHeader>> {
         let guard = self.internal.read().await;
@@ -140,6 +213,15 @@ where
         guard.get_provider()
     }
 
+    /// Subscribe to canonical state updates from the engine.
+    ///
+    /// The stream yields a notification every time the engine extends or reorgs the canonical
+    /// chain. This is the same stream the batch builder uses to update the worker's pool.
+    pub async fn subscribe_canon_state(&self) -> CanonStateNotificationStream {
+        let guard = self.internal.read().await;
+        guard.subscribe_canon_state()
+    }
+
     /// Return the node's EVM config.
     /// Used for tests.
     // pub async fn get_evm_config(&self) -> N::EvmConfig {
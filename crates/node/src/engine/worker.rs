@@ -19,14 +19,37 @@ use reth_network_api::{
 use reth_network_peers::{NodeRecord, PeerId};
 use reth_node_builder::NodeTypesWithDB;
 use reth_provider::providers::BlockchainProvider;
-use reth_transaction_pool::{blobstore::DiskFileBlobStore, EthTransactionPool};
+use reth_transaction_pool::{
+    blobstore::DiskFileBlobStore, CoinbaseTipOrdering, EthPooledTransaction,
+    EthTransactionValidator, Pool, TransactionValidationTaskExecutor,
+};
 use std::{
     net::{IpAddr, SocketAddr},
     sync::Arc,
 };
+use tn_batch_builder::{
+    BatchBuilderHandle, HookedTransactionValidator, MinPriorityFeeValidator,
+    TransactionValidationHook,
+};
 
 /// The explicit type for the worker's transaction pool.
-pub type WorkerTxPool<DB> = EthTransactionPool<BlockchainProvider<DB>, DiskFileBlobStore>;
+///
+/// The pool is an eth pool with transactions below the minimum priority fee or rejected by the
+/// operator's [WorkerTxValidationHook] rejected at admission.
+pub type WorkerTxPool<DB> = Pool<
+    HookedTransactionValidator<
+        MinPriorityFeeValidator<
+            TransactionValidationTaskExecutor<
+                EthTransactionValidator<BlockchainProvider<DB>, EthPooledTransaction>,
+            >,
+        >,
+    >,
+    CoinbaseTipOrdering<EthPooledTransaction>,
+    DiskFileBlobStore,
+>;
+
+/// The operator's additional validation for transactions submitted to the worker's pool.
+pub type WorkerTxValidationHook = Arc<dyn TransactionValidationHook<EthPooledTransaction>>;
 
 /// Execution components on a per-worker basis.
 pub(super) struct WorkerComponents<DB>
@@ -37,6 +60,8 @@ where
     rpc_handle: RpcServerHandle,
     /// The worker's transaction pool.
     pool: WorkerTxPool<DB>,
+    /// Handle to pause and resume the worker's batch builder.
+    batch_builder: BatchBuilderHandle,
 }
 
 impl<DB> WorkerComponents<DB>
@@ -44,8 +69,12 @@ where
     DB: NodeTypesWithDB,
 {
     /// Create a new instance of [Self].
-    pub fn new(rpc_handle: RpcServerHandle, pool: WorkerTxPool<DB>) -> Self {
-        Self { rpc_handle, pool }
+    pub fn new(
+        rpc_handle: RpcServerHandle,
+        pool: WorkerTxPool<DB>,
+        batch_builder: BatchBuilderHandle,
+    ) -> Self {
+        Self { rpc_handle, pool, batch_builder }
     }
 
     /// Return a reference to the rpc handle
@@ -57,6 +86,11 @@ where
     pub fn pool(&self) -> WorkerTxPool<DB> {
         self.pool.clone()
     }
+
+    /// Return a reference to the handle for the worker's batch builder.
+    pub fn batch_builder(&self) -> &BatchBuilderHandle {
+        &self.batch_builder
+    }
 }
 
 /// A type that implements all network trait that does nothing.
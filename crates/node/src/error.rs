@@ -5,7 +5,7 @@ use reth_beacon_consensus::BeaconForkChoiceUpdateError;
 use reth_provider::ProviderError;
 use thiserror::Error;
 use tn_executor::SubscriberError;
-use tn_types::WorkerId;
+use tn_types::{Address, BlockNumber, WorkerId, B256};
 
 #[derive(Debug, Error)]
 pub enum NodeError {
@@ -42,4 +42,42 @@ pub enum ExecutionError {
     /// Worker id is not included in the execution node's known worker hashmap.
     #[error("Worker not found: {0:?}")]
     WorkerNotFound(WorkerId),
+
+    /// The genesis block could not be sealed with senders to start the worker's tx pool.
+    #[error("Failed to create genesis block for starting tx pool")]
+    GenesisBlock,
+
+    /// The last finalized block is missing from the database when the batch builder starts.
+    #[error("Failed to find finalized block during batch builder startup! ({number} - {hash:?})")]
+    MissingFinalizedBlock {
+        /// The number of the last finalized block.
+        number: BlockNumber,
+        /// The hash of the last finalized block.
+        hash: B256,
+    },
+
+    /// The execution address doesn't match this validator's entry in the consensus registry.
+    #[error("Execution address {derived} does not match registered address {registered}")]
+    BeneficiaryMismatch {
+        /// The execution address used as the beneficiary for this validator's batches.
+        derived: Address,
+        /// The `ecdsaPubkey` for this validator in the consensus registry.
+        registered: Address,
+    },
+
+    /// The consensus registry could not be read.
+    #[error("Failed to read consensus registry: {0}")]
+    ConsensusRegistry(String),
+
+    /// The configured pool validation tasks and reth's validation service exceed the available
+    /// cpu cores.
+    #[error(
+        "Pool validation tasks ({configured}) must be below available cpu cores ({available})"
+    )]
+    PoolValidationTasks {
+        /// The configured number of pool validation tasks.
+        configured: usize,
+        /// The number of cpu cores available to the node.
+        available: usize,
+    },
 }
@@ -26,15 +26,22 @@ use tn_primary::{
     network::{PrimaryNetwork, PrimaryNetworkHandle},
     ConsensusBus, NodeMode, StateSynchronizer,
 };
-use tn_storage::{open_db, tables::ConsensusBlocks, DatabaseType};
-use tn_types::{BatchValidation, ConsensusHeader, Database as TNDatabase, Multiaddr, TaskManager};
+use tn_storage::{
+    open_db, tables::ConsensusBlocks, DatabaseType, PreloadTables as _, StoreSizeMonitor,
+};
+use tn_types::{
+    BatchValidation, ConsensusHeader, Database as TNDatabase, Multiaddr, ShutdownStage, TaskManager,
+};
 use tn_worker::{WorkerNetwork, WorkerNetworkHandle};
-use tokio::{runtime::Builder, sync::mpsc};
+use tokio::{
+    runtime::Builder,
+    sync::{mpsc, watch},
+};
 use tracing::{info, instrument, warn};
 
 pub mod dirs;
 pub mod engine;
-mod error;
+pub mod error;
 pub mod primary;
 pub mod worker;
 
@@ -80,14 +87,16 @@ fn dial_worker(
 
 /// Start up the primary and worker libp2p networks and return handles to use it.
 /// This will also dial initial peers and the networks should be ready to use once it resolves.
+///
+/// The returned watch channel tracks the number of peers connected to the worker's network.
 async fn start_networks<DB: TNDatabase>(
     consensus_config: &ConsensusConfig<DB>,
     consensus_bus: &ConsensusBus,
-    task_manager: &TaskManager,
+    primary_task_manager: &TaskManager,
     worker_id: &u16,
     validator: Arc<dyn BatchValidation>,
     state_sync: StateSynchronizer<DB>,
-) -> eyre::Result<(PrimaryNetworkHandle, WorkerNetworkHandle)> {
+) -> eyre::Result<(PrimaryNetworkHandle, WorkerNetworkHandle, watch::Receiver<usize>)> {
     let (event_stream, rx_event_stream) = mpsc::channel(1000);
     let (worker_event_stream, rx_worker_event_stream) = mpsc::channel(1000);
     let primary_network = ConsensusNetwork::new_for_primary(consensus_config, event_stream)
@@ -96,28 +105,38 @@ async fn start_networks<DB: TNDatabase>(
         .expect("worker p2p network create failed!");
     let primary_network_handle = primary_network.network_handle();
     let worker_network_handle = worker_network.network_handle();
-    let rx_shutdown = consensus_config.shutdown().subscribe();
-    task_manager.spawn_task("primary network run loop", async move {
-        tokio::select!(
-            _ = &rx_shutdown => {
-                Ok(())
-            }
-            res = primary_network.run() => {
-                res
-            }
-        )
-    });
-    let rx_shutdown = consensus_config.shutdown().subscribe();
-    task_manager.spawn_task("worker network run loop", async move {
-        tokio::select!(
-            _ = &rx_shutdown => {
-                Ok(())
-            }
-            res = worker_network.run() => {
-               res
-            }
-        )
-    });
+    // The primary network is stopped after the proposer and before the primary stores are flushed.
+    let rx_shutdown = primary_task_manager.stage_shutdown(ShutdownStage::Network);
+    primary_task_manager.spawn_ordered_task(
+        ShutdownStage::Network,
+        "primary network run loop",
+        async move {
+            tokio::select!(
+                _ = &rx_shutdown => {
+                    Ok(())
+                }
+                res = primary_network.run() => {
+                    res
+                }
+            )
+        },
+    );
+    // The worker network also writes to node storage so it stops with the primary network.
+    let rx_shutdown = primary_task_manager.stage_shutdown(ShutdownStage::Network);
+    primary_task_manager.spawn_ordered_task(
+        ShutdownStage::Network,
+        "worker network run loop",
+        async move {
+            tokio::select!(
+                _ = &rx_shutdown => {
+                    Ok(())
+                }
+                res = worker_network.run() => {
+                   res
+                }
+            )
+        },
+    );
     primary_network_handle.subscribe(IdentTopic::new("tn-primary")).await?;
     let my_authority = consensus_config.authority();
 
@@ -160,19 +179,20 @@ async fn start_networks<DB: TNDatabase>(
         consensus_bus.clone(),
         state_sync,
     );
-    primary_network.spawn(task_manager);
+    primary_network.spawn(primary_task_manager);
 
     // Receive incoming messages from other workers.
-    WorkerNetwork::new(
+    let worker_network = WorkerNetwork::new(
         rx_worker_event_stream,
         worker_network_handle.clone(),
         consensus_config.clone(),
         *worker_id,
         validator,
-    )
-    .spawn(task_manager);
+    );
+    let worker_connected_peers = worker_network.subscribe_connected_peers();
+    worker_network.spawn(primary_task_manager);
 
-    Ok((primary_network_handle, worker_network_handle))
+    Ok((primary_network_handle, worker_network_handle, worker_connected_peers))
 }
 
 /// Inner working of launch_node().
@@ -212,8 +232,8 @@ where
         let config = builder.tn_config.clone();
         let mut task_manager = TaskManager::new("Task Manager");
         let mut engine_task_manager = TaskManager::new("Engine Task Manager");
+        let mut primary_task_manager = TaskManager::new("Primary Task Manager");
         let engine = ExecutionNode::<TelcoinNode<DB>>::new(builder, &engine_task_manager)?;
-        let validator = engine.new_batch_validator().await;
 
         info!(target: "telcoin::node", "execution engine created");
 
@@ -222,14 +242,18 @@ where
         let key_config = KeyConfig::read_config(tn_datadir)?;
         let consensus_config = ConsensusConfig::new(config, tn_datadir, node_storage, key_config)?;
 
+        // batches are only valid in the committee's epoch
+        engine.set_epoch(consensus_config.committee().epoch()).await;
+        let validator = engine.new_batch_validator().await;
+
         let (worker_id, _worker_info) = consensus_config.config().workers().first_worker()?;
         let worker = WorkerNode::new(*worker_id, consensus_config.clone());
         let consensus_bus =
                     ConsensusBus::new_with_args(consensus_config.config().parameters.gc_depth);
         let state_sync = StateSynchronizer::new(consensus_config.clone(), consensus_bus.clone());
 
-        let (primary_network_handle, worker_network_handle) =
-            start_networks(&consensus_config, &consensus_bus, &task_manager, worker_id, validator.clone(), state_sync.clone()).await?;
+        let (primary_network_handle, worker_network_handle, worker_connected_peers) =
+            start_networks(&consensus_config, &consensus_bus, &primary_task_manager, worker_id, validator.clone(), state_sync.clone()).await?;
 
         let primary = PrimaryNode::new(
                 consensus_config.clone(),
@@ -248,6 +272,19 @@ where
                 .send_modify(|blocks| blocks.push_latest(recent_block));
         }
 
+        // Check the last finalized round executed every batch from its consensus output.
+        // A partially executed round is re-sent by the subscriber and resumes after the last
+        // finalized batch.
+        match engine.last_output_needs_replay(&db).await {
+            Ok(Some(round)) => {
+                warn!(target: "telcoin::node", ?round, "last finalized round was partially executed, resuming its consensus output");
+            }
+            Ok(None) => {}
+            Err(e) => {
+                warn!(target: "telcoin::node", ?e, "failed to check the last finalized round for missing batches");
+            }
+        }
+
         // Prime the last consensus header from the DB.
         let (_, last_db_block) = db
             .last_record::<ConsensusBlocks>()
@@ -293,7 +330,7 @@ where
         let consensus_output_rx = consensus_bus.subscribe_consensus_output();
 
         // start the primary
-        let mut primary_task_manager = primary.start().await?;
+        primary.start(&primary_task_manager).await?;
 
         // start the worker
         let batch_provider = worker.start(validator, worker_network_handle).await?;
@@ -307,6 +344,7 @@ where
             )
             .await?;
         // spawn block maker for worker
+        engine.set_worker_connected_peers(*worker_id, worker_connected_peers).await;
         engine
             .start_batch_builder(
                 *worker_id,
@@ -358,6 +396,21 @@ where
     let _ = std::fs::create_dir_all(&consensus_db_path);
     let db = open_db(&consensus_db_path);
 
+    // warm up hot tables to avoid latency spikes during the first rounds
+    let preload: Vec<&str> =
+        builder.tn_config.parameters.preload_tables.iter().map(String::as_str).collect();
+    if !preload.is_empty() {
+        let rows = db.preload_tables(&preload)?;
+        tracing::info!(target: "telcoin::node", ?preload, ?rows, "preloaded node storage");
+    }
+
+    // record the size of the store for operators - the monitor stops when dropped
+    let _store_size_monitor = builder
+        .tn_config
+        .parameters
+        .store_size_interval
+        .map(|interval| StoreSizeMonitor::spawn(&consensus_db_path, interval));
+
     let mut running = true;
     while running {
         running = launch_node_inner(&builder, &tn_datadir, db.clone())?;
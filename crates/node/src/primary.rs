@@ -8,7 +8,9 @@ use tn_primary::{
     ConsensusBus, Primary, StateSynchronizer,
 };
 use tn_primary_metrics::Metrics;
-use tn_types::{Database as ConsensusDatabase, TaskManager, DEFAULT_BAD_NODES_STAKE_THRESHOLD};
+use tn_types::{
+    Database as ConsensusDatabase, ShutdownStage, TaskManager, DEFAULT_BAD_NODES_STAKE_THRESHOLD,
+};
 use tokio::sync::RwLock;
 use tracing::instrument;
 
@@ -30,12 +32,14 @@ impl<CDB: ConsensusDatabase> PrimaryNodeInner<CDB> {
     /// Starts the primary node with the provided info. If the node is already running then this
     /// method will return an error instead.
     #[instrument(name = "primary_node", skip_all)]
-    async fn start(&mut self) -> eyre::Result<TaskManager> {
-        let task_manager = TaskManager::new("Primary Task Manager");
+    ///
+    /// The primary's tasks are spawned on `task_manager` so they are shutdown in order with the
+    /// primary network.
+    async fn start(&mut self, task_manager: &TaskManager) -> eyre::Result<()> {
         // spawn primary and update `self`
-        self.spawn_primary(&task_manager).await?;
+        self.spawn_primary(task_manager).await?;
 
-        Ok(task_manager)
+        Ok(())
     }
 
     /// Spawn a new primary. Optionally also spawn the consensus and a client executing
@@ -64,7 +68,8 @@ impl<CDB: ConsensusDatabase> PrimaryNodeInner<CDB> {
             self.consensus_config.committee().clone(),
             self.consensus_config.node_storage().clone(),
             DEFAULT_BAD_NODES_STAKE_THRESHOLD,
-        );
+        )
+        .with_leader_weights(&self.consensus_config.parameters().leader_weights);
 
         // Spawn the consensus core who only sequences transactions.
         let ordering_engine = Bullshark::new(
@@ -86,7 +91,7 @@ impl<CDB: ConsensusDatabase> PrimaryNodeInner<CDB> {
         // subscriber handler if it missed some transactions.
         Executor::spawn(
             self.consensus_config.clone(),
-            self.consensus_config.shutdown().subscribe(),
+            task_manager.stage_shutdown(ShutdownStage::Network),
             consensus_bus.clone(),
             task_manager,
             self.primary.network_handle().clone(),
@@ -115,12 +120,12 @@ impl<CDB: ConsensusDatabase> PrimaryNode<CDB> {
         Self { internal: Arc::new(RwLock::new(inner)) }
     }
 
-    pub async fn start(&self) -> eyre::Result<TaskManager>
+    pub async fn start(&self, task_manager: &TaskManager) -> eyre::Result<()>
     where
         CDB: ConsensusDatabase,
     {
         let mut guard = self.internal.write().await;
-        guard.start().await
+        guard.start(task_manager).await
     }
 
     pub async fn shutdown(&self) {
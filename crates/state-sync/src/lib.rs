@@ -168,8 +168,41 @@ pub fn last_executed_consensus_block<DB: Database>(
     }
 }
 
+/// Returns the number of the first consensus header that was not fully executed.
+///
+/// Large outputs are executed and finalized in chunks. If the node stopped between chunks, the
+/// latest executed block belongs to the last executed consensus header but its batch index
+/// (difficulty) is before the header's last batch. That header is executed again and the engine
+/// resumes after the last finalized batch.
+fn first_unexecuted_consensus_number(
+    consensus_bus: &ConsensusBus,
+    last_executed_block: &ConsensusHeader,
+) -> u64 {
+    let executed_batches = consensus_bus
+        .recent_blocks()
+        .borrow()
+        .latest_block()
+        .header()
+        .difficulty
+        .saturating_to::<usize>()
+        + 1;
+    if executed_batches < last_executed_block.sub_dag.num_primary_blocks() {
+        info!(
+            target: "telcoin::state-sync",
+            number = last_executed_block.number,
+            executed_batches,
+            "last executed consensus header was partially executed"
+        );
+        last_executed_block.number
+    } else {
+        last_executed_block.number + 1
+    }
+}
+
 /// Send any consensus headers that were not executed before last shutdown to the consensus header
 /// channel.
+///
+/// This includes the last executed consensus header if it was only partially executed.
 pub async fn stream_missing_consensus<DB: Database>(
     config: &ConsensusConfig<DB>,
     consensus_bus: &ConsensusBus,
@@ -183,11 +216,10 @@ pub async fn stream_missing_consensus<DB: Database>(
     let (_, last_db_block) = db
         .last_record::<ConsensusBlocks>()
         .unwrap_or_else(|| (last_executed_block.number, last_executed_block.clone()));
-    if last_db_block.number > last_executed_block.number {
-        for consensus_block_number in last_executed_block.number + 1..=last_db_block.number {
-            if let Some(consensus_header) = db.get::<ConsensusBlocks>(&consensus_block_number)? {
-                consensus_bus.consensus_header().send(consensus_header).await?;
-            }
+    let first_missing = first_unexecuted_consensus_number(consensus_bus, &last_executed_block);
+    for consensus_block_number in first_missing..=last_db_block.number {
+        if let Some(consensus_header) = db.get::<ConsensusBlocks>(&consensus_block_number)? {
+            consensus_bus.consensus_header().send(consensus_header).await?;
         }
     }
     Ok(())
@@ -195,6 +227,8 @@ pub async fn stream_missing_consensus<DB: Database>(
 
 /// Collect and return any consensus headers that were not executed before last shutdown to the
 /// consensus header channel.
+///
+/// This includes the last executed consensus header if it was only partially executed.
 pub async fn get_missing_consensus<DB: Database>(
     config: &ConsensusConfig<DB>,
     consensus_bus: &ConsensusBus,
@@ -209,11 +243,10 @@ pub async fn get_missing_consensus<DB: Database>(
     let (_, last_db_block) = db
         .last_record::<ConsensusBlocks>()
         .unwrap_or_else(|| (last_executed_block.number, last_executed_block.clone()));
-    if last_db_block.number > last_executed_block.number {
-        for consensus_block_number in last_executed_block.number + 1..=last_db_block.number {
-            if let Some(consensus_header) = db.get::<ConsensusBlocks>(&consensus_block_number)? {
-                result.push(consensus_header);
-            }
+    let first_missing = first_unexecuted_consensus_number(consensus_bus, &last_executed_block);
+    for consensus_block_number in first_missing..=last_db_block.number {
+        if let Some(consensus_header) = db.get::<ConsensusBlocks>(&consensus_block_number)? {
+            result.push(consensus_header);
         }
     }
     Ok(result)
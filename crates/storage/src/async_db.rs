@@ -0,0 +1,97 @@
+//! Async adapter for DB operations that can block long enough to stall an executor.
+
+use tn_types::{Database, Table};
+
+/// Wraps a [`Database`] and runs the heavy operations (full table iteration, multi gets and
+/// clearing tables) on tokio's blocking pool so callers in async tasks don't stall the runtime.
+/// Use [`AsyncDatabase::inner`] for cheap single key operations.
+#[derive(Clone, Debug)]
+pub struct AsyncDatabase<DB> {
+    db: DB,
+}
+
+impl<DB: Database> AsyncDatabase<DB> {
+    /// Create a new adapter for db.
+    pub fn new(db: DB) -> Self {
+        Self { db }
+    }
+
+    /// The wrapped DB.
+    pub fn inner(&self) -> &DB {
+        &self.db
+    }
+
+    /// Collect all the records in table T in key order.
+    pub async fn iter<T: Table>(&self) -> eyre::Result<Vec<(T::Key, T::Value)>> {
+        let db = self.db.clone();
+        Ok(tokio::task::spawn_blocking(move || db.iter::<T>().collect()).await?)
+    }
+
+    /// Returns the values for keys in the same order, see [`Database::multi_get`].
+    pub async fn multi_get<T: Table>(
+        &self,
+        keys: Vec<T::Key>,
+    ) -> eyre::Result<Vec<Option<T::Value>>> {
+        let db = self.db.clone();
+        tokio::task::spawn_blocking(move || db.multi_get::<T>(keys.iter())).await?
+    }
+
+    /// Remove all the records from table T.
+    pub async fn clear_table<T: Table>(&self) -> eyre::Result<()> {
+        let db = self.db.clone();
+        tokio::task::spawn_blocking(move || db.clear_table::<T>()).await?
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AsyncDatabase;
+    use crate::{mem_db::MemDatabase, test::TestTable};
+    use std::sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    };
+    use tn_types::{Database, DbTxMut};
+
+    #[tokio::test]
+    async fn test_async_iter_does_not_block_runtime() {
+        let db = MemDatabase::default();
+        db.open_table::<TestTable>();
+        let mut txn = db.write_txn().unwrap();
+        for key in 0..200_000u64 {
+            txn.insert::<TestTable>(&key, &key.to_string()).expect("Failed to insert");
+        }
+        txn.commit().unwrap();
+        let db = AsyncDatabase::new(db);
+
+        // count the ticks another task gets on this single threaded runtime while iter runs
+        let in_flight = Arc::new(AtomicBool::new(false));
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let ticker = {
+            let in_flight = in_flight.clone();
+            let ticks = ticks.clone();
+            tokio::spawn(async move {
+                loop {
+                    if in_flight.load(Ordering::Relaxed) {
+                        ticks.fetch_add(1, Ordering::Relaxed);
+                    }
+                    tokio::task::yield_now().await;
+                }
+            })
+        };
+
+        in_flight.store(true, Ordering::Relaxed);
+        let records = db.iter::<TestTable>().await.expect("iter");
+        in_flight.store(false, Ordering::Relaxed);
+        ticker.abort();
+
+        assert_eq!(records.len(), 200_000);
+        assert_eq!(records.first(), Some(&(0, "0".to_string())));
+        assert!(ticks.load(Ordering::Relaxed) > 0, "iter blocked the runtime");
+
+        let values = db.multi_get::<TestTable>(vec![1, 200_001]).await.expect("multi get");
+        assert_eq!(values, vec![Some("1".to_string()), None]);
+        db.clear_table::<TestTable>().await.expect("clear table");
+        assert!(db.inner().is_empty::<TestTable>());
+    }
+}
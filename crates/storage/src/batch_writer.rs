@@ -0,0 +1,132 @@
+//! Helper for large imports that can not fit in a single write txn.
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use tn_types::{encode, encode_key, Database, DbTxMut, Table};
+
+/// Accumulate inserts in a write txn and commit it every max_ops inserts or once max_bytes
+/// of keys and values have been written, whichever comes first.
+/// Call [`BatchWriter::flush`] when done, any pending writes are discarded if this is dropped
+/// without flushing.
+pub struct BatchWriter<'a, DB: Database> {
+    db: &'a DB,
+    txn: Option<DB::TXMut<'a>>,
+    max_ops: usize,
+    max_bytes: usize,
+    ops: usize,
+    bytes: usize,
+    commits: Arc<AtomicUsize>,
+}
+
+impl<'a, DB: Database> BatchWriter<'a, DB> {
+    /// Create a new writer for db that will commit every max_ops inserts or max_bytes.
+    pub fn new(db: &'a DB, max_ops: usize, max_bytes: usize) -> Self {
+        Self {
+            db,
+            txn: None,
+            max_ops,
+            max_bytes,
+            ops: 0,
+            bytes: 0,
+            commits: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Use the provided counter to track the number of commits.
+    pub fn with_commit_counter(mut self, commits: Arc<AtomicUsize>) -> Self {
+        self.commits = commits;
+        self
+    }
+
+    /// Number of commits this writer has done.
+    pub fn commits(&self) -> usize {
+        self.commits.load(Ordering::Relaxed)
+    }
+
+    /// Insert key and value into table T, commits if a threshold is reached.
+    pub fn insert<T: Table>(&mut self, key: &T::Key, value: &T::Value) -> eyre::Result<()> {
+        self.insert_raw::<T>(&encode_key(key), &encode(value))
+    }
+
+    /// Insert already encoded key and value bytes into table T, commits if a threshold is
+    /// reached.
+    pub fn insert_raw<T: Table>(&mut self, key: &[u8], value: &[u8]) -> eyre::Result<()> {
+        if self.txn.is_none() {
+            self.txn = Some(self.db.write_txn()?);
+        }
+        if let Some(txn) = &mut self.txn {
+            txn.insert_raw::<T>(key, value)?;
+        }
+        self.ops += 1;
+        self.bytes += key.len() + value.len();
+        if self.ops >= self.max_ops || self.bytes >= self.max_bytes {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Commit any pending inserts.
+    pub fn flush(&mut self) -> eyre::Result<()> {
+        if let Some(txn) = self.txn.take() {
+            txn.commit()?;
+            self.commits.fetch_add(1, Ordering::Relaxed);
+        }
+        self.ops = 0;
+        self.bytes = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BatchWriter;
+    use crate::{redb::ReDB, test::TestTable};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use tempfile::tempdir;
+    use tn_types::Database;
+
+    #[test]
+    fn test_batch_writer_auto_commit() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = ReDB::open(temp_dir.path()).expect("Cannot open database");
+        db.open_table::<TestTable>().expect("failed to open table!");
+
+        let max = 100_000;
+        let commits = Arc::new(AtomicUsize::new(0));
+        let mut writer =
+            BatchWriter::new(&db, 5_000, usize::MAX).with_commit_counter(commits.clone());
+        for (key, value) in (0..max).map(|i| (i, i.to_string())) {
+            writer.insert::<TestTable>(&key, &value).expect("Failed to insert");
+        }
+        writer.flush().expect("Failed to flush");
+        // Nothing pending so flushing again should not commit.
+        writer.flush().expect("Failed to flush");
+        assert_eq!(commits.load(Ordering::Relaxed), (max / 5_000) as usize);
+        assert_eq!(writer.commits(), (max / 5_000) as usize);
+
+        for (key, value) in (0..max).map(|i| (i, i.to_string())) {
+            assert_eq!(db.get::<TestTable>(&key).expect("Failed to get"), Some(value));
+        }
+    }
+
+    #[test]
+    fn test_batch_writer_byte_limit() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = ReDB::open(temp_dir.path()).expect("Cannot open database");
+        db.open_table::<TestTable>().expect("failed to open table!");
+
+        let mut writer = BatchWriter::new(&db, usize::MAX, 1_024);
+        for (key, value) in (0..1_000).map(|i| (i, i.to_string())) {
+            writer.insert::<TestTable>(&key, &value).expect("Failed to insert");
+        }
+        writer.flush().expect("Failed to flush");
+        assert!(writer.commits() > 1);
+        assert_eq!(db.iter::<TestTable>().count(), 1_000);
+    }
+}
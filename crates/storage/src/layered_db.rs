@@ -9,8 +9,8 @@ use std::{
     time::{Duration, Instant},
 };
 
-use crate::mem_db::MemDatabase;
-use tn_types::{DBIter, Database, DbTx, DbTxMut, Table};
+use crate::mem_db::{MemDatabase, MemDbSnapshot};
+use tn_types::{DBIter, DBIterRaw, Database, DbTx, DbTxMut, Durability, Table};
 
 #[derive(Clone, Debug)]
 pub struct LayeredDbTx {
@@ -49,6 +49,17 @@ impl<DB: Database> DbTxMut for LayeredDbTxMut<DB> {
         Ok(())
     }
 
+    fn insert_raw<T: Table>(&mut self, key: &[u8], value: &[u8]) -> eyre::Result<()> {
+        self.mem_db.write_txn()?.insert_raw::<T>(key, value)?;
+        let ins = Box::new(KeyValueInsertRaw::<T> {
+            key: key.to_vec(),
+            value: value.to_vec(),
+            _casper: PhantomData,
+        });
+        self.tx.send(DBMessage::Insert(ins)).map_err(|_| eyre::eyre!("DB thread gone, FATAL!"))?;
+        Ok(())
+    }
+
     fn remove<T: Table>(&mut self, key: &T::Key) -> eyre::Result<()> {
         self.mem_db.remove::<T>(key)?;
         let rm = Box::new(KeyRemove::<T> { key: key.clone() });
@@ -79,11 +90,15 @@ fn db_run<DB: Database>(db: DB, rx: Receiver<DBMessage<DB>>) {
     }
     while let Ok(msg) = rx.recv() {
         match msg {
-            DBMessage::StartTxn => {
+            DBMessage::StartTxn(durability) => {
                 if let Some((_txn, count)) = &mut txn {
                     *count += 1;
                 } else {
-                    match db.write_txn() {
+                    let ntxn = match durability {
+                        Some(durability) => db.write_txn_with_durability(durability),
+                        None => db.write_txn(),
+                    };
+                    match ntxn {
                         Ok(ntxn) => txn = Some((ntxn, 1)),
                         Err(e) => tracing::error!("DB ERROR getting write txn (background): {e}"),
                     }
@@ -127,6 +142,13 @@ fn db_run<DB: Database>(db: DB, rx: Receiver<DBMessage<DB>>) {
                     tracing::error!("DB Clear: {e}")
                 }
             }
+            DBMessage::Flush(ack) => {
+                // Writes in an open transaction are persisted when it commits.
+                if let Err(e) = db.flush() {
+                    tracing::error!("DB Flush: {e}")
+                }
+                let _ = ack.send(());
+            }
             DBMessage::Shutdown => break,
         }
         // if it has been 24 hours since last compaction then do it again.
@@ -183,11 +205,17 @@ impl<DB: Database> LayeredDatabase<DB> {
         Self { mem_db: MemDatabase::new(), db, tx, thread }
     }
 
+    /// Open the table in memory and load every row from the persistant DB.
+    ///
+    /// Rows are loaded as the stored bytes without decoding, so a corrupt row does not panic here
+    /// and can still be reported by [`Database::verify_table`].
     pub fn open_table<T: Table>(&self) {
         self.mem_db.open_table::<T>();
-        for (key, value) in self.db.iter::<T>() {
-            // mem db insert should not fail.
-            let _ = self.mem_db.insert::<T>(&key, &value);
+        // mem db write txns and inserts should not fail.
+        if let Ok(mut txn) = self.mem_db.write_txn() {
+            for (key, value) in self.db.iter_raw::<T>() {
+                let _ = txn.insert_raw::<T>(&key, &value);
+            }
         }
     }
 }
@@ -203,6 +231,11 @@ impl<DB: Database> Database for LayeredDatabase<DB> {
     where
         Self: 'txn;
 
+    type Snapshot<'snap>
+        = MemDbSnapshot
+    where
+        Self: 'snap;
+
     fn read_txn(&self) -> eyre::Result<Self::TX<'_>> {
         Ok(LayeredDbTx { mem_db: self.mem_db.clone() })
     }
@@ -211,10 +244,28 @@ impl<DB: Database> Database for LayeredDatabase<DB> {
     /// last commit happens. Also, all write operations are saved in memory then passed to
     /// thread for persistance in the background so operations will return quickly.
     fn write_txn(&self) -> eyre::Result<Self::TXMut<'_>> {
-        self.tx.send(DBMessage::StartTxn).map_err(|_| eyre::eyre!("DB thread gone, FATAL!"))?;
+        self.tx
+            .send(DBMessage::StartTxn(None))
+            .map_err(|_| eyre::eyre!("DB thread gone, FATAL!"))?;
+        Ok(LayeredDbTxMut { mem_db: self.mem_db.clone(), tx: self.tx.clone() })
+    }
+
+    /// The durability is passed to the persistant DB's write txn. Since write txns are
+    /// "overlapped", a txn started while another is open commits with the durability of the
+    /// first txn.
+    fn write_txn_with_durability(&self, durability: Durability) -> eyre::Result<Self::TXMut<'_>> {
+        self.tx
+            .send(DBMessage::StartTxn(Some(durability)))
+            .map_err(|_| eyre::eyre!("DB thread gone, FATAL!"))?;
         Ok(LayeredDbTxMut { mem_db: self.mem_db.clone(), tx: self.tx.clone() })
     }
 
+    /// The snapshot is taken from the memory layer, the persistant DB may not have caught up
+    /// with it yet.
+    fn snapshot(&self) -> eyre::Result<Self::Snapshot<'_>> {
+        self.mem_db.snapshot()
+    }
+
     fn contains_key<T: Table>(&self, key: &T::Key) -> eyre::Result<bool> {
         self.mem_db.contains_key::<T>(key)
     }
@@ -252,10 +303,24 @@ impl<DB: Database> Database for LayeredDatabase<DB> {
         self.mem_db.iter::<T>()
     }
 
+    /// Raw reads come from the persistant DB so callers see the bytes actually stored, for
+    /// instance to verify the DB after an unclean shutdown.  Writes sent before this call are
+    /// flushed first, rows in a background transaction that has not committed yet are not visible.
+    fn iter_raw<T: Table>(&self) -> DBIterRaw<'_> {
+        if let Err(e) = self.flush() {
+            tracing::error!("DB Flush before raw iteration: {e}");
+        }
+        self.db.iter_raw::<T>()
+    }
+
     fn skip_to<T: Table>(&self, key: &T::Key) -> eyre::Result<DBIter<'_, T>> {
         self.mem_db.skip_to::<T>(key)
     }
 
+    fn range_iter<T: Table>(&self, from: &T::Key, to: &T::Key) -> DBIter<'_, T> {
+        self.mem_db.range_iter::<T>(from, to)
+    }
+
     fn reverse_iter<T: Table>(&self) -> DBIter<'_, T> {
         self.mem_db.reverse_iter::<T>()
     }
@@ -267,6 +332,24 @@ impl<DB: Database> Database for LayeredDatabase<DB> {
     fn last_record<T: Table>(&self) -> Option<(T::Key, T::Value)> {
         self.mem_db.last_record::<T>()
     }
+
+    fn first_record<T: Table>(&self) -> Option<(T::Key, T::Value)> {
+        self.mem_db.first_record::<T>()
+    }
+
+    fn approximate_len<T: Table>(&self) -> usize {
+        self.mem_db.approximate_len::<T>()
+    }
+
+    /// Wait for the background thread to persist all writes sent before this call.
+    fn flush(&self) -> eyre::Result<()> {
+        let (ack_tx, ack_rx) = mpsc::channel();
+        self.tx
+            .send(DBMessage::Flush(ack_tx))
+            .map_err(|_| eyre::eyre!("DB thread gone, FATAL!"))?;
+        ack_rx.recv().map_err(|_| eyre::eyre!("DB thread gone, FATAL!"))?;
+        Ok(())
+    }
 }
 
 trait InsertTrait<DB: Database>: Send + 'static {
@@ -289,6 +372,12 @@ struct KeyValueInsert<T: Table> {
     value: T::Value,
 }
 
+struct KeyValueInsertRaw<T: Table> {
+    key: Vec<u8>,
+    value: Vec<u8>,
+    _casper: PhantomData<T>,
+}
+
 struct KeyRemove<T: Table> {
     key: T::Key,
 }
@@ -306,6 +395,17 @@ impl<T: Table, DB: Database> InsertTrait<DB> for KeyValueInsert<T> {
     }
 }
 
+impl<T: Table, DB: Database> InsertTrait<DB> for KeyValueInsertRaw<T> {
+    fn insert(&self, db: &DB) -> eyre::Result<()> {
+        let mut txn = db.write_txn()?;
+        txn.insert_raw::<T>(&self.key, &self.value)?;
+        txn.commit()
+    }
+    fn insert_txn(&self, txn: &mut DB::TXMut<'_>) -> eyre::Result<()> {
+        txn.insert_raw::<T>(&self.key, &self.value)
+    }
+}
+
 impl<T: Table, DB: Database> RemoveTrait<DB> for KeyRemove<T> {
     fn remove(&self, db: &DB) -> eyre::Result<()> {
         db.remove::<T>(&self.key)
@@ -327,22 +427,25 @@ impl<T: Table, DB: Database> ClearTrait<DB> for ClearTable<T> {
 }
 
 enum DBMessage<DB: Database> {
-    StartTxn,
+    /// Start a write txn, with the persistant DB's default durability if `None`.
+    StartTxn(Option<Durability>),
     CommitTxn,
     Insert(Box<dyn InsertTrait<DB>>),
     Remove(Box<dyn RemoveTrait<DB>>),
     Clear(Box<dyn ClearTrait<DB>>),
+    Flush(Sender<()>),
     Shutdown,
 }
 
 impl<DB: Database> Debug for DBMessage<DB> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            DBMessage::StartTxn => write!(f, "StartTxn"),
+            DBMessage::StartTxn(durability) => write!(f, "StartTxn({durability:?})"),
             DBMessage::CommitTxn => write!(f, "CommitTxn"),
             DBMessage::Insert(_) => write!(f, "Insert"),
             DBMessage::Remove(_) => write!(f, "Remove"),
             DBMessage::Clear(_) => write!(f, "Clear"),
+            DBMessage::Flush(_) => write!(f, "Flush"),
             DBMessage::Shutdown => write!(f, "Shutdown"),
         }
     }
@@ -353,9 +456,14 @@ mod test {
     use super::LayeredDatabase;
     #[cfg(feature = "redb")]
     use crate::redb::ReDB;
-    use crate::{mdbx::MdbxDatabase, test::*};
+    use crate::{
+        mdbx::MdbxDatabase,
+        tables::{ConsensusBlockNumbersByDigest, ConsensusBlocks},
+        test::*,
+    };
     use std::path::Path;
     use tempfile::tempdir;
+    use tn_types::{Database, DbTxMut, Durability};
 
     #[cfg(feature = "redb")]
     fn open_redb(path: &Path) -> LayeredDatabase<ReDB> {
@@ -422,6 +530,18 @@ mod test {
         test_skip(db);
     }
 
+    #[test]
+    fn test_layereddb_range_iter() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        #[cfg(feature = "redb")]
+        {
+            let db = open_redb(temp_dir.path());
+            test_range_iter(db);
+        }
+        let db = open_mdbx(temp_dir.path());
+        test_range_iter(db);
+    }
+
     #[test]
     fn test_layereddb_skip_to_previous_simple() {
         let temp_dir = tempdir().expect("failed to create temp dir");
@@ -532,6 +652,139 @@ mod test {
         test_multi_remove(db);
     }
 
+    #[test]
+    fn test_layereddb_first_last_record() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        #[cfg(feature = "redb")]
+        {
+            let db = open_redb(temp_dir.path());
+            test_first_last_record(db);
+        }
+        let db = open_mdbx(temp_dir.path());
+        test_first_last_record(db);
+    }
+
+    #[test]
+    fn test_layereddb_approximate_len() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        #[cfg(feature = "redb")]
+        {
+            let db = open_redb(temp_dir.path());
+            test_approximate_len(db);
+        }
+        let db = open_mdbx(temp_dir.path());
+        test_approximate_len(db);
+    }
+
+    #[test]
+    fn test_layereddb_snapshot() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = LayeredDatabase::open(
+            crate::open_mdbx_tables(temp_dir.path()).expect("Cannot open database"),
+        );
+        db.open_table::<ConsensusBlocks>();
+        db.open_table::<ConsensusBlockNumbersByDigest>();
+        test_snapshot(db);
+    }
+
+    #[test]
+    fn test_layereddb_insert_if_absent() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        #[cfg(feature = "redb")]
+        {
+            let db = open_redb(temp_dir.path());
+            test_insert_if_absent(db);
+        }
+        let db = open_mdbx(temp_dir.path());
+        test_insert_if_absent(db);
+    }
+
+    #[test]
+    fn test_layereddb_insert_checked() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        #[cfg(feature = "redb")]
+        {
+            let db = open_redb(temp_dir.path());
+            test_insert_checked(db);
+        }
+        let db = open_mdbx(temp_dir.path());
+        test_insert_checked(db);
+    }
+
+    #[test]
+    fn test_layereddb_raw_copy() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        #[cfg(feature = "redb")]
+        {
+            let db = open_redb(temp_dir.path());
+            test_raw_copy(db);
+        }
+        let db = open_mdbx(temp_dir.path());
+        test_raw_copy(db);
+    }
+
+    #[test]
+    fn test_layereddb_verify_table() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        #[cfg(feature = "redb")]
+        {
+            let db = open_redb(temp_dir.path());
+            test_verify_table(db);
+        }
+        let db = open_mdbx(temp_dir.path());
+        test_verify_table(db);
+    }
+
+    #[test]
+    fn test_layereddb_iter_checked() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        #[cfg(feature = "redb")]
+        {
+            let db = open_redb(temp_dir.path());
+            test_iter_checked(db);
+        }
+        let db = open_mdbx(temp_dir.path());
+        test_iter_checked(db);
+    }
+
+    #[test]
+    fn test_layereddb_open_corrupt_table() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = MdbxDatabase::open(temp_dir.path()).expect("Cannot open database");
+        db.open_table::<TestTable>().expect("failed to open table!");
+        db.insert::<TestTable>(&1, &"1".to_string()).expect("Failed to insert");
+
+        // a string value with a length prefix longer than the bytes can't decode
+        let corrupt_key = tn_types::encode_key(&2u64);
+        let mut txn = db.write_txn().unwrap();
+        txn.insert_raw::<TestTable>(&corrupt_key, &[0xff, 0xff, 0x01])
+            .expect("Failed to raw insert");
+        txn.commit().unwrap();
+
+        // loading the corrupt row does not panic and the row is reported
+        let db = LayeredDatabase::open(db);
+        db.open_table::<TestTable>();
+        let report = db.verify_table::<TestTable>().expect("verify table");
+        assert_eq!(report.valid, 1);
+        assert_eq!(report.corrupt_keys, vec![corrupt_key]);
+    }
+
+    /// Per-txn durability is passed to the persistant DB.
+    ///
+    /// ReDB loses commits with [Durability::None] when reopened, so the [Durability::Sync] commit
+    /// only survives if it reaches ReDB with its own durability.
+    #[test]
+    fn test_layereddb_durability_reopen() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        test_durability_reopen(|| {
+            let db = crate::ReDB::open_with_durability(temp_dir.path(), Durability::None)?;
+            db.open_table::<TestTable>()?;
+            let db = LayeredDatabase::open(db);
+            db.open_table::<TestTable>();
+            Ok(db)
+        })
+    }
+
     #[test]
     fn test_layereddb_dbsimpbench() {
         // Init a DB
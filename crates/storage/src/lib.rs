@@ -3,7 +3,11 @@
 
 #![warn(future_incompatible, nonstandard_style, rust_2018_idioms, rust_2021_compatibility)]
 
+mod async_db;
+mod batch_writer;
 mod stores;
+pub use async_db::AsyncDatabase;
+pub use batch_writer::BatchWriter;
 use layered_db::LayeredDatabase;
 #[cfg(feature = "reth-libmdbx")]
 use mdbx::MdbxDatabase;
@@ -13,17 +17,23 @@ pub use redb::database::ReDB;
 #[cfg(feature = "rocksdb")]
 use rocks::database::RocksDatabase;
 use tables::{
-    Batches, CertificateDigestByOrigin, CertificateDigestByRound, Certificates,
-    ConsensusBlockNumbersByDigest, ConsensusBlocks, LastProposed, Payload, Votes,
+    BatchReceivedAt, Batches, CertificateDigestByOrigin, CertificateDigestByRound, Certificates,
+    ConsensusBlockNumbersByDigest, ConsensusBlocks, LastCommitted, LastProposed, Payload, Votes,
 };
 // Always build redb, we use it as the default for persistant consensus data.
 pub mod layered_db;
 #[cfg(feature = "reth-libmdbx")]
 pub mod mdbx;
 pub mod mem_db;
+mod migrate;
+pub use migrate::{migrate_db, migrate_db_paths, DbBackend};
+mod preload;
+pub use preload::PreloadTables;
 pub mod redb;
 #[cfg(feature = "rocksdb")]
 pub mod rocks;
+mod size_monitor;
+pub use size_monitor::{store_size, StoreSizeMetrics, StoreSizeMonitor};
 pub use tn_types::error::StoreError;
 
 pub type ProposerKey = u32;
@@ -47,11 +57,24 @@ const CERTIFICATE_DIGEST_BY_ROUND_CF: &str = "certificate_digest_by_round";
 const CERTIFICATE_DIGEST_BY_ORIGIN_CF: &str = "certificate_digest_by_origin";
 const PAYLOAD_CF: &str = "payload";
 const BATCHES_CF: &str = "batches";
+const BATCH_RECEIVED_AT_CF: &str = "batch_received_at";
 const CONSENSUS_BLOCK_CF: &str = "consensus_block";
 const CONSENSUS_BLOCK_NUMBER_BY_DIGEST_CF: &str = "consensus_block_number_by_digest";
+const LAST_COMMITTED_CF: &str = "last_committed";
+
+/// The max encoded size of a certificate in the DB.
+///
+/// Well above what the network limits allow, this is a last line of defense against storing
+/// enormous values that slipped past validation.
+const MAX_CERTIFICATE_BYTES: usize = 4 * 1024 * 1024; // 4 MiB
+/// The max encoded size of a batch in the DB.
+///
+/// Well above what the network limits allow, this is a last line of defense against storing
+/// enormous values that slipped past validation.
+const MAX_BATCH_BYTES: usize = 16 * 1024 * 1024; // 16 MiB
 
 macro_rules! tables {
-    ( $($table:ident;$name:expr;<$K:ty, $V:ty>),*) => {
+    ( $($table:ident;$name:expr;<$K:ty, $V:ty>$(;$max:expr)?),*) => {
             $(
                 #[derive(Debug)]
                 pub struct $table {}
@@ -60,6 +83,7 @@ macro_rules! tables {
                     type Value = $V;
 
                     const NAME: &'static str = $name;
+                    $(const MAX_VALUE_BYTES: Option<usize> = Some($max);)?
                 }
             )*
     };
@@ -69,21 +93,26 @@ pub mod tables {
     use super::{PayloadToken, ProposerKey};
     use tn_types::{
         AuthorityIdentifier, Batch, BlockHash, Certificate, CertificateDigest, ConsensusHeader,
-        Header, Round, VoteInfo, WorkerId,
+        Header, Round, TimestampSec, VoteInfo, WorkerId,
     };
 
     tables!(
         LastProposed;crate::LAST_PROPOSED_CF;<ProposerKey, Header>,
         Votes;crate::VOTES_CF;<AuthorityIdentifier, VoteInfo>,
-        Certificates;crate::CERTIFICATES_CF;<CertificateDigest, Certificate>,
+        Certificates;crate::CERTIFICATES_CF;<CertificateDigest, Certificate>;crate::MAX_CERTIFICATE_BYTES,
         CertificateDigestByRound;crate::CERTIFICATE_DIGEST_BY_ROUND_CF;<(Round, AuthorityIdentifier), CertificateDigest>,
         CertificateDigestByOrigin;crate::CERTIFICATE_DIGEST_BY_ORIGIN_CF;<(AuthorityIdentifier, Round), CertificateDigest>,
         Payload;crate::PAYLOAD_CF;<(BlockHash, WorkerId), PayloadToken>,
         // Table is used for "normal" consensus as well as for the consensus chain.
-        Batches;crate::BATCHES_CF;<BlockHash, Batch>,
+        Batches;crate::BATCHES_CF;<BlockHash, Batch>;crate::MAX_BATCH_BYTES,
+        // When a batch was received from a peer, Batch::received_at is not serialized so it is
+        // kept here for latency analytics.  Batches built by this node will not have an entry.
+        BatchReceivedAt;crate::BATCH_RECEIVED_AT_CF;<BlockHash, TimestampSec>,
         // These tables are for the consensus chain not the normal consensus.
         ConsensusBlocks;crate::CONSENSUS_BLOCK_CF;<u64, ConsensusHeader>,
-        ConsensusBlockNumbersByDigest;crate::CONSENSUS_BLOCK_NUMBER_BY_DIGEST_CF;<BlockHash, u64>
+        ConsensusBlockNumbersByDigest;crate::CONSENSUS_BLOCK_NUMBER_BY_DIGEST_CF;<BlockHash, u64>,
+        // The latest committed round of each authority, only ever moves forward.
+        LastCommitted;crate::LAST_COMMITTED_CF;<AuthorityIdentifier, Round>
     );
 }
 
@@ -116,16 +145,7 @@ pub fn open_db<Path: AsRef<std::path::Path> + Send>(store_path: Path) -> Databas
 /// Open or reopen all the storage of the node backed by MDBX.
 #[cfg(feature = "reth-libmdbx")]
 fn _open_mdbx<P: AsRef<std::path::Path> + Send>(store_path: P) -> LayeredDatabase<MdbxDatabase> {
-    let db = MdbxDatabase::open(store_path).expect("Cannot open database");
-    db.open_table::<LastProposed>().expect("failed to open table!");
-    db.open_table::<Votes>().expect("failed to open table!");
-    db.open_table::<Certificates>().expect("failed to open table!");
-    db.open_table::<CertificateDigestByRound>().expect("failed to open table!");
-    db.open_table::<CertificateDigestByOrigin>().expect("failed to open table!");
-    db.open_table::<Payload>().expect("failed to open table!");
-    db.open_table::<Batches>().expect("failed to open table!");
-    db.open_table::<ConsensusBlocks>().expect("failed to open table!");
-    db.open_table::<ConsensusBlockNumbersByDigest>().expect("failed to open table!");
+    let db = open_mdbx_tables(store_path).expect("Cannot open database");
 
     let db = LayeredDatabase::open(db);
     db.open_table::<LastProposed>();
@@ -135,8 +155,10 @@ fn _open_mdbx<P: AsRef<std::path::Path> + Send>(store_path: P) -> LayeredDatabas
     db.open_table::<CertificateDigestByOrigin>();
     db.open_table::<Payload>();
     db.open_table::<Batches>();
+    db.open_table::<BatchReceivedAt>();
     db.open_table::<ConsensusBlocks>();
     db.open_table::<ConsensusBlockNumbersByDigest>();
+    db.open_table::<LastCommitted>();
     db
 }
 
@@ -152,24 +174,17 @@ fn _open_rocks<P: AsRef<std::path::Path> + Send>(store_path: P) -> LayeredDataba
     db.open_table::<CertificateDigestByOrigin>();
     db.open_table::<Payload>();
     db.open_table::<Batches>();
+    db.open_table::<BatchReceivedAt>();
     db.open_table::<ConsensusBlocks>();
     db.open_table::<ConsensusBlockNumbersByDigest>();
+    db.open_table::<LastCommitted>();
     db
 }
 
 /// Open or reopen all the storage of the node backed by ReDB.
 #[cfg(feature = "redb")]
 fn _open_redb<P: AsRef<std::path::Path> + Send>(store_path: P) -> LayeredDatabase<ReDB> {
-    let db = ReDB::open(store_path).expect("Cannot open database");
-    db.open_table::<LastProposed>().expect("failed to open table!");
-    db.open_table::<Votes>().expect("failed to open table!");
-    db.open_table::<Certificates>().expect("failed to open table!");
-    db.open_table::<CertificateDigestByRound>().expect("failed to open table!");
-    db.open_table::<CertificateDigestByOrigin>().expect("failed to open table!");
-    db.open_table::<Payload>().expect("failed to open table!");
-    db.open_table::<Batches>().expect("failed to open table!");
-    db.open_table::<ConsensusBlocks>().expect("failed to open table!");
-    db.open_table::<ConsensusBlockNumbersByDigest>().expect("failed to open table!");
+    let db = open_redb_tables(store_path).expect("Cannot open database");
 
     let db = LayeredDatabase::open(db);
     db.open_table::<LastProposed>();
@@ -179,14 +194,52 @@ fn _open_redb<P: AsRef<std::path::Path> + Send>(store_path: P) -> LayeredDatabas
     db.open_table::<CertificateDigestByOrigin>();
     db.open_table::<Payload>();
     db.open_table::<Batches>();
+    db.open_table::<BatchReceivedAt>();
     db.open_table::<ConsensusBlocks>();
     db.open_table::<ConsensusBlockNumbersByDigest>();
+    db.open_table::<LastCommitted>();
     db
 }
 
+/// Open the MDBX backend (without the memory layer) and create all the node tables.
+#[cfg(feature = "reth-libmdbx")]
+fn open_mdbx_tables<P: AsRef<std::path::Path>>(store_path: P) -> eyre::Result<MdbxDatabase> {
+    let db = MdbxDatabase::open(store_path)?;
+    db.open_table::<LastProposed>()?;
+    db.open_table::<Votes>()?;
+    db.open_table::<Certificates>()?;
+    db.open_table::<CertificateDigestByRound>()?;
+    db.open_table::<CertificateDigestByOrigin>()?;
+    db.open_table::<Payload>()?;
+    db.open_table::<Batches>()?;
+    db.open_table::<BatchReceivedAt>()?;
+    db.open_table::<ConsensusBlocks>()?;
+    db.open_table::<ConsensusBlockNumbersByDigest>()?;
+    db.open_table::<LastCommitted>()?;
+    Ok(db)
+}
+
+/// Open the ReDB backend (without the memory layer) and create all the node tables.
+fn open_redb_tables<P: AsRef<std::path::Path>>(store_path: P) -> eyre::Result<ReDB> {
+    let db = ReDB::open(store_path)?;
+    db.open_table::<LastProposed>()?;
+    db.open_table::<Votes>()?;
+    db.open_table::<Certificates>()?;
+    db.open_table::<CertificateDigestByRound>()?;
+    db.open_table::<CertificateDigestByOrigin>()?;
+    db.open_table::<Payload>()?;
+    db.open_table::<Batches>()?;
+    db.open_table::<BatchReceivedAt>()?;
+    db.open_table::<ConsensusBlocks>()?;
+    db.open_table::<ConsensusBlockNumbersByDigest>()?;
+    db.open_table::<LastCommitted>()?;
+    Ok(db)
+}
+
 #[cfg(test)]
 mod test {
-    use tn_types::{Database, DbTxMut};
+    use crate::mem_db::MemDatabase;
+    use tn_types::{error::StoreErrorKind, Database, DbTxMut, Durability};
 
     #[derive(Debug)]
     pub struct TestTable {}
@@ -197,6 +250,16 @@ mod test {
         const NAME: &'static str = "TestTable";
     }
 
+    #[derive(Debug)]
+    pub struct LimitedTestTable {}
+    impl tn_types::Table for LimitedTestTable {
+        type Key = u64;
+        type Value = String;
+
+        const NAME: &'static str = "LimitedTestTable";
+        const MAX_VALUE_BYTES: Option<usize> = Some(16);
+    }
+
     /// Runs a simple bench/test for the provided DB.  Can use it for larger dataset tests as well
     /// as comparing backends. For example run ```cargo test dbsimpbench --features rocksdb --
     /// --nocapture --test-threads 1``` to run each backend through the bench one at a time.
@@ -338,6 +401,42 @@ mod test {
         assert_eq!(db.skip_to::<TestTable>(&000).expect("Skip failed").count(), 3);
     }
 
+    pub fn test_range_iter<DB: Database>(db: DB) {
+        let mut txn = db.write_txn().unwrap();
+        for i in (10..=50).step_by(10) {
+            txn.insert::<TestTable>(&i, &i.to_string()).expect("Failed to insert");
+        }
+        txn.commit().unwrap();
+
+        let keys = |from, to| db.range_iter::<TestTable>(&from, &to).map(|(k, _)| k).collect();
+
+        // from is inclusive and to is exclusive
+        let range: Vec<u64> = keys(20, 40);
+        assert_eq!(range, vec![20, 30]);
+
+        // bounds between keys
+        let range: Vec<u64> = keys(15, 45);
+        assert_eq!(range, vec![20, 30, 40]);
+
+        // bounds beyond the table
+        let range: Vec<u64> = keys(0, 100);
+        assert_eq!(range, vec![10, 20, 30, 40, 50]);
+
+        // values are returned with the keys
+        let entries: Vec<_> = db.range_iter::<TestTable>(&50, &51).collect();
+        assert_eq!(entries, vec![(50, "50".to_string())]);
+
+        // empty ranges
+        let range: Vec<u64> = keys(30, 30);
+        assert!(range.is_empty());
+        let range: Vec<u64> = keys(40, 20);
+        assert!(range.is_empty());
+        let range: Vec<u64> = keys(51, 100);
+        assert!(range.is_empty());
+        let range: Vec<u64> = keys(0, 10);
+        assert!(range.is_empty());
+    }
+
     pub fn test_skip_to_previous_simple<DB: Database>(db: DB) {
         let mut txn = db.write_txn().unwrap();
         txn.insert::<TestTable>(&123, &"123".to_string()).expect("Failed to insert");
@@ -377,6 +476,189 @@ mod test {
         assert!(db.get::<TestTable>(&123456789).expect("Failed to get").is_none());
     }
 
+    pub fn test_insert_if_absent<DB: Database>(db: DB) {
+        // absent key is inserted
+        assert!(db.insert_if_absent::<TestTable>(&1, &"1".to_string()).expect("Failed to insert"));
+        assert_eq!(db.get::<TestTable>(&1).expect("Failed to get"), Some("1".to_string()));
+
+        // existing key is not overwritten
+        assert!(!db.insert_if_absent::<TestTable>(&1, &"2".to_string()).expect("Failed to insert"));
+        assert_eq!(db.get::<TestTable>(&1).expect("Failed to get"), Some("1".to_string()));
+    }
+
+    pub fn test_insert_checked<DB: Database>(db: DB) {
+        // absent key is inserted
+        db.insert_checked::<TestTable>(&1, &"1".to_string()).expect("Failed to insert");
+        assert_eq!(db.get::<TestTable>(&1).expect("Failed to get"), Some("1".to_string()));
+
+        // same value re-insert is a no-op
+        db.insert_checked::<TestTable>(&1, &"1".to_string()).expect("Same value re-insert");
+        assert_eq!(db.get::<TestTable>(&1).expect("Failed to get"), Some("1".to_string()));
+
+        // conflicting value is rejected and the original is kept
+        assert!(db.insert_checked::<TestTable>(&1, &"2".to_string()).is_err());
+        assert_eq!(db.get::<TestTable>(&1).expect("Failed to get"), Some("1".to_string()));
+    }
+
+    pub fn test_value_size_limit<DB: Database>(db: DB) {
+        // under the limit succeeds
+        db.insert::<LimitedTestTable>(&1, &"small".to_string()).expect("Failed to insert");
+        assert_eq!(
+            db.get::<LimitedTestTable>(&1).expect("Failed to get"),
+            Some("small".to_string())
+        );
+
+        // over the limit is rejected
+        let err = db
+            .insert::<LimitedTestTable>(&2, &"x".repeat(100))
+            .expect_err("oversized value inserted");
+        assert!(matches!(
+            err.downcast_ref::<StoreErrorKind>(),
+            Some(StoreErrorKind::ValueTooLarge { table: "LimitedTestTable", max: 16, .. })
+        ));
+        assert!(db.get::<LimitedTestTable>(&2).expect("Failed to get").is_none());
+
+        // also rejected inside a transaction
+        let mut txn = db.write_txn().unwrap();
+        assert!(txn.insert::<LimitedTestTable>(&3, &"x".repeat(100)).is_err());
+        txn.commit().unwrap();
+        assert!(db.get::<LimitedTestTable>(&3).expect("Failed to get").is_none());
+
+        // tables without a limit accept large values
+        db.insert::<TestTable>(&1, &"x".repeat(100)).expect("Failed to insert");
+    }
+
+    pub fn test_verify_table<DB: Database>(db: DB) {
+        let mut txn = db.write_txn().unwrap();
+        for (key, val) in (0..10).map(|i| (i, i.to_string())) {
+            txn.insert::<TestTable>(&key, &val).expect("Failed to batch insert");
+        }
+        txn.commit().unwrap();
+        assert_eq!(db.verify_table::<TestTable>().expect("verify table").valid, 10);
+
+        // a string value with a length prefix longer than the bytes can't decode
+        let corrupt_key = tn_types::encode_key(&100u64);
+        let mut txn = db.write_txn().unwrap();
+        txn.insert_raw::<TestTable>(&corrupt_key, &[0xff, 0xff, 0x01])
+            .expect("Failed to raw insert");
+        txn.commit().unwrap();
+
+        let report = db.verify_table::<TestTable>().expect("verify table");
+        assert_eq!(report.valid, 10);
+        assert_eq!(report.corrupt(), 1);
+        assert_eq!(report.corrupt_keys, vec![corrupt_key]);
+        assert!(!report.is_ok());
+    }
+
+    pub fn test_iter_checked<DB: Database>(db: DB) {
+        let mut txn = db.write_txn().unwrap();
+        for (key, val) in (0..10).map(|i| (i, i.to_string())) {
+            txn.insert::<TestTable>(&key, &val).expect("Failed to batch insert");
+        }
+        txn.commit().unwrap();
+
+        // a string value with a length prefix longer than the bytes can't decode
+        let corrupt_key = tn_types::encode_key(&5u64);
+        let mut txn = db.write_txn().unwrap();
+        txn.insert_raw::<TestTable>(&corrupt_key, &[0xff, 0xff, 0x01])
+            .expect("Failed to raw insert");
+        txn.commit().unwrap();
+
+        let rows: Vec<_> = db.iter_checked::<TestTable>().collect();
+        assert_eq!(rows.len(), 10);
+        for (i, row) in rows.into_iter().enumerate() {
+            let key = i as u64;
+            if key == 5 {
+                let err = row.expect_err("corrupt row");
+                assert!(matches!(
+                    err.downcast_ref::<StoreErrorKind>(),
+                    Some(StoreErrorKind::CorruptRow { table: "TestTable", key, .. })
+                        if *key == corrupt_key
+                ));
+            } else {
+                assert_eq!(row.expect("valid row"), (key, key.to_string()));
+            }
+        }
+    }
+
+    pub fn test_export_table_ndjson<DB: Database>(db: DB) {
+        let mut txn = db.write_txn().unwrap();
+        for (key, val) in (0..10).map(|i| (i, i.to_string())) {
+            txn.insert::<TestTable>(&key, &val).expect("Failed to batch insert");
+        }
+        txn.commit().unwrap();
+
+        let mut out = Vec::new();
+        let written = db.export_table_ndjson::<TestTable, _>(&mut out).expect("export table");
+        assert_eq!(written, 10);
+
+        // every line round-trips to the original key and value
+        let lines: Vec<_> = std::str::from_utf8(&out).expect("utf8 export").lines().collect();
+        assert_eq!(lines.len(), 10);
+        for line in lines {
+            let row: serde_json::Value = serde_json::from_str(line).expect("json line");
+            let key: u64 = serde_json::from_value(row["key"].clone()).expect("key");
+            let value: String = serde_json::from_value(row["value"].clone()).expect("value");
+            assert_eq!(db.get::<TestTable>(&key).expect("Failed to get"), Some(value));
+        }
+    }
+
+    /// Writes with each durability then drops and reopens the DB with `open`.
+    /// Only Sync commits are guaranteed to survive, None commits are only checked in process since
+    /// a backend can lose them on a crash or reopen.
+    pub fn test_durability_reopen<DB: Database>(open: impl Fn() -> eyre::Result<DB>) {
+        let db = open().expect("open db");
+        for (key, durability) in [(1, Durability::None), (2, Durability::Eventual)] {
+            let mut txn = db.write_txn_with_durability(durability).unwrap();
+            txn.insert::<TestTable>(&key, &key.to_string()).expect("Failed to insert");
+            txn.commit().unwrap();
+            assert_eq!(db.get::<TestTable>(&key).unwrap(), Some(key.to_string()));
+        }
+        let mut txn = db.write_txn_with_durability(Durability::Sync).unwrap();
+        txn.insert::<TestTable>(&3, &"3".to_string()).expect("Failed to insert");
+        txn.commit().unwrap();
+        drop(db);
+
+        let db = reopen(open);
+        assert_eq!(db.get::<TestTable>(&3).unwrap(), Some("3".to_string()));
+    }
+
+    /// Like [test_durability_reopen] for backends that only set durability when opened, `open`
+    /// opens the DB with the durability for all of its write txns.
+    pub fn test_open_durability_reopen<DB: Database>(
+        open: impl Fn(Durability) -> eyre::Result<DB>,
+    ) {
+        for (key, durability) in [(1, Durability::None), (2, Durability::Eventual)] {
+            let db = reopen(|| open(durability));
+            let mut txn = db.write_txn().unwrap();
+            txn.insert::<TestTable>(&key, &key.to_string()).expect("Failed to insert");
+            txn.commit().unwrap();
+            assert_eq!(db.get::<TestTable>(&key).unwrap(), Some(key.to_string()));
+        }
+        let db = reopen(|| open(Durability::Sync));
+        let mut txn = db.write_txn().unwrap();
+        txn.insert::<TestTable>(&3, &"3".to_string()).expect("Failed to insert");
+        txn.commit().unwrap();
+        drop(db);
+
+        let db = reopen(|| open(Durability::Sync));
+        assert_eq!(db.get::<TestTable>(&3).unwrap(), Some("3".to_string()));
+    }
+
+    /// Open a DB that was just dropped.
+    fn reopen<DB: Database>(open: impl Fn() -> eyre::Result<DB>) -> DB {
+        // the metrics thread may hold the DB open briefly after drop
+        let mut reopened = open();
+        for _ in 0..20 {
+            if reopened.is_ok() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            reopened = open();
+        }
+        reopened.expect("reopen db")
+    }
+
     pub fn test_iter<DB: Database>(db: DB) {
         db.insert::<TestTable>(&123456789, &"123456789".to_string()).expect("Failed to insert");
 
@@ -484,4 +766,100 @@ mod test {
             assert_eq!(Some(v), val);
         }
     }
+
+    pub fn test_raw_copy<DB: Database>(db: DB) {
+        let src = MemDatabase::new();
+        src.open_table::<TestTable>();
+        let mut txn = src.write_txn().unwrap();
+        for (key, val) in (0..101).map(|i| (i, i.to_string())) {
+            txn.insert::<TestTable>(&key, &val).expect("Failed to batch insert");
+        }
+        txn.commit().unwrap();
+
+        // Copy the table without decoding it
+        let mut txn = db.write_txn().unwrap();
+        txn.multi_insert_raw::<TestTable>(src.iter_raw::<TestTable>())
+            .expect("Failed to raw insert");
+        txn.commit().unwrap();
+
+        assert_eq!(
+            src.iter::<TestTable>().collect::<Vec<_>>(),
+            db.iter::<TestTable>().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            src.iter_raw::<TestTable>().collect::<Vec<_>>(),
+            db.iter_raw::<TestTable>().collect::<Vec<_>>()
+        );
+    }
+
+    pub fn test_first_last_record<DB: Database>(db: DB) {
+        assert!(db.first_record::<TestTable>().is_none());
+        assert!(db.last_record::<TestTable>().is_none());
+
+        let mut txn = db.write_txn().unwrap();
+        for (key, val) in (10..111).map(|i| (i, i.to_string())) {
+            txn.insert::<TestTable>(&key, &val).expect("Failed to batch insert");
+        }
+        txn.commit().unwrap();
+
+        assert_eq!(db.first_record::<TestTable>(), Some((10, "10".to_string())));
+        assert_eq!(db.last_record::<TestTable>(), Some((110, "110".to_string())));
+    }
+
+    pub fn test_approximate_len<DB: Database>(db: DB) {
+        let max = 1_000;
+        let mut txn = db.write_txn().unwrap();
+        for (key, val) in (0..max).map(|i| (i, i.to_string())) {
+            txn.insert::<TestTable>(&key, &val).expect("Failed to batch insert");
+        }
+        txn.commit().unwrap();
+
+        // Allow for 10% error for backends that estimate.
+        let len = db.approximate_len::<TestTable>();
+        assert!(len.abs_diff(max as usize) <= max as usize / 10, "approximate_len {len}");
+    }
+
+    pub fn test_snapshot<DB: Database>(db: DB) {
+        use crate::tables::{ConsensusBlockNumbersByDigest, ConsensusBlocks};
+        use tn_types::{BlockHash, ConsensusHeader, DbSnapshot, DbTx};
+
+        let header = |number| ConsensusHeader { number, ..Default::default() };
+        let mut txn = db.write_txn().unwrap();
+        for i in 0..10_u64 {
+            txn.insert::<ConsensusBlocks>(&i, &header(i)).unwrap();
+            txn.insert::<ConsensusBlockNumbersByDigest>(&BlockHash::with_last_byte(i as u8), &i)
+                .unwrap();
+        }
+        txn.commit().unwrap();
+
+        let snapshot = db.snapshot().expect("Failed to get snapshot");
+
+        // Write to both tables after the snapshot was taken.
+        let mut txn = db.write_txn().unwrap();
+        for i in 10..20_u64 {
+            txn.insert::<ConsensusBlocks>(&i, &header(i)).unwrap();
+            txn.insert::<ConsensusBlockNumbersByDigest>(&BlockHash::with_last_byte(i as u8), &i)
+                .unwrap();
+        }
+        txn.insert::<ConsensusBlockNumbersByDigest>(&BlockHash::with_last_byte(0), &100).unwrap();
+        txn.commit().unwrap();
+
+        // The DB sees the new writes.
+        assert_eq!(db.iter::<ConsensusBlocks>().count(), 20);
+        assert_eq!(db.iter::<ConsensusBlockNumbersByDigest>().count(), 20);
+        assert_eq!(
+            db.get::<ConsensusBlockNumbersByDigest>(&BlockHash::with_last_byte(0)).unwrap(),
+            Some(100)
+        );
+
+        // The snapshot does not.
+        assert_eq!(snapshot.iter::<ConsensusBlocks>().count(), 10);
+        assert_eq!(snapshot.iter::<ConsensusBlockNumbersByDigest>().count(), 10);
+        assert_eq!(snapshot.get::<ConsensusBlocks>(&15).unwrap(), None);
+        assert_eq!(snapshot.get::<ConsensusBlocks>(&5).unwrap(), Some(header(5)));
+        assert_eq!(
+            snapshot.get::<ConsensusBlockNumbersByDigest>(&BlockHash::with_last_byte(0)).unwrap(),
+            Some(0)
+        );
+    }
 }
@@ -11,11 +11,12 @@ use std::{
 };
 
 use reth_libmdbx::{
-    ffi::MDBX_dbi, Cursor, DatabaseFlags, Environment, Geometry, PageSize, Transaction, WriteFlags,
-    RO, RW,
+    ffi::MDBX_dbi, Cursor, DatabaseFlags, Environment, EnvironmentFlags, Geometry, Mode, PageSize,
+    SyncMode, Transaction, WriteFlags, RO, RW,
 };
 use tn_types::{
-    decode, decode_key, encode, encode_key, DBIter, Database, DbTx, DbTxMut, KeyT, Table, ValueT,
+    decode, decode_key, encode, encode_key, ensure_value_size, DBIter, DBIterRaw, Database,
+    DbSnapshot, DbTx, DbTxMut, Durability, KeyT, Table, ValueT,
 };
 
 use crate::mdbx::metrics::MdbxMetrics;
@@ -49,6 +50,14 @@ impl DbTx for MdbxTx {
     }
 }
 
+/// MDBX read transactions are already a consistent snapshot of the DB.
+impl DbSnapshot for MdbxTx {
+    fn iter<T: Table>(&self) -> DBIter<'_, T> {
+        let cursor = self.cursor::<T>().expect("Failed to get cursor!");
+        Box::new(MdbxIter { cursor, _key: PhantomData, _val: PhantomData })
+    }
+}
+
 /// Wrapper for the libmdbx transaction.
 #[derive(Debug)]
 pub struct MdbxTxMut {
@@ -78,10 +87,16 @@ impl DbTxMut for MdbxTxMut {
     fn insert<T: Table>(&mut self, key: &T::Key, value: &T::Value) -> eyre::Result<()> {
         let key_buf = encode_key(key);
         let value_buf = encode(value);
+        ensure_value_size::<T>(value_buf.len())?;
         self.inner.put(self.get_dbi::<T>()?, key_buf, value_buf, WriteFlags::UPSERT)?;
         Ok(())
     }
 
+    fn insert_raw<T: Table>(&mut self, key: &[u8], value: &[u8]) -> eyre::Result<()> {
+        self.inner.put(self.get_dbi::<T>()?, key, value, WriteFlags::UPSERT)?;
+        Ok(())
+    }
+
     fn remove<T: Table>(&mut self, key: &T::Key) -> eyre::Result<()> {
         let key_buf = encode_key(key);
         self.inner.del(self.get_dbi::<T>()?, key_buf, None)?;
@@ -140,9 +155,27 @@ impl MdbxDatabase {
     /// Creates a new database at the specified path if it doesn't exist. Does NOT create tables.
     /// Check [`init_db`].
     pub fn open<P: AsRef<Path>>(path: P) -> eyre::Result<Self> {
+        Self::open_with_durability(path, Durability::Sync)
+    }
+
+    /// Creates a new database like [`Self::open`] with `durability` for all write txns.
+    /// MDBX sets the sync mode for the environment so write txns can not override it.
+    pub fn open_with_durability<P: AsRef<Path>>(
+        path: P,
+        durability: Durability,
+    ) -> eyre::Result<Self> {
+        let sync_mode = match durability {
+            Durability::None => SyncMode::UtterlyNoSync,
+            Durability::Eventual => SyncMode::SafeNoSync,
+            Durability::Sync => SyncMode::Durable,
+        };
         let env = Environment::builder()
             .set_max_dbs(32)
             .write_map()
+            .set_flags(EnvironmentFlags {
+                mode: Mode::ReadWrite { sync_mode },
+                ..Default::default()
+            })
             .set_geometry(Geometry {
                 // Maximum database size of 4 terabytes
                 size: Some(0..(4 * TERABYTE)),
@@ -205,6 +238,11 @@ impl Database for MdbxDatabase {
     where
         Self: 'txn;
 
+    type Snapshot<'snap>
+        = MdbxTx
+    where
+        Self: 'snap;
+
     fn read_txn(&self) -> eyre::Result<Self::TX<'_>> {
         Ok(MdbxTx { inner: self.inner.begin_ro_txn()? })
     }
@@ -213,6 +251,10 @@ impl Database for MdbxDatabase {
         Ok(MdbxTxMut { inner: self.inner.begin_rw_txn()? })
     }
 
+    fn snapshot(&self) -> eyre::Result<Self::Snapshot<'_>> {
+        self.read_txn()
+    }
+
     fn contains_key<T: Table>(&self, key: &T::Key) -> eyre::Result<bool> {
         Ok(self.read_txn()?.get::<T>(key)?.is_some())
     }
@@ -255,6 +297,15 @@ impl Database for MdbxDatabase {
         Box::new(MdbxIter { cursor, _key: PhantomData, _val: PhantomData })
     }
 
+    fn iter_raw<T: Table>(&self) -> DBIterRaw<'_> {
+        let cursor = self
+            .read_txn()
+            .expect("Failed to get cursor!")
+            .cursor::<T>()
+            .expect("Failed to get cursor!");
+        Box::new(MdbxRawIter { cursor })
+    }
+
     fn skip_to<T: Table>(&self, key: &T::Key) -> eyre::Result<DBIter<'_, T>> {
         let cursor = self
             .read_txn()
@@ -266,6 +317,21 @@ impl Database for MdbxDatabase {
         Ok(Box::new(i.skip_while(move |(k, _)| k < &key)))
     }
 
+    fn range_iter<T: Table>(&self, from: &T::Key, to: &T::Key) -> DBIter<'_, T> {
+        let cursor = self
+            .read_txn()
+            .expect("Failed to get cursor!")
+            .cursor::<T>()
+            .expect("Failed to get cursor!");
+        Box::new(MdbxRangeIter {
+            cursor,
+            from: Some(encode_key(from)),
+            to: encode_key(to),
+            _key: PhantomData,
+            _val: PhantomData,
+        })
+    }
+
     fn reverse_iter<T: Table>(&self) -> DBIter<'_, T> {
         let cursor = self
             .read_txn()
@@ -295,6 +361,23 @@ impl Database for MdbxDatabase {
             .ok()?
             .map(|(k, v)| (decode_key::<T::Key>(&k), decode::<T::Value>(&v)))
     }
+
+    fn first_record<T: Table>(&self) -> Option<(T::Key, T::Value)> {
+        self.read_txn()
+            .ok()?
+            .cursor::<T>()
+            .ok()?
+            .first::<Vec<u8>, Vec<u8>>()
+            .ok()?
+            .map(|(k, v)| (decode_key::<T::Key>(&k), decode::<T::Value>(&v)))
+    }
+
+    /// Uses the MDBX table stats so does not need to scan.
+    fn approximate_len<T: Table>(&self) -> usize {
+        self.read_txn()
+            .and_then(|txn| Ok(txn.inner.db_stat_with_dbi(txn.get_dbi::<T>()?)?.entries()))
+            .unwrap_or_default()
+    }
 }
 
 pub struct MdbxIter<K, V>
@@ -323,6 +406,54 @@ where
     }
 }
 
+/// Iterate over the keys in a table in the range `[from, to)`.
+pub struct MdbxRangeIter<K, V>
+where
+    K: KeyT,
+    V: ValueT,
+{
+    cursor: Cursor<RO>,
+    /// The encoded start of the range, the cursor is positioned here on the first call to next.
+    from: Option<Vec<u8>>,
+    /// The encoded (exclusive) end of the range.
+    to: Vec<u8>,
+    _key: PhantomData<K>,
+    _val: PhantomData<V>,
+}
+
+impl<K, V> Iterator for MdbxRangeIter<K, V>
+where
+    K: KeyT,
+    V: ValueT,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = match self.from.take() {
+            // lands on the key or the first one greater than the key
+            Some(from) => self.cursor.set_range::<Vec<u8>, Vec<u8>>(&from),
+            None => self.cursor.next::<Vec<u8>, Vec<u8>>(),
+        };
+        match result {
+            Ok(Some((k, v))) if k < self.to => Some((decode_key::<K>(&k), decode::<V>(&v))),
+            _ => None,
+        }
+    }
+}
+
+/// Iterate over a table yielding the stored bytes without decoding them.
+pub struct MdbxRawIter {
+    cursor: Cursor<RO>,
+}
+
+impl Iterator for MdbxRawIter {
+    type Item = (Vec<u8>, Vec<u8>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cursor.next::<Vec<u8>, Vec<u8>>().ok().flatten()
+    }
+}
+
 pub struct MdbxRevIter<K, V>
 where
     K: KeyT,
@@ -399,6 +530,13 @@ mod test {
         test_skip(db)
     }
 
+    #[test]
+    fn test_mdbx_range_iter() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        test_range_iter(db)
+    }
+
     #[test]
     fn test_mdbx_skip_to_previous_simple() {
         let temp_dir = tempdir().expect("failed to create temp dir");
@@ -464,6 +602,80 @@ mod test {
         test_multi_remove(db)
     }
 
+    #[test]
+    fn test_mdbx_first_last_record() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        test_first_last_record(db)
+    }
+
+    #[test]
+    fn test_mdbx_approximate_len() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        test_approximate_len(db)
+    }
+
+    #[test]
+    fn test_mdbx_snapshot() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = crate::open_mdbx_tables(temp_dir.path()).expect("Cannot open database");
+        test_snapshot(db)
+    }
+
+    #[test]
+    fn test_mdbx_insert_if_absent() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        test_insert_if_absent(db)
+    }
+
+    #[test]
+    fn test_mdbx_insert_checked() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        test_insert_checked(db)
+    }
+
+    #[test]
+    fn test_mdbx_verify_table() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        test_verify_table(db)
+    }
+
+    #[test]
+    fn test_mdbx_iter_checked() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        test_iter_checked(db)
+    }
+
+    #[test]
+    fn test_mdbx_export_table_ndjson() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        test_export_table_ndjson(db)
+    }
+
+    /// MDBX only sets durability when opened, so each durability is its own open.
+    #[test]
+    fn test_mdbx_durability_reopen() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        test_open_durability_reopen(|durability| {
+            let db = MdbxDatabase::open_with_durability(temp_dir.path(), durability)?;
+            db.open_table::<TestTable>()?;
+            Ok(db)
+        })
+    }
+
+    #[test]
+    fn test_mdbx_raw_copy() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        test_raw_copy(db)
+    }
+
     #[test]
     fn test_mdbx_dbsimpbench() {
         // Init a DB
@@ -15,7 +15,10 @@ use dashmap::DashMap;
 use ouroboros::self_referencing;
 use parking_lot::{RwLock, RwLockReadGuard};
 use prometheus::{default_registry, register_int_gauge_with_registry, IntGauge, Registry};
-use tn_types::{decode, decode_key, encode, encode_key, DBIter, Database, DbTx, DbTxMut, Table};
+use tn_types::{
+    decode, decode_key, encode, encode_key, ensure_value_size, DBIter, DBIterRaw, Database,
+    DbSnapshot, DbTx, DbTxMut, Table,
+};
 
 type StoreType = DashMap<&'static str, Arc<RwLock<BTreeMap<Vec<u8>, Vec<u8>>>>>;
 
@@ -57,11 +60,19 @@ impl DbTxMut for MemDbTxMut {
         if let Some(table) = self.store.get(T::NAME) {
             let key_bytes = encode_key(key);
             let value_bytes = encode(value);
+            ensure_value_size::<T>(value_bytes.len())?;
             table.write().insert(key_bytes, value_bytes);
         }
         Ok(())
     }
 
+    fn insert_raw<T: Table>(&mut self, key: &[u8], value: &[u8]) -> eyre::Result<()> {
+        if let Some(table) = self.store.get(T::NAME) {
+            table.write().insert(key.to_vec(), value.to_vec());
+        }
+        Ok(())
+    }
+
     fn remove<T: Table>(&mut self, key: &T::Key) -> eyre::Result<()> {
         if let Some(table) = self.store.get(T::NAME) {
             let key_bytes = encode_key(key);
@@ -83,6 +94,32 @@ impl DbTxMut for MemDbTxMut {
     }
 }
 
+/// A copy of the in-memory tables taken while holding all the table locks.
+#[derive(Clone, Debug)]
+pub struct MemDbSnapshot {
+    store: HashMap<&'static str, BTreeMap<Vec<u8>, Vec<u8>>>,
+}
+
+impl DbTx for MemDbSnapshot {
+    fn get<T: Table>(&self, key: &T::Key) -> eyre::Result<Option<T::Value>> {
+        Ok(self.store.get(T::NAME).and_then(|table| table.get(&encode_key(key)).map(|v| decode(v))))
+    }
+}
+
+impl DbSnapshot for MemDbSnapshot {
+    fn iter<T: Table>(&self) -> DBIter<'_, T> {
+        if let Some(table) = self.store.get(T::NAME) {
+            Box::new(table.iter().map(|(key_bytes, value_bytes)| {
+                let key = decode_key(key_bytes);
+                let value = decode(value_bytes);
+                (key, value)
+            }))
+        } else {
+            panic!("Invalid table {}", T::NAME);
+        }
+    }
+}
+
 /// Implement the Database trait with an in-memory store.
 /// This means no persistance.
 /// This DB also plays loose with transactions, but since it is in-memory and we do not do
@@ -165,8 +202,10 @@ impl Default for MemDatabase {
         db.open_table::<crate::tables::CertificateDigestByOrigin>();
         db.open_table::<crate::tables::Payload>();
         db.open_table::<crate::tables::Batches>();
+        db.open_table::<crate::tables::BatchReceivedAt>();
         db.open_table::<crate::tables::ConsensusBlocks>();
         db.open_table::<crate::tables::ConsensusBlockNumbersByDigest>();
+        db.open_table::<crate::tables::LastCommitted>();
         db
     }
 }
@@ -182,6 +221,11 @@ impl Database for MemDatabase {
     where
         Self: 'txn;
 
+    type Snapshot<'snap>
+        = MemDbSnapshot
+    where
+        Self: 'snap;
+
     fn read_txn(&self) -> eyre::Result<Self::TX<'_>> {
         Ok(MemDbTx { store: (*self.store).clone() })
     }
@@ -190,6 +234,15 @@ impl Database for MemDatabase {
         Ok(MemDbTxMut { store: (*self.store).clone() })
     }
 
+    /// There is no MVCC for the in-memory store so this copies every table.  All the table read
+    /// locks are held while copying so the snapshot is consistent across tables.
+    fn snapshot(&self) -> eyre::Result<Self::Snapshot<'_>> {
+        let tables: Vec<_> = self.store.iter().map(|kv| (*kv.key(), kv.value().clone())).collect();
+        let guards: Vec<_> = tables.iter().map(|(name, table)| (*name, table.read())).collect();
+        let store = guards.iter().map(|(name, guard)| (*name, (**guard).clone())).collect();
+        Ok(MemDbSnapshot { store })
+    }
+
     fn contains_key<T: Table>(&self, key: &T::Key) -> eyre::Result<bool> {
         if let Some(table) = self.store.get(T::NAME) {
             let key_bytes = encode_key(key);
@@ -206,6 +259,7 @@ impl Database for MemDatabase {
         if let Some(table) = self.store.get(T::NAME) {
             let key_bytes = encode_key(key);
             let value_bytes = encode(value);
+            ensure_value_size::<T>(value_bytes.len())?;
             table.write().insert(key_bytes, value_bytes);
         }
         Ok(())
@@ -259,6 +313,31 @@ impl Database for MemDatabase {
         }
     }
 
+    fn iter_raw<T: Table>(&self) -> DBIterRaw<'_> {
+        if let Some(table) = self.store.get(T::NAME) {
+            Box::new(MemDBRawIter(
+                MemDBIterBuilder {
+                    table: TabAndGuardBuilder {
+                        table: table.clone(),
+                        guard_builder: |table| table.read(),
+                        casper: PhantomData::<T>,
+                    }
+                    .build(),
+                    iter_builder: |table: &'_ TabAndGuard<T>| {
+                        table.with(|fields| {
+                            let iter = Box::new(fields.guard.iter());
+                            iter
+                        })
+                    },
+                    casper: PhantomData::<T>,
+                }
+                .build(),
+            ))
+        } else {
+            panic!("Invalid table {}", T::NAME);
+        }
+    }
+
     fn skip_to<T: Table>(&self, key: &T::Key) -> eyre::Result<DBIter<'_, T>> {
         if let Some(table) = self.store.get(T::NAME) {
             Ok(Box::new(
@@ -287,6 +366,34 @@ impl Database for MemDatabase {
         }
     }
 
+    fn range_iter<T: Table>(&self, from: &T::Key, to: &T::Key) -> DBIter<'_, T> {
+        if let Some(table) = self.store.get(T::NAME) {
+            Box::new(
+                MemDBIterBuilder {
+                    table: TabAndGuardBuilder {
+                        table: table.clone(),
+                        guard_builder: |table| table.read(),
+                        casper: PhantomData::<T>,
+                    }
+                    .build(),
+                    iter_builder: |table: &'_ TabAndGuard<T>| {
+                        table.with(|fields| {
+                            let from_bytes = encode_key(from);
+                            // BTreeMap panics if the range end is before the start
+                            let to_bytes = encode_key(to).max(from_bytes.clone());
+                            let iter = Box::new(fields.guard.range(from_bytes..to_bytes));
+                            iter
+                        })
+                    },
+                    casper: PhantomData::<T>,
+                }
+                .build(),
+            )
+        } else {
+            panic!("Invalid table {}", T::NAME);
+        }
+    }
+
     fn reverse_iter<T: Table>(&self) -> DBIter<'_, T> {
         if let Some(table) = self.store.get(T::NAME) {
             Box::new(
@@ -344,6 +451,26 @@ impl Database for MemDatabase {
             None
         }
     }
+
+    fn first_record<T: Table>(&self) -> Option<(T::Key, T::Value)> {
+        if let Some(table) = self.store.get(T::NAME) {
+            table.read().first_key_value().map(|(key_bytes, value_bytes)| {
+                let key = decode_key(key_bytes);
+                let value = decode(value_bytes);
+                (key, value)
+            })
+        } else {
+            None
+        }
+    }
+
+    fn approximate_len<T: Table>(&self) -> usize {
+        if let Some(table) = self.store.get(T::NAME) {
+            table.read().len()
+        } else {
+            0
+        }
+    }
 }
 
 #[self_referencing]
@@ -384,6 +511,22 @@ impl<T: Table> Iterator for MemDBIter<T> {
     }
 }
 
+/// Wraps a [`MemDBIter`] to yield the stored bytes without decoding them.
+pub struct MemDBRawIter<T: Table>(MemDBIter<T>);
+
+impl<T: Table> Iterator for MemDBRawIter<T> {
+    type Item = (Vec<u8>, Vec<u8>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.with_mut(|fields| {
+            fields
+                .iter
+                .next()
+                .map(|(key_bytes, value_bytes)| (key_bytes.clone(), value_bytes.clone()))
+        })
+    }
+}
+
 #[derive(Debug)]
 struct MemDBMetrics {
     table_counts: HashMap<&'static str, IntGauge>,
@@ -449,6 +592,12 @@ mod test {
         test_skip(db)
     }
 
+    #[test]
+    fn test_memdb_range_iter() {
+        let db = open_db();
+        test_range_iter(db)
+    }
+
     #[test]
     fn test_memdb_skip_to_previous_simple() {
         let db = open_db();
@@ -505,6 +654,66 @@ mod test {
         test_multi_remove(db)
     }
 
+    #[test]
+    fn test_memdb_first_last_record() {
+        let db = open_db();
+        test_first_last_record(db)
+    }
+
+    #[test]
+    fn test_memdb_approximate_len() {
+        let db = open_db();
+        test_approximate_len(db)
+    }
+
+    #[test]
+    fn test_memdb_snapshot() {
+        test_snapshot(MemDatabase::default())
+    }
+
+    #[test]
+    fn test_memdb_insert_if_absent() {
+        let db = open_db();
+        test_insert_if_absent(db)
+    }
+
+    #[test]
+    fn test_memdb_insert_checked() {
+        let db = open_db();
+        test_insert_checked(db)
+    }
+
+    #[test]
+    fn test_memdb_value_size_limit() {
+        let db = open_db();
+        db.open_table::<LimitedTestTable>();
+        test_value_size_limit(db)
+    }
+
+    #[test]
+    fn test_memdb_verify_table() {
+        let db = open_db();
+        test_verify_table(db)
+    }
+
+    #[test]
+    fn test_memdb_iter_checked() {
+        let db = open_db();
+        test_iter_checked(db)
+    }
+
+    #[test]
+    fn test_memdb_export_table_ndjson() {
+        let db = open_db();
+        test_export_table_ndjson(db)
+    }
+
+    #[test]
+    fn test_memdb_raw_copy() {
+        let db = open_db();
+        test_raw_copy(db)
+    }
+
     #[test]
     fn test_memdb_dbsimpbench() {
         // Init a DB
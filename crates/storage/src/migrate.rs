@@ -0,0 +1,193 @@
+//! Copy the node tables from one DB backend to another.
+//! Used by operators switching backends (for instance RocksDB to MDBX).
+
+use std::{path::Path, str::FromStr};
+
+use tn_types::{Database, DbTxMut, Table};
+
+#[cfg(feature = "reth-libmdbx")]
+use crate::open_mdbx_tables;
+#[cfg(feature = "rocksdb")]
+use crate::rocks::database::RocksDatabase;
+use crate::{
+    open_redb_tables,
+    tables::{
+        BatchReceivedAt, Batches, CertificateDigestByOrigin, CertificateDigestByRound,
+        Certificates, ConsensusBlockNumbersByDigest, ConsensusBlocks, LastCommitted, LastProposed,
+        Payload, Votes,
+    },
+};
+
+/// The DB backends a node can store it's data in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DbBackend {
+    Mdbx,
+    ReDb,
+    RocksDb,
+}
+
+impl FromStr for DbBackend {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mdbx" => Ok(Self::Mdbx),
+            "redb" => Ok(Self::ReDb),
+            "rocksdb" | "rocks" => Ok(Self::RocksDb),
+            _ => Err(eyre::eyre!("Unknown DB backend {s}, expected mdbx, redb or rocksdb")),
+        }
+    }
+}
+
+/// Copy table T from src to dst in a single dst write txn.
+/// Any existing data in the dst table is removed first.
+/// Returns the number of rows copied.
+fn migrate_table<T: Table>(src: &impl Database, dst: &impl Database) -> eyre::Result<usize> {
+    let mut rows = 0;
+    let mut txn = dst.write_txn()?;
+    txn.clear_table::<T>()?;
+    for (key, value) in src.iter_raw::<T>() {
+        txn.insert_raw::<T>(&key, &value)?;
+        rows += 1;
+    }
+    txn.commit()?;
+    tracing::info!(target: "telcoin::storage", "Migrated {rows} rows for table {}", T::NAME);
+    Ok(rows)
+}
+
+/// Copy every table the node uses from src to dst.
+/// The dst tables will be replaced with the contents of src.
+pub fn migrate_db(src: &impl Database, dst: &impl Database) -> eyre::Result<()> {
+    migrate_table::<LastProposed>(src, dst)?;
+    migrate_table::<Votes>(src, dst)?;
+    migrate_table::<Certificates>(src, dst)?;
+    migrate_table::<CertificateDigestByRound>(src, dst)?;
+    migrate_table::<CertificateDigestByOrigin>(src, dst)?;
+    migrate_table::<Payload>(src, dst)?;
+    migrate_table::<Batches>(src, dst)?;
+    migrate_table::<BatchReceivedAt>(src, dst)?;
+    migrate_table::<ConsensusBlocks>(src, dst)?;
+    migrate_table::<ConsensusBlockNumbersByDigest>(src, dst)?;
+    migrate_table::<LastCommitted>(src, dst)?;
+    Ok(())
+}
+
+/// Open the DBs at src_path and dst_path with the provided backends and copy all of src into dst.
+/// This is the entry point for the CLI.
+pub fn migrate_db_paths<P: AsRef<Path>>(
+    src_backend: DbBackend,
+    src_path: P,
+    dst_backend: DbBackend,
+    dst_path: P,
+) -> eyre::Result<()> {
+    match src_backend {
+        #[cfg(feature = "reth-libmdbx")]
+        DbBackend::Mdbx => migrate_to(&open_mdbx_tables(src_path)?, dst_backend, dst_path),
+        DbBackend::ReDb => migrate_to(&open_redb_tables(src_path)?, dst_backend, dst_path),
+        #[cfg(feature = "rocksdb")]
+        DbBackend::RocksDb => migrate_to(&RocksDatabase::open_db(src_path)?, dst_backend, dst_path),
+        #[allow(unreachable_patterns)]
+        _ => Err(eyre::eyre!("DB backend {src_backend:?} is not enabled in this build")),
+    }
+}
+
+/// Open the dst DB and migrate src into it.
+fn migrate_to<P: AsRef<Path>>(
+    src: &impl Database,
+    dst_backend: DbBackend,
+    dst_path: P,
+) -> eyre::Result<()> {
+    match dst_backend {
+        #[cfg(feature = "reth-libmdbx")]
+        DbBackend::Mdbx => migrate_db(src, &open_mdbx_tables(dst_path)?),
+        DbBackend::ReDb => migrate_db(src, &open_redb_tables(dst_path)?),
+        #[cfg(feature = "rocksdb")]
+        DbBackend::RocksDb => migrate_db(src, &RocksDatabase::open_db(dst_path)?),
+        #[allow(unreachable_patterns)]
+        _ => Err(eyre::eyre!("DB backend {dst_backend:?} is not enabled in this build")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{migrate_db, DbBackend};
+    use crate::{mem_db::MemDatabase, open_redb_tables, tables::*};
+    use tempfile::tempdir;
+    use tn_types::{
+        AuthorityIdentifier, Batch, BlockHash, Certificate, CertificateDigest, ConsensusHeader,
+        Database, DbTxMut, Header, Table, VoteDigest, VoteInfo,
+    };
+
+    fn populate<DB: Database>(db: &DB) {
+        let mut txn = db.write_txn().unwrap();
+        for i in 0..10_u8 {
+            let authority = AuthorityIdentifier::dummy_for_test(i);
+            let digest = CertificateDigest::new([i; 32]);
+            let hash = BlockHash::repeat_byte(i);
+            txn.insert::<LastProposed>(&(i as u32), &Header::default()).unwrap();
+            txn.insert::<Votes>(
+                &authority,
+                &VoteInfo { epoch: 0, round: i as u32, vote_digest: VoteDigest::default() },
+            )
+            .unwrap();
+            txn.insert::<Certificates>(&digest, &Certificate::default()).unwrap();
+            txn.insert::<CertificateDigestByRound>(&(i as u32, authority.clone()), &digest)
+                .unwrap();
+            txn.insert::<LastCommitted>(&authority, &(i as u32)).unwrap();
+            txn.insert::<CertificateDigestByOrigin>(&(authority, i as u32), &digest).unwrap();
+            txn.insert::<Payload>(&(hash, i as u16), &0).unwrap();
+            txn.insert::<Batches>(&hash, &Batch::default()).unwrap();
+            txn.insert::<BatchReceivedAt>(&hash, &(i as u64)).unwrap();
+            txn.insert::<ConsensusBlocks>(&(i as u64), &ConsensusHeader::default()).unwrap();
+            txn.insert::<ConsensusBlockNumbersByDigest>(&hash, &(i as u64)).unwrap();
+        }
+        txn.commit().unwrap();
+    }
+
+    fn assert_table_eq<T: Table>(src: &impl Database, dst: &impl Database) {
+        let src_rows: Vec<_> = src.iter_raw::<T>().collect();
+        assert_eq!(src_rows.len(), 10, "table {} not populated", T::NAME);
+        assert_eq!(src_rows, dst.iter_raw::<T>().collect::<Vec<_>>(), "table {}", T::NAME);
+    }
+
+    fn assert_tables_eq(src: &impl Database, dst: &impl Database) {
+        assert_table_eq::<LastProposed>(src, dst);
+        assert_table_eq::<Votes>(src, dst);
+        assert_table_eq::<Certificates>(src, dst);
+        assert_table_eq::<CertificateDigestByRound>(src, dst);
+        assert_table_eq::<CertificateDigestByOrigin>(src, dst);
+        assert_table_eq::<Payload>(src, dst);
+        assert_table_eq::<Batches>(src, dst);
+        assert_table_eq::<BatchReceivedAt>(src, dst);
+        assert_table_eq::<ConsensusBlocks>(src, dst);
+        assert_table_eq::<ConsensusBlockNumbersByDigest>(src, dst);
+        assert_table_eq::<LastCommitted>(src, dst);
+    }
+
+    #[test]
+    fn test_migrate_db() {
+        let src = MemDatabase::default();
+        populate(&src);
+
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let redb = open_redb_tables(temp_dir.path()).expect("failed to open redb");
+        migrate_db(&src, &redb).expect("failed to migrate to redb");
+        assert_tables_eq(&src, &redb);
+
+        #[cfg(feature = "reth-libmdbx")]
+        {
+            let temp_dir = tempdir().expect("failed to create temp dir");
+            let mdbx = crate::open_mdbx_tables(temp_dir.path()).expect("failed to open mdbx");
+            migrate_db(&redb, &mdbx).expect("failed to migrate redb to mdbx");
+            assert_tables_eq(&src, &mdbx);
+        }
+    }
+
+    #[test]
+    fn test_db_backend_from_str() {
+        assert_eq!("mdbx".parse::<DbBackend>().unwrap(), DbBackend::Mdbx);
+        assert_eq!("ReDB".parse::<DbBackend>().unwrap(), DbBackend::ReDb);
+        assert_eq!("rocksdb".parse::<DbBackend>().unwrap(), DbBackend::RocksDb);
+        assert!("sled".parse::<DbBackend>().is_err());
+    }
+}
@@ -0,0 +1,119 @@
+//! Warm up hot tables when the node starts.
+//!
+//! Cold starts read certificates and batches from disk lazily, which causes latency spikes during
+//! the first rounds after boot. Preloading sequentially iterates the configured tables so their
+//! pages are cached before consensus starts reading them. Rows are read with
+//! [`Database::iter_raw`], so the layered DB reads them from its persistent DB instead of memory.
+
+use tn_types::{Database, Table};
+
+use crate::{
+    tables::{
+        BatchReceivedAt, Batches, CertificateDigestByOrigin, CertificateDigestByRound,
+        Certificates, ConsensusBlockNumbersByDigest, ConsensusBlocks, LastCommitted, LastProposed,
+        Payload, Votes,
+    },
+    BATCHES_CF, BATCH_RECEIVED_AT_CF, CERTIFICATES_CF, CERTIFICATE_DIGEST_BY_ORIGIN_CF,
+    CERTIFICATE_DIGEST_BY_ROUND_CF, CONSENSUS_BLOCK_CF, CONSENSUS_BLOCK_NUMBER_BY_DIGEST_CF,
+    LAST_COMMITTED_CF, LAST_PROPOSED_CF, PAYLOAD_CF, VOTES_CF,
+};
+
+/// Preload the node tables by name.
+pub trait PreloadTables {
+    /// Sequentially read every row of the named tables.
+    ///
+    /// Tables are preloaded in the order provided. Returns the total number of rows read or an
+    /// error if a table name is unknown.
+    fn preload_tables(&self, tables: &[&str]) -> eyre::Result<usize>;
+}
+
+impl<DB: Database> PreloadTables for DB {
+    fn preload_tables(&self, tables: &[&str]) -> eyre::Result<usize> {
+        let mut rows = 0;
+        for table in tables {
+            rows += match *table {
+                LAST_PROPOSED_CF => preload_table::<LastProposed>(self),
+                VOTES_CF => preload_table::<Votes>(self),
+                CERTIFICATES_CF => preload_table::<Certificates>(self),
+                CERTIFICATE_DIGEST_BY_ROUND_CF => preload_table::<CertificateDigestByRound>(self),
+                CERTIFICATE_DIGEST_BY_ORIGIN_CF => preload_table::<CertificateDigestByOrigin>(self),
+                PAYLOAD_CF => preload_table::<Payload>(self),
+                BATCHES_CF => preload_table::<Batches>(self),
+                BATCH_RECEIVED_AT_CF => preload_table::<BatchReceivedAt>(self),
+                CONSENSUS_BLOCK_CF => preload_table::<ConsensusBlocks>(self),
+                CONSENSUS_BLOCK_NUMBER_BY_DIGEST_CF => {
+                    preload_table::<ConsensusBlockNumbersByDigest>(self)
+                }
+                LAST_COMMITTED_CF => preload_table::<LastCommitted>(self),
+                _ => return Err(eyre::eyre!("Unknown table {table}, unable to preload")),
+            };
+        }
+        Ok(rows)
+    }
+}
+
+/// Read the stored bytes of every row of table T without decoding them.
+/// Returns the number of rows read.
+fn preload_table<T: Table>(db: &impl Database) -> usize {
+    let start = std::time::Instant::now();
+    let rows = db.iter_raw::<T>().count();
+    tracing::info!(target: "telcoin::storage", "Preloaded {rows} rows for table {} in {:?}", T::NAME, start.elapsed());
+    rows
+}
+
+#[cfg(test)]
+mod test {
+    use super::PreloadTables;
+    use crate::{mem_db::MemDatabase, open_db, open_redb_tables, tables::*};
+    use tempfile::tempdir;
+    use tn_types::{BlockHash, Database, Table};
+
+    fn assert_preload_reads_every_row<DB: Database>(db: DB) {
+        for number in 0..10 {
+            db.insert::<ConsensusBlockNumbersByDigest>(&BlockHash::with_last_byte(number), &0)
+                .unwrap();
+        }
+        for number in 0..5 {
+            db.insert::<BatchReceivedAt>(&BlockHash::with_last_byte(number), &0).unwrap();
+        }
+
+        // every row of each table is read
+        assert_eq!(db.preload_tables(&[ConsensusBlockNumbersByDigest::NAME]).unwrap(), 10);
+        assert_eq!(
+            db.preload_tables(&[ConsensusBlockNumbersByDigest::NAME, BatchReceivedAt::NAME])
+                .unwrap(),
+            15
+        );
+
+        // empty tables and no tables
+        assert_eq!(db.preload_tables(&[Certificates::NAME]).unwrap(), 0);
+        assert_eq!(db.preload_tables(&[]).unwrap(), 0);
+
+        // unknown tables are an error
+        assert!(db.preload_tables(&["not_a_table"]).is_err());
+    }
+
+    #[test]
+    fn test_preload_reads_every_row() {
+        assert_preload_reads_every_row(MemDatabase::default());
+
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        assert_preload_reads_every_row(open_redb_tables(temp_dir.path()).expect("open redb"));
+    }
+
+    #[test]
+    fn test_preload_reads_node_storage_on_startup() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        for number in 0..10 {
+            db.insert::<ConsensusBlockNumbersByDigest>(&BlockHash::with_last_byte(number), &0)
+                .unwrap();
+        }
+        drop(db);
+
+        // the rows persisted before the restart are read from the persistent DB
+        let db = open_db(temp_dir.path());
+        assert_eq!(db.preload_tables(&[ConsensusBlockNumbersByDigest::NAME]).unwrap(), 10);
+        assert_eq!(db.preload_tables(&[Certificates::NAME]).unwrap(), 0);
+    }
+}
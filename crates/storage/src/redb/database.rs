@@ -17,11 +17,14 @@ use redb::{
     TableDefinition, WriteTransaction,
 };
 
-use tn_types::{DBIter, Database, DbTx, DbTxMut, KeyT, Table, ValueT};
+use tn_types::{
+    encode, ensure_value_size, DBIter, DBIterRaw, Database, DbSnapshot, DbTx, DbTxMut, Durability,
+    KeyT, Table, ValueT,
+};
 
 use super::{
     metrics::ReDbMetrics,
-    wraps::{KeyWrap, ValWrap},
+    wraps::{KeyWrap, RawKeyWrap, RawValWrap, ValWrap},
 };
 
 #[derive(Debug)]
@@ -36,6 +39,32 @@ impl DbTx for ReDbTx {
     }
 }
 
+/// ReDB read transactions are already a consistent snapshot of the DB.
+impl DbSnapshot for ReDbTx {
+    fn iter<T: Table>(&self) -> DBIter<'_, T> {
+        let td = TableDefinition::<KeyWrap<T::Key>, ValWrap<T::Value>>::new(T::NAME);
+        Box::new(
+            ReDBTableIterBuilder {
+                table: self
+                    .tx
+                    .open_table(td)
+                    .expect("Missing table, DB not configured/opened correctly"),
+                iter_builder: |table: &ReadOnlyTable<KeyWrap<T::Key>, ValWrap<T::Value>>| {
+                    Box::new(
+                        table.iter().expect("Unable to get a DB iter").filter(|r| r.is_ok()).map(
+                            |r| {
+                                let (k, v) = r.expect("row is okay");
+                                (k.value().clone(), v.value().clone())
+                            },
+                        ),
+                    )
+                },
+            }
+            .build(),
+        )
+    }
+}
+
 pub struct ReDbTxMut {
     tx: WriteTransaction,
 }
@@ -55,11 +84,23 @@ impl DbTx for ReDbTxMut {
 
 impl DbTxMut for ReDbTxMut {
     fn insert<T: Table>(&mut self, key: &T::Key, value: &T::Value) -> eyre::Result<()> {
+        // ReDb encodes through the table wrappers so only encode here if the table has a limit.
+        if T::MAX_VALUE_BYTES.is_some() {
+            ensure_value_size::<T>(encode(value).len())?;
+        }
         let td = TableDefinition::<KeyWrap<T::Key>, ValWrap<T::Value>>::new(T::NAME);
         self.tx.open_table(td)?.insert(key, value)?;
         Ok(())
     }
 
+    /// The bytes are stored through the raw table wrappers (see wraps.rs) without decoding them.
+    fn insert_raw<T: Table>(&mut self, key: &[u8], value: &[u8]) -> eyre::Result<()> {
+        ensure_value_size::<T>(value.len())?;
+        let td = TableDefinition::<RawKeyWrap<T::Key>, RawValWrap<T::Value>>::new(T::NAME);
+        self.tx.open_table(td)?.insert(key, value)?;
+        Ok(())
+    }
+
     fn remove<T: Table>(&mut self, key: &T::Key) -> eyre::Result<()> {
         let td = TableDefinition::<KeyWrap<T::Key>, ValWrap<T::Value>>::new(T::NAME);
         self.tx.open_table(td)?.remove(key)?;
@@ -86,6 +127,20 @@ impl DbTxMut for ReDbTxMut {
 pub struct ReDB {
     db: Arc<RwLock<ReDatabase>>,
     shutdown_tx: SyncSender<()>,
+    /// Durability of write txns that don't set their own.
+    durability: Durability,
+}
+
+/// Map durability to the ReDB setting.
+///
+/// ReDB only persists a [`Durability::None`] commit once a later commit is persisted, it will be
+/// lost if the DB is reopened first.
+fn redb_durability(durability: Durability) -> redb::Durability {
+    match durability {
+        Durability::None => redb::Durability::None,
+        Durability::Eventual => redb::Durability::Eventual,
+        Durability::Sync => redb::Durability::Immediate,
+    }
 }
 
 impl Drop for ReDB {
@@ -103,6 +158,14 @@ impl Drop for ReDB {
 
 impl ReDB {
     pub fn open<P: AsRef<Path>>(path: P) -> eyre::Result<ReDB> {
+        Self::open_with_durability(path, Durability::Sync)
+    }
+
+    /// Open the DB with `durability` as the default for write txns.
+    pub fn open_with_durability<P: AsRef<Path>>(
+        path: P,
+        durability: Durability,
+    ) -> eyre::Result<ReDB> {
         let db = Arc::new(RwLock::new(ReDatabase::create(path.as_ref().join("redb"))?));
         let db_cloned = Arc::clone(&db);
         let (shutdown_tx, rx) = mpsc::sync_channel::<()>(0);
@@ -148,7 +211,7 @@ impl ReDB {
             tracing::info!(target: "telcoin::redb", "Ending ReDb metrics thread");
         });
 
-        Ok(ReDB { db, shutdown_tx })
+        Ok(ReDB { db, shutdown_tx, durability })
     }
 
     pub fn open_table<T: Table>(&self) -> eyre::Result<()> {
@@ -163,6 +226,7 @@ impl ReDB {
 impl Database for ReDB {
     type TX<'txn> = ReDbTx;
     type TXMut<'txn> = ReDbTxMut;
+    type Snapshot<'snap> = ReDbTx;
 
     fn read_txn(&self) -> eyre::Result<Self::TX<'_>> {
         let tx = self.db.read().begin_read()?;
@@ -173,10 +237,19 @@ impl Database for ReDB {
     /// open will block until it closes.  This can be problematic when used directly in async
     /// code.  Note that the LayeredDatabase handles this issues.
     fn write_txn(&self) -> eyre::Result<Self::TXMut<'_>> {
-        let tx = self.db.read().begin_write()?;
+        self.write_txn_with_durability(self.durability)
+    }
+
+    fn write_txn_with_durability(&self, durability: Durability) -> eyre::Result<Self::TXMut<'_>> {
+        let mut tx = self.db.read().begin_write()?;
+        tx.set_durability(redb_durability(durability));
         Ok(ReDbTxMut { tx })
     }
 
+    fn snapshot(&self) -> eyre::Result<Self::Snapshot<'_>> {
+        self.read_txn()
+    }
+
     fn contains_key<T: Table>(&self, key: &T::Key) -> eyre::Result<bool> {
         self.read_txn()?.contains_key::<T>(key)
     }
@@ -241,6 +314,35 @@ impl Database for ReDB {
         )
     }
 
+    /// Rows are read through the raw table wrappers (see wraps.rs) so they are never decoded.
+    fn iter_raw<T: Table>(&self) -> DBIterRaw<'_> {
+        let td = TableDefinition::<RawKeyWrap<T::Key>, RawValWrap<T::Value>>::new(T::NAME);
+        let guard = self.db.read();
+        Box::new(
+            ReDBRawIterBuilder {
+                guard,
+                table_builder: |guard: &mut RwLockReadGuard<'_, ReDatabase>| {
+                    guard
+                        .begin_read()
+                        .expect("Failed to get read txn, DB broken")
+                        .open_table(td)
+                        .expect("Missing table, DB not configured/opened correctly")
+                },
+                iter_builder: |table: &ReadOnlyTable<RawKeyWrap<T::Key>, RawValWrap<T::Value>>| {
+                    Box::new(
+                        table.iter().expect("Unable to get a DB iter").filter(|r| r.is_ok()).map(
+                            |r| {
+                                let (k, v) = r.expect("row is okay");
+                                (k.value().to_vec(), v.value().to_vec())
+                            },
+                        ),
+                    )
+                },
+            }
+            .build(),
+        )
+    }
+
     fn skip_to<T: Table>(&self, key: &T::Key) -> eyre::Result<DBIter<'_, T>> {
         let td = TableDefinition::<KeyWrap<T::Key>, ValWrap<T::Value>>::new(T::NAME);
         let guard = self.db.read();
@@ -273,6 +375,39 @@ impl Database for ReDB {
         ))
     }
 
+    fn range_iter<T: Table>(&self, from: &T::Key, to: &T::Key) -> DBIter<'_, T> {
+        let td = TableDefinition::<KeyWrap<T::Key>, ValWrap<T::Value>>::new(T::NAME);
+        let guard = self.db.read();
+        let from = from.clone();
+        // an empty range if the end is before the start
+        let to = if to < &from { from.clone() } else { to.clone() };
+        Box::new(
+            ReDBIterBuilder {
+                guard,
+                table_builder: |guard: &mut RwLockReadGuard<'_, ReDatabase>| {
+                    guard
+                        .begin_read()
+                        .expect("Failed to get read txn, DB broken")
+                        .open_table(td)
+                        .expect("Missing table, DB not configured/opened correctly")
+                },
+                iter_builder: |table: &ReadOnlyTable<KeyWrap<T::Key>, ValWrap<T::Value>>| {
+                    Box::new(
+                        table
+                            .range::<T::Key>(from..to)
+                            .expect("Unable to get a DB range iter")
+                            .filter(|r| r.is_ok())
+                            .map(|r| {
+                                let (k, v) = r.expect("row is okay");
+                                (k.value().clone(), v.value().clone())
+                            }),
+                    )
+                },
+            }
+            .build(),
+        )
+    }
+
     fn reverse_iter<T: Table>(&self) -> DBIter<'_, T> {
         let td = TableDefinition::<KeyWrap<T::Key>, ValWrap<T::Value>>::new(T::NAME);
         let guard = self.db.read();
@@ -324,6 +459,22 @@ impl Database for ReDB {
         read_table.last().ok().flatten().map(|(k, v)| (k.value().clone(), v.value().clone()))
     }
 
+    fn first_record<T: Table>(&self) -> Option<(T::Key, T::Value)> {
+        let td = TableDefinition::<KeyWrap<T::Key>, ValWrap<T::Value>>::new(T::NAME);
+        let read_table = self.db.read().begin_read().ok()?.open_table(td).ok()?;
+        read_table.first().ok().flatten().map(|(k, v)| (k.value().clone(), v.value().clone()))
+    }
+
+    fn approximate_len<T: Table>(&self) -> usize {
+        let td = TableDefinition::<KeyWrap<T::Key>, ValWrap<T::Value>>::new(T::NAME);
+        if let Ok(txn) = self.read_txn() {
+            if let Ok(table) = txn.tx.open_table(td) {
+                return table.len().unwrap_or_default() as usize;
+            }
+        }
+        0
+    }
+
     fn compact(&self) -> eyre::Result<()> {
         self.db.write().compact()?;
         Ok(())
@@ -356,13 +507,68 @@ where
     }
 }
 
+/// Iterates the encoded bytes of a table's rows, see [`Database::iter_raw`].
+#[self_referencing(pub_extras)]
+pub struct ReDBRawIter<'a, K, V>
+where
+    K: KeyT,
+    V: ValueT,
+{
+    guard: RwLockReadGuard<'a, ReDatabase>,
+    #[borrows(mut guard)]
+    table: ReadOnlyTable<RawKeyWrap<K>, RawValWrap<V>>,
+    #[borrows(table)]
+    #[covariant]
+    iter: Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'this>,
+}
+
+impl<K, V> Iterator for ReDBRawIter<'_, K, V>
+where
+    K: KeyT,
+    V: ValueT,
+{
+    type Item = (Vec<u8>, Vec<u8>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.with_mut(|fields| fields.iter.next())
+    }
+}
+
+#[self_referencing(pub_extras)]
+pub struct ReDBTableIter<K, V>
+where
+    K: KeyT,
+    V: ValueT,
+{
+    table: ReadOnlyTable<KeyWrap<K>, ValWrap<V>>,
+    #[borrows(table)]
+    #[covariant]
+    iter: Box<dyn Iterator<Item = (K, V)> + 'this>,
+}
+
+impl<K, V> Iterator for ReDBTableIter<K, V>
+where
+    K: KeyT,
+    V: ValueT,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.with_mut(|fields| fields.iter.next())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::path::Path;
 
     use tempfile::tempdir;
 
-    use crate::test::{db_simp_bench, TestTable};
+    use crate::test::{
+        db_simp_bench, test_approximate_len, test_durability_reopen, test_first_last_record,
+        test_insert_checked, test_insert_if_absent, test_iter_checked, test_range_iter,
+        test_raw_copy, test_snapshot, test_verify_table, TestTable,
+    };
 
     use tn_types::{Database, DbTxMut};
 
@@ -414,6 +620,13 @@ mod test {
         assert_eq!(result[2], None);
     }
 
+    #[test]
+    fn test_redb_range_iter() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        test_range_iter(db)
+    }
+
     #[test]
     fn test_redb_skip() {
         let temp_dir = tempdir().expect("failed to create temp dir");
@@ -608,6 +821,90 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_redb_first_last_record() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        test_first_last_record(db)
+    }
+
+    #[test]
+    fn test_redb_approximate_len() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        test_approximate_len(db)
+    }
+
+    #[test]
+    fn test_redb_snapshot() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = crate::open_redb_tables(temp_dir.path()).expect("Cannot open database");
+        test_snapshot(db)
+    }
+
+    #[test]
+    fn test_redb_insert_if_absent() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        test_insert_if_absent(db)
+    }
+
+    #[test]
+    fn test_redb_insert_checked() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        test_insert_checked(db)
+    }
+
+    #[test]
+    fn test_redb_durability_reopen() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        test_durability_reopen(|| {
+            let db = ReDB::open(temp_dir.path())?;
+            db.open_table::<TestTable>()?;
+            Ok(db)
+        })
+    }
+
+    #[test]
+    fn test_redb_raw_copy() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        test_raw_copy(db)
+    }
+
+    #[test]
+    fn test_redb_raw_bytes_not_decoded() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        db.insert::<TestTable>(&1, &"1".to_string()).expect("Failed to insert");
+
+        // a string value with a length prefix longer than the bytes can't decode
+        let key = tn_types::encode_key(&2u64);
+        let value = vec![0xff, 0xff, 0x01];
+        let mut txn = db.write_txn().unwrap();
+        txn.insert_raw::<TestTable>(&key, &value).expect("Failed to raw insert");
+        txn.commit().unwrap();
+
+        let rows: Vec<_> = db.iter_raw::<TestTable>().collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1], (key, value));
+    }
+
+    #[test]
+    fn test_redb_verify_table() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        test_verify_table(db)
+    }
+
+    #[test]
+    fn test_redb_iter_checked() {
+        let temp_dir = tempdir().expect("failed to create temp dir");
+        let db = open_db(temp_dir.path());
+        test_iter_checked(db)
+    }
+
     #[test]
     fn test_redb_dbsimpbench() {
         // Init a DB
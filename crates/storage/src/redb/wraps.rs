@@ -87,3 +87,89 @@ impl<V: ValueT> Value for ValWrap<V> {
         TypeName::new(std::any::type_name::<V>())
     }
 }
+
+/// Reads and writes the encoded bytes of a [KeyWrap] table's keys without decoding them.
+///
+/// Uses the same type name as [KeyWrap] so it opens the same tables.
+#[derive(Debug)]
+pub struct RawKeyWrap<K: KeyT>(PhantomData<K>);
+impl<K: KeyT> Key for RawKeyWrap<K> {
+    fn compare(data1: &[u8], data2: &[u8]) -> std::cmp::Ordering {
+        KeyWrap::<K>::compare(data1, data2)
+    }
+}
+
+impl<K: KeyT> Value for RawKeyWrap<K> {
+    type SelfType<'a>
+        = &'a [u8]
+    where
+        Self: 'a;
+
+    type AsBytes<'a>
+        = &'a [u8]
+    where
+        Self: 'a;
+
+    fn fixed_width() -> Option<usize> {
+        KeyWrap::<K>::fixed_width()
+    }
+
+    fn from_bytes<'a>(data: &'a [u8]) -> Self::SelfType<'a>
+    where
+        Self: 'a,
+    {
+        data
+    }
+
+    fn as_bytes<'a, 'b: 'a>(value: &'a Self::SelfType<'b>) -> Self::AsBytes<'a>
+    where
+        Self: 'a,
+        Self: 'b,
+    {
+        *value
+    }
+
+    fn type_name() -> TypeName {
+        KeyWrap::<K>::type_name()
+    }
+}
+
+/// Reads and writes the encoded bytes of a [ValWrap] table's values without decoding them.
+///
+/// Uses the same type name as [ValWrap] so it opens the same tables.
+#[derive(Debug)]
+pub struct RawValWrap<V: ValueT>(PhantomData<V>);
+impl<V: ValueT> Value for RawValWrap<V> {
+    type SelfType<'a>
+        = &'a [u8]
+    where
+        Self: 'a;
+
+    type AsBytes<'a>
+        = &'a [u8]
+    where
+        Self: 'a;
+
+    fn fixed_width() -> Option<usize> {
+        ValWrap::<V>::fixed_width()
+    }
+
+    fn from_bytes<'a>(data: &'a [u8]) -> Self::SelfType<'a>
+    where
+        Self: 'a,
+    {
+        data
+    }
+
+    fn as_bytes<'a, 'b: 'a>(value: &'a Self::SelfType<'b>) -> Self::AsBytes<'a>
+    where
+        Self: 'a,
+        Self: 'b,
+    {
+        *value
+    }
+
+    fn type_name() -> TypeName {
+        ValWrap::<V>::type_name()
+    }
+}
@@ -1,16 +1,16 @@
 use super::{
     default_db_options,
-    iter::Iter,
+    iter::{Iter, RawIter},
     metrics::{DBMetrics, RocksDBPerfContext, SamplingInterval},
-    open_cf_opts_transactional, MetricConf, ReadWriteOptions, METRICS_ERROR,
+    open_cf_opts_transactional, MetricConf, ReadWriteOptions, RocksDBRawIter, METRICS_ERROR,
     ROCKSDB_PROPERTY_TOTAL_BLOB_FILES_SIZE,
 };
 use crate::{
-    rocks::CF_METRICS_REPORT_PERIOD_MILLIS, BATCHES_CF, CERTIFICATES_CF,
+    rocks::CF_METRICS_REPORT_PERIOD_MILLIS, BATCHES_CF, BATCH_RECEIVED_AT_CF, CERTIFICATES_CF,
     CERTIFICATE_DIGEST_BY_ORIGIN_CF, CERTIFICATE_DIGEST_BY_ROUND_CF, CONSENSUS_BLOCK_CF,
-    CONSENSUS_BLOCK_NUMBER_BY_DIGEST_CF, LAST_PROPOSED_CF, PAYLOAD_CF, VOTES_CF,
+    CONSENSUS_BLOCK_NUMBER_BY_DIGEST_CF, LAST_COMMITTED_CF, LAST_PROPOSED_CF, PAYLOAD_CF, VOTES_CF,
 };
-use rocksdb::{properties, AsColumnFamilyRef, Transaction};
+use rocksdb::{properties, AsColumnFamilyRef, Transaction, WriteOptions};
 use std::{
     fmt::Debug,
     path::Path,
@@ -20,7 +20,10 @@ use std::{
     },
     time::Duration,
 };
-use tn_types::{decode, encode, encode_key, DBIter, Database, DbTx, DbTxMut, Table};
+use tn_types::{
+    decode, encode, encode_key, ensure_value_size, DBIter, DBIterRaw, Database, DbSnapshot, DbTx,
+    DbTxMut, Durability, Table,
+};
 
 pub struct RocksDbTxMut<'txn> {
     db: RocksDatabase,
@@ -63,6 +66,7 @@ impl DbTxMut for RocksDbTxMut<'_> {
             if self.db.write_sample_interval.sample() { Some(RocksDBPerfContext) } else { None };
         let key_buf = encode_key(key);
         let value_buf = encode(value);
+        ensure_value_size::<T>(value_buf.len())?;
         self.db
             .db_metrics
             .op_metrics
@@ -76,6 +80,29 @@ impl DbTxMut for RocksDbTxMut<'_> {
         Ok(())
     }
 
+    fn insert_raw<T: Table>(&mut self, key: &[u8], value: &[u8]) -> eyre::Result<()> {
+        let cf = self
+            .db
+            .rocksdb
+            .cf_handle(T::NAME)
+            .unwrap_or_else(|| panic!("invalid table {}", T::NAME));
+        let _timer = self
+            .db
+            .db_metrics
+            .op_metrics
+            .rocksdb_put_latency_seconds
+            .with_label_values(&[T::NAME])
+            .start_timer();
+        self.db
+            .db_metrics
+            .op_metrics
+            .rocksdb_put_bytes
+            .with_label_values(&[T::NAME])
+            .observe((key.len() + value.len()) as f64);
+        self.txn.put_cf(&cf, key, value)?;
+        Ok(())
+    }
+
     fn remove<T: Table>(&mut self, key: &T::Key) -> eyre::Result<()> {
         let cf = self
             .db
@@ -112,6 +139,65 @@ impl DbTxMut for RocksDbTxMut<'_> {
     }
 }
 
+/// Read only view of the DB.  Wraps a transaction created with snapshot set and reads using that
+/// snapshot so writes committed after it was created are not visible.
+pub struct RocksDbSnapshot<'txn> {
+    db: RocksDatabase,
+    txn: Transaction<'txn, rocksdb::OptimisticTransactionDB>,
+}
+
+impl Debug for RocksDbSnapshot<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RocksDbSnapshot")
+    }
+}
+
+impl RocksDbSnapshot<'_> {
+    /// Read options that will read from the transaction snapshot.
+    fn readopts(&self) -> rocksdb::ReadOptions {
+        let mut readopts = self.db.opts.readopts();
+        readopts.set_snapshot(&self.txn.snapshot());
+        readopts
+    }
+}
+
+impl DbTx for RocksDbSnapshot<'_> {
+    fn get<T: Table>(&self, key: &T::Key) -> eyre::Result<Option<T::Value>> {
+        let cf = self
+            .db
+            .rocksdb
+            .cf_handle(T::NAME)
+            .unwrap_or_else(|| panic!("invalid table {}", T::NAME));
+        let key_buf = encode_key(key);
+        Ok(self
+            .txn
+            .get_cf_opt(&cf, key_buf, &self.readopts())
+            .map(|res| res.map(|bytes| decode::<T::Value>(&bytes)))?)
+    }
+}
+
+impl DbSnapshot for RocksDbSnapshot<'_> {
+    fn iter<T: Table>(&self) -> DBIter<'_, T> {
+        let cf = self
+            .db
+            .rocksdb
+            .cf_handle(T::NAME)
+            .unwrap_or_else(|| panic!("invalid table {}", T::NAME));
+        let db_iter = RocksDBRawIter::OptimisticTransaction(
+            self.txn.raw_iterator_cf_opt(&cf, self.readopts()),
+        );
+        Box::new(Iter::<T::Key, T::Value>::new(
+            T::NAME.to_string(),
+            db_iter,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ))
+    }
+}
+
 /// An interface to a btree map database. This is mainly intended
 /// for tests and performing benchmark comparisons or anywhere where an ephemeral database is
 /// useful.
@@ -119,6 +205,8 @@ impl DbTxMut for RocksDbTxMut<'_> {
 pub struct RocksDatabase {
     rocksdb: Arc<super::RocksDB>,
     opts: ReadWriteOptions,
+    /// Durability of write txns that don't set their own.
+    durability: Durability,
     db_metrics: Arc<DBMetrics>,
     get_sample_interval: SamplingInterval,
     write_sample_interval: SamplingInterval,
@@ -164,6 +252,8 @@ impl RocksDatabase {
         Self {
             rocksdb: db.clone(),
             opts: opts.clone(),
+            // Without sync_to_disk commits are written to the WAL but not synced.
+            durability: if opts.sync_to_disk { Durability::Sync } else { Durability::Eventual },
             db_metrics: db_metrics_cloned,
             get_sample_interval: db.get_sampling_interval(),
             write_sample_interval: db.write_sampling_interval(),
@@ -197,8 +287,10 @@ impl RocksDatabase {
                     .optimize_for_large_values_no_scan(1 << 10)
                     .options,
             ),
+            (BATCH_RECEIVED_AT_CF, cf_options.clone()),
             (CONSENSUS_BLOCK_CF, cf_options.clone()),
-            (CONSENSUS_BLOCK_NUMBER_BY_DIGEST_CF, cf_options),
+            (CONSENSUS_BLOCK_NUMBER_BY_DIGEST_CF, cf_options.clone()),
+            (LAST_COMMITTED_CF, cf_options),
         ];
         let rocksdb = open_cf_opts_transactional(
             path,
@@ -210,6 +302,12 @@ impl RocksDatabase {
         Ok(Self::new(rocksdb, &crate::rocks::ReadWriteOptions::default(), &column_family_options))
     }
 
+    /// Set the durability of write txns that don't set their own.
+    pub fn with_durability(mut self, durability: Durability) -> Self {
+        self.durability = durability;
+        self
+    }
+
     /// Open a DB with a single table, for testing.
     pub fn open_db_with_table<T: Table, P: AsRef<Path>>(path: P) -> eyre::Result<RocksDatabase> {
         let db_options = default_db_options().optimize_db_for_write_throughput(2);
@@ -318,6 +416,15 @@ impl RocksDatabase {
     /// Returns an unbounded iterator visiting each key-value pair in the map.
     /// This is potentially unsafe as it can perform a full table scan
     fn unbounded_iter_inner<T: Table>(&self) -> Iter<'_, T::Key, T::Value> {
+        self.iter_inner_with_readopts::<T>(self.opts.readopts())
+    }
+
+    /// Returns an iterator visiting each key-value pair in the map using the read options.
+    /// The read options can bound the iterator, for instance with an upper bound.
+    fn iter_inner_with_readopts<T: Table>(
+        &self,
+        readopts: rocksdb::ReadOptions,
+    ) -> Iter<'_, T::Key, T::Value> {
         let cf =
             self.rocksdb.cf_handle(T::NAME).unwrap_or_else(|| panic!("invalid table {}", T::NAME));
         let _timer = self
@@ -332,7 +439,7 @@ impl RocksDatabase {
             self.db_metrics.op_metrics.rocksdb_iter_keys.with_label_values(&[T::NAME]);
         let _perf_ctx =
             if self.iter_sample_interval.sample() { Some(RocksDBPerfContext) } else { None };
-        let db_iter = self.rocksdb.raw_iterator_cf(&cf, self.opts.readopts());
+        let db_iter = self.rocksdb.raw_iterator_cf(&cf, readopts);
         Iter::new(
             T::NAME.to_string(),
             db_iter,
@@ -348,6 +455,7 @@ impl RocksDatabase {
 impl Database for RocksDatabase {
     type TX<'txn> = RocksDbTxMut<'txn>;
     type TXMut<'txn> = RocksDbTxMut<'txn>;
+    type Snapshot<'snap> = RocksDbSnapshot<'snap>;
 
     fn read_txn(&self) -> eyre::Result<Self::TX<'_>> {
         let txn = self.rocksdb.transaction()?;
@@ -355,10 +463,26 @@ impl Database for RocksDatabase {
     }
 
     fn write_txn(&self) -> eyre::Result<Self::TXMut<'_>> {
-        let txn = self.rocksdb.transaction()?;
+        self.write_txn_with_durability(self.durability)
+    }
+
+    fn write_txn_with_durability(&self, durability: Durability) -> eyre::Result<Self::TXMut<'_>> {
+        let mut write_opts = WriteOptions::default();
+        match durability {
+            // Skip the WAL, commits only survive once the memtable is flushed.
+            Durability::None => write_opts.disable_wal(true),
+            Durability::Eventual => (),
+            Durability::Sync => write_opts.set_sync(true),
+        }
+        let txn = self.rocksdb.transaction_with_write_options(&write_opts)?;
         Ok(RocksDbTxMut { db: self.clone(), txn })
     }
 
+    fn snapshot(&self) -> eyre::Result<Self::Snapshot<'_>> {
+        let txn = self.rocksdb.transaction()?;
+        Ok(RocksDbSnapshot { db: self.clone(), txn })
+    }
+
     fn contains_key<T: Table>(&self, key: &T::Key) -> eyre::Result<bool> {
         let cf =
             self.rocksdb.cf_handle(T::NAME).unwrap_or_else(|| panic!("invalid table {}", T::NAME));
@@ -410,6 +534,7 @@ impl Database for RocksDatabase {
             if self.write_sample_interval.sample() { Some(RocksDBPerfContext) } else { None };
         let key_buf = encode_key(key);
         let value_buf = encode(value);
+        ensure_value_size::<T>(value_buf.len())?;
         self.db_metrics
             .op_metrics
             .rocksdb_put_bytes
@@ -456,10 +581,31 @@ impl Database for RocksDatabase {
         Box::new(self.unbounded_iter_inner::<T>())
     }
 
+    fn iter_raw<T: Table>(&self) -> DBIterRaw<'_> {
+        let cf =
+            self.rocksdb.cf_handle(T::NAME).unwrap_or_else(|| panic!("invalid table {}", T::NAME));
+        Box::new(RawIter::new(self.rocksdb.raw_iterator_cf(&cf, self.opts.readopts())))
+    }
+
     fn skip_to<T: Table>(&self, key: &T::Key) -> eyre::Result<DBIter<'_, T>> {
         Ok(Box::new(self.unbounded_iter_inner::<T>().skip_to(key)?))
     }
 
+    /// Uses the RocksDB iterate upper bound so the iterator stops at `to`.
+    fn range_iter<T: Table>(&self, from: &T::Key, to: &T::Key) -> DBIter<'_, T> {
+        let to_bytes = encode_key(to);
+        if to_bytes <= encode_key(from) {
+            return Box::new(std::iter::empty());
+        }
+        let mut readopts = self.opts.readopts();
+        readopts.set_iterate_upper_bound(to_bytes);
+        Box::new(
+            self.iter_inner_with_readopts::<T>(readopts)
+                .skip_to(from)
+                .expect("skip_to does not fail"),
+        )
+    }
+
     fn reverse_iter<T: Table>(&self) -> DBIter<'_, T> {
         Box::new(self.unbounded_iter_inner::<T>().skip_to_last().reverse())
     }
@@ -471,4 +617,24 @@ impl Database for RocksDatabase {
     fn last_record<T: Table>(&self) -> Option<(T::Key, T::Value)> {
         self.unbounded_iter_inner::<T>().skip_to_last().next()
     }
+
+    fn first_record<T: Table>(&self) -> Option<(T::Key, T::Value)> {
+        self.unbounded_iter_inner::<T>().seek_to_first().next()
+    }
+
+    /// Uses the RocksDB estimated number of keys property, this is an estimate and
+    /// can be off especially after deletes.
+    fn approximate_len<T: Table>(&self) -> usize {
+        let cf =
+            self.rocksdb.cf_handle(T::NAME).unwrap_or_else(|| panic!("invalid table {}", T::NAME));
+        Self::get_int_property(&self.rocksdb, &cf, properties::ESTIMATE_NUM_KEYS)
+            .map(|len| len.try_into().unwrap_or_default())
+            .unwrap_or_default()
+    }
+
+    /// Flush the RocksDB memtables to disk.
+    fn flush(&self) -> eyre::Result<()> {
+        self.rocksdb.flush()?;
+        Ok(())
+    }
 }
@@ -123,7 +123,7 @@ impl<'a, K: Serialize, V> Iter<'a, K, V> {
     }
 
     /// Seeks to the first key in the database (at this column family).
-    pub fn _seek_to_first(mut self) -> Self {
+    pub fn seek_to_first(mut self) -> Self {
         self.is_initialized = true;
         self.db_iter.seek_to_first();
         self
@@ -138,6 +138,38 @@ impl<'a, K: Serialize, V> Iter<'a, K, V> {
     }
 }
 
+/// An iterator over all encoded key-value pairs in a data map, the bytes are not decoded.
+pub struct RawIter<'a> {
+    db_iter: RocksDBRawIter<'a>,
+    is_initialized: bool,
+}
+
+impl<'a> RawIter<'a> {
+    pub(super) fn new(db_iter: RocksDBRawIter<'a>) -> Self {
+        Self { db_iter, is_initialized: false }
+    }
+}
+
+impl Iterator for RawIter<'_> {
+    type Item = (Vec<u8>, Vec<u8>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.is_initialized {
+            self.db_iter.seek_to_first();
+            self.is_initialized = true;
+        }
+        if self.db_iter.valid() {
+            let raw_key = self.db_iter.key().expect("Valid iterator failed to get key").to_vec();
+            let raw_value =
+                self.db_iter.value().expect("Valid iterator failed to get value").to_vec();
+            self.db_iter.next();
+            Some((raw_key, raw_value))
+        } else {
+            None
+        }
+    }
+}
+
 /// An iterator with a reverted direction to the original. The `RevIter`
 /// is hosting an iteration which is consuming in the opposing direction.
 /// It's not possible to do further manipulation (ex re-reverse) to the
@@ -280,13 +280,21 @@ impl RocksDB {
 
     pub fn transaction(
         &self,
+    ) -> Result<Transaction<'_, rocksdb::OptimisticTransactionDB>, TypedStoreError> {
+        self.transaction_with_write_options(&WriteOptions::default())
+    }
+
+    /// Start a transaction that commits with `write_opts`.
+    pub fn transaction_with_write_options(
+        &self,
+        write_opts: &WriteOptions,
     ) -> Result<Transaction<'_, rocksdb::OptimisticTransactionDB>, TypedStoreError> {
         match self {
             Self::OptimisticTransactionDB(db) => {
                 let mut tx_opts = OptimisticTransactionOptions::new();
                 tx_opts.set_snapshot(true);
 
-                Ok(db.underlying.transaction_opt(&WriteOptions::default(), &tx_opts))
+                Ok(db.underlying.transaction_opt(write_opts, &tx_opts))
             }
             Self::DBWithThreadMode(_) => panic!(),
         }
@@ -69,6 +69,13 @@ fn test_rocksdb_skip() {
     test_skip(db)
 }
 
+#[test]
+fn test_rocksdb_range_iter() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = open_db(temp_dir.path());
+    test_range_iter(db)
+}
+
 #[test]
 fn test_rocksdb_skip_to_previous_simple() {
     let temp_dir = TempDir::new().unwrap();
@@ -230,6 +237,48 @@ fn refcount_with_compaction_test() {
 }
 */
 
+#[test]
+fn test_rocksdb_first_last_record() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = open_db(temp_dir.path());
+    test_first_last_record(db)
+}
+
+#[test]
+fn test_rocksdb_approximate_len() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = open_db(temp_dir.path());
+    test_approximate_len(db)
+}
+
+#[test]
+fn test_rocksdb_snapshot() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = RocksDatabase::open_db(temp_dir.path()).expect("Cannot open database");
+    test_snapshot(db)
+}
+
+#[test]
+fn test_rocksdb_insert_if_absent() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = open_db(temp_dir.path());
+    test_insert_if_absent(db)
+}
+
+#[test]
+fn test_rocksdb_insert_checked() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = open_db(temp_dir.path());
+    test_insert_checked(db)
+}
+
+#[test]
+fn test_rocksdb_raw_copy() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = open_db(temp_dir.path());
+    test_raw_copy(db)
+}
+
 #[test]
 fn test_rocksdb_dbsimpbench() {
     // Init a DB
@@ -0,0 +1,191 @@
+//! Record the on-disk size of the store on a timer.
+//!
+//! Operators use the `store_bytes` gauge and the logged growth rate to predict disk exhaustion.
+//! The size is the total size of all files in the store's directory, so it works for every
+//! backend and includes any space that has not been reclaimed by [Database::compact] yet.
+//!
+//! [Database::compact]: tn_types::Database::compact
+
+use prometheus::{default_registry, register_int_gauge_with_registry, IntGauge, Registry};
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, SyncSender},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// Metrics for the on-disk size of the store.
+#[derive(Debug)]
+pub struct StoreSizeMetrics {
+    /// The total size of the store's files in bytes.
+    pub store_bytes: IntGauge,
+}
+
+impl StoreSizeMetrics {
+    /// Create metrics registered with the provided registry.
+    pub fn try_new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        Ok(Self {
+            store_bytes: register_int_gauge_with_registry!(
+                "store_bytes",
+                "The total size of the store's files on disk in bytes.",
+                registry,
+            )?,
+        })
+    }
+}
+
+impl Default for StoreSizeMetrics {
+    fn default() -> Self {
+        // try_new() should not fail except under certain conditions with testing (see comment
+        // below). This pushes the panic or retry decision lower and supporting try_new
+        // allways a user to deal with errors if desired (have a non-panic option).
+        // We always want do use default_registry() when not in test.
+        match Self::try_new(default_registry()) {
+            Ok(metrics) => metrics,
+            Err(_) => {
+                // If we are in a test then don't panic on prometheus errors (usually an already
+                // registered error) but try again with a new Registry. This is not
+                // great for prod code, however should not happen, but will happen in tests due to
+                // how Rust runs them so lets just gloss over it. cfg(test) does not
+                // always work as expected.
+                Self::try_new(&Registry::new()).expect("Prometheus error, are you using it wrong?")
+            }
+        }
+    }
+}
+
+/// Background thread that periodically records the size of the store.
+///
+/// The thread shuts down when the monitor is dropped.
+#[derive(Debug)]
+pub struct StoreSizeMonitor {
+    /// Notify the thread to shut down.
+    shutdown_tx: SyncSender<()>,
+}
+
+impl StoreSizeMonitor {
+    /// Spawn a thread that records the size of the store at `path` every `interval`.
+    pub fn spawn(path: impl Into<PathBuf>, interval: Duration) -> Self {
+        Self::spawn_with_metrics(path, interval, Arc::new(StoreSizeMetrics::default()))
+    }
+
+    /// Spawn a thread that records the size of the store to the provided metrics.
+    pub fn spawn_with_metrics(
+        path: impl Into<PathBuf>,
+        interval: Duration,
+        metrics: Arc<StoreSizeMetrics>,
+    ) -> Self {
+        let path = path.into();
+        let (shutdown_tx, rx) = mpsc::sync_channel::<()>(0);
+        std::thread::spawn(move || {
+            tracing::info!(target: "telcoin::storage", ?path, ?interval, "Starting store size thread");
+            let mut last: Option<(u64, Instant)> = None;
+            loop {
+                match store_size(&path) {
+                    Ok(bytes) => {
+                        metrics.store_bytes.set(bytes.try_into().unwrap_or(i64::MAX));
+                        let now = Instant::now();
+                        if let Some((last_bytes, last_time)) = last {
+                            let elapsed = now.duration_since(last_time).as_secs_f64();
+                            let growth = bytes as f64 - last_bytes as f64;
+                            let bytes_per_sec = if elapsed > 0.0 { growth / elapsed } else { 0.0 };
+                            tracing::info!(target: "telcoin::storage", ?bytes, ?bytes_per_sec, "store size");
+                        } else {
+                            tracing::info!(target: "telcoin::storage", ?bytes, "store size");
+                        }
+                        last = Some((bytes, now));
+                    }
+                    Err(e) => {
+                        tracing::error!(target: "telcoin::storage", ?path, "Error while trying to read store size: {e}");
+                    }
+                }
+
+                if let Err(mpsc::RecvTimeoutError::Timeout) = rx.recv_timeout(interval) {
+                    continue;
+                }
+                break;
+            }
+            tracing::info!(target: "telcoin::storage", "Ending store size thread");
+        });
+
+        Self { shutdown_tx }
+    }
+}
+
+impl Drop for StoreSizeMonitor {
+    fn drop(&mut self) {
+        // shutdown_tx is a sync sender with no buffer so this should block until the thread
+        // reads it and shuts down
+        let _ = self.shutdown_tx.send(());
+    }
+}
+
+/// The total size in bytes of all files in the directory (or the size of a single file).
+pub fn store_size(path: &Path) -> std::io::Result<u64> {
+    let metadata = std::fs::symlink_metadata(path)?;
+    if !metadata.is_dir() {
+        return Ok(metadata.len());
+    }
+
+    let mut bytes = 0;
+    for entry in std::fs::read_dir(path)? {
+        bytes += store_size(&entry?.path())?;
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{StoreSizeMetrics, StoreSizeMonitor};
+    use crate::{open_redb_tables, tables::Batches};
+    use prometheus::Registry;
+    use std::{
+        sync::Arc,
+        time::{Duration, Instant},
+    };
+    use tempfile::tempdir;
+    use tn_types::{Batch, BlockHash, Database};
+
+    /// Insert batches with `tx_bytes` of transaction data each.
+    fn insert_batches<DB: Database>(db: &DB, range: std::ops::Range<u8>, tx_bytes: usize) {
+        for number in range {
+            let batch = Batch { transactions: vec![vec![number; tx_bytes]], ..Default::default() };
+            db.insert::<Batches>(&BlockHash::with_last_byte(number), &batch).unwrap();
+        }
+    }
+
+    /// Wait for the gauge to exceed `min` bytes.
+    fn wait_for_size(metrics: &StoreSizeMetrics, min: i64) -> i64 {
+        let start = Instant::now();
+        while metrics.store_bytes.get() <= min {
+            assert!(start.elapsed() < Duration::from_secs(10), "store size not recorded");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        metrics.store_bytes.get()
+    }
+
+    #[test]
+    fn test_store_size_increases_after_inserts() {
+        let temp_dir = tempdir().unwrap();
+        let db = open_redb_tables(temp_dir.path()).unwrap();
+        insert_batches(&db, 0..16, 64 * 1024);
+
+        let metrics = Arc::new(StoreSizeMetrics::try_new(&Registry::new()).unwrap());
+        let _monitor = StoreSizeMonitor::spawn_with_metrics(
+            temp_dir.path(),
+            Duration::from_millis(20),
+            metrics.clone(),
+        );
+
+        // the inserted volume is reported
+        let first = wait_for_size(&metrics, 0);
+        assert!(first >= 16 * 64 * 1024);
+
+        // the reported size increases after more inserts
+        insert_batches(&db, 16..128, 256 * 1024);
+        let second = wait_for_size(&metrics, first);
+        assert!(second >= 112 * 256 * 1024);
+    }
+}
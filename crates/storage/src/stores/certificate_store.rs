@@ -1,11 +1,6 @@
 //! NOTE: tests for this module are in test-utils storage_tests.rs to avoid circular dependancies.
 
-use std::{
-    cmp::{max, Ordering},
-    collections::BTreeMap,
-    future::Future,
-    sync::LazyLock,
-};
+use std::{cmp::max, collections::BTreeMap, future::Future, sync::LazyLock};
 use tn_utils::fail_point;
 
 use crate::{
@@ -13,7 +8,7 @@ use crate::{
     StoreResult, ROUNDS_TO_KEEP,
 };
 use tn_types::{
-    AuthorityIdentifier, Certificate, CertificateDigest, Database, DbTx, DbTxMut, Hash, Round,
+    AuthorityIdentifier, Certificate, CertificateDigest, Database, DbTxMut, Hash, Round,
 };
 use tn_utils::sync::notify_read::NotifyRead;
 
@@ -87,6 +82,14 @@ pub trait CertificateStore {
     /// The result is returned with certificates sorted in round asc order
     fn after_round(&self, round: Round) -> StoreResult<Vec<Certificate>>;
 
+    /// Retrieves all the certificates with `from <= round <= to`.
+    /// The result is returned with certificates sorted in round asc order
+    fn fetch_certificates_in_round_range(
+        &self,
+        from: Round,
+        to: Round,
+    ) -> StoreResult<Vec<Certificate>>;
+
     /// Retrieves origins with certificates in each round >= the provided round.
     fn origins_after_round(
         &self,
@@ -321,35 +324,47 @@ impl<DB: Database> CertificateStore for DB {
     /// Retrieves all the certificates with round >= the provided round.
     /// The result is returned with certificates sorted in round asc order
     fn after_round(&self, round: Round) -> StoreResult<Vec<Certificate>> {
-        let txn = self.read_txn()?;
+        self.fetch_certificates_in_round_range(round, Round::MAX)
+    }
+
+    /// Retrieves all the certificates with `from <= round <= to`.
+    /// The result is returned with certificates sorted in round asc order
+    fn fetch_certificates_in_round_range(
+        &self,
+        from: Round,
+        to: Round,
+    ) -> StoreResult<Vec<Certificate>> {
+        if from > to {
+            return Ok(Vec::new());
+        }
+
         // Skip to a row at or before the requested round.
         // TODO: Add a more efficient seek method to typed store.
-        let iter = if round > 0 {
-            self.skip_to::<CertificateDigestByRound>(&(round - 1, AuthorityIdentifier::default()))?
+        let iter = if from > 0 {
+            self.skip_to::<CertificateDigestByRound>(&(from - 1, AuthorityIdentifier::default()))?
         } else {
             self.iter::<CertificateDigestByRound>()
         };
 
-        let mut certs = Vec::new();
-        for ((r, _), d) in iter {
-            match r.cmp(&round) {
-                Ordering::Equal | Ordering::Greater => {
-                    // Fetch all those certificates from main storage, return an error if any one is
-                    // missing.
-                    if let Some(cert) = txn.get::<Certificates>(&d)? {
-                        certs.push(cert);
-                    } else {
-                        return Err(eyre::Report::msg(format!(
-                            "Certificate with some digests not found, CertificateStore invariant violation: {d}"
-                        )));
-                    }
-                }
-                Ordering::Less => {
-                    continue;
-                }
-            }
-        }
-        Ok(certs)
+        // collect the digests from the round index
+        let digests: Vec<CertificateDigest> = iter
+            .skip_while(|((r, _), _)| *r < from)
+            .take_while(|((r, _), _)| *r <= to)
+            .map(|(_, digest)| digest)
+            .collect();
+
+        // Fetch all those certificates from main storage, return an error if any one is missing.
+        self.multi_get::<Certificates>(digests.iter())?
+            .into_iter()
+            .zip(digests.iter())
+            .map(|(cert, d)| {
+                cert.ok_or_else(|| {
+                    eyre::Report::msg(format!(
+                        "Certificate with some digests not found, CertificateStore invariant violation: {d}"
+                    ))
+                })
+            })
+            .collect()
     }
 
     /// Retrieves origins with certificates in each round >= the provided round.
@@ -1,15 +1,36 @@
 //! NOTE: tests for this module are in test-utils storage_tests.rs to avoid circular dependancies.
 
 use crate::{
-    tables::{ConsensusBlockNumbersByDigest, ConsensusBlocks},
+    tables::{Batches, ConsensusBlockNumbersByDigest, ConsensusBlocks, LastCommitted},
     StoreResult,
 };
+use parking_lot::{const_mutex, Mutex};
 use std::{cmp::max, collections::HashMap};
 use tn_types::{
-    AuthorityIdentifier, CommittedSubDag, ConsensusHeader, Database, DbTxMut, Round, SequenceNumber,
+    AuthorityIdentifier, Batch, CommittedSubDag, ConsensusHeader, Database, DbTxMut, Round,
+    SequenceNumber,
 };
 use tracing::debug;
 
+/// Serializes [ConsensusStore::bump_last_committed] so the read and the write of a round are
+/// never interleaved with another update.
+static BUMP_LAST_COMMITTED: Mutex<()> = const_mutex(());
+
+/// A committed sub dag with the batches for each of it's certificates resolved from storage.
+///
+/// Exported sub dags contain everything needed to replay consensus output.
+#[derive(Clone, Debug)]
+pub struct ExportedSubDag {
+    /// The number of the sub dag in the consensus chain.
+    pub number: SequenceNumber,
+    /// The committed sub dag, including it's certificates.
+    pub sub_dag: CommittedSubDag,
+    /// The batches for each certificate in the sub dag (matches certificates one-to-one).
+    ///
+    /// Batches are in the same order as the certificate's header payload.
+    pub batches: Vec<Vec<Batch>>,
+}
+
 /// Implement persistent storage of the sequencer.
 /// Uses DB tables:
 ///   - LastCommitted<AuthorityIdentifier, Round>: The latest committed round of each validator.
@@ -28,6 +49,18 @@ pub trait ConsensusStore: Clone {
     /// Load the last committed round of each validator.
     fn read_last_committed(&self) -> HashMap<AuthorityIdentifier, Round>;
 
+    /// Advance the [LastCommitted] round for the authority.
+    ///
+    /// The current round is read before a write txn is opened and only updated if `round` is
+    /// greater, so a stale commit never regresses the watermark. Updates are serialized so
+    /// concurrent bumps can not race between the read and the write. Returns true if the round
+    /// advanced.
+    fn bump_last_committed(
+        &self,
+        authority: &AuthorityIdentifier,
+        round: Round,
+    ) -> StoreResult<bool>;
+
     /// Returns the latest subdag committed. If none is committed yet, then
     /// None is returned instead.
     fn get_latest_sub_dag(&self) -> Option<CommittedSubDag>;
@@ -38,9 +71,29 @@ pub trait ConsensusStore: Clone {
         from: &SequenceNumber,
     ) -> StoreResult<Vec<CommittedSubDag>>;
 
+    /// Load the `count` most recent committed sub dags, newest first.
+    ///
+    /// The consensus chain is read in reverse from the highest sequence number, so older sub dags
+    /// are never scanned.
+    fn read_recent_committed_sub_dags(
+        &self,
+        count: usize,
+    ) -> Vec<(SequenceNumber, CommittedSubDag)>;
+
     /// Reads from storage the latest commit sub dag where its ReputationScores are marked as
     /// "final". If none exists yet then this method will return None.
     fn read_latest_commit_with_final_reputation_scores(&self) -> Option<CommittedSubDag>;
+
+    /// Export the committed sub dags numbered `from` through `to` (inclusive) in sequence order.
+    ///
+    /// Sub dags are read lazily from the consensus chain, which includes their certificates. The
+    /// batches for each certificate are resolved from the [Batches] table and an error is returned
+    /// for any sub dag with a missing batch.
+    fn export_committed_range(
+        &self,
+        from: SequenceNumber,
+        to: SequenceNumber,
+    ) -> StoreResult<Box<dyn Iterator<Item = StoreResult<ExportedSubDag>> + '_>>;
 }
 impl<DB: Database> ConsensusStore for DB {
     fn write_subdag_for_test(&self, number: u64, sub_dag: CommittedSubDag) {
@@ -84,6 +137,22 @@ impl<DB: Database> ConsensusStore for DB {
         res
     }
 
+    fn bump_last_committed(
+        &self,
+        authority: &AuthorityIdentifier,
+        round: Round,
+    ) -> StoreResult<bool> {
+        let _guard = BUMP_LAST_COMMITTED.lock();
+        if self.get::<LastCommitted>(authority)?.is_some_and(|current| current >= round) {
+            return Ok(false);
+        }
+
+        let mut txn = self.write_txn()?;
+        txn.insert::<LastCommitted>(authority, &round)?;
+        txn.commit()?;
+        Ok(true)
+    }
+
     fn get_latest_sub_dag(&self) -> Option<CommittedSubDag> {
         self.last_record::<ConsensusBlocks>().map(|(_, block)| block.sub_dag)
     }
@@ -98,6 +167,16 @@ impl<DB: Database> ConsensusStore for DB {
             .collect::<Vec<CommittedSubDag>>())
     }
 
+    fn read_recent_committed_sub_dags(
+        &self,
+        count: usize,
+    ) -> Vec<(SequenceNumber, CommittedSubDag)> {
+        self.reverse_iter::<ConsensusBlocks>()
+            .take(count)
+            .map(|(number, block)| (number, block.sub_dag))
+            .collect()
+    }
+
     fn read_latest_commit_with_final_reputation_scores(&self) -> Option<CommittedSubDag> {
         for commit in self.reverse_iter::<ConsensusBlocks>().map(|(_, block)| block.sub_dag) {
             // found a final of schedule score, so we'll return that
@@ -112,6 +191,33 @@ impl<DB: Database> ConsensusStore for DB {
         debug!("No final reputation scores have been found");
         None
     }
+
+    fn export_committed_range(
+        &self,
+        from: SequenceNumber,
+        to: SequenceNumber,
+    ) -> StoreResult<Box<dyn Iterator<Item = StoreResult<ExportedSubDag>> + '_>> {
+        let blocks = self.skip_to::<ConsensusBlocks>(&from)?.take_while(move |(n, _)| *n <= to);
+        Ok(Box::new(blocks.map(|(number, block)| {
+            let batches = block
+                .sub_dag
+                .certificates
+                .iter()
+                .map(|cert| {
+                    cert.header()
+                        .payload()
+                        .keys()
+                        .map(|digest| {
+                            self.get::<Batches>(digest)?.ok_or_else(|| {
+                                eyre::eyre!("missing batch {digest} for committed sub dag {number}")
+                            })
+                        })
+                        .collect::<StoreResult<Vec<_>>>()
+                })
+                .collect::<StoreResult<Vec<_>>>()?;
+            Ok(ExportedSubDag { number, sub_dag: block.sub_dag, batches })
+        })))
+    }
 }
 
 // NOTE: tests for this module are in test-utils storage_tests.rs to avoid circular dependancies.
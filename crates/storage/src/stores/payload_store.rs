@@ -1,5 +1,5 @@
-use crate::tables::Payload;
-use tn_types::{BlockHash, Database, WorkerId};
+use crate::tables::{Batches, Payload};
+use tn_types::{BlockHash, ConsensusHeader, Database, WorkerId};
 use tn_utils::fail_point;
 
 /// Access the batch digests for the primary node for the own created batches.
@@ -9,6 +9,16 @@ pub trait PayloadStore {
     /// Queries the store whether the batch with provided `digest` and `worker_id` exists. It
     /// returns `true` if exists, `false` otherwise.
     fn contains_payload(&self, digest: BlockHash, worker_id: WorkerId) -> eyre::Result<bool>;
+
+    /// Return the batches referenced by the consensus header that are not in the store.
+    ///
+    /// A batch is present if it is in the [Payload] table for the worker or the [Batches] table.
+    /// Missing batches are returned in the order of the header's certificate payloads so they can
+    /// be fetched from peers.
+    fn missing_batches_for_header(
+        &self,
+        header: &ConsensusHeader,
+    ) -> eyre::Result<Vec<(BlockHash, WorkerId)>>;
 }
 
 impl<DB: Database> PayloadStore for DB {
@@ -24,4 +34,21 @@ impl<DB: Database> PayloadStore for DB {
     fn contains_payload(&self, digest: BlockHash, worker_id: WorkerId) -> eyre::Result<bool> {
         self.contains_key::<Payload>(&(digest, worker_id))
     }
+
+    fn missing_batches_for_header(
+        &self,
+        header: &ConsensusHeader,
+    ) -> eyre::Result<Vec<(BlockHash, WorkerId)>> {
+        let mut missing = Vec::new();
+        for cert in &header.sub_dag.certificates {
+            for (digest, (worker_id, _)) in cert.header().payload() {
+                if !self.contains_payload(*digest, *worker_id)?
+                    && !self.contains_key::<Batches>(digest)?
+                {
+                    missing.push((*digest, *worker_id));
+                }
+            }
+        }
+        Ok(missing)
+    }
 }
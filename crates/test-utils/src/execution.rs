@@ -18,7 +18,7 @@ use reth_revm::database::StateProviderDatabase;
 use reth_rpc_eth_types::utils::recover_raw_transaction;
 use reth_transaction_pool::{EthPooledTransaction, TransactionOrigin, TransactionPool};
 use secp256k1::Secp256k1;
-use std::{str::FromStr, sync::Arc};
+use std::{collections::BTreeMap, str::FromStr, sync::Arc};
 use telcoin_network::node::NodeCommand;
 use tempfile::tempdir;
 use tn_config::Config;
@@ -30,8 +30,9 @@ use tn_types::{
     Batch, Block, BlockBody, BlockExt as _, BlockHeader as _, Bytes, Encodable2718 as _,
     EthPrimitives, EthSignature, ExecHeader, ExecutionKeypair, Genesis, GenesisAccount,
     SealedHeader, SignedTransactionIntoRecoveredExt as _, TaskManager, TimestampSec, Transaction,
-    TransactionSigned, TxEip1559, TxHash, TxKind, Withdrawals, B256, EMPTY_OMMER_ROOT_HASH,
-    EMPTY_TRANSACTIONS, EMPTY_WITHDRAWALS, ETHEREUM_BLOCK_GAS_LIMIT, MIN_PROTOCOL_BASE_FEE, U256,
+    TransactionSigned, TxEip1559, TxEip2930, TxHash, TxKind, TxLegacy, Withdrawals, B256,
+    EMPTY_OMMER_ROOT_HASH, EMPTY_TRANSACTIONS, EMPTY_WITHDRAWALS, ETHEREUM_BLOCK_GAS_LIMIT,
+    MIN_PROTOCOL_BASE_FEE, U256,
 };
 use tracing::debug;
 
@@ -141,8 +142,14 @@ pub fn execution_builder<CliExt: clap::Args + fmt::Debug>(
     // TODO: this a temporary approach until upstream reth supports public rpc hooks
     let opt_faucet_args = None;
 
-    let builder =
-        TnBuilder { database, node_config, tn_config, opt_faucet_args, consensus_metrics: None };
+    let builder = TnBuilder {
+        database,
+        node_config,
+        tn_config,
+        opt_faucet_args,
+        consensus_metrics: None,
+        opt_tx_validation_hook: None,
+    };
 
     Ok((builder, ext))
 }
@@ -179,6 +186,7 @@ pub fn faucet_test_execution_node(
         tn_config,
         opt_faucet_args: Some(faucet),
         consensus_metrics: None,
+        opt_tx_validation_hook: None,
     };
 
     // create engine node
@@ -393,11 +401,13 @@ impl TransactionFactory {
     /// Address: 0xb14d3c4f5fbfbcfb98af2d330000d49c95b93aa7
     /// Secret: 9bf49a6a0755f953811fce125f2683d50429c3bb49e074147e0089a52eae155f
     pub fn new() -> Self {
-        let mut rng = StdRng::from_seed([0; 32]);
-        let secp = Secp256k1::new();
-        let (secret_key, _public_key) = secp.generate_keypair(&mut rng);
-        let keypair = ExecutionKeypair::from_secret_key(&secp, &secret_key);
-        Self { keypair, nonce: 0 }
+        Self::from_seed([0; 32])
+    }
+
+    /// Create a new instance of self deterministically from the provided seed.
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        let mut rng = StdRng::from_seed(seed);
+        Self::new_random_from_seed(&mut rng)
     }
 
     /// create a new instance of self from a provided seed.
@@ -422,6 +432,11 @@ impl TransactionFactory {
         public_key_to_address(public_key)
     }
 
+    /// Return the nonce for the next transaction.
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
     /// Change the nonce for the next transaction.
     pub fn set_nonce(&mut self, nonce: u64) {
         self.nonce = nonce;
@@ -483,6 +498,83 @@ impl TransactionFactory {
         TransactionSigned::new_unhashed(transaction, signature)
     }
 
+    /// Create and sign a legacy transaction.
+    ///
+    /// The chain id is always included for replay protection (EIP-155).
+    pub fn create_legacy(
+        &mut self,
+        chain: Arc<ChainSpec>,
+        gas_limit: Option<u64>,
+        gas_price: u128,
+        to: Option<Address>,
+        value: U256,
+        input: Bytes,
+    ) -> TransactionSigned {
+        let gas_limit = gas_limit.unwrap_or(1_000_000);
+        let tx_kind = match to {
+            Some(address) => TxKind::Call(address),
+            None => TxKind::Create,
+        };
+
+        // Legacy
+        let transaction = Transaction::Legacy(TxLegacy {
+            chain_id: Some(chain.chain.id()),
+            nonce: self.nonce,
+            gas_price,
+            gas_limit,
+            to: tx_kind,
+            value,
+            input,
+        });
+
+        let tx_signature_hash = transaction.signature_hash();
+        let signature = self.sign_hash(tx_signature_hash);
+
+        // increase nonce for next tx
+        self.inc_nonce();
+
+        TransactionSigned::new_unhashed(transaction, signature)
+    }
+
+    /// Create and sign an EIP2930 transaction with the provided access list.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_eip2930(
+        &mut self,
+        chain: Arc<ChainSpec>,
+        gas_limit: Option<u64>,
+        gas_price: u128,
+        to: Option<Address>,
+        value: U256,
+        input: Bytes,
+        access_list: AccessList,
+    ) -> TransactionSigned {
+        let gas_limit = gas_limit.unwrap_or(1_000_000);
+        let tx_kind = match to {
+            Some(address) => TxKind::Call(address),
+            None => TxKind::Create,
+        };
+
+        // Eip2930
+        let transaction = Transaction::Eip2930(TxEip2930 {
+            chain_id: chain.chain.id(),
+            nonce: self.nonce,
+            gas_price,
+            gas_limit,
+            to: tx_kind,
+            value,
+            access_list,
+            input,
+        });
+
+        let tx_signature_hash = transaction.signature_hash();
+        let signature = self.sign_hash(tx_signature_hash);
+
+        // increase nonce for next tx
+        self.inc_nonce();
+
+        TransactionSigned::new_unhashed(transaction, signature)
+    }
+
     /// Create and sign an EIP1559 transaction with all possible parameters passed.
     ///
     /// All arguments are optional and default to:
@@ -579,6 +671,44 @@ impl TransactionFactory {
             .expect("recovered tx added to pool")
     }
 
+    /// Create `count` sequential-nonce transactions and submit them to the provided
+    /// [TransactionPool] together.
+    ///
+    /// Returns the transaction hashes in nonce order.
+    pub async fn submit_batch_to_pool<Pool>(
+        &mut self,
+        count: usize,
+        chain: Arc<ChainSpec>,
+        gas_price: u128,
+        to: Address,
+        value: U256,
+        pool: Pool,
+    ) -> Vec<TxHash>
+    where
+        Pool: TransactionPool<Transaction = EthPooledTransaction>,
+    {
+        let transactions: Vec<EthPooledTransaction> = (0..count)
+            .map(|_| {
+                let tx = self.create_eip1559(
+                    chain.clone(),
+                    None,
+                    gas_price,
+                    Some(to),
+                    value,
+                    Bytes::new(),
+                );
+                let pooled_tx = tx.try_into_pooled().expect("tx valid for pool");
+                pooled_tx.try_into_ecrecovered().expect("tx is recovered").into()
+            })
+            .collect();
+
+        pool.add_transactions(TransactionOrigin::Local, transactions)
+            .await
+            .into_iter()
+            .map(|res| res.expect("recovered tx added to pool"))
+            .collect()
+    }
+
     /// Submit a transaction to the provided pool.
     pub async fn submit_tx_to_pool<Pool>(&self, tx: TransactionSigned, pool: Pool) -> TxHash
     where
@@ -595,6 +725,77 @@ impl TransactionFactory {
     }
 }
 
+/// Factory for signing transactions from many accounts.
+///
+/// The accounts are deterministically derived from a master seed so tests with many senders are
+/// reproducible. Transactions are created round-robin across the accounts.
+#[derive(Clone, Debug)]
+pub struct MultiAccountFactory {
+    /// The factory for each account.
+    factories: Vec<TransactionFactory>,
+    /// The index of the factory used for the next transaction.
+    next: usize,
+}
+
+impl MultiAccountFactory {
+    /// Create a new instance of self with `count` accounts derived from the master seed.
+    pub fn new(seed: [u8; 32], count: usize) -> Self {
+        assert!(count > 0, "multi account factory requires at least one account");
+        let mut rng = StdRng::from_seed(seed);
+        let factories =
+            (0..count).map(|_| TransactionFactory::new_random_from_seed(&mut rng)).collect();
+        Self { factories, next: 0 }
+    }
+
+    /// Return the addresses for all accounts in the order they are used.
+    pub fn addresses(&self) -> Vec<Address> {
+        self.factories.iter().map(|factory| factory.address()).collect()
+    }
+
+    /// Return the factories for all accounts.
+    pub fn factories(&self) -> &[TransactionFactory] {
+        &self.factories
+    }
+
+    /// Return the factory for the next account and advance the round-robin index.
+    pub fn next_factory(&mut self) -> &mut TransactionFactory {
+        let index = self.next;
+        self.next = (self.next + 1) % self.factories.len();
+        &mut self.factories[index]
+    }
+
+    /// Create and sign an EIP1559 transaction from the next account.
+    pub fn create_eip1559(
+        &mut self,
+        chain: Arc<ChainSpec>,
+        gas_limit: Option<u64>,
+        gas_price: u128,
+        to: Option<Address>,
+        value: U256,
+        input: Bytes,
+    ) -> TransactionSigned {
+        self.next_factory().create_eip1559(chain, gas_limit, gas_price, to, value, input)
+    }
+
+    /// Create and submit the next transaction from the next account to the provided
+    /// [TransactionPool].
+    pub async fn create_and_submit_eip1559_pool_tx<Pool>(
+        &mut self,
+        chain: Arc<ChainSpec>,
+        gas_price: u128,
+        to: Address,
+        value: U256,
+        pool: Pool,
+    ) -> TxHash
+    where
+        Pool: TransactionPool<Transaction = EthPooledTransaction>,
+    {
+        self.next_factory()
+            .create_and_submit_eip1559_pool_tx(chain, gas_price, to, value, pool)
+            .await
+    }
+}
+
 /// Helper to get the gas price based on the provider's latest header.
 pub fn get_gas_price<Provider>(provider: &Provider) -> u128
 where
@@ -628,3 +829,106 @@ pub async fn get_contract_state_for_genesis(
 
     Ok(execution_outcome)
 }
+
+/// Builder for a test [ChainSpec] with funded accounts and contracts at genesis.
+///
+/// Contracts are either added directly with their runtime bytecode and storage, or deployed by
+/// executing transactions against a temporary pre-genesis chain. The resulting code and storage
+/// for deployed contracts is then written into the final genesis.
+#[derive(Debug)]
+pub struct GenesisBuilder {
+    /// The genesis being extended.
+    genesis: Genesis,
+}
+
+impl Default for GenesisBuilder {
+    fn default() -> Self {
+        Self::new(adiri_genesis())
+    }
+}
+
+impl GenesisBuilder {
+    /// Create a new instance of [Self] that extends the provided genesis.
+    pub fn new(genesis: Genesis) -> Self {
+        Self { genesis }
+    }
+
+    /// Fund an account at genesis.
+    pub fn with_funded_account(self, address: Address, balance: U256) -> Self {
+        self.with_account(address, GenesisAccount::default().with_balance(balance))
+    }
+
+    /// Add a contract with runtime bytecode and storage at genesis.
+    pub fn with_contract(
+        self,
+        address: Address,
+        bytecode: Bytes,
+        storage: BTreeMap<B256, B256>,
+    ) -> Self {
+        let account = GenesisAccount::default()
+            .with_code(Some(bytecode))
+            .with_storage((!storage.is_empty()).then_some(storage));
+        self.with_account(address, account)
+    }
+
+    /// Add an account at genesis.
+    pub fn with_account(mut self, address: Address, account: GenesisAccount) -> Self {
+        self.genesis = self.genesis.extend_accounts(vec![(address, account)]);
+        self
+    }
+
+    /// Execute raw transactions against a temporary chain built from the current genesis and
+    /// add the resulting code and storage for each address in `contracts` to genesis.
+    ///
+    /// The `senders` are funded on the temporary chain only and are not included in the final
+    /// genesis.
+    pub async fn with_executed_contracts(
+        self,
+        senders: Vec<Address>,
+        raw_txs_to_execute: Vec<Vec<u8>>,
+        contracts: Vec<Address>,
+    ) -> eyre::Result<Self> {
+        let funded_senders = senders
+            .into_iter()
+            .map(|address| (address, GenesisAccount::default().with_balance(U256::MAX)));
+        let pre_genesis = self.genesis.clone().extend_accounts(funded_senders);
+        let pre_genesis_chain: Arc<ChainSpec> = Arc::new(pre_genesis.into());
+
+        // fetch state changes from pre-genesis for actual genesis
+        let execution_outcome =
+            get_contract_state_for_genesis(pre_genesis_chain, raw_txs_to_execute).await?;
+        let bundle = execution_outcome.bundle;
+
+        let mut builder = self;
+        for address in contracts {
+            let account = bundle
+                .state
+                .get(&address)
+                .ok_or_else(|| eyre::eyre!("contract {address} missing from bundle state"))?;
+            let bytecode = account
+                .info
+                .as_ref()
+                .and_then(|info| info.code.as_ref())
+                .map(|code| code.original_bytes())
+                .ok_or_else(|| eyre::eyre!("contract {address} missing code"))?;
+            let storage = account
+                .storage
+                .iter()
+                .map(|(k, v)| ((*k).into(), v.present_value.into()))
+                .collect();
+            builder = builder.with_contract(address, bytecode, storage);
+        }
+
+        Ok(builder)
+    }
+
+    /// Return the extended genesis.
+    pub fn into_genesis(self) -> Genesis {
+        self.genesis
+    }
+
+    /// Build the [ChainSpec] from the extended genesis.
+    pub fn build(self) -> ChainSpec {
+        self.genesis.into()
+    }
+}
@@ -6,6 +6,8 @@ mod execution;
 pub use execution::*;
 mod worker;
 pub use worker::*;
+mod worker_harness;
+pub use worker_harness::*;
 
 pub mod committee;
 pub use committee::*;
@@ -25,8 +27,11 @@ mod temp_dirs;
 pub use temp_dirs::*;
 
 mod tracing;
-pub use tracing::init_test_tracing;
+pub use tracing::{capture_test_tracing, init_test_tracing, TracingCapture};
 
+#[cfg(test)]
+#[path = "tests/execution_tests.rs"]
+mod execution_tests;
 #[cfg(test)]
 #[path = "tests/output_tests.rs"]
 mod output_tests;
@@ -0,0 +1,298 @@
+use crate::{
+    execution_builder, test_genesis, GenesisBuilder, MultiAccountFactory, TransactionFactory,
+};
+use jsonrpsee::{core::client::ClientT, rpc_params};
+use reth_chainspec::ChainSpec;
+use reth_cli_commands::node::NoArgs;
+use reth_transaction_pool::{EthPooledTransaction, PoolTransaction, TransactionOrigin};
+use std::{collections::BTreeMap, sync::Arc};
+use tn_batch_builder::TransactionValidationHook;
+use tn_config::{Config, ConsensusRegistry, CONSENSUS_REGISTRY_ADDRESS};
+use tn_node::{engine::ExecutionNode, error::ExecutionError};
+use tn_rpc::RawTransactionResult;
+use tn_types::{
+    hex, keccak256, Address, Bytes, Notifier, SolCall as _, TaskManager, TxHash, B256, U256,
+};
+
+/// Create transactions round-robin from the factory and return the hashes.
+fn create_hashes(factory: &mut MultiAccountFactory, chain: Arc<ChainSpec>) -> Vec<TxHash> {
+    (0..10)
+        .map(|_| {
+            factory
+                .create_eip1559(
+                    chain.clone(),
+                    None,
+                    7,
+                    Some(Address::ZERO),
+                    U256::from(1),
+                    Bytes::new(),
+                )
+                .hash()
+        })
+        .collect()
+}
+
+#[test]
+fn test_transaction_factory_from_seed() {
+    let seed = [7; 32];
+    assert_eq!(
+        TransactionFactory::from_seed(seed).address(),
+        TransactionFactory::from_seed(seed).address()
+    );
+    assert_ne!(TransactionFactory::from_seed(seed).address(), TransactionFactory::new().address());
+    assert_eq!(
+        TransactionFactory::from_seed([0; 32]).address(),
+        TransactionFactory::new().address()
+    );
+}
+
+#[test]
+fn test_multi_account_factory_is_deterministic() {
+    let chain: Arc<ChainSpec> = Arc::new(test_genesis().into());
+    let seed = [1; 32];
+
+    let mut first = MultiAccountFactory::new(seed, 4);
+    let mut second = MultiAccountFactory::new(seed, 4);
+
+    // same seed produces the same distinct signers
+    let addresses = first.addresses();
+    assert_eq!(addresses, second.addresses());
+    for (index, address) in addresses.iter().enumerate() {
+        assert!(!addresses[index + 1..].contains(address));
+    }
+
+    // same seed produces the same transactions
+    let first_hashes = create_hashes(&mut first, chain.clone());
+    let second_hashes = create_hashes(&mut second, chain.clone());
+    assert_eq!(first_hashes, second_hashes);
+
+    // transactions are created round-robin so each account signed the expected number
+    let nonces: Vec<u64> = first.factories().iter().map(|f| f.nonce()).collect();
+    assert_eq!(nonces, vec![3, 3, 2, 2]);
+
+    // a different seed produces different signers
+    let other = MultiAccountFactory::new([2; 32], 4);
+    assert_ne!(addresses, other.addresses());
+}
+
+#[test]
+fn test_genesis_builder_accounts_and_contracts() {
+    let funded = Address::random();
+    let contract = Address::random();
+    // runtime code that returns 42
+    let bytecode = Bytes::from(hex::decode("602a60005260206000f3").expect("valid hex"));
+    let storage = BTreeMap::from([(B256::ZERO, B256::with_last_byte(1))]);
+
+    let chain = GenesisBuilder::default()
+        .with_funded_account(funded, U256::from(100))
+        .with_contract(contract, bytecode.clone(), storage.clone())
+        .build();
+
+    let funded_account = chain.genesis.alloc.get(&funded).expect("funded account at genesis");
+    assert_eq!(funded_account.balance, U256::from(100));
+
+    let contract_account = chain.genesis.alloc.get(&contract).expect("contract at genesis");
+    assert_eq!(contract_account.code, Some(bytecode));
+    assert_eq!(contract_account.storage, Some(storage));
+}
+
+#[tokio::test]
+async fn test_genesis_builder_executed_contract() {
+    let chain: Arc<ChainSpec> = Arc::new(test_genesis().into());
+    let mut tx_factory = TransactionFactory::new();
+    let factory_address = tx_factory.address();
+
+    // initcode that deploys runtime code `602a60005260206000f3` (returns 42)
+    let initcode = hex::decode("69602a60005260206000f3600052600a6016f3").expect("valid hex");
+    let deploy_tx = tx_factory.create_eip1559_encoded(
+        chain.clone(),
+        Some(1_000_000),
+        7,
+        None,
+        U256::ZERO,
+        initcode.into(),
+    );
+    let contract = factory_address.create(0);
+
+    let chain = GenesisBuilder::default()
+        .with_executed_contracts(vec![factory_address], vec![deploy_tx], vec![contract])
+        .await
+        .expect("contract deployed")
+        .build();
+
+    // contract code is present at genesis
+    let contract_account = chain.genesis.alloc.get(&contract).expect("contract at genesis");
+    let expected = Bytes::from(hex::decode("602a60005260206000f3").expect("valid hex"));
+    assert_eq!(contract_account.code, Some(expected));
+
+    // pre-genesis senders are not funded in the final genesis
+    assert!(!chain.genesis.alloc.contains_key(&factory_address));
+}
+
+/// Runtime code for a mock registry that returns a single active validator for every call.
+fn mock_registry_code(registered: Address) -> Bytes {
+    let entry = ConsensusRegistry::ValidatorInfo {
+        blsPubkey: Bytes::copy_from_slice(Config::default().validator_info.public_key().as_ref()),
+        ed25519Pubkey: B256::ZERO,
+        ecdsaPubkey: registered,
+        activationEpoch: 0,
+        exitEpoch: 0,
+        validatorIndex: Default::default(),
+        currentStatus: ConsensusRegistry::ValidatorStatus::Active,
+    };
+    let data = ConsensusRegistry::getValidatorsCall::abi_encode_returns(&(vec![entry],));
+    let len = (data.len() as u16).to_be_bytes();
+
+    // copy the returndata appended after the 14-byte prefix into memory and return it
+    let mut code = vec![0x61, len[0], len[1], 0x60, 0x0e, 0x60, 0x00, 0x39];
+    code.extend([0x61, len[0], len[1], 0x60, 0x00, 0xf3]);
+    code.extend(data);
+    code.into()
+}
+
+#[tokio::test]
+async fn test_beneficiary_verified_against_registry() -> eyre::Result<()> {
+    let address = Address::random();
+
+    // the registered address does not match the node's execution address
+    let chain = GenesisBuilder::new(test_genesis())
+        .with_contract(
+            CONSENSUS_REGISTRY_ADDRESS,
+            mock_registry_code(Address::random()),
+            BTreeMap::new(),
+        )
+        .build();
+    let (builder, _) = execution_builder::<NoArgs>(Some(Arc::new(chain)), Some(address), None)?;
+    let execution_node = ExecutionNode::new(&builder, &TaskManager::default())?;
+    let err = execution_node.verify_beneficiary().await.expect_err("beneficiary mismatch");
+    assert!(
+        matches!(err, ExecutionError::BeneficiaryMismatch { derived, .. } if derived == address)
+    );
+
+    // the registered address matches
+    let chain = GenesisBuilder::new(test_genesis())
+        .with_contract(CONSENSUS_REGISTRY_ADDRESS, mock_registry_code(address), BTreeMap::new())
+        .build();
+    let (builder, _) = execution_builder::<NoArgs>(Some(Arc::new(chain)), Some(address), None)?;
+    let execution_node = ExecutionNode::new(&builder, &TaskManager::default())?;
+    execution_node.verify_beneficiary().await?;
+
+    Ok(())
+}
+
+/// Validation hook that rejects transactions sent to a blocked address.
+#[derive(Debug)]
+struct BlockedRecipientHook {
+    blocked: Address,
+}
+
+impl TransactionValidationHook<EthPooledTransaction> for BlockedRecipientHook {
+    fn validate(
+        &self,
+        _origin: TransactionOrigin,
+        transaction: &EthPooledTransaction,
+    ) -> Result<(), String> {
+        if transaction.to() == Some(self.blocked) {
+            return Err(format!("recipient {} is blocked", self.blocked));
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_tx_validation_hook_rejects_at_worker_rpc() -> eyre::Result<()> {
+    let chain: Arc<ChainSpec> = Arc::new(test_genesis().into());
+    let blocked = Address::random();
+
+    // create engine node with the validation hook
+    let (mut builder, _) = execution_builder::<NoArgs>(Some(chain.clone()), None, None)?;
+    builder.opt_tx_validation_hook = Some(Arc::new(BlockedRecipientHook { blocked }));
+    let execution_node = ExecutionNode::new(&builder, &TaskManager::default())?;
+
+    let shutdown = Notifier::default();
+    let worker_id = 0;
+    let (to_worker, _next_batch) = tokio::sync::mpsc::channel(2);
+    execution_node
+        .start_batch_builder(worker_id, to_worker, &TaskManager::default(), shutdown.subscribe())
+        .await?;
+    let client = execution_node.worker_http_client(&worker_id).await?.expect("worker rpc client");
+
+    // transactions to the blocked address are rejected
+    let mut tx_factory = TransactionFactory::default();
+    let blocked_tx = tx_factory.create_eip1559_encoded(
+        chain.clone(),
+        None,
+        7,
+        Some(blocked),
+        U256::from(1),
+        Bytes::new(),
+    );
+    let response = client
+        .request::<String, _>("eth_sendRawTransaction", rpc_params![Bytes::from(blocked_tx)])
+        .await;
+    assert!(response.is_err());
+
+    // all other transactions are accepted
+    let allowed_tx = tx_factory.create_eip1559_encoded(
+        chain,
+        None,
+        7,
+        Some(Address::random()),
+        U256::from(1),
+        Bytes::new(),
+    );
+    let response = client
+        .request::<String, _>("eth_sendRawTransaction", rpc_params![Bytes::from(allowed_tx)])
+        .await;
+    assert!(response.is_ok());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_send_raw_transactions_partial_failure() -> eyre::Result<()> {
+    let chain: Arc<ChainSpec> = Arc::new(test_genesis().into());
+
+    // create engine node
+    let (builder, _) = execution_builder::<NoArgs>(Some(chain.clone()), None, None)?;
+    let execution_node = ExecutionNode::new(&builder, &TaskManager::default())?;
+
+    let shutdown = Notifier::default();
+    let worker_id = 0;
+    let (to_worker, _next_batch) = tokio::sync::mpsc::channel(2);
+    execution_node
+        .start_batch_builder(worker_id, to_worker, &TaskManager::default(), shutdown.subscribe())
+        .await?;
+    let client = execution_node.worker_http_client(&worker_id).await?.expect("worker rpc client");
+
+    // two valid transactions, a malformed transaction, and a duplicate
+    let mut tx_factory = TransactionFactory::default();
+    let mut valid_tx = || {
+        Bytes::from(tx_factory.create_eip1559_encoded(
+            chain.clone(),
+            None,
+            7,
+            Some(Address::random()),
+            U256::from(1),
+            Bytes::new(),
+        ))
+    };
+    let first = valid_tx();
+    let second = valid_tx();
+    let malformed = Bytes::from_static(&[0x02, 0xde, 0xad]);
+    let transactions = vec![first.clone(), malformed, second.clone(), first.clone()];
+
+    let results = client
+        .request::<Vec<RawTransactionResult>, _>(
+            "tn_sendRawTransactions",
+            rpc_params![transactions],
+        )
+        .await?;
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0], RawTransactionResult::Added { hash: keccak256(&first) });
+    assert!(matches!(results[1], RawTransactionResult::Rejected { .. }));
+    assert_eq!(results[2], RawTransactionResult::Added { hash: keccak256(&second) });
+    assert!(matches!(results[3], RawTransactionResult::Rejected { .. }));
+
+    Ok(())
+}
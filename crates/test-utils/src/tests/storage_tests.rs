@@ -9,10 +9,15 @@ use std::{
 use crate::{fixture_batch_with_transactions, temp_dir, CommitteeFixture};
 use futures::future::join_all;
 use tempfile::TempDir;
-use tn_storage::{mem_db::MemDatabase, open_db, CertificateStore, ConsensusStore, ProposerStore};
+use tn_storage::{
+    mem_db::MemDatabase,
+    open_db,
+    tables::{Batches, LastCommitted},
+    CertificateStore, ConsensusStore, PayloadStore, ProposerStore,
+};
 use tn_types::{
-    AuthorityIdentifier, Certificate, CertificateDigest, CommittedSubDag, Hash as _, Header,
-    HeaderBuilder, ReputationScores, Round,
+    AuthorityIdentifier, Certificate, CertificateDigest, CommittedSubDag, ConsensusHeader,
+    Database as _, Hash as _, Header, HeaderBuilder, ReputationScores, Round,
 };
 
 pub fn create_header_for_round(round: Round) -> Header {
@@ -135,6 +140,189 @@ async fn test_consensus_store_read_latest_final_reputation_scores() {
     assert!(commit.reputation_score.final_of_schedule);
 }
 
+#[tokio::test]
+async fn test_consensus_store_read_recent_committed_sub_dags() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = open_db(temp_dir.path());
+    let fixture = CommitteeFixture::builder(MemDatabase::default).build();
+    let committee = fixture.committee();
+
+    // nothing committed yet
+    assert!(store.read_recent_committed_sub_dags(5).is_empty());
+
+    for sequence_number in 0..10 {
+        let mut leader = Certificate::default();
+        leader.header.round = sequence_number as Round;
+        let sub_dag = CommittedSubDag::new(
+            vec![],
+            leader,
+            sequence_number,
+            ReputationScores::new(&committee),
+            None,
+        );
+        store.write_subdag_for_test(sequence_number, sub_dag);
+    }
+
+    // the last 5 sub dags are returned in descending order
+    let recent = store.read_recent_committed_sub_dags(5);
+    assert_eq!(recent.iter().map(|(number, _)| *number).collect::<Vec<_>>(), vec![9, 8, 7, 6, 5]);
+    for (number, sub_dag) in &recent {
+        assert_eq!(sub_dag.leader_round(), *number as Round);
+    }
+
+    // requesting more than the chain length returns every sub dag
+    assert_eq!(store.read_recent_committed_sub_dags(20).len(), 10);
+}
+
+#[tokio::test]
+async fn test_consensus_store_bump_last_committed() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = open_db(temp_dir.path());
+    let authority = AuthorityIdentifier::default();
+
+    // first commit always advances
+    assert!(store.bump_last_committed(&authority, 5).unwrap());
+    assert_eq!(store.get::<LastCommitted>(&authority).unwrap(), Some(5));
+
+    // stale or repeated rounds are a no-op
+    assert!(!store.bump_last_committed(&authority, 3).unwrap());
+    assert!(!store.bump_last_committed(&authority, 5).unwrap());
+    assert_eq!(store.get::<LastCommitted>(&authority).unwrap(), Some(5));
+
+    // higher rounds advance
+    assert!(store.bump_last_committed(&authority, 8).unwrap());
+    assert_eq!(store.get::<LastCommitted>(&authority).unwrap(), Some(8));
+
+    // the stale bumps did not leave a txn open, so the latest round was persisted
+    drop(store);
+    let store = open_db(temp_dir.path());
+    assert_eq!(store.get::<LastCommitted>(&authority).unwrap(), Some(8));
+    assert!(!store.bump_last_committed(&authority, 7).unwrap());
+    assert!(store.bump_last_committed(&authority, 9).unwrap());
+    drop(store);
+    let store = open_db(temp_dir.path());
+    assert_eq!(store.get::<LastCommitted>(&authority).unwrap(), Some(9));
+}
+
+#[tokio::test]
+async fn test_consensus_store_export_committed_range() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = open_db(temp_dir.path());
+    let fixture = CommitteeFixture::builder(MemDatabase::default).build();
+    let committee = fixture.committee();
+
+    // commit a sub dag with a certificate from every authority for each number
+    let mut committed = Vec::new();
+    for number in 0..10 {
+        let certificates: Vec<_> = fixture
+            .authorities()
+            .map(|authority| {
+                let batch = fixture_batch_with_transactions(2);
+                store.insert::<Batches>(&batch.digest(), &batch).expect("batch stored");
+                let header = authority
+                    .header_builder(&committee)
+                    .round(number as Round + 1)
+                    .with_payload_batch(batch, 0, 0)
+                    .build();
+                fixture.certificate(&header)
+            })
+            .collect();
+        let leader = certificates[0].clone();
+        let sub_dag = CommittedSubDag::new(
+            certificates,
+            leader,
+            number,
+            ReputationScores::new(&committee),
+            None,
+        );
+        store.write_subdag_for_test(number, sub_dag.clone());
+        committed.push(sub_dag);
+    }
+
+    // export a range from the middle of the chain
+    let exported = store
+        .export_committed_range(3, 6)
+        .expect("export range")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("sub dags resolved");
+    assert_eq!(exported.iter().map(|e| e.number).collect::<Vec<_>>(), vec![3, 4, 5, 6]);
+
+    for (exported, committed) in exported.iter().zip(&committed[3..=6]) {
+        let exported_digests: Vec<_> =
+            exported.sub_dag.certificates.iter().map(|cert| cert.digest()).collect();
+        let committed_digests: Vec<_> =
+            committed.certificates.iter().map(|cert| cert.digest()).collect();
+        assert_eq!(exported_digests, committed_digests);
+        assert_eq!(exported.sub_dag.leader.digest(), committed.leader.digest());
+
+        // batches are resolved for every certificate
+        assert_eq!(exported.batches.len(), committed.certificates.len());
+        for (batches, cert) in exported.batches.iter().zip(&committed.certificates) {
+            let batch_digests: Vec<_> = batches.iter().map(|batch| batch.digest()).collect();
+            let payload: Vec<_> = cert.header().payload().keys().copied().collect();
+            assert_eq!(batch_digests, payload);
+        }
+    }
+
+    // ranges past the end of the chain only include committed sub dags
+    let exported = store.export_committed_range(8, 20).expect("export range");
+    assert_eq!(exported.count(), 2);
+}
+
+#[tokio::test]
+async fn test_payload_store_missing_batches_for_header() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = open_db(temp_dir.path());
+    let fixture = CommitteeFixture::builder(MemDatabase::default).build();
+    let committee = fixture.committee();
+
+    // each authority's certificate references a batch from a different worker
+    let mut expected_missing = Vec::new();
+    let certificates: Vec<_> = fixture
+        .authorities()
+        .enumerate()
+        .map(|(i, authority)| {
+            let worker_id = i as u16;
+            let batch = fixture_batch_with_transactions(2);
+            let digest = batch.digest();
+            match i % 3 {
+                // batch stored by a worker
+                0 => store.insert::<Batches>(&digest, &batch).expect("batch stored"),
+                // batch availability recorded by the primary
+                1 => store.write_payload(&digest, &worker_id).expect("payload stored"),
+                // batch missing
+                _ => expected_missing.push((digest, worker_id)),
+            }
+            let header = authority
+                .header_builder(&committee)
+                .round(1)
+                .with_payload_batch(batch, worker_id, 0)
+                .build();
+            fixture.certificate(&header)
+        })
+        .collect();
+    assert!(!expected_missing.is_empty());
+
+    let leader = certificates[0].clone();
+    let sub_dag =
+        CommittedSubDag::new(certificates, leader, 1, ReputationScores::new(&committee), None);
+    let header = ConsensusHeader { sub_dag, number: 1, ..Default::default() };
+
+    let missing = store.missing_batches_for_header(&header).expect("missing batches");
+    assert_eq!(missing, expected_missing);
+
+    // payloads recorded for a different worker are still missing
+    let (digest, worker_id) = expected_missing[0];
+    store.write_payload(&digest, &(worker_id + 1)).expect("payload stored");
+    let missing = store.missing_batches_for_header(&header).expect("missing batches");
+    assert_eq!(missing, expected_missing);
+
+    // fetched batches are no longer missing
+    store.write_payload(&digest, &worker_id).expect("payload stored");
+    let missing = store.missing_batches_for_header(&header).expect("missing batches");
+    assert_eq!(missing, expected_missing[1..]);
+}
+
 #[tokio::test]
 async fn test_certificate_store_write_and_read() {
     let db = open_db(temp_dir());
@@ -347,6 +535,42 @@ async fn test_certificate_store_after_round() {
     }
 }
 
+#[tokio::test]
+async fn test_certificate_store_round_range() {
+    let store = open_db(temp_dir());
+    let certs = certificates(20);
+    store.write_all(certs.clone()).unwrap();
+
+    let (from, to): (Round, Round) = (5, 9);
+    let expected: HashSet<_> =
+        certs.iter().filter(|c| c.round() >= from && c.round() <= to).map(|c| c.digest()).collect();
+
+    let result = store
+        .fetch_certificates_in_round_range(from, to)
+        .expect("Error returned while reading round range");
+    assert_eq!(result.len(), expected.len());
+    assert_eq!(result.len() as u32, (to - from + 1) * 4);
+
+    // certificates are returned in increasing round order
+    let mut last_round = from;
+    for certificate in result.iter() {
+        assert!(certificate.round() >= last_round);
+        assert!(certificate.round() <= to);
+        last_round = certificate.round();
+    }
+    let result: HashSet<_> = result.iter().map(|c| c.digest()).collect();
+    assert_eq!(result, expected);
+
+    // a single round
+    let result = store.fetch_certificates_in_round_range(to, to).unwrap();
+    assert_eq!(result.len(), 4);
+    assert!(result.iter().all(|c| c.round() == to));
+
+    // empty ranges
+    assert!(store.fetch_certificates_in_round_range(to, from).unwrap().is_empty());
+    assert!(store.fetch_certificates_in_round_range(100, 200).unwrap().is_empty());
+}
+
 #[tokio::test]
 async fn test_certificate_store_notify_read() {
     let store = open_db(temp_dir());
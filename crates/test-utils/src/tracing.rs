@@ -1,6 +1,11 @@
 //! Tracing helper to subscribe to tracing output.
 
-use tracing_subscriber::EnvFilter;
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+use tracing::subscriber::DefaultGuard;
+use tracing_subscriber::{fmt::MakeWriter, EnvFilter};
 
 ///  Initializes a tracing subscriber for tests that's configurable with `RUST_LOG`. This function
 /// silently fails if the subscriber could not be installed.
@@ -11,3 +16,58 @@ pub fn init_test_tracing() {
         .with_writer(std::io::stdout)
         .try_init();
 }
+
+/// Formatted tracing output captured by [capture_test_tracing].
+#[derive(Clone, Debug, Default)]
+pub struct TracingCapture {
+    /// The formatted output.
+    buffer: Arc<Mutex<Vec<u8>>>,
+}
+
+impl TracingCapture {
+    /// Return the captured lines that contain `pattern`.
+    ///
+    /// Each line is a single event formatted with the context of its spans.
+    pub fn lines_containing(&self, pattern: &str) -> Vec<String> {
+        let buffer = self.buffer.lock().expect("tracing capture lock poisoned");
+        String::from_utf8_lossy(&buffer)
+            .lines()
+            .filter(|line| line.contains(pattern))
+            .map(ToString::to_string)
+            .collect()
+    }
+}
+
+impl io::Write for TracingCapture {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.lock().expect("tracing capture lock poisoned").extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for TracingCapture {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// Capture all tracing events emitted on the current thread.
+///
+/// Events are captured until the returned guard is dropped. Tasks spawned on a current-thread
+/// runtime are also captured.
+pub fn capture_test_tracing() -> (TracingCapture, DefaultGuard) {
+    let capture = TracingCapture::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::TRACE)
+        .with_ansi(false)
+        .with_writer(capture.clone())
+        .finish();
+    let guard = tracing::subscriber::set_default(subscriber);
+    (capture, guard)
+}
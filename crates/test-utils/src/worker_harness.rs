@@ -0,0 +1,316 @@
+//! Test harness for the worker's execution pipeline.
+//!
+//! The harness initializes genesis, the provider, and the transaction pool, then spawns a
+//! [BatchBuilder] that sends built batches to the test instead of a worker.
+
+use crate::{get_gas_price, test_genesis, TransactionFactory};
+use reth_blockchain_tree::noop::NoopBlockchainTree;
+use reth_chainspec::ChainSpec;
+use reth_db::{
+    test_utils::{create_test_rw_db, tempdir_path, TempDatabase},
+    DatabaseEnv,
+};
+use reth_db_common::init::init_genesis;
+use reth_provider::{
+    providers::{BlockchainProvider, StaticFileProvider},
+    CanonStateNotificationSender, CanonStateSubscriptions as _, ProviderFactory,
+};
+use reth_tasks::TaskManager;
+use reth_transaction_pool::{
+    blobstore::InMemoryBlobStore, EthTransactionPool, PoolConfig, TransactionPool as _,
+    TransactionValidationTaskExecutor,
+};
+use std::{sync::Arc, time::Duration};
+use tn_batch_builder::{BatchBuilder, BatchBuilderHandle};
+use tn_config::IncludedTxPropagation;
+use tn_node_traits::TelcoinNode;
+use tn_types::{
+    error::BlockSealError, Address, BlockBody, Genesis, GenesisAccount, LastCanonicalUpdate,
+    SealedBatch, SealedBlock, TxHash, WorkerId, U160, U256,
+};
+use tokio::{
+    sync::{broadcast, mpsc, oneshot, watch},
+    task::JoinHandle,
+    time::timeout,
+};
+
+/// The provider used by the [WorkerTestHarness].
+pub type TestBlockchainProvider = BlockchainProvider<TelcoinNode<Arc<TempDatabase<DatabaseEnv>>>>;
+
+/// The transaction pool used by the [WorkerTestHarness].
+pub type TestWorkerPool = EthTransactionPool<TestBlockchainProvider, InMemoryBlobStore>;
+
+/// A batch sent by the batch builder with the channel to acknowledge quorum.
+pub type TestBatch = (SealedBatch, oneshot::Sender<Result<(), BlockSealError>>);
+
+/// Builder for a [WorkerTestHarness].
+#[derive(Debug)]
+pub struct WorkerTestHarnessBuilder {
+    /// The genesis for the chain.
+    ///
+    /// The default [TransactionFactory] account is always funded.
+    genesis: Genesis,
+    /// The config for the transaction pool.
+    pool_config: PoolConfig,
+    /// The max amount of time between batch builds.
+    max_batch_delay: Duration,
+    /// The max number of batches waiting for quorum at the same time.
+    max_concurrent_builds: usize,
+    /// The beneficiary for built batches.
+    beneficiary: Address,
+    /// The minimum number of connected peers before building batches.
+    ///
+    /// The batch builder does not track connected peers when this is `None`.
+    min_peers_to_build: Option<usize>,
+    /// The id of the worker the batch builder proposes batches for.
+    worker_id: WorkerId,
+    /// The max amount of time a queued transaction stays in the pool.
+    max_queued_tx_lifetime: Option<Duration>,
+    /// The policy for propagating transactions included in a batch.
+    included_tx_propagation: IncludedTxPropagation,
+}
+
+impl Default for WorkerTestHarnessBuilder {
+    fn default() -> Self {
+        Self {
+            genesis: test_genesis(),
+            pool_config: PoolConfig::default(),
+            max_batch_delay: Duration::from_secs(1),
+            max_concurrent_builds: 1,
+            beneficiary: Address::from(U160::from(33)),
+            min_peers_to_build: None,
+            worker_id: 0,
+            max_queued_tx_lifetime: None,
+            included_tx_propagation: IncludedTxPropagation::default(),
+        }
+    }
+}
+
+impl WorkerTestHarnessBuilder {
+    /// Fund an account at genesis.
+    pub fn with_funded_account(mut self, address: Address, balance: U256) -> Self {
+        self.genesis = self
+            .genesis
+            .extend_accounts(vec![(address, GenesisAccount::default().with_balance(balance))]);
+        self
+    }
+
+    /// Set the config for the transaction pool (ie - pending pool limits).
+    pub fn with_pool_config(mut self, pool_config: PoolConfig) -> Self {
+        self.pool_config = pool_config;
+        self
+    }
+
+    /// Set the max amount of time between batch builds.
+    pub fn with_max_batch_delay(mut self, max_batch_delay: Duration) -> Self {
+        self.max_batch_delay = max_batch_delay;
+        self
+    }
+
+    /// Set the max number of batches waiting for quorum at the same time.
+    pub fn with_max_concurrent_builds(mut self, max_concurrent_builds: usize) -> Self {
+        self.max_concurrent_builds = max_concurrent_builds;
+        self
+    }
+
+    /// Set the beneficiary for built batches.
+    pub fn with_beneficiary(mut self, beneficiary: Address) -> Self {
+        self.beneficiary = beneficiary;
+        self
+    }
+
+    /// Set the id of the worker the batch builder proposes batches for.
+    pub fn with_worker_id(mut self, worker_id: WorkerId) -> Self {
+        self.worker_id = worker_id;
+        self
+    }
+
+    /// Evict queued transactions that stay in the pool longer than `max_queued_tx_lifetime`.
+    pub fn with_max_queued_tx_lifetime(mut self, max_queued_tx_lifetime: Duration) -> Self {
+        self.max_queued_tx_lifetime = Some(max_queued_tx_lifetime);
+        self
+    }
+
+    /// Set the policy for propagating transactions included in a batch.
+    pub fn with_included_tx_propagation(
+        mut self,
+        included_tx_propagation: IncludedTxPropagation,
+    ) -> Self {
+        self.included_tx_propagation = included_tx_propagation;
+        self
+    }
+
+    /// Defer building batches until `min_peers_to_build` peers are connected.
+    ///
+    /// The number of connected peers starts at `0` and is updated through
+    /// [WorkerTestHarness::connected_peers].
+    pub fn with_min_peers_to_build(mut self, min_peers_to_build: usize) -> Self {
+        self.min_peers_to_build = Some(min_peers_to_build);
+        self
+    }
+
+    /// Initialize genesis and spawn the batch builder.
+    ///
+    /// This must be called within a tokio runtime.
+    pub fn build(self) -> WorkerTestHarness {
+        let Self {
+            genesis,
+            pool_config,
+            max_batch_delay,
+            max_concurrent_builds,
+            beneficiary,
+            min_peers_to_build,
+            worker_id,
+            max_queued_tx_lifetime,
+            included_tx_propagation,
+        } = self;
+        let head_timestamp = genesis.timestamp;
+        let chain: Arc<ChainSpec> = Arc::new(genesis.into());
+
+        // provider
+        let factory = ProviderFactory::new(
+            create_test_rw_db(),
+            Arc::clone(&chain),
+            StaticFileProvider::read_write(tempdir_path())
+                .expect("static file provider read write created with tempdir path"),
+        );
+        init_genesis(&factory).expect("init genesis");
+        let (canon_state_notifications, _) = broadcast::channel(16);
+        let tree =
+            NoopBlockchainTree::with_canon_state_notifications(canon_state_notifications.clone());
+        let blockchain_db: TestBlockchainProvider =
+            BlockchainProvider::new(factory, Arc::new(tree)).expect("test blockchain provider");
+
+        // txpool
+        let task_manager = TaskManager::current();
+        let blob_store = InMemoryBlobStore::default();
+        let validator = TransactionValidationTaskExecutor::eth_builder(Arc::clone(&chain))
+            .with_head_timestamp(head_timestamp)
+            .with_additional_tasks(1)
+            .build_with_tasks(blockchain_db.clone(), task_manager.executor(), blob_store.clone());
+        let txpool = reth_transaction_pool::Pool::eth_pool(validator, blob_store, pool_config);
+
+        // batch builder
+        let pool_info = txpool.block_info();
+        let latest_canon_state = LastCanonicalUpdate {
+            tip: SealedBlock::new(chain.sealed_genesis_header(), BlockBody::default()),
+            pending_block_base_fee: pool_info.pending_basefee,
+            pending_block_blob_fee: pool_info.pending_blob_fee,
+        };
+        let (to_worker, from_batch_builder) = mpsc::channel(2);
+        let (connected_peers, connected_peers_rx) = watch::channel(0);
+        let mut batch_builder = BatchBuilder::new(
+            blockchain_db.clone(),
+            txpool.clone(),
+            blockchain_db.canonical_state_stream(),
+            latest_canon_state,
+            to_worker,
+            beneficiary,
+            max_batch_delay,
+        )
+        .with_max_concurrent_builds(max_concurrent_builds)
+        .with_worker_id(worker_id)
+        .with_included_tx_propagation(included_tx_propagation);
+        if let Some(max_queued_tx_lifetime) = max_queued_tx_lifetime {
+            batch_builder = batch_builder.with_max_queued_tx_lifetime(max_queued_tx_lifetime);
+        }
+        if let Some(min_peers_to_build) = min_peers_to_build {
+            batch_builder =
+                batch_builder.with_min_peers_to_build(min_peers_to_build, connected_peers_rx);
+        }
+        let handle = batch_builder.handle();
+        let batch_builder_task = tokio::spawn(async move {
+            let _ = Box::pin(batch_builder).await;
+        });
+
+        WorkerTestHarness {
+            chain,
+            blockchain_db,
+            tx_factory: TransactionFactory::default(),
+            txpool,
+            handle,
+            from_batch_builder,
+            connected_peers,
+            canon_state_notifications,
+            _task_manager: task_manager,
+            _batch_builder_task: batch_builder_task,
+        }
+    }
+}
+
+/// A running batch builder with a funded [TransactionFactory].
+///
+/// Built batches are received by the test instead of a worker. Batches must be acknowledged with
+/// the returned channel for the transactions to be mined.
+#[derive(Debug)]
+pub struct WorkerTestHarness {
+    /// The chain for the harness's genesis.
+    pub chain: Arc<ChainSpec>,
+    /// The provider initialized with genesis.
+    pub blockchain_db: TestBlockchainProvider,
+    /// The factory for the account funded at genesis.
+    pub tx_factory: TransactionFactory,
+    /// The worker's transaction pool.
+    pub txpool: TestWorkerPool,
+    /// The handle to pause and resume the batch builder.
+    pub handle: BatchBuilderHandle,
+    /// The batches sent by the batch builder.
+    pub from_batch_builder: mpsc::Receiver<TestBatch>,
+    /// The number of connected peers reported to the batch builder.
+    ///
+    /// Only used if the harness was built with a `min_peers_to_build`.
+    pub connected_peers: watch::Sender<usize>,
+    /// The sender for canonical state notifications received by the batch builder.
+    ///
+    /// The provider does not execute blocks, so tests send notifications to advance the
+    /// canonical tip.
+    pub canon_state_notifications: CanonStateNotificationSender,
+    /// The task manager for the pool's validation tasks.
+    _task_manager: TaskManager,
+    /// The spawned batch builder.
+    _batch_builder_task: JoinHandle<()>,
+}
+
+impl WorkerTestHarness {
+    /// Return a builder for the harness.
+    pub fn builder() -> WorkerTestHarnessBuilder {
+        WorkerTestHarnessBuilder::default()
+    }
+
+    /// Create a harness with the default configuration.
+    ///
+    /// This must be called within a tokio runtime.
+    pub fn new() -> Self {
+        Self::builder().build()
+    }
+
+    /// Submit a transfer of `value` to `to` from the funded account.
+    pub async fn submit_transfer(&mut self, to: Address, value: U256) -> TxHash {
+        let gas_price = get_gas_price(&self.blockchain_db);
+        self.tx_factory
+            .create_and_submit_eip1559_pool_tx(
+                self.chain.clone(),
+                gas_price,
+                to,
+                value,
+                &self.txpool,
+            )
+            .await
+    }
+
+    /// Wait for the next batch from the batch builder.
+    ///
+    /// Panics if a batch isn't built within `duration`.
+    pub async fn next_batch(&mut self, duration: Duration) -> TestBatch {
+        timeout(duration, self.from_batch_builder.recv())
+            .await
+            .expect("batch built before timeout")
+            .expect("batch builder's sender didn't drop")
+    }
+}
+
+impl Default for WorkerTestHarness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
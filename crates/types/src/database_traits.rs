@@ -1,7 +1,11 @@
 //! Database traits for compatibility.
 
+use crate::{
+    error::{StoreError, StoreErrorKind},
+    try_decode, try_decode_key,
+};
 use serde::{de::DeserializeOwned, Serialize};
-use std::{borrow::Borrow, fmt::Debug};
+use std::{borrow::Borrow, fmt::Debug, io::Write};
 
 pub trait KeyT: Serialize + DeserializeOwned + Send + Sync + Ord + Clone + Debug + 'static {}
 pub trait ValueT: Serialize + DeserializeOwned + Send + Sync + Clone + Debug + 'static {}
@@ -14,6 +18,22 @@ pub trait Table: Send + Sync + Debug + 'static {
     type Value: ValueT;
 
     const NAME: &'static str;
+
+    /// The max size of an encoded value.  Inserts of larger values are rejected with
+    /// [StoreErrorKind::ValueTooLarge].  None (the default) means no limit.
+    const MAX_VALUE_BYTES: Option<usize> = None;
+}
+
+/// Return an error if `size` encoded value bytes is more than the table allows.
+///
+/// DB backends call this before writing a value, see [Table::MAX_VALUE_BYTES].
+pub fn ensure_value_size<T: Table>(size: usize) -> eyre::Result<()> {
+    match T::MAX_VALUE_BYTES {
+        Some(max) if size > max => {
+            Err(StoreErrorKind::ValueTooLarge { table: T::NAME, size, max }.into())
+        }
+        _ => Ok(()),
+    }
 }
 
 /// Interface to a DB read transaction.
@@ -32,6 +52,22 @@ pub trait DbTxMut: DbTx {
     /// Returns the value for the given key from the map, if it exists.
     fn insert<T: Table>(&mut self, key: &T::Key, value: &T::Value) -> eyre::Result<()>;
 
+    /// Inserts already encoded key and value bytes into table T.
+    /// The bytes must have been produced by encode_key/encode (for instance from
+    /// [`Database::iter_raw`]), they are not verified.
+    fn insert_raw<T: Table>(&mut self, key: &[u8], value: &[u8]) -> eyre::Result<()>;
+
+    /// Inserts every encoded key-value pair from rows into table T.
+    fn multi_insert_raw<T: Table>(
+        &mut self,
+        rows: impl IntoIterator<Item = (Vec<u8>, Vec<u8>)>,
+    ) -> eyre::Result<()> {
+        for (key, value) in rows {
+            self.insert_raw::<T>(&key, &value)?;
+        }
+        Ok(())
+    }
+
     /// Removes the entry for the given key from the map.
     fn remove<T: Table>(&mut self, key: &T::Key) -> eyre::Result<()>;
 
@@ -42,8 +78,60 @@ pub trait DbTxMut: DbTx {
     fn commit(self) -> eyre::Result<()>;
 }
 
+/// Interface to a consistent read only view of the DB.
+/// Reads from a snapshot, across all tables, will not see any writes committed after it was
+/// created.
+pub trait DbSnapshot: DbTx {
+    /// Returns an unbounded iterator visiting each key-value pair in the table as of the snapshot.
+    fn iter<T: Table>(&self) -> DBIter<'_, T>;
+}
+
+/// How durable a committed write transaction is.
+///
+/// Trades durability for commit latency, see each backend for how these map to its settings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Durability {
+    /// Commits are not synced to disk.  A crash (or for some backends a reopen) can lose
+    /// commits, only use for tests or data that can be rebuilt.
+    None,
+    /// Commits are synced to disk eventually.  A crash can lose the most recent commits but the
+    /// DB will be consistent once reopened.
+    Eventual,
+    /// Commits are synced to disk before commit returns.
+    #[default]
+    Sync,
+}
+
+/// The result of verifying every row in a table decodes, see [`Database::verify_table`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// The number of rows with a key and value that decoded.
+    pub valid: usize,
+    /// The encoded keys of rows that failed to decode.
+    pub corrupt_keys: Vec<Vec<u8>>,
+}
+
+impl VerifyReport {
+    /// The number of rows that failed to decode.
+    pub fn corrupt(&self) -> usize {
+        self.corrupt_keys.len()
+    }
+
+    /// True if every row decoded.
+    pub fn is_ok(&self) -> bool {
+        self.corrupt_keys.is_empty()
+    }
+}
+
 pub type DBIter<'i, T> = Box<dyn Iterator<Item = (<T as Table>::Key, <T as Table>::Value)> + 'i>;
 
+/// Iterator over the encoded (key, value) bytes of a table.
+pub type DBIterRaw<'i> = Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'i>;
+
+/// Iterator over a table that decodes each row as it is visited, see [`Database::iter_checked`].
+pub type DBIterChecked<'i, T> =
+    Box<dyn Iterator<Item = Result<(<T as Table>::Key, <T as Table>::Value), StoreError>> + 'i>;
+
 pub trait Database: Send + Sync + Clone + Unpin + 'static {
     type TX<'txn>: DbTx + Send + Debug + 'txn
     where
@@ -51,13 +139,30 @@ pub trait Database: Send + Sync + Clone + Unpin + 'static {
     type TXMut<'txn>: DbTxMut + Send + Debug + 'txn
     where
         Self: 'txn;
+    type Snapshot<'snap>: DbSnapshot + Send + Debug + 'snap
+    where
+        Self: 'snap;
 
     /// Return a read txn object.
     fn read_txn(&self) -> eyre::Result<Self::TX<'_>>;
 
     /// Return a write txn object.
+    /// The txn commits with the durability the DB was opened with.
     fn write_txn(&self) -> eyre::Result<Self::TXMut<'_>>;
 
+    /// Return a write txn object that commits with `durability` instead of the DB's default.
+    /// Backends that can only set durability when opened use [`Self::write_txn`].
+    fn write_txn_with_durability(&self, durability: Durability) -> eyre::Result<Self::TXMut<'_>> {
+        let _ = durability;
+        self.write_txn()
+    }
+
+    /// Return a snapshot of the DB.  Reads from the snapshot will see every table as it was when
+    /// this was called, for instance a long running tool can copy tables from a consistent view
+    /// while the node keeps writing.  Keeping a snapshot open can keep the backend from
+    /// reclaiming space so drop it when done.
+    fn snapshot(&self) -> eyre::Result<Self::Snapshot<'_>>;
+
     /// Returns true if the map contains a value for the specified key.
     fn contains_key<T: Table>(&self, key: &T::Key) -> eyre::Result<bool>;
 
@@ -69,6 +174,31 @@ pub trait Database: Send + Sync + Clone + Unpin + 'static {
     /// inserts.
     fn insert<T: Table>(&self, key: &T::Key, value: &T::Value) -> eyre::Result<()>;
 
+    /// Inserts the given key-value pair into the map if the key is not already in the map.
+    /// Returns true if the value was inserted.
+    /// The check and insert are not atomic, concurrent writers of the same key can race.
+    fn insert_if_absent<T: Table>(&self, key: &T::Key, value: &T::Value) -> eyre::Result<bool> {
+        if self.contains_key::<T>(key)? {
+            return Ok(false);
+        }
+        self.insert::<T>(key, value)?;
+        Ok(true)
+    }
+
+    /// Inserts the given key-value pair into the map, failing if a different value is already
+    /// stored for the key.  Re-inserting the same value is a no-op.
+    /// The check and insert are not atomic, concurrent writers of the same key can race.
+    fn insert_checked<T: Table>(&self, key: &T::Key, value: &T::Value) -> eyre::Result<()>
+    where
+        T::Value: PartialEq,
+    {
+        match self.get::<T>(key)? {
+            Some(existing) if existing == *value => Ok(()),
+            Some(_) => Err(eyre::eyre!("conflicting value for key {key:?} in table {}", T::NAME)),
+            None => self.insert::<T>(key, value),
+        }
+    }
+
     /// Removes the entry for the given key from the map.
     /// This will create and commit a TXN, useful for one-offs but use a transaction for multiple
     /// removes.
@@ -86,11 +216,68 @@ pub trait Database: Send + Sync + Clone + Unpin + 'static {
     /// If this is backed by storage an underlying error will most likely end the iterator early.
     fn iter<T: Table>(&self) -> DBIter<'_, T>;
 
+    /// Returns an unbounded iterator visiting each key-value pair in the map as the encoded bytes
+    /// stored in the table.  Useful for copying tables between DBs without a decode/encode round
+    /// trip, use with [`DbTxMut::insert_raw`].
+    fn iter_raw<T: Table>(&self) -> DBIterRaw<'_>;
+
+    /// Returns an unbounded iterator visiting each key-value pair in the map that decodes rows
+    /// lazily.  Rows that fail to decode are returned as a [StoreErrorKind::CorruptRow] instead of
+    /// panicking, so callers can log and skip them when reading a partially corrupt DB.
+    /// Like [`Self::verify_table`] this relies on [`Self::iter_raw`] returning the stored bytes.
+    fn iter_checked<T: Table>(&self) -> DBIterChecked<'_, T> {
+        Box::new(self.iter_raw::<T>().map(|(key, value)| -> Result<_, StoreError> {
+            let corrupt = |reason: String| StoreErrorKind::CorruptRow {
+                table: T::NAME,
+                key: key.clone(),
+                reason,
+            };
+            let decoded_key = try_decode_key::<T::Key>(&key).map_err(|e| corrupt(e.to_string()))?;
+            let decoded_value =
+                try_decode::<T::Value>(&value).map_err(|e| corrupt(e.to_string()))?;
+            Ok((decoded_key, decoded_value))
+        }))
+    }
+
+    /// Verify every row in the table decodes, for instance as a self-check on startup after an
+    /// unclean shutdown.  Rows that fail to decode are reported instead of panicking.
+    /// Note this relies on [`Self::iter_raw`] returning the stored bytes, backends that decode in
+    /// iter_raw will not be able to report corrupt rows.
+    fn verify_table<T: Table>(&self) -> eyre::Result<VerifyReport> {
+        let mut report = VerifyReport::default();
+        for (key, value) in self.iter_raw::<T>() {
+            if try_decode_key::<T::Key>(&key).is_ok() && try_decode::<T::Value>(&value).is_ok() {
+                report.valid += 1;
+            } else {
+                report.corrupt_keys.push(key);
+            }
+        }
+        Ok(report)
+    }
+
+    /// Write every key-value pair in the table to `w` as newline-delimited JSON, for instance to
+    /// inspect a table without a custom tool.  Each line is an object with `key` and `value`.
+    /// Returns the number of lines written.
+    fn export_table_ndjson<T: Table, W: Write>(&self, mut w: W) -> eyre::Result<usize> {
+        let mut lines = 0;
+        for (key, value) in self.iter::<T>() {
+            serde_json::to_writer(&mut w, &serde_json::json!({ "key": key, "value": value }))?;
+            w.write_all(b"\n")?;
+            lines += 1;
+        }
+        w.flush()?;
+        Ok(lines)
+    }
+
     /// Skips all the elements that are smaller than the given key,
     /// and either lands on the key or the first one greater than
     /// the key.
     fn skip_to<T: Table>(&self, key: &T::Key) -> eyre::Result<DBIter<'_, T>>;
 
+    /// Returns an iterator over the keys in the range `[from, to)` in order.
+    /// The iterator is empty if `to` is not greater than `from`.
+    fn range_iter<T: Table>(&self, from: &T::Key, to: &T::Key) -> DBIter<'_, T>;
+
     /// Iterates over all the keys in reverse.
     fn reverse_iter<T: Table>(&self) -> DBIter<'_, T>;
 
@@ -101,6 +288,19 @@ pub trait Database: Send + Sync + Clone + Unpin + 'static {
     /// Returns the last (key, value) in the database.
     fn last_record<T: Table>(&self) -> Option<(T::Key, T::Value)>;
 
+    /// Returns the first (key, value) in the database.
+    fn first_record<T: Table>(&self) -> Option<(T::Key, T::Value)> {
+        self.iter::<T>().next()
+    }
+
+    /// Returns the number of records in the table.
+    /// Backends may override this with a cheaper estimate (for instance from DB stats) so this
+    /// is not guaranteed to be exact, use it for metrics and sizing not logic.
+    /// The default will scan the table.
+    fn approximate_len<T: Table>(&self) -> usize {
+        self.iter::<T>().count()
+    }
+
     /// Returns a vector of values corresponding to the keys provided.
     fn multi_get<'a, T: Table>(
         &'a self,
@@ -115,4 +315,10 @@ pub trait Database: Send + Sync + Clone + Unpin + 'static {
     fn compact(&self) -> eyre::Result<()> {
         Ok(())
     }
+
+    /// Make sure all writes that have returned are persisted (for instance on shutdown).
+    /// No-op for backends that persist writes before returning.
+    fn flush(&self) -> eyre::Result<()> {
+        Ok(())
+    }
 }
@@ -26,6 +26,19 @@ pub type AcceptNotification = Arc<NotifyOnce>;
 
 pub type StoreError = eyre::Report;
 
+/// Typed storage errors.
+///
+/// These are returned wrapped in a [StoreError] so callers can downcast to check the kind.
+#[derive(Debug, Error)]
+pub enum StoreErrorKind {
+    /// The encoded value is larger than the table allows.
+    #[error("value for table {table} is {size} bytes, max is {max} bytes")]
+    ValueTooLarge { table: &'static str, size: usize, max: usize },
+    /// A stored row failed to decode.
+    #[error("corrupt row in table {table} with encoded key {key:?}: {reason}")]
+    CorruptRow { table: &'static str, key: Vec<u8>, reason: String },
+}
+
 #[derive(Debug, Error)]
 pub enum DagError {
     // TEMPORARY - use this in certificate error instead
@@ -0,0 +1,54 @@
+//! Hardfork overrides for the EVM.
+//!
+//! The engine executes output with the spec id for the network's current hardfork. Operators can
+//! override the spec id for testing forward-compatibility with a later hardfork. The hardfork must
+//! be scheduled in the chain spec.
+
+use reth_chainspec::{ChainSpec, EthereumHardfork, ForkCondition, Hardforks as _};
+use reth_revm::primitives::SpecId;
+use serde::{Deserialize, Serialize};
+
+/// The post-merge hardforks the EVM can be configured to execute with.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EvmHardfork {
+    /// The Shanghai hardfork - the network's current hardfork.
+    Shanghai,
+    /// The Cancun hardfork.
+    Cancun,
+    /// The Prague hardfork.
+    Prague,
+}
+
+impl EvmHardfork {
+    /// The spec id used to configure the EVM.
+    pub fn spec_id(&self) -> SpecId {
+        match self {
+            Self::Shanghai => SpecId::SHANGHAI,
+            Self::Cancun => SpecId::CANCUN,
+            Self::Prague => SpecId::PRAGUE,
+        }
+    }
+
+    /// The corresponding ethereum hardfork.
+    pub fn ethereum_hardfork(&self) -> EthereumHardfork {
+        match self {
+            Self::Shanghai => EthereumHardfork::Shanghai,
+            Self::Cancun => EthereumHardfork::Cancun,
+            Self::Prague => EthereumHardfork::Prague,
+        }
+    }
+
+    /// Is the hardfork scheduled in the chain spec?
+    ///
+    /// Hardforks that are never activated by the chain spec can't be used to execute the chain.
+    pub fn is_scheduled(&self, chain_spec: &ChainSpec) -> bool {
+        chain_spec.fork(self.ethereum_hardfork()) != ForkCondition::Never
+    }
+}
+
+impl std::fmt::Display for EvmHardfork {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.ethereum_hardfork(), f)
+    }
+}
@@ -6,6 +6,7 @@ mod committee;
 mod crypto;
 pub mod database_traits;
 mod genesis;
+mod hardfork;
 mod helpers;
 mod notifier;
 mod primary;
@@ -20,6 +21,7 @@ pub use committee::*;
 pub use crypto::*;
 pub use database_traits::*;
 pub use genesis::*;
+pub use hardfork::*;
 pub use helpers::*;
 pub use notifier::*;
 pub use primary::*;
@@ -31,12 +33,13 @@ pub use worker::*;
 pub use alloy::{
     consensus::{
         constants::{EMPTY_OMMER_ROOT_HASH, EMPTY_RECEIPTS, EMPTY_TRANSACTIONS, EMPTY_WITHDRAWALS},
-        proofs::calculate_transaction_root,
-        BlockHeader, Header as ExecHeader, Transaction as TransactionTrait, TxEip1559,
+        proofs::{calculate_ommers_root, calculate_transaction_root},
+        BlockHeader, Header as ExecHeader, Transaction as TransactionTrait, TxEip1559, TxEip2930,
+        TxLegacy,
     },
     eips::{
         eip1559::{ETHEREUM_BLOCK_GAS_LIMIT, MIN_PROTOCOL_BASE_FEE},
-        eip2718::Encodable2718,
+        eip2718::{Decodable2718, Encodable2718},
         eip4844::{env_settings::EnvKzgSettings, BlobAndProofV1, BlobTransactionSidecar},
         BlockHashOrNumber, BlockNumHash,
     },
@@ -46,10 +49,10 @@ pub use alloy::{
         hex_literal, keccak256, Address, BlockHash, BlockNumber, Bloom, Bytes, Sealable, TxHash,
         TxKind, B256, U160, U256,
     },
-    rpc::types::{AccessList, Withdrawals},
+    rpc::types::{AccessList, AccessListItem, Withdrawals},
     signers::Signature as EthSignature,
     sol,
-    sol_types::{SolType, SolValue},
+    sol_types::{SolCall, SolType, SolValue},
 };
 pub use libp2p::Multiaddr;
 pub use reth_primitives::{
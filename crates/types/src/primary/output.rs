@@ -5,8 +5,8 @@ use super::{CertificateDigest, ConsensusHeader, SignatureVerificationState};
 use crate::{
     crypto, encode,
     error::{CertificateError, CertificateResult},
-    Address, Batch, BlockHash, Certificate, Committee, Digest, Epoch, Hash, ReputationScores,
-    Round, TimestampSec, B256,
+    Address, Batch, BlockHash, Certificate, Committee, Digest, Epoch, ExecHeader, Hash,
+    ReputationScores, Round, TimestampSec, B256,
 };
 use blake2::Digest as _;
 use serde::{Deserialize, Serialize};
@@ -94,6 +94,14 @@ impl ConsensusOutput {
         self.batches.iter().flat_map(|batches| batches.iter().cloned()).collect()
     }
 
+    /// The ommers for the block that completes this output.
+    ///
+    /// Each batch is represented by its [Batch::ommer_header] in the same canonical order the
+    /// batches are executed.
+    pub fn ommers(&self) -> Vec<ExecHeader> {
+        self.batches.iter().flatten().map(Batch::ommer_header).collect()
+    }
+
     /// Build a new ConsensusHeader frome this output.
     pub fn consensus_header(&self) -> ConsensusHeader {
         ConsensusHeader {
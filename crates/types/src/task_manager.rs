@@ -1,6 +1,6 @@
 //! Task manager interface to spawn tasks to the tokio runtime.
 
-use crate::Notifier;
+use crate::{Noticer, Notifier};
 use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, StreamExt};
 use std::{
     fmt::{Debug, Display},
@@ -39,12 +39,39 @@ impl Future for TaskHandle {
     }
 }
 
+/// The order tasks are stopped in when a [TaskManager] shuts down.
+///
+/// Tasks spawned with [TaskManager::spawn_ordered_task] are stopped one stage at a time. A stage
+/// is only notified after all the tasks in the previous stage have exited.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShutdownStage {
+    /// Tasks that create new work (ie - the proposer).
+    Proposing,
+    /// Tasks that accept work from the network.
+    Network,
+    /// Tasks that persist state once no new work can arrive (ie - flushing stores).
+    Storage,
+}
+
+impl ShutdownStage {
+    /// All stages in the order they are stopped.
+    pub const ORDER: [ShutdownStage; 3] = [Self::Proposing, Self::Network, Self::Storage];
+}
+
+/// The tasks for a [ShutdownStage] and the notifier used to stop them.
+#[derive(Default)]
+struct OrderedTasks {
+    shutdown: Notifier,
+    tasks: FuturesUnordered<TaskHandle>,
+}
+
 /// A basic task manager.
 ///
 /// Allows new tasks to be be started on the tokio runtime and tracks
 /// there JoinHandles.
 pub struct TaskManager {
     tasks: FuturesUnordered<TaskHandle>,
+    ordered: [OrderedTasks; ShutdownStage::ORDER.len()],
     submanagers: Vec<TaskManager>,
     name: String,
     new_task_rx: mpsc::Receiver<TaskHandle>,
@@ -85,6 +112,7 @@ impl TaskManager {
         let (new_task_tx, new_task_rx) = mpsc::channel(100);
         Self {
             tasks: FuturesUnordered::new(),
+            ordered: Default::default(),
             submanagers: Vec::new(),
             name: name.to_string(),
             new_task_rx,
@@ -105,6 +133,29 @@ impl TaskManager {
         self.tasks.push(TaskHandle { name, handle });
     }
 
+    /// Return a Noticer that will resolve when the tasks for `stage` should shutdown.
+    ///
+    /// Tasks spawned with [Self::spawn_ordered_task] should exit once this resolves.
+    pub fn stage_shutdown(&self, stage: ShutdownStage) -> Noticer {
+        self.ordered[stage as usize].shutdown.subscribe()
+    }
+
+    /// Spawns a task on tokio that is stopped in order with `stage` on shutdown.
+    ///
+    /// Ordered tasks are critical just like the tasks from [Self::spawn_task]. They must listen to
+    /// [Self::stage_shutdown] instead of the node's shutdown Notifier or the ordering is lost.
+    pub fn spawn_ordered_task<F, S: ToString>(&self, stage: ShutdownStage, name: S, future: F)
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let name = name.to_string();
+        let handle = tokio::spawn(async move {
+            future.await;
+        });
+        self.ordered[stage as usize].tasks.push(TaskHandle { name, handle });
+    }
+
     /// Return a clonable spawner (also implements Reth's TaskSpawner trait).
     pub fn get_spawner(&self) -> TaskManagerClone {
         TaskManagerClone { new_task_tx: self.new_task_tx.clone() }
@@ -135,7 +186,7 @@ impl TaskManager {
     /// Abort all of our direct tasks (not sub task managers though).
     /// This is included for some tests, should not use in real code.
     pub fn abort(&self) {
-        for task in self.tasks.iter() {
+        for task in self.tasks.iter().chain(self.ordered.iter().flat_map(|o| o.tasks.iter())) {
             task.handle.abort();
         }
     }
@@ -185,17 +236,12 @@ impl TaskManager {
                     // expected to run for the lifetime of the application (until shutdown is indicated).
                     // If the manager changes to also manage tasks that do not live for the app lifetime then
                     // some of these match arms will be expected for those tasks.
-                    match res {
-                        Some(Ok(name)) => {
-                            tracing::error!(target: "tn::tasks", "{}: {name} returned Ok, node exiting", self.name);
-                        }
-                        Some(Err((name, join_err))) => {
-                            tracing::error!(target: "tn::tasks", "{}: {name} returned error {join_err}, node exiting", self.name);
-                        }
-                        None => {
-                            tracing::error!(target: "tn::tasks", "{}: Out of tasks! node exiting", self.name);
-                        }
-                    }
+                    Self::log_task_exit(&self.name, res);
+                    break;
+                }
+                Some(res) = Self::next_ordered(&mut self.ordered) => {
+                    // Ordered tasks are also expected to run until shutdown.
+                    Self::log_task_exit(&self.name, Some(res));
                     break;
                 }
                 Some((_, name)) = future_managers.next() => {
@@ -204,8 +250,9 @@ impl TaskManager {
                 }
             }
         }
-        // No matter how we exit notify shutdown and allow a chance for other tasks to exit
-        // cleanly.
+        // Stop the ordered tasks first, then notify shutdown no matter how we exit and allow a
+        // chance for other tasks to exit cleanly.
+        self.shutdown_ordered().await;
         shutdown.notify();
         let task_name = self.name.clone();
         // wait some time for shutdown...
@@ -258,6 +305,76 @@ impl TaskManager {
         }
     }
 
+    /// Log a critical task exiting (or running out of tasks).
+    fn log_task_exit(manager: &str, res: Option<Result<String, (String, JoinError)>>) {
+        match res {
+            Some(Ok(name)) => {
+                tracing::error!(target: "tn::tasks", "{manager}: {name} returned Ok, node exiting");
+            }
+            Some(Err((name, join_err))) => {
+                tracing::error!(target: "tn::tasks", "{manager}: {name} returned error {join_err}, node exiting");
+            }
+            None => {
+                tracing::error!(target: "tn::tasks", "{manager}: Out of tasks! node exiting");
+            }
+        }
+    }
+
+    /// Will resolve once one of the ordered tasks resolves.
+    ///
+    /// Resolves to None if there are no ordered tasks.
+    fn next_ordered(
+        ordered: &mut [OrderedTasks],
+    ) -> impl Future<Output = Option<Result<String, (String, JoinError)>>> + '_ {
+        futures::future::poll_fn(move |cx| {
+            let mut pending = false;
+            for stage in ordered.iter_mut() {
+                match stage.tasks.poll_next_unpin(cx) {
+                    Poll::Ready(Some(res)) => return Poll::Ready(Some(res)),
+                    Poll::Ready(None) => (),
+                    Poll::Pending => pending = true,
+                }
+            }
+            if pending {
+                Poll::Pending
+            } else {
+                Poll::Ready(None)
+            }
+        })
+    }
+
+    /// Stop the ordered tasks one [ShutdownStage] at a time.
+    ///
+    /// Each stage has two seconds for it's tasks to exit before the next stage is notified.
+    async fn shutdown_ordered(&mut self) {
+        for stage in ShutdownStage::ORDER {
+            let ordered = &mut self.ordered[stage as usize];
+            ordered.shutdown.notify();
+            let name = &self.name;
+            if tokio::time::timeout(Duration::from_secs(2), async {
+                while let Some(res) = ordered.tasks.next().await {
+                    match res {
+                        Ok(task) => {
+                            tracing::info!(
+                                target = "tn::tasks",
+                                "{name}: {task} shutdown successfully ({stage:?})"
+                            )
+                        }
+                        Err((task, err)) => tracing::error!(
+                            target = "tn::tasks",
+                            "{name}: {task} shutdown with error {err} ({stage:?})"
+                        ),
+                    }
+                }
+            })
+            .await
+            .is_err()
+            {
+                tracing::error!(target = "tn::tasks", "{name}: {stage:?} tasks NOT shutdown");
+            }
+        }
+    }
+
     /// Will resolve when ctrl-c is pressed or a SIGTERM is received.
     async fn exit(do_exit: bool) {
         if !do_exit {
@@ -295,6 +412,11 @@ impl Display for TaskManager {
         for task in self.tasks.iter() {
             writeln!(f, "Task: {}", task.name)?;
         }
+        for (stage, ordered) in ShutdownStage::ORDER.iter().zip(self.ordered.iter()) {
+            for task in ordered.tasks.iter() {
+                writeln!(f, "Task: {} ({stage:?})", task.name)?;
+            }
+        }
         for sub in &self.submanagers {
             writeln!(f, "++++++++++++++++++++++++++++++++++++++++++++++++++++")?;
             writeln!(f, "{sub}")?;
@@ -359,3 +481,66 @@ impl reth_tasks::TaskSpawner for TaskManagerClone {
         join
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{Notifier, ShutdownStage, TaskManager};
+    use parking_lot::Mutex;
+    use std::{sync::Arc, time::Duration};
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_ordered_shutdown() {
+        let mut task_manager = TaskManager::new("Ordered Task Manager");
+        let shutdown = Notifier::new();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let (work_tx, mut work_rx) = mpsc::channel::<u32>(10);
+
+        // proposer sends work until told to stop
+        let rx_stop = task_manager.stage_shutdown(ShutdownStage::Proposing);
+        let proposer_events = events.clone();
+        let proposer_tx = work_tx.clone();
+        task_manager.spawn_ordered_task(ShutdownStage::Proposing, "proposer", async move {
+            proposer_tx.send(1).await.expect("network accepts work");
+            rx_stop.await;
+            proposer_events.lock().push("proposer stopped");
+        });
+
+        // network accepts work until told to stop then drains what is left
+        let rx_stop = task_manager.stage_shutdown(ShutdownStage::Network);
+        let network_events = events.clone();
+        task_manager.spawn_ordered_task(ShutdownStage::Network, "network", async move {
+            rx_stop.await;
+            work_rx.close();
+            while work_rx.recv().await.is_some() {}
+            network_events.lock().push("network stopped");
+        });
+
+        // flush stores once the network can no longer accept work
+        let rx_stop = task_manager.stage_shutdown(ShutdownStage::Storage);
+        let storage_events = events.clone();
+        let storage_tx = work_tx.clone();
+        task_manager.spawn_ordered_task(ShutdownStage::Storage, "flush stores", async move {
+            rx_stop.await;
+            assert!(storage_tx.is_closed());
+            assert!(storage_tx.try_send(2).is_err());
+            storage_events.lock().push("stores flushed");
+        });
+
+        // regular tasks stop on the node shutdown
+        let rx_shutdown = shutdown.subscribe();
+        task_manager.spawn_task("regular", async move {
+            rx_shutdown.await;
+        });
+
+        let shutdown_clone = shutdown.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            shutdown_clone.notify();
+        });
+        task_manager.join(shutdown).await;
+
+        assert_eq!(*events.lock(), vec!["proposer stopped", "network stopped", "stores flushed"]);
+        assert!(work_tx.is_closed());
+    }
+}
@@ -0,0 +1,108 @@
+//! Merkle proofs for transactions included in an executed block.
+//!
+//! The executed block's `transactions_root` is the root of an ordered trie over the block's
+//! encoded transactions (see [calculate_transaction_root](crate::calculate_transaction_root)).
+//! Light clients use these proofs to show a transaction was included in a specific block.
+//!
+//! The engine skips batch transactions that fail to execute, so proofs are always built from the
+//! executed block's transactions instead of the batch's.
+
+use crate::{Bytes, Encodable2718 as _, TransactionSigned, TxHash, B256};
+use alloy::trie::{
+    proof::{verify_proof, ProofRetainer},
+    root::adjust_index_for_rlp,
+    HashBuilder, Nibbles,
+};
+use alloy_rlp::Encodable as _;
+use serde::{Deserialize, Serialize};
+
+/// A Merkle proof that a transaction is included in a block's transactions root.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionInclusionProof {
+    /// The index of the transaction in the block.
+    pub index: usize,
+    /// The EIP-2718 encoded transaction.
+    pub transaction: Bytes,
+    /// The trie nodes from the root to the transaction's leaf.
+    pub proof: Vec<Bytes>,
+}
+
+impl TransactionInclusionProof {
+    /// Return the inclusion proof for the transaction with this hash in a block's transactions.
+    ///
+    /// `transactions` are the block's transactions in order. Returns `None` if the transaction is
+    /// not in the block.
+    pub fn new(transactions: &[TransactionSigned], hash: TxHash) -> Option<Self> {
+        let index = transactions.iter().position(|tx| tx.hash() == hash)?;
+        let target = index_key(index);
+        let encoded: Vec<_> = transactions.iter().map(|tx| tx.encoded_2718()).collect();
+
+        // build the ordered trie the same way as the transactions root
+        let mut hb = HashBuilder::default().with_proof_retainer(ProofRetainer::new(vec![target]));
+        let len = encoded.len();
+        for i in 0..len {
+            let leaf = adjust_index_for_rlp(i, len);
+            hb.add_leaf(index_key(leaf), &encoded[leaf]);
+        }
+        hb.root();
+
+        let proof = hb.take_proof_nodes().into_nodes_sorted().into_iter().map(|(_, node)| node);
+        Some(Self {
+            index,
+            transaction: Bytes::from(encoded[index].clone()),
+            proof: proof.collect(),
+        })
+    }
+
+    /// Verify the transaction is included in the trie with the given transactions root.
+    pub fn verify(&self, transactions_root: B256) -> bool {
+        verify_proof(
+            transactions_root,
+            index_key(self.index),
+            Some(self.transaction.to_vec()),
+            self.proof.iter(),
+        )
+        .is_ok()
+    }
+}
+
+/// The trie key for the transaction at this index.
+fn index_key(index: usize) -> Nibbles {
+    let mut buf = Vec::new();
+    index.encode(&mut buf);
+    Nibbles::unpack(&buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TransactionInclusionProof;
+    use crate::{
+        calculate_transaction_root, EthSignature, Transaction, TransactionSigned, TxLegacy, B256,
+        U256,
+    };
+
+    fn signed_tx(nonce: u64) -> TransactionSigned {
+        let tx = Transaction::Legacy(TxLegacy { nonce, gas_limit: 21_000, ..Default::default() });
+        let signature = EthSignature::new(U256::from(1), U256::from(1), false);
+        TransactionSigned::new_unhashed(tx, signature)
+    }
+
+    #[test]
+    fn test_transaction_proof_verifies_against_root() {
+        // enough transactions for branch and extension nodes
+        let transactions: Vec<_> = (0..130).map(signed_tx).collect();
+        let root = calculate_transaction_root(&transactions);
+
+        for index in [0, 1, 127, 128, 129] {
+            let proof = TransactionInclusionProof::new(&transactions, transactions[index].hash())
+                .expect("proof");
+            assert_eq!(proof.index, index);
+            assert!(proof.verify(root));
+            assert!(!proof.verify(B256::ZERO));
+        }
+
+        // unknown transactions have no proof
+        assert!(TransactionInclusionProof::new(&transactions, signed_tx(999).hash()).is_none());
+    }
+}
@@ -4,6 +4,8 @@ use tokio::sync::{mpsc::Sender, oneshot};
 #[allow(clippy::mutable_key_type)]
 mod info;
 pub use info::*;
+mod inclusion_proof;
+pub use inclusion_proof::*;
 mod sealed_batch;
 pub use sealed_batch::*;
 mod pending_batch;
@@ -2,7 +2,8 @@
 //!
 //! This is an experimental approach to supporting pending blocks for workers.
 
-use crate::{Address, SealedBlock};
+use crate::{Address, Epoch, SealedBlock, TimestampGranularity};
+use std::collections::HashSet;
 
 /// The arguments passed to the worker's block builder.
 #[derive(Debug)]
@@ -11,12 +12,47 @@ pub struct BatchBuilderArgs<Pool> {
     pub pool: Pool,
     /// The attributes for the next block.
     pub batch_config: PendingBlockConfig,
+    /// Senders with transactions in batches that are still waiting for quorum.
+    ///
+    /// Transactions from these senders are not included in the next batch.
+    pub in_flight_senders: HashSet<Address>,
+    /// The unit for the batch's timestamp.
+    pub timestamp_granularity: TimestampGranularity,
+    /// The epoch the batch is built in.
+    pub epoch: Epoch,
 }
 
 impl<Pool> BatchBuilderArgs<Pool> {
     /// Create a new instance of [Self].
     pub fn new(pool: Pool, batch_config: PendingBlockConfig) -> Self {
-        Self { pool, batch_config }
+        Self {
+            pool,
+            batch_config,
+            in_flight_senders: HashSet::new(),
+            timestamp_granularity: TimestampGranularity::default(),
+            epoch: 0,
+        }
+    }
+
+    /// Exclude transactions from senders with batches that are still waiting for quorum.
+    pub fn with_in_flight_senders(mut self, in_flight_senders: HashSet<Address>) -> Self {
+        self.in_flight_senders = in_flight_senders;
+        self
+    }
+
+    /// Set the unit for the batch's timestamp.
+    pub fn with_timestamp_granularity(
+        mut self,
+        timestamp_granularity: TimestampGranularity,
+    ) -> Self {
+        self.timestamp_granularity = timestamp_granularity;
+        self
+    }
+
+    /// Set the epoch the batch is built in.
+    pub fn with_epoch(mut self, epoch: Epoch) -> Self {
+        self.epoch = epoch;
+        self
     }
 }
 
@@ -4,12 +4,15 @@
 //! have reached quorum.
 
 use crate::{
-    adiri_chain_spec, crypto, encode, now, Address, BlockHash, ExecHeader, TimestampSec,
+    adiri_chain_spec, crypto, encode, now, Address, BlockHash, Epoch, ExecHeader, TimestampSec,
     MIN_PROTOCOL_BASE_FEE,
 };
 use blake2::Digest as _;
 use serde::{Deserialize, Serialize};
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    time::{SystemTime, UNIX_EPOCH},
+};
 use thiserror::Error;
 
 /// The batch for workers to communicate for consensus.
@@ -70,6 +73,8 @@ pub struct Batch {
     pub beneficiary: Address,
     /// A scalar value equal to the reasonable output of Unix’s time() at this batch’s inception;
     /// formally Hs.
+    ///
+    /// The unit is the network's [TimestampGranularity].
     pub timestamp: u64,
     /// A scalar representing EIP1559 base fee which can move up or down each batch according
     /// to a formula which is a function of gas used in parent batch and gas target
@@ -78,6 +83,10 @@ pub struct Batch {
     /// above the gas target, and decreasing when batchs are below the gas target. The base fee per
     /// gas is burned.
     pub base_fee_per_gas: Option<u64>,
+    /// The epoch this batch was built in.
+    ///
+    /// Peers reject batches from other epochs so a batch can't be replayed in a later epoch.
+    pub epoch: Epoch,
     /// Timestamp of when the entity was received by another node. This will help
     /// calculate latencies that are not affected by clock drift or network
     /// delays. This field is not set for own batchs.
@@ -97,6 +106,7 @@ impl Batch {
             beneficiary: header.beneficiary,
             timestamp: header.timestamp,
             base_fee_per_gas: header.base_fee_per_gas,
+            epoch: 0,
             received_at: None,
         }
     }
@@ -116,8 +126,25 @@ impl Batch {
         BlockHash::from_slice(&hasher.finalize()[..])
     }
 
+    /// The header representing this batch in the ommers of the block that completes its output.
+    ///
+    /// The header's `extra_data` is the batch digest, the same as the block executed from this
+    /// batch.
+    pub fn ommer_header(&self) -> ExecHeader {
+        ExecHeader {
+            parent_hash: self.parent_hash,
+            beneficiary: self.beneficiary,
+            timestamp: self.timestamp,
+            base_fee_per_gas: self.base_fee_per_gas,
+            extra_data: self.digest().into(),
+            ..Default::default()
+        }
+    }
+
     /// Timestamp of this batch header.
-    pub fn created_at(&self) -> TimestampSec {
+    ///
+    /// The unit is the network's [TimestampGranularity].
+    pub fn created_at(&self) -> u64 {
         self.timestamp
     }
 
@@ -169,6 +196,7 @@ impl Default for Batch {
             beneficiary: Address::ZERO,
             timestamp: now(),
             base_fee_per_gas: Some(MIN_PROTOCOL_BASE_FEE),
+            epoch: 0,
         }
     }
 }
@@ -197,6 +225,60 @@ pub fn max_batch_size(_timestamp: u64) -> usize {
     1_000_000
 }
 
+/// The unit for batch timestamps.
+///
+/// Execution headers are always timestamped in seconds. Batches built more than once per second
+/// collide with the parent's timestamp in seconds, so millisecond timestamps keep batches strictly
+/// increasing without jumping ahead of the wall clock. Every node must use the same granularity
+/// to validate batches.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampGranularity {
+    /// Batch timestamps are seconds since the unix epoch.
+    #[default]
+    Seconds,
+    /// Batch timestamps are milliseconds since the unix epoch.
+    Millis,
+}
+
+impl TimestampGranularity {
+    /// The current time in this granularity.
+    pub fn now(&self) -> u64 {
+        match self {
+            Self::Seconds => now(),
+            Self::Millis => match SystemTime::now().duration_since(UNIX_EPOCH) {
+                Ok(n) => n.as_millis() as u64,
+                Err(_) => panic!("SystemTime before UNIX EPOCH!"),
+            },
+        }
+    }
+
+    /// Convert a timestamp in seconds (ie - an execution header's timestamp) to this granularity.
+    pub fn from_secs(&self, secs: TimestampSec) -> u64 {
+        match self {
+            Self::Seconds => secs,
+            Self::Millis => secs.saturating_mul(1_000),
+        }
+    }
+
+    /// Convert a timestamp in this granularity to seconds.
+    pub fn to_secs(&self, timestamp: u64) -> TimestampSec {
+        match self {
+            Self::Seconds => timestamp,
+            Self::Millis => timestamp / 1_000,
+        }
+    }
+}
+
+/// The size of an encoded transaction in a batch.  Measured in bytes.
+///
+/// Batches store transactions with their EIP-2718 encoding. Workers use this to measure
+/// transactions while building a batch and while validating a peer's batch, so a batch built
+/// within [max_batch_size] never fails a peer's size check.
+pub fn transaction_size(encoded_tx: &[u8]) -> usize {
+    encoded_tx.len()
+}
+
 /// Defines the validation procedure for receiving either a new single transaction (from a client)
 /// of a batch of transactions (from another validator).
 ///
@@ -204,6 +286,28 @@ pub fn max_batch_size(_timestamp: u64) -> usize {
 pub trait BatchValidation: Send + Sync {
     /// Determines if this batch can be voted on
     fn validate_batch(&self, b: SealedBatch) -> Result<(), BatchValidationError>;
+
+    /// Validate many batches.
+    ///
+    /// Each batch is validated independently and the results are returned in the same order as
+    /// the provided batches. The default implementation validates batches sequentially.
+    fn validate_batches(&self, batches: &[SealedBatch]) -> Vec<Result<(), BatchValidationError>> {
+        batches.iter().map(|batch| self.validate_batch(batch.clone())).collect()
+    }
+
+    /// Validate a batch received from a peer and stamp it with the time it was received.
+    ///
+    /// `received_at` is not part of the digest so the returned batch keeps the original digest.
+    /// Locally built batches should not use this, their `received_at` stays `None`.
+    fn validate_received_batch(
+        &self,
+        sealed_batch: SealedBatch,
+    ) -> Result<SealedBatch, BatchValidationError> {
+        self.validate_batch(sealed_batch.clone())?;
+        let (mut batch, digest) = sealed_batch.split();
+        batch.set_received_at(now());
+        Ok(batch.seal(digest))
+    }
 }
 
 /// Block validation error types
@@ -241,6 +345,12 @@ pub enum BatchValidationError {
     /// Error while calculating max possible gas from icluded transactions.
     #[error("Unable to reduce max possible gas limit for peer's batch")]
     CalculateMaxPossibleGas,
+    /// The total possible gas for the batch's transactions overflowed.
+    #[error("Peer's batch total possible gas overflows")]
+    GasOverflow,
+    /// The total size of the batch's transactions overflowed.
+    #[error("Peer's batch total transaction size overflows")]
+    SizeOverflow,
     /// Error when peer's transaction list exceeds the maximum bytes allowed.
     #[error("Peer's transactions exceed max byte size: {0}")]
     HeaderTransactionBytesExceedsMax(usize),
@@ -248,4 +358,35 @@ pub enum BatchValidationError {
     /// If any transaction fails to decode, the entire batch validation fails.
     #[error("Failed to decode transaction for batch {0}: {1}")]
     RecoverTransaction(BlockHash, String),
+    /// The parent state could not be read for strict validation.
+    #[error("Failed to read parent state for peer's batch: {0}")]
+    ParentState(String),
+    /// The parent state is unavailable because it was pruned.
+    #[error("Parent state at block {0} is unavailable because it was pruned")]
+    StatePruned(u64),
+    /// A transaction's nonce is lower than the sender's nonce in the parent state.
+    #[error(
+        "Transaction nonce {tx_nonce} from {sender} is lower than the sender's nonce {state_nonce}"
+    )]
+    NonceTooLow {
+        /// The transaction's sender.
+        sender: Address,
+        /// The transaction's nonce.
+        tx_nonce: u64,
+        /// The sender's nonce in the parent state.
+        state_nonce: u64,
+    },
+    /// A transaction's sender has no balance in the parent state.
+    #[error("Transaction sender {0} has no balance")]
+    SenderHasNoBalance(Address),
+    /// The batch was built in a different epoch.
+    ///
+    /// Batches from previous epochs are rejected to prevent replaying them in a later epoch.
+    #[error("Batch built in epoch {batch_epoch} but the current epoch is {current_epoch}")]
+    WrongEpoch {
+        /// The epoch the batch was built in.
+        batch_epoch: Epoch,
+        /// The validator's current epoch.
+        current_epoch: Epoch,
+    },
 }